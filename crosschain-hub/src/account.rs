@@ -0,0 +1,500 @@
+//! Typed account headers. Every account layout carrying a header starts with
+//! an 8-byte discriminator naming its type and a layout version, so one
+//! account type can never be decoded as another and old layouts are refused
+//! until `MigrateAccount` rewrites them. New account types implement
+//! `VersionedAccount` and go through `pack` / `unpack`.
+
+use crate::bounded::BoundedVec;
+use crate::bridge::{BreakerScope, BridgeConfig, EscrowLedger, TokenConfig, MAX_SUPPORTED_TOKENS};
+use crate::eidas::{ComplianceBand, ComplianceMatrix, EidasLevel, HoldRules, MAX_COMPLIANCE_BANDS};
+use crate::error::CrossChainError;
+use crate::treasury::{
+    FeeAccrual, FeeLedger, FeeSchedule, FeeScheduleHistory, FEE_HISTORY_CAPACITY,
+};
+use crate::wallet::{RegistrationPolicy, Wallet};
+use crate::{
+    CrossChainConfig, CrossChainState, PauseFlags, PendingConfigUpdate, MAX_SUPPORTED_CHAINS,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use std::io::Write;
+
+pub const ACCOUNT_HEADER_LEN: usize = 8 + 1;
+
+pub trait VersionedAccount: BorshSerialize + BorshDeserialize {
+    const DISCRIMINATOR: [u8; 8];
+    /// Bumped whenever the body layout changes incompatibly.
+    const VERSION: u8;
+
+    /// Header followed by the Borsh body.
+    fn pack(&self) -> std::io::Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(ACCOUNT_HEADER_LEN);
+        self.pack_into(&mut data)?;
+        Ok(data)
+    }
+
+    /// Writes the header and body to the start of `dst`, leaving any tail
+    /// padding untouched.
+    fn pack_into<W: Write>(&self, mut dst: W) -> std::io::Result<()> {
+        dst.write_all(&Self::DISCRIMINATOR)?;
+        dst.write_all(&[Self::VERSION])?;
+        self.serialize(&mut dst)
+    }
+
+    /// Decodes an account after checking its header. Trailing bytes past the
+    /// body are allowed so fixed-size accounts can carry padding.
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut body = check_header(data, &Self::DISCRIMINATOR, Self::VERSION)?;
+        Ok(Self::deserialize(&mut body)?)
+    }
+
+    /// Whether `data` already starts with this type's discriminator, at any
+    /// version.
+    fn is_tagged(data: &[u8]) -> bool {
+        data.len() >= ACCOUNT_HEADER_LEN && data[..8] == Self::DISCRIMINATOR
+    }
+}
+
+/// Returns the body after a header matching `discriminator` and `version`.
+pub fn check_header<'a>(
+    data: &'a [u8],
+    discriminator: &[u8; 8],
+    version: u8,
+) -> Result<&'a [u8], CrossChainError> {
+    if data.len() < ACCOUNT_HEADER_LEN || data[..8] != *discriminator {
+        return Err(CrossChainError::InvalidAccountDiscriminator);
+    }
+
+    if data[8] != version {
+        return Err(CrossChainError::AccountMigrationRequired);
+    }
+
+    Ok(&data[ACCOUNT_HEADER_LEN..])
+}
+
+/// Account types written before headers existed, which `MigrateAccount`
+/// can upgrade in place.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountKind {
+    State,
+    Wallet,
+}
+
+/// Hub config as the first release wrote it. Frozen: never edit these
+/// fields, they describe bytes already on chain.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyConfigV0 {
+    pub admin: Pubkey,
+    pub bridge_authority: Pubkey,
+    pub eidas_authority: Pubkey,
+    pub arcium_program: Pubkey,
+    pub supported_chains: Vec<u64>,
+    pub min_cross_chain_amount: u64,
+    pub max_cross_chain_amount: u64,
+    pub fee_basis_points: u16,
+    pub paused: bool,
+}
+
+/// Hub state as the first release wrote it, at a keypair address rather
+/// than the state PDA.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyStateV0 {
+    pub config: LegacyConfigV0,
+    pub total_volume: u64,
+    pub total_transactions: u64,
+    pub registered_wallets: u64,
+    pub compliance_records: u64,
+}
+
+impl LegacyStateV0 {
+    /// Keeps the original config and totals; everything added since starts
+    /// from its default.
+    pub fn into_state(self, bump: u8) -> CrossChainState {
+        let config = self.config;
+        CrossChainState {
+            config: CrossChainConfig {
+                admin: config.admin,
+                bridge_authority: config.bridge_authority,
+                eidas_authority: config.eidas_authority,
+                arcium_program: config.arcium_program,
+                supported_chains: config.supported_chains.into(),
+                min_cross_chain_amount: config.min_cross_chain_amount,
+                max_cross_chain_amount: config.max_cross_chain_amount,
+                fee_basis_points: config.fee_basis_points,
+                paused: config.paused,
+                ..Default::default()
+            },
+            total_volume: self.total_volume,
+            total_transactions: self.total_transactions,
+            registered_wallets: self.registered_wallets,
+            compliance_records: self.compliance_records,
+            bump,
+            ..Default::default()
+        }
+    }
+}
+
+/// Wallet as the first release wrote it, at a keypair address rather than
+/// the owner's wallet PDA.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyWalletV0 {
+    pub owner: Pubkey,
+    pub created_at: i64,
+    pub is_compliance_verified: bool,
+    pub eidas_level: EidasLevel,
+    pub public_key: Vec<u8>,
+    pub metadata: Vec<u8>,
+}
+
+impl From<LegacyWalletV0> for Wallet {
+    fn from(legacy: LegacyWalletV0) -> Self {
+        Self {
+            owner: legacy.owner,
+            created_at: legacy.created_at,
+            is_compliance_verified: legacy.is_compliance_verified,
+            eidas_level: legacy.eidas_level,
+            public_key: legacy.public_key,
+            metadata: legacy.metadata,
+            kyc_verified: false,
+            aml_screened: false,
+            pending_verification: false,
+            frozen: false,
+        }
+    }
+}
+
+/// Body of a version 1 wallet, before registration quarantine.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyWalletV1 {
+    pub owner: Pubkey,
+    pub created_at: i64,
+    pub is_compliance_verified: bool,
+    pub eidas_level: EidasLevel,
+    pub public_key: Vec<u8>,
+    pub metadata: Vec<u8>,
+    pub kyc_verified: bool,
+    pub aml_screened: bool,
+}
+
+impl From<LegacyWalletV1> for Wallet {
+    fn from(legacy: LegacyWalletV1) -> Self {
+        Self {
+            owner: legacy.owner,
+            created_at: legacy.created_at,
+            is_compliance_verified: legacy.is_compliance_verified,
+            eidas_level: legacy.eidas_level,
+            public_key: legacy.public_key,
+            metadata: legacy.metadata,
+            kyc_verified: legacy.kyc_verified,
+            aml_screened: legacy.aml_screened,
+            pending_verification: false,
+            frozen: false,
+        }
+    }
+}
+
+/// Body of a version 2 wallet, before compliance freezes.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyWalletV2 {
+    pub owner: Pubkey,
+    pub created_at: i64,
+    pub is_compliance_verified: bool,
+    pub eidas_level: EidasLevel,
+    pub public_key: Vec<u8>,
+    pub metadata: Vec<u8>,
+    pub kyc_verified: bool,
+    pub aml_screened: bool,
+    pub pending_verification: bool,
+}
+
+impl From<LegacyWalletV2> for Wallet {
+    fn from(legacy: LegacyWalletV2) -> Self {
+        Self {
+            owner: legacy.owner,
+            created_at: legacy.created_at,
+            is_compliance_verified: legacy.is_compliance_verified,
+            eidas_level: legacy.eidas_level,
+            public_key: legacy.public_key,
+            metadata: legacy.metadata,
+            kyc_verified: legacy.kyc_verified,
+            aml_screened: legacy.aml_screened,
+            pending_verification: legacy.pending_verification,
+            frozen: false,
+        }
+    }
+}
+
+/// Config inside a version 1 state, before registration policies and the
+/// config timelock.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyConfigV1 {
+    pub admin: Pubkey,
+    pub bridge_authority: Pubkey,
+    pub eidas_authority: Pubkey,
+    pub arcium_program: Pubkey,
+    pub supported_chains: BoundedVec<u64, MAX_SUPPORTED_CHAINS>,
+    pub min_cross_chain_amount: u64,
+    pub max_cross_chain_amount: u64,
+    pub fee_basis_points: u16,
+    pub paused: bool,
+    pub guardian: Pubkey,
+    pub genesis_hash: [u8; 32],
+    pub require_top_level: bool,
+}
+
+/// Bridge config inside a version 1 state, before inclusion proofs,
+/// Wormhole, Axelar and cancellation fees.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyBridgeConfigV1 {
+    pub min_confirmation_blocks: u64,
+    pub max_confirmation_blocks: u64,
+    pub relayer_fee: u64,
+    pub protocol_fee: u64,
+    pub emergency_breaker: bool,
+    pub supported_tokens: BoundedVec<TokenConfig, MAX_SUPPORTED_TOKENS>,
+    pub breaker_scope: BreakerScope,
+    pub min_relayer_stake: u64,
+    pub required_relayer_signatures: u8,
+    pub claim_delivery: bool,
+    pub claim_requires_compliance: bool,
+    pub transfer_timeout_slots: u64,
+    pub terms_grace_slots: u64,
+}
+
+/// Body of a version 1 state. Embedded types not copied here
+/// (`TokenConfig`, `ComplianceBand`, `FeeAccrual`, `EscrowLedger`, ...)
+/// have kept their layout since; freeze a copy before changing one.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyStateV1 {
+    pub config: LegacyConfigV1,
+    pub total_volume: u64,
+    pub total_transactions: u64,
+    pub registered_wallets: u64,
+    pub compliance_records: u64,
+    pub bridge_config: LegacyBridgeConfigV1,
+    pub compliance_bands: BoundedVec<ComplianceBand, MAX_COMPLIANCE_BANDS>,
+    pub travel_rule_threshold: Option<u64>,
+    pub pending_admin: Option<Pubkey>,
+    pub pause_flags: PauseFlags,
+    pub fee_entries: Vec<FeeAccrual>,
+    pub escrow_ledger: EscrowLedger,
+    pub bump: u8,
+}
+
+impl From<LegacyStateV1> for CrossChainState {
+    fn from(legacy: LegacyStateV1) -> Self {
+        let config = legacy.config;
+        let bridge = legacy.bridge_config;
+        Self {
+            config: CrossChainConfig {
+                admin: config.admin,
+                bridge_authority: config.bridge_authority,
+                eidas_authority: config.eidas_authority,
+                arcium_program: config.arcium_program,
+                supported_chains: config.supported_chains,
+                min_cross_chain_amount: config.min_cross_chain_amount,
+                max_cross_chain_amount: config.max_cross_chain_amount,
+                fee_basis_points: config.fee_basis_points,
+                paused: config.paused,
+                guardian: config.guardian,
+                genesis_hash: config.genesis_hash,
+                require_top_level: config.require_top_level,
+                ..Default::default()
+            },
+            total_volume: legacy.total_volume,
+            total_transactions: legacy.total_transactions,
+            registered_wallets: legacy.registered_wallets,
+            compliance_records: legacy.compliance_records,
+            bridge_config: BridgeConfig {
+                min_confirmation_blocks: bridge.min_confirmation_blocks,
+                max_confirmation_blocks: bridge.max_confirmation_blocks,
+                relayer_fee: bridge.relayer_fee,
+                protocol_fee: bridge.protocol_fee,
+                emergency_breaker: bridge.emergency_breaker,
+                supported_tokens: bridge.supported_tokens,
+                breaker_scope: bridge.breaker_scope,
+                min_relayer_stake: bridge.min_relayer_stake,
+                required_relayer_signatures: bridge.required_relayer_signatures,
+                claim_delivery: bridge.claim_delivery,
+                claim_requires_compliance: bridge.claim_requires_compliance,
+                transfer_timeout_slots: bridge.transfer_timeout_slots,
+                terms_grace_slots: bridge.terms_grace_slots,
+                ..Default::default()
+            },
+            compliance_matrix: ComplianceMatrix {
+                bands: legacy.compliance_bands,
+                travel_rule_threshold: legacy.travel_rule_threshold,
+                hold_rules: HoldRules::default(),
+            },
+            pending_admin: legacy.pending_admin,
+            pending_config: None,
+            pause_flags: legacy.pause_flags,
+            fee_ledger: FeeLedger {
+                entries: legacy.fee_entries,
+                by_chain: Vec::new(),
+            },
+            escrow_ledger: legacy.escrow_ledger,
+            bump: legacy.bump,
+        }
+    }
+}
+
+/// Config inside a version 2 state, before the hub chain id.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyConfigV2 {
+    pub admin: Pubkey,
+    pub bridge_authority: Pubkey,
+    pub eidas_authority: Pubkey,
+    pub arcium_program: Pubkey,
+    pub supported_chains: BoundedVec<u64, MAX_SUPPORTED_CHAINS>,
+    pub min_cross_chain_amount: u64,
+    pub max_cross_chain_amount: u64,
+    pub fee_basis_points: u16,
+    pub paused: bool,
+    pub guardian: Pubkey,
+    pub genesis_hash: [u8; 32],
+    pub require_top_level: bool,
+    pub registration: RegistrationPolicy,
+    pub config_timelock_slots: u64,
+    pub max_pending_transfers: u16,
+}
+
+impl From<LegacyConfigV2> for CrossChainConfig {
+    /// The hub chain id starts unset; the next config update fixes it.
+    fn from(config: LegacyConfigV2) -> Self {
+        Self {
+            admin: config.admin,
+            bridge_authority: config.bridge_authority,
+            eidas_authority: config.eidas_authority,
+            arcium_program: config.arcium_program,
+            supported_chains: config.supported_chains,
+            min_cross_chain_amount: config.min_cross_chain_amount,
+            max_cross_chain_amount: config.max_cross_chain_amount,
+            fee_basis_points: config.fee_basis_points,
+            paused: config.paused,
+            guardian: config.guardian,
+            genesis_hash: config.genesis_hash,
+            require_top_level: config.require_top_level,
+            registration: config.registration,
+            config_timelock_slots: config.config_timelock_slots,
+            max_pending_transfers: config.max_pending_transfers,
+            hub_chain_id: 0,
+        }
+    }
+}
+
+/// Queued config inside a version 2 state.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyPendingConfigV2 {
+    pub config: LegacyConfigV2,
+    pub eta_slot: u64,
+}
+
+/// Body of a version 2 state. As with `LegacyStateV1`, embedded types not
+/// copied here have kept their layout since.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyStateV2 {
+    pub config: LegacyConfigV2,
+    pub total_volume: u64,
+    pub total_transactions: u64,
+    pub registered_wallets: u64,
+    pub compliance_records: u64,
+    pub bridge_config: BridgeConfig,
+    pub compliance_matrix: ComplianceMatrix,
+    pub pending_admin: Option<Pubkey>,
+    pub pending_config: Option<LegacyPendingConfigV2>,
+    pub pause_flags: PauseFlags,
+    pub fee_ledger: FeeLedger,
+    pub escrow_ledger: EscrowLedger,
+    pub bump: u8,
+}
+
+impl From<LegacyStateV2> for CrossChainState {
+    fn from(legacy: LegacyStateV2) -> Self {
+        Self {
+            config: legacy.config.into(),
+            total_volume: legacy.total_volume,
+            total_transactions: legacy.total_transactions,
+            registered_wallets: legacy.registered_wallets,
+            compliance_records: legacy.compliance_records,
+            bridge_config: legacy.bridge_config,
+            compliance_matrix: legacy.compliance_matrix,
+            pending_admin: legacy.pending_admin,
+            pending_config: legacy.pending_config.map(|pending| PendingConfigUpdate {
+                config: pending.config.into(),
+                eta_slot: pending.eta_slot,
+            }),
+            pause_flags: legacy.pause_flags,
+            fee_ledger: legacy.fee_ledger,
+            escrow_ledger: legacy.escrow_ledger,
+            bump: legacy.bump,
+        }
+    }
+}
+
+/// Decodes a tagged state at an older version into the current layout, or
+/// returns `None` when it is already current.
+pub fn upgrade_state(data: &[u8]) -> Result<Option<CrossChainState>, ProgramError> {
+    match data.get(8) {
+        Some(&version) if version == CrossChainState::VERSION => Ok(None),
+        Some(1) => {
+            let mut body = check_header(data, &CrossChainState::DISCRIMINATOR, 1)?;
+            Ok(Some(LegacyStateV1::deserialize(&mut body)?.into()))
+        }
+        Some(2) => {
+            let mut body = check_header(data, &CrossChainState::DISCRIMINATOR, 2)?;
+            Ok(Some(LegacyStateV2::deserialize(&mut body)?.into()))
+        }
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// Fee history as version 1 wrote it, before corridor entries. Frozen.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct LegacyFeeScheduleHistoryV1 {
+    pub entries: BoundedVec<FeeSchedule, FEE_HISTORY_CAPACITY>,
+    pub evicted: u64,
+    pub bump: u8,
+}
+
+impl From<LegacyFeeScheduleHistoryV1> for FeeScheduleHistory {
+    fn from(legacy: LegacyFeeScheduleHistoryV1) -> Self {
+        Self {
+            entries: legacy.entries,
+            evicted: legacy.evicted,
+            ..FeeScheduleHistory::new(legacy.bump)
+        }
+    }
+}
+
+/// Decodes a tagged fee history at an older version into the current
+/// layout, or returns `None` when it is already current.
+pub fn upgrade_fee_history(data: &[u8]) -> Result<Option<FeeScheduleHistory>, ProgramError> {
+    match data.get(8) {
+        Some(&version) if version == FeeScheduleHistory::VERSION => Ok(None),
+        Some(1) => {
+            let mut body = check_header(data, &FeeScheduleHistory::DISCRIMINATOR, 1)?;
+            Ok(Some(
+                LegacyFeeScheduleHistoryV1::deserialize(&mut body)?.into(),
+            ))
+        }
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+/// Decodes a tagged wallet at an older version into the current layout, or
+/// returns `None` when it is already current.
+pub fn upgrade_wallet(data: &[u8]) -> Result<Option<Wallet>, ProgramError> {
+    match data.get(8) {
+        Some(&version) if version == Wallet::VERSION => Ok(None),
+        Some(1) => {
+            let mut body = check_header(data, &Wallet::DISCRIMINATOR, 1)?;
+            Ok(Some(LegacyWalletV1::deserialize(&mut body)?.into()))
+        }
+        Some(2) => {
+            let mut body = check_header(data, &Wallet::DISCRIMINATOR, 2)?;
+            Ok(Some(LegacyWalletV2::deserialize(&mut body)?.into()))
+        }
+        _ => Err(ProgramError::InvalidAccountData),
+    }
+}
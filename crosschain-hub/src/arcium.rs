@@ -1,30 +1,171 @@
+use crate::bounded::BoundedBytes;
+use crate::error::CrossChainError;
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use solana_program::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+pub const ARCIUM_COMPUTATION_SEED: &[u8] = b"arcium_computation";
+pub const ENCRYPTED_STATE_SEED: &[u8] = b"encrypted_state";
+/// Seed of the Arcium PDA that signs result callbacks into the hub.
+pub const ARCIUM_CALLBACK_AUTHORITY_SEED: &[u8] = b"callback_authority";
+pub const MAX_COMPUTATION_RESULT_LEN: usize = 1024;
+/// Decode-time cap on each variable-length field of `ArciumTransactionData`;
+/// the configured `max_encrypted_state_size` is enforced on top of it.
+pub const MAX_ARCIUM_INPUT_LEN: usize = 2048;
+pub const MAX_ENCRYPTION_PUBLIC_KEY_LEN: usize = 32;
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct ArciumTransactionData {
-    pub encrypted_payload: Vec<u8>,
-    pub ciphertext: Vec<u8>,
-    pub proof: Vec<u8>,
-    pub public_inputs: Vec<u8>,
+    pub encrypted_payload: BoundedBytes<MAX_ARCIUM_INPUT_LEN>,
+    pub ciphertext: BoundedBytes<MAX_ARCIUM_INPUT_LEN>,
+    pub proof: BoundedBytes<MAX_ARCIUM_INPUT_LEN>,
+    pub public_inputs: BoundedBytes<MAX_ARCIUM_INPUT_LEN>,
     pub arcium_program_id: Pubkey,
-    pub encryption_public_key: Vec<u8>,
+    pub encryption_public_key: BoundedBytes<MAX_ENCRYPTION_PUBLIC_KEY_LEN>,
+    /// Requester-chosen id that seeds the computation account.
+    pub computation_offset: u64,
+}
+
+/// Instructions of the Arcium program the hub calls.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum ArciumInstruction {
+    /// Queues an encrypted computation. When the MXE cluster finishes it,
+    /// Arcium invokes `callback_program` with `ArciumCallback`, signed by its
+    /// callback authority PDA, to write the result to `callback_account`.
+    QueueComputation {
+        computation_offset: u64,
+        ciphertext: Vec<u8>,
+        public_inputs: Vec<u8>,
+        encryption_public_key: Vec<u8>,
+        proof: Vec<u8>,
+        callback_program: Pubkey,
+        callback_account: Pubkey,
+    },
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ComputationStatus {
+    Queued,
+    Completed,
+    Failed,
+}
+
+/// Hub-side record of a queued Arcium computation, written by the callback.
+/// Allocated for `MAX_COMPUTATION_RESULT_LEN` bytes of result.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ArciumComputation {
+    pub requester: Pubkey,
+    pub arcium_program: Pubkey,
+    pub computation_offset: u64,
+    pub ciphertext_hash: [u8; 32],
+    pub status: ComputationStatus,
+    pub result: Vec<u8>,
+    pub queued_slot: u64,
+    pub completed_slot: u64,
+    pub bump: u8,
+}
+
+impl ArciumComputation {
+    pub const LEN: usize = 32 + 32 + 8 + 32 + 1 + 4 + MAX_COMPUTATION_RESULT_LEN + 8 + 8 + 1;
+
+    pub fn new(
+        requester: Pubkey,
+        data: &ArciumTransactionData,
+        arcium_program: Pubkey,
+        slot: u64,
+        bump: u8,
+    ) -> Self {
+        Self {
+            requester,
+            arcium_program,
+            computation_offset: data.computation_offset,
+            ciphertext_hash: hash(&data.ciphertext).to_bytes(),
+            status: ComputationStatus::Queued,
+            result: Vec::new(),
+            queued_slot: slot,
+            completed_slot: 0,
+            bump,
+        }
+    }
+
+    /// Records the callback outcome. A computation resolves exactly once; a
+    /// failed one keeps whatever error output Arcium returned.
+    pub fn resolve(
+        &mut self,
+        success: bool,
+        result: Vec<u8>,
+        slot: u64,
+    ) -> Result<bool, CrossChainError> {
+        if self.status != ComputationStatus::Queued {
+            return Err(CrossChainError::ComputationNotPending);
+        }
+
+        if result.len() > MAX_COMPUTATION_RESULT_LEN {
+            return Err(CrossChainError::EncryptedStateTooLarge);
+        }
+
+        self.status = if success {
+            ComputationStatus::Completed
+        } else {
+            ComputationStatus::Failed
+        };
+        self.result = result;
+        self.completed_slot = slot;
+        Ok(true)
+    }
 }
 
+/// Confidential balance of a wallet, held in a PDA derived from the wallet
+/// account. Only the Pedersen commitment is meaningful on-chain; every update
+/// must prove the new commitment follows from the stored one.
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct EncryptedWalletState {
+    pub wallet: Pubkey,
     pub encrypted_balance: Vec<u8>,
     pub encrypted_nonce: Vec<u8>,
     pub ciphertext_commitment: Vec<u8>,
     pub encryption_public_key: Vec<u8>,
+    pub updated_slot: u64,
+    pub bump: u8,
+}
+
+impl EncryptedWalletState {
+    pub fn new(wallet: Pubkey, data: EncryptedStateData, slot: u64, bump: u8) -> Self {
+        Self {
+            wallet,
+            encrypted_balance: data.encrypted_balance.into_inner(),
+            encrypted_nonce: data.encrypted_nonce.into_inner(),
+            ciphertext_commitment: data.ciphertext_commitment.into_inner(),
+            encryption_public_key: data.encryption_public_key.into_inner(),
+            updated_slot: slot,
+            bump,
+        }
+    }
+}
+
+/// Ciphertext and commitment supplied by `InitEncryptedState` and
+/// `UpdateEncryptedState`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct EncryptedStateData {
+    pub encrypted_balance: BoundedBytes<MAX_ARCIUM_INPUT_LEN>,
+    pub encrypted_nonce: BoundedBytes<MAX_ARCIUM_INPUT_LEN>,
+    pub ciphertext_commitment: BoundedBytes<PEDERSEN_COMMITMENT_LEN>,
+    pub encryption_public_key: BoundedBytes<MAX_ENCRYPTION_PUBLIC_KEY_LEN>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct ConfidentialTransferProof {
-    pub zero_balance_proof: Vec<u8>,
-    pub range_proof: Vec<u8>,
-    pub ciphertext: Vec<u8>,
-    pub public_encryption_key: Vec<u8>,
+    pub zero_balance_proof: BoundedBytes<MAX_ARCIUM_INPUT_LEN>,
+    /// Data of the `VerifyBatchedRangeProofU64` instruction sent to the ZK
+    /// ElGamal proof program earlier in the transaction.
+    pub range_proof: BoundedBytes<MAX_ARCIUM_INPUT_LEN>,
+    /// ElGamal ciphertext of the amount; its commitment half must be covered
+    /// by `range_proof`.
+    pub ciphertext: BoundedBytes<ELGAMAL_CIPHERTEXT_LEN>,
+    pub public_encryption_key: BoundedBytes<MAX_ENCRYPTION_PUBLIC_KEY_LEN>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
@@ -59,42 +200,412 @@ pub enum ArciumOperation {
 pub fn validate_arcium_transaction(
     data: &ArciumTransactionData,
     config: &ArciumConfig,
-) -> Result<bool, String> {
+) -> Result<bool, CrossChainError> {
     if config.encryption_enabled && data.encrypted_payload.is_empty() {
-        return Err("Encrypted payload required".to_string());
+        return Err(CrossChainError::EncryptedPayloadRequired);
     }
 
     if config.proof_required && data.proof.is_empty() {
-        return Err("Zero-knowledge proof required".to_string());
+        return Err(CrossChainError::ProofRequired);
     }
 
     if data.ciphertext.len() > config.max_encrypted_state_size {
-        return Err("Encrypted state too large".to_string());
+        return Err(CrossChainError::EncryptedStateTooLarge);
     }
 
     Ok(true)
 }
 
-pub fn create_encrypted_payload(plaintext: &[u8], public_key: &[u8]) -> Result<Vec<u8>, String> {
+/// Checks a new encrypted state fits `max_encrypted_state_size` and carries a
+/// well-formed commitment and key.
+pub fn validate_encrypted_state(
+    data: &EncryptedStateData,
+    config: &ArciumConfig,
+) -> Result<bool, CrossChainError> {
+    if data.encrypted_balance.is_empty() {
+        return Err(CrossChainError::EncryptedPayloadRequired);
+    }
+
+    if data.encrypted_balance.len() + data.encrypted_nonce.len() > config.max_encrypted_state_size {
+        return Err(CrossChainError::EncryptedStateTooLarge);
+    }
+
+    if data.ciphertext_commitment.len() != PEDERSEN_COMMITMENT_LEN {
+        return Err(CrossChainError::InvalidCommitment);
+    }
+
+    if data.encryption_public_key.len() != X25519_KEY_LEN {
+        return Err(CrossChainError::InvalidEncryptionKey);
+    }
+
+    Ok(true)
+}
+
+/// Version byte leading every sealed Arcium payload.
+pub const ENCRYPTED_PAYLOAD_VERSION: u8 = 1;
+pub const X25519_KEY_LEN: usize = 32;
+pub const XCHACHA20_NONCE_LEN: usize = 24;
+pub const POLY1305_TAG_LEN: usize = 16;
+/// Bytes `create_encrypted_payload` adds around the plaintext: version,
+/// ephemeral public key, nonce and authentication tag.
+pub const ENCRYPTED_PAYLOAD_OVERHEAD: usize =
+    1 + X25519_KEY_LEN + XCHACHA20_NONCE_LEN + POLY1305_TAG_LEN;
+
+const PAYLOAD_KEY_DOMAIN: &[u8] = b"rivicq-arcium-payload-v1";
+
+/// Encrypts `plaintext` to the X25519 `public_key`: a fresh ephemeral key
+/// agrees a shared secret with the recipient, which keys XChaCha20-Poly1305.
+/// The output is `version || ephemeral_public || nonce || ciphertext || tag`
+/// and only the holder of the matching secret can open it with
+/// `decrypt_payload`.
+#[cfg(not(target_os = "solana"))]
+pub fn create_encrypted_payload(
+    plaintext: &[u8],
+    public_key: &[u8],
+) -> Result<Vec<u8>, CrossChainError> {
+    use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+    use chacha20poly1305::XChaCha20Poly1305;
+    use x25519_dalek::{EphemeralSecret, PublicKey};
+
     if plaintext.is_empty() {
-        return Err("Plaintext cannot be empty".to_string());
+        return Err(CrossChainError::EmptyPlaintext);
     }
 
     if public_key.is_empty() {
-        return Err("Public key cannot be empty".to_string());
+        return Err(CrossChainError::EmptyPublicKey);
+    }
+
+    let recipient = PublicKey::from(x25519_key(public_key)?);
+    let ephemeral = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public = PublicKey::from(&ephemeral);
+    let shared = ephemeral.diffie_hellman(&recipient);
+    if !shared.was_contributory() {
+        return Err(CrossChainError::InvalidEncryptionKey);
+    }
+
+    let key = payload_key(
+        shared.as_bytes(),
+        ephemeral_public.as_bytes(),
+        recipient.as_bytes(),
+    );
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = XChaCha20Poly1305::new(&key.into())
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| CrossChainError::InvalidEncryptionKey)?;
+
+    let mut payload = Vec::with_capacity(ENCRYPTED_PAYLOAD_OVERHEAD + plaintext.len());
+    payload.push(ENCRYPTED_PAYLOAD_VERSION);
+    payload.extend_from_slice(ephemeral_public.as_bytes());
+    payload.extend_from_slice(&nonce);
+    payload.extend_from_slice(&ciphertext);
+    Ok(payload)
+}
+
+/// Opens a payload sealed by `create_encrypted_payload` with the recipient's
+/// X25519 secret key. Any tampering, or the wrong key, fails authentication.
+#[cfg(not(target_os = "solana"))]
+pub fn decrypt_payload(payload: &[u8], secret_key: &[u8]) -> Result<Vec<u8>, CrossChainError> {
+    use chacha20poly1305::aead::{Aead, KeyInit};
+    use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+    use x25519_dalek::{PublicKey, StaticSecret};
+
+    let secret = StaticSecret::from(x25519_key(secret_key)?);
+
+    if payload.len() <= ENCRYPTED_PAYLOAD_OVERHEAD {
+        return Err(CrossChainError::PayloadDecryptionFailed);
+    }
+
+    if payload[0] != ENCRYPTED_PAYLOAD_VERSION {
+        return Err(CrossChainError::PayloadDecryptionFailed);
+    }
+
+    let (ephemeral, rest) = payload[1..].split_at(X25519_KEY_LEN);
+    let (nonce, ciphertext) = rest.split_at(XCHACHA20_NONCE_LEN);
+
+    let ephemeral_public = PublicKey::from(x25519_key(ephemeral)?);
+    let shared = secret.diffie_hellman(&ephemeral_public);
+    if !shared.was_contributory() {
+        return Err(CrossChainError::PayloadDecryptionFailed);
     }
 
-    Ok(plaintext.to_vec())
+    let recipient = PublicKey::from(&secret);
+    let key = payload_key(
+        shared.as_bytes(),
+        ephemeral_public.as_bytes(),
+        recipient.as_bytes(),
+    );
+    XChaCha20Poly1305::new(&key.into())
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| CrossChainError::PayloadDecryptionFailed)
 }
 
-pub fn verify_zero_knowledge_proof(proof: &[u8], public_inputs: &[u8]) -> Result<bool, String> {
+#[cfg(not(target_os = "solana"))]
+fn x25519_key(bytes: &[u8]) -> Result<[u8; X25519_KEY_LEN], CrossChainError> {
+    bytes
+        .try_into()
+        .map_err(|_| CrossChainError::InvalidEncryptionKey)
+}
+
+/// Binds the symmetric key to both public keys so a payload cannot be
+/// re-addressed to another recipient.
+#[cfg(not(target_os = "solana"))]
+fn payload_key(shared: &[u8; 32], ephemeral: &[u8; 32], recipient: &[u8; 32]) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(PAYLOAD_KEY_DOMAIN);
+    hasher.update(shared);
+    hasher.update(ephemeral);
+    hasher.update(recipient);
+    hasher.finalize().into()
+}
+
+/// Native program that verifies ZK ElGamal proofs (range, equality,
+/// validity). It cannot be invoked through CPI, so the hub checks that its
+/// instructions ran earlier in the same transaction.
+pub const ZK_ELGAMAL_PROOF_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("ZkE1Gama1Proof11111111111111111111111111111");
+/// `ProofInstruction` discriminators of the batched range-proof verifiers.
+pub const VERIFY_BATCHED_RANGE_PROOF_U64: u8 = 6;
+pub const VERIFY_BATCHED_RANGE_PROOF_U128: u8 = 7;
+pub const VERIFY_BATCHED_RANGE_PROOF_U256: u8 = 8;
+pub const MAX_RANGE_PROOF_COMMITMENTS: usize = 8;
+pub const PEDERSEN_COMMITMENT_LEN: usize = 32;
+/// Twisted ElGamal ciphertext: Pedersen commitment followed by the
+/// decryption handle.
+pub const ELGAMAL_CIPHERTEXT_LEN: usize = 64;
+/// Widest range a confidential amount may be proven in.
+pub const MAX_CONFIDENTIAL_AMOUNT_BITS: u8 = 64;
+
+const RANGE_PROOF_CONTEXT_LEN: usize =
+    MAX_RANGE_PROOF_COMMITMENTS * PEDERSEN_COMMITMENT_LEN + MAX_RANGE_PROOF_COMMITMENTS;
+
+/// Public part of a batched range proof: each commitment is proven to open to
+/// a value below `2^bit_length`. Unused slots are dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeProofContext {
+    pub commitments: Vec<[u8; PEDERSEN_COMMITMENT_LEN]>,
+    pub bit_lengths: Vec<u8>,
+}
+
+impl RangeProofContext {
+    /// Bit length the proof establishes for `commitment`, if it covers it.
+    pub fn bit_length_of(&self, commitment: &[u8]) -> Option<u8> {
+        self.commitments
+            .iter()
+            .position(|c| c.as_slice() == commitment)
+            .map(|i| self.bit_lengths[i])
+    }
+}
+
+/// Decodes the context of a `VerifyBatchedRangeProofU{64,128,256}`
+/// instruction carrying its proof inline. Proofs read from an account or a
+/// pre-verified context state account are not accepted.
+pub fn parse_range_proof_instruction(data: &[u8]) -> Result<RangeProofContext, CrossChainError> {
+    match data.first() {
+        Some(&VERIFY_BATCHED_RANGE_PROOF_U64)
+        | Some(&VERIFY_BATCHED_RANGE_PROOF_U128)
+        | Some(&VERIFY_BATCHED_RANGE_PROOF_U256) => {}
+        _ => return Err(CrossChainError::InvalidRangeProof),
+    }
+
+    let context = data
+        .get(1..1 + RANGE_PROOF_CONTEXT_LEN)
+        .ok_or(CrossChainError::InvalidRangeProof)?;
+    if data.len() == 1 + RANGE_PROOF_CONTEXT_LEN {
+        // context without a proof body
+        return Err(CrossChainError::InvalidRangeProof);
+    }
+
+    let (commitments, bit_lengths) =
+        context.split_at(MAX_RANGE_PROOF_COMMITMENTS * PEDERSEN_COMMITMENT_LEN);
+    let mut parsed = RangeProofContext {
+        commitments: Vec::new(),
+        bit_lengths: Vec::new(),
+    };
+    for (commitment, bits) in commitments
+        .chunks_exact(PEDERSEN_COMMITMENT_LEN)
+        .zip(bit_lengths.iter())
+    {
+        if *bits == 0 {
+            continue;
+        }
+        let mut c = [0u8; PEDERSEN_COMMITMENT_LEN];
+        c.copy_from_slice(commitment);
+        parsed.commitments.push(c);
+        parsed.bit_lengths.push(*bits);
+    }
+
+    if parsed.commitments.is_empty() {
+        return Err(CrossChainError::InvalidRangeProof);
+    }
+
+    Ok(parsed)
+}
+
+/// Checks that `proof.range_proof` is a range-proof instruction the ZK
+/// ElGamal proof program verified in this transaction (`verified`), and that
+/// it bounds the commitment inside `proof.ciphertext` to at most
+/// `MAX_CONFIDENTIAL_AMOUNT_BITS`.
+pub fn verify_confidential_range_proof(
+    proof: &ConfidentialTransferProof,
+    verified: &[RangeProofContext],
+) -> Result<bool, CrossChainError> {
+    if proof.range_proof.is_empty() {
+        return Err(CrossChainError::ProofRequired);
+    }
+
+    let context = parse_range_proof_instruction(&proof.range_proof)?;
+    if !verified.contains(&context) {
+        return Err(CrossChainError::RangeProofNotVerified);
+    }
+
+    if proof.ciphertext.len() != ELGAMAL_CIPHERTEXT_LEN {
+        return Err(CrossChainError::InvalidRangeProof);
+    }
+
+    match context.bit_length_of(&proof.ciphertext[..PEDERSEN_COMMITMENT_LEN]) {
+        Some(bits) if bits <= MAX_CONFIDENTIAL_AMOUNT_BITS => Ok(true),
+        _ => Err(CrossChainError::InvalidRangeProof),
+    }
+}
+
+/// Checks the verified range proof behind `proof` keeps the hidden amount at
+/// or below the publicly disclosed `amount_bound`: a proof over `n` bits
+/// admits amounts up to `2^n - 1`.
+pub fn verify_confidential_amount_bound(
+    proof: &ConfidentialTransferProof,
+    verified: &[RangeProofContext],
+    amount_bound: u64,
+) -> Result<bool, CrossChainError> {
+    verify_confidential_range_proof(proof, verified)?;
+
+    let bits = parse_range_proof_instruction(&proof.range_proof)?
+        .bit_length_of(&proof.ciphertext[..PEDERSEN_COMMITMENT_LEN])
+        .ok_or(CrossChainError::InvalidRangeProof)?;
+    if (1u128 << bits) - 1 > amount_bound as u128 {
+        return Err(CrossChainError::ConfidentialBoundExceeded);
+    }
+
+    Ok(true)
+}
+
+/// Checks that `proof` is a well-formed range-proof instruction covering
+/// every 32-byte commitment in `public_inputs`. The proof itself is verified
+/// by the ZK ElGamal proof program; on-chain callers must also confirm that
+/// instruction ran, as `verify_confidential_range_proof` does.
+pub fn verify_zero_knowledge_proof(
+    proof: &[u8],
+    public_inputs: &[u8],
+) -> Result<bool, CrossChainError> {
     if proof.is_empty() {
-        return Err("Proof cannot be empty".to_string());
+        return Err(CrossChainError::ProofRequired);
     }
 
     if public_inputs.is_empty() {
-        return Err("Public inputs cannot be empty".to_string());
+        return Err(CrossChainError::EmptyPublicInputs);
+    }
+
+    if !public_inputs.len().is_multiple_of(PEDERSEN_COMMITMENT_LEN) {
+        return Err(CrossChainError::InvalidRangeProof);
+    }
+
+    let context = parse_range_proof_instruction(proof)?;
+    if public_inputs
+        .chunks_exact(PEDERSEN_COMMITMENT_LEN)
+        .any(|commitment| context.bit_length_of(commitment).is_none())
+    {
+        return Err(CrossChainError::InvalidRangeProof);
     }
 
     Ok(true)
 }
+
+/// Checks that a batched range proof the ZK ElGamal proof program verified in
+/// this transaction (`verified`) covers both `old_commitment` and
+/// `new_commitment`. Both openings feed one proof transcript, so it can only
+/// be produced by someone holding the old state and deriving the new one.
+pub fn verify_state_transition(
+    old_commitment: &[u8],
+    new_commitment: &[u8],
+    verified: &[RangeProofContext],
+) -> Result<bool, CrossChainError> {
+    if verified.is_empty() {
+        return Err(CrossChainError::RangeProofNotVerified);
+    }
+
+    let within_range = |context: &RangeProofContext, commitment: &[u8]| {
+        matches!(
+            context.bit_length_of(commitment),
+            Some(bits) if bits <= MAX_CONFIDENTIAL_AMOUNT_BITS
+        )
+    };
+    if verified.iter().any(|context| {
+        within_range(context, old_commitment) && within_range(context, new_commitment)
+    }) {
+        Ok(true)
+    } else {
+        Err(CrossChainError::InvalidStateTransition)
+    }
+}
+
+/// Builds the `QueueComputation` CPI. `arcium_accounts` are the Arcium
+/// accounts the caller supplied (mempool, cluster, fee pool), passed through
+/// with their signer and writable flags.
+pub fn queue_computation_instruction(
+    arcium_program: &Pubkey,
+    requester: &Pubkey,
+    computation_account: &Pubkey,
+    callback_program: &Pubkey,
+    data: &ArciumTransactionData,
+    arcium_accounts: Vec<AccountMeta>,
+) -> Result<Instruction, CrossChainError> {
+    let instruction = ArciumInstruction::QueueComputation {
+        computation_offset: data.computation_offset,
+        ciphertext: data.ciphertext.to_vec(),
+        public_inputs: data.public_inputs.to_vec(),
+        encryption_public_key: data.encryption_public_key.to_vec(),
+        proof: data.proof.to_vec(),
+        callback_program: *callback_program,
+        callback_account: *computation_account,
+    };
+    let payload = instruction
+        .try_to_vec()
+        .map_err(|_| CrossChainError::EncryptedStateTooLarge)?;
+
+    let mut accounts = vec![
+        AccountMeta::new(*requester, true),
+        AccountMeta::new_readonly(*computation_account, false),
+        AccountMeta::new_readonly(*callback_program, false),
+    ];
+    accounts.extend(arcium_accounts);
+
+    Ok(Instruction::new_with_bytes(
+        *arcium_program,
+        &payload,
+        accounts,
+    ))
+}
+
+pub fn get_arcium_computation_address(
+    requester: &Pubkey,
+    computation_offset: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ARCIUM_COMPUTATION_SEED,
+            requester.as_ref(),
+            &computation_offset.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn get_encrypted_state_address(wallet: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ENCRYPTED_STATE_SEED, wallet.as_ref()], program_id)
+}
+
+pub fn get_arcium_callback_authority(arcium_program: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ARCIUM_CALLBACK_AUTHORITY_SEED], arcium_program)
+}
@@ -0,0 +1,165 @@
+use crate::bridge::get_transfer_record_address;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{clock::Clock, pubkey::Pubkey};
+
+pub const AUDIT_LOG_SEED: &[u8] = b"audit_log";
+pub const AUDIT_LOG_CAPACITY: usize = 64;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    WalletRegistered,
+    ComplianceVerified,
+    ComplianceRenewed,
+    TransferInitiated,
+    TransferConfirmed,
+    TransferCompleted,
+    TransferClaimed,
+    TransferRefunded,
+    TransferHeld,
+    HoldReleased,
+    HoldRejected,
+    TransferCancelled,
+    WalletRecovered,
+    WalletFrozen,
+    WalletUnfrozen,
+}
+
+/// One fixed-size audit entry. `actor` signed the instruction; `subject` is
+/// the wallet owner or transfer record it acted on.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub action: AuditAction,
+    pub actor: Pubkey,
+    pub subject: Pubkey,
+    pub amount: u64,
+}
+
+impl AuditEntry {
+    pub const LEN: usize = 8 + 8 + 8 + 1 + 32 + 32 + 8;
+
+    /// The sequence id is assigned when the entry is appended.
+    pub fn new(
+        action: AuditAction,
+        actor: Pubkey,
+        subject: Pubkey,
+        amount: u64,
+        clock: &Clock,
+    ) -> Self {
+        Self {
+            sequence: 0,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+            action,
+            actor,
+            subject,
+            amount,
+        }
+    }
+}
+
+/// Program-wide append-only audit trail. Sequence ids increase by one per
+/// entry and never repeat, so a reader can tell how many entries it missed
+/// once the oldest of the last `AUDIT_LOG_CAPACITY` are overwritten.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AuditLogBuffer {
+    pub head: u16,
+    pub next_sequence: u64,
+    pub entries: Vec<AuditEntry>,
+    pub bump: u8,
+}
+
+impl AuditLogBuffer {
+    pub const LEN: usize = 2 + 8 + 4 + AUDIT_LOG_CAPACITY * AuditEntry::LEN + 1;
+
+    pub fn new(bump: u8) -> Self {
+        Self {
+            head: 0,
+            next_sequence: 0,
+            entries: Vec::with_capacity(AUDIT_LOG_CAPACITY),
+            bump,
+        }
+    }
+
+    /// Appends `entry` and returns the sequence id it was given.
+    pub fn append(&mut self, mut entry: AuditEntry) -> u64 {
+        let sequence = self.next_sequence;
+        entry.sequence = sequence;
+
+        if self.entries.len() < AUDIT_LOG_CAPACITY {
+            self.entries.push(entry);
+        } else {
+            self.entries[self.head as usize] = entry;
+        }
+
+        self.head = ((self.head as usize + 1) % AUDIT_LOG_CAPACITY) as u16;
+        self.next_sequence = sequence.saturating_add(1);
+        sequence
+    }
+
+    /// Entries ordered from oldest to most recent.
+    pub fn entries_in_order(&self) -> Vec<&AuditEntry> {
+        let split = if self.entries.len() < AUDIT_LOG_CAPACITY {
+            0
+        } else {
+            self.head as usize
+        };
+
+        self.entries[split..]
+            .iter()
+            .chain(self.entries[..split].iter())
+            .collect()
+    }
+}
+
+/// Names an audit record by what it is about rather than by a random UUID:
+/// the transfer record or other subject it concerns, the slot it was written
+/// in and its audit sequence id. A transfer's record is the PDA of its sender
+/// and nonce, which bridge messages carry, so audit records join to
+/// transfers, messages and on-chain `AuditEntry`s without a lookup table.
+/// Renders as `subject:slot:sequence`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AuditId {
+    pub subject: Pubkey,
+    pub slot: u64,
+    pub sequence: u64,
+}
+
+impl AuditId {
+    /// Id for a record about the transfer `sender` initiated with `nonce`.
+    pub fn for_transfer(
+        sender: &Pubkey,
+        nonce: u64,
+        slot: u64,
+        sequence: u64,
+        program_id: &Pubkey,
+    ) -> Self {
+        Self {
+            subject: get_transfer_record_address(sender, nonce, program_id).0,
+            slot,
+            sequence,
+        }
+    }
+}
+
+impl From<&AuditEntry> for AuditId {
+    fn from(entry: &AuditEntry) -> Self {
+        Self {
+            subject: entry.subject,
+            slot: entry.slot,
+            sequence: entry.sequence,
+        }
+    }
+}
+
+impl std::fmt::Display for AuditId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.subject, self.slot, self.sequence)
+    }
+}
+
+pub fn get_audit_log_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUDIT_LOG_SEED], program_id)
+}
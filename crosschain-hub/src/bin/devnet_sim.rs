@@ -0,0 +1,121 @@
+//! `devnet-sim`: boots a local validator with the hub and a mock EVM node,
+//! then runs initiate → relay → complete round trips against them.
+//!
+//! ```text
+//! cargo build-sbf
+//! cargo run --features devnet-sim --bin devnet-sim -- \
+//!     --program target/deploy/crosschain_hub.so [--transfers 3] [--amount 5000000]
+//! ```
+//!
+//! `solana-test-validator` must be on `PATH`. Pass `--keep-running` to leave
+//! both nodes up after the flows finish for manual testing.
+
+use crosschain_hub::devnet::{
+    DevnetError, DevnetSim, MockEvmNode, TestValidator, DEFAULT_VALIDATOR_RPC_PORT,
+    MOCK_EVM_CHAIN_ID,
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signer::{keypair::Keypair, Signer},
+};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+struct Args {
+    program: PathBuf,
+    program_id: Pubkey,
+    rpc_port: u16,
+    transfers: u64,
+    amount: u64,
+    keep_running: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut program = None;
+    let mut args = Args {
+        program: PathBuf::new(),
+        program_id: Keypair::new().pubkey(),
+        rpc_port: DEFAULT_VALIDATOR_RPC_PORT,
+        transfers: 1,
+        amount: 5_000_000,
+        keep_running: false,
+    };
+
+    let mut argv = std::env::args().skip(1);
+    while let Some(flag) = argv.next() {
+        let mut value = || argv.next().ok_or(format!("{flag} requires a value"));
+        match flag.as_str() {
+            "--program" => program = Some(PathBuf::from(value()?)),
+            "--program-id" => {
+                args.program_id = Pubkey::from_str(&value()?).map_err(|e| e.to_string())?
+            }
+            "--rpc-port" => args.rpc_port = value()?.parse().map_err(|_| "invalid --rpc-port")?,
+            "--transfers" => {
+                args.transfers = value()?.parse().map_err(|_| "invalid --transfers")?
+            }
+            "--amount" => args.amount = value()?.parse().map_err(|_| "invalid --amount")?,
+            "--keep-running" => args.keep_running = true,
+            other => return Err(format!("unknown argument {other}")),
+        }
+    }
+
+    args.program = program.ok_or("--program <path to crosschain_hub.so> is required")?;
+    Ok(args)
+}
+
+fn run(args: &Args) -> Result<(), DevnetError> {
+    let validator = TestValidator::start(&args.program_id, &args.program, args.rpc_port)?;
+    let evm = MockEvmNode::start(MOCK_EVM_CHAIN_ID)?;
+    println!("validator  {}", validator.rpc.url);
+    println!("mock evm   {} (chain {MOCK_EVM_CHAIN_ID})", evm.url());
+    println!("program    {}", args.program_id);
+
+    let sim = DevnetSim::new(&validator.rpc, &evm, args.program_id);
+    sim.bootstrap()?;
+
+    let sender = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+    sim.onboard(&sender)?;
+
+    for nonce in 0..args.transfers {
+        let report = sim.run_transfer(&sender, &recipient, args.amount, nonce)?;
+        println!(
+            "transfer {nonce}: initiate {} -> evm 0x{} (block {}) -> complete {}",
+            report.initiate,
+            hex::encode(report.evm_tx_hash),
+            report.evm_block,
+            report.complete
+        );
+    }
+    println!(
+        "recipient {recipient} balance: {} lamports",
+        validator.rpc.balance(&recipient)?
+    );
+
+    if args.keep_running {
+        println!("nodes running; press Ctrl-C to stop");
+        loop {
+            std::thread::park();
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("devnet-sim: {e}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("devnet-sim: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
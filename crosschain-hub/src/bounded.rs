@@ -0,0 +1,327 @@
+//! Length-capped collections for instruction and message payloads.
+//!
+//! Borsh decodes a `Vec` or `String` by reading a `u32` length prefix and then
+//! that many elements, so an attacker-chosen prefix can make the program spin
+//! through a huge claimed length before the input runs out. These wrappers
+//! check the prefix against an explicit maximum before reading any element.
+//! The wire format is identical to the unbounded types, so accounts written
+//! before the switch still decode.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::ops::{Deref, DerefMut};
+
+/// Reads a Borsh length prefix and rejects it when it exceeds `max`, returning
+/// the raw prefix so the caller can hand it back to the inner decoder.
+fn read_bounded_len<R: Read>(reader: &mut R, max: usize) -> Result<[u8; 4]> {
+    let mut prefix = [0u8; 4];
+    reader.read_exact(&mut prefix)?;
+    let len = u32::from_le_bytes(prefix) as usize;
+    if len > max {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("length {len} exceeds maximum {max}"),
+        ));
+    }
+    Ok(prefix)
+}
+
+fn check_len(len: usize, max: usize) -> Result<()> {
+    if len > max {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("length {len} exceeds maximum {max}"),
+        ));
+    }
+    Ok(())
+}
+
+/// A `Vec<T>` holding at most `N` elements on the wire.
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BoundedVec<T, const N: usize>(Vec<T>);
+
+/// Byte buffer of at most `N` bytes.
+pub type BoundedBytes<const N: usize> = BoundedVec<u8, N>;
+
+impl<T, const N: usize> BoundedVec<T, N> {
+    pub const MAX_LEN: usize = N;
+
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T, const N: usize> Deref for BoundedVec<T, N> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T, const N: usize> DerefMut for BoundedVec<T, N> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.0
+    }
+}
+
+impl<T, const N: usize> From<Vec<T>> for BoundedVec<T, N> {
+    fn from(items: Vec<T>) -> Self {
+        Self(items)
+    }
+}
+
+impl<T: Clone, const N: usize> From<&[T]> for BoundedVec<T, N> {
+    fn from(items: &[T]) -> Self {
+        Self(items.to_vec())
+    }
+}
+
+impl<T, const N: usize, const M: usize> From<[T; M]> for BoundedVec<T, N> {
+    fn from(items: [T; M]) -> Self {
+        Self(items.into())
+    }
+}
+
+impl<T, const N: usize> From<BoundedVec<T, N>> for Vec<T> {
+    fn from(items: BoundedVec<T, N>) -> Self {
+        items.0
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for BoundedVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T, const N: usize> IntoIterator for BoundedVec<T, N> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a BoundedVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq<Vec<T>> for BoundedVec<T, N> {
+    fn eq(&self, other: &Vec<T>) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq<BoundedVec<T, N>> for Vec<T> {
+    fn eq(&self, other: &BoundedVec<T, N>) -> bool {
+        *self == other.0
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for BoundedVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: BorshSerialize, const N: usize> BorshSerialize for BoundedVec<T, N> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        check_len(self.0.len(), N)?;
+        self.0.serialize(writer)
+    }
+}
+
+impl<T: BorshDeserialize, const N: usize> BorshDeserialize for BoundedVec<T, N> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let prefix = read_bounded_len(reader, N)?;
+        Vec::<T>::deserialize_reader(&mut (&prefix[..]).chain(reader)).map(Self)
+    }
+}
+
+/// A `String` of at most `N` bytes on the wire.
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BoundedString<const N: usize>(String);
+
+impl<const N: usize> BoundedString<N> {
+    pub const MAX_LEN: usize = N;
+
+    pub fn new() -> Self {
+        Self(String::new())
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl<const N: usize> Deref for BoundedString<N> {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for BoundedString<N> {
+    fn deref_mut(&mut self) -> &mut String {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> std::borrow::Borrow<str> for BoundedString<N> {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<const N: usize> From<String> for BoundedString<N> {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl<const N: usize> From<&str> for BoundedString<N> {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl<const N: usize> From<BoundedString<N>> for String {
+    fn from(value: BoundedString<N>) -> Self {
+        value.0
+    }
+}
+
+impl<const N: usize> PartialEq<str> for BoundedString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for BoundedString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<const N: usize> PartialEq<String> for BoundedString<N> {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == *other
+    }
+}
+
+impl<const N: usize> fmt::Debug for BoundedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<const N: usize> fmt::Display for BoundedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<const N: usize> BorshSerialize for BoundedString<N> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        check_len(self.0.len(), N)?;
+        self.0.serialize(writer)
+    }
+}
+
+impl<const N: usize> BorshDeserialize for BoundedString<N> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let prefix = read_bounded_len(reader, N)?;
+        String::deserialize_reader(&mut (&prefix[..]).chain(reader)).map(Self)
+    }
+}
+
+/// A `HashMap` holding at most `N` entries on the wire.
+#[derive(Clone)]
+pub struct BoundedMap<K, V, const N: usize>(HashMap<K, V>);
+
+impl<K, V, const N: usize> BoundedMap<K, V, N> {
+    pub const MAX_LEN: usize = N;
+
+    pub fn new() -> Self {
+        Self(HashMap::new())
+    }
+
+    pub fn into_inner(self) -> HashMap<K, V> {
+        self.0
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq, const N: usize> PartialEq for BoundedMap<K, V, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<K, V, const N: usize> Default for BoundedMap<K, V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, const N: usize> Deref for BoundedMap<K, V, N> {
+    type Target = HashMap<K, V>;
+
+    fn deref(&self) -> &HashMap<K, V> {
+        &self.0
+    }
+}
+
+impl<K, V, const N: usize> DerefMut for BoundedMap<K, V, N> {
+    fn deref_mut(&mut self) -> &mut HashMap<K, V> {
+        &mut self.0
+    }
+}
+
+impl<K, V, const N: usize> From<HashMap<K, V>> for BoundedMap<K, V, N> {
+    fn from(map: HashMap<K, V>) -> Self {
+        Self(map)
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, const N: usize> fmt::Debug for BoundedMap<K, V, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<K, V, const N: usize> BorshSerialize for BoundedMap<K, V, N>
+where
+    K: BorshSerialize + PartialOrd,
+    V: BorshSerialize,
+{
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        check_len(self.0.len(), N)?;
+        self.0.serialize(writer)
+    }
+}
+
+impl<K, V, const N: usize> BorshDeserialize for BoundedMap<K, V, N>
+where
+    K: BorshDeserialize + Eq + Hash,
+    V: BorshDeserialize,
+{
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let prefix = read_bounded_len(reader, N)?;
+        HashMap::<K, V>::deserialize_reader(&mut (&prefix[..]).chain(reader)).map(Self)
+    }
+}
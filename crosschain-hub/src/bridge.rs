@@ -1,6 +1,57 @@
-use crate::eidas::EidasLevel;
+use crate::account::VersionedAccount;
+use crate::arcium::{ConfidentialTransferProof, ELGAMAL_CIPHERTEXT_LEN, X25519_KEY_LEN};
+use crate::bounded::{BoundedBytes, BoundedString, BoundedVec};
+use crate::caip::Caip2ChainId;
+use crate::canonical::check_canonical;
+use crate::eidas::{
+    ComplianceMatrix, ComplianceProfile, EidasLevel, Ivms101Payload, SignedComplianceAttestation,
+};
+use crate::error::CrossChainError;
+use crate::merkle::InclusionProof;
+use crate::{CrossChainState, MAX_FEE_BASIS_POINTS, MAX_RELAY_CONFIRMATIONS};
+use axelar::{AxelarApproval, AxelarConfig, AxelarTransport};
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use relay::RelayerQuorumTransport;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, hash::hashv, pubkey::Pubkey,
+};
+use std::borrow::Cow;
+use wormhole::{WormholeConfig, WormholeEmitter, WormholeTransport};
+
+pub mod axelar;
+pub mod relay;
+pub mod wormhole;
+
+pub const CHAIN_CONFIG_SEED: &[u8] = b"chain";
+pub const TRANSFER_RECORD_SEED: &[u8] = b"transfer";
+pub const TRANSFER_BATCH_SEED: &[u8] = b"transfer_batch";
+pub const CHAIN_PAIR_POLICY_SEED: &[u8] = b"chain_pair";
+pub const ESCROW_AUTHORITY_SEED: &[u8] = b"escrow_authority";
+pub const INBOUND_RECEIPT_SEED: &[u8] = b"inbound";
+pub const WRAPPED_REGISTRY_SEED: &[u8] = b"wrapped_registry";
+pub const MINT_AUTHORITY_SEED: &[u8] = b"mint_authority";
+pub const DISCREPANCY_SEED: &[u8] = b"discrepancy";
+pub const MAX_ESCROW_ASSETS: usize = 64;
+pub const MAX_WRAPPED_ASSETS: usize = 64;
+pub const MAX_DISTRIBUTION_RECIPIENTS: usize = 64;
+pub const DISTRIBUTION_DOMAIN: &[u8] = b"rivicq-distribution-v1";
+
+pub const MAX_TRANSFER_PAYLOAD_LEN: usize = 256;
+pub const MAX_BATCH_TRANSFERS: usize = 16;
+pub const MAX_BATCH_PAYLOAD_LEN: usize = 4 + MAX_BATCH_TRANSFERS * BatchEntry::LEN;
+pub const MAX_CONTRACT_CALL_PAYLOAD_LEN: usize = 4096;
+pub const MAX_CALL_TARGET_LEN: usize = 32;
+/// Largest payload any message type may carry; see `MessageType::max_payload_len`.
+pub const MAX_MESSAGE_PAYLOAD_LEN: usize = MAX_CONTRACT_CALL_PAYLOAD_LEN + SealedPayload::OVERHEAD;
+pub const MAX_MESSAGE_ID_LEN: usize = 64;
+pub const MAX_SUPPORTED_TOKENS: usize = 32;
+pub const MAX_TOKEN_SYMBOL_LEN: usize = 16;
+pub const MAX_RELAY_TX_HASH_LEN: usize = 64;
+pub const MAX_RELAY_SIGNATURES: usize = 16;
+pub const MAX_RELAY_SIGNATURE_LEN: usize = 65;
+/// Poly1305 tag appended to every sealed ciphertext.
+pub const SEALED_PAYLOAD_TAG_LEN: usize = 16;
+pub const SEALED_PAYLOAD_VERSION: u8 = 1;
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct CrossChainTransferData {
@@ -14,6 +65,506 @@ pub struct CrossChainTransferData {
     pub nonce: u64,
     pub timestamp: i64,
     pub status: TransferStatus,
+    /// IVMS 101 originator and beneficiary data for regulated transfers.
+    pub travel_rule: Option<Ivms101Payload>,
+}
+
+impl CrossChainTransferData {
+    /// Size without a Travel Rule payload; see `TransferRecord::space`.
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 33 + 8 + 8 + 8 + 1 + 1;
+
+    /// Chain whose evidence completes the transfer: the destination for one
+    /// leaving the hub, the source for one arriving.
+    pub fn confirming_chain(&self, hub_chain_id: u64) -> u64 {
+        if self.source_chain == hub_chain_id {
+            self.destination_chain
+        } else {
+            self.source_chain
+        }
+    }
+}
+
+/// On-chain record of an outbound transfer, seeded by sender and nonce. The
+/// account itself escrows native transfers; SPL transfers are held in a token
+/// account owned by the escrow authority PDA.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TransferRecord {
+    pub transfer: CrossChainTransferData,
+    pub escrowed_amount: u64,
+    pub created_slot: u64,
+    pub completed_slot: u64,
+    pub bump: u8,
+    pub wrapped: bool,
+    pub relayer_fee: u64,
+    pub timeout_slots: u64,
+    /// Destination gateway codehash pinned in the registry when the transfer
+    /// was initiated; relayers must deliver only to a gateway matching it.
+    pub gateway_codehash: [u8; 32],
+    pub terms: TransferTerms,
+}
+
+/// Fee and delivery rules in force when a transfer was initiated. Completion
+/// keeps applying them after the admin changes the config, until
+/// `expires_slot` passes and the rules then in force take over.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default, PartialEq)]
+pub struct TransferTerms {
+    pub fee_basis_points: u16,
+    pub relayer_fee: u64,
+    pub claim_delivery: bool,
+    /// Last slot the terms hold; `u64::MAX` when they never lapse.
+    pub expires_slot: u64,
+}
+
+impl TransferTerms {
+    pub const LEN: usize = 2 + 8 + 1 + 8;
+}
+
+impl TransferRecord {
+    pub const LEN: usize =
+        CrossChainTransferData::LEN + 8 + 8 + 8 + 1 + 1 + 8 + 8 + 32 + TransferTerms::LEN;
+
+    /// Account size for this record, including any Travel Rule payload.
+    pub fn space(&self) -> usize {
+        Self::LEN
+            + self
+                .transfer
+                .travel_rule
+                .as_ref()
+                .map_or(0, Ivms101Payload::encoded_len)
+    }
+
+    /// The `TokenTransfer` message relayers deliver for this record. Its
+    /// payload is the Travel Rule data, empty for unregulated transfers.
+    pub fn bridge_message(&self, id: String) -> CrossChainMessage {
+        CrossChainMessage {
+            id: id.into(),
+            source_chain: self.transfer.source_chain,
+            destination_chain: self.transfer.destination_chain,
+            sender: self.transfer.sender,
+            recipient: self.transfer.recipient,
+            message_type: MessageType::TokenTransfer,
+            payload: self
+                .transfer
+                .travel_rule
+                .as_ref()
+                .map(|p| p.try_to_vec().unwrap_or_default())
+                .unwrap_or_default()
+                .into(),
+            nonce: self.transfer.nonce,
+            timestamp: self.transfer.timestamp,
+            gateway_codehash: self.gateway_codehash,
+            compression: PayloadCompression::None,
+        }
+    }
+
+    /// Moves a transfer whose terms lapsed before `slot` onto `current`,
+    /// recharging its fee against the escrow. Returns whether it moved.
+    pub fn apply_terms(&mut self, current: &TransferTerms, slot: u64) -> bool {
+        if slot <= self.terms.expires_slot {
+            return false;
+        }
+
+        // A fee too large to represent is capped at the escrow like any other.
+        let fee = calculate_cross_chain_fee(
+            self.transfer.amount,
+            current.fee_basis_points,
+            current.relayer_fee,
+        )
+        .map_or(self.escrowed_amount, |fee| fee.min(self.escrowed_amount));
+        self.transfer.fee = fee;
+        self.relayer_fee = current.relayer_fee.min(fee);
+        self.terms = TransferTerms {
+            expires_slot: u64::MAX,
+            ..current.clone()
+        };
+        true
+    }
+
+    /// Amount released to the recipient once the transfer completes. Wrapped
+    /// assets are burned on initiation, so nothing is released locally.
+    pub fn release_amount(&self) -> u64 {
+        if self.wrapped {
+            return 0;
+        }
+        self.escrowed_amount.saturating_sub(self.transfer.fee)
+    }
+
+    /// Amount returned to the sender by a refund: the escrow minus fees,
+    /// re-minted for wrapped assets since those were burned on initiation.
+    pub fn refund_amount(&self) -> u64 {
+        self.escrowed_amount.saturating_sub(self.transfer.fee)
+    }
+
+    /// A transfer can be refunded once it has sat in flight, or in review,
+    /// past its timeout.
+    pub fn validate_refund(&self, slot: u64) -> Result<bool, CrossChainError> {
+        if !matches!(
+            self.transfer.status,
+            TransferStatus::Initiated | TransferStatus::Held
+        ) {
+            return Err(CrossChainError::TransferNotInFlight);
+        }
+
+        if slot < self.created_slot.saturating_add(self.timeout_slots) {
+            return Err(CrossChainError::RefundNotYetAvailable);
+        }
+
+        Ok(true)
+    }
+
+    /// Parks the transfer for review; the sender can reclaim it once
+    /// `review_timeout_slots` pass without a decision.
+    pub fn hold(&mut self, review_timeout_slots: u64) {
+        self.transfer.status = TransferStatus::Held;
+        self.timeout_slots = review_timeout_slots;
+    }
+
+    /// Puts a reviewed transfer in flight with a full transfer timeout from
+    /// `slot`, so the time spent in review does not count against it.
+    pub fn release_hold(&mut self, slot: u64, timeout_slots: u64) -> Result<bool, CrossChainError> {
+        self.validate_held()?;
+        self.transfer.status = TransferStatus::Initiated;
+        self.timeout_slots = slot
+            .saturating_sub(self.created_slot)
+            .saturating_add(timeout_slots);
+        Ok(true)
+    }
+
+    /// A sender may withdraw a transfer until relayers have confirmed it.
+    pub fn validate_cancel(&self) -> Result<bool, CrossChainError> {
+        if !matches!(
+            self.transfer.status,
+            TransferStatus::Pending | TransferStatus::Initiated
+        ) {
+            return Err(CrossChainError::TransferNotInFlight);
+        }
+        Ok(true)
+    }
+
+    /// Replaces the transfer fee with the cancellation fee, capped at the
+    /// escrow. Nothing is owed to relayers for a transfer never relayed.
+    pub fn charge_cancellation(&mut self, fee_basis_points: u16) {
+        self.transfer.fee = calculate_cross_chain_fee(self.transfer.amount, fee_basis_points, 0)
+            .map_or(self.escrowed_amount, |fee| fee.min(self.escrowed_amount));
+        self.relayer_fee = 0;
+    }
+
+    pub fn validate_held(&self) -> Result<bool, CrossChainError> {
+        if self.transfer.status != TransferStatus::Held {
+            return Err(CrossChainError::TransferNotHeld);
+        }
+        Ok(true)
+    }
+}
+
+/// One transfer inside a batch.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct BatchEntry {
+    pub recipient: Pubkey,
+    pub nonce: u64,
+    pub amount: u64,
+    pub fee: u64,
+}
+
+impl BatchEntry {
+    pub const LEN: usize = 32 + 8 + 8 + 8;
+}
+
+/// Native transfers from one sender to one chain, initiated together and
+/// escrowed in a single account seeded by the sender and the first nonce.
+/// A batch is accepted, settled and refunded as a whole. Batches are
+/// allocated at their largest size, so smaller batches leave trailing zeroes.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TransferBatch {
+    pub sender: Pubkey,
+    pub source_chain: u64,
+    pub destination_chain: u64,
+    pub first_nonce: u64,
+    pub entries: BoundedVec<BatchEntry, MAX_BATCH_TRANSFERS>,
+    /// Sum of the entries' amounts, fees included.
+    pub escrowed_amount: u64,
+    pub fee: u64,
+    pub relayer_fee: u64,
+    pub status: TransferStatus,
+    pub timestamp: i64,
+    pub created_slot: u64,
+    pub completed_slot: u64,
+    pub timeout_slots: u64,
+    pub gateway_codehash: [u8; 32],
+    pub bump: u8,
+}
+
+impl VersionedAccount for TransferBatch {
+    const DISCRIMINATOR: [u8; 8] = *b"RQBATCH_";
+    const VERSION: u8 = 1;
+}
+
+impl TransferBatch {
+    pub const LEN: usize = 32
+        + 8
+        + 8
+        + 8
+        + 4
+        + MAX_BATCH_TRANSFERS * BatchEntry::LEN
+        + 8
+        + 8
+        + 8
+        + 1
+        + 8
+        + 8
+        + 8
+        + 8
+        + 32
+        + 1;
+
+    pub fn release_amount(&self) -> u64 {
+        self.escrowed_amount.saturating_sub(self.fee)
+    }
+
+    /// The transfer relayers confirm to settle the batch: the batch account
+    /// as recipient, carrying the total amount under the first nonce.
+    pub fn settlement_transfer(&self, batch_key: &Pubkey) -> CrossChainTransferData {
+        CrossChainTransferData {
+            sender: self.sender,
+            recipient: *batch_key,
+            amount: self.escrowed_amount,
+            destination_chain: self.destination_chain,
+            source_chain: self.source_chain,
+            token_address: None,
+            fee: self.fee,
+            nonce: self.first_nonce,
+            timestamp: self.timestamp,
+            status: TransferStatus::Initiated,
+            travel_rule: None,
+        }
+    }
+
+    /// The single `TokenTransferBatch` message relayers deliver for the
+    /// batch; its payload lists the entries.
+    pub fn bridge_message(&self, batch_key: &Pubkey) -> CrossChainMessage {
+        CrossChainMessage {
+            id: batch_key.to_string().into(),
+            source_chain: self.source_chain,
+            destination_chain: self.destination_chain,
+            sender: self.sender,
+            recipient: *batch_key,
+            message_type: MessageType::TokenTransferBatch,
+            payload: self.entries.try_to_vec().unwrap_or_default().into(),
+            nonce: self.first_nonce,
+            timestamp: self.timestamp,
+            gateway_codehash: self.gateway_codehash,
+            compression: PayloadCompression::None,
+        }
+    }
+
+    pub fn validate_in_flight(&self) -> Result<bool, CrossChainError> {
+        if self.status != TransferStatus::Initiated {
+            return Err(CrossChainError::TransferNotInFlight);
+        }
+        Ok(true)
+    }
+
+    pub fn validate_refund(&self, slot: u64) -> Result<bool, CrossChainError> {
+        self.validate_in_flight()?;
+        if slot < self.created_slot.saturating_add(self.timeout_slots) {
+            return Err(CrossChainError::RefundNotYetAvailable);
+        }
+        Ok(true)
+    }
+}
+
+/// Checks the shape of a batch before any entry is charged: at least one
+/// entry, every entry a native transfer from `sender` to the same chain
+/// without Travel Rule data, and strictly increasing nonces. Limits and
+/// compliance are checked per entry by the processor.
+pub fn validate_batch(
+    transfers: &[CrossChainTransferData],
+    sender: &Pubkey,
+) -> Result<bool, CrossChainError> {
+    let first = transfers.first().ok_or(CrossChainError::InvalidBatch)?;
+
+    for (index, transfer) in transfers.iter().enumerate() {
+        if transfer.sender != *sender
+            || transfer.destination_chain != first.destination_chain
+            || transfer.source_chain != first.source_chain
+            || transfer.token_address.is_some()
+            || transfer.travel_rule.is_some()
+        {
+            return Err(CrossChainError::InvalidBatch);
+        }
+
+        if index > 0 && transfer.nonce <= transfers[index - 1].nonce {
+            return Err(CrossChainError::InvalidBatch);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Hub-issued representation of a remote token. The local mint's authority
+/// is the program's mint authority PDA. Amounts in bridge messages are
+/// denominated in `remote_decimals` and rescaled to the local mint's.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct WrappedAsset {
+    pub chain_id: u64,
+    pub remote_token: Pubkey,
+    pub local_mint: Pubkey,
+    pub remote_decimals: u8,
+    pub local_decimals: u8,
+}
+
+impl WrappedAsset {
+    /// Converts an amount received from the origin chain into local base
+    /// units. Precision the local mint cannot hold is truncated.
+    pub fn to_local_amount(&self, remote_amount: u64) -> Result<u64, CrossChainError> {
+        rescale(remote_amount, self.remote_decimals, self.local_decimals)
+    }
+
+    /// Converts a local amount into the origin chain's base units.
+    pub fn to_remote_amount(&self, local_amount: u64) -> Result<u64, CrossChainError> {
+        rescale(local_amount, self.local_decimals, self.remote_decimals)
+    }
+}
+
+fn rescale(amount: u64, from_decimals: u8, to_decimals: u8) -> Result<u64, CrossChainError> {
+    let factor = |diff: u8| {
+        10u64
+            .checked_pow(diff as u32)
+            .ok_or(CrossChainError::ArithmeticOverflow)
+    };
+    if from_decimals > to_decimals {
+        Ok(factor(from_decimals - to_decimals).map_or(0, |f| amount / f))
+    } else {
+        amount
+            .checked_mul(factor(to_decimals - from_decimals)?)
+            .ok_or(CrossChainError::ArithmeticOverflow)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct WrappedAssetRegistry {
+    pub assets: Vec<WrappedAsset>,
+}
+
+impl WrappedAssetRegistry {
+    pub const LEN: usize = 4 + MAX_WRAPPED_ASSETS * (8 + 32 + 32 + 1 + 1);
+
+    pub fn find_by_remote(&self, chain_id: u64, remote_token: &Pubkey) -> Option<&WrappedAsset> {
+        self.assets
+            .iter()
+            .find(|a| a.chain_id == chain_id && a.remote_token == *remote_token)
+    }
+
+    pub fn find_by_mint(&self, local_mint: &Pubkey) -> Option<&WrappedAsset> {
+        self.assets.iter().find(|a| a.local_mint == *local_mint)
+    }
+
+    pub fn register(&mut self, asset: WrappedAsset) -> Result<bool, CrossChainError> {
+        if self
+            .find_by_remote(asset.chain_id, &asset.remote_token)
+            .is_some()
+            || self.find_by_mint(&asset.local_mint).is_some()
+        {
+            return Err(CrossChainError::WrappedAssetExists);
+        }
+
+        if self.assets.len() >= MAX_WRAPPED_ASSETS {
+            return Err(CrossChainError::WrappedRegistryFull);
+        }
+
+        self.assets.push(asset);
+        Ok(true)
+    }
+
+    pub fn deregister(
+        &mut self,
+        chain_id: u64,
+        remote_token: &Pubkey,
+    ) -> Result<bool, CrossChainError> {
+        let index = self
+            .assets
+            .iter()
+            .position(|a| a.chain_id == chain_id && a.remote_token == *remote_token)
+            .ok_or(CrossChainError::UnknownWrappedAsset)?;
+        self.assets.remove(index);
+        Ok(true)
+    }
+}
+
+/// Amount the hub owes to in-flight transfers for one asset; `mint` is `None`
+/// for native lamports.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct EscrowBalance {
+    pub mint: Option<Pubkey>,
+    pub outstanding: u64,
+}
+
+/// Running total of escrow owed per asset, maintained as transfers are
+/// initiated and settled so `Reconcile` can compare it to what is actually
+/// held without walking every transfer record.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct EscrowLedger {
+    pub entries: Vec<EscrowBalance>,
+}
+
+impl EscrowLedger {
+    pub fn outstanding(&self, mint: Option<Pubkey>) -> u64 {
+        self.entries
+            .iter()
+            .find(|e| e.mint == mint)
+            .map_or(0, |e| e.outstanding)
+    }
+
+    pub fn credit(&mut self, mint: Option<Pubkey>, amount: u64) -> Result<bool, CrossChainError> {
+        match self.entries.iter_mut().find(|e| e.mint == mint) {
+            Some(entry) => entry.outstanding = entry.outstanding.saturating_add(amount),
+            None => {
+                if self.entries.len() >= MAX_ESCROW_ASSETS {
+                    return Err(CrossChainError::EscrowLedgerFull);
+                }
+                self.entries.push(EscrowBalance {
+                    mint,
+                    outstanding: amount,
+                });
+            }
+        }
+        Ok(true)
+    }
+
+    /// Settles escrow; saturates so records created before the ledger existed
+    /// cannot underflow it.
+    pub fn debit(&mut self, mint: Option<Pubkey>, amount: u64) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.mint == mint) {
+            entry.outstanding = entry.outstanding.saturating_sub(amount);
+        }
+    }
+}
+
+/// Result of the latest reconciliation for a token, kept in a PDA per mint so
+/// monitors can alert on `flagged` without replaying history.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct EscrowDiscrepancy {
+    pub mint: Pubkey,
+    pub expected: u64,
+    pub actual: u64,
+    pub drift: i128,
+    pub flagged: bool,
+    pub last_reconciled_slot: u64,
+    pub bump: u8,
+}
+
+impl EscrowDiscrepancy {
+    pub const LEN: usize = 32 + 8 + 8 + 16 + 1 + 8 + 1;
+}
+
+pub fn get_discrepancy_address(mint: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[DISCREPANCY_SEED, mint.as_ref()], program_id)
+}
+
+/// Signed difference between what escrow holds and what in-flight transfers
+/// are owed; positive is surplus, negative a shortfall.
+pub fn escrow_drift(expected: u64, actual: u64) -> i128 {
+    actual as i128 - expected as i128
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
@@ -24,6 +575,8 @@ pub enum TransferStatus {
     Completed,
     Failed,
     Cancelled,
+    /// Escrowed but waiting on manual review before it is relayed.
+    Held,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
@@ -33,30 +586,469 @@ pub struct BridgeConfig {
     pub relayer_fee: u64,
     pub protocol_fee: u64,
     pub emergency_breaker: bool,
-    pub supported_tokens: Vec<TokenConfig>,
+    pub supported_tokens: BoundedVec<TokenConfig, MAX_SUPPORTED_TOKENS>,
+    pub breaker_scope: BreakerScope,
+    pub min_relayer_stake: u64,
+    pub required_relayer_signatures: u8,
+    pub claim_delivery: bool,
+    pub claim_requires_compliance: bool,
+    pub transfer_timeout_slots: u64,
+    /// Slots an in-flight transfer keeps the fee and delivery terms it was
+    /// initiated under; zero keeps them until it settles.
+    pub terms_grace_slots: u64,
+    /// Refuses confirmation-only completions; every inbound transfer must be
+    /// proven against a posted state root.
+    pub require_inclusion_proof: bool,
+    /// Core bridge used by chains whose transport is Wormhole.
+    pub wormhole: Option<WormholeConfig>,
+    /// Gateway used by chains whose transport is Axelar.
+    pub axelar: Option<AxelarConfig>,
+    /// Charged on the amount when a sender cancels a transfer, in place of
+    /// its transfer fee.
+    pub cancellation_fee_bps: u16,
+}
+
+impl Default for BridgeConfig {
+    fn default() -> Self {
+        Self {
+            min_confirmation_blocks: 12,
+            max_confirmation_blocks: 100,
+            relayer_fee: 0,
+            protocol_fee: 0,
+            emergency_breaker: false,
+            supported_tokens: BoundedVec::new(),
+            breaker_scope: BreakerScope::All,
+            min_relayer_stake: 1_000_000_000,
+            required_relayer_signatures: 1,
+            claim_delivery: false,
+            claim_requires_compliance: false,
+            transfer_timeout_slots: 216_000,
+            terms_grace_slots: 0,
+            require_inclusion_proof: false,
+            wormhole: None,
+            axelar: None,
+            cancellation_fee_bps: 10,
+        }
+    }
+}
+
+impl BridgeConfig {
+    pub fn blocks_outbound(&self) -> bool {
+        self.emergency_breaker && self.breaker_scope != BreakerScope::Inbound
+    }
+
+    pub fn blocks_inbound(&self) -> bool {
+        self.emergency_breaker && self.breaker_scope != BreakerScope::Outbound
+    }
+
+    /// Whether moving to `next` changes a fee or a trust setting: relayer
+    /// quorum or stake, inclusion proofs, or the Wormhole or Axelar
+    /// endpoints. Refused while a config timelock is set, as
+    /// `CrossChainConfig::is_sensitive_change` is.
+    pub fn is_sensitive_change(&self, next: &BridgeConfig) -> bool {
+        self.relayer_fee != next.relayer_fee
+            || self.protocol_fee != next.protocol_fee
+            || self.cancellation_fee_bps != next.cancellation_fee_bps
+            || self.min_relayer_stake != next.min_relayer_stake
+            || self.required_relayer_signatures != next.required_relayer_signatures
+            || self.require_inclusion_proof != next.require_inclusion_proof
+            || self.wormhole != next.wormhole
+            || self.axelar != next.axelar
+    }
+}
+
+/// Direction halted by a tripped emergency breaker. Unlike the global
+/// `CrossChainConfig.paused` switch, the breaker only affects bridge traffic.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum BreakerScope {
+    Outbound,
+    Inbound,
+    All,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct TokenConfig {
     pub mint: Pubkey,
-    pub symbol: String,
+    pub symbol: BoundedString<MAX_TOKEN_SYMBOL_LEN>,
     pub decimals: u8,
     pub max_transfer: u64,
     pub min_transfer: u64,
     pub enabled: bool,
 }
 
+/// Registry entry for a counterpart chain, one PDA per chain id.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ChainConfig {
+    pub chain_id: u64,
+    pub enabled: bool,
+    pub min_eidas_level: EidasLevel,
+    pub inbound_enabled: bool,
+    pub bump: u8,
+    pub caip2: Caip2ChainId,
+    /// X25519 key sealed payloads to this chain must be encrypted to; all
+    /// zeroes when the chain only accepts plaintext payloads.
+    pub encryption_key: [u8; 32],
+    /// Codehash of the counterpart gateway contract on this chain; all zeroes
+    /// when no gateway is pinned.
+    pub gateway_codehash: [u8; 32],
+    /// Amount initiated towards this chain.
+    pub outbound_volume: u64,
+    /// Amount completed from this chain.
+    pub inbound_volume: u64,
+    pub rate_limit: RateLimit,
+    /// Gateway whose Wormhole messages may complete transfers from this
+    /// chain; all zeroes when none is registered.
+    pub wormhole_emitter: WormholeEmitter,
+    pub transport: TransportKind,
+}
+
+impl ChainConfig {
+    pub const LEN: usize = 8
+        + 1
+        + 1
+        + 1
+        + 1
+        + Caip2ChainId::LEN
+        + 32
+        + 32
+        + 8
+        + 8
+        + RateLimit::LEN
+        + WormholeEmitter::LEN
+        + 1;
+
+    pub fn record_outbound(&mut self, amount: u64) -> Result<(), CrossChainError> {
+        self.outbound_volume = self
+            .outbound_volume
+            .checked_add(amount)
+            .ok_or(CrossChainError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_inbound(&mut self, amount: u64) -> Result<(), CrossChainError> {
+        self.inbound_volume = self
+            .inbound_volume
+            .checked_add(amount)
+            .ok_or(CrossChainError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    pub fn encryption_key(&self) -> Option<&[u8; 32]> {
+        if self.encryption_key == [0u8; 32] {
+            None
+        } else {
+            Some(&self.encryption_key)
+        }
+    }
+
+    /// Whether `next` changes how this chain's transfers are trusted: its
+    /// transport, Wormhole emitter or pinned gateway, or re-enabling it in
+    /// either direction. Disabling stays immediate so a compromised chain
+    /// can be cut off while a timelock is set.
+    pub fn is_sensitive_change(&self, next: &ChainConfigData) -> bool {
+        self.transport != next.transport
+            || self.wormhole_emitter != next.wormhole_emitter
+            || self.gateway_codehash != next.gateway_codehash
+            || (!self.enabled && next.enabled)
+            || (!self.inbound_enabled && next.inbound_enabled)
+    }
+
+    pub fn wormhole_emitter(&self) -> Option<&WormholeEmitter> {
+        if self.wormhole_emitter == WormholeEmitter::default() {
+            None
+        } else {
+            Some(&self.wormhole_emitter)
+        }
+    }
+}
+
+/// Cap on the amount initiated towards one chain within a window of slots,
+/// limiting what a compromised key or contract can drain before someone
+/// reacts. A zero `max_outflow` disables the cap.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default, PartialEq)]
+pub struct RateLimit {
+    pub max_outflow: u64,
+    pub window_slots: u64,
+    pub window_start: u64,
+    /// Amount initiated since `window_start`.
+    pub window_outflow: u64,
+}
+
+impl RateLimit {
+    pub const LEN: usize = 8 + 8 + 8 + 8;
+
+    /// Counts `amount` against the current window, starting a new window
+    /// once the previous one has elapsed.
+    pub fn consume(&mut self, amount: u64, slot: u64) -> Result<bool, CrossChainError> {
+        if self.max_outflow == 0 {
+            return Ok(true);
+        }
+
+        let mut outflow = self.window_outflow;
+        let mut start = self.window_start;
+        if slot >= self.window_start.saturating_add(self.window_slots) {
+            outflow = 0;
+            start = slot;
+        }
+
+        let outflow = outflow
+            .checked_add(amount)
+            .ok_or(CrossChainError::ArithmeticOverflow)?;
+        if outflow > self.max_outflow {
+            return Err(CrossChainError::RateLimitExceeded);
+        }
+
+        self.window_start = start;
+        self.window_outflow = outflow;
+        Ok(true)
+    }
+
+    /// Whether `limit` is at least as strict as this one: a cap no higher
+    /// (and never disabled) over a window no shorter.
+    pub fn is_tightened_by(&self, limit: &RateLimit) -> bool {
+        limit.max_outflow != 0
+            && (self.max_outflow == 0 || limit.max_outflow <= self.max_outflow)
+            && limit.window_slots >= self.window_slots
+    }
+}
+
+/// Overrides for one (source, destination) corridor, applied on top of both
+/// chains' own configs. Corridors without a policy account use the default,
+/// which changes nothing.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ChainPairPolicy {
+    pub source_chain: u64,
+    pub destination_chain: u64,
+    pub enabled: bool,
+    /// Scales the protocol fee, in basis points of the hub's rate.
+    pub fee_multiplier_bps: u16,
+    /// Largest single transfer on the corridor; zero leaves only the hub's
+    /// limit in force.
+    pub max_amount: u64,
+    /// Relayer confirmations required on top of the bridge config's; zero
+    /// defers to it. Other transports rely on their own finality.
+    pub required_confirmations: u8,
+    pub bump: u8,
+}
+
+impl VersionedAccount for ChainPairPolicy {
+    const DISCRIMINATOR: [u8; 8] = *b"RQCHPAIR";
+    const VERSION: u8 = 1;
+}
+
+impl ChainPairPolicy {
+    pub const LEN: usize = 8 + 8 + 1 + 2 + 8 + 1 + 1;
+
+    pub fn new(source_chain: u64, destination_chain: u64) -> Self {
+        Self {
+            source_chain,
+            destination_chain,
+            enabled: true,
+            fee_multiplier_bps: 10_000,
+            max_amount: 0,
+            required_confirmations: 0,
+            bump: 0,
+        }
+    }
+
+    pub fn check_amount(&self, amount: u64) -> Result<bool, CrossChainError> {
+        if !self.enabled {
+            return Err(CrossChainError::CorridorDisabled);
+        }
+
+        if self.max_amount != 0 && amount > self.max_amount {
+            return Err(CrossChainError::AmountTooHigh);
+        }
+
+        Ok(true)
+    }
+
+    /// The protocol fee rate charged on this corridor, capped at
+    /// `MAX_FEE_BASIS_POINTS` so a later rise in the hub's rate cannot carry
+    /// a corridor past it.
+    pub fn fee_basis_points(&self, base_bps: u16) -> Result<u16, CrossChainError> {
+        u16::try_from(base_bps as u32 * self.fee_multiplier_bps as u32 / 10_000)
+            .map(|bps| bps.min(MAX_FEE_BASIS_POINTS))
+            .map_err(|_| CrossChainError::ArithmeticOverflow)
+    }
+
+    /// Refuses a multiplier that would scale the hub's current rate past
+    /// `MAX_FEE_BASIS_POINTS`.
+    pub fn validate(&self, base_bps: u16) -> Result<bool, CrossChainError> {
+        if base_bps as u32 * self.fee_multiplier_bps as u32 / 10_000 > MAX_FEE_BASIS_POINTS as u32 {
+            return Err(CrossChainError::InvalidConfig);
+        }
+
+        Ok(true)
+    }
+
+    pub fn required_confirmations(&self, base: u8) -> u8 {
+        base.max(self.required_confirmations)
+    }
+
+    /// Whether replacing this policy with `next` reprices the corridor or
+    /// lowers its confirmation requirement; refused while a config timelock
+    /// is set.
+    pub fn is_sensitive_change(&self, next: &ChainPairPolicy) -> bool {
+        self.fee_multiplier_bps != next.fee_multiplier_bps
+            || next.required_confirmations < self.required_confirmations
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ChainPairPolicyData {
+    pub enabled: bool,
+    pub fee_multiplier_bps: u16,
+    pub max_amount: u64,
+    pub required_confirmations: u8,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ChainConfigData {
+    pub enabled: bool,
+    pub min_eidas_level: EidasLevel,
+    pub inbound_enabled: bool,
+    pub encryption_key: [u8; 32],
+    pub gateway_codehash: [u8; 32],
+    pub wormhole_emitter: WormholeEmitter,
+    pub transport: TransportKind,
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct CrossChainMessage {
-    pub id: String,
+    pub id: BoundedString<MAX_MESSAGE_ID_LEN>,
     pub source_chain: u64,
     pub destination_chain: u64,
     pub sender: Pubkey,
     pub recipient: Pubkey,
     pub message_type: MessageType,
-    pub payload: Vec<u8>,
+    pub payload: BoundedBytes<MAX_MESSAGE_PAYLOAD_LEN>,
     pub nonce: u64,
     pub timestamp: i64,
+    /// Codehash of the gateway the message is addressed to, taken from the
+    /// registry; zero when the destination chain has no pinned gateway.
+    pub gateway_codehash: [u8; 32],
+    pub compression: PayloadCompression,
+}
+
+impl CrossChainMessage {
+    /// Run-length encodes the payload when that makes it smaller. Returns
+    /// whether the payload was compressed.
+    pub fn compress(&mut self) -> bool {
+        if self.compression != PayloadCompression::None {
+            return false;
+        }
+
+        let packed = rle_compress(&self.payload);
+        if packed.len() >= self.payload.len() {
+            return false;
+        }
+
+        self.payload = packed.into();
+        self.compression = PayloadCompression::Rle;
+        true
+    }
+
+    /// The payload as the message type defines it, decompressed if needed.
+    /// Decompressed payloads are held to the same limit as plain ones.
+    pub fn plain_payload(&self) -> Result<Cow<'_, [u8]>, MessageError> {
+        match self.compression {
+            PayloadCompression::None => Ok(Cow::Borrowed(&self.payload)),
+            PayloadCompression::Rle => {
+                let malformed = || MessageError::Malformed("Invalid RLE payload".to_string());
+                let len = rle_decoded_len(&self.payload).ok_or_else(malformed)?;
+                let max = self.message_type.max_payload_len();
+                if len > max {
+                    return Err(MessageError::PayloadTooLarge {
+                        message_type: self.message_type.clone(),
+                        len,
+                        max,
+                    });
+                }
+                Ok(Cow::Owned(
+                    rle_decompress(&self.payload).ok_or_else(malformed)?,
+                ))
+            }
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PayloadCompression {
+    #[default]
+    None,
+    /// PackBits run-length encoding, cheap enough to decode on-chain. A
+    /// header byte `n` below 128 is followed by `n + 1` literal bytes; above
+    /// 128 the next byte repeats `257 - n` times. 128 is never written.
+    Rle,
+}
+
+pub fn rle_compress(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        let mut run = 1;
+        while i + run < data.len() && run < 128 && data[i + run] == data[i] {
+            run += 1;
+        }
+        if run > 1 {
+            out.push((257 - run) as u8);
+            out.push(data[i]);
+            i += run;
+            continue;
+        }
+
+        let start = i;
+        i += 1;
+        while i < data.len() && i - start < 128 && !(i + 1 < data.len() && data[i] == data[i + 1]) {
+            i += 1;
+        }
+        out.push((i - start - 1) as u8);
+        out.extend_from_slice(&data[start..i]);
+    }
+    out
+}
+
+/// Length `data` expands to, or `None` if it is not valid PackBits. Lets the
+/// program bound a payload before allocating for it.
+pub fn rle_decoded_len(data: &[u8]) -> Option<usize> {
+    let mut len = 0usize;
+    let mut i = 0;
+    while i < data.len() {
+        let header = data[i] as usize;
+        i += 1;
+        match header {
+            0..=127 => {
+                i += header + 1;
+                len += header + 1;
+            }
+            128 => return None,
+            _ => {
+                i += 1;
+                len += 257 - header;
+            }
+        }
+        if i > data.len() {
+            return None;
+        }
+    }
+    Some(len)
+}
+
+pub fn rle_decompress(data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(rle_decoded_len(data)?);
+    let mut i = 0;
+    while i < data.len() {
+        let header = data[i] as usize;
+        if header < 128 {
+            out.extend_from_slice(&data[i + 1..i + header + 2]);
+            i += header + 2;
+        } else {
+            out.resize(out.len() + 257 - header, data[i + 1]);
+            i += 2;
+        }
+    }
+    Some(out)
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
@@ -65,35 +1057,485 @@ pub enum MessageType {
     TokenReceive,
     ContractCall,
     ContractCallWithToken,
+    TokenRedeem,
+    Sealed,
+    ConfidentialTransfer,
+    ComplianceAttestation,
+    TokenTransferBatch,
+}
+
+impl MessageType {
+    pub fn max_payload_len(&self) -> usize {
+        match self {
+            MessageType::TokenTransfer
+            | MessageType::TokenReceive
+            | MessageType::TokenRedeem
+            | MessageType::ConfidentialTransfer
+            | MessageType::ComplianceAttestation => MAX_TRANSFER_PAYLOAD_LEN,
+            MessageType::ContractCall | MessageType::ContractCallWithToken => {
+                MAX_CONTRACT_CALL_PAYLOAD_LEN
+            }
+            MessageType::Sealed => MAX_CONTRACT_CALL_PAYLOAD_LEN + SealedPayload::OVERHEAD,
+            MessageType::TokenTransferBatch => MAX_BATCH_PAYLOAD_LEN,
+        }
+    }
+}
+
+/// Payload carried by `Sealed` messages: the payload of an `inner_type`
+/// message encrypted to the destination chain's registered key, so it never
+/// appears in plaintext in transaction history. Senders derive the key with
+/// X25519 between `ephemeral_key` and `recipient_key` and encrypt with
+/// ChaCha20-Poly1305 under `nonce`; the program only checks routing and size.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SealedPayload {
+    pub version: u8,
+    pub inner_type: MessageType,
+    pub recipient_key: [u8; 32],
+    pub ephemeral_key: [u8; 32],
+    pub nonce: [u8; 12],
+    pub ciphertext: BoundedBytes<{ MAX_CONTRACT_CALL_PAYLOAD_LEN + SEALED_PAYLOAD_TAG_LEN }>,
+}
+
+impl SealedPayload {
+    pub const OVERHEAD: usize = 1 + 1 + 32 + 32 + 12 + 4 + SEALED_PAYLOAD_TAG_LEN;
+}
+
+/// Payload carried by `ContractCall` messages.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ContractCallEnvelope {
+    pub target: BoundedBytes<MAX_CALL_TARGET_LEN>,
+    pub calldata: BoundedBytes<MAX_CONTRACT_CALL_PAYLOAD_LEN>,
+    pub gas_limit: u64,
+}
+
+/// Payload carried by `ContractCallWithToken` messages.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ContractCallWithTokenEnvelope {
+    pub call: ContractCallEnvelope,
+    pub token: Pubkey,
+    pub amount: u64,
+}
+
+/// Payload carried by `TokenRedeem` messages: burned wrapped tokens the origin
+/// chain should release in its native asset.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct RedemptionPayload {
+    pub remote_token: Pubkey,
+    pub local_mint: Pubkey,
+    pub amount: u64,
+}
+
+/// Payload carried by `ConfidentialTransfer` messages. The amount is only
+/// present as a twisted ElGamal ciphertext under the recipient's key; the
+/// source chain escrowed and charged fees on `amount_bound`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ConfidentialTransferPayload {
+    pub ciphertext: [u8; ELGAMAL_CIPHERTEXT_LEN],
+    pub encryption_public_key: [u8; X25519_KEY_LEN],
+    pub amount_bound: u64,
+}
+
+impl ConfidentialTransferPayload {
+    pub fn from_proof(
+        proof: &ConfidentialTransferProof,
+        amount_bound: u64,
+    ) -> Result<Self, CrossChainError> {
+        Ok(Self {
+            ciphertext: proof
+                .ciphertext
+                .as_slice()
+                .try_into()
+                .map_err(|_| CrossChainError::InvalidRangeProof)?,
+            encryption_public_key: proof
+                .public_encryption_key
+                .as_slice()
+                .try_into()
+                .map_err(|_| CrossChainError::InvalidEncryptionKey)?,
+            amount_bound,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageError {
+    Malformed(String),
+    PayloadTooLarge {
+        message_type: MessageType,
+        len: usize,
+        max: usize,
+    },
+    InvalidEnvelope(String),
+}
+
+impl std::fmt::Display for MessageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MessageError::Malformed(e) => write!(f, "Failed to decode message: {}", e),
+            MessageError::PayloadTooLarge {
+                message_type,
+                len,
+                max,
+            } => write!(
+                f,
+                "{:?} payload of {} bytes exceeds limit of {}",
+                message_type, len, max
+            ),
+            MessageError::InvalidEnvelope(e) => write!(f, "Invalid call envelope: {}", e),
+        }
+    }
+}
+
+/// Hub payload for transports that carry raw bytes: the bridge message
+/// together with the amount and asset it moves, which the message itself
+/// does not carry.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TransferPayload {
+    pub message: CrossChainMessage,
+    pub amount: u64,
+    pub token_address: Option<Pubkey>,
+}
+
+/// First byte of every encoded `TransferPayload`, bumped on incompatible
+/// format changes.
+pub const TRANSFER_PAYLOAD_VERSION: u8 = 1;
+
+impl TransferPayload {
+    pub fn for_record(record: &TransferRecord) -> Self {
+        Self {
+            message: record.bridge_message(format!(
+                "{}-{}",
+                record.transfer.sender, record.transfer.nonce
+            )),
+            amount: record.transfer.amount,
+            token_address: record.transfer.token_address,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = vec![TRANSFER_PAYLOAD_VERSION];
+        data.extend(self.try_to_vec().unwrap_or_default());
+        data
+    }
+
+    /// Decodes a payload canonically, so one transfer has exactly one
+    /// payload encoding.
+    pub fn decode(data: &[u8]) -> Result<Self, MessageError> {
+        let body = match data.split_first() {
+            Some((&TRANSFER_PAYLOAD_VERSION, body)) => body,
+            _ => {
+                return Err(MessageError::Malformed(
+                    "Unsupported transfer payload version".to_string(),
+                ))
+            }
+        };
+
+        let payload =
+            Self::try_from_slice(body).map_err(|e| MessageError::Malformed(e.to_string()))?;
+        check_canonical(&payload, body).map_err(|e| MessageError::Malformed(e.to_string()))?;
+        validate_bridge_message(&payload.message)?;
+        Ok(payload)
+    }
+
+    /// Whether the payload describes `transfer`.
+    pub fn matches(&self, transfer: &CrossChainTransferData) -> bool {
+        self.message.message_type == MessageType::TokenTransfer
+            && self.message.source_chain == transfer.source_chain
+            && self.message.destination_chain == transfer.destination_chain
+            && self.message.sender == transfer.sender
+            && self.message.recipient == transfer.recipient
+            && self.message.nonce == transfer.nonce
+            && self.amount == transfer.amount
+            && self.token_address == transfer.token_address
+    }
+}
+
+/// Transport a counterpart chain's messages travel over, chosen per chain in
+/// the registry.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransportKind {
+    /// Bonded relayers deliver transfers and confirm them with signatures.
+    #[default]
+    RelayerQuorum,
+    Wormhole,
+    Axelar,
+}
+
+/// What a completion rests on. Each transport accepts its own kinds.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub enum TransportEvidence {
+    Confirmations(BoundedVec<RelayConfirmation, MAX_RELAY_CONFIRMATIONS>),
+    /// Proof against a state root the relayers posted earlier.
+    InclusionProof(InclusionProof),
+    /// A VAA the Wormhole core bridge has posted.
+    Vaa,
+    /// A message the Axelar gateway has approved.
+    GatewayApproval(AxelarApproval),
+    /// Relayer quorum signatures checked by one ed25519 instruction over a
+    /// single shared message.
+    AggregateAttestation(AggregateAttestation),
+}
+
+pub struct TransportContext<'s> {
+    pub program_id: &'s Pubkey,
+    pub state: &'s CrossChainState,
+}
+
+/// A transfer escrowed on the hub, ready to be sent to its destination.
+pub struct OutboundTransfer<'r> {
+    pub record_key: &'r Pubkey,
+    pub record: &'r TransferRecord,
+}
+
+/// A transfer claimed to have been delivered or sent on `source_chain`, the
+/// chain confirming it (see `CrossChainTransferData::confirming_chain`).
+pub struct InboundTransfer<'r> {
+    pub transfer: &'r CrossChainTransferData,
+    pub source_chain: &'r ChainConfig,
+    pub policy: &'r ChainPairPolicy,
+    pub evidence: &'r TransportEvidence,
+}
+
+/// Carries transfers between the hub and counterpart chains. The processor
+/// only calls through this trait, so adding a transport means adding an
+/// adapter and a `TransportKind` variant. Each method consumes the
+/// transport's own accounts from `accounts`.
+pub trait MessageTransport {
+    /// Sends an outbound transfer to its destination chain.
+    fn post_message<'a, 'b>(
+        &self,
+        ctx: &TransportContext,
+        outbound: &OutboundTransfer,
+        payer: &'b AccountInfo<'a>,
+        system_program: &'b AccountInfo<'a>,
+        accounts: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    ) -> ProgramResult;
+
+    /// Checks the evidence that an inbound transfer happened on its source
+    /// chain.
+    fn verify_message<'a, 'b>(
+        &self,
+        ctx: &TransportContext,
+        inbound: &InboundTransfer,
+        instructions_sysvar: &'b AccountInfo<'a>,
+        accounts: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    ) -> ProgramResult;
+}
+
+pub fn transport_for(kind: TransportKind) -> &'static dyn MessageTransport {
+    match kind {
+        TransportKind::RelayerQuorum => &RelayerQuorumTransport,
+        TransportKind::Wormhole => &WormholeTransport,
+        TransportKind::Axelar => &AxelarTransport,
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct RelayConfirmation {
     pub relayer: Pubkey,
-    pub tx_hash: Vec<u8>,
+    pub tx_hash: BoundedBytes<MAX_RELAY_TX_HASH_LEN>,
     pub block_number: u64,
     pub timestamp: i64,
-    pub signatures: Vec<Vec<u8>>,
-    pub signers: Vec<Pubkey>,
+    pub signatures: BoundedVec<BoundedBytes<MAX_RELAY_SIGNATURE_LEN>, MAX_RELAY_SIGNATURES>,
+    pub signers: BoundedVec<Pubkey, MAX_RELAY_SIGNATURES>,
+}
+
+/// A relayer quorum's attestation of one source transaction. The signatures
+/// and signer keys live only in the ed25519 instruction at
+/// `ed25519_instruction_index`, whose entries all point at one copy of the
+/// `relay_message_hash` digest, so neither is repeated in the completion.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AggregateAttestation {
+    pub tx_hash: BoundedBytes<MAX_RELAY_TX_HASH_LEN>,
+    pub block_number: u64,
+    pub timestamp: i64,
+    pub ed25519_instruction_index: u8,
+}
+
+pub fn calculate_cross_chain_fee(
+    amount: u64,
+    protocol_fee_bps: u16,
+    relayer_fee: u64,
+) -> Result<u64, CrossChainError> {
+    let protocol_fee = u64::try_from(amount as u128 * protocol_fee_bps as u128 / 10000)
+        .map_err(|_| CrossChainError::ArithmeticOverflow)?;
+    protocol_fee
+        .checked_add(relayer_fee)
+        .ok_or(CrossChainError::ArithmeticOverflow)
+}
+
+/// One foreign recipient of a `DistributeCrossChain` payout, weighted by
+/// `share` relative to the other recipients.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct DistributionShare {
+    pub recipient: Pubkey,
+    pub share: u32,
+}
+
+pub fn validate_distribution(shares: &[DistributionShare]) -> Result<bool, CrossChainError> {
+    if shares.is_empty() || shares.len() > MAX_DISTRIBUTION_RECIPIENTS {
+        return Err(CrossChainError::InvalidDistribution);
+    }
+
+    if shares.iter().any(|s| s.share == 0) {
+        return Err(CrossChainError::InvalidDistribution);
+    }
+
+    Ok(true)
+}
+
+/// Commitment to the recipient list, carried as the transfer recipient so a
+/// single bridge message binds every payout.
+pub fn distribution_commitment(shares: &[DistributionShare]) -> Pubkey {
+    let payload = shares.to_vec().try_to_vec().unwrap_or_default();
+    Pubkey::new_from_array(hashv(&[DISTRIBUTION_DOMAIN, &payload]).to_bytes())
 }
 
-pub fn calculate_cross_chain_fee(amount: u64, protocol_fee_bps: u16, relayer_fee: u64) -> u64 {
-    let protocol_fee = (amount as u128 * protocol_fee_bps as u128 / 10000) as u64;
-    protocol_fee + relayer_fee
+/// Pro-rata payouts of `amount` by share. Rounding dust goes to the first
+/// recipient so the payouts always sum to `amount`.
+pub fn split_distribution(
+    amount: u64,
+    shares: &[DistributionShare],
+) -> Result<Vec<u64>, CrossChainError> {
+    let total: u128 = shares.iter().map(|s| s.share as u128).sum();
+    if total == 0 {
+        return Ok(vec![0; shares.len()]);
+    }
+
+    let mut payouts = shares
+        .iter()
+        .map(|s| {
+            u64::try_from(amount as u128 * s.share as u128 / total)
+                .map_err(|_| CrossChainError::ArithmeticOverflow)
+        })
+        .collect::<Result<Vec<u64>, _>>()?;
+    let paid = payouts.iter().try_fold(0u64, |sum, p| sum.checked_add(*p));
+    let dust = paid
+        .and_then(|paid| amount.checked_sub(paid))
+        .ok_or(CrossChainError::ArithmeticOverflow)?;
+    payouts[0] = payouts[0]
+        .checked_add(dust)
+        .ok_or(CrossChainError::ArithmeticOverflow)?;
+    Ok(payouts)
 }
 
 pub fn validate_cross_chain_transfer(
     amount: u64,
     _destination_chain: u64,
     config: &BridgeConfig,
-    eidas_level: &EidasLevel,
-) -> Result<bool, String> {
+    profile: &ComplianceProfile,
+    matrix: &ComplianceMatrix,
+) -> Result<bool, CrossChainError> {
+    if config.blocks_outbound() {
+        return Err(CrossChainError::BreakerTripped);
+    }
+
     if !config.supported_tokens.iter().any(|t| t.enabled) {
-        return Err("No enabled tokens available for transfer".to_string());
+        return Err(CrossChainError::NoEnabledTokens);
     }
 
-    if *eidas_level == EidasLevel::None && amount > 1_000_000_000 {
-        return Err("Large transfers require eIDAS compliance".to_string());
+    matrix.check(amount, profile)
+}
+
+pub fn get_chain_config_address(chain_id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CHAIN_CONFIG_SEED, &chain_id.to_le_bytes()], program_id)
+}
+
+pub fn get_chain_pair_policy_address(
+    source_chain: u64,
+    destination_chain: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CHAIN_PAIR_POLICY_SEED,
+            &source_chain.to_le_bytes(),
+            &destination_chain.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn get_transfer_batch_address(
+    sender: &Pubkey,
+    first_nonce: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            TRANSFER_BATCH_SEED,
+            sender.as_ref(),
+            &first_nonce.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn get_transfer_record_address(
+    sender: &Pubkey,
+    nonce: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TRANSFER_RECORD_SEED, sender.as_ref(), &nonce.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Receipt for a transfer that originated on a remote chain, preventing the
+/// same remote nonce from being minted twice.
+pub fn get_inbound_receipt_address(
+    source_chain: u64,
+    sender: &Pubkey,
+    nonce: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            INBOUND_RECEIPT_SEED,
+            &source_chain.to_le_bytes(),
+            sender.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn get_wrapped_registry_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WRAPPED_REGISTRY_SEED], program_id)
+}
+
+pub fn get_mint_authority_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MINT_AUTHORITY_SEED], program_id)
+}
+
+pub fn get_escrow_authority_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ESCROW_AUTHORITY_SEED], program_id)
+}
+
+pub fn validate_destination_chain(
+    chain: &ChainConfig,
+    eidas_level: &EidasLevel,
+) -> Result<bool, CrossChainError> {
+    if !chain.enabled {
+        return Err(CrossChainError::ChainDisabled);
+    }
+
+    if *eidas_level < chain.min_eidas_level {
+        return Err(CrossChainError::ComplianceRequired);
+    }
+
+    Ok(true)
+}
+
+/// Inbound completions are only accepted from registered chains that are both
+/// enabled and explicitly open for inbound traffic.
+pub fn validate_source_chain(chain: &ChainConfig) -> Result<bool, CrossChainError> {
+    if !chain.enabled {
+        return Err(CrossChainError::ChainDisabled);
+    }
+
+    if !chain.inbound_enabled {
+        return Err(CrossChainError::InboundDisabled);
     }
 
     Ok(true)
@@ -103,16 +1545,164 @@ pub fn encode_bridge_message(message: &CrossChainMessage) -> Vec<u8> {
     message.try_to_vec().unwrap_or_default()
 }
 
-pub fn decode_bridge_message(data: &[u8]) -> Result<CrossChainMessage, String> {
-    CrossChainMessage::try_from_slice(data).map_err(|e| format!("Failed to decode message: {}", e))
+pub fn decode_bridge_message(data: &[u8]) -> Result<CrossChainMessage, MessageError> {
+    let message = CrossChainMessage::try_from_slice(data)
+        .map_err(|e| MessageError::Malformed(e.to_string()))?;
+    check_canonical(&message, data).map_err(|e| MessageError::Malformed(e.to_string()))?;
+    validate_bridge_message(&message)?;
+    Ok(message)
 }
 
-pub fn verify_relay_confirmation(
-    confirmations: &[RelayConfirmation],
-    required_signatures: u8,
-) -> Result<bool, String> {
-    if confirmations.len() < required_signatures as usize {
-        return Err("Insufficient relay confirmations".to_string());
+/// Payloads are decoded canonically so a relayed message has exactly one
+/// byte representation.
+fn decode_envelope<T: BorshSerialize + BorshDeserialize>(
+    payload: &[u8],
+) -> Result<T, MessageError> {
+    let envelope =
+        T::try_from_slice(payload).map_err(|e| MessageError::InvalidEnvelope(e.to_string()))?;
+    check_canonical(&envelope, payload)
+        .map_err(|e| MessageError::InvalidEnvelope(e.to_string()))?;
+    Ok(envelope)
+}
+
+pub fn validate_bridge_message(message: &CrossChainMessage) -> Result<(), MessageError> {
+    let max = message.message_type.max_payload_len();
+    if message.payload.len() > max {
+        return Err(MessageError::PayloadTooLarge {
+            message_type: message.message_type.clone(),
+            len: message.payload.len(),
+            max,
+        });
+    }
+
+    let payload = message.plain_payload()?;
+    match message.message_type {
+        MessageType::TokenTransfer | MessageType::TokenReceive => Ok(()),
+        MessageType::ContractCall => {
+            let envelope: ContractCallEnvelope = decode_envelope(&payload)?;
+            validate_call_envelope(&envelope)
+        }
+        MessageType::ContractCallWithToken => {
+            let envelope: ContractCallWithTokenEnvelope = decode_envelope(&payload)?;
+            if envelope.amount == 0 {
+                return Err(MessageError::InvalidEnvelope(
+                    "Token amount must be positive".to_string(),
+                ));
+            }
+            validate_call_envelope(&envelope.call)
+        }
+        MessageType::TokenRedeem => {
+            let redemption: RedemptionPayload = decode_envelope(&payload)?;
+            if redemption.amount == 0 {
+                return Err(MessageError::InvalidEnvelope(
+                    "Redeemed amount must be positive".to_string(),
+                ));
+            }
+            Ok(())
+        }
+        MessageType::Sealed => {
+            let sealed: SealedPayload = decode_envelope(&payload)?;
+            validate_sealed_payload(&sealed)
+        }
+        MessageType::ConfidentialTransfer => {
+            let confidential: ConfidentialTransferPayload = decode_envelope(&payload)?;
+            if confidential.amount_bound == 0 {
+                return Err(MessageError::InvalidEnvelope(
+                    "Amount bound must be positive".to_string(),
+                ));
+            }
+            Ok(())
+        }
+        MessageType::TokenTransferBatch => {
+            let entries: BoundedVec<BatchEntry, MAX_BATCH_TRANSFERS> = decode_envelope(&payload)?;
+            if entries.is_empty() || entries.iter().any(|entry| entry.amount == 0) {
+                return Err(MessageError::InvalidEnvelope(
+                    "Batch entries must be present and positive".to_string(),
+                ));
+            }
+            Ok(())
+        }
+        MessageType::ComplianceAttestation => {
+            let signed: SignedComplianceAttestation = decode_envelope(&payload)?;
+            if signed.attestation.expires_at <= signed.attestation.issued_at {
+                return Err(MessageError::InvalidEnvelope(
+                    "Attestation expires before it is issued".to_string(),
+                ));
+            }
+            Ok(())
+        }
     }
+}
+
+fn validate_sealed_payload(sealed: &SealedPayload) -> Result<(), MessageError> {
+    if sealed.version != SEALED_PAYLOAD_VERSION {
+        return Err(MessageError::InvalidEnvelope(
+            "Unsupported sealed payload version".to_string(),
+        ));
+    }
+
+    if sealed.inner_type == MessageType::Sealed {
+        return Err(MessageError::InvalidEnvelope(
+            "Sealed payloads cannot be nested".to_string(),
+        ));
+    }
+
+    let max = sealed.inner_type.max_payload_len() + SEALED_PAYLOAD_TAG_LEN;
+    if sealed.ciphertext.len() <= SEALED_PAYLOAD_TAG_LEN || sealed.ciphertext.len() > max {
+        return Err(MessageError::InvalidEnvelope(
+            "Sealed ciphertext length out of range".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Destination-side check that a message was addressed to the gateway with
+/// `local_codehash`. Messages carrying no pinned codehash are rejected as well,
+/// so an unpinned source registry cannot route to any gateway.
+pub fn verify_gateway_codehash(
+    message: &CrossChainMessage,
+    local_codehash: &[u8; 32],
+) -> Result<bool, CrossChainError> {
+    if message.gateway_codehash == [0u8; 32] || message.gateway_codehash != *local_codehash {
+        return Err(CrossChainError::GatewayMismatch);
+    }
+
     Ok(true)
 }
+
+/// Checks a sealed message is encrypted to the key registered for its
+/// destination chain. Plaintext messages pass unchanged.
+pub fn validate_sealed_recipient(
+    message: &CrossChainMessage,
+    chain: &ChainConfig,
+) -> Result<bool, CrossChainError> {
+    if message.message_type != MessageType::Sealed {
+        return Ok(true);
+    }
+
+    let sealed = SealedPayload::try_from_slice(&message.payload)
+        .map_err(|_| CrossChainError::InvalidBridgeMessage)?;
+
+    match chain.encryption_key() {
+        Some(key) if *key == sealed.recipient_key => Ok(true),
+        _ => Err(CrossChainError::EncryptionKeyMismatch),
+    }
+}
+
+fn validate_call_envelope(envelope: &ContractCallEnvelope) -> Result<(), MessageError> {
+    if envelope.target.is_empty() || envelope.target.len() > MAX_CALL_TARGET_LEN {
+        return Err(MessageError::InvalidEnvelope(format!(
+            "Call target must be 1..={} bytes",
+            MAX_CALL_TARGET_LEN
+        )));
+    }
+
+    if envelope.gas_limit == 0 {
+        return Err(MessageError::InvalidEnvelope(
+            "Gas limit must be positive".to_string(),
+        ));
+    }
+
+    Ok(())
+}
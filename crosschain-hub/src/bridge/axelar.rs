@@ -0,0 +1,354 @@
+//! Axelar transport. Outbound transfers to a chain using it are sent through
+//! the Axelar gateway's `CallContract`, signed by the hub's calling PDA.
+//! Inbound completions rest on an `IncomingMessage` the gateway approved
+//! after verifying the Axelar validator set's signatures, addressed to this
+//! program and carrying the transfer's payload.
+//!
+//! Each chain's Axelar name and gateway address live in an `AxelarRoute`
+//! account set by the admin. Only the gateway's account and message formats
+//! are mirrored here, so the hub does not depend on the Axelar SDK.
+
+use super::{
+    CrossChainTransferData, InboundTransfer, MessageTransport, OutboundTransfer, TransferPayload,
+    TransportContext, TransportEvidence,
+};
+use crate::account::VersionedAccount;
+use crate::bounded::{BoundedBytes, BoundedString};
+use crate::error::CrossChainError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    keccak,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+pub const AXELAR_ROUTE_SEED: &[u8] = b"axelar_route";
+/// Seed of the PDA the gateway expects to sign for the calling program.
+pub const AXELAR_CALL_CONTRACT_SEED: &[u8] = b"gtw-call-contract";
+
+/// Seeds of the gateway's own accounts.
+pub const GATEWAY_ROOT_SEED: &[u8] = b"gateway";
+pub const INCOMING_MESSAGE_SEED: &[u8] = b"incoming message";
+
+pub const MAX_AXELAR_CHAIN_NAME_LEN: usize = 32;
+pub const MAX_AXELAR_ADDRESS_LEN: usize = 128;
+pub const MAX_AXELAR_MESSAGE_ID_LEN: usize = 128;
+pub const MAX_AXELAR_PAYLOAD_LEN: usize = 1024;
+
+const CALL_CONTRACT_INSTRUCTION: u8 = 2;
+const MESSAGE_APPROVED: u8 = 0;
+
+pub type AxelarChainName = BoundedString<MAX_AXELAR_CHAIN_NAME_LEN>;
+pub type AxelarAddress = BoundedString<MAX_AXELAR_ADDRESS_LEN>;
+
+/// Gateway deployment the hub uses, set through `UpdateBridgeConfig`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct AxelarConfig {
+    /// Gateway program of the cluster the hub is deployed on.
+    pub gateway: Pubkey,
+    /// Name Axelar knows the hub's own chain by.
+    pub chain_name: AxelarChainName,
+}
+
+/// Axelar name and gateway contract of a counterpart chain. Routes are
+/// allocated at their largest size, so shorter names leave trailing zeroes.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct AxelarRoute {
+    pub chain_id: u64,
+    pub chain_name: AxelarChainName,
+    pub gateway_address: AxelarAddress,
+    pub bump: u8,
+}
+
+impl VersionedAccount for AxelarRoute {
+    const DISCRIMINATOR: [u8; 8] = *b"RQAXLRTE";
+    const VERSION: u8 = 1;
+}
+
+impl AxelarRoute {
+    pub const LEN: usize = 8 + 4 + MAX_AXELAR_CHAIN_NAME_LEN + 4 + MAX_AXELAR_ADDRESS_LEN + 1;
+}
+
+/// Completion evidence for an Axelar transfer: the id Axelar assigned the
+/// message on its source chain, and the payload the gateway approved.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct AxelarApproval {
+    pub message_id: BoundedString<MAX_AXELAR_MESSAGE_ID_LEN>,
+    pub payload: BoundedBytes<MAX_AXELAR_PAYLOAD_LEN>,
+}
+
+/// The gateway's record of an approved message.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct IncomingMessage {
+    pub bump: u8,
+    pub signing_pda_bump: u8,
+    pub pad: [u8; 3],
+    pub status: u8,
+    pub message_hash: [u8; 32],
+    pub payload_hash: [u8; 32],
+}
+
+impl IncomingMessage {
+    pub fn unpack(data: &[u8]) -> Result<Self, CrossChainError> {
+        Self::deserialize(&mut &data[..]).map_err(|_| CrossChainError::InvalidGatewayApproval)
+    }
+
+    pub fn is_approved(&self) -> bool {
+        self.status == MESSAGE_APPROVED
+    }
+}
+
+#[derive(BorshSerialize, Debug, Clone)]
+struct CrossChainId<'m> {
+    chain: &'m str,
+    id: &'m str,
+}
+
+/// Message the validator set signs; the gateway stores its keccak hash.
+#[derive(BorshSerialize, Debug, Clone)]
+struct AxelarMessage<'m> {
+    cc_id: CrossChainId<'m>,
+    source_address: &'m str,
+    destination_chain: &'m str,
+    destination_address: &'m str,
+    payload_hash: [u8; 32],
+}
+
+#[derive(BorshSerialize, Debug, Clone)]
+struct CallContractData<'m> {
+    destination_chain: &'m str,
+    destination_contract_address: &'m str,
+    payload: &'m [u8],
+    signing_pda_bump: u8,
+}
+
+/// Id the gateway keys an incoming message by.
+pub fn command_id(source_chain: &str, message_id: &str) -> [u8; 32] {
+    keccak::hashv(&[source_chain.as_bytes(), b"-", message_id.as_bytes()]).to_bytes()
+}
+
+/// Hash the gateway records for a message from `route` to this program.
+pub fn message_hash(
+    config: &AxelarConfig,
+    route: &AxelarRoute,
+    program_id: &Pubkey,
+    message_id: &str,
+    payload: &[u8],
+) -> [u8; 32] {
+    let destination_address = program_id.to_string();
+    let message = AxelarMessage {
+        cc_id: CrossChainId {
+            chain: &route.chain_name,
+            id: message_id,
+        },
+        source_address: &route.gateway_address,
+        destination_chain: &config.chain_name,
+        destination_address: &destination_address,
+        payload_hash: keccak::hash(payload).to_bytes(),
+    };
+    keccak::hash(&message.try_to_vec().unwrap_or_default()).to_bytes()
+}
+
+/// Checks that the gateway approved a message from `chain_id`'s gateway, the
+/// chain confirming the transfer, to this program, and that its payload
+/// carries `transfer`.
+pub fn verify_gateway_approval(
+    config: &AxelarConfig,
+    route: &AxelarRoute,
+    chain_id: u64,
+    program_id: &Pubkey,
+    incoming: &IncomingMessage,
+    approval: &AxelarApproval,
+    transfer: &CrossChainTransferData,
+) -> Result<bool, CrossChainError> {
+    if route.chain_id != chain_id || !incoming.is_approved() {
+        return Err(CrossChainError::InvalidGatewayApproval);
+    }
+
+    let expected = message_hash(
+        config,
+        route,
+        program_id,
+        &approval.message_id,
+        &approval.payload,
+    );
+    if incoming.message_hash != expected
+        || incoming.payload_hash != keccak::hash(&approval.payload).to_bytes()
+    {
+        return Err(CrossChainError::InvalidGatewayApproval);
+    }
+
+    let payload = TransferPayload::decode(&approval.payload)?;
+    if !payload.matches(transfer) {
+        return Err(CrossChainError::InvalidGatewayApproval);
+    }
+
+    Ok(true)
+}
+
+pub fn get_axelar_route_address(chain_id: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AXELAR_ROUTE_SEED, &chain_id.to_le_bytes()], program_id)
+}
+
+pub fn get_axelar_signing_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AXELAR_CALL_CONTRACT_SEED], program_id)
+}
+
+pub fn get_gateway_root_address(gateway: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[GATEWAY_ROOT_SEED], gateway).0
+}
+
+pub fn get_incoming_message_address(command_id: &[u8; 32], gateway: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[INCOMING_MESSAGE_SEED, command_id], gateway).0
+}
+
+/// Gateway `CallContract` from `program_id`, signed by its calling PDA.
+pub fn call_contract_instruction(
+    gateway: &Pubkey,
+    program_id: &Pubkey,
+    route: &AxelarRoute,
+    payload: &[u8],
+) -> Instruction {
+    let (signing_pda, signing_pda_bump) = get_axelar_signing_address(program_id);
+    let data = CallContractData {
+        destination_chain: &route.chain_name,
+        destination_contract_address: &route.gateway_address,
+        payload,
+        signing_pda_bump,
+    };
+    let mut bytes = vec![CALL_CONTRACT_INSTRUCTION];
+    bytes.extend(data.try_to_vec().unwrap_or_default());
+
+    Instruction::new_with_bytes(
+        *gateway,
+        &bytes,
+        vec![
+            AccountMeta::new_readonly(*program_id, false),
+            AccountMeta::new_readonly(signing_pda, true),
+            AccountMeta::new_readonly(get_gateway_root_address(gateway), false),
+        ],
+    )
+}
+
+fn load_route(
+    ctx: &TransportContext,
+    chain_id: u64,
+    route_account: &AccountInfo,
+) -> Result<AxelarRoute, ProgramError> {
+    let (expected_route, _) = get_axelar_route_address(chain_id, ctx.program_id);
+    if expected_route != *route_account.key || route_account.owner != ctx.program_id {
+        return Err(CrossChainError::AxelarNotConfigured.into());
+    }
+    AxelarRoute::unpack(&route_account.data.borrow())
+}
+
+pub struct AxelarTransport;
+
+impl MessageTransport for AxelarTransport {
+    /// Consumes the gateway program, its root account, this program, the
+    /// calling PDA and the destination chain's route.
+    fn post_message<'a, 'b>(
+        &self,
+        ctx: &TransportContext,
+        outbound: &OutboundTransfer,
+        _payer: &'b AccountInfo<'a>,
+        _system_program: &'b AccountInfo<'a>,
+        accounts: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    ) -> ProgramResult {
+        let axelar = ctx
+            .state
+            .bridge_config
+            .axelar
+            .as_ref()
+            .ok_or(CrossChainError::AxelarNotConfigured)?;
+
+        let gateway = next_account_info(accounts)?;
+        let gateway_root = next_account_info(accounts)?;
+        let program = next_account_info(accounts)?;
+        let signing_pda = next_account_info(accounts)?;
+        let route_account = next_account_info(accounts)?;
+
+        if *gateway.key != axelar.gateway
+            || *gateway_root.key != get_gateway_root_address(&axelar.gateway)
+            || program.key != ctx.program_id
+        {
+            return Err(CrossChainError::AxelarNotConfigured.into());
+        }
+
+        let (expected_signing, signing_bump) = get_axelar_signing_address(ctx.program_id);
+        if expected_signing != *signing_pda.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let route = load_route(
+            ctx,
+            outbound.record.transfer.destination_chain,
+            route_account,
+        )?;
+
+        invoke_signed(
+            &call_contract_instruction(
+                gateway.key,
+                ctx.program_id,
+                &route,
+                &TransferPayload::for_record(outbound.record).encode(),
+            ),
+            &[
+                program.clone(),
+                signing_pda.clone(),
+                gateway_root.clone(),
+                gateway.clone(),
+            ],
+            &[&[AXELAR_CALL_CONTRACT_SEED, &[signing_bump]]],
+        )
+    }
+
+    /// Accepts only a gateway approval. Consumes the source chain's route and
+    /// the gateway's incoming message account.
+    fn verify_message<'a, 'b>(
+        &self,
+        ctx: &TransportContext,
+        inbound: &InboundTransfer,
+        _instructions_sysvar: &'b AccountInfo<'a>,
+        accounts: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    ) -> ProgramResult {
+        let TransportEvidence::GatewayApproval(approval) = inbound.evidence else {
+            return Err(CrossChainError::EvidenceNotSupported.into());
+        };
+
+        let axelar = ctx
+            .state
+            .bridge_config
+            .axelar
+            .as_ref()
+            .ok_or(CrossChainError::AxelarNotConfigured)?;
+
+        let route_account = next_account_info(accounts)?;
+        let message_account = next_account_info(accounts)?;
+
+        let route = load_route(ctx, inbound.source_chain.chain_id, route_account)?;
+        let expected_message = get_incoming_message_address(
+            &command_id(&route.chain_name, &approval.message_id),
+            &axelar.gateway,
+        );
+        if *message_account.owner != axelar.gateway || expected_message != *message_account.key {
+            return Err(CrossChainError::InvalidGatewayApproval.into());
+        }
+
+        let incoming = IncomingMessage::unpack(&message_account.data.borrow())?;
+        verify_gateway_approval(
+            axelar,
+            &route,
+            inbound.source_chain.chain_id,
+            ctx.program_id,
+            &incoming,
+            approval,
+            inbound.transfer,
+        )?;
+        Ok(())
+    }
+}
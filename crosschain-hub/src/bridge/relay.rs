@@ -0,0 +1,167 @@
+//! Trusted relayer transport. Bonded relayers watch `TransferInitiated`
+//! events and deliver transfers themselves, so nothing is posted on-chain;
+//! inbound completions rest on a quorum of relayer confirmations, an
+//! aggregate attestation from that quorum, or an inclusion proof against a
+//! state root the relayers posted earlier.
+
+use super::{
+    AggregateAttestation, InboundTransfer, MessageTransport, OutboundTransfer, RelayConfirmation,
+    TransportContext, TransportEvidence,
+};
+use crate::account::VersionedAccount;
+use crate::error::CrossChainError;
+use crate::merkle::{get_state_root_address, verify_inclusion, StateRoot};
+use crate::relayer::{
+    get_relayer_address, relay_signers, verify_aggregate_attestation, verify_relay_confirmation,
+    Relayer,
+};
+use borsh::BorshDeserialize;
+use solana_program::pubkey::Pubkey;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+};
+
+pub struct RelayerQuorumTransport;
+
+impl MessageTransport for RelayerQuorumTransport {
+    fn post_message<'a, 'b>(
+        &self,
+        _ctx: &TransportContext,
+        _outbound: &OutboundTransfer,
+        _payer: &'b AccountInfo<'a>,
+        _system_program: &'b AccountInfo<'a>,
+        _accounts: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    ) -> ProgramResult {
+        Ok(())
+    }
+
+    /// Confirmations and aggregate attestations consume one relayer registry
+    /// account per distinct signer; an inclusion proof consumes the state
+    /// root account.
+    fn verify_message<'a, 'b>(
+        &self,
+        ctx: &TransportContext,
+        inbound: &InboundTransfer,
+        instructions_sysvar: &'b AccountInfo<'a>,
+        accounts: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    ) -> ProgramResult {
+        match inbound.evidence {
+            TransportEvidence::Confirmations(_) | TransportEvidence::AggregateAttestation(_)
+                if ctx.state.bridge_config.require_inclusion_proof =>
+            {
+                Err(CrossChainError::InclusionProofRequired.into())
+            }
+            TransportEvidence::Confirmations(confirmations) => {
+                verify_relay_quorum(ctx, inbound, confirmations, instructions_sysvar, accounts)
+            }
+            TransportEvidence::AggregateAttestation(attestation) => {
+                verify_aggregate_quorum(ctx, inbound, attestation, instructions_sysvar, accounts)
+            }
+            TransportEvidence::InclusionProof(proof) => {
+                let state_root_account = next_account_info(accounts)?;
+                let (expected_root, _) = get_state_root_address(
+                    inbound.source_chain.chain_id,
+                    proof.block_number,
+                    ctx.program_id,
+                );
+                if expected_root != *state_root_account.key
+                    || state_root_account.owner != ctx.program_id
+                {
+                    return Err(CrossChainError::InvalidInclusionProof.into());
+                }
+
+                let state_root = StateRoot::unpack(&state_root_account.data.borrow())?;
+                verify_inclusion(
+                    &state_root,
+                    inbound.source_chain.chain_id,
+                    inbound.transfer,
+                    proof,
+                )?;
+                Ok(())
+            }
+            _ => Err(CrossChainError::EvidenceNotSupported.into()),
+        }
+    }
+}
+
+/// Checks the relayer quorum for a completion. Expects one relayer registry
+/// account per distinct signer, in `relay_signers` order; signatures must be
+/// verified by ed25519 program instructions earlier in the same transaction.
+fn verify_relay_quorum<'a, 'b>(
+    ctx: &TransportContext,
+    inbound: &InboundTransfer,
+    confirmations: &[RelayConfirmation],
+    instructions_sysvar: &'b AccountInfo<'a>,
+    accounts: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+) -> ProgramResult {
+    let relayers = load_relayers(ctx, &relay_signers(confirmations), accounts)?;
+
+    let bridge_config = &ctx.state.bridge_config;
+    verify_relay_confirmation(
+        &ctx.state.signing_domain(ctx.program_id),
+        inbound.transfer,
+        confirmations,
+        &relayers,
+        &crate::load_ed25519_signatures(instructions_sysvar)?,
+        inbound
+            .policy
+            .required_confirmations(bridge_config.required_relayer_signatures),
+        bridge_config.min_relayer_stake,
+    )?;
+    Ok(())
+}
+
+/// Checks an aggregate attestation. Only the ed25519 instruction it names is
+/// read; one relayer registry account per signature follows, in that
+/// instruction's order.
+fn verify_aggregate_quorum<'a, 'b>(
+    ctx: &TransportContext,
+    inbound: &InboundTransfer,
+    attestation: &AggregateAttestation,
+    instructions_sysvar: &'b AccountInfo<'a>,
+    accounts: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+) -> ProgramResult {
+    let verified = crate::load_ed25519_instruction(
+        instructions_sysvar,
+        attestation.ed25519_instruction_index,
+    )?;
+    let signers: Vec<Pubkey> = verified.iter().map(|v| v.signer).collect();
+    let relayers = load_relayers(ctx, &signers, accounts)?;
+
+    let bridge_config = &ctx.state.bridge_config;
+    verify_aggregate_attestation(
+        &ctx.state.signing_domain(ctx.program_id),
+        inbound.transfer,
+        attestation,
+        &relayers,
+        &verified,
+        inbound
+            .policy
+            .required_confirmations(bridge_config.required_relayer_signatures),
+        bridge_config.min_relayer_stake,
+    )?;
+    Ok(())
+}
+
+/// Reads the relayer registry account of each signer, in order.
+fn load_relayers<'a, 'b>(
+    ctx: &TransportContext,
+    signers: &[Pubkey],
+    accounts: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+) -> Result<Vec<Relayer>, ProgramError> {
+    let mut relayers = Vec::with_capacity(signers.len());
+    for signer in signers {
+        let relayer_account = next_account_info(accounts)?;
+        let (expected_relayer, _) = get_relayer_address(signer, ctx.program_id);
+        if expected_relayer != *relayer_account.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if relayer_account.owner != ctx.program_id {
+            return Err(CrossChainError::UnauthorizedRelayer.into());
+        }
+        relayers.push(Relayer::try_from_slice(&relayer_account.data.borrow())?);
+    }
+    Ok(relayers)
+}
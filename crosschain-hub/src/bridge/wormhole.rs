@@ -0,0 +1,322 @@
+//! Wormhole transport. Outbound transfers to a chain using it are posted to
+//! the Wormhole core bridge as messages emitted by the hub's emitter PDA; the
+//! guardians sign them into VAAs that counterpart gateways redeem. Inbound
+//! completions rest on a VAA the core bridge has already verified and posted,
+//! emitted by the gateway registered for the source chain.
+//!
+//! Only the core bridge's wire format is mirrored here, so the hub does not
+//! depend on the Wormhole SDK.
+
+use super::{
+    CrossChainTransferData, InboundTransfer, MessageTransport, OutboundTransfer, TransferPayload,
+    TransportContext, TransportEvidence,
+};
+use crate::error::CrossChainError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    keccak,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction, system_program, sysvar,
+};
+
+pub const WORMHOLE_EMITTER_SEED: &[u8] = b"emitter";
+pub const WORMHOLE_MESSAGE_SEED: &[u8] = b"wormhole_message";
+
+/// Seeds of the core bridge's own accounts.
+pub const CORE_BRIDGE_SEED: &[u8] = b"Bridge";
+pub const CORE_FEE_COLLECTOR_SEED: &[u8] = b"fee_collector";
+pub const CORE_SEQUENCE_SEED: &[u8] = b"Sequence";
+pub const CORE_POSTED_VAA_SEED: &[u8] = b"PostedVAA";
+
+const POST_MESSAGE_INSTRUCTION: u8 = 1;
+const POSTED_VAA_MAGIC: &[u8; 3] = b"vaa";
+
+/// Core bridge deployment the hub posts to, set through `UpdateBridgeConfig`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct WormholeConfig {
+    /// Core bridge program of the cluster the hub is deployed on.
+    pub core_bridge: Pubkey,
+    /// Finality guardians wait for before signing: 0 confirmed, 1 finalized.
+    pub consistency_level: u8,
+}
+
+/// Wormhole chain id and emitter address of a counterpart gateway.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WormholeEmitter {
+    pub chain: u16,
+    pub address: [u8; 32],
+}
+
+impl WormholeEmitter {
+    pub const LEN: usize = 2 + 32;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+struct PostMessageData {
+    nonce: u32,
+    payload: Vec<u8>,
+    consistency_level: u8,
+}
+
+/// Header of the core bridge's `Bridge` account, up to the message fee.
+#[derive(BorshDeserialize, Debug, Clone)]
+struct CoreBridgeData {
+    _guardian_set_index: u32,
+    _last_lamports: u64,
+    _guardian_set_expiration_time: u32,
+    fee: u64,
+}
+
+/// Lamports the core bridge charges per posted message, read from its
+/// `Bridge` account.
+pub fn core_bridge_fee(bridge_data: &[u8]) -> Result<u64, CrossChainError> {
+    CoreBridgeData::deserialize(&mut &bridge_data[..])
+        .map(|data| data.fee)
+        .map_err(|_| CrossChainError::InvalidWormholeAccount)
+}
+
+/// A VAA the core bridge verified against the guardian set and posted.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct PostedVaa {
+    pub vaa_version: u8,
+    pub consistency_level: u8,
+    pub vaa_time: u32,
+    pub vaa_signature_account: Pubkey,
+    pub submission_time: u32,
+    pub nonce: u32,
+    pub sequence: u64,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub payload: Vec<u8>,
+}
+
+impl PostedVaa {
+    pub fn unpack(data: &[u8]) -> Result<Self, CrossChainError> {
+        let body = data
+            .strip_prefix(POSTED_VAA_MAGIC)
+            .ok_or(CrossChainError::InvalidVaa)?;
+        Self::deserialize(&mut &body[..]).map_err(|_| CrossChainError::InvalidVaa)
+    }
+
+    /// Keccak hash of the signed VAA body; the core bridge seeds the posted
+    /// account with it.
+    pub fn body_hash(&self) -> [u8; 32] {
+        keccak::hashv(&[
+            &self.vaa_time.to_be_bytes(),
+            &self.nonce.to_be_bytes(),
+            &self.emitter_chain.to_be_bytes(),
+            &self.emitter_address,
+            &self.sequence.to_be_bytes(),
+            &[self.consistency_level],
+            &self.payload,
+        ])
+        .to_bytes()
+    }
+}
+
+/// Checks that a posted VAA was emitted by the source chain's gateway and
+/// carries `transfer`.
+pub fn verify_transfer_vaa(
+    vaa: &PostedVaa,
+    emitter: Option<&WormholeEmitter>,
+    transfer: &CrossChainTransferData,
+) -> Result<bool, CrossChainError> {
+    let emitter = emitter.ok_or(CrossChainError::InvalidVaa)?;
+    if vaa.emitter_chain != emitter.chain || vaa.emitter_address != emitter.address {
+        return Err(CrossChainError::InvalidVaa);
+    }
+
+    let payload = TransferPayload::decode(&vaa.payload)?;
+    if !payload.matches(transfer) {
+        return Err(CrossChainError::InvalidVaa);
+    }
+
+    Ok(true)
+}
+
+pub fn get_wormhole_emitter_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WORMHOLE_EMITTER_SEED], program_id)
+}
+
+/// Message account the core bridge creates for a transfer record's post.
+pub fn get_wormhole_message_address(record: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WORMHOLE_MESSAGE_SEED, record.as_ref()], program_id)
+}
+
+pub fn get_core_bridge_address(core_bridge: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CORE_BRIDGE_SEED], core_bridge).0
+}
+
+pub fn get_core_fee_collector_address(core_bridge: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CORE_FEE_COLLECTOR_SEED], core_bridge).0
+}
+
+pub fn get_core_sequence_address(emitter: &Pubkey, core_bridge: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CORE_SEQUENCE_SEED, emitter.as_ref()], core_bridge).0
+}
+
+pub fn get_posted_vaa_address(body_hash: &[u8; 32], core_bridge: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[CORE_POSTED_VAA_SEED, body_hash], core_bridge).0
+}
+
+/// Core bridge `PostMessage`. The message account and emitter must sign; the
+/// message fee has to reach the fee collector earlier in the transaction.
+pub fn post_message_instruction(
+    core_bridge: &Pubkey,
+    payer: &Pubkey,
+    message: &Pubkey,
+    emitter: &Pubkey,
+    payload: Vec<u8>,
+    consistency_level: u8,
+) -> Instruction {
+    let data = PostMessageData {
+        nonce: 0,
+        payload,
+        consistency_level,
+    };
+    let mut bytes = vec![POST_MESSAGE_INSTRUCTION];
+    bytes.extend(data.try_to_vec().unwrap_or_default());
+
+    Instruction::new_with_bytes(
+        *core_bridge,
+        &bytes,
+        vec![
+            AccountMeta::new(get_core_bridge_address(core_bridge), false),
+            AccountMeta::new(*message, true),
+            AccountMeta::new_readonly(*emitter, true),
+            AccountMeta::new(get_core_sequence_address(emitter, core_bridge), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(get_core_fee_collector_address(core_bridge), false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub struct WormholeTransport;
+
+impl MessageTransport for WormholeTransport {
+    /// Consumes the core bridge program, its bridge account, the message PDA,
+    /// the hub emitter, the emitter's sequence account, the fee collector and
+    /// the clock and rent sysvars. The payer covers the message fee.
+    fn post_message<'a, 'b>(
+        &self,
+        ctx: &TransportContext,
+        outbound: &OutboundTransfer,
+        payer: &'b AccountInfo<'a>,
+        system_program: &'b AccountInfo<'a>,
+        accounts: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    ) -> ProgramResult {
+        let wormhole = ctx
+            .state
+            .bridge_config
+            .wormhole
+            .as_ref()
+            .ok_or(CrossChainError::WormholeNotConfigured)?;
+
+        let core_bridge = next_account_info(accounts)?;
+        let bridge_account = next_account_info(accounts)?;
+        let message_account = next_account_info(accounts)?;
+        let emitter = next_account_info(accounts)?;
+        let sequence = next_account_info(accounts)?;
+        let fee_collector = next_account_info(accounts)?;
+        let clock_sysvar = next_account_info(accounts)?;
+        let rent_sysvar = next_account_info(accounts)?;
+
+        if *core_bridge.key != wormhole.core_bridge
+            || *bridge_account.owner != wormhole.core_bridge
+            || *bridge_account.key != get_core_bridge_address(&wormhole.core_bridge)
+        {
+            return Err(CrossChainError::InvalidWormholeAccount.into());
+        }
+
+        let (expected_emitter, emitter_bump) = get_wormhole_emitter_address(ctx.program_id);
+        let (expected_message, message_bump) =
+            get_wormhole_message_address(outbound.record_key, ctx.program_id);
+        if expected_emitter != *emitter.key || expected_message != *message_account.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let fee = core_bridge_fee(&bridge_account.data.borrow())?;
+        if fee > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, fee_collector.key, fee),
+                &[payer.clone(), fee_collector.clone(), system_program.clone()],
+            )?;
+        }
+
+        invoke_signed(
+            &post_message_instruction(
+                core_bridge.key,
+                payer.key,
+                message_account.key,
+                emitter.key,
+                TransferPayload::for_record(outbound.record).encode(),
+                wormhole.consistency_level,
+            ),
+            &[
+                bridge_account.clone(),
+                message_account.clone(),
+                emitter.clone(),
+                sequence.clone(),
+                payer.clone(),
+                fee_collector.clone(),
+                clock_sysvar.clone(),
+                rent_sysvar.clone(),
+                system_program.clone(),
+                core_bridge.clone(),
+            ],
+            &[
+                &[WORMHOLE_EMITTER_SEED, &[emitter_bump]],
+                &[
+                    WORMHOLE_MESSAGE_SEED,
+                    outbound.record_key.as_ref(),
+                    &[message_bump],
+                ],
+            ],
+        )
+    }
+
+    /// Accepts only a posted VAA, passed as the first transport account.
+    fn verify_message<'a, 'b>(
+        &self,
+        ctx: &TransportContext,
+        inbound: &InboundTransfer,
+        _instructions_sysvar: &'b AccountInfo<'a>,
+        accounts: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    ) -> ProgramResult {
+        if !matches!(inbound.evidence, TransportEvidence::Vaa) {
+            return Err(CrossChainError::VaaRequired.into());
+        }
+
+        let wormhole = ctx
+            .state
+            .bridge_config
+            .wormhole
+            .as_ref()
+            .ok_or(CrossChainError::WormholeNotConfigured)?;
+
+        let vaa_account = next_account_info(accounts)?;
+        if *vaa_account.owner != wormhole.core_bridge {
+            return Err(CrossChainError::InvalidWormholeAccount.into());
+        }
+
+        let vaa = PostedVaa::unpack(&vaa_account.data.borrow())?;
+        if get_posted_vaa_address(&vaa.body_hash(), &wormhole.core_bridge) != *vaa_account.key {
+            return Err(CrossChainError::InvalidVaa.into());
+        }
+
+        verify_transfer_vaa(
+            &vaa,
+            inbound.source_chain.wormhole_emitter(),
+            inbound.transfer,
+        )?;
+        Ok(())
+    }
+}
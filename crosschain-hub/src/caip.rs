@@ -0,0 +1,102 @@
+use crate::error::CrossChainError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::hash::hashv;
+
+pub const CAIP2_NAMESPACE_MAX_LEN: usize = 8;
+pub const CAIP2_REFERENCE_MAX_LEN: usize = 32;
+pub const EIP155_NAMESPACE: &str = "eip155";
+
+/// Legacy ids derived for non-EVM chains have the top bit set, which no EVM
+/// chain id in use reaches, so they cannot collide with `eip155` references.
+pub const DERIVED_LEGACY_ID_FLAG: u64 = 1 << 63;
+
+/// CAIP-2 chain identifier (`namespace:reference`), stored zero-padded so
+/// registry accounts keep a fixed size.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Caip2ChainId {
+    pub namespace: [u8; CAIP2_NAMESPACE_MAX_LEN],
+    pub reference: [u8; CAIP2_REFERENCE_MAX_LEN],
+}
+
+impl Caip2ChainId {
+    pub const LEN: usize = CAIP2_NAMESPACE_MAX_LEN + CAIP2_REFERENCE_MAX_LEN;
+
+    pub fn new(namespace: &str, reference: &str) -> Result<Self, CrossChainError> {
+        let namespace_ok = (3..=CAIP2_NAMESPACE_MAX_LEN).contains(&namespace.len())
+            && namespace
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+        let reference_ok = (1..=CAIP2_REFERENCE_MAX_LEN).contains(&reference.len())
+            && reference
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+
+        if !namespace_ok || !reference_ok {
+            return Err(CrossChainError::InvalidChainId);
+        }
+
+        let mut id = Caip2ChainId {
+            namespace: [0; CAIP2_NAMESPACE_MAX_LEN],
+            reference: [0; CAIP2_REFERENCE_MAX_LEN],
+        };
+        id.namespace[..namespace.len()].copy_from_slice(namespace.as_bytes());
+        id.reference[..reference.len()].copy_from_slice(reference.as_bytes());
+        Ok(id)
+    }
+
+    pub fn parse(value: &str) -> Result<Self, CrossChainError> {
+        let (namespace, reference) = value
+            .split_once(':')
+            .ok_or(CrossChainError::InvalidChainId)?;
+        Self::new(namespace, reference)
+    }
+
+    /// Legacy registry ids were EVM chain ids, so they map onto `eip155`.
+    pub fn from_legacy(chain_id: u64) -> Self {
+        let reference = chain_id.to_string();
+        let mut id = Caip2ChainId {
+            namespace: [0; CAIP2_NAMESPACE_MAX_LEN],
+            reference: [0; CAIP2_REFERENCE_MAX_LEN],
+        };
+        id.namespace[..EIP155_NAMESPACE.len()].copy_from_slice(EIP155_NAMESPACE.as_bytes());
+        id.reference[..reference.len()].copy_from_slice(reference.as_bytes());
+        id
+    }
+
+    pub fn namespace(&self) -> &str {
+        trim_padding(&self.namespace)
+    }
+
+    pub fn reference(&self) -> &str {
+        trim_padding(&self.reference)
+    }
+
+    /// The u64 the chain is keyed by in the registry and in transfer data.
+    /// `eip155` chains keep their numeric id; other namespaces get a stable
+    /// id derived from the full identifier.
+    pub fn legacy_id(&self) -> u64 {
+        if self.namespace() == EIP155_NAMESPACE {
+            if let Ok(id) = self.reference().parse::<u64>() {
+                if id & DERIVED_LEGACY_ID_FLAG == 0 {
+                    return id;
+                }
+            }
+        }
+
+        let digest = hashv(&[&self.namespace, &self.reference]).to_bytes();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        u64::from_le_bytes(bytes) | DERIVED_LEGACY_ID_FLAG
+    }
+}
+
+impl std::fmt::Display for Caip2ChainId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.namespace(), self.reference())
+    }
+}
+
+fn trim_padding(bytes: &[u8]) -> &str {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    std::str::from_utf8(&bytes[..end]).unwrap_or_default()
+}
@@ -0,0 +1,29 @@
+//! Canonical decoding of externally supplied borsh payloads. Borsh accepts
+//! more than one encoding for some values, for example map entries in any
+//! order or repeated keys, so two byte strings can decode to the same value.
+//! Anything hashed or signed is only accepted in the one encoding that
+//! re-serializes to the same bytes.
+
+use crate::error::CrossChainError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::program_error::ProgramError;
+
+/// Decodes `data` and rejects it unless it is the canonical encoding of the
+/// decoded value.
+pub fn decode_canonical<T: BorshSerialize + BorshDeserialize>(
+    data: &[u8],
+) -> Result<T, ProgramError> {
+    let value = T::try_from_slice(data)?;
+    check_canonical(&value, data)?;
+    Ok(value)
+}
+
+pub fn check_canonical<T: BorshSerialize>(value: &T, data: &[u8]) -> Result<bool, CrossChainError> {
+    let encoded = value
+        .try_to_vec()
+        .map_err(|_| CrossChainError::NonCanonicalEncoding)?;
+    if encoded != data {
+        return Err(CrossChainError::NonCanonicalEncoding);
+    }
+    Ok(true)
+}
@@ -0,0 +1,458 @@
+//! Off-chain helpers for assembling hub transactions. Flows that touch the
+//! multisig, bridge and compliance accounts together overflow the legacy
+//! message account limit, so they are sent as v0 transactions that load the
+//! non-signer accounts from address lookup tables.
+
+use crate::bridge::{get_transfer_record_address, CrossChainTransferData, TransferRecord};
+use crate::error::CrossChainError;
+use crate::relayer::{get_relayer_lease_address, RelayerLease};
+use borsh::BorshDeserialize;
+use num_traits::FromPrimitive;
+use solana_address_lookup_table_interface::instruction as lookup_table_instruction;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::{Instruction, InstructionError},
+    message::{v0, AddressLookupTableAccount, CompileError, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{signers::Signers, SignerError},
+    transaction::{TransactionError, VersionedTransaction},
+};
+use thiserror::Error;
+
+#[cfg(feature = "client")]
+pub mod instructions;
+
+const PROGRAM_LOG_PREFIX: &str = "Program log: ";
+
+/// Addresses appended per `ExtendLookupTable` so the extend transaction itself
+/// stays under the packet size limit.
+pub const MAX_EXTEND_ADDRESSES: usize = 20;
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+/// Base fee per signature, charged on top of any priority fee.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("failed to compile v0 message: {0}")]
+    Compile(#[from] CompileError),
+    #[error("failed to sign transaction: {0}")]
+    Sign(#[from] SignerError),
+    #[error("rpc transport error: {0}")]
+    Transport(String),
+    #[error("simulation failed: {reason}")]
+    Simulation {
+        reason: String,
+        error: Option<CrossChainError>,
+        logs: Vec<String>,
+    },
+    #[error("transfer record {0} already exists for a different transfer")]
+    IdempotencyConflict(Pubkey),
+    #[error("failed to decode account {0}")]
+    InvalidAccount(Pubkey),
+    #[error("fee spend for chain {chain_id} would exceed its cap of {cap} lamports")]
+    FeeBudgetExceeded { chain_id: u64, cap: u64 },
+}
+
+/// Result of simulating a transaction against the cluster.
+#[derive(Debug, Clone, Default)]
+pub struct SimulationResult {
+    pub err: Option<TransactionError>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+/// Minimal RPC surface needed to submit transactions, so the helpers work with
+/// any client (nonblocking RPC, banks client, test doubles).
+pub trait RpcTransport {
+    fn simulate(&self, tx: &VersionedTransaction) -> Result<SimulationResult, ClientError>;
+    fn send(&self, tx: &VersionedTransaction) -> Result<Signature, ClientError>;
+    fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, ClientError>;
+
+    /// Recent prioritization fees (micro-lamports per compute unit) paid by
+    /// transactions writing to `accounts`. Transports without fee data may
+    /// return nothing, in which case the policy minimum is bid.
+    fn get_recent_prioritization_fees(
+        &self,
+        _accounts: &[Pubkey],
+    ) -> Result<Vec<u64>, ClientError> {
+        Ok(Vec::new())
+    }
+}
+
+/// Outcome of an idempotent submission: either a new transaction, or the
+/// record left by an earlier submission of the same transfer.
+#[derive(Debug)]
+pub enum Submission {
+    Sent(Signature),
+    Existing(Box<TransferRecord>),
+}
+
+/// Accounts in `instructions` that can be loaded through a lookup table:
+/// everything except signers and invoked programs, which v0 messages must
+/// keep in their static keys. Order follows first use.
+pub fn lookup_table_candidates(instructions: &[Instruction]) -> Vec<Pubkey> {
+    let programs: Vec<Pubkey> = instructions.iter().map(|ix| ix.program_id).collect();
+    let signers: Vec<Pubkey> = instructions
+        .iter()
+        .flat_map(|ix| ix.accounts.iter())
+        .filter(|meta| meta.is_signer)
+        .map(|meta| meta.pubkey)
+        .collect();
+
+    let mut candidates: Vec<Pubkey> = Vec::new();
+    for meta in instructions.iter().flat_map(|ix| ix.accounts.iter()) {
+        if programs.contains(&meta.pubkey)
+            || signers.contains(&meta.pubkey)
+            || candidates.contains(&meta.pubkey)
+        {
+            continue;
+        }
+        candidates.push(meta.pubkey);
+    }
+    candidates
+}
+
+/// Creates a lookup table owned by `authority`. Returns the instruction and the
+/// table address derived from `recent_slot`.
+pub fn create_lookup_table(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+) -> (Instruction, Pubkey) {
+    lookup_table_instruction::create_lookup_table(*authority, *payer, recent_slot)
+}
+
+/// Extend instructions adding the `addresses` not already in `existing`,
+/// chunked by `MAX_EXTEND_ADDRESSES`. Empty when the table already covers them.
+pub fn extend_lookup_table(
+    table: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    existing: &[Pubkey],
+    addresses: &[Pubkey],
+) -> Vec<Instruction> {
+    let mut missing: Vec<Pubkey> = Vec::new();
+    for address in addresses {
+        if !existing.contains(address) && !missing.contains(address) {
+            missing.push(*address);
+        }
+    }
+
+    missing
+        .chunks(MAX_EXTEND_ADDRESSES)
+        .map(|chunk| {
+            lookup_table_instruction::extend_lookup_table(
+                *table,
+                *authority,
+                Some(*payer),
+                chunk.to_vec(),
+            )
+        })
+        .collect()
+}
+
+/// Compiles `instructions` into a v0 message resolving accounts through
+/// `lookup_tables`, and signs it.
+pub fn build_versioned_transaction<T: Signers + ?Sized>(
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    recent_blockhash: Hash,
+    signers: &T,
+) -> Result<VersionedTransaction, ClientError> {
+    let message = v0::Message::try_compile(payer, instructions, lookup_tables, recent_blockhash)?;
+    Ok(VersionedTransaction::try_new(
+        VersionedMessage::V0(message),
+        signers,
+    )?)
+}
+
+/// Maps a failed transaction back to the hub's typed error, when the failure
+/// was a `CrossChainError` raised by one of our instructions.
+pub fn decode_transaction_error(err: &TransactionError) -> Option<CrossChainError> {
+    match err {
+        TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+            CrossChainError::from_u32(*code)
+        }
+        _ => None,
+    }
+}
+
+/// `msg!` output from the simulation logs, without the runtime prefix.
+pub fn program_logs(logs: &[String]) -> Vec<String> {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix(PROGRAM_LOG_PREFIX))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Simulates `tx` and only sends it when simulation succeeds, so a failing
+/// transaction is reported with a readable reason instead of costing fees.
+pub fn send_with_preflight<R: RpcTransport + ?Sized>(
+    rpc: &R,
+    tx: &VersionedTransaction,
+) -> Result<Signature, ClientError> {
+    let simulation = rpc.simulate(tx)?;
+
+    if let Some(err) = simulation.err {
+        let error = decode_transaction_error(&err);
+        let reason = match error {
+            Some(e) => e.to_string(),
+            None => err.to_string(),
+        };
+        return Err(ClientError::Simulation {
+            reason,
+            error,
+            logs: program_logs(&simulation.logs),
+        });
+    }
+
+    rpc.send(tx)
+}
+
+/// Idempotency key for `InitiateCrossChain`: the transfer record PDA, which is
+/// fully determined by sender and nonce.
+pub fn transfer_idempotency_key(transfer: &CrossChainTransferData, program_id: &Pubkey) -> Pubkey {
+    get_transfer_record_address(&transfer.sender, transfer.nonce, program_id).0
+}
+
+/// Submits an `InitiateCrossChain` transaction unless the transfer record
+/// already exists, in which case the existing record is returned. A record
+/// under the same key with different terms means the nonce was reused and is
+/// reported as a conflict rather than silently accepted.
+pub fn initiate_cross_chain_idempotent<R: RpcTransport + ?Sized>(
+    rpc: &R,
+    program_id: &Pubkey,
+    transfer: &CrossChainTransferData,
+    tx: &VersionedTransaction,
+) -> Result<Submission, ClientError> {
+    let key = transfer_idempotency_key(transfer, program_id);
+
+    if let Some(data) = rpc.get_account_data(&key)? {
+        let record =
+            TransferRecord::try_from_slice(&data).map_err(|_| ClientError::InvalidAccount(key))?;
+
+        let existing = &record.transfer;
+        if existing.recipient != transfer.recipient
+            || existing.amount != transfer.amount
+            || existing.destination_chain != transfer.destination_chain
+            || existing.token_address != transfer.token_address
+        {
+            return Err(ClientError::IdempotencyConflict(key));
+        }
+
+        return Ok(Submission::Existing(Box::new(record)));
+    }
+
+    send_with_preflight(rpc, tx).map(Submission::Sent)
+}
+
+/// Whether `relayer` currently holds the leader lease at `slot`. Redundant
+/// relayer instances call this before submitting completions; standbys
+/// should try `AcquireRelayerLease` once it returns false for everyone.
+pub fn is_relay_leader<R: RpcTransport + ?Sized>(
+    rpc: &R,
+    program_id: &Pubkey,
+    relayer: &Pubkey,
+    slot: u64,
+) -> Result<bool, ClientError> {
+    let key = get_relayer_lease_address(program_id).0;
+
+    match rpc.get_account_data(&key)? {
+        Some(data) => {
+            let lease = RelayerLease::try_from_slice(&data)
+                .map_err(|_| ClientError::InvalidAccount(key))?;
+            Ok(lease.is_leader(relayer, slot))
+        }
+        None => Ok(false),
+    }
+}
+
+/// How the relayer bids for block space when submitting completions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriorityFeePolicy {
+    /// Percentile of recent prioritization fees to match, 0-100.
+    pub percentile: u8,
+    /// Bounds on the bid, in micro-lamports per compute unit.
+    pub min_unit_price: u64,
+    pub max_unit_price: u64,
+    /// Headroom on top of simulated compute units, in basis points.
+    pub compute_unit_margin_bps: u16,
+}
+
+impl Default for PriorityFeePolicy {
+    fn default() -> Self {
+        Self {
+            percentile: 75,
+            min_unit_price: 0,
+            max_unit_price: 1_000_000,
+            compute_unit_margin_bps: 1_000,
+        }
+    }
+}
+
+/// Unit price at `policy.percentile` of `recent_fees`, clamped to the policy
+/// bounds.
+pub fn estimate_unit_price(recent_fees: &[u64], policy: &PriorityFeePolicy) -> u64 {
+    let mut fees = recent_fees.to_vec();
+    fees.sort_unstable();
+
+    let estimate = match fees.len() {
+        0 => 0,
+        len => fees[(len - 1) * policy.percentile.min(100) as usize / 100],
+    };
+    estimate.clamp(
+        policy.min_unit_price,
+        policy.max_unit_price.max(policy.min_unit_price),
+    )
+}
+
+/// Compute unit limit for a transaction that simulated at `units_consumed`.
+pub fn compute_unit_limit(units_consumed: u64, margin_bps: u16) -> u32 {
+    let limit = units_consumed as u128 * (10_000 + margin_bps as u128) / 10_000;
+    limit.min(MAX_COMPUTE_UNIT_LIMIT as u128) as u32
+}
+
+/// Worst-case priority fee in lamports, rounded up.
+pub fn priority_fee_lamports(unit_limit: u32, unit_price: u64) -> u64 {
+    let micro_lamports = unit_limit as u128 * unit_price as u128;
+    micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64
+}
+
+/// Prepends the compute budget instructions for `unit_limit` and
+/// `unit_price` to `instructions`.
+pub fn with_compute_budget(
+    instructions: &[Instruction],
+    unit_limit: u32,
+    unit_price: u64,
+) -> Vec<Instruction> {
+    let mut priced = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(unit_limit),
+        ComputeBudgetInstruction::set_compute_unit_price(unit_price),
+    ];
+    priced.extend_from_slice(instructions);
+    priced
+}
+
+/// Fee spend for one destination chain within the current budget period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainFeeCap {
+    pub chain_id: u64,
+    pub cap_lamports: u64,
+    pub spent_lamports: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeeBudgetStatus {
+    WithinBudget,
+    /// Spend has crossed the alert threshold but is still under the cap.
+    Alert {
+        chain_id: u64,
+        spent: u64,
+        cap: u64,
+    },
+}
+
+/// Per-chain caps on what the relayer spends on Solana fees. Chains without
+/// a cap are not relayed. Callers reset the budget at each period boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeBudget {
+    pub chains: Vec<ChainFeeCap>,
+    /// Fraction of a cap, in basis points, at which spend is reported.
+    pub alert_threshold_bps: u16,
+}
+
+impl FeeBudget {
+    /// Status after spending `lamports` more on `chain_id`, without recording it.
+    pub fn check(&self, chain_id: u64, lamports: u64) -> Result<FeeBudgetStatus, ClientError> {
+        let chain = self
+            .chains
+            .iter()
+            .find(|c| c.chain_id == chain_id)
+            .ok_or(ClientError::FeeBudgetExceeded { chain_id, cap: 0 })?;
+
+        let spent = chain.spent_lamports.saturating_add(lamports);
+        if spent > chain.cap_lamports {
+            return Err(ClientError::FeeBudgetExceeded {
+                chain_id,
+                cap: chain.cap_lamports,
+            });
+        }
+
+        let threshold = chain.cap_lamports as u128 * self.alert_threshold_bps as u128 / 10_000;
+        if spent as u128 >= threshold {
+            return Ok(FeeBudgetStatus::Alert {
+                chain_id,
+                spent,
+                cap: chain.cap_lamports,
+            });
+        }
+
+        Ok(FeeBudgetStatus::WithinBudget)
+    }
+
+    /// Records `lamports` spent on `chain_id` once a transaction has landed.
+    pub fn record(&mut self, chain_id: u64, lamports: u64) -> Result<FeeBudgetStatus, ClientError> {
+        let status = self.check(chain_id, lamports)?;
+        if let Some(chain) = self.chains.iter_mut().find(|c| c.chain_id == chain_id) {
+            chain.spent_lamports = chain.spent_lamports.saturating_add(lamports);
+        }
+        Ok(status)
+    }
+
+    pub fn reset(&mut self) {
+        for chain in &mut self.chains {
+            chain.spent_lamports = 0;
+        }
+    }
+}
+
+/// Instructions with a compute budget attached, and what they may cost.
+#[derive(Debug, Clone)]
+pub struct PricedInstructions {
+    pub instructions: Vec<Instruction>,
+    pub unit_limit: u32,
+    pub unit_price: u64,
+    /// Signature fees plus the worst-case priority fee.
+    pub max_fee_lamports: u64,
+    pub budget_status: FeeBudgetStatus,
+}
+
+/// Prices a completion for `chain_id`: sizes the compute limit from a prior
+/// simulation, bids from recent fees on the accounts it writes, and refuses
+/// when the worst-case fee would break the chain's cap.
+pub fn price_instructions<R: RpcTransport + ?Sized>(
+    rpc: &R,
+    instructions: &[Instruction],
+    units_consumed: u64,
+    signatures: u64,
+    policy: &PriorityFeePolicy,
+    budget: &FeeBudget,
+    chain_id: u64,
+) -> Result<PricedInstructions, ClientError> {
+    let mut writable: Vec<Pubkey> = Vec::new();
+    for meta in instructions.iter().flat_map(|ix| ix.accounts.iter()) {
+        if meta.is_writable && !writable.contains(&meta.pubkey) {
+            writable.push(meta.pubkey);
+        }
+    }
+
+    let recent_fees = rpc.get_recent_prioritization_fees(&writable)?;
+    let unit_price = estimate_unit_price(&recent_fees, policy);
+    let unit_limit = compute_unit_limit(units_consumed, policy.compute_unit_margin_bps);
+    let max_fee_lamports = LAMPORTS_PER_SIGNATURE
+        .saturating_mul(signatures)
+        .saturating_add(priority_fee_lamports(unit_limit, unit_price));
+    let budget_status = budget.check(chain_id, max_fee_lamports)?;
+
+    Ok(PricedInstructions {
+        instructions: with_compute_budget(instructions, unit_limit, unit_price),
+        unit_limit,
+        unit_price,
+        max_fee_lamports,
+        budget_status,
+    })
+}
@@ -0,0 +1,1747 @@
+//! Builders for hub instructions with their account metas in handler order,
+//! so clients never hand-serialize Borsh or track PDA seeds themselves. Each
+//! builder fails only when an argument overflows a bounded field.
+
+pub use crate::audit::get_audit_log_address;
+pub use crate::bridge::axelar::{
+    get_axelar_route_address, get_axelar_signing_address, get_gateway_root_address,
+    get_incoming_message_address,
+};
+pub use crate::bridge::wormhole::{
+    get_core_bridge_address, get_core_fee_collector_address, get_core_sequence_address,
+    get_wormhole_emitter_address, get_wormhole_message_address,
+};
+pub use crate::bridge::{
+    get_chain_config_address, get_chain_pair_policy_address, get_transfer_batch_address,
+    get_transfer_record_address, get_wrapped_registry_address,
+};
+pub use crate::eidas::{
+    get_compliance_record_address, get_restricted_jurisdictions_address, get_sponsor_address,
+};
+pub use crate::get_state_address;
+pub use crate::governance::{
+    get_governance_address, get_governance_authority_address, get_proposal_address,
+    get_vote_record_address,
+};
+pub use crate::merkle::get_state_root_address;
+pub use crate::relayer::get_relayer_address;
+pub use crate::treasury::{
+    get_fee_history_address, get_treasury_address, get_treasury_report_address,
+};
+pub use crate::wallet::{
+    get_activity_log_address, get_guardian_set_address, get_nonce_registry_address,
+    get_recipient_list_address, get_second_factor_address, get_session_key_address,
+    get_wallet_address, get_wallet_config_address,
+};
+
+use crate::account::AccountKind;
+use crate::bridge::axelar::{command_id, AxelarApproval};
+use crate::bridge::{
+    AggregateAttestation, ChainConfigData, ChainPairPolicyData, CrossChainTransferData,
+    RelayConfirmation, TransferBatch, TransportEvidence,
+};
+use crate::eidas::{ComplianceData, FreezeReason, SignedComplianceAttestation};
+use crate::governance::GovernanceConfig;
+use crate::merkle::InclusionProof;
+use crate::multisig::ProposedInstruction;
+use crate::relayer::{aggregate_ed25519_instruction_data, relay_signers};
+use crate::wallet::{
+    RecipientListKind, SessionKeyData, TransactionSignatureData, WalletConfig, WalletData,
+};
+use crate::{CrossChainConfig, CrossChainInstruction};
+use borsh::BorshSerialize;
+use solana_sdk::{
+    ed25519_program,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+fn build(
+    program_id: &Pubkey,
+    instruction: CrossChainInstruction,
+    accounts: Vec<AccountMeta>,
+) -> std::io::Result<Instruction> {
+    Ok(Instruction::new_with_bytes(
+        *program_id,
+        &instruction.try_to_vec()?,
+        accounts,
+    ))
+}
+
+fn state_meta(program_id: &Pubkey, writable: bool) -> AccountMeta {
+    let state = get_state_address(program_id).0;
+    if writable {
+        AccountMeta::new(state, false)
+    } else {
+        AccountMeta::new_readonly(state, false)
+    }
+}
+
+pub fn initialize_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    config: CrossChainConfig,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::Initialize { config },
+        vec![
+            state_meta(program_id, true),
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(get_audit_log_address(program_id).0, false),
+            AccountMeta::new(get_fee_history_address(program_id).0, false),
+        ],
+    )
+}
+
+/// Always passes the instructions sysvar, which the hub only reads while
+/// `require_top_level` is set.
+pub fn update_config_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    config: CrossChainConfig,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::UpdateConfig { config },
+        vec![
+            state_meta(program_id, true),
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(get_fee_history_address(program_id).0, false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+    )
+}
+
+pub fn queue_config_update_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    config: CrossChainConfig,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::QueueConfigUpdate { config },
+        vec![
+            state_meta(program_id, true),
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+    )
+}
+
+pub fn execute_config_update_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::ExecuteConfigUpdate,
+        vec![
+            state_meta(program_id, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(get_fee_history_address(program_id).0, false),
+        ],
+    )
+}
+
+/// Registrations attesting an eIDAS level above `None` append the eIDAS
+/// authority as a signer.
+pub fn register_wallet_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    wallet_data: WalletData,
+) -> std::io::Result<Instruction> {
+    sponsored_register_wallet_ix(program_id, owner, owner, wallet_data)
+}
+
+/// `register_wallet_ix` with the wallet's accounts paid for by `payer`, the
+/// eIDAS authority or an approved sponsor.
+pub fn sponsored_register_wallet_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    wallet_data: WalletData,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::RegisterWallet { wallet_data },
+        vec![
+            state_meta(program_id, true),
+            AccountMeta::new(get_wallet_address(owner, program_id).0, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(get_activity_log_address(owner, program_id).0, false),
+            AccountMeta::new(get_nonce_registry_address(owner, program_id).0, false),
+            AccountMeta::new(get_audit_log_address(program_id).0, false),
+            AccountMeta::new(get_wallet_config_address(owner, program_id).0, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(get_sponsor_address(payer, program_id).0, false),
+        ],
+    )
+}
+
+pub fn update_wallet_config_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    config: WalletConfig,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::UpdateWalletConfig { config },
+        vec![
+            AccountMeta::new_readonly(get_wallet_address(owner, program_id).0, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(get_wallet_config_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(get_second_factor_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(*owner, false),
+        ],
+    )
+}
+
+pub fn sign_transaction_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    signature_data: TransactionSignatureData,
+) -> std::io::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(get_wallet_address(owner, program_id).0, false),
+        AccountMeta::new_readonly(*owner, true),
+        AccountMeta::new(get_activity_log_address(owner, program_id).0, false),
+        AccountMeta::new(get_nonce_registry_address(owner, program_id).0, false),
+        AccountMeta::new_readonly(get_compliance_record_address(owner, program_id).0, false),
+        AccountMeta::new_readonly(get_wallet_config_address(owner, program_id).0, false),
+    ];
+    accounts.extend(recipient_list_metas(program_id, owner));
+    accounts.extend(second_factor_metas(program_id, owner));
+    accounts.extend(session_key_metas(owner));
+
+    build(
+        program_id,
+        CrossChainInstruction::SignTransaction { signature_data },
+        accounts,
+    )
+}
+
+fn recipient_list_metas(program_id: &Pubkey, owner: &Pubkey) -> [AccountMeta; 2] {
+    [RecipientListKind::Allow, RecipientListKind::Block].map(|kind| {
+        AccountMeta::new_readonly(get_recipient_list_address(owner, kind, program_id).0, false)
+    })
+}
+
+/// The owner's second factor followed by the co-signer slot, which holds the
+/// owner until `with_second_factor` puts the device there.
+fn second_factor_metas(program_id: &Pubkey, owner: &Pubkey) -> [AccountMeta; 2] {
+    [
+        AccountMeta::new_readonly(get_second_factor_address(owner, program_id).0, false),
+        AccountMeta::new_readonly(*owner, false),
+    ]
+}
+
+/// Makes `device` the co-signer of an owner instruction that carries the
+/// second-factor slot; the transaction must then also be signed by it.
+/// Instructions without the slot are returned unchanged.
+pub fn with_second_factor(
+    program_id: &Pubkey,
+    mut instruction: Instruction,
+    owner: &Pubkey,
+    device: &Pubkey,
+) -> Instruction {
+    let second_factor = get_second_factor_address(owner, program_id).0;
+    if let Some(index) = instruction
+        .accounts
+        .iter()
+        .position(|meta| meta.pubkey == second_factor)
+    {
+        if let Some(cosigner) = instruction.accounts.get_mut(index + 1) {
+            *cosigner = AccountMeta::new_readonly(*device, true);
+        }
+    }
+    instruction
+}
+
+/// The session key account and signer, both holding the owner until
+/// `with_session_key` fills them in.
+fn session_key_metas(owner: &Pubkey) -> [AccountMeta; 2] {
+    [
+        AccountMeta::new_readonly(*owner, false),
+        AccountMeta::new_readonly(*owner, false),
+    ]
+}
+
+/// Has `session_key` sign an owner instruction that carries the session
+/// slots in the owner's place: the owner no longer signs, and the session
+/// key pays for anything the instruction creates. Instructions without the
+/// slots are returned unchanged.
+pub fn with_session_key(
+    program_id: &Pubkey,
+    mut instruction: Instruction,
+    owner: &Pubkey,
+    session_key: &Pubkey,
+) -> Instruction {
+    let second_factor = get_second_factor_address(owner, program_id).0;
+    let Some(index) = instruction
+        .accounts
+        .iter()
+        .position(|meta| meta.pubkey == second_factor)
+    else {
+        return instruction;
+    };
+    if instruction.accounts.len() < index + 4 {
+        return instruction;
+    }
+
+    for meta in &mut instruction.accounts {
+        if meta.pubkey == *owner {
+            meta.is_signer = false;
+        }
+    }
+    instruction.accounts[index + 2] = AccountMeta::new_readonly(
+        get_session_key_address(owner, session_key, program_id).0,
+        false,
+    );
+    instruction.accounts[index + 3] = AccountMeta::new(*session_key, true);
+    instruction
+}
+
+/// Delegates `scope` to `session_key`, replacing any scope it already has.
+pub fn issue_session_key_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    session_key: &Pubkey,
+    scope: SessionKeyData,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::IssueSessionKey {
+            session_key: *session_key,
+            scope,
+        },
+        vec![
+            AccountMeta::new_readonly(get_wallet_address(owner, program_id).0, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(
+                get_session_key_address(owner, session_key, program_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn revoke_session_key_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    session_key: &Pubkey,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::RevokeSessionKey {
+            session_key: *session_key,
+        },
+        vec![
+            AccountMeta::new_readonly(get_wallet_address(owner, program_id).0, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(
+                get_session_key_address(owner, session_key, program_id).0,
+                false,
+            ),
+        ],
+    )
+}
+
+/// Registers `device` as the owner's second factor, co-signing any
+/// operation above `threshold` while the wallet config requires 2FA.
+/// Replacing a registered device needs `current_device` to sign as well.
+pub fn register_second_factor_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    device: &Pubkey,
+    current_device: Option<&Pubkey>,
+    threshold: u64,
+) -> std::io::Result<Instruction> {
+    let cosigner = match current_device {
+        Some(current) => AccountMeta::new_readonly(*current, true),
+        None => AccountMeta::new_readonly(*owner, false),
+    };
+    build(
+        program_id,
+        CrossChainInstruction::RegisterSecondFactor { threshold },
+        vec![
+            AccountMeta::new_readonly(get_wallet_address(owner, program_id).0, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(get_second_factor_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(*device, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            cosigner,
+        ],
+    )
+}
+
+/// Proposes `device` as the owner's new second factor without the current
+/// one, or applies the proposal once the recovery delay has passed.
+pub fn recover_second_factor_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    device: &Pubkey,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::RecoverSecondFactor,
+        vec![
+            AccountMeta::new_readonly(get_wallet_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(get_second_factor_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(*device, true),
+        ],
+    )
+}
+
+/// Names the guardians who can recover the owner's wallet once `threshold`
+/// of them agree; an empty list removes them. While 2FA is on, wrap with
+/// `with_second_factor`.
+pub fn set_guardians_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    guardians: Vec<Pubkey>,
+    threshold: u8,
+) -> std::io::Result<Instruction> {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(get_wallet_address(owner, program_id).0, false),
+        AccountMeta::new(*owner, true),
+        AccountMeta::new(get_guardian_set_address(owner, program_id).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(get_wallet_config_address(owner, program_id).0, false),
+    ];
+    accounts.extend(second_factor_metas(program_id, owner));
+
+    build(
+        program_id,
+        CrossChainInstruction::SetGuardians {
+            guardians: guardians.into(),
+            threshold,
+        },
+        accounts,
+    )
+}
+
+/// `guardian` proposes, or approves, moving `owner`'s wallet to `new_owner`.
+pub fn propose_recovery_ix(
+    program_id: &Pubkey,
+    guardian: &Pubkey,
+    owner: &Pubkey,
+    new_owner: &Pubkey,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::ProposeRecovery {
+            new_owner: *new_owner,
+        },
+        vec![
+            AccountMeta::new_readonly(get_wallet_address(owner, program_id).0, false),
+            AccountMeta::new(get_guardian_set_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(*guardian, true),
+        ],
+    )
+}
+
+pub fn veto_recovery_ix(program_id: &Pubkey, owner: &Pubkey) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::VetoRecovery,
+        vec![
+            AccountMeta::new_readonly(get_wallet_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new(get_guardian_set_address(owner, program_id).0, false),
+        ],
+    )
+}
+
+/// Moves `old_owner`'s wallet to `new_owner`, who signs and pays for the
+/// accounts opened under the new key.
+pub fn execute_recovery_ix(
+    program_id: &Pubkey,
+    old_owner: &Pubkey,
+    new_owner: &Pubkey,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::ExecuteRecovery,
+        vec![
+            AccountMeta::new(get_wallet_address(old_owner, program_id).0, false),
+            AccountMeta::new(get_guardian_set_address(old_owner, program_id).0, false),
+            AccountMeta::new_readonly(get_wallet_config_address(old_owner, program_id).0, false),
+            AccountMeta::new_readonly(get_activity_log_address(old_owner, program_id).0, false),
+            AccountMeta::new(
+                get_recipient_list_address(old_owner, RecipientListKind::Allow, program_id).0,
+                false,
+            ),
+            AccountMeta::new(
+                get_recipient_list_address(old_owner, RecipientListKind::Block, program_id).0,
+                false,
+            ),
+            AccountMeta::new(get_second_factor_address(old_owner, program_id).0, false),
+            AccountMeta::new(*new_owner, true),
+            AccountMeta::new(get_wallet_address(new_owner, program_id).0, false),
+            AccountMeta::new(get_activity_log_address(new_owner, program_id).0, false),
+            AccountMeta::new(get_nonce_registry_address(new_owner, program_id).0, false),
+            AccountMeta::new(get_wallet_config_address(new_owner, program_id).0, false),
+            AccountMeta::new(get_guardian_set_address(new_owner, program_id).0, false),
+            AccountMeta::new(
+                get_recipient_list_address(new_owner, RecipientListKind::Allow, program_id).0,
+                false,
+            ),
+            AccountMeta::new(
+                get_recipient_list_address(new_owner, RecipientListKind::Block, program_id).0,
+                false,
+            ),
+            AccountMeta::new(get_second_factor_address(new_owner, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(get_audit_log_address(program_id).0, false),
+        ],
+    )
+}
+
+/// Adds `recipient` to the owner's list of `kind`, or removes it.
+pub fn update_recipient_list_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    kind: RecipientListKind,
+    recipient: Pubkey,
+    add: bool,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        if add {
+            CrossChainInstruction::AddRecipientListEntry { kind, recipient }
+        } else {
+            CrossChainInstruction::RemoveRecipientListEntry { kind, recipient }
+        },
+        vec![
+            AccountMeta::new_readonly(get_wallet_address(owner, program_id).0, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(get_recipient_list_address(owner, kind, program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn verify_compliance_ix(
+    program_id: &Pubkey,
+    verifier: &Pubkey,
+    owner: &Pubkey,
+    compliance_data: ComplianceData,
+) -> std::io::Result<Instruction> {
+    sponsored_verify_compliance_ix(program_id, verifier, owner, verifier, compliance_data)
+}
+
+/// `verify_compliance_ix` with the compliance record paid for by `payer`,
+/// the owner or an approved sponsor.
+pub fn sponsored_verify_compliance_ix(
+    program_id: &Pubkey,
+    verifier: &Pubkey,
+    owner: &Pubkey,
+    payer: &Pubkey,
+    compliance_data: ComplianceData,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::VerifyCompliance { compliance_data },
+        vec![
+            state_meta(program_id, true),
+            AccountMeta::new(get_wallet_address(owner, program_id).0, false),
+            AccountMeta::new(*verifier, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(get_compliance_record_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(get_restricted_jurisdictions_address(program_id).0, false),
+            AccountMeta::new(get_audit_log_address(program_id).0, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(get_sponsor_address(payer, program_id).0, false),
+        ],
+    )
+}
+
+/// Approves `sponsor` to pay for end users' onboarding accounts, up to
+/// `max_sponsored` instructions (zero for no cap), or withdraws it.
+pub fn set_sponsor_ix(
+    program_id: &Pubkey,
+    eidas_authority: &Pubkey,
+    sponsor: &Pubkey,
+    enabled: bool,
+    max_sponsored: u64,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::SetSponsor {
+            sponsor: *sponsor,
+            enabled,
+            max_sponsored,
+        },
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new(get_sponsor_address(sponsor, program_id).0, false),
+            AccountMeta::new(*eidas_authority, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Freezes `owner`'s wallet for `reason`. Signed by the eIDAS authority.
+pub fn freeze_wallet_ix(
+    program_id: &Pubkey,
+    eidas_authority: &Pubkey,
+    owner: &Pubkey,
+    reason: FreezeReason,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::FreezeWallet { reason },
+        freeze_accounts(program_id, eidas_authority, owner),
+    )
+}
+
+pub fn unfreeze_wallet_ix(
+    program_id: &Pubkey,
+    eidas_authority: &Pubkey,
+    owner: &Pubkey,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::UnfreezeWallet,
+        freeze_accounts(program_id, eidas_authority, owner),
+    )
+}
+
+fn freeze_accounts(
+    program_id: &Pubkey,
+    eidas_authority: &Pubkey,
+    owner: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        state_meta(program_id, false),
+        AccountMeta::new(get_wallet_address(owner, program_id).0, false),
+        AccountMeta::new_readonly(*eidas_authority, true),
+        AccountMeta::new(get_audit_log_address(program_id).0, false),
+    ]
+}
+
+pub fn register_chain_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    chain_id: u64,
+    chain_data: ChainConfigData,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::RegisterChain {
+            chain_id,
+            chain_data,
+        },
+        vec![
+            state_meta(program_id, true),
+            AccountMeta::new(get_chain_config_address(chain_id, program_id).0, false),
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn set_axelar_route_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    chain_id: u64,
+    chain_name: &str,
+    gateway_address: &str,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::SetAxelarRoute {
+            chain_id,
+            chain_name: chain_name.into(),
+            gateway_address: gateway_address.into(),
+        },
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new(get_axelar_route_address(chain_id, program_id).0, false),
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn set_chain_pair_policy_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    source_chain: u64,
+    destination_chain: u64,
+    policy: ChainPairPolicyData,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::SetChainPairPolicy {
+            source_chain,
+            destination_chain,
+            policy,
+        },
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new(
+                get_chain_pair_policy_address(source_chain, destination_chain, program_id).0,
+                false,
+            ),
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(get_fee_history_address(program_id).0, false),
+        ],
+    )
+}
+
+pub fn register_wrapped_asset_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    chain_id: u64,
+    remote_token: Pubkey,
+    remote_decimals: u8,
+    local_mint: &Pubkey,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::RegisterWrappedAsset {
+            chain_id,
+            remote_token,
+            remote_decimals,
+        },
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new(get_wrapped_registry_address(program_id).0, false),
+            AccountMeta::new_readonly(*local_mint, false),
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn deregister_wrapped_asset_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    chain_id: u64,
+    remote_token: Pubkey,
+    local_mint: &Pubkey,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::DeregisterWrappedAsset {
+            chain_id,
+            remote_token,
+        },
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new(get_wrapped_registry_address(program_id).0, false),
+            AccountMeta::new_readonly(*local_mint, false),
+            AccountMeta::new_readonly(*admin, true),
+        ],
+    )
+}
+
+pub fn stake_relayer_ix(
+    program_id: &Pubkey,
+    operator: &Pubkey,
+    amount: u64,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::StakeRelayer { amount },
+        vec![
+            state_meta(program_id, true),
+            AccountMeta::new(get_relayer_address(operator, program_id).0, false),
+            AccountMeta::new(*operator, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Native transfers are complete as built. SPL transfers append the sender
+/// token account, mint and token program, followed by the escrow token
+/// account unless the mint is a hub-issued wrapped asset.
+pub fn initiate_cross_chain_ix(
+    program_id: &Pubkey,
+    transfer_data: CrossChainTransferData,
+) -> std::io::Result<Instruction> {
+    let sender = transfer_data.sender;
+    let record = get_transfer_record_address(&sender, transfer_data.nonce, program_id).0;
+    let chain = get_chain_config_address(transfer_data.destination_chain, program_id).0;
+    let policy = get_chain_pair_policy_address(
+        transfer_data.source_chain,
+        transfer_data.destination_chain,
+        program_id,
+    )
+    .0;
+    let mut accounts = vec![
+        state_meta(program_id, true),
+        AccountMeta::new_readonly(get_wallet_address(&sender, program_id).0, false),
+        AccountMeta::new(sender, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(chain, false),
+        AccountMeta::new(record, false),
+        AccountMeta::new(get_activity_log_address(&sender, program_id).0, false),
+        AccountMeta::new(get_nonce_registry_address(&sender, program_id).0, false),
+        AccountMeta::new_readonly(get_compliance_record_address(&sender, program_id).0, false),
+        AccountMeta::new_readonly(get_restricted_jurisdictions_address(program_id).0, false),
+        AccountMeta::new(get_audit_log_address(program_id).0, false),
+        AccountMeta::new_readonly(get_wallet_config_address(&sender, program_id).0, false),
+        AccountMeta::new_readonly(policy, false),
+    ];
+    accounts.extend(recipient_list_metas(program_id, &sender));
+    accounts.extend(second_factor_metas(program_id, &sender));
+    accounts.extend(session_key_metas(&sender));
+
+    build(
+        program_id,
+        CrossChainInstruction::InitiateCrossChain { transfer_data },
+        accounts,
+    )
+}
+
+/// Initiates `transfers` as one batch, seeded by the first entry's sender
+/// and nonce. The program rejects an empty batch.
+pub fn initiate_cross_chain_batch_ix(
+    program_id: &Pubkey,
+    transfers: Vec<CrossChainTransferData>,
+) -> std::io::Result<Instruction> {
+    let (sender, nonce, source_chain, destination_chain) =
+        transfers
+            .first()
+            .map_or((Pubkey::default(), 0, 0, 0), |first| {
+                (
+                    first.sender,
+                    first.nonce,
+                    first.source_chain,
+                    first.destination_chain,
+                )
+            });
+    let batch = get_transfer_batch_address(&sender, nonce, program_id).0;
+    let chain = get_chain_config_address(destination_chain, program_id).0;
+    let policy = get_chain_pair_policy_address(source_chain, destination_chain, program_id).0;
+    let mut accounts = vec![
+        state_meta(program_id, true),
+        AccountMeta::new_readonly(get_wallet_address(&sender, program_id).0, false),
+        AccountMeta::new(sender, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(chain, false),
+        AccountMeta::new(batch, false),
+        AccountMeta::new(get_activity_log_address(&sender, program_id).0, false),
+        AccountMeta::new(get_nonce_registry_address(&sender, program_id).0, false),
+        AccountMeta::new_readonly(get_compliance_record_address(&sender, program_id).0, false),
+        AccountMeta::new_readonly(get_restricted_jurisdictions_address(program_id).0, false),
+        AccountMeta::new(get_audit_log_address(program_id).0, false),
+        AccountMeta::new_readonly(get_wallet_config_address(&sender, program_id).0, false),
+        AccountMeta::new_readonly(policy, false),
+    ];
+    accounts.extend(recipient_list_metas(program_id, &sender));
+    accounts.extend(second_factor_metas(program_id, &sender));
+
+    build(
+        program_id,
+        CrossChainInstruction::InitiateCrossChainBatch {
+            transfers: transfers.into(),
+        },
+        accounts,
+    )
+}
+
+/// Settles `batch` on relayer confirmations of its settlement transfer,
+/// paying each entry's recipient. The ed25519 instructions carrying the
+/// relayer signatures must precede it in the same transaction.
+pub fn complete_cross_chain_batch_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    batch: &TransferBatch,
+    confirmations: Vec<RelayConfirmation>,
+) -> std::io::Result<Instruction> {
+    let mut accounts = vec![
+        state_meta(program_id, true),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(
+            get_transfer_batch_address(&batch.sender, batch.first_nonce, program_id).0,
+            false,
+        ),
+        AccountMeta::new(get_activity_log_address(&batch.sender, program_id).0, false),
+        AccountMeta::new(
+            get_chain_config_address(batch.destination_chain, program_id).0,
+            false,
+        ),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new(get_audit_log_address(program_id).0, false),
+        AccountMeta::new_readonly(
+            get_chain_pair_policy_address(batch.source_chain, batch.destination_chain, program_id)
+                .0,
+            false,
+        ),
+    ];
+    accounts.extend(
+        relay_signers(&confirmations).iter().map(|signer| {
+            AccountMeta::new_readonly(get_relayer_address(signer, program_id).0, false)
+        }),
+    );
+    accounts.push(AccountMeta::new(get_treasury_address(program_id).0, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.extend(
+        batch
+            .entries
+            .iter()
+            .map(|entry| AccountMeta::new(entry.recipient, false)),
+    );
+
+    build(
+        program_id,
+        CrossChainInstruction::CompleteCrossChainBatch {
+            evidence: TransportEvidence::Confirmations(confirmations.into()),
+        },
+        accounts,
+    )
+}
+
+/// Reclaims `sender`'s batch `first_nonce` after it timed out in flight.
+pub fn refund_cross_chain_batch_ix(
+    program_id: &Pubkey,
+    sender: &Pubkey,
+    first_nonce: u64,
+) -> std::io::Result<Instruction> {
+    refund_recovered_batch_ix(program_id, sender, sender, first_nonce)
+}
+
+/// Reclaims a timed-out batch `sender` initiated before its wallet was
+/// recovered to `new_owner`, who signs and is refunded.
+pub fn refund_recovered_batch_ix(
+    program_id: &Pubkey,
+    new_owner: &Pubkey,
+    sender: &Pubkey,
+    first_nonce: u64,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::RefundCrossChainBatch,
+        vec![
+            state_meta(program_id, true),
+            AccountMeta::new(
+                get_transfer_batch_address(sender, first_nonce, program_id).0,
+                false,
+            ),
+            AccountMeta::new(*new_owner, true),
+            AccountMeta::new(get_activity_log_address(sender, program_id).0, false),
+            AccountMeta::new(get_treasury_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(get_audit_log_address(program_id).0, false),
+        ],
+    )
+}
+
+/// Metas shared by both completion paths, up to the evidence accounts.
+/// `hub_chain_id` is the hub's configured chain id, which decides the chain
+/// whose config and evidence confirm the transfer.
+fn completion_accounts(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    hub_chain_id: u64,
+    transfer_data: &CrossChainTransferData,
+) -> Vec<AccountMeta> {
+    let sender = transfer_data.sender;
+    vec![
+        state_meta(program_id, true),
+        AccountMeta::new(transfer_data.recipient, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(
+            get_transfer_record_address(&sender, transfer_data.nonce, program_id).0,
+            false,
+        ),
+        AccountMeta::new(get_activity_log_address(&sender, program_id).0, false),
+        AccountMeta::new(
+            get_chain_config_address(transfer_data.confirming_chain(hub_chain_id), program_id).0,
+            false,
+        ),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new(get_audit_log_address(program_id).0, false),
+        AccountMeta::new_readonly(
+            get_chain_pair_policy_address(
+                transfer_data.source_chain,
+                transfer_data.destination_chain,
+                program_id,
+            )
+            .0,
+            false,
+        ),
+    ]
+}
+
+/// Completes a transfer escrowed on the hub, passing the relayer account of
+/// every confirmation signer. The ed25519 instructions carrying the relayer
+/// signatures must precede it in the same transaction.
+pub fn complete_cross_chain_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    hub_chain_id: u64,
+    transfer_data: CrossChainTransferData,
+    confirmations: Vec<RelayConfirmation>,
+) -> std::io::Result<Instruction> {
+    let mut accounts = completion_accounts(program_id, authority, hub_chain_id, &transfer_data);
+    accounts.extend(
+        relay_signers(&confirmations).iter().map(|signer| {
+            AccountMeta::new_readonly(get_relayer_address(signer, program_id).0, false)
+        }),
+    );
+    accounts.push(AccountMeta::new(get_treasury_address(program_id).0, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+
+    build(
+        program_id,
+        CrossChainInstruction::CompleteCrossChain {
+            transfer_data,
+            confirmations: confirmations.into(),
+        },
+        accounts,
+    )
+}
+
+/// The ed25519 program instruction verifying every relayer signature over
+/// one shared `relay_message_hash` digest, as an aggregate attestation
+/// expects.
+pub fn aggregate_attestation_ix(message: &[u8], signatures: &[(Pubkey, [u8; 64])]) -> Instruction {
+    Instruction::new_with_bytes(
+        ed25519_program::id(),
+        &aggregate_ed25519_instruction_data(message, signatures),
+        vec![],
+    )
+}
+
+/// Completes a transfer on an aggregate attestation, passing the relayer
+/// account of each signer in the order of the named ed25519 instruction.
+/// That instruction, built by `aggregate_attestation_ix`, must precede it in
+/// the same transaction.
+pub fn complete_cross_chain_with_aggregate_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    hub_chain_id: u64,
+    transfer_data: CrossChainTransferData,
+    attestation: AggregateAttestation,
+    signers: &[Pubkey],
+) -> std::io::Result<Instruction> {
+    let mut accounts = completion_accounts(program_id, authority, hub_chain_id, &transfer_data);
+    accounts.extend(
+        signers.iter().map(|signer| {
+            AccountMeta::new_readonly(get_relayer_address(signer, program_id).0, false)
+        }),
+    );
+    accounts.push(AccountMeta::new(get_treasury_address(program_id).0, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+
+    build(
+        program_id,
+        CrossChainInstruction::CompleteCrossChainWithEvidence {
+            transfer_data,
+            evidence: TransportEvidence::AggregateAttestation(attestation),
+        },
+        accounts,
+    )
+}
+
+/// Completes a transfer proven against the state root posted for
+/// `proof.block_number`. No relayer accounts or signatures are needed.
+pub fn complete_cross_chain_with_proof_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    hub_chain_id: u64,
+    transfer_data: CrossChainTransferData,
+    proof: InclusionProof,
+) -> std::io::Result<Instruction> {
+    let mut accounts = completion_accounts(program_id, authority, hub_chain_id, &transfer_data);
+    accounts.push(AccountMeta::new_readonly(
+        get_state_root_address(
+            transfer_data.confirming_chain(hub_chain_id),
+            proof.block_number,
+            program_id,
+        )
+        .0,
+        false,
+    ));
+    accounts.push(AccountMeta::new(get_treasury_address(program_id).0, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+
+    build(
+        program_id,
+        CrossChainInstruction::CompleteCrossChainWithProof {
+            transfer_data,
+            proof,
+        },
+        accounts,
+    )
+}
+
+/// Completes a transfer with a VAA the Wormhole core bridge has verified and
+/// posted at `posted_vaa`.
+pub fn complete_cross_chain_with_vaa_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    hub_chain_id: u64,
+    transfer_data: CrossChainTransferData,
+    posted_vaa: &Pubkey,
+) -> std::io::Result<Instruction> {
+    let mut accounts = completion_accounts(program_id, authority, hub_chain_id, &transfer_data);
+    accounts.push(AccountMeta::new_readonly(*posted_vaa, false));
+    accounts.push(AccountMeta::new(get_treasury_address(program_id).0, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+
+    build(
+        program_id,
+        CrossChainInstruction::CompleteCrossChainWithVaa { transfer_data },
+        accounts,
+    )
+}
+
+/// Completes a transfer with a message the Axelar gateway approved from the
+/// confirming chain, which Axelar knows as `source_chain_name`.
+pub fn complete_cross_chain_with_approval_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    hub_chain_id: u64,
+    gateway: &Pubkey,
+    source_chain_name: &str,
+    transfer_data: CrossChainTransferData,
+    approval: AxelarApproval,
+) -> std::io::Result<Instruction> {
+    let mut accounts = completion_accounts(program_id, authority, hub_chain_id, &transfer_data);
+    accounts.push(AccountMeta::new_readonly(
+        get_axelar_route_address(transfer_data.confirming_chain(hub_chain_id), program_id).0,
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(
+        get_incoming_message_address(
+            &command_id(source_chain_name, &approval.message_id),
+            gateway,
+        ),
+        false,
+    ));
+    accounts.push(AccountMeta::new(get_treasury_address(program_id).0, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+
+    build(
+        program_id,
+        CrossChainInstruction::CompleteCrossChainWithEvidence {
+            transfer_data,
+            evidence: TransportEvidence::GatewayApproval(approval),
+        },
+        accounts,
+    )
+}
+
+/// Accounts `InitiateCrossChain` and `ReleaseHold` take to post a transfer
+/// to the Wormhole core bridge, for chains whose transport is Wormhole.
+pub fn wormhole_post_accounts(
+    program_id: &Pubkey,
+    core_bridge: &Pubkey,
+    sender: &Pubkey,
+    nonce: u64,
+) -> Vec<AccountMeta> {
+    let record = get_transfer_record_address(sender, nonce, program_id).0;
+    let emitter = get_wormhole_emitter_address(program_id).0;
+    vec![
+        AccountMeta::new_readonly(*core_bridge, false),
+        AccountMeta::new(get_core_bridge_address(core_bridge), false),
+        AccountMeta::new(get_wormhole_message_address(&record, program_id).0, false),
+        AccountMeta::new_readonly(emitter, false),
+        AccountMeta::new(get_core_sequence_address(&emitter, core_bridge), false),
+        AccountMeta::new(get_core_fee_collector_address(core_bridge), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ]
+}
+
+/// Accounts `InitiateCrossChain` and `ReleaseHold` take to send a transfer
+/// through the Axelar gateway, for chains whose transport is Axelar.
+pub fn axelar_call_accounts(
+    program_id: &Pubkey,
+    gateway: &Pubkey,
+    destination_chain: u64,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(*gateway, false),
+        AccountMeta::new_readonly(get_gateway_root_address(gateway), false),
+        AccountMeta::new_readonly(*program_id, false),
+        AccountMeta::new_readonly(get_axelar_signing_address(program_id).0, false),
+        AccountMeta::new_readonly(
+            get_axelar_route_address(destination_chain, program_id).0,
+            false,
+        ),
+    ]
+}
+
+/// Posts a source-chain state root. The ed25519 instructions carrying each
+/// signer's signature over `state_root_digest` must precede it.
+pub fn post_state_root_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    chain_id: u64,
+    block_number: u64,
+    root: [u8; 32],
+    signers: Vec<Pubkey>,
+) -> std::io::Result<Instruction> {
+    let mut accounts = vec![
+        state_meta(program_id, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(
+            get_state_root_address(chain_id, block_number, program_id).0,
+            false,
+        ),
+        AccountMeta::new_readonly(get_chain_config_address(chain_id, program_id).0, false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(
+        signers.iter().map(|signer| {
+            AccountMeta::new_readonly(get_relayer_address(signer, program_id).0, false)
+        }),
+    );
+
+    build(
+        program_id,
+        CrossChainInstruction::PostStateRoot {
+            chain_id,
+            block_number,
+            root,
+            signers: signers.into(),
+        },
+        accounts,
+    )
+}
+
+/// Moves a first-release account at `legacy` to its PDA. `payer` is the
+/// hub admin for the state and the owner for a wallet. Pass the PDA itself
+/// as `legacy` to upgrade an older header in place.
+pub fn migrate_account_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    kind: AccountKind,
+    legacy: &Pubkey,
+) -> std::io::Result<Instruction> {
+    let account = match kind {
+        AccountKind::State => get_state_address(program_id).0,
+        AccountKind::Wallet => get_wallet_address(payer, program_id).0,
+    };
+    build(
+        program_id,
+        CrossChainInstruction::MigrateAccount { kind },
+        vec![
+            AccountMeta::new(*legacy, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(account, false),
+        ],
+    )
+}
+
+/// Native transfers are complete as built. SPL transfers append the sender
+/// and recipient token accounts and the token program.
+pub fn transfer_internal_ix(
+    program_id: &Pubkey,
+    sender: &Pubkey,
+    recipient: &Pubkey,
+    amount: u64,
+    mint: Option<Pubkey>,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::TransferInternal { amount, mint },
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new_readonly(get_wallet_address(sender, program_id).0, false),
+            AccountMeta::new(*sender, true),
+            AccountMeta::new_readonly(get_wallet_address(recipient, program_id).0, false),
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new(get_activity_log_address(sender, program_id).0, false),
+            AccountMeta::new(get_activity_log_address(recipient, program_id).0, false),
+            AccountMeta::new_readonly(get_compliance_record_address(sender, program_id).0, false),
+            AccountMeta::new_readonly(
+                get_compliance_record_address(recipient, program_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(get_restricted_jurisdictions_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn get_config_ix(program_id: &Pubkey) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::GetConfig,
+        vec![state_meta(program_id, false)],
+    )
+}
+
+/// The ed25519 instruction carrying the eIDAS authority's signature over the
+/// attestation digest must precede it in the same transaction.
+pub fn export_attestation_ix(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    attestation: SignedComplianceAttestation,
+) -> std::io::Result<Instruction> {
+    let chain = get_chain_config_address(attestation.attestation.destination_chain, program_id).0;
+    build(
+        program_id,
+        CrossChainInstruction::ExportAttestation { attestation },
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new_readonly(get_wallet_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(get_compliance_record_address(owner, program_id).0, false),
+            AccountMeta::new_readonly(chain, false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+    )
+}
+
+/// Sends `sender`'s held transfer `nonce` on to `destination_chain` after
+/// review. Chains not served by relayers append their transport's accounts,
+/// such as `wormhole_post_accounts`.
+pub fn release_hold_ix(
+    program_id: &Pubkey,
+    officer: &Pubkey,
+    sender: &Pubkey,
+    nonce: u64,
+    destination_chain: u64,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::ReleaseHold,
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new(
+                get_transfer_record_address(sender, nonce, program_id).0,
+                false,
+            ),
+            AccountMeta::new(*officer, true),
+            AccountMeta::new(get_audit_log_address(program_id).0, false),
+            AccountMeta::new_readonly(
+                get_chain_config_address(destination_chain, program_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Refunds a held native transfer. SPL transfers append the accounts
+/// `RefundCrossChain` takes for their mint.
+pub fn reject_hold_ix(
+    program_id: &Pubkey,
+    officer: &Pubkey,
+    sender: &Pubkey,
+    nonce: u64,
+) -> std::io::Result<Instruction> {
+    reject_recovered_hold_ix(program_id, officer, sender, sender, nonce)
+}
+
+/// Refunds a held native transfer `sender` initiated before its wallet was
+/// recovered to `new_owner`, who receives the refund.
+pub fn reject_recovered_hold_ix(
+    program_id: &Pubkey,
+    officer: &Pubkey,
+    new_owner: &Pubkey,
+    sender: &Pubkey,
+    nonce: u64,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::RejectHold,
+        vec![
+            AccountMeta::new(*officer, true),
+            state_meta(program_id, true),
+            AccountMeta::new(
+                get_transfer_record_address(sender, nonce, program_id).0,
+                false,
+            ),
+            AccountMeta::new(*new_owner, false),
+            AccountMeta::new(get_activity_log_address(sender, program_id).0, false),
+            AccountMeta::new(get_treasury_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(get_audit_log_address(program_id).0, false),
+        ],
+    )
+}
+
+/// Withdraws the sender's unconfirmed native transfer. SPL transfers append
+/// the accounts `RefundCrossChain` takes for their mint.
+pub fn cancel_cross_chain_ix(
+    program_id: &Pubkey,
+    sender: &Pubkey,
+    nonce: u64,
+    destination_chain: u64,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::CancelCrossChain,
+        vec![
+            AccountMeta::new_readonly(
+                get_chain_config_address(destination_chain, program_id).0,
+                false,
+            ),
+            state_meta(program_id, true),
+            AccountMeta::new(
+                get_transfer_record_address(sender, nonce, program_id).0,
+                false,
+            ),
+            AccountMeta::new(*sender, true),
+            AccountMeta::new(get_activity_log_address(sender, program_id).0, false),
+            AccountMeta::new(get_treasury_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(get_audit_log_address(program_id).0, false),
+        ],
+    )
+}
+
+/// Signed by the admin, or by the guardian when only tightening the limit.
+pub fn set_rate_limit_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    chain_id: u64,
+    max_outflow: u64,
+    window_slots: u64,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::SetRateLimit {
+            max_outflow,
+            window_slots,
+        },
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new(get_chain_config_address(chain_id, program_id).0, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+    )
+}
+
+pub fn configure_governance_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    config: GovernanceConfig,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::ConfigureGovernance { config },
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new(get_governance_address(program_id).0, false),
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// `index` must be the governance account's current `proposal_count`.
+pub fn create_proposal_ix(
+    program_id: &Pubkey,
+    proposer: &Pubkey,
+    proposer_token_account: &Pubkey,
+    index: u64,
+    instruction: ProposedInstruction,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::CreateProposal { instruction },
+        vec![
+            AccountMeta::new(get_governance_address(program_id).0, false),
+            AccountMeta::new(get_proposal_address(index, program_id).0, false),
+            AccountMeta::new(*proposer, true),
+            AccountMeta::new_readonly(*proposer_token_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// `vault` is a token account of the governance mint owned by the governance
+/// authority.
+#[allow(clippy::too_many_arguments)]
+pub fn cast_vote_ix(
+    program_id: &Pubkey,
+    voter: &Pubkey,
+    voter_token_account: &Pubkey,
+    vault: &Pubkey,
+    index: u64,
+    support: bool,
+    weight: u64,
+) -> std::io::Result<Instruction> {
+    let proposal = get_proposal_address(index, program_id).0;
+    build(
+        program_id,
+        CrossChainInstruction::CastVote { support, weight },
+        vec![
+            AccountMeta::new_readonly(get_governance_address(program_id).0, false),
+            AccountMeta::new(proposal, false),
+            AccountMeta::new(
+                get_vote_record_address(&proposal, voter, program_id).0,
+                false,
+            ),
+            AccountMeta::new(*voter, true),
+            AccountMeta::new(*voter_token_account, false),
+            AccountMeta::new(*vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+pub fn withdraw_vote_ix(
+    program_id: &Pubkey,
+    voter: &Pubkey,
+    voter_token_account: &Pubkey,
+    vault: &Pubkey,
+    index: u64,
+) -> std::io::Result<Instruction> {
+    let proposal = get_proposal_address(index, program_id).0;
+    build(
+        program_id,
+        CrossChainInstruction::WithdrawVote,
+        vec![
+            AccountMeta::new_readonly(get_governance_address(program_id).0, false),
+            AccountMeta::new_readonly(proposal, false),
+            AccountMeta::new(
+                get_vote_record_address(&proposal, voter, program_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(*voter, true),
+            AccountMeta::new(*voter_token_account, false),
+            AccountMeta::new(*vault, false),
+            AccountMeta::new_readonly(get_governance_authority_address(program_id).0, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+/// Appends the proposed instruction's program and accounts, with the
+/// governance authority left unsigned since the hub signs for it.
+pub fn execute_proposal_ix(
+    program_id: &Pubkey,
+    index: u64,
+    proposed: &ProposedInstruction,
+) -> std::io::Result<Instruction> {
+    let authority = get_governance_authority_address(program_id).0;
+    let mut accounts = vec![
+        AccountMeta::new_readonly(get_governance_address(program_id).0, false),
+        AccountMeta::new(get_proposal_address(index, program_id).0, false),
+        AccountMeta::new_readonly(authority, false),
+        AccountMeta::new_readonly(proposed.program_id, false),
+    ];
+    accounts.extend(
+        proposed
+            .accounts
+            .iter()
+            .filter(|meta| meta.pubkey != authority)
+            .map(|meta| AccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: false,
+                is_writable: meta.is_writable,
+            }),
+    );
+    build(program_id, CrossChainInstruction::ExecuteProposal, accounts)
+}
+
+/// Claims everything the signer may withdraw in `mint`. SPL claims pass the
+/// treasury's token account for the mint as `treasury_token_account`.
+pub fn claim_fees_ix(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    destination: &Pubkey,
+    mint: Option<Pubkey>,
+    treasury_token_account: Option<&Pubkey>,
+) -> std::io::Result<Instruction> {
+    let mut accounts = vec![
+        state_meta(program_id, true),
+        AccountMeta::new(get_treasury_address(program_id).0, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    if let Some(token_account) = treasury_token_account {
+        accounts.push(AccountMeta::new(*token_account, false));
+        accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    }
+    build(
+        program_id,
+        CrossChainInstruction::ClaimFees { mint },
+        accounts,
+    )
+}
+
+pub fn publish_treasury_report_ix(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::PublishTreasuryReport,
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new(get_treasury_report_address(program_id).0, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    )
+}
+
+/// Reports the volume of each of `chain_ids` alongside the hub totals.
+pub fn get_statistics_ix(program_id: &Pubkey, chain_ids: &[u64]) -> std::io::Result<Instruction> {
+    let mut accounts = vec![state_meta(program_id, false)];
+    accounts.extend(chain_ids.iter().map(|chain_id| {
+        AccountMeta::new_readonly(get_chain_config_address(*chain_id, program_id).0, false)
+    }));
+    build(program_id, CrossChainInstruction::GetStatistics, accounts)
+}
+
+pub fn get_wallet_status_ix(program_id: &Pubkey, owner: &Pubkey) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::GetWalletStatus { owner: *owner },
+        vec![AccountMeta::new_readonly(
+            get_wallet_address(owner, program_id).0,
+            false,
+        )],
+    )
+}
+
+pub fn get_transfer_status_ix(
+    program_id: &Pubkey,
+    sender: &Pubkey,
+    nonce: u64,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::GetTransferStatus {
+            sender: *sender,
+            nonce,
+        },
+        vec![AccountMeta::new_readonly(
+            get_transfer_record_address(sender, nonce, program_id).0,
+            false,
+        )],
+    )
+}
+
+pub fn precheck_recipient_ix(
+    program_id: &Pubkey,
+    sender: &Pubkey,
+    recipient: &Pubkey,
+    destination_chain: u64,
+    amount: u64,
+) -> std::io::Result<Instruction> {
+    build(
+        program_id,
+        CrossChainInstruction::PrecheckRecipient {
+            sender: *sender,
+            recipient: *recipient,
+            destination_chain,
+            amount,
+        },
+        vec![
+            state_meta(program_id, false),
+            AccountMeta::new_readonly(get_wallet_address(sender, program_id).0, false),
+            AccountMeta::new_readonly(
+                get_chain_config_address(destination_chain, program_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(get_compliance_record_address(sender, program_id).0, false),
+            AccountMeta::new_readonly(
+                get_compliance_record_address(recipient, program_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(get_restricted_jurisdictions_address(program_id).0, false),
+            AccountMeta::new_readonly(get_wallet_config_address(sender, program_id).0, false),
+        ],
+    )
+}
+
+#[cfg(feature = "test-fixtures")]
+fn fixture_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    instruction: crate::FixtureInstruction,
+    extra: Vec<AccountMeta>,
+) -> std::io::Result<Instruction> {
+    let mut accounts = vec![state_meta(program_id, true), AccountMeta::new(*admin, true)];
+    accounts.extend(extra);
+    build(
+        program_id,
+        CrossChainInstruction::Fixture { instruction },
+        accounts,
+    )
+}
+
+#[cfg(feature = "test-fixtures")]
+pub fn mint_test_tokens_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> std::io::Result<Instruction> {
+    fixture_ix(
+        program_id,
+        admin,
+        crate::FixtureInstruction::MintTestTokens { amount },
+        vec![
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(
+                crate::bridge::get_mint_authority_address(program_id).0,
+                false,
+            ),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+#[cfg(feature = "test-fixtures")]
+pub fn reset_rate_limit_window_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    chain_id: u64,
+) -> std::io::Result<Instruction> {
+    fixture_ix(
+        program_id,
+        admin,
+        crate::FixtureInstruction::ResetRateLimitWindow,
+        vec![AccountMeta::new(
+            get_chain_config_address(chain_id, program_id).0,
+            false,
+        )],
+    )
+}
+
+#[cfg(feature = "test-fixtures")]
+pub fn force_complete_transfer_ix(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    sender: &Pubkey,
+    nonce: u64,
+    recipient: &Pubkey,
+) -> std::io::Result<Instruction> {
+    fixture_ix(
+        program_id,
+        admin,
+        crate::FixtureInstruction::ForceCompleteTransfer,
+        vec![
+            AccountMeta::new(
+                get_transfer_record_address(sender, nonce, program_id).0,
+                false,
+            ),
+            AccountMeta::new(*recipient, false),
+            AccountMeta::new(get_treasury_address(program_id).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(get_activity_log_address(sender, program_id).0, false),
+        ],
+    )
+}
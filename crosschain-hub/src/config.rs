@@ -0,0 +1,561 @@
+//! Configuration files for the off-chain services. The CLI, relayer and
+//! indexer share one file describing the cluster, the program, the remote
+//! chains and the fee policy; each service reads its own optional section.
+//!
+//! Files are TOML, or JSON when the extension is `.json`. Any value can be
+//! overridden from the environment (see `ServiceConfig::apply_overrides`),
+//! and the result is validated before a service starts.
+
+use crate::client::{ChainFeeCap, FeeBudget, PriorityFeePolicy};
+use crate::wallet::RegistrationPolicy;
+use crate::{CrossChainConfig, MAX_FEE_BASIS_POINTS, MAX_SUPPORTED_CHAINS};
+use serde::{Deserialize, Deserializer};
+use solana_sdk::pubkey::Pubkey;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error;
+
+pub const CONFIG_ENV_PREFIX: &str = "RIVICQ_";
+const DEFAULT_COMMITMENT: &str = "confirmed";
+const DEFAULT_POLL_INTERVAL_MS: u64 = 2_000;
+const DEFAULT_ALERT_THRESHOLD_BPS: u16 = 8_000;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ConfigError {
+    #[error("failed to read config {path}: {reason}")]
+    Io { path: PathBuf, reason: String },
+    #[error("failed to parse config: {0}")]
+    Parse(String),
+    #[error("invalid environment override {var}: {reason}")]
+    InvalidOverride { var: String, reason: String },
+    #[error("invalid config value {field}: {reason}")]
+    Invalid { field: String, reason: String },
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceConfig {
+    pub cluster: ClusterConfig,
+    #[serde(deserialize_with = "deserialize_pubkey")]
+    pub program_id: Pubkey,
+    #[serde(default)]
+    pub chains: Vec<ChainEndpoint>,
+    #[serde(default)]
+    pub fees: FeePolicyConfig,
+    pub cli: Option<CliConfig>,
+    pub relayer: Option<RelayerServiceConfig>,
+    pub indexer: Option<IndexerServiceConfig>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ClusterConfig {
+    pub rpc_url: String,
+    pub ws_url: Option<String>,
+    #[serde(default = "default_commitment")]
+    pub commitment: String,
+}
+
+/// A remote chain the hub bridges to, with the endpoint used to watch it and
+/// the cap on Solana fees spent relaying its transfers.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChainEndpoint {
+    pub chain_id: u64,
+    pub name: String,
+    pub rpc_url: String,
+    #[serde(default)]
+    pub fee_cap_lamports: u64,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// File form of `PriorityFeePolicy` plus the fee budget alert threshold.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FeePolicyConfig {
+    pub percentile: u8,
+    pub min_unit_price: u64,
+    pub max_unit_price: u64,
+    pub compute_unit_margin_bps: u16,
+    pub alert_threshold_bps: u16,
+}
+
+impl Default for FeePolicyConfig {
+    fn default() -> Self {
+        let policy = PriorityFeePolicy::default();
+        Self {
+            percentile: policy.percentile,
+            min_unit_price: policy.min_unit_price,
+            max_unit_price: policy.max_unit_price,
+            compute_unit_margin_bps: policy.compute_unit_margin_bps,
+            alert_threshold_bps: DEFAULT_ALERT_THRESHOLD_BPS,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CliConfig {
+    pub keystore_path: PathBuf,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RelayerServiceConfig {
+    pub keystore_path: PathBuf,
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Leader lease length requested each term; zero runs without a lease.
+    #[serde(default)]
+    pub lease_duration_slots: u64,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct IndexerServiceConfig {
+    pub database_url: String,
+    #[serde(default)]
+    pub start_slot: u64,
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+}
+
+impl ServiceConfig {
+    pub fn from_toml_str(contents: &str) -> Result<Self, ConfigError> {
+        toml::from_str(contents).map_err(|e| ConfigError::Parse(e.to_string()))
+    }
+
+    pub fn from_json_str(contents: &str) -> Result<Self, ConfigError> {
+        serde_json::from_str(contents).map_err(|e| ConfigError::Parse(e.to_string()))
+    }
+
+    /// Applies `RIVICQ_*` overrides from `vars`. Recognised variables:
+    ///
+    /// - `RIVICQ_RPC_URL`, `RIVICQ_WS_URL`, `RIVICQ_COMMITMENT`, `RIVICQ_PROGRAM_ID`
+    /// - `RIVICQ_CHAIN_<id>_RPC_URL`, `RIVICQ_CHAIN_<id>_FEE_CAP`,
+    ///   `RIVICQ_CHAIN_<id>_ENABLED` for a chain already in the file
+    /// - `RIVICQ_FEE_MAX_UNIT_PRICE`, `RIVICQ_FEE_PERCENTILE`
+    /// - `RIVICQ_CLI_KEYSTORE`, `RIVICQ_RELAYER_KEYSTORE`,
+    ///   `RIVICQ_INDEXER_DATABASE_URL` for sections present in the file
+    ///
+    /// Other `RIVICQ_` variables are ignored so secrets such as keystore
+    /// passphrases can share the prefix.
+    pub fn apply_overrides<I>(&mut self, vars: I) -> Result<(), ConfigError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (var, value) in vars {
+            let Some(key) = var.strip_prefix(CONFIG_ENV_PREFIX) else {
+                continue;
+            };
+
+            match key {
+                "RPC_URL" => self.cluster.rpc_url = value,
+                "WS_URL" => self.cluster.ws_url = Some(value),
+                "COMMITMENT" => self.cluster.commitment = value,
+                "PROGRAM_ID" => self.program_id = parse_override(&var, &value)?,
+                "FEE_MAX_UNIT_PRICE" => self.fees.max_unit_price = parse_override(&var, &value)?,
+                "FEE_PERCENTILE" => self.fees.percentile = parse_override(&var, &value)?,
+                "CLI_KEYSTORE" => {
+                    if let Some(cli) = self.cli.as_mut() {
+                        cli.keystore_path = PathBuf::from(value);
+                    }
+                }
+                "RELAYER_KEYSTORE" => {
+                    if let Some(relayer) = self.relayer.as_mut() {
+                        relayer.keystore_path = PathBuf::from(value);
+                    }
+                }
+                "INDEXER_DATABASE_URL" => {
+                    if let Some(indexer) = self.indexer.as_mut() {
+                        indexer.database_url = value;
+                    }
+                }
+                _ => {
+                    if let Some(chain_key) = key.strip_prefix("CHAIN_") {
+                        self.apply_chain_override(&var, chain_key, value)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn apply_chain_override(
+        &mut self,
+        var: &str,
+        chain_key: &str,
+        value: String,
+    ) -> Result<(), ConfigError> {
+        let (id, field) = chain_key
+            .split_once('_')
+            .ok_or_else(|| override_error(var, "expected CHAIN_<id>_<field>"))?;
+        let chain_id: u64 = parse_override(var, id)?;
+        let chain = self
+            .chains
+            .iter_mut()
+            .find(|c| c.chain_id == chain_id)
+            .ok_or_else(|| override_error(var, "chain is not configured"))?;
+
+        match field {
+            "RPC_URL" => chain.rpc_url = value,
+            "FEE_CAP" => chain.fee_cap_lamports = parse_override(var, &value)?,
+            "ENABLED" => chain.enabled = parse_override(var, &value)?,
+            _ => return Err(override_error(var, "unknown chain field")),
+        }
+
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_url(
+            "cluster.rpc_url",
+            &self.cluster.rpc_url,
+            &["http://", "https://"],
+        )?;
+        if let Some(ws_url) = &self.cluster.ws_url {
+            check_url("cluster.ws_url", ws_url, &["ws://", "wss://"])?;
+        }
+
+        if !matches!(
+            self.cluster.commitment.as_str(),
+            "processed" | "confirmed" | "finalized"
+        ) {
+            return Err(invalid(
+                "cluster.commitment",
+                "must be processed, confirmed or finalized",
+            ));
+        }
+
+        for (i, chain) in self.chains.iter().enumerate() {
+            if chain.chain_id == 0 {
+                return Err(invalid("chains.chain_id", "must be non-zero"));
+            }
+
+            if self.chains[..i]
+                .iter()
+                .any(|c| c.chain_id == chain.chain_id)
+            {
+                return Err(invalid(
+                    "chains.chain_id",
+                    &format!("chain {} is listed twice", chain.chain_id),
+                ));
+            }
+
+            check_url("chains.rpc_url", &chain.rpc_url, &["http://", "https://"])?;
+        }
+
+        let fees = &self.fees;
+        if fees.percentile > 100 {
+            return Err(invalid("fees.percentile", "must be at most 100"));
+        }
+        if fees.min_unit_price > fees.max_unit_price {
+            return Err(invalid(
+                "fees.min_unit_price",
+                "must not exceed max_unit_price",
+            ));
+        }
+        if fees.compute_unit_margin_bps > 10_000 || fees.alert_threshold_bps > 10_000 {
+            return Err(invalid("fees", "basis points must be at most 10000"));
+        }
+
+        if let Some(cli) = &self.cli {
+            check_path("cli.keystore_path", &cli.keystore_path)?;
+        }
+
+        if let Some(relayer) = &self.relayer {
+            check_path("relayer.keystore_path", &relayer.keystore_path)?;
+            if relayer.poll_interval_ms == 0 {
+                return Err(invalid("relayer.poll_interval_ms", "must be non-zero"));
+            }
+            if self.chains.iter().all(|c| !c.enabled) {
+                return Err(invalid(
+                    "chains",
+                    "relayer needs at least one enabled chain",
+                ));
+            }
+        }
+
+        if let Some(indexer) = &self.indexer {
+            if indexer.database_url.is_empty() {
+                return Err(invalid("indexer.database_url", "must be set"));
+            }
+            if indexer.poll_interval_ms == 0 {
+                return Err(invalid("indexer.poll_interval_ms", "must be non-zero"));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn priority_fee_policy(&self) -> PriorityFeePolicy {
+        PriorityFeePolicy {
+            percentile: self.fees.percentile,
+            min_unit_price: self.fees.min_unit_price,
+            max_unit_price: self.fees.max_unit_price,
+            compute_unit_margin_bps: self.fees.compute_unit_margin_bps,
+        }
+    }
+
+    /// Fee budget with a cap for each enabled chain, starting from zero spend.
+    pub fn fee_budget(&self) -> FeeBudget {
+        FeeBudget {
+            chains: self
+                .chains
+                .iter()
+                .filter(|c| c.enabled)
+                .map(|c| ChainFeeCap {
+                    chain_id: c.chain_id,
+                    cap_lamports: c.fee_cap_lamports,
+                    spent_lamports: 0,
+                })
+                .collect(),
+            alert_threshold_bps: self.fees.alert_threshold_bps,
+        }
+    }
+
+    pub fn chain(&self, chain_id: u64) -> Option<&ChainEndpoint> {
+        self.chains.iter().find(|c| c.chain_id == chain_id)
+    }
+}
+
+/// Fluent construction of the hub's on-chain `CrossChainConfig` for the CLI
+/// and client. `build` names the offending field, so a bad config is caught
+/// here instead of surfacing as `InvalidConfig` from `Initialize` or
+/// `UpdateConfig`.
+#[derive(Debug, Clone, Default)]
+pub struct CrossChainConfigBuilder {
+    config: CrossChainConfig,
+}
+
+impl CrossChainConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from an existing config, e.g. the one read back from the state
+    /// account before preparing an `UpdateConfig`.
+    pub fn from_config(config: CrossChainConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn admin(mut self, admin: Pubkey) -> Self {
+        self.config.admin = admin;
+        self
+    }
+
+    pub fn bridge_authority(mut self, authority: Pubkey) -> Self {
+        self.config.bridge_authority = authority;
+        self
+    }
+
+    pub fn eidas_authority(mut self, authority: Pubkey) -> Self {
+        self.config.eidas_authority = authority;
+        self
+    }
+
+    pub fn guardian(mut self, guardian: Pubkey) -> Self {
+        self.config.guardian = guardian;
+        self
+    }
+
+    pub fn arcium_program(mut self, program: Pubkey) -> Self {
+        self.config.arcium_program = program;
+        self
+    }
+
+    /// Replaces the supported chain list.
+    pub fn supported_chains(mut self, chains: impl IntoIterator<Item = u64>) -> Self {
+        self.config.supported_chains = chains.into_iter().collect();
+        self
+    }
+
+    pub fn amount_limits(mut self, min: u64, max: u64) -> Self {
+        self.config.min_cross_chain_amount = min;
+        self.config.max_cross_chain_amount = max;
+        self
+    }
+
+    pub fn fee_basis_points(mut self, fee_basis_points: u16) -> Self {
+        self.config.fee_basis_points = fee_basis_points;
+        self
+    }
+
+    pub fn paused(mut self, paused: bool) -> Self {
+        self.config.paused = paused;
+        self
+    }
+
+    pub fn genesis_hash(mut self, genesis_hash: [u8; 32]) -> Self {
+        self.config.genesis_hash = genesis_hash;
+        self
+    }
+
+    pub fn require_top_level(mut self, require_top_level: bool) -> Self {
+        self.config.require_top_level = require_top_level;
+        self
+    }
+
+    pub fn registration(mut self, registration: RegistrationPolicy) -> Self {
+        self.config.registration = registration;
+        self
+    }
+
+    pub fn config_timelock_slots(mut self, slots: u64) -> Self {
+        self.config.config_timelock_slots = slots;
+        self
+    }
+
+    pub fn max_pending_transfers(mut self, max_pending_transfers: u16) -> Self {
+        self.config.max_pending_transfers = max_pending_transfers;
+        self
+    }
+
+    pub fn hub_chain_id(mut self, hub_chain_id: u64) -> Self {
+        self.config.hub_chain_id = hub_chain_id;
+        self
+    }
+
+    /// Checks the config against the program's own `validate` bounds plus
+    /// the sanity checks only a client can make: no unset authority and no
+    /// zero or repeated chain id.
+    pub fn build(self) -> Result<CrossChainConfig, ConfigError> {
+        let config = self.config;
+
+        if config.fee_basis_points > MAX_FEE_BASIS_POINTS {
+            return Err(invalid(
+                "fee_basis_points",
+                &format!("must be at most {MAX_FEE_BASIS_POINTS}"),
+            ));
+        }
+
+        if config.min_cross_chain_amount == 0 {
+            return Err(invalid("min_cross_chain_amount", "must be non-zero"));
+        }
+        if config.min_cross_chain_amount >= config.max_cross_chain_amount {
+            return Err(invalid(
+                "min_cross_chain_amount",
+                "must be below max_cross_chain_amount",
+            ));
+        }
+
+        if config.supported_chains.is_empty() {
+            return Err(invalid("supported_chains", "must list at least one chain"));
+        }
+        if config.supported_chains.len() > MAX_SUPPORTED_CHAINS {
+            return Err(invalid(
+                "supported_chains",
+                &format!("must list at most {MAX_SUPPORTED_CHAINS} chains"),
+            ));
+        }
+        for (i, chain_id) in config.supported_chains.iter().enumerate() {
+            if *chain_id == 0 {
+                return Err(invalid("supported_chains", "chain id must be non-zero"));
+            }
+            if config.supported_chains[..i].contains(chain_id) {
+                return Err(invalid(
+                    "supported_chains",
+                    &format!("duplicate chain id {chain_id}"),
+                ));
+            }
+        }
+
+        for (field, key) in [
+            ("admin", &config.admin),
+            ("bridge_authority", &config.bridge_authority),
+            ("eidas_authority", &config.eidas_authority),
+            ("guardian", &config.guardian),
+        ] {
+            if *key == Pubkey::default() {
+                return Err(invalid(field, "must be set"));
+            }
+        }
+
+        config
+            .validate()
+            .map_err(|_| invalid("min_cross_chain_amount", "too low to charge any fee"))?;
+        Ok(config)
+    }
+}
+
+/// Reads `path`, applies overrides from the process environment and
+/// validates the result.
+pub fn load_config(path: &Path) -> Result<ServiceConfig, ConfigError> {
+    let contents = std::fs::read_to_string(path).map_err(|e| ConfigError::Io {
+        path: path.to_path_buf(),
+        reason: e.to_string(),
+    })?;
+
+    let mut config = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => ServiceConfig::from_json_str(&contents)?,
+        _ => ServiceConfig::from_toml_str(&contents)?,
+    };
+
+    config.apply_overrides(std::env::vars())?;
+    config.validate()?;
+    Ok(config)
+}
+
+fn deserialize_pubkey<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    Pubkey::from_str(&value).map_err(serde::de::Error::custom)
+}
+
+fn parse_override<T: FromStr>(var: &str, value: &str) -> Result<T, ConfigError>
+where
+    T::Err: std::fmt::Display,
+{
+    value
+        .parse()
+        .map_err(|e: T::Err| override_error(var, &e.to_string()))
+}
+
+fn override_error(var: &str, reason: &str) -> ConfigError {
+    ConfigError::InvalidOverride {
+        var: var.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn invalid(field: &str, reason: &str) -> ConfigError {
+    ConfigError::Invalid {
+        field: field.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn check_url(field: &str, url: &str, schemes: &[&str]) -> Result<(), ConfigError> {
+    let valid = schemes
+        .iter()
+        .any(|scheme| url.len() > scheme.len() && url.starts_with(scheme));
+    if !valid {
+        return Err(invalid(
+            field,
+            &format!("must start with {}", schemes.join(" or ")),
+        ));
+    }
+
+    Ok(())
+}
+
+fn check_path(field: &str, path: &Path) -> Result<(), ConfigError> {
+    if path.as_os_str().is_empty() {
+        return Err(invalid(field, "must be set"));
+    }
+
+    Ok(())
+}
+
+fn default_commitment() -> String {
+    DEFAULT_COMMITMENT.to_string()
+}
+
+fn default_poll_interval_ms() -> u64 {
+    DEFAULT_POLL_INTERVAL_MS
+}
+
+fn default_true() -> bool {
+    true
+}
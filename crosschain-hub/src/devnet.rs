@@ -0,0 +1,821 @@
+//! Local development harness for integrators: a `solana-test-validator` with
+//! the hub loaded, a mock EVM node answering the anvil subset of JSON-RPC the
+//! relayer uses, and a driver that runs initiate → relay → complete flows
+//! end to end. Used by the `devnet-sim` binary; nothing here is needed by the
+//! program or the production client.
+
+use crate::bridge::{
+    get_transfer_record_address, ChainConfigData, CrossChainTransferData, RelayConfirmation,
+    TransferRecord, TransferStatus,
+};
+use crate::client::instructions::{
+    complete_cross_chain_ix, initialize_ix, initiate_cross_chain_ix, register_chain_ix,
+    register_wallet_ix, stake_relayer_ix,
+};
+use crate::client::{
+    build_versioned_transaction, send_with_preflight, ClientError, RpcTransport, SimulationResult,
+};
+use crate::config::{ConfigError, CrossChainConfigBuilder};
+use crate::domain::SigningDomain;
+use crate::eidas::EidasLevel;
+use crate::relayer::relay_message_hash;
+use crate::wallet::WalletData;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+use solana_sdk::{
+    ed25519_instruction::new_ed25519_instruction,
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{keypair::Keypair, Signer},
+    transaction::{TransactionError, VersionedTransaction},
+};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Chain id anvil reports by default; the simulator registers it on the hub.
+pub const MOCK_EVM_CHAIN_ID: u64 = 31337;
+/// Chain id the simulator configures the hub with; outbound transfers name
+/// it as their source.
+pub const DEVNET_HUB_CHAIN_ID: u64 = 900;
+pub const DEFAULT_VALIDATOR_RPC_PORT: u16 = 8899;
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const RELAYER_STAKE: u64 = 1_000_000_000;
+
+#[derive(Error, Debug)]
+pub enum DevnetError {
+    #[error("i/o error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("malformed http exchange: {0}")]
+    Http(String),
+    #[error("json-rpc error: {0}")]
+    Rpc(String),
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("transaction {0} failed: {1}")]
+    TransactionFailed(Signature, String),
+    #[error("timed out waiting for {0}")]
+    Timeout(String),
+    #[error("unexpected on-chain state: {0}")]
+    State(String),
+}
+
+/// Posts a JSON body over plain HTTP/1.1 and returns the decoded response.
+/// Only `http://host:port[/path]` URLs are supported; both local endpoints
+/// the harness talks to are unencrypted.
+pub fn http_post_json(url: &str, body: &Value) -> Result<Value, DevnetError> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| DevnetError::Http(format!("unsupported url {url}")))?;
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+
+    let payload = body.to_string();
+    let mut stream = TcpStream::connect(host)?;
+    stream.set_read_timeout(Some(CONFIRM_TIMEOUT))?;
+    write!(
+        stream,
+        "POST {path} HTTP/1.1\r\nHost: {host}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{payload}",
+        payload.len()
+    )?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let split = find_header_end(&response)
+        .ok_or_else(|| DevnetError::Http("response has no header terminator".to_string()))?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    if !head.starts_with("HTTP/1.1 200") && !head.starts_with("HTTP/1.0 200") {
+        let status = head.lines().next().unwrap_or_default().to_string();
+        return Err(DevnetError::Http(status));
+    }
+
+    serde_json::from_slice(&response[split + 4..]).map_err(|e| DevnetError::Http(e.to_string()))
+}
+
+/// Calls `method` on a JSON-RPC 2.0 endpoint and returns its `result`.
+pub fn json_rpc(url: &str, method: &str, params: Value) -> Result<Value, DevnetError> {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let mut response = http_post_json(url, &request)?;
+
+    if let Some(error) = response.get("error") {
+        let message = error["message"].as_str().unwrap_or("unknown error");
+        return Err(DevnetError::Rpc(format!("{method}: {message}")));
+    }
+
+    Ok(response["result"].take())
+}
+
+fn find_header_end(data: &[u8]) -> Option<usize> {
+    data.windows(4).position(|w| w == b"\r\n\r\n")
+}
+
+fn hex_quantity(value: u64) -> String {
+    format!("0x{value:x}")
+}
+
+fn parse_quantity(value: &Value) -> Option<u64> {
+    match value.as_str()? {
+        "latest" | "pending" => Some(u64::MAX),
+        "earliest" => Some(0),
+        s => u64::from_str_radix(s.trim_start_matches("0x"), 16).ok(),
+    }
+}
+
+/// A transaction accepted by the mock EVM node. Every transaction is mined
+/// into its own block and emits one log carrying the raw payload, which is
+/// how the mock stands in for the destination gateway's delivery event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockEvmTransaction {
+    pub hash: [u8; 32],
+    pub data: Vec<u8>,
+    pub block_number: u64,
+}
+
+impl MockEvmTransaction {
+    fn receipt(&self) -> Value {
+        json!({
+            "transactionHash": format!("0x{}", hex::encode(self.hash)),
+            "blockNumber": hex_quantity(self.block_number),
+            "status": "0x1",
+            "logs": [self.log()],
+        })
+    }
+
+    fn log(&self) -> Value {
+        json!({
+            "transactionHash": format!("0x{}", hex::encode(self.hash)),
+            "blockNumber": hex_quantity(self.block_number),
+            "logIndex": "0x0",
+            "data": format!("0x{}", hex::encode(&self.data)),
+            "topics": [],
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockEvmState {
+    chain_id: u64,
+    block_number: u64,
+    transactions: HashMap<[u8; 32], MockEvmTransaction>,
+}
+
+impl MockEvmState {
+    fn handle(&mut self, method: &str, params: &Value) -> Result<Value, String> {
+        match method {
+            "eth_chainId" => Ok(json!(hex_quantity(self.chain_id))),
+            "net_version" => Ok(json!(self.chain_id.to_string())),
+            "eth_blockNumber" => Ok(json!(hex_quantity(self.block_number))),
+            "evm_mine" => {
+                self.block_number += 1;
+                Ok(json!("0x0"))
+            }
+            "eth_sendRawTransaction" => {
+                let raw = params[0]
+                    .as_str()
+                    .and_then(|s| hex::decode(s.trim_start_matches("0x")).ok())
+                    .ok_or("invalid raw transaction")?;
+                Ok(json!(format!("0x{}", hex::encode(self.mine(raw).hash))))
+            }
+            "eth_getTransactionReceipt" => {
+                let receipt = params[0]
+                    .as_str()
+                    .and_then(|s| hex::decode(s.trim_start_matches("0x")).ok())
+                    .and_then(|h| <[u8; 32]>::try_from(h).ok())
+                    .and_then(|h| self.transactions.get(&h))
+                    .map(MockEvmTransaction::receipt);
+                Ok(receipt.unwrap_or(Value::Null))
+            }
+            "eth_getLogs" => {
+                let filter = &params[0];
+                let from = parse_quantity(&filter["fromBlock"]).unwrap_or(0);
+                let to = parse_quantity(&filter["toBlock"]).unwrap_or(u64::MAX);
+                let mut mined: Vec<_> = self
+                    .transactions
+                    .values()
+                    .filter(|tx| tx.block_number >= from && tx.block_number <= to)
+                    .collect();
+                mined.sort_by_key(|tx| tx.block_number);
+                Ok(Value::Array(mined.iter().map(|tx| tx.log()).collect()))
+            }
+            _ => Err(format!("method {method} not supported")),
+        }
+    }
+
+    fn mine(&mut self, data: Vec<u8>) -> MockEvmTransaction {
+        self.block_number += 1;
+        let tx = MockEvmTransaction {
+            hash: Keccak256::digest(&data).into(),
+            data,
+            block_number: self.block_number,
+        };
+        self.transactions.insert(tx.hash, tx.clone());
+        tx
+    }
+}
+
+/// Anvil-style JSON-RPC stub standing in for an EVM counterpart chain.
+/// Transactions are auto-mined on receipt; the node shuts down on drop.
+pub struct MockEvmNode {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockEvmState>>,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockEvmNode {
+    /// Starts the node on an ephemeral localhost port.
+    pub fn start(chain_id: u64) -> Result<Self, DevnetError> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(MockEvmState {
+            chain_id,
+            ..Default::default()
+        }));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let state = Arc::clone(&state);
+            let shutdown = Arc::clone(&shutdown);
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        // A broken client connection must not take the node down.
+                        let _ = serve_connection(stream, &state);
+                    }
+                }
+            })
+        };
+
+        Ok(Self {
+            addr,
+            state,
+            shutdown,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn block_number(&self) -> u64 {
+        self.lock().block_number
+    }
+
+    pub fn transaction(&self, hash: &[u8; 32]) -> Option<MockEvmTransaction> {
+        self.lock().transactions.get(hash).cloned()
+    }
+
+    /// Mines `data` directly, as if a contract on the EVM side had emitted a
+    /// deposit event, for driving inbound flows.
+    pub fn emit(&self, data: Vec<u8>) -> MockEvmTransaction {
+        self.lock().mine(data)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockEvmState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for MockEvmNode {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it observes the flag.
+        let _ = TcpStream::connect(self.addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn serve_connection(mut stream: TcpStream, state: &Mutex<MockEvmState>) -> Result<(), DevnetError> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut request = Vec::new();
+    let mut buf = [0u8; 4096];
+    let split = loop {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            return Err(DevnetError::Http(
+                "connection closed mid-request".to_string(),
+            ));
+        }
+        request.extend_from_slice(&buf[..n]);
+        if let Some(split) = find_header_end(&request) {
+            break split;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&request[..split]).to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    while request.len() < split + 4 + content_length {
+        let n = stream.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let body = request
+        .get(split + 4..split + 4 + content_length)
+        .ok_or_else(|| DevnetError::Http("truncated request body".to_string()))?;
+    let response = match serde_json::from_slice::<Value>(body) {
+        Ok(call) => {
+            let method = call["method"].as_str().unwrap_or_default();
+            let result = state
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .handle(method, &call["params"]);
+            match result {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": call["id"], "result": result }),
+                Err(message) => json!({
+                    "jsonrpc": "2.0",
+                    "id": call["id"],
+                    "error": { "code": -32601, "message": message },
+                }),
+            }
+        }
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": -32700, "message": e.to_string() },
+        }),
+    };
+
+    let payload = response.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{payload}",
+        payload.len()
+    )?;
+    Ok(())
+}
+
+/// Blocking Solana JSON-RPC client over the harness's HTTP helper.
+pub struct LocalRpc {
+    pub url: String,
+}
+
+impl LocalRpc {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+
+    pub fn call(&self, method: &str, params: Value) -> Result<Value, DevnetError> {
+        json_rpc(&self.url, method, params)
+    }
+
+    pub fn latest_blockhash(&self) -> Result<Hash, DevnetError> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        result["value"]["blockhash"]
+            .as_str()
+            .and_then(|s| Hash::from_str(s).ok())
+            .ok_or_else(|| DevnetError::Rpc("getLatestBlockhash: malformed result".to_string()))
+    }
+
+    pub fn genesis_hash(&self) -> Result<Hash, DevnetError> {
+        self.call("getGenesisHash", json!([]))?
+            .as_str()
+            .and_then(|s| Hash::from_str(s).ok())
+            .ok_or_else(|| DevnetError::Rpc("getGenesisHash: malformed result".to_string()))
+    }
+
+    pub fn balance(&self, address: &Pubkey) -> Result<u64, DevnetError> {
+        let result = self.call(
+            "getBalance",
+            json!([address.to_string(), { "commitment": "confirmed" }]),
+        )?;
+        result["value"]
+            .as_u64()
+            .ok_or_else(|| DevnetError::Rpc("getBalance: malformed result".to_string()))
+    }
+
+    pub fn airdrop(&self, to: &Pubkey, lamports: u64) -> Result<(), DevnetError> {
+        let signature = self
+            .call("requestAirdrop", json!([to.to_string(), lamports]))?
+            .as_str()
+            .and_then(|s| Signature::from_str(s).ok())
+            .ok_or_else(|| DevnetError::Rpc("requestAirdrop: malformed result".to_string()))?;
+        self.confirm(&signature)
+    }
+
+    /// Polls until `signature` reaches confirmed commitment.
+    pub fn confirm(&self, signature: &Signature) -> Result<(), DevnetError> {
+        let deadline = Instant::now() + CONFIRM_TIMEOUT;
+        while Instant::now() < deadline {
+            let result = self.call("getSignatureStatuses", json!([[signature.to_string()]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(DevnetError::TransactionFailed(
+                        *signature,
+                        status["err"].to_string(),
+                    ));
+                }
+                if matches!(
+                    status["confirmationStatus"].as_str(),
+                    Some("confirmed") | Some("finalized")
+                ) {
+                    return Ok(());
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        Err(DevnetError::Timeout(format!("signature {signature}")))
+    }
+
+    /// Simulates, sends and confirms `instructions` paid for by the first signer.
+    pub fn submit(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Signature, DevnetError> {
+        let payer = signers
+            .first()
+            .ok_or_else(|| DevnetError::State("no fee payer".to_string()))?;
+        let tx = build_versioned_transaction(
+            &payer.pubkey(),
+            instructions,
+            &[],
+            self.latest_blockhash()?,
+            signers,
+        )?;
+        let signature = send_with_preflight(self, &tx)?;
+        self.confirm(&signature)?;
+        Ok(signature)
+    }
+
+    fn encode_transaction(tx: &VersionedTransaction) -> Result<String, ClientError> {
+        bincode::serialize(tx)
+            .map(|bytes| BASE64.encode(bytes))
+            .map_err(|e| ClientError::Transport(e.to_string()))
+    }
+}
+
+impl RpcTransport for LocalRpc {
+    fn simulate(&self, tx: &VersionedTransaction) -> Result<SimulationResult, ClientError> {
+        let encoded = Self::encode_transaction(tx)?;
+        let result = self
+            .call(
+                "simulateTransaction",
+                json!([encoded, { "encoding": "base64", "commitment": "confirmed" }]),
+            )
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        let value = &result["value"];
+
+        let err = match &value["err"] {
+            Value::Null => None,
+            err => Some(
+                serde_json::from_value::<TransactionError>(err.clone())
+                    .map_err(|e| ClientError::Transport(e.to_string()))?,
+            ),
+        };
+        let logs = value["logs"]
+            .as_array()
+            .map(|logs| {
+                logs.iter()
+                    .filter_map(|l| l.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(SimulationResult {
+            err,
+            logs,
+            units_consumed: value["unitsConsumed"].as_u64(),
+        })
+    }
+
+    fn send(&self, tx: &VersionedTransaction) -> Result<Signature, ClientError> {
+        let encoded = Self::encode_transaction(tx)?;
+        self.call(
+            "sendTransaction",
+            json!([encoded, { "encoding": "base64", "skipPreflight": true }]),
+        )
+        .map_err(|e| ClientError::Transport(e.to_string()))?
+        .as_str()
+        .and_then(|s| Signature::from_str(s).ok())
+        .ok_or_else(|| ClientError::Transport("sendTransaction: malformed result".to_string()))
+    }
+
+    fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>, ClientError> {
+        let result = self
+            .call(
+                "getAccountInfo",
+                json!([address.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+            )
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+
+        match &result["value"] {
+            Value::Null => Ok(None),
+            value => value["data"][0]
+                .as_str()
+                .and_then(|data| BASE64.decode(data).ok())
+                .map(Some)
+                .ok_or(ClientError::InvalidAccount(*address)),
+        }
+    }
+}
+
+/// A `solana-test-validator` child process with the hub program preloaded.
+/// The validator and its ledger directory are torn down on drop.
+pub struct TestValidator {
+    child: Child,
+    ledger: PathBuf,
+    pub rpc: LocalRpc,
+}
+
+impl TestValidator {
+    /// Launches the validator (which must be on `PATH`) and waits for it to
+    /// report healthy.
+    pub fn start(
+        program_id: &Pubkey,
+        program_so: &Path,
+        rpc_port: u16,
+    ) -> Result<Self, DevnetError> {
+        let ledger = std::env::temp_dir().join(format!("rivicq-devnet-{}", std::process::id()));
+        let child = Command::new("solana-test-validator")
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(&ledger)
+            .arg("--rpc-port")
+            .arg(rpc_port.to_string())
+            .arg("--bpf-program")
+            .arg(program_id.to_string())
+            .arg(program_so)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let validator = Self {
+            child,
+            ledger,
+            rpc: LocalRpc::new(format!("http://127.0.0.1:{rpc_port}")),
+        };
+
+        let deadline = Instant::now() + STARTUP_TIMEOUT;
+        while Instant::now() < deadline {
+            if let Ok(health) = validator.rpc.call("getHealth", json!([])) {
+                if health == "ok" {
+                    return Ok(validator);
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        Err(DevnetError::Timeout(
+            "solana-test-validator to start".to_string(),
+        ))
+    }
+}
+
+impl Drop for TestValidator {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.ledger);
+    }
+}
+
+/// Signatures and hashes produced by one simulated round trip.
+#[derive(Debug, Clone)]
+pub struct FlowReport {
+    pub record: Pubkey,
+    pub initiate: Signature,
+    pub evm_tx_hash: [u8; 32],
+    pub evm_block: u64,
+    pub complete: Signature,
+}
+
+/// Drives the hub against a local validator and a mock EVM node. One key
+/// plays admin, bridge authority, eIDAS authority and relayer so a single
+/// airdrop bootstraps the whole deployment.
+pub struct DevnetSim<'a> {
+    pub rpc: &'a LocalRpc,
+    pub evm: &'a MockEvmNode,
+    pub program_id: Pubkey,
+    pub operator: Keypair,
+}
+
+impl<'a> DevnetSim<'a> {
+    pub fn new(rpc: &'a LocalRpc, evm: &'a MockEvmNode, program_id: Pubkey) -> Self {
+        Self {
+            rpc,
+            evm,
+            program_id,
+            operator: Keypair::new(),
+        }
+    }
+
+    /// Initializes the hub, registers the mock EVM chain for both directions
+    /// and bonds the operator as the single relayer.
+    pub fn bootstrap(&self) -> Result<(), DevnetError> {
+        let operator = self.operator.pubkey();
+        self.rpc.airdrop(&operator, 10 * RELAYER_STAKE)?;
+
+        let config = CrossChainConfigBuilder::new()
+            .admin(operator)
+            .bridge_authority(operator)
+            .eidas_authority(operator)
+            .guardian(operator)
+            .supported_chains([MOCK_EVM_CHAIN_ID])
+            .genesis_hash(self.rpc.genesis_hash()?.to_bytes())
+            .hub_chain_id(DEVNET_HUB_CHAIN_ID)
+            .build()?;
+        self.rpc.submit(
+            &[initialize_ix(&self.program_id, &operator, config)?],
+            &[&self.operator],
+        )?;
+
+        let chain_data = ChainConfigData {
+            enabled: true,
+            min_eidas_level: EidasLevel::None,
+            inbound_enabled: true,
+            encryption_key: [0u8; 32],
+            gateway_codehash: [0u8; 32],
+            wormhole_emitter: Default::default(),
+            transport: Default::default(),
+        };
+        let register_chain =
+            register_chain_ix(&self.program_id, &operator, MOCK_EVM_CHAIN_ID, chain_data)?;
+        let stake = stake_relayer_ix(&self.program_id, &operator, RELAYER_STAKE)?;
+        self.rpc
+            .submit(&[register_chain, stake], &[&self.operator])?;
+        Ok(())
+    }
+
+    /// Funds `owner` and registers its wallet.
+    pub fn onboard(&self, owner: &Keypair) -> Result<(), DevnetError> {
+        let pubkey = owner.pubkey();
+        self.rpc.airdrop(&pubkey, 2 * RELAYER_STAKE)?;
+
+        let register = register_wallet_ix(
+            &self.program_id,
+            &pubkey,
+            WalletData {
+                public_key: pubkey.to_bytes().to_vec().into(),
+                metadata: b"devnet-sim".to_vec().into(),
+                eidas_level: EidasLevel::None,
+            },
+        )?;
+        self.rpc.submit(&[register], &[owner])?;
+        Ok(())
+    }
+
+    /// Runs one native transfer from `sender` to `recipient` through the
+    /// mock EVM chain: initiate on the hub, deliver the bridge message to the
+    /// EVM node, then complete on the hub with the relayer's signed
+    /// confirmation of the EVM receipt.
+    pub fn run_transfer(
+        &self,
+        sender: &Keypair,
+        recipient: &Pubkey,
+        amount: u64,
+        nonce: u64,
+    ) -> Result<FlowReport, DevnetError> {
+        let (record, initiate) = self.initiate(sender, recipient, amount, nonce)?;
+        let (evm_tx_hash, evm_block) = self.relay(&record)?;
+        let complete = self.complete(&record, evm_tx_hash, evm_block)?;
+
+        Ok(FlowReport {
+            record,
+            initiate,
+            evm_tx_hash,
+            evm_block,
+            complete,
+        })
+    }
+
+    fn initiate(
+        &self,
+        sender: &Keypair,
+        recipient: &Pubkey,
+        amount: u64,
+        nonce: u64,
+    ) -> Result<(Pubkey, Signature), DevnetError> {
+        let owner = sender.pubkey();
+        let (record, _) = get_transfer_record_address(&owner, nonce, &self.program_id);
+        let transfer_data = CrossChainTransferData {
+            sender: owner,
+            recipient: *recipient,
+            amount,
+            destination_chain: MOCK_EVM_CHAIN_ID,
+            source_chain: DEVNET_HUB_CHAIN_ID,
+            token_address: None,
+            fee: 0,
+            nonce,
+            timestamp: 0,
+            status: TransferStatus::Initiated,
+            travel_rule: None,
+        };
+
+        let initiate = initiate_cross_chain_ix(&self.program_id, transfer_data)?;
+        let signature = self.rpc.submit(&[initiate], &[sender])?;
+        Ok((record, signature))
+    }
+
+    /// Delivers the record's bridge message to the mock EVM node and waits
+    /// for its receipt, the way a relayer would on a real counterpart chain.
+    fn relay(&self, record: &Pubkey) -> Result<([u8; 32], u64), DevnetError> {
+        let record = self.load_record(record)?;
+        let message = record.bridge_message(format!(
+            "{}-{}",
+            record.transfer.sender, record.transfer.nonce
+        ));
+        let raw = format!("0x{}", hex::encode(message.try_to_vec()?));
+
+        let evm_url = self.evm.url();
+        let hash = json_rpc(&evm_url, "eth_sendRawTransaction", json!([raw]))?;
+        let receipt = json_rpc(&evm_url, "eth_getTransactionReceipt", json!([hash]))?;
+        if receipt["status"] != "0x1" {
+            return Err(DevnetError::State(format!(
+                "evm transaction {hash} reverted"
+            )));
+        }
+
+        let tx_hash = hash
+            .as_str()
+            .and_then(|s| hex::decode(s.trim_start_matches("0x")).ok())
+            .and_then(|h| <[u8; 32]>::try_from(h).ok())
+            .ok_or_else(|| {
+                DevnetError::Rpc("eth_sendRawTransaction: malformed hash".to_string())
+            })?;
+        let block = parse_quantity(&receipt["blockNumber"])
+            .ok_or_else(|| DevnetError::Rpc("receipt has no block number".to_string()))?;
+        Ok((tx_hash, block))
+    }
+
+    fn complete(
+        &self,
+        record_address: &Pubkey,
+        evm_tx_hash: [u8; 32],
+        evm_block: u64,
+    ) -> Result<Signature, DevnetError> {
+        let record = self.load_record(record_address)?;
+        let transfer = record.transfer;
+        let operator = self.operator.pubkey();
+        let domain = SigningDomain::new(self.program_id, self.rpc.genesis_hash()?.to_bytes());
+
+        let digest = relay_message_hash(&domain, &evm_tx_hash, &transfer);
+        let signature = self.operator.sign_message(&digest);
+        let dalek = ed25519_dalek::Keypair::from_bytes(&self.operator.to_bytes())
+            .map_err(|e| DevnetError::State(e.to_string()))?;
+        let verify = new_ed25519_instruction(&dalek, &digest);
+
+        let confirmation = RelayConfirmation {
+            relayer: operator,
+            tx_hash: evm_tx_hash.to_vec().into(),
+            block_number: evm_block,
+            timestamp: 0,
+            signatures: vec![signature.as_ref().into()].into(),
+            signers: vec![operator].into(),
+        };
+
+        let complete = complete_cross_chain_ix(
+            &self.program_id,
+            &operator,
+            DEVNET_HUB_CHAIN_ID,
+            transfer,
+            vec![confirmation],
+        )?;
+        self.rpc.submit(&[verify, complete], &[&self.operator])
+    }
+
+    fn load_record(&self, address: &Pubkey) -> Result<TransferRecord, DevnetError> {
+        let data = self
+            .rpc
+            .get_account_data(address)?
+            .ok_or_else(|| DevnetError::State(format!("transfer record {address} not found")))?;
+        TransferRecord::deserialize(&mut &data[..])
+            .map_err(|_| DevnetError::State(format!("transfer record {address} is malformed")))
+    }
+}
@@ -1,8 +1,19 @@
+use crate::account::VersionedAccount;
+use crate::audit::AuditId;
+use crate::bounded::{BoundedBytes, BoundedMap, BoundedString, BoundedVec};
+use crate::domain::SigningDomain;
+use crate::error::CrossChainError;
+use crate::relayer::VerifiedSignature;
+use crate::rfc3161::MAX_TIMESTAMP_TOKEN_LEN;
+#[cfg(not(target_os = "solana"))]
+use crate::sigverify::{verify_signature, SignatureAlgorithm};
+use crate::x509::MAX_CERTIFICATE_LEN;
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
-use std::collections::HashMap;
+use solana_program::{hash::hash, pubkey::Pubkey};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Default)]
+#[derive(
+    BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default,
+)]
 pub enum EidasLevel {
     #[default]
     None,
@@ -41,6 +52,41 @@ pub struct QualifiedSignature {
     pub signer_role: String,
 }
 
+/// A qualified electronic seal by a legal entity, recorded against the
+/// wallet that attached it. `sealed_data_hash` is the SHA-256 of the sealed
+/// data.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct QualifiedSeal {
+    pub owner: Pubkey,
+    pub seal: Vec<u8>,
+    pub certificate: QualifiedCertificate,
+    pub organization: String,
+    pub organization_identifier: OrganizationIdentifier,
+    pub sealed_data_hash: [u8; 32],
+    pub timestamp: i64,
+    pub signature_algorithm: String,
+}
+
+/// Registration scheme of an ETSI EN 319 412-1 organization identifier.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrganizationIdScheme {
+    /// ISO 17442 Legal Entity Identifier
+    Lei,
+    /// VAT registration number
+    Vat,
+    /// National trade register number
+    Ntr,
+}
+
+/// Parsed form of an organizationIdentifier such as `VATDE-123456789`:
+/// scheme, ISO 3166-1 country (`XG` for global LEIs) and reference.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct OrganizationIdentifier {
+    pub scheme: OrganizationIdScheme,
+    pub country: String,
+    pub reference: String,
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct QualifiedTimestamp {
     pub ts_token: Vec<u8>,
@@ -59,10 +105,17 @@ pub struct ComplianceData {
     pub restricted: bool,
     pub verification_date: i64,
     pub expiry_date: i64,
-    pub jurisdiction: String,
-    pub metadata: HashMap<String, String>,
+    pub jurisdiction: BoundedString<MAX_JURISDICTION_LEN>,
+    pub metadata: ComplianceMetadata,
 }
 
+/// Free-form attestation attributes carried alongside a compliance record.
+pub type ComplianceMetadata = BoundedMap<
+    BoundedString<MAX_METADATA_KEY_LEN>,
+    BoundedString<MAX_METADATA_VALUE_LEN>,
+    MAX_METADATA_ENTRIES,
+>;
+
 impl Default for ComplianceData {
     fn default() -> Self {
         Self {
@@ -73,30 +126,631 @@ impl Default for ComplianceData {
             restricted: false,
             verification_date: 0,
             expiry_date: 0,
-            jurisdiction: String::new(),
-            metadata: HashMap::new(),
+            jurisdiction: BoundedString::new(),
+            metadata: BoundedMap::new(),
+        }
+    }
+}
+
+pub const MAX_COMPLIANCE_BANDS: usize = 16;
+pub const MAX_JURISDICTION_LEN: usize = 16;
+pub const MAX_METADATA_ENTRIES: usize = 16;
+pub const MAX_METADATA_KEY_LEN: usize = 64;
+pub const MAX_METADATA_VALUE_LEN: usize = 256;
+pub const MAX_SERIAL_NUMBER_LEN: usize = 32;
+/// Cap on the document bytes signed, sealed or timestamped in one instruction.
+pub const MAX_SIGNED_DATA_LEN: usize = 1024;
+/// Room for a DER ECDSA signature or an RSA-4096 one.
+pub const MAX_SIGNATURE_LEN: usize = 512;
+pub const MAX_ALGORITHM_NAME_LEN: usize = 16;
+pub const QUALIFIED_SEAL_SEED: &[u8] = b"qualified_seal";
+pub const MAX_ORGANIZATION_REFERENCE_LEN: usize = 35;
+/// Bound on the encrypted IVMS 101 record so a Travel Rule payload fits a
+/// `TokenTransfer` bridge message.
+pub const MAX_IVMS101_PII_LEN: usize = 192;
+const LEI_LEN: usize = 20;
+pub const TRUSTED_ISSUER_REGISTRY_SEED: &[u8] = b"trusted_issuers";
+pub const MAX_TRUSTED_ISSUERS: usize = 64;
+
+/// A qualified trust service provider from the EU Trusted List, identified by
+/// the hash of its DER-encoded subject name.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct TrustedIssuer {
+    pub issuer_hash: [u8; 32],
+    pub added_at: i64,
+}
+
+/// Issuers whose certificates are accepted for qualified signatures,
+/// maintained by the eIDAS authority. The account is allocated for
+/// `MAX_TRUSTED_ISSUERS` entries.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct TrustedIssuerRegistry {
+    pub issuers: Vec<TrustedIssuer>,
+    pub bump: u8,
+}
+
+impl TrustedIssuerRegistry {
+    pub const LEN: usize = 4 + MAX_TRUSTED_ISSUERS * (32 + 8) + 1;
+
+    pub fn is_trusted(&self, issuer_hash: &[u8; 32]) -> bool {
+        self.issuers.iter().any(|i| i.issuer_hash == *issuer_hash)
+    }
+
+    /// Adding an issuer that is already listed is a no-op.
+    pub fn add(&mut self, issuer_hash: [u8; 32], now: i64) -> Result<bool, CrossChainError> {
+        if self.is_trusted(&issuer_hash) {
+            return Ok(true);
+        }
+
+        if self.issuers.len() >= MAX_TRUSTED_ISSUERS {
+            return Err(CrossChainError::TrustedIssuerRegistryFull);
+        }
+
+        self.issuers.push(TrustedIssuer {
+            issuer_hash,
+            added_at: now,
+        });
+        Ok(true)
+    }
+
+    pub fn revoke(&mut self, issuer_hash: &[u8; 32]) -> Result<bool, CrossChainError> {
+        let index = self
+            .issuers
+            .iter()
+            .position(|i| i.issuer_hash == *issuer_hash)
+            .ok_or(CrossChainError::UntrustedIssuer)?;
+        self.issuers.remove(index);
+        Ok(true)
+    }
+}
+
+pub fn get_trusted_issuer_registry_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TRUSTED_ISSUER_REGISTRY_SEED], program_id)
+}
+
+pub const REVOCATION_LIST_SEED: &[u8] = b"revocation";
+pub const MAX_REVOKED_CERTIFICATES: usize = 128;
+
+/// Certificates an issuer has revoked, posted by the eIDAS authority from the
+/// issuer's CRL or OCSP responses. Serial numbers are stored hashed so entries
+/// have a fixed size regardless of serial length.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct RevocationList {
+    pub issuer_hash: [u8; 32],
+    pub revoked_serials: Vec<[u8; 32]>,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl RevocationList {
+    pub const LEN: usize = 32 + 4 + MAX_REVOKED_CERTIFICATES * 32 + 8 + 1;
+
+    pub fn is_revoked(&self, serial_number: &[u8]) -> bool {
+        self.revoked_serials.contains(&serial_hash(serial_number))
+    }
+
+    pub fn revoke(&mut self, serial_number: &[u8], now: i64) -> Result<bool, CrossChainError> {
+        if !self.is_revoked(serial_number) {
+            if self.revoked_serials.len() >= MAX_REVOKED_CERTIFICATES {
+                return Err(CrossChainError::RevocationListFull);
+            }
+            self.revoked_serials.push(serial_hash(serial_number));
+        }
+
+        self.updated_at = now;
+        Ok(true)
+    }
+}
+
+pub fn serial_hash(serial_number: &[u8]) -> [u8; 32] {
+    hash(serial_number).to_bytes()
+}
+
+pub fn get_revocation_list_address(issuer_hash: &[u8; 32], program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[REVOCATION_LIST_SEED, issuer_hash], program_id)
+}
+
+/// Rejects a certificate revoked by its issuer. `list` is `None` when the
+/// issuer has never had a revocation posted.
+pub fn check_revocation(
+    list: Option<&RevocationList>,
+    serial_number: &[u8],
+) -> Result<bool, CrossChainError> {
+    if list.is_some_and(|list| list.is_revoked(serial_number)) {
+        return Err(CrossChainError::CertificateRevoked);
+    }
+
+    Ok(true)
+}
+
+pub const COMPLIANCE_RECORD_SEED: &[u8] = b"compliance";
+
+/// The full result of a wallet owner's last compliance verification. The
+/// wallet carries the level and flags used for policy; the record keeps the
+/// expiry and jurisdiction so stale verifications stop being honoured.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ComplianceRecord {
+    pub owner: Pubkey,
+    pub data: ComplianceData,
+    pub renewals: u32,
+    pub bump: u8,
+}
+
+impl ComplianceRecord {
+    /// Expiry is exclusive: a record stops being valid at `expiry_date`.
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.data.expiry_date
+    }
+
+    pub fn renew(&mut self, expiry_date: i64, now: i64) -> Result<bool, CrossChainError> {
+        if !self.data.verified {
+            return Err(CrossChainError::ComplianceRequired);
+        }
+
+        if expiry_date <= now {
+            return Err(CrossChainError::InvalidComplianceExpiry);
+        }
+
+        self.data.verification_date = now;
+        self.data.expiry_date = expiry_date;
+        self.renewals = self.renewals.saturating_add(1);
+        Ok(true)
+    }
+}
+
+pub fn get_compliance_record_address(owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COMPLIANCE_RECORD_SEED, owner.as_ref()], program_id)
+}
+
+pub const SPONSOR_SEED: &[u8] = b"sponsor";
+
+/// An institution the eIDAS authority approved to pay rent for its end
+/// users' wallet registration and compliance accounts, stored at
+/// `[b"sponsor", sponsor]`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Sponsor {
+    pub sponsor: Pubkey,
+    pub enabled: bool,
+    /// Sponsored instructions allowed in total; zero leaves it uncapped.
+    pub max_sponsored: u64,
+    pub sponsored: u64,
+    pub bump: u8,
+}
+
+impl VersionedAccount for Sponsor {
+    const DISCRIMINATOR: [u8; 8] = *b"RQSPONSR";
+    const VERSION: u8 = 1;
+}
+
+impl Sponsor {
+    pub const LEN: usize = 32 + 1 + 8 + 8 + 1;
+
+    /// Counts one sponsored instruction against the quota.
+    pub fn charge(&mut self) -> Result<bool, CrossChainError> {
+        if !self.enabled {
+            return Err(CrossChainError::SponsorNotApproved);
+        }
+
+        if self.max_sponsored > 0 && self.sponsored >= self.max_sponsored {
+            return Err(CrossChainError::SponsorQuotaExhausted);
+        }
+
+        self.sponsored += 1;
+        Ok(true)
+    }
+}
+
+pub fn get_sponsor_address(sponsor: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SPONSOR_SEED, sponsor.as_ref()], program_id)
+}
+
+/// A positive verification must not already be expired when it is recorded.
+pub fn validate_compliance_data(data: &ComplianceData, now: i64) -> Result<bool, CrossChainError> {
+    if data.verified && data.expiry_date <= now {
+        return Err(CrossChainError::InvalidComplianceExpiry);
+    }
+
+    Ok(true)
+}
+
+/// Rejects a compliance-verified wallet whose record is missing or expired.
+/// Wallets that were never verified have nothing to expire.
+pub fn check_compliance_record(
+    verified: bool,
+    record: Option<&ComplianceRecord>,
+    now: i64,
+) -> Result<bool, CrossChainError> {
+    if !verified {
+        return Ok(true);
+    }
+
+    match record {
+        Some(record) if !record.is_expired(now) => Ok(true),
+        _ => Err(CrossChainError::ComplianceExpired),
+    }
+}
+
+pub const ATTESTATION_DOMAIN: &[u8] = b"rivicq-attestation-v1";
+
+/// A wallet's compliance status exported for one destination chain, so its
+/// contracts can honor the hub's eIDAS verification. The eIDAS authority
+/// signs `digest`; counterparts check the signature against the authority
+/// key and refuse the attestation from `expires_at`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ComplianceAttestation {
+    pub owner: Pubkey,
+    pub destination_chain: u64,
+    pub eidas_level: EidasLevel,
+    pub kyc_verified: bool,
+    pub aml_screened: bool,
+    pub jurisdiction: [u8; 2],
+    pub issued_at: i64,
+    pub expires_at: i64,
+    pub authority: Pubkey,
+}
+
+impl ComplianceAttestation {
+    /// What the authority signs, bound to the hub's signing domain.
+    pub fn digest(&self, domain: &SigningDomain) -> [u8; 32] {
+        domain.hash(
+            ATTESTATION_DOMAIN,
+            &[&self.try_to_vec().unwrap_or_default()],
+        )
+    }
+
+    /// Checks the attestation states exactly what `record` holds and does
+    /// not outlive it.
+    pub fn validate(&self, record: &ComplianceRecord, now: i64) -> Result<bool, CrossChainError> {
+        if !record.data.verified || record.is_expired(now) {
+            return Err(CrossChainError::ComplianceExpired);
+        }
+
+        let matches = self.owner == record.owner
+            && self.eidas_level == record.data.eidas_level
+            && self.kyc_verified == record.data.kyc_verified
+            && self.aml_screened == record.data.aml_screened
+            && self.jurisdiction == parse_jurisdiction(&record.data.jurisdiction)?;
+        if !matches
+            || self.issued_at > now
+            || self.expires_at <= now
+            || self.expires_at > record.data.expiry_date
+        {
+            return Err(CrossChainError::InvalidAttestation);
+        }
+
+        Ok(true)
+    }
+}
+
+/// Payload of a `ComplianceAttestation` bridge message.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SignedComplianceAttestation {
+    pub attestation: ComplianceAttestation,
+    pub signature: [u8; 64],
+}
+
+impl SignedComplianceAttestation {
+    pub const LEN: usize = 32 + 8 + 1 + 1 + 1 + 2 + 8 + 8 + 32 + 64;
+
+    /// Requires an ed25519 signature by the attestation's authority over its
+    /// digest among the signatures checked earlier in the transaction.
+    pub fn verify(
+        &self,
+        domain: &SigningDomain,
+        verified: &[VerifiedSignature],
+    ) -> Result<bool, CrossChainError> {
+        let digest = self.attestation.digest(domain);
+        let signed = verified.iter().any(|v| {
+            v.signer == self.attestation.authority
+                && v.signature == self.signature
+                && v.message == digest
+        });
+        if !signed {
+            return Err(CrossChainError::AttestationSignatureMissing);
+        }
+        Ok(true)
+    }
+}
+
+pub const RESTRICTED_JURISDICTIONS_SEED: &[u8] = b"restricted_jurisdictions";
+pub const MAX_RESTRICTED_JURISDICTIONS: usize = 64;
+pub const DEFAULT_RESTRICTED_JURISDICTIONS: [[u8; 2]; 3] = [*b"KP", *b"IR", *b"SY"];
+
+/// ISO 3166-1 alpha-2 codes transfers and verifications are refused for,
+/// maintained by the eIDAS authority. Until the account is first written the
+/// default list applies.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RestrictedJurisdictions {
+    pub codes: Vec<[u8; 2]>,
+    pub bump: u8,
+}
+
+impl Default for RestrictedJurisdictions {
+    fn default() -> Self {
+        Self {
+            codes: DEFAULT_RESTRICTED_JURISDICTIONS.to_vec(),
+            bump: 0,
+        }
+    }
+}
+
+impl RestrictedJurisdictions {
+    pub const LEN: usize = 4 + MAX_RESTRICTED_JURISDICTIONS * 2 + 1;
+
+    pub fn is_restricted(&self, jurisdiction: &str) -> bool {
+        parse_jurisdiction(jurisdiction).is_ok_and(|code| self.codes.contains(&code))
+    }
+
+    /// Adding a code that is already listed is a no-op.
+    pub fn add(&mut self, code: [u8; 2]) -> Result<bool, CrossChainError> {
+        if self.codes.contains(&code) {
+            return Ok(true);
+        }
+
+        if self.codes.len() >= MAX_RESTRICTED_JURISDICTIONS {
+            return Err(CrossChainError::RestrictedJurisdictionListFull);
+        }
+
+        self.codes.push(code);
+        Ok(true)
+    }
+
+    pub fn remove(&mut self, code: &[u8; 2]) -> Result<bool, CrossChainError> {
+        let index = self
+            .codes
+            .iter()
+            .position(|c| c == code)
+            .ok_or(CrossChainError::InvalidJurisdiction)?;
+        self.codes.remove(index);
+        Ok(true)
+    }
+}
+
+/// Normalises a two-letter country code to upper case.
+pub fn parse_jurisdiction(jurisdiction: &str) -> Result<[u8; 2], CrossChainError> {
+    match jurisdiction.as_bytes() {
+        [a, b] if a.is_ascii_alphabetic() && b.is_ascii_alphabetic() => {
+            Ok([a.to_ascii_uppercase(), b.to_ascii_uppercase()])
+        }
+        _ => Err(CrossChainError::InvalidJurisdiction),
+    }
+}
+
+pub fn get_restricted_jurisdictions_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RESTRICTED_JURISDICTIONS_SEED], program_id)
+}
+
+/// Compliance attributes of a wallet that transfer policy is evaluated against.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct ComplianceProfile {
+    pub eidas_level: EidasLevel,
+    pub kyc_verified: bool,
+    pub aml_screened: bool,
+}
+
+/// Requirements applying to transfers of at least `min_amount`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ComplianceBand {
+    pub min_amount: u64,
+    pub required_level: EidasLevel,
+    pub require_kyc: bool,
+    pub require_aml: bool,
+}
+
+/// FATF Travel Rule data for a transfer, in IVMS 101 form. Only a hash of
+/// the originator's name is public; the full originator and beneficiary
+/// records are encrypted to the beneficiary VASP, identified by its LEI.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct Ivms101Payload {
+    pub originator_name_hash: [u8; 32],
+    pub beneficiary_vasp: BoundedString<LEI_LEN>,
+    pub encrypted_pii: BoundedBytes<MAX_IVMS101_PII_LEN>,
+}
+
+impl Ivms101Payload {
+    pub const MAX_LEN: usize = 32 + 4 + LEI_LEN + 4 + MAX_IVMS101_PII_LEN;
+
+    pub fn encoded_len(&self) -> usize {
+        32 + 4 + self.beneficiary_vasp.len() + 4 + self.encrypted_pii.len()
+    }
+
+    pub fn validate(&self) -> Result<bool, CrossChainError> {
+        if self.originator_name_hash == [0u8; 32]
+            || !is_valid_lei(&self.beneficiary_vasp)
+            || self.encrypted_pii.is_empty()
+            || self.encrypted_pii.len() > MAX_IVMS101_PII_LEN
+        {
+            return Err(CrossChainError::InvalidTravelRulePayload);
+        }
+
+        Ok(true)
+    }
+}
+
+/// Risk rules that divert an outbound transfer into manual review. A held
+/// transfer is escrowed but not relayed until the eIDAS authority releases
+/// or rejects it.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct HoldRules {
+    /// Transfers of at least this amount are held.
+    pub amount_threshold: Option<u64>,
+    /// Holds transfers from wallets without AML screening.
+    pub hold_unscreened: bool,
+    /// Slots a held transfer waits for review before its sender can reclaim it.
+    pub review_timeout_slots: u64,
+    pub velocity: VelocityRules,
+}
+
+impl Default for HoldRules {
+    fn default() -> Self {
+        Self {
+            amount_threshold: None,
+            hold_unscreened: false,
+            review_timeout_slots: 432_000,
+            velocity: VelocityRules::default(),
+        }
+    }
+}
+
+impl HoldRules {
+    pub fn should_hold(&self, amount: u64, profile: &ComplianceProfile) -> bool {
+        self.amount_threshold.is_some_and(|t| amount >= t)
+            || (self.hold_unscreened && !profile.aml_screened)
+    }
+}
+
+/// Per-wallet limits on how many transfers, and how much, may be initiated
+/// within `window_slots`. A transfer that exceeds them raises a
+/// `VelocityAlert` event and, with `auto_hold`, is held for review.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct VelocityRules {
+    pub window_slots: u64,
+    /// Zero leaves the transfer count unchecked.
+    pub max_transfers: u16,
+    pub max_amount: Option<u64>,
+    pub auto_hold: bool,
+}
+
+impl Default for VelocityRules {
+    fn default() -> Self {
+        Self {
+            window_slots: 9_000,
+            max_transfers: 0,
+            max_amount: None,
+            auto_hold: false,
+        }
+    }
+}
+
+impl VelocityRules {
+    pub fn is_enabled(&self) -> bool {
+        self.window_slots > 0 && (self.max_transfers > 0 || self.max_amount.is_some())
+    }
+
+    pub fn is_exceeded(&self, transfers: u16, amount: u64) -> bool {
+        (self.max_transfers > 0 && transfers > self.max_transfers)
+            || self.max_amount.is_some_and(|max| amount > max)
+    }
+}
+
+/// Amount bands in ascending `min_amount` order; the highest band not above
+/// the transfer amount decides what is required. Transfers of at least
+/// `travel_rule_threshold` must carry an IVMS 101 payload.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ComplianceMatrix {
+    pub bands: BoundedVec<ComplianceBand, MAX_COMPLIANCE_BANDS>,
+    pub travel_rule_threshold: Option<u64>,
+    pub hold_rules: HoldRules,
+}
+
+impl Default for ComplianceMatrix {
+    fn default() -> Self {
+        Self {
+            bands: vec![ComplianceBand {
+                min_amount: 1_000_000_001,
+                required_level: EidasLevel::Basic,
+                require_kyc: false,
+                require_aml: false,
+            }]
+            .into(),
+            travel_rule_threshold: None,
+            hold_rules: HoldRules::default(),
+        }
+    }
+}
+
+impl ComplianceMatrix {
+    pub fn validate(&self) -> Result<bool, CrossChainError> {
+        if self.bands.len() > MAX_COMPLIANCE_BANDS {
+            return Err(CrossChainError::InvalidComplianceMatrix);
+        }
+
+        if self
+            .bands
+            .windows(2)
+            .any(|w| w[0].min_amount >= w[1].min_amount)
+        {
+            return Err(CrossChainError::InvalidComplianceMatrix);
+        }
+
+        Ok(true)
+    }
+
+    pub fn band_for(&self, amount: u64) -> Option<&ComplianceBand> {
+        self.bands.iter().rev().find(|b| amount >= b.min_amount)
+    }
+
+    pub fn check(&self, amount: u64, profile: &ComplianceProfile) -> Result<bool, CrossChainError> {
+        let band = match self.band_for(amount) {
+            Some(band) => band,
+            None => return Ok(true),
+        };
+
+        if profile.eidas_level < band.required_level {
+            return Err(CrossChainError::ComplianceRequired);
+        }
+
+        if band.require_kyc && !profile.kyc_verified {
+            return Err(CrossChainError::KycRequired);
+        }
+
+        if band.require_aml && !profile.aml_screened {
+            return Err(CrossChainError::AmlScreeningRequired);
+        }
+
+        Ok(true)
+    }
+
+    /// A payload is required at or above the threshold and, when attached
+    /// below it, must still be well formed.
+    pub fn check_travel_rule(
+        &self,
+        amount: u64,
+        payload: Option<&Ivms101Payload>,
+    ) -> Result<bool, CrossChainError> {
+        match payload {
+            Some(payload) => payload.validate(),
+            None if self.travel_rule_threshold.is_some_and(|t| amount >= t) => {
+                Err(CrossChainError::TravelRuleRequired)
+            }
+            None => Ok(true),
         }
     }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct QualifiedSignatureData {
-    pub data_to_sign: Vec<u8>,
-    pub certificate: Vec<u8>,
-    pub signature: Vec<u8>,
+    pub data_to_sign: BoundedBytes<MAX_SIGNED_DATA_LEN>,
+    pub certificate: BoundedBytes<MAX_CERTIFICATE_LEN>,
+    pub signature: BoundedBytes<MAX_SIGNATURE_LEN>,
+    pub timestamp: Option<i64>,
+    /// `Ed25519` or `secp256k1`, matching the certificate's key.
+    pub signature_algorithm: BoundedString<MAX_ALGORITHM_NAME_LEN>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct QualifiedSealData {
+    pub data_to_seal: BoundedBytes<MAX_SIGNED_DATA_LEN>,
+    /// DER QCert for eSeal of the sealing organization.
+    pub certificate: BoundedBytes<MAX_CERTIFICATE_LEN>,
+    pub seal: BoundedBytes<MAX_SIGNATURE_LEN>,
     pub timestamp: Option<i64>,
+    /// `Ed25519` or `secp256k1`, matching the certificate's key.
+    pub signature_algorithm: BoundedString<MAX_ALGORITHM_NAME_LEN>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct TimestampData {
-    pub data_to_timestamp: Vec<u8>,
-    pub hash_algorithm: String,
+    pub data_to_timestamp: BoundedBytes<MAX_SIGNED_DATA_LEN>,
+    pub hash_algorithm: BoundedString<MAX_ALGORITHM_NAME_LEN>,
     pub require_qts: bool,
+    /// DER RFC 3161 TimeStampToken over the hash of `data_to_timestamp`.
+    pub ts_token: BoundedBytes<MAX_TIMESTAMP_TOKEN_LEN>,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct AuditLog {
-    pub id: String,
+    pub id: AuditId,
     pub timestamp: i64,
     pub action: String,
     pub user: Pubkey,
@@ -106,43 +760,178 @@ pub struct AuditLog {
     pub signature: Option<Vec<u8>>,
 }
 
-pub fn validate_certificate(cert: &QualifiedCertificate) -> Result<bool, String> {
+/// Why the eIDAS authority froze a wallet, carried by the `WalletFrozen`
+/// event.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreezeReason {
+    Sanctions,
+    AmlInvestigation,
+    CourtOrder,
+    RegulatorRequest,
+    Other,
+}
+
+/// Checks a certificate against the current unix time `now`, which on-chain
+/// callers take from the `Clock` sysvar. Validity is inclusive of both
+/// `not_before` and `not_after`, as in X.509.
+pub fn validate_certificate(
+    cert: &QualifiedCertificate,
+    now: i64,
+) -> Result<bool, CrossChainError> {
     if !cert.qscd {
-        return Err("Certificate must be from QSCD".to_string());
+        return Err(CrossChainError::CertificateNotQscd);
     }
 
-    if cert.not_before > 10000000000i64 {
-        return Err("Certificate not yet valid".to_string());
+    if now < cert.not_before {
+        return Err(CrossChainError::CertificateNotYetValid);
     }
 
-    if cert.not_after > 0 && cert.not_after < 1000000i64 {
-        return Err("Certificate expired".to_string());
+    if now > cert.not_after {
+        return Err(CrossChainError::CertificateExpired);
     }
 
     Ok(true)
 }
 
+/// Off-chain verification of a qualified signature over `data` by
+/// `public_key`, which must be the key the certificate was issued for.
+#[cfg(not(target_os = "solana"))]
 pub fn verify_qualified_signature(
     signature: &QualifiedSignature,
-    _data: &[u8],
-) -> Result<bool, String> {
-    validate_certificate(&signature.certificate)?;
+    public_key: &[u8],
+    data: &[u8],
+    now: i64,
+) -> Result<bool, CrossChainError> {
+    validate_certificate(&signature.certificate, now)?;
 
     if signature.timestamp == 0 {
-        return Err("Timestamp required for qualified signature".to_string());
+        return Err(CrossChainError::TimestampRequired);
     }
 
-    Ok(true)
+    let algorithm = SignatureAlgorithm::from_name(&signature.signature_algorithm)?;
+
+    if hash(public_key).to_bytes().as_slice() != signature.certificate.public_key_hash {
+        return Err(CrossChainError::InvalidQualifiedSignature);
+    }
+
+    verify_signature(algorithm, public_key, &signature.signature, data)
+}
+
+/// Off-chain verification of a qualified seal over `data` by `public_key`.
+/// The certificate must be a QCert for eSeal.
+#[cfg(not(target_os = "solana"))]
+pub fn verify_qualified_seal(
+    seal: &QualifiedSeal,
+    public_key: &[u8],
+    data: &[u8],
+    now: i64,
+) -> Result<bool, CrossChainError> {
+    if seal.certificate.certificate_type != CertificateType::QESe {
+        return Err(CrossChainError::CertificateNotForSeal);
+    }
+
+    validate_certificate(&seal.certificate, now)?;
+
+    if seal.timestamp == 0 {
+        return Err(CrossChainError::TimestampRequired);
+    }
+
+    if hash(data).to_bytes() != seal.sealed_data_hash {
+        return Err(CrossChainError::InvalidQualifiedSignature);
+    }
+
+    let algorithm = SignatureAlgorithm::from_name(&seal.signature_algorithm)?;
+
+    if hash(public_key).to_bytes().as_slice() != seal.certificate.public_key_hash {
+        return Err(CrossChainError::InvalidQualifiedSignature);
+    }
+
+    verify_signature(algorithm, public_key, &seal.seal, data)
+}
+
+/// Parses an ETSI EN 319 412-1 organizationIdentifier: a three letter scheme,
+/// a two letter country, a hyphen and the reference. LEIs must pass the
+/// ISO 17442 check digits.
+pub fn parse_organization_identifier(
+    value: &str,
+) -> Result<OrganizationIdentifier, CrossChainError> {
+    let invalid = CrossChainError::InvalidOrganizationIdentifier;
+    let (prefix, reference) = value.split_once('-').ok_or(invalid)?;
+    if prefix.len() != 5 || !prefix.is_ascii() {
+        return Err(CrossChainError::InvalidOrganizationIdentifier);
+    }
+
+    let (scheme, country) = prefix.split_at(3);
+    let scheme = match scheme {
+        "LEI" => OrganizationIdScheme::Lei,
+        "VAT" => OrganizationIdScheme::Vat,
+        "NTR" => OrganizationIdScheme::Ntr,
+        _ => return Err(CrossChainError::InvalidOrganizationIdentifier),
+    };
+
+    if !country.bytes().all(|b| b.is_ascii_uppercase()) {
+        return Err(CrossChainError::InvalidOrganizationIdentifier);
+    }
+
+    if reference.is_empty()
+        || reference.len() > MAX_ORGANIZATION_REFERENCE_LEN
+        || !reference.bytes().all(|b| b.is_ascii_alphanumeric())
+    {
+        return Err(CrossChainError::InvalidOrganizationIdentifier);
+    }
+
+    if scheme == OrganizationIdScheme::Lei && !is_valid_lei(reference) {
+        return Err(CrossChainError::InvalidOrganizationIdentifier);
+    }
+
+    Ok(OrganizationIdentifier {
+        scheme,
+        country: country.to_string(),
+        reference: reference.to_string(),
+    })
+}
+
+/// ISO 17442: 20 upper-case alphanumerics whose ISO 7064 MOD 97-10 remainder
+/// is 1, with letters expanded to 10..=35 as in IBANs.
+pub fn is_valid_lei(lei: &str) -> bool {
+    if lei.len() != LEI_LEN
+        || !lei
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b.is_ascii_uppercase())
+    {
+        return false;
+    }
+
+    let remainder = lei.bytes().fold(0u32, |acc, b| {
+        if b.is_ascii_digit() {
+            (acc * 10 + (b - b'0') as u32) % 97
+        } else {
+            (acc * 100 + (b - b'A' + 10) as u32) % 97
+        }
+    });
+    remainder == 1
+}
+
+pub fn get_qualified_seal_address(
+    owner: &Pubkey,
+    data_hash: &[u8; 32],
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[QUALIFIED_SEAL_SEED, owner.as_ref(), data_hash],
+        program_id,
+    )
 }
 
 pub fn create_audit_log(
+    id: AuditId,
     action: String,
     user: Pubkey,
     details: String,
     compliance_status: String,
 ) -> AuditLog {
     AuditLog {
-        id: uuid::Uuid::new_v4().to_string(),
+        id,
         timestamp: chrono::Utc::now().timestamp(),
         action,
         user,
@@ -153,7 +942,15 @@ pub fn create_audit_log(
     }
 }
 
-pub fn check_restricted_jurisdiction(jurisdiction: &str) -> bool {
-    let restricted: Vec<&str> = vec!["KP", "IR", "SY"];
-    restricted.contains(&jurisdiction)
+/// Rejects a jurisdiction on the sanctions list. An empty jurisdiction means
+/// none was recorded and is not checked.
+pub fn check_restricted_jurisdiction(
+    list: &RestrictedJurisdictions,
+    jurisdiction: &str,
+) -> Result<bool, CrossChainError> {
+    if list.is_restricted(jurisdiction) {
+        return Err(CrossChainError::RestrictedJurisdiction);
+    }
+
+    Ok(true)
 }
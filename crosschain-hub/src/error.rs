@@ -0,0 +1,370 @@
+use num_derive::FromPrimitive;
+use solana_program::program_error::ProgramError;
+use thiserror::Error;
+
+use crate::bridge::MessageError;
+
+/// Errors surfaced to clients as `ProgramError::Custom(code)`. Discriminants are
+/// part of the public interface and must never be renumbered; decode a code with
+/// `num_traits::FromPrimitive::from_u32`.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq, FromPrimitive)]
+pub enum CrossChainError {
+    #[error("Signer is not authorized for this operation")]
+    Unauthorized = 1,
+
+    // Processor
+    #[error("eIDAS compliance required")]
+    ComplianceRequired = 1001,
+    #[error("Program is paused")]
+    ProgramPaused = 1002,
+    #[error("Amount below minimum cross-chain amount")]
+    AmountTooLow = 1003,
+    #[error("Amount above maximum cross-chain amount")]
+    AmountTooHigh = 1004,
+    #[error("Config fee or amount bounds are invalid")]
+    InvalidConfig = 1057,
+    #[error("Instruction must be invoked at the top level of the transaction")]
+    CpiNotAllowed = 1090,
+    #[error("Account discriminator does not match the expected account type")]
+    InvalidAccountDiscriminator = 1093,
+    #[error("Account layout is outdated; run MigrateAccount")]
+    AccountMigrationRequired = 1094,
+    #[error("Arithmetic overflow")]
+    ArithmeticOverflow = 1098,
+    #[error("Payload is not canonically encoded")]
+    NonCanonicalEncoding = 1099,
+
+    // Intents
+    #[error("Invalid transfer intent")]
+    InvalidIntent = 1005,
+    #[error("Solver bid rejected")]
+    BidRejected = 1006,
+    #[error("Intent is not open")]
+    IntentNotOpen = 1007,
+    #[error("Signer is not the winning solver")]
+    NotWinningSolver = 1008,
+    #[error("Intent is still awaiting fill")]
+    IntentAwaitingFill = 1009,
+
+    // Keepers
+    #[error("Keeper crank not allowed")]
+    CrankNotAllowed = 1010,
+    #[error("Nothing to crank")]
+    NothingToCrank = 1011,
+
+    // Bridge
+    #[error("Chain is not registered")]
+    ChainNotRegistered = 1012,
+    #[error("Chain is disabled")]
+    ChainDisabled = 1013,
+    #[error("Transfer is not in flight")]
+    TransferNotInFlight = 1014,
+    #[error("Transfer timeout has not elapsed")]
+    RefundNotYetAvailable = 1049,
+    #[error("Malformed bridge message")]
+    InvalidBridgeMessage = 1017,
+    #[error("Bridge message payload too large")]
+    PayloadTooLarge = 1018,
+    #[error("Invalid contract call envelope")]
+    InvalidCallEnvelope = 1019,
+    #[error("No enabled tokens available for transfer")]
+    NoEnabledTokens = 1020,
+    #[error("Insufficient relay confirmations")]
+    InsufficientConfirmations = 1021,
+    #[error("Bridge emergency breaker is tripped")]
+    BreakerTripped = 1035,
+    #[error("Inbound transfers from this chain are disabled")]
+    InboundDisabled = 1036,
+    #[error("No wrapped asset registered for this token")]
+    UnknownWrappedAsset = 1037,
+    #[error("Wrapped asset already registered")]
+    WrappedAssetExists = 1038,
+    #[error("Wrapped asset registry is full")]
+    WrappedRegistryFull = 1039,
+    #[error("Distribution recipients are empty, zero-weighted or too many")]
+    InvalidDistribution = 1048,
+    #[error("Escrow ledger has no room for another asset")]
+    EscrowLedgerFull = 1050,
+    #[error("Invalid CAIP-2 chain identifier")]
+    InvalidChainId = 1051,
+    #[error("Sealed payload is not encrypted to the chain's registered key")]
+    EncryptionKeyMismatch = 1061,
+    #[error("Message is not addressed to this gateway")]
+    GatewayMismatch = 1062,
+    #[error("Confirmation signer is not a registered relayer")]
+    UnauthorizedRelayer = 1043,
+    #[error("Relayer stake below the bonding minimum")]
+    InsufficientRelayerStake = 1044,
+    #[error("Relay confirmation signature was not verified")]
+    InvalidRelaySignature = 1045,
+    #[error("Relayer lease is held by another relayer")]
+    RelayerLeaseHeld = 1075,
+    #[error("Relayer lease duration is out of range")]
+    InvalidRelayerLease = 1076,
+    #[error("Inclusion proof does not match the posted state root")]
+    InvalidInclusionProof = 1102,
+    #[error("Completion requires an inclusion proof")]
+    InclusionProofRequired = 1103,
+    #[error("State root already posted for this block")]
+    StateRootAlreadyPosted = 1104,
+    #[error("Transfer is not held for review")]
+    TransferNotHeld = 1105,
+    #[error("Transfer requires review and cannot be sent through this path")]
+    ReviewNotSupported = 1106,
+    #[error("Transfer exceeds the chain's outflow limit for this window")]
+    RateLimitExceeded = 1107,
+    #[error("Fee ledger has no room for another asset")]
+    FeeLedgerFull = 1046,
+    #[error("Withdrawal exceeds accrued fees")]
+    InsufficientAccruedFees = 1047,
+
+    // Wallet
+    #[error("Wallet already registered")]
+    WalletAlreadyRegistered = 1015,
+    #[error("Nonce already used")]
+    NonceAlreadyUsed = 1016,
+    #[error("Signer does not own the wallet")]
+    WalletOwnerMismatch = 1022,
+    #[error("Basic eIDAS level transaction limit exceeded")]
+    EidasLevelLimitExceeded = 1023,
+    #[error("Daily transfer limit exceeded")]
+    DailyLimitExceeded = 1024,
+    #[error("Multisig owners, threshold or proposal are invalid")]
+    InvalidMultisigConfig = 1052,
+    #[error("Signer is not a multisig owner")]
+    NotMultisigOwner = 1053,
+    #[error("Owner already approved this transaction")]
+    AlreadyApproved = 1054,
+    #[error("Multisig approval threshold not met")]
+    ThresholdNotMet = 1055,
+    #[error("Multisig transaction already executed")]
+    TransactionAlreadyExecuted = 1056,
+    #[error("Wallet owner does not allow transfers to this chain")]
+    ChainNotAllowedForWallet = 1092,
+    #[error("Sender and recipient are the same wallet")]
+    SelfTransfer = 1095,
+    #[error("eIDAS level is below the registration minimum")]
+    EidasLevelBelowMinimum = 1096,
+    #[error("Wallet is quarantined pending eIDAS verification")]
+    WalletPendingVerification = 1097,
+
+    // eIDAS
+    #[error("Certificate must be from a QSCD")]
+    CertificateNotQscd = 1025,
+    #[error("Certificate not yet valid")]
+    CertificateNotYetValid = 1026,
+    #[error("Certificate expired")]
+    CertificateExpired = 1027,
+    #[error("Timestamp required for qualified signature")]
+    TimestampRequired = 1028,
+    #[error("Compliance matrix bands must be ascending and bounded")]
+    InvalidComplianceMatrix = 1040,
+    #[error("KYC verification required for this amount")]
+    KycRequired = 1041,
+    #[error("AML screening required for this amount")]
+    AmlScreeningRequired = 1042,
+    #[error("Certificate DER is malformed or unsupported")]
+    InvalidCertificate = 1058,
+    #[error("Certificate does not declare eIDAS QcCompliance")]
+    CertificateNotQualified = 1059,
+    #[error("Certificate key usage does not permit signing")]
+    InvalidKeyUsage = 1060,
+    #[error("Certificate issuer is not on the trusted list")]
+    UntrustedIssuer = 1063,
+    #[error("Trusted issuer registry is full")]
+    TrustedIssuerRegistryFull = 1064,
+    #[error("Certificate has been revoked by its issuer")]
+    CertificateRevoked = 1065,
+    #[error("Revocation list is full")]
+    RevocationListFull = 1066,
+    #[error("Timestamp token is malformed or unsupported")]
+    InvalidTimestampToken = 1067,
+    #[error("Timestamp token does not cover the supplied data")]
+    TimestampImprintMismatch = 1068,
+    #[error("Timestamp genTime is outside the clock tolerance")]
+    TimestampOutOfTolerance = 1069,
+    #[error("Compliance verification has expired")]
+    ComplianceExpired = 1070,
+    #[error("Compliance expiry must be in the future")]
+    InvalidComplianceExpiry = 1071,
+    #[error("Jurisdiction must be an ISO 3166-1 alpha-2 code")]
+    InvalidJurisdiction = 1072,
+    #[error("Jurisdiction is restricted")]
+    RestrictedJurisdiction = 1073,
+    #[error("Restricted jurisdiction list is full")]
+    RestrictedJurisdictionListFull = 1074,
+    #[error("Signature algorithm is not supported")]
+    UnsupportedSignatureAlgorithm = 1077,
+    #[error("Qualified signature does not verify")]
+    InvalidQualifiedSignature = 1078,
+    #[error("Organization identifier must be a valid LEI, VAT or NTR reference")]
+    InvalidOrganizationIdentifier = 1079,
+    #[error("Certificate is not a qualified certificate for electronic seals")]
+    CertificateNotForSeal = 1080,
+    #[error("Travel Rule payload required for this amount")]
+    TravelRuleRequired = 1081,
+    #[error("Travel Rule payload is malformed")]
+    InvalidTravelRulePayload = 1082,
+    #[error("Attestation does not match the compliance record or has an invalid expiry")]
+    InvalidAttestation = 1100,
+    #[error("Attestation is not signed by the eIDAS authority")]
+    AttestationSignatureMissing = 1101,
+
+    // Arcium
+    #[error("Encrypted payload required")]
+    EncryptedPayloadRequired = 1029,
+    #[error("Zero-knowledge proof required")]
+    ProofRequired = 1030,
+    #[error("Encrypted state too large")]
+    EncryptedStateTooLarge = 1031,
+    #[error("Plaintext cannot be empty")]
+    EmptyPlaintext = 1032,
+    #[error("Public key cannot be empty")]
+    EmptyPublicKey = 1033,
+    #[error("Public inputs cannot be empty")]
+    EmptyPublicInputs = 1034,
+    #[error("Arcium computation is not awaiting a result")]
+    ComputationNotPending = 1083,
+    #[error("Encryption key must be a 32-byte X25519 key")]
+    InvalidEncryptionKey = 1084,
+    #[error("Encrypted payload failed to authenticate")]
+    PayloadDecryptionFailed = 1085,
+    #[error("Range proof is malformed or does not cover the amount")]
+    InvalidRangeProof = 1086,
+    #[error("Range proof was not verified in this transaction")]
+    RangeProofNotVerified = 1087,
+    #[error("Commitment must be a 32-byte Pedersen commitment")]
+    InvalidCommitment = 1088,
+    #[error("Proof does not tie the stored commitment to the new one")]
+    InvalidStateTransition = 1089,
+    #[error("Range proof allows amounts above the disclosed bound")]
+    ConfidentialBoundExceeded = 1091,
+
+    // Governance
+    #[error("Governance quorum and voting period must be non-zero")]
+    InvalidGovernanceConfig = 1108,
+    #[error("Token balance below the required voting weight")]
+    InsufficientVotingWeight = 1109,
+    #[error("Voting on this proposal has closed")]
+    VotingClosed = 1110,
+    #[error("Voting on this proposal is still open")]
+    VotingInProgress = 1111,
+    #[error("Proposal did not reach quorum or a majority")]
+    ProposalNotPassed = 1112,
+    #[error("Proposal timelock has not elapsed")]
+    TimelockActive = 1113,
+    #[error("Voter has already voted on this proposal")]
+    AlreadyVoted = 1114,
+    #[error("Vote tokens have already been withdrawn")]
+    VoteAlreadyWithdrawn = 1115,
+
+    // Config timelock
+    #[error("Sensitive config change must be queued behind the timelock")]
+    ConfigChangeTimelocked = 1116,
+    #[error("No config update is queued")]
+    NoPendingConfigUpdate = 1117,
+
+    // Wallet limits
+    #[error("Wallet has too many pending outbound transfers")]
+    TooManyPendingTransfers = 1118,
+
+    // Wormhole
+    #[error("Wormhole is not configured for this hub")]
+    WormholeNotConfigured = 1119,
+    #[error("VAA was not emitted by the source chain gateway for this transfer")]
+    InvalidVaa = 1120,
+    #[error("Completions must be proven by a posted Wormhole VAA")]
+    VaaRequired = 1121,
+    #[error("Account does not belong to the configured Wormhole core bridge")]
+    InvalidWormholeAccount = 1122,
+
+    // Transports
+    #[error("Evidence kind is not accepted by the source chain's transport")]
+    EvidenceNotSupported = 1123,
+    #[error("Axelar is not configured for this hub or chain")]
+    AxelarNotConfigured = 1124,
+    #[error("Message was not approved by the Axelar gateway for this transfer")]
+    InvalidGatewayApproval = 1125,
+
+    // Batches
+    #[error("Batch entries must be native transfers from the sender to one chain with increasing nonces")]
+    InvalidBatch = 1126,
+    #[error("Destination chain's transport does not carry batched transfers")]
+    BatchNotSupported = 1127,
+
+    // Wrapped assets
+    #[error("Wrapped asset cannot be deregistered while its mint has supply")]
+    WrappedSupplyOutstanding = 1128,
+
+    // Corridors
+    #[error("Transfers between these chains are disabled")]
+    CorridorDisabled = 1129,
+    #[error("Outbound transfer does not leave from the hub's chain")]
+    SourceChainMismatch = 1149,
+
+    // Cancellation
+    #[error(
+        "Destination chain's transport delivered the message on initiation; it cannot be cancelled"
+    )]
+    CancellationNotSupported = 1130,
+
+    // Recipient lists
+    #[error("Recipient is not on the wallet's allowlist")]
+    RecipientNotAllowed = 1131,
+    #[error("Recipient is on the wallet's blocklist")]
+    RecipientBlocked = 1132,
+    #[error("Recipient list is full")]
+    RecipientListFull = 1133,
+
+    // Second factor
+    #[error("Operation must be co-signed by the wallet's second factor")]
+    SecondFactorRequired = 1134,
+    #[error("Second factor recovery delay has not elapsed")]
+    SecondFactorRecoveryLocked = 1135,
+
+    // Session keys
+    #[error("Session key has expired")]
+    SessionKeyExpired = 1136,
+    #[error("Operation is outside the session key's scope")]
+    SessionKeyOutOfScope = 1137,
+
+    // Sponsors
+    #[error("Payer is not an approved sponsor")]
+    SponsorNotApproved = 1138,
+    #[error("Sponsor has used its quota")]
+    SponsorQuotaExhausted = 1139,
+
+    // Social recovery
+    #[error("Guardian list or recovery threshold is invalid")]
+    InvalidGuardianSet = 1140,
+    #[error("Signer is not one of the wallet's guardians")]
+    NotGuardian = 1141,
+    #[error("No recovery is pending for this wallet")]
+    NoPendingRecovery = 1143,
+    #[error("Not enough guardians have approved the recovery")]
+    RecoveryNotApproved = 1144,
+    #[error("Recovery veto window has not elapsed")]
+    RecoveryLocked = 1145,
+
+    // Freezes
+    #[error("Wallet is frozen by the compliance authority")]
+    WalletFrozen = 1146,
+    #[error("Wallet is not frozen")]
+    WalletNotFrozen = 1147,
+}
+
+impl From<CrossChainError> for ProgramError {
+    fn from(e: CrossChainError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl From<MessageError> for CrossChainError {
+    fn from(e: MessageError) -> Self {
+        match e {
+            MessageError::Malformed(_) => CrossChainError::InvalidBridgeMessage,
+            MessageError::PayloadTooLarge { .. } => CrossChainError::PayloadTooLarge,
+            MessageError::InvalidEnvelope(_) => CrossChainError::InvalidCallEnvelope,
+        }
+    }
+}
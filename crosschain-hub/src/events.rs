@@ -0,0 +1,195 @@
+//! Structured program events. Each event is logged with `sol_log_data` as an
+//! 8-byte discriminator followed by the borsh-encoded `HubEvent`, so indexers
+//! can decode them instead of scraping `msg!` text. The discriminator keeps
+//! events apart from the bridge messages the hub also logs. Variants are only
+//! ever appended.
+
+use crate::eidas::{EidasLevel, FreezeReason};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+pub const EVENT_DISCRIMINATOR: [u8; 8] = *b"RQEVENT_";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum HubEvent {
+    TransferInitiated {
+        record: Pubkey,
+        sender: Pubkey,
+        recipient: Pubkey,
+        destination_chain: u64,
+        nonce: u64,
+        amount: u64,
+        fee: u64,
+        token: Option<Pubkey>,
+    },
+    TransferCompleted {
+        record: Pubkey,
+        recipient: Pubkey,
+        amount: u64,
+        fee: u64,
+        /// Confirmed and held in escrow for a `ClaimCrossChain`.
+        claimable: bool,
+    },
+    WalletRegistered {
+        owner: Pubkey,
+        wallet: Pubkey,
+        eidas_level: EidasLevel,
+        pending_verification: bool,
+    },
+    ComplianceVerified {
+        owner: Pubkey,
+        verifier: Pubkey,
+        verified: bool,
+        eidas_level: EidasLevel,
+    },
+    ConfigUpdated {
+        admin: Pubkey,
+        fee_basis_points: u16,
+        paused: bool,
+    },
+    AttestationExported {
+        owner: Pubkey,
+        destination_chain: u64,
+        eidas_level: EidasLevel,
+        expires_at: i64,
+    },
+    /// Escrowed but waiting on `ReleaseHold` or `RejectHold`; relayers act
+    /// only on the `TransferInitiated` that follows a release.
+    TransferHeld {
+        record: Pubkey,
+        sender: Pubkey,
+        destination_chain: u64,
+        amount: u64,
+    },
+    HoldRejected {
+        record: Pubkey,
+        officer: Pubkey,
+        refund: u64,
+    },
+    ConfigUpdateQueued {
+        admin: Pubkey,
+        eta_slot: u64,
+    },
+    /// `sender`'s transfers in the window starting at `window_start`
+    /// exceeded the velocity rules, `record` included.
+    VelocityAlert {
+        sender: Pubkey,
+        record: Pubkey,
+        window_start: u64,
+        transfers: u16,
+        amount: u64,
+        held: bool,
+    },
+    BatchInitiated {
+        batch: Pubkey,
+        sender: Pubkey,
+        destination_chain: u64,
+        transfers: u16,
+        amount: u64,
+        fee: u64,
+    },
+    BatchCompleted {
+        batch: Pubkey,
+        transfers: u16,
+        amount: u64,
+        fee: u64,
+    },
+    /// Relayers must drop the transfer; it can no longer complete.
+    TransferCancelled {
+        record: Pubkey,
+        sender: Pubkey,
+        refund: u64,
+        fee: u64,
+    },
+    SecondFactorUpdated {
+        owner: Pubkey,
+        device: Pubkey,
+        threshold: u64,
+    },
+    /// The owner proposed a new device without the current one; it applies
+    /// at `recovery_slot` unless the current device replaces it first.
+    SecondFactorRecoveryStarted {
+        owner: Pubkey,
+        device: Pubkey,
+        recovery_slot: u64,
+    },
+    SessionKeyIssued {
+        owner: Pubkey,
+        session_key: Pubkey,
+        expires_slot: u64,
+    },
+    SessionKeyRevoked {
+        owner: Pubkey,
+        session_key: Pubkey,
+    },
+    SponsorUpdated {
+        sponsor: Pubkey,
+        enabled: bool,
+        max_sponsored: u64,
+    },
+    GuardiansUpdated {
+        owner: Pubkey,
+        guardians: u8,
+        threshold: u8,
+    },
+    /// A guardian backed moving `owner`'s wallet to `new_owner`; the owner
+    /// can veto until `executable_slot`.
+    RecoveryProposed {
+        owner: Pubkey,
+        new_owner: Pubkey,
+        guardian: Pubkey,
+        approvals: u8,
+        executable_slot: u64,
+    },
+    /// A guardian proposed someone other than the pending `new_owner`; the
+    /// pending recovery is replaced once `objections` reaches the threshold.
+    RecoveryObjected {
+        owner: Pubkey,
+        new_owner: Pubkey,
+        guardian: Pubkey,
+        objections: u8,
+    },
+    RecoveryVetoed {
+        owner: Pubkey,
+        new_owner: Pubkey,
+    },
+    WalletRecovered {
+        old_owner: Pubkey,
+        new_owner: Pubkey,
+    },
+    WalletFrozen {
+        owner: Pubkey,
+        reason: FreezeReason,
+    },
+    WalletUnfrozen {
+        owner: Pubkey,
+    },
+}
+
+impl HubEvent {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = EVENT_DISCRIMINATOR.to_vec();
+        data.extend(self.try_to_vec().unwrap_or_default());
+        data
+    }
+
+    pub fn emit(&self) {
+        sol_log_data(&[&self.encode()]);
+    }
+}
+
+/// Decodes one `sol_log_data` field. Anything that is not a hub event,
+/// including bridge messages, yields `None`.
+pub fn parse_event(data: &[u8]) -> Option<HubEvent> {
+    let body = data.strip_prefix(&EVENT_DISCRIMINATOR)?;
+    HubEvent::try_from_slice(body).ok()
+}
+
+/// Decodes a `Program data: <base64>` line from a transaction's log messages.
+#[cfg(not(target_os = "solana"))]
+pub fn parse_event_log(line: &str) -> Option<HubEvent> {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+    let field = line.strip_prefix("Program data: ")?;
+    parse_event(&BASE64.decode(field.trim()).ok()?)
+}
@@ -0,0 +1,18 @@
+//! Instructions for local and devnet QA: a token faucet, resetting outflow
+//! windows and completing transfers without relayers. The variant is always
+//! part of the instruction set so discriminants stay stable, but the program
+//! only executes it when built with the `test-fixtures` feature; every other
+//! build rejects it as invalid data. Each fixture is signed by the admin.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum FixtureInstruction {
+    /// Mints `amount` of a mint whose authority is the hub mint authority.
+    MintTestTokens { amount: u64 },
+    /// Clears a chain's outflow window so rate-limited flows can be rerun
+    /// without waiting it out.
+    ResetRateLimitWindow,
+    /// Settles an in-flight native transfer as if relayers had confirmed it.
+    ForceCompleteTransfer,
+}
@@ -0,0 +1,167 @@
+//! Token-weighted governance. Holders of the configured SPL token lock tokens
+//! to vote on proposals; a proposal that reaches quorum with more votes for
+//! than against executes after a timelock, signed by the governance authority
+//! PDA. Handing the hub admin to that PDA moves control from a single key to
+//! token holders.
+
+use crate::account::VersionedAccount;
+use crate::error::CrossChainError;
+use crate::multisig::ProposedInstruction;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+pub const GOVERNANCE_SEED: &[u8] = b"governance";
+pub const GOVERNANCE_AUTHORITY_SEED: &[u8] = b"governance_authority";
+pub const PROPOSAL_SEED: &[u8] = b"proposal";
+pub const VOTE_RECORD_SEED: &[u8] = b"vote";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct GovernanceConfig {
+    pub token_mint: Pubkey,
+    /// Tokens a proposer must hold to open a proposal.
+    pub min_proposal_weight: u64,
+    /// Total votes cast, for and against, a proposal needs to pass.
+    pub quorum_votes: u64,
+    pub voting_slots: u64,
+    /// Slots between the end of voting and the earliest execution.
+    pub timelock_slots: u64,
+}
+
+impl GovernanceConfig {
+    pub fn validate(&self) -> Result<bool, CrossChainError> {
+        if self.quorum_votes == 0 || self.voting_slots == 0 {
+            return Err(CrossChainError::InvalidGovernanceConfig);
+        }
+        Ok(true)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Governance {
+    pub config: GovernanceConfig,
+    pub proposal_count: u64,
+    pub bump: u8,
+    pub authority_bump: u8,
+}
+
+impl VersionedAccount for Governance {
+    const DISCRIMINATOR: [u8; 8] = *b"RQGOVERN";
+    const VERSION: u8 = 1;
+}
+
+impl Governance {
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 8 + 1 + 1;
+}
+
+/// Proposal to execute `instruction` as the governance authority, one PDA per
+/// proposal index.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Proposal {
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub instruction: ProposedInstruction,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub voting_ends_slot: u64,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl VersionedAccount for Proposal {
+    const DISCRIMINATOR: [u8; 8] = *b"RQPROPSL";
+    const VERSION: u8 = 1;
+}
+
+impl Proposal {
+    pub fn is_voting(&self, slot: u64) -> bool {
+        slot < self.voting_ends_slot
+    }
+
+    pub fn cast(&mut self, support: bool, weight: u64, slot: u64) -> Result<bool, CrossChainError> {
+        if !self.is_voting(slot) {
+            return Err(CrossChainError::VotingClosed);
+        }
+
+        let tally = if support {
+            &mut self.votes_for
+        } else {
+            &mut self.votes_against
+        };
+        *tally = tally
+            .checked_add(weight)
+            .ok_or(CrossChainError::ArithmeticOverflow)?;
+        Ok(true)
+    }
+
+    pub fn validate_execution(
+        &self,
+        config: &GovernanceConfig,
+        slot: u64,
+    ) -> Result<bool, CrossChainError> {
+        if self.executed {
+            return Err(CrossChainError::TransactionAlreadyExecuted);
+        }
+
+        if self.is_voting(slot) {
+            return Err(CrossChainError::VotingInProgress);
+        }
+
+        let turnout = self.votes_for.saturating_add(self.votes_against);
+        if turnout < config.quorum_votes || self.votes_for <= self.votes_against {
+            return Err(CrossChainError::ProposalNotPassed);
+        }
+
+        if slot < self.voting_ends_slot.saturating_add(config.timelock_slots) {
+            return Err(CrossChainError::TimelockActive);
+        }
+
+        Ok(true)
+    }
+}
+
+/// Tokens a voter locked on one proposal. They are returned by
+/// `WithdrawVote` once voting ends; the record stays so the voter cannot
+/// vote on the same proposal again.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub weight: u64,
+    pub support: bool,
+    pub withdrawn: bool,
+    pub bump: u8,
+}
+
+impl VersionedAccount for VoteRecord {
+    const DISCRIMINATOR: [u8; 8] = *b"RQVOTREC";
+    const VERSION: u8 = 1;
+}
+
+impl VoteRecord {
+    pub const LEN: usize = 32 + 32 + 8 + 1 + 1 + 1;
+}
+
+pub fn get_governance_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GOVERNANCE_SEED], program_id)
+}
+
+/// Signer of executed proposals and owner of the token vault holding locked
+/// votes.
+pub fn get_governance_authority_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GOVERNANCE_AUTHORITY_SEED], program_id)
+}
+
+pub fn get_proposal_address(index: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PROPOSAL_SEED, &index.to_le_bytes()], program_id)
+}
+
+pub fn get_vote_record_address(
+    proposal: &Pubkey,
+    voter: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VOTE_RECORD_SEED, proposal.as_ref(), voter.as_ref()],
+        program_id,
+    )
+}
@@ -0,0 +1,156 @@
+use crate::account::VersionedAccount;
+use crate::domain::SigningDomain;
+use crate::error::CrossChainError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+pub const INTENT_SEED: &[u8] = b"intent";
+pub const INTENT_MESSAGE_DOMAIN: &[u8] = b"rivicq-intent-v1";
+
+/// Seconds the winning solver has after the auction deadline to prove the fill
+/// before the owner may cancel and reclaim the escrow.
+pub const INTENT_FILL_WINDOW: i64 = 3600;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TransferIntentData {
+    pub amount: u64,
+    pub destination_chain: u64,
+    pub recipient: Pubkey,
+    pub max_fee: u64,
+    pub deadline: i64,
+    pub nonce: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub enum IntentStatus {
+    Open,
+    Filled,
+    Cancelled,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TransferIntent {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub destination_chain: u64,
+    pub recipient: Pubkey,
+    pub max_fee: u64,
+    pub deadline: i64,
+    pub nonce: u64,
+    pub status: IntentStatus,
+    pub best_solver: Option<Pubkey>,
+    pub best_fee: u64,
+    pub fill_tx_hash: [u8; 32],
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl VersionedAccount for TransferIntent {
+    const DISCRIMINATOR: [u8; 8] = *b"RQINTENT";
+    const VERSION: u8 = 1;
+}
+
+impl TransferIntent {
+    /// Body size with `best_solver` set; an unbid intent leaves the tail as
+    /// padding.
+    pub const LEN: usize = 32 + 8 + 8 + 32 + 8 + 8 + 8 + 1 + 33 + 8 + 32 + 8 + 1;
+
+    /// Lamports held in escrow by the intent account on top of its rent.
+    pub fn escrow_amount(&self) -> u64 {
+        self.amount.saturating_add(self.max_fee)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct IntentFillData {
+    pub destination_tx_hash: [u8; 32],
+}
+
+pub fn get_intent_address(owner: &Pubkey, nonce: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[INTENT_SEED, owner.as_ref(), &nonce.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Digest an owner signs when authorizing an intent off-chain, e.g. for a
+/// solver to post on their behalf.
+pub fn intent_signing_hash(
+    domain: &SigningDomain,
+    owner: &Pubkey,
+    data: &TransferIntentData,
+) -> [u8; 32] {
+    let payload = data.try_to_vec().unwrap_or_default();
+    domain.hash(INTENT_MESSAGE_DOMAIN, &[owner.as_ref(), &payload])
+}
+
+pub fn validate_intent(data: &TransferIntentData, now: i64) -> Result<bool, CrossChainError> {
+    if data.amount == 0 {
+        return Err(CrossChainError::InvalidIntent);
+    }
+
+    if data.deadline <= now {
+        return Err(CrossChainError::InvalidIntent);
+    }
+
+    if data.amount.checked_add(data.max_fee).is_none() {
+        return Err(CrossChainError::InvalidIntent);
+    }
+
+    Ok(true)
+}
+
+/// Solvers compete on fee: a bid is accepted while the auction is open if it
+/// fits under the owner's `max_fee` and strictly undercuts the current best.
+pub fn validate_solver_bid(
+    intent: &TransferIntent,
+    fee: u64,
+    now: i64,
+) -> Result<bool, CrossChainError> {
+    if intent.status != IntentStatus::Open {
+        return Err(CrossChainError::IntentNotOpen);
+    }
+
+    if now >= intent.deadline {
+        return Err(CrossChainError::BidRejected);
+    }
+
+    if fee > intent.max_fee {
+        return Err(CrossChainError::BidRejected);
+    }
+
+    if intent.best_solver.is_some() && fee >= intent.best_fee {
+        return Err(CrossChainError::BidRejected);
+    }
+
+    Ok(true)
+}
+
+pub fn can_cancel_intent(intent: &TransferIntent, now: i64) -> bool {
+    if intent.status != IntentStatus::Open {
+        return false;
+    }
+
+    intent.best_solver.is_none() || now > intent.deadline.saturating_add(INTENT_FILL_WINDOW)
+}
+
+/// An open intent is expired once its auction closed without bids, or the
+/// winning solver let the fill window lapse. Anyone may then unwind it.
+pub fn is_intent_expired(intent: &TransferIntent, now: i64) -> bool {
+    if intent.status != IntentStatus::Open {
+        return false;
+    }
+
+    match intent.best_solver {
+        None => now >= intent.deadline,
+        Some(_) => now > intent.deadline.saturating_add(INTENT_FILL_WINDOW),
+    }
+}
+
+/// Splits the escrow on fill into the solver payout (amount plus winning fee)
+/// and the refund of unused fee headroom to the owner.
+pub fn settle_intent(intent: &TransferIntent) -> (u64, u64) {
+    let solver_payout = intent.amount.saturating_add(intent.best_fee);
+    let owner_refund = intent.max_fee.saturating_sub(intent.best_fee);
+    (solver_payout, owner_refund)
+}
@@ -0,0 +1,70 @@
+use crate::error::CrossChainError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+pub const KEEPER_JOB_SEED: &[u8] = b"keeper_job";
+pub const KEEPER_TIP_SEED: &[u8] = b"keeper_tip";
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq)]
+pub enum KeeperJobKind {
+    ExpireIntent,
+}
+
+impl KeeperJobKind {
+    pub fn seed(&self) -> [u8; 1] {
+        [*self as u8]
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct KeeperJobConfig {
+    pub tip_per_crank: u64,
+    pub min_interval_slots: u64,
+    pub enabled: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct KeeperJob {
+    pub kind: KeeperJobKind,
+    pub tip_per_crank: u64,
+    pub min_interval_slots: u64,
+    pub enabled: bool,
+    pub last_crank_slot: u64,
+    pub total_cranks: u64,
+    pub total_tips_paid: u64,
+    pub bump: u8,
+    pub tip_bump: u8,
+}
+
+impl KeeperJob {
+    pub const LEN: usize = 1 + 8 + 8 + 1 + 8 + 8 + 8 + 1 + 1;
+}
+
+pub fn get_keeper_job_address(kind: KeeperJobKind, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[KEEPER_JOB_SEED, &kind.seed()], program_id)
+}
+
+pub fn get_keeper_tip_address(job: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[KEEPER_TIP_SEED, job.as_ref()], program_id)
+}
+
+/// Rate-limits cranks so a keeper cannot drain the tip account by spamming
+/// no-op maintenance; the first crank is always allowed.
+pub fn validate_crank(job: &KeeperJob, slot: u64) -> Result<bool, CrossChainError> {
+    if !job.enabled {
+        return Err(CrossChainError::CrankNotAllowed);
+    }
+
+    if job.total_cranks > 0 && slot < job.last_crank_slot.saturating_add(job.min_interval_slots) {
+        return Err(CrossChainError::CrankNotAllowed);
+    }
+
+    Ok(true)
+}
+
+/// Tip owed for a successful crank, capped by what the tip account holds above
+/// its rent-exempt minimum so it is never closed by a payout.
+pub fn payable_tip(job: &KeeperJob, tip_balance: u64, rent_exempt_minimum: u64) -> u64 {
+    job.tip_per_crank
+        .min(tip_balance.saturating_sub(rent_exempt_minimum))
+}
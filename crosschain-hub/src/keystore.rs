@@ -0,0 +1,241 @@
+//! Passphrase-encrypted keystores for relayer and operator keys, and the
+//! rotation flow that moves a relayer's on-chain registration to a new key.
+//!
+//! Keys are sealed with XChaCha20-Poly1305 under a PBKDF2-HMAC-SHA256 key,
+//! with the file's header fields bound in as associated data. The `kdf` field
+//! is versioned so a memory-hard KDF can be added without breaking existing
+//! files.
+
+use crate::relayer::get_relayer_address;
+use crate::CrossChainInstruction;
+use borsh::BorshSerialize;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hmac::Hmac;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signer::{keypair::Keypair, Signer},
+    system_program,
+};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+pub const KEYSTORE_VERSION: u8 = 2;
+pub const KEYSTORE_KDF: &str = "pbkdf2-hmac-sha256";
+/// OWASP's 2023 recommendation for PBKDF2-HMAC-SHA256.
+pub const DEFAULT_KEYSTORE_ITERATIONS: u32 = 600_000;
+/// Keystores derived with fewer iterations are refused, so a file edited to
+/// a trivial work factor cannot be used to brute-force the passphrase.
+pub const MIN_KEYSTORE_ITERATIONS: u32 = 100_000;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+const KEYPAIR_LEN: usize = 64;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum KeystoreError {
+    #[error("unsupported keystore version {0}")]
+    UnsupportedVersion(u8),
+    #[error("unsupported key derivation function {0}")]
+    UnsupportedKdf(String),
+    #[error("{0} key derivation iterations is below the minimum of {MIN_KEYSTORE_ITERATIONS}")]
+    WeakKdf(u32),
+    #[error("keystore is malformed")]
+    Malformed,
+    #[error("wrong passphrase or corrupted keystore")]
+    Authentication,
+    #[error("decrypted key does not match the keystore public key")]
+    KeyMismatch,
+}
+
+/// On-disk keystore, stored as JSON with hex-encoded binary fields.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EncryptedKeystore {
+    pub version: u8,
+    pub pubkey: String,
+    pub kdf: String,
+    pub iterations: u32,
+    pub salt: String,
+    pub nonce: String,
+    /// The encrypted keypair followed by the Poly1305 tag.
+    pub ciphertext: String,
+}
+
+impl EncryptedKeystore {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, KeystoreError> {
+        serde_json::from_str(json).map_err(|_| KeystoreError::Malformed)
+    }
+}
+
+/// Encrypts `keypair` under `passphrase`. Use `DEFAULT_KEYSTORE_ITERATIONS`
+/// unless there is a reason to pay more; anything below
+/// `MIN_KEYSTORE_ITERATIONS` is refused.
+pub fn encrypt_keypair(
+    keypair: &Keypair,
+    passphrase: &str,
+    iterations: u32,
+) -> Result<EncryptedKeystore, KeystoreError> {
+    if iterations < MIN_KEYSTORE_ITERATIONS {
+        return Err(KeystoreError::WeakKdf(iterations));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let pubkey = keypair.pubkey().to_string();
+    let key = derive_key(passphrase, &salt, iterations);
+    let secret = Zeroizing::new(keypair.to_bytes());
+    let ciphertext = XChaCha20Poly1305::new(&(*key).into())
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &secret[..],
+                aad: &keystore_aad(&pubkey, iterations, &salt),
+            },
+        )
+        .map_err(|_| KeystoreError::Malformed)?;
+
+    Ok(EncryptedKeystore {
+        version: KEYSTORE_VERSION,
+        pubkey,
+        kdf: KEYSTORE_KDF.to_string(),
+        iterations,
+        salt: hex::encode(salt),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    })
+}
+
+/// Authenticates and decrypts a keystore. The header fields are checked as
+/// associated data, and the recovered key must match the recorded public key.
+pub fn decrypt_keypair(
+    keystore: &EncryptedKeystore,
+    passphrase: &str,
+) -> Result<Keypair, KeystoreError> {
+    if keystore.version != KEYSTORE_VERSION {
+        return Err(KeystoreError::UnsupportedVersion(keystore.version));
+    }
+
+    if keystore.kdf != KEYSTORE_KDF {
+        return Err(KeystoreError::UnsupportedKdf(keystore.kdf.clone()));
+    }
+
+    if keystore.iterations < MIN_KEYSTORE_ITERATIONS {
+        return Err(KeystoreError::WeakKdf(keystore.iterations));
+    }
+
+    let decode = |field: &str| hex::decode(field).map_err(|_| KeystoreError::Malformed);
+    let salt = decode(&keystore.salt)?;
+    let nonce = decode(&keystore.nonce)?;
+    let ciphertext = decode(&keystore.ciphertext)?;
+    if salt.len() != SALT_LEN
+        || nonce.len() != NONCE_LEN
+        || ciphertext.len() != KEYPAIR_LEN + TAG_LEN
+    {
+        return Err(KeystoreError::Malformed);
+    }
+
+    let key = derive_key(passphrase, &salt, keystore.iterations);
+    let secret = XChaCha20Poly1305::new(&(*key).into())
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: &keystore_aad(&keystore.pubkey, keystore.iterations, &salt),
+            },
+        )
+        .map(Zeroizing::new)
+        .map_err(|_| KeystoreError::Authentication)?;
+    let keypair = Keypair::from_bytes(&secret).map_err(|_| KeystoreError::Malformed)?;
+
+    if keypair.pubkey().to_string() != keystore.pubkey {
+        return Err(KeystoreError::KeyMismatch);
+    }
+
+    Ok(keypair)
+}
+
+/// A relayer key rotation ready to submit. `instruction` must be signed by
+/// both keys; `keystore` should only replace the old file once it lands.
+#[derive(Debug)]
+pub struct RelayerKeyRotation {
+    pub old_authority: Keypair,
+    pub new_authority: Keypair,
+    pub keystore: EncryptedKeystore,
+    pub instruction: Instruction,
+}
+
+/// Unlocks the current relayer keystore, generates a new key sealed under
+/// `passphrase`, and builds the `RotateRelayerKey` instruction that moves the
+/// registration and stake to it in one step.
+pub fn rotate_relayer_keystore(
+    program_id: &Pubkey,
+    state: &Pubkey,
+    current: &EncryptedKeystore,
+    passphrase: &str,
+    iterations: u32,
+) -> Result<RelayerKeyRotation, KeystoreError> {
+    let old_authority = decrypt_keypair(current, passphrase)?;
+    let new_authority = Keypair::new();
+    let keystore = encrypt_keypair(&new_authority, passphrase, iterations)?;
+
+    let data = CrossChainInstruction::RotateRelayerKey
+        .try_to_vec()
+        .map_err(|_| KeystoreError::Malformed)?;
+    let instruction = Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*state, false),
+            AccountMeta::new(
+                get_relayer_address(&old_authority.pubkey(), program_id).0,
+                false,
+            ),
+            AccountMeta::new(old_authority.pubkey(), true),
+            AccountMeta::new(
+                get_relayer_address(&new_authority.pubkey(), program_id).0,
+                false,
+            ),
+            AccountMeta::new(new_authority.pubkey(), true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+
+    Ok(RelayerKeyRotation {
+        old_authority,
+        new_authority,
+        keystore,
+        instruction,
+    })
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    pbkdf2::pbkdf2::<HmacSha256>(passphrase.as_bytes(), salt, iterations, &mut *key);
+    key
+}
+
+/// Associated data covering every header field, so none can be edited
+/// without failing authentication.
+fn keystore_aad(pubkey: &str, iterations: u32, salt: &[u8]) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(1 + KEYSTORE_KDF.len() + pubkey.len() + 4 + salt.len());
+    aad.push(KEYSTORE_VERSION);
+    aad.extend_from_slice(KEYSTORE_KDF.as_bytes());
+    aad.extend_from_slice(pubkey.as_bytes());
+    aad.extend_from_slice(&iterations.to_le_bytes());
+    aad.extend_from_slice(salt);
+    aad
+}
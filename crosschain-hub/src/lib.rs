@@ -1,25 +1,80 @@
+use axelar::{
+    get_axelar_route_address, AxelarAddress, AxelarChainName, AxelarRoute, AXELAR_ROUTE_SEED,
+};
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
+    instruction::AccountMeta,
+    log::sol_log_data,
     msg,
-    program::invoke,
+    program::{invoke, invoke_signed, set_return_data},
     program_error::ProgramError,
+    program_option::COption,
+    program_pack::Pack,
     pubkey::Pubkey,
     rent::Rent,
     system_instruction,
-    sysvar::{clock::Clock, Sysvar},
+    sysvar::{clock::Clock, instructions as sysvar_instructions, Sysvar},
 };
 
+pub mod account;
 pub mod arcium;
+pub mod audit;
+pub mod bounded;
 pub mod bridge;
+pub mod caip;
+pub mod canonical;
+#[cfg(not(target_os = "solana"))]
+pub mod client;
+#[cfg(not(target_os = "solana"))]
+pub mod config;
+#[cfg(all(not(target_os = "solana"), feature = "client"))]
+pub mod devnet;
+pub mod domain;
 pub mod eidas;
+pub mod error;
+pub mod events;
+pub mod fixtures;
+pub mod governance;
+pub mod intent;
+pub mod keeper;
+#[cfg(not(target_os = "solana"))]
+pub mod keystore;
+pub mod merkle;
+pub mod multisig;
+pub mod query;
+pub mod relayer;
+pub mod rfc3161;
+pub mod sigverify;
+pub mod treasury;
 pub mod wallet;
+pub mod x509;
 
+pub use account::*;
 pub use arcium::*;
+pub use audit::*;
+pub use bounded::*;
 pub use bridge::*;
+pub use caip::*;
+pub use canonical::*;
+pub use domain::*;
 pub use eidas::*;
+pub use error::*;
+pub use events::*;
+pub use fixtures::*;
+pub use governance::*;
+pub use intent::*;
+pub use keeper::*;
+pub use merkle::*;
+pub use multisig::*;
+pub use query::*;
+pub use relayer::*;
+pub use rfc3161::*;
+pub use sigverify::*;
+pub use treasury::*;
 pub use wallet::*;
+pub use x509::*;
 
 #[cfg(test)]
 mod tests;
@@ -32,11 +87,29 @@ pub struct CrossChainConfig {
     pub bridge_authority: Pubkey,
     pub eidas_authority: Pubkey,
     pub arcium_program: Pubkey,
-    pub supported_chains: Vec<u64>,
+    pub supported_chains: BoundedVec<u64, MAX_SUPPORTED_CHAINS>,
     pub min_cross_chain_amount: u64,
     pub max_cross_chain_amount: u64,
     pub fee_basis_points: u16,
     pub paused: bool,
+    pub guardian: Pubkey,
+    /// Genesis hash of the cluster the hub is deployed on, fixed at
+    /// initialization and bound into every signing domain.
+    pub genesis_hash: [u8; 32],
+    /// Rejects `UpdateConfig` and `CompleteCrossChain` unless they are
+    /// top-level instructions of the transaction rather than reached by CPI.
+    pub require_top_level: bool,
+    pub registration: RegistrationPolicy,
+    /// Slots a sensitive change must wait between `QueueConfigUpdate` and
+    /// `ExecuteConfigUpdate`. Zero lets `UpdateConfig` apply it directly.
+    pub config_timelock_slots: u64,
+    /// Outbound transfers a wallet may have escrowed at once. Zero leaves
+    /// it uncapped.
+    pub max_pending_transfers: u16,
+    /// Chain id the hub itself is known by. Outbound transfers must name it
+    /// as their source chain, so the corridor policy applied is always the
+    /// hub's own. Fixed once non-zero.
+    pub hub_chain_id: u64,
 }
 
 impl Default for CrossChainConfig {
@@ -46,22 +119,215 @@ impl Default for CrossChainConfig {
             bridge_authority: Pubkey::default(),
             eidas_authority: Pubkey::default(),
             arcium_program: Pubkey::default(),
-            supported_chains: vec![1, 10, 42161],
+            supported_chains: vec![1, 10, 42161].into(),
             min_cross_chain_amount: 1000,
             max_cross_chain_amount: 1_000_000_000,
             fee_basis_points: 25,
             paused: false,
+            guardian: Pubkey::default(),
+            genesis_hash: [0u8; 32],
+            require_top_level: false,
+            registration: RegistrationPolicy::default(),
+            config_timelock_slots: 0,
+            max_pending_transfers: 32,
+            hub_chain_id: 0,
+        }
+    }
+}
+
+pub const STATE_SEED: &[u8] = b"state";
+pub const MAX_SUPPORTED_CHAINS: usize = 64;
+pub const MAX_RELAY_CONFIRMATIONS: usize = 16;
+
+/// Upper bound on the protocol fee; anything above 10% is treated as a
+/// misconfiguration rather than a pricing decision.
+pub const MAX_FEE_BASIS_POINTS: u16 = 1_000;
+
+impl CrossChainConfig {
+    /// Bounds every config must satisfy. A non-zero fee must also charge at
+    /// least one unit at the minimum amount, so the floor cannot be set low
+    /// enough to bridge fee-free.
+    pub fn validate(&self) -> Result<bool, CrossChainError> {
+        if self.fee_basis_points > MAX_FEE_BASIS_POINTS {
+            return Err(CrossChainError::InvalidConfig);
+        }
+
+        if self.min_cross_chain_amount == 0
+            || self.min_cross_chain_amount >= self.max_cross_chain_amount
+        {
+            return Err(CrossChainError::InvalidConfig);
+        }
+
+        if self.supported_chains.is_empty() {
+            return Err(CrossChainError::InvalidConfig);
+        }
+
+        if self.fee_basis_points > 0
+            && calculate_cross_chain_fee(self.min_cross_chain_amount, self.fee_basis_points, 0)?
+                == 0
+        {
+            return Err(CrossChainError::InvalidConfig);
+        }
+
+        Ok(true)
+    }
+
+    /// Whether moving to `next` changes fees, rotates an authority, drops a
+    /// supported chain or shortens the timelock itself. Such changes must be
+    /// queued while a timelock is configured, giving users time to exit.
+    pub fn is_sensitive_change(&self, next: &CrossChainConfig) -> bool {
+        self.fee_basis_points != next.fee_basis_points
+            || self.bridge_authority != next.bridge_authority
+            || self.eidas_authority != next.eidas_authority
+            || self.arcium_program != next.arcium_program
+            || self.guardian != next.guardian
+            || self
+                .supported_chains
+                .iter()
+                .any(|chain| !next.supported_chains.contains(chain))
+            || next.config_timelock_slots < self.config_timelock_slots
+            || self.hub_chain_id != next.hub_chain_id
+    }
+
+    /// Checks an outbound transfer leaves from the hub's own chain; the
+    /// corridor policy is looked up by this id, so a sender must not be
+    /// able to pick another.
+    pub fn check_outbound_source(&self, source_chain: u64) -> Result<bool, CrossChainError> {
+        if source_chain != self.hub_chain_id {
+            return Err(CrossChainError::SourceChainMismatch);
+        }
+
+        Ok(true)
+    }
+
+    /// Checks a sensitive instruction was not reached through CPI, given the
+    /// program of the top-level instruction currently executing.
+    pub fn check_invocation(
+        &self,
+        top_level_program: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<bool, CrossChainError> {
+        if self.require_top_level && top_level_program != program_id {
+            return Err(CrossChainError::CpiNotAllowed);
         }
+
+        Ok(true)
+    }
+}
+
+/// Per-subsystem pause switches, checked alongside the global
+/// `CrossChainConfig.paused` flag.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct PauseFlags(pub u8);
+
+impl PauseFlags {
+    pub const BRIDGE_OUT: PauseFlags = PauseFlags(1 << 0);
+    pub const BRIDGE_IN: PauseFlags = PauseFlags(1 << 1);
+    pub const WALLET_REGISTRATION: PauseFlags = PauseFlags(1 << 2);
+    pub const COMPLIANCE: PauseFlags = PauseFlags(1 << 3);
+    pub const ARCIUM: PauseFlags = PauseFlags(1 << 4);
+    pub const INTERNAL_TRANSFER: PauseFlags = PauseFlags(1 << 5);
+
+    pub fn contains(&self, flags: PauseFlags) -> bool {
+        flags.0 != 0 && self.0 & flags.0 == flags.0
+    }
+
+    pub fn insert(&mut self, flags: PauseFlags) {
+        self.0 |= flags.0;
+    }
+
+    pub fn remove(&mut self, flags: PauseFlags) {
+        self.0 &= !flags.0;
     }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
 pub struct CrossChainState {
     pub config: CrossChainConfig,
+    /// Sum of every initiated and completed transfer amount; a transfer
+    /// escrowed and later completed on the hub counts once per leg.
     pub total_volume: u64,
+    /// Number of transfer legs counted in `total_volume`.
     pub total_transactions: u64,
     pub registered_wallets: u64,
     pub compliance_records: u64,
+    pub bridge_config: BridgeConfig,
+    pub compliance_matrix: ComplianceMatrix,
+    pub pending_admin: Option<Pubkey>,
+    pub pending_config: Option<PendingConfigUpdate>,
+    pub pause_flags: PauseFlags,
+    pub fee_ledger: FeeLedger,
+    pub escrow_ledger: EscrowLedger,
+    pub bump: u8,
+}
+
+impl CrossChainState {
+    pub fn is_paused(&self, subsystem: PauseFlags) -> bool {
+        self.config.paused || self.pause_flags.contains(subsystem)
+    }
+
+    pub fn signing_domain(&self, program_id: &Pubkey) -> SigningDomain {
+        SigningDomain::new(*program_id, self.config.genesis_hash)
+    }
+
+    /// Counts one transfer leg in the hub totals. Neither total changes if
+    /// either would overflow.
+    pub fn record_volume(&mut self, amount: u64) -> Result<(), CrossChainError> {
+        let total_volume = self
+            .total_volume
+            .checked_add(amount)
+            .ok_or(CrossChainError::ArithmeticOverflow)?;
+        let total_transactions = self
+            .total_transactions
+            .checked_add(1)
+            .ok_or(CrossChainError::ArithmeticOverflow)?;
+        self.total_volume = total_volume;
+        self.total_transactions = total_transactions;
+        Ok(())
+    }
+
+    /// Fees in force, stamped as effective from `slot`.
+    pub fn fee_schedule(&self, slot: u64) -> FeeSchedule {
+        FeeSchedule {
+            fee_basis_points: self.config.fee_basis_points,
+            relayer_fee: self.bridge_config.relayer_fee,
+            effective_slot: slot,
+        }
+    }
+
+    /// Terms a transfer on `policy`'s corridor initiated at `slot` is held
+    /// to, at the corridor's rate rather than the hub's base rate.
+    pub fn transfer_terms(
+        &self,
+        policy: &ChainPairPolicy,
+        slot: u64,
+    ) -> Result<TransferTerms, CrossChainError> {
+        Ok(TransferTerms {
+            fee_basis_points: policy.fee_basis_points(self.config.fee_basis_points)?,
+            relayer_fee: self.bridge_config.relayer_fee,
+            claim_delivery: self.bridge_config.claim_delivery,
+            expires_slot: match self.bridge_config.terms_grace_slots {
+                0 => u64::MAX,
+                grace => slot.saturating_add(grace),
+            },
+        })
+    }
+}
+
+/// Config queued by `QueueConfigUpdate`, executable from `eta_slot`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct PendingConfigUpdate {
+    pub config: CrossChainConfig,
+    pub eta_slot: u64,
+}
+
+impl VersionedAccount for CrossChainState {
+    const DISCRIMINATOR: [u8; 8] = *b"RQSTATE_";
+    const VERSION: u8 = 3;
+}
+
+pub fn get_state_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[STATE_SEED], program_id)
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
@@ -86,6 +352,7 @@ pub enum CrossChainInstruction {
     },
     CompleteCrossChain {
         transfer_data: bridge::CrossChainTransferData,
+        confirmations: BoundedVec<bridge::RelayConfirmation, MAX_RELAY_CONFIRMATIONS>,
     },
     CreateQualifiedSignature {
         signature_data: eidas::QualifiedSignatureData,
@@ -99,14 +366,287 @@ pub enum CrossChainInstruction {
     ProcessArciumTransaction {
         arcium_data: arcium::ArciumTransactionData,
     },
+    PostIntent {
+        intent_data: intent::TransferIntentData,
+    },
+    BidIntent {
+        fee: u64,
+    },
+    FillIntent {
+        fill_data: intent::IntentFillData,
+    },
+    CancelIntent,
+    RegisterKeeperJob {
+        kind: keeper::KeeperJobKind,
+        job_config: keeper::KeeperJobConfig,
+    },
+    UpdateKeeperJob {
+        job_config: keeper::KeeperJobConfig,
+    },
+    FundKeeperJob {
+        amount: u64,
+    },
+    CrankKeeperJob,
+    RegisterChain {
+        chain_id: u64,
+        chain_data: bridge::ChainConfigData,
+    },
+    UpdateChain {
+        chain_data: bridge::ChainConfigData,
+    },
+    UpdateWalletKey {
+        public_key: BoundedBytes<{ wallet::MAX_WALLET_PUBLIC_KEY_LEN }>,
+    },
+    UpdateBridgeConfig {
+        bridge_config: bridge::BridgeConfig,
+    },
+    TripBreaker {
+        scope: bridge::BreakerScope,
+    },
+    ResetBreaker,
+    RegisterWrappedAsset {
+        chain_id: u64,
+        remote_token: Pubkey,
+        remote_decimals: u8,
+    },
+    UpdateComplianceMatrix {
+        matrix: eidas::ComplianceMatrix,
+    },
+    StakeRelayer {
+        amount: u64,
+    },
+    SlashRelayer {
+        amount: u64,
+    },
+    ProposeAdmin {
+        new_admin: Option<Pubkey>,
+    },
+    AcceptAdmin,
+    SetPause {
+        flags: PauseFlags,
+        paused: bool,
+    },
+    WithdrawProtocolFees {
+        mint: Option<Pubkey>,
+        amount: u64,
+    },
+    ClaimRelayerFees {
+        mint: Option<Pubkey>,
+        amount: u64,
+    },
+    DistributeCrossChain {
+        transfer_data: bridge::CrossChainTransferData,
+        shares: BoundedVec<bridge::DistributionShare, { bridge::MAX_DISTRIBUTION_RECIPIENTS }>,
+    },
+    ClaimCrossChain,
+    RefundCrossChain,
+    RedeemWrapped {
+        transfer_data: bridge::CrossChainTransferData,
+    },
+    Reconcile {
+        mint: Pubkey,
+    },
+    RegisterCaip2Chain {
+        chain_id: caip::Caip2ChainId,
+        chain_data: bridge::ChainConfigData,
+    },
+    CreateMultisig {
+        seed: u64,
+        config: multisig::MultisigConfig,
+    },
+    ProposeTransaction {
+        instruction: multisig::ProposedInstruction,
+    },
+    ApproveTransaction,
+    ExecuteTransaction,
+    AddTrustedIssuer {
+        issuer_hash: [u8; 32],
+    },
+    RevokeTrustedIssuer {
+        issuer_hash: [u8; 32],
+    },
+    RevokeCertificate {
+        issuer_hash: [u8; 32],
+        serial_number: BoundedBytes<{ eidas::MAX_SERIAL_NUMBER_LEN }>,
+    },
+    RenewCompliance {
+        expiry_date: i64,
+    },
+    AddRestrictedJurisdiction {
+        jurisdiction: BoundedString<{ eidas::MAX_JURISDICTION_LEN }>,
+    },
+    RemoveRestrictedJurisdiction {
+        jurisdiction: BoundedString<{ eidas::MAX_JURISDICTION_LEN }>,
+    },
+    AcquireRelayerLease {
+        duration_slots: u64,
+    },
+    ReleaseRelayerLease,
+    RotateRelayerKey,
+    CreateQualifiedSeal {
+        seal_data: eidas::QualifiedSealData,
+    },
+    VerifyQualifiedSeal {
+        seal_data: eidas::QualifiedSealData,
+    },
+    ArciumCallback {
+        success: bool,
+        result: BoundedBytes<{ arcium::MAX_COMPUTATION_RESULT_LEN }>,
+    },
+    InitEncryptedState {
+        state_data: arcium::EncryptedStateData,
+    },
+    UpdateEncryptedState {
+        state_data: arcium::EncryptedStateData,
+    },
+    InitiateConfidentialCrossChain {
+        transfer_data: bridge::CrossChainTransferData,
+        proof: arcium::ConfidentialTransferProof,
+    },
+    GetConfig,
+    GetWalletStatus {
+        owner: Pubkey,
+    },
+    GetTransferStatus {
+        sender: Pubkey,
+        nonce: u64,
+    },
+    PrecheckRecipient {
+        sender: Pubkey,
+        recipient: Pubkey,
+        destination_chain: u64,
+        amount: u64,
+    },
+    UpdateWalletConfig {
+        config: wallet::WalletConfig,
+    },
+    MigrateAccount {
+        kind: account::AccountKind,
+    },
+    TransferInternal {
+        amount: u64,
+        mint: Option<Pubkey>,
+    },
+    GetStatistics,
+    ExportAttestation {
+        attestation: eidas::SignedComplianceAttestation,
+    },
+    PostStateRoot {
+        chain_id: u64,
+        block_number: u64,
+        root: [u8; 32],
+        signers: BoundedVec<Pubkey, MAX_RELAY_SIGNATURES>,
+    },
+    CompleteCrossChainWithProof {
+        transfer_data: bridge::CrossChainTransferData,
+        proof: merkle::InclusionProof,
+    },
+    ReleaseHold,
+    RejectHold,
+    SetRateLimit {
+        max_outflow: u64,
+        window_slots: u64,
+    },
+    ConfigureGovernance {
+        config: governance::GovernanceConfig,
+    },
+    CreateProposal {
+        instruction: multisig::ProposedInstruction,
+    },
+    CastVote {
+        support: bool,
+        weight: u64,
+    },
+    WithdrawVote,
+    ExecuteProposal,
+    ClaimFees {
+        mint: Option<Pubkey>,
+    },
+    PublishTreasuryReport,
+    Fixture {
+        instruction: fixtures::FixtureInstruction,
+    },
+    QueueConfigUpdate {
+        config: CrossChainConfig,
+    },
+    ExecuteConfigUpdate,
+    CompleteCrossChainWithVaa {
+        transfer_data: bridge::CrossChainTransferData,
+    },
+    CompleteCrossChainWithEvidence {
+        transfer_data: bridge::CrossChainTransferData,
+        evidence: bridge::TransportEvidence,
+    },
+    SetAxelarRoute {
+        chain_id: u64,
+        chain_name: AxelarChainName,
+        gateway_address: AxelarAddress,
+    },
+    InitiateCrossChainBatch {
+        transfers: BoundedVec<bridge::CrossChainTransferData, MAX_BATCH_TRANSFERS>,
+    },
+    CompleteCrossChainBatch {
+        evidence: bridge::TransportEvidence,
+    },
+    RefundCrossChainBatch,
+    DeregisterWrappedAsset {
+        chain_id: u64,
+        remote_token: Pubkey,
+    },
+    SetChainPairPolicy {
+        source_chain: u64,
+        destination_chain: u64,
+        policy: bridge::ChainPairPolicyData,
+    },
+    CancelCrossChain,
+    AddRecipientListEntry {
+        kind: wallet::RecipientListKind,
+        recipient: Pubkey,
+    },
+    RemoveRecipientListEntry {
+        kind: wallet::RecipientListKind,
+        recipient: Pubkey,
+    },
+    RegisterSecondFactor {
+        threshold: u64,
+    },
+    RecoverSecondFactor,
+    IssueSessionKey {
+        session_key: Pubkey,
+        scope: wallet::SessionKeyData,
+    },
+    RevokeSessionKey {
+        session_key: Pubkey,
+    },
+    SetSponsor {
+        sponsor: Pubkey,
+        enabled: bool,
+        max_sponsored: u64,
+    },
+    SetGuardians {
+        guardians: BoundedVec<Pubkey, MAX_GUARDIANS>,
+        threshold: u8,
+    },
+    ProposeRecovery {
+        new_owner: Pubkey,
+    },
+    VetoRecovery,
+    ExecuteRecovery,
+    FreezeWallet {
+        reason: FreezeReason,
+    },
+    UnfreezeWallet,
 }
 
+#[cfg(all(target_os = "solana", not(feature = "no-entrypoint")))]
+solana_program::entrypoint!(process_instruction);
+
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
-    let instruction = CrossChainInstruction::try_from_slice(data)?;
+    let instruction: CrossChainInstruction = decode_canonical(data)?;
 
     match instruction {
         CrossChainInstruction::Initialize { config } => initialize(program_id, accounts, config),
@@ -125,9 +665,15 @@ pub fn process_instruction(
         CrossChainInstruction::InitiateCrossChain { transfer_data } => {
             initiate_cross_chain(program_id, accounts, transfer_data)
         }
-        CrossChainInstruction::CompleteCrossChain { transfer_data } => {
-            complete_cross_chain(program_id, accounts, transfer_data)
-        }
+        CrossChainInstruction::CompleteCrossChain {
+            transfer_data,
+            confirmations,
+        } => complete_cross_chain(
+            program_id,
+            accounts,
+            transfer_data,
+            TransportEvidence::Confirmations(confirmations),
+        ),
         CrossChainInstruction::CreateQualifiedSignature { signature_data } => {
             create_qualified_signature(program_id, accounts, signature_data)
         }
@@ -140,309 +686,8339 @@ pub fn process_instruction(
         CrossChainInstruction::ProcessArciumTransaction { arcium_data } => {
             process_arcium_transaction(program_id, accounts, arcium_data)
         }
+        CrossChainInstruction::PostIntent { intent_data } => {
+            post_intent(program_id, accounts, intent_data)
+        }
+        CrossChainInstruction::BidIntent { fee } => bid_intent(program_id, accounts, fee),
+        CrossChainInstruction::FillIntent { fill_data } => {
+            fill_intent(program_id, accounts, fill_data)
+        }
+        CrossChainInstruction::CancelIntent => cancel_intent(program_id, accounts),
+        CrossChainInstruction::RegisterKeeperJob { kind, job_config } => {
+            register_keeper_job(program_id, accounts, kind, job_config)
+        }
+        CrossChainInstruction::UpdateKeeperJob { job_config } => {
+            update_keeper_job(program_id, accounts, job_config)
+        }
+        CrossChainInstruction::FundKeeperJob { amount } => {
+            fund_keeper_job(program_id, accounts, amount)
+        }
+        CrossChainInstruction::CrankKeeperJob => crank_keeper_job(program_id, accounts),
+        CrossChainInstruction::RegisterChain {
+            chain_id,
+            chain_data,
+        } => register_chain(program_id, accounts, chain_id, chain_data),
+        CrossChainInstruction::UpdateChain { chain_data } => {
+            update_chain(program_id, accounts, chain_data)
+        }
+        CrossChainInstruction::UpdateWalletKey { public_key } => {
+            update_wallet_key(program_id, accounts, public_key.into_inner())
+        }
+        CrossChainInstruction::UpdateBridgeConfig { bridge_config } => {
+            update_bridge_config(program_id, accounts, bridge_config)
+        }
+        CrossChainInstruction::TripBreaker { scope } => trip_breaker(program_id, accounts, scope),
+        CrossChainInstruction::ResetBreaker => reset_breaker(program_id, accounts),
+        CrossChainInstruction::RegisterWrappedAsset {
+            chain_id,
+            remote_token,
+            remote_decimals,
+        } => register_wrapped_asset(
+            program_id,
+            accounts,
+            chain_id,
+            remote_token,
+            remote_decimals,
+        ),
+        CrossChainInstruction::UpdateComplianceMatrix { matrix } => {
+            update_compliance_matrix(program_id, accounts, matrix)
+        }
+        CrossChainInstruction::StakeRelayer { amount } => {
+            stake_relayer(program_id, accounts, amount)
+        }
+        CrossChainInstruction::SlashRelayer { amount } => {
+            slash_relayer(program_id, accounts, amount)
+        }
+        CrossChainInstruction::ProposeAdmin { new_admin } => {
+            propose_admin(program_id, accounts, new_admin)
+        }
+        CrossChainInstruction::AcceptAdmin => accept_admin(program_id, accounts),
+        CrossChainInstruction::SetPause { flags, paused } => {
+            set_pause(program_id, accounts, flags, paused)
+        }
+        CrossChainInstruction::WithdrawProtocolFees { mint, amount } => {
+            withdraw_treasury_fees(program_id, accounts, mint, amount, false)
+        }
+        CrossChainInstruction::ClaimRelayerFees { mint, amount } => {
+            withdraw_treasury_fees(program_id, accounts, mint, amount, true)
+        }
+        CrossChainInstruction::DistributeCrossChain {
+            transfer_data,
+            shares,
+        } => distribute_cross_chain(program_id, accounts, transfer_data, shares.into_inner()),
+        CrossChainInstruction::ClaimCrossChain => claim_cross_chain(program_id, accounts),
+        CrossChainInstruction::RefundCrossChain => refund_cross_chain(program_id, accounts),
+        CrossChainInstruction::RedeemWrapped { transfer_data } => {
+            redeem_wrapped(program_id, accounts, transfer_data)
+        }
+        CrossChainInstruction::Reconcile { mint } => reconcile(program_id, accounts, mint),
+        CrossChainInstruction::RegisterCaip2Chain {
+            chain_id,
+            chain_data,
+        } => register_chain_with_caip2(program_id, accounts, chain_id, chain_data),
+        CrossChainInstruction::CreateMultisig { seed, config } => {
+            create_multisig(program_id, accounts, seed, config)
+        }
+        CrossChainInstruction::ProposeTransaction { instruction } => {
+            propose_transaction(program_id, accounts, instruction)
+        }
+        CrossChainInstruction::ApproveTransaction => approve_transaction(program_id, accounts),
+        CrossChainInstruction::ExecuteTransaction => execute_transaction(program_id, accounts),
+        CrossChainInstruction::AddTrustedIssuer { issuer_hash } => {
+            update_trusted_issuers(program_id, accounts, issuer_hash, true)
+        }
+        CrossChainInstruction::RevokeTrustedIssuer { issuer_hash } => {
+            update_trusted_issuers(program_id, accounts, issuer_hash, false)
+        }
+        CrossChainInstruction::RevokeCertificate {
+            issuer_hash,
+            serial_number,
+        } => revoke_certificate(
+            program_id,
+            accounts,
+            issuer_hash,
+            serial_number.into_inner(),
+        ),
+        CrossChainInstruction::RenewCompliance { expiry_date } => {
+            renew_compliance(program_id, accounts, expiry_date)
+        }
+        CrossChainInstruction::AddRestrictedJurisdiction { jurisdiction } => {
+            update_restricted_jurisdictions(program_id, accounts, jurisdiction.into_inner(), true)
+        }
+        CrossChainInstruction::RemoveRestrictedJurisdiction { jurisdiction } => {
+            update_restricted_jurisdictions(program_id, accounts, jurisdiction.into_inner(), false)
+        }
+        CrossChainInstruction::AcquireRelayerLease { duration_slots } => {
+            acquire_relayer_lease(program_id, accounts, duration_slots)
+        }
+        CrossChainInstruction::ReleaseRelayerLease => release_relayer_lease(program_id, accounts),
+        CrossChainInstruction::RotateRelayerKey => rotate_relayer_key(program_id, accounts),
+        CrossChainInstruction::CreateQualifiedSeal { seal_data } => {
+            create_qualified_seal(program_id, accounts, seal_data)
+        }
+        CrossChainInstruction::VerifyQualifiedSeal { seal_data } => {
+            verify_qualified_seal_on_chain(program_id, accounts, seal_data)
+        }
+        CrossChainInstruction::ArciumCallback { success, result } => {
+            arcium_callback(program_id, accounts, success, result.into_inner())
+        }
+        CrossChainInstruction::InitEncryptedState { state_data } => {
+            init_encrypted_state(program_id, accounts, state_data)
+        }
+        CrossChainInstruction::UpdateEncryptedState { state_data } => {
+            update_encrypted_state(program_id, accounts, state_data)
+        }
+        CrossChainInstruction::InitiateConfidentialCrossChain {
+            transfer_data,
+            proof,
+        } => initiate_confidential_cross_chain(program_id, accounts, transfer_data, proof),
+        CrossChainInstruction::GetConfig => get_config(program_id, accounts),
+        CrossChainInstruction::GetWalletStatus { owner } => {
+            get_wallet_status(program_id, accounts, &owner)
+        }
+        CrossChainInstruction::GetTransferStatus { sender, nonce } => {
+            get_transfer_status(program_id, accounts, &sender, nonce)
+        }
+        CrossChainInstruction::PrecheckRecipient {
+            sender,
+            recipient,
+            destination_chain,
+            amount,
+        } => precheck_recipient(
+            program_id,
+            accounts,
+            &sender,
+            &recipient,
+            destination_chain,
+            amount,
+        ),
+        CrossChainInstruction::UpdateWalletConfig { config } => {
+            update_wallet_config(program_id, accounts, config)
+        }
+        CrossChainInstruction::MigrateAccount { kind } => {
+            migrate_account(program_id, accounts, kind)
+        }
+        CrossChainInstruction::TransferInternal { amount, mint } => {
+            transfer_internal(program_id, accounts, amount, mint)
+        }
+        CrossChainInstruction::GetStatistics => get_statistics(program_id, accounts),
+        CrossChainInstruction::ExportAttestation { attestation } => {
+            export_attestation(program_id, accounts, attestation)
+        }
+        CrossChainInstruction::PostStateRoot {
+            chain_id,
+            block_number,
+            root,
+            signers,
+        } => post_state_root(
+            program_id,
+            accounts,
+            chain_id,
+            block_number,
+            root,
+            signers.into_inner(),
+        ),
+        CrossChainInstruction::CompleteCrossChainWithProof {
+            transfer_data,
+            proof,
+        } => complete_cross_chain(
+            program_id,
+            accounts,
+            transfer_data,
+            TransportEvidence::InclusionProof(proof),
+        ),
+        CrossChainInstruction::ReleaseHold => release_hold(program_id, accounts),
+        CrossChainInstruction::RejectHold => reject_hold(program_id, accounts),
+        CrossChainInstruction::SetRateLimit {
+            max_outflow,
+            window_slots,
+        } => set_rate_limit(program_id, accounts, max_outflow, window_slots),
+        CrossChainInstruction::ConfigureGovernance { config } => {
+            configure_governance(program_id, accounts, config)
+        }
+        CrossChainInstruction::CreateProposal { instruction } => {
+            create_proposal(program_id, accounts, instruction)
+        }
+        CrossChainInstruction::CastVote { support, weight } => {
+            cast_vote(program_id, accounts, support, weight)
+        }
+        CrossChainInstruction::WithdrawVote => withdraw_vote(program_id, accounts),
+        CrossChainInstruction::ExecuteProposal => execute_proposal(program_id, accounts),
+        CrossChainInstruction::ClaimFees { mint } => claim_fees(program_id, accounts, mint),
+        CrossChainInstruction::PublishTreasuryReport => {
+            publish_treasury_report(program_id, accounts)
+        }
+        CrossChainInstruction::Fixture { instruction } => {
+            process_fixture(program_id, accounts, instruction)
+        }
+        CrossChainInstruction::QueueConfigUpdate { config } => {
+            queue_config_update(program_id, accounts, config)
+        }
+        CrossChainInstruction::ExecuteConfigUpdate => execute_config_update(program_id, accounts),
+        CrossChainInstruction::CompleteCrossChainWithVaa { transfer_data } => {
+            complete_cross_chain(program_id, accounts, transfer_data, TransportEvidence::Vaa)
+        }
+        CrossChainInstruction::CompleteCrossChainWithEvidence {
+            transfer_data,
+            evidence,
+        } => complete_cross_chain(program_id, accounts, transfer_data, evidence),
+        CrossChainInstruction::SetAxelarRoute {
+            chain_id,
+            chain_name,
+            gateway_address,
+        } => set_axelar_route(program_id, accounts, chain_id, chain_name, gateway_address),
+        CrossChainInstruction::InitiateCrossChainBatch { transfers } => {
+            initiate_cross_chain_batch(program_id, accounts, transfers.into_inner())
+        }
+        CrossChainInstruction::CompleteCrossChainBatch { evidence } => {
+            complete_cross_chain_batch(program_id, accounts, evidence)
+        }
+        CrossChainInstruction::RefundCrossChainBatch => {
+            refund_cross_chain_batch(program_id, accounts)
+        }
+        CrossChainInstruction::DeregisterWrappedAsset {
+            chain_id,
+            remote_token,
+        } => deregister_wrapped_asset(program_id, accounts, chain_id, remote_token),
+        CrossChainInstruction::SetChainPairPolicy {
+            source_chain,
+            destination_chain,
+            policy,
+        } => set_chain_pair_policy(
+            program_id,
+            accounts,
+            source_chain,
+            destination_chain,
+            policy,
+        ),
+        CrossChainInstruction::CancelCrossChain => cancel_cross_chain(program_id, accounts),
+        CrossChainInstruction::AddRecipientListEntry { kind, recipient } => {
+            update_recipient_list(program_id, accounts, kind, recipient, true)
+        }
+        CrossChainInstruction::RemoveRecipientListEntry { kind, recipient } => {
+            update_recipient_list(program_id, accounts, kind, recipient, false)
+        }
+        CrossChainInstruction::RegisterSecondFactor { threshold } => {
+            register_second_factor(program_id, accounts, threshold)
+        }
+        CrossChainInstruction::RecoverSecondFactor => recover_second_factor(program_id, accounts),
+        CrossChainInstruction::IssueSessionKey { session_key, scope } => {
+            issue_session_key(program_id, accounts, session_key, scope)
+        }
+        CrossChainInstruction::RevokeSessionKey { session_key } => {
+            revoke_session_key(program_id, accounts, session_key)
+        }
+        CrossChainInstruction::SetSponsor {
+            sponsor,
+            enabled,
+            max_sponsored,
+        } => set_sponsor(program_id, accounts, sponsor, enabled, max_sponsored),
+        CrossChainInstruction::SetGuardians {
+            guardians,
+            threshold,
+        } => set_guardians(program_id, accounts, guardians, threshold),
+        CrossChainInstruction::ProposeRecovery { new_owner } => {
+            propose_recovery(program_id, accounts, new_owner)
+        }
+        CrossChainInstruction::VetoRecovery => veto_recovery(program_id, accounts),
+        CrossChainInstruction::ExecuteRecovery => execute_recovery(program_id, accounts),
+        CrossChainInstruction::FreezeWallet { reason } => {
+            set_wallet_frozen(program_id, accounts, Some(reason))
+        }
+        CrossChainInstruction::UnfreezeWallet => set_wallet_frozen(program_id, accounts, None),
     }
 }
 
-fn initialize(
+fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    new_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
     program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    config: CrossChainConfig,
+    space: usize,
+    extra_lamports: u64,
+    signer_seeds: &[&[u8]],
 ) -> ProgramResult {
-    let account_info_iter = &mut accounts.iter();
-    let state_account = next_account_info(account_info_iter)?;
-    let admin = next_account_info(account_info_iter)?;
-    let system_program = next_account_info(account_info_iter)?;
-
-    if !admin.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
-    }
-
-    let state = CrossChainState {
-        config: CrossChainConfig {
-            admin: *admin.key,
-            ..config
-        },
-        ..Default::default()
-    };
-
     let rent = Rent::get()?;
-    let space = state.try_to_vec()?.len();
+    let lamports = rent
+        .minimum_balance(space)
+        .checked_add(extra_lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
 
-    invoke(
+    invoke_signed(
         &system_instruction::create_account(
-            admin.key,
-            state_account.key,
-            rent.minimum_balance(space),
+            payer.key,
+            new_account.key,
+            lamports,
             space as u64,
             program_id,
         ),
-        &[admin.clone(), state_account.clone(), system_program.clone()],
-    )?;
-
-    state.serialize(&mut &mut state_account.data.borrow_mut()[..])?;
+        &[payer.clone(), new_account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )
+}
 
-    msg!("Cross-chain hub initialized successfully");
+/// Writes the hub state, resizing the account when variable-length config
+/// (supported chains or tokens) changed the serialized length.
+fn write_state<'a>(
+    state_account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    state: &CrossChainState,
+) -> ProgramResult {
+    let data = state.pack()?;
+    if data.len() != state_account.data_len() {
+        resize_account(state_account, payer, system_program, data.len())?;
+    }
+    state_account.data.borrow_mut()[..data.len()].copy_from_slice(&data);
     Ok(())
 }
 
-fn update_config(
-    _program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    config: CrossChainConfig,
+/// Grows or shrinks a program-owned account, topping up rent from `payer` when
+/// the new size needs a larger rent-exempt balance.
+fn resize_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    new_len: usize,
 ) -> ProgramResult {
-    let account_info_iter = &mut accounts.iter();
-    let state_account = next_account_info(account_info_iter)?;
-    let admin = next_account_info(account_info_iter)?;
+    let required = Rent::get()?.minimum_balance(new_len);
+    let shortfall = required.saturating_sub(account.lamports());
 
-    if !admin.is_signer {
-        return Err(ProgramError::MissingRequiredSignature);
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, shortfall),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
     }
 
-    let mut state = CrossChainState::try_from_slice(&state_account.data.borrow())?;
-
-    if state.config.admin != *admin.key {
-        return Err(ProgramError::Custom(1));
-    }
+    account.realloc(new_len, false)
+}
 
-    state.config = config;
-    state.serialize(&mut &mut state_account.data.borrow_mut()[..])?;
+/// Moves lamports out of a program-owned account without a system CPI.
+fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    let from_balance = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let to_balance = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
 
-    msg!("Config updated successfully");
+    **from.try_borrow_mut_lamports()? = from_balance;
+    **to.try_borrow_mut_lamports()? = to_balance;
     Ok(())
 }
 
-fn register_wallet(
+/// Loads the hub state, rejecting any account other than the program-owned
+/// state PDA so a forged look-alike cannot stand in for the real config.
+fn load_state(
+    program_id: &Pubkey,
+    state_account: &AccountInfo,
+) -> Result<CrossChainState, ProgramError> {
+    if state_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let state = CrossChainState::unpack(&state_account.data.borrow())?;
+    let expected = Pubkey::create_program_address(&[STATE_SEED, &[state.bump]], program_id)
+        .map_err(|_| ProgramError::InvalidSeeds)?;
+    if expected != *state_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(state)
+}
+
+/// Brings an account up to the current layout. A header at an older version
+/// is upgraded in place. An account written by the first release, before
+/// headers and PDAs existed, moves to its PDA and the original is closed;
+/// the hub admin migrates the state and each owner their own wallet.
+fn migrate_account(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    wallet_data: WalletData,
+    kind: AccountKind,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let state_account = next_account_info(account_info_iter)?;
-    let wallet_account = next_account_info(account_info_iter)?;
-    let owner = next_account_info(account_info_iter)?;
+    let legacy_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
     let system_program = next_account_info(account_info_iter)?;
 
-    if !owner.is_signer {
+    if !payer.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    let mut state = CrossChainState::try_from_slice(&state_account.data.borrow())?;
+    if legacy_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
 
-    let wallet = Wallet {
-        owner: *owner.key,
+    let tagged = match kind {
+        AccountKind::State => CrossChainState::is_tagged(&legacy_account.data.borrow()),
+        AccountKind::Wallet => Wallet::is_tagged(&legacy_account.data.borrow()),
+    };
+    if tagged {
+        return upgrade_account(program_id, legacy_account, payer, system_program, kind);
+    }
+
+    let account = next_account_info(account_info_iter)?;
+
+    match kind {
+        AccountKind::State => {
+            let legacy = LegacyStateV0::try_from_slice(&legacy_account.data.borrow())?;
+            if legacy.config.admin != *payer.key {
+                return Err(CrossChainError::Unauthorized.into());
+            }
+
+            let (expected, bump) = get_state_address(program_id);
+            if expected != *account.key {
+                return Err(ProgramError::InvalidSeeds);
+            }
+
+            let data = legacy.into_state(bump).pack()?;
+            create_pda_account(
+                payer,
+                account,
+                system_program,
+                program_id,
+                data.len(),
+                0,
+                &[STATE_SEED, &[bump]],
+            )?;
+            account.data.borrow_mut().copy_from_slice(&data);
+        }
+        AccountKind::Wallet => {
+            let legacy = LegacyWalletV0::try_from_slice(&legacy_account.data.borrow())?;
+            if legacy.owner != *payer.key {
+                return Err(CrossChainError::Unauthorized.into());
+            }
+
+            let (expected, bump) = get_wallet_address(&legacy.owner, program_id);
+            if expected != *account.key {
+                return Err(ProgramError::InvalidSeeds);
+            }
+
+            let data = Wallet::from(legacy).pack()?;
+            create_pda_account(
+                payer,
+                account,
+                system_program,
+                program_id,
+                data.len(),
+                0,
+                &[WALLET_SEED, payer.key.as_ref(), &[bump]],
+            )?;
+            account.data.borrow_mut().copy_from_slice(&data);
+        }
+    }
+
+    transfer_lamports(legacy_account, payer, legacy_account.lamports())?;
+    legacy_account.data.borrow_mut().fill(0);
+
+    msg!("Account {} migrated to {}", legacy_account.key, account.key);
+    Ok(())
+}
+
+/// Rewrites a tagged account at an older version under the current header.
+fn upgrade_account<'a>(
+    program_id: &Pubkey,
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    kind: AccountKind,
+) -> ProgramResult {
+    let upgraded = match kind {
+        AccountKind::State => match upgrade_state(&account.data.borrow())? {
+            Some(state) => {
+                let expected =
+                    Pubkey::create_program_address(&[STATE_SEED, &[state.bump]], program_id)
+                        .map_err(|_| ProgramError::InvalidSeeds)?;
+                if expected != *account.key {
+                    return Err(ProgramError::InvalidSeeds);
+                }
+                Some(state.pack()?)
+            }
+            None => None,
+        },
+        AccountKind::Wallet => match upgrade_wallet(&account.data.borrow())? {
+            Some(wallet) => {
+                let (expected, _) = get_wallet_address(&wallet.owner, program_id);
+                if expected != *account.key {
+                    return Err(ProgramError::InvalidSeeds);
+                }
+                Some(wallet.pack()?)
+            }
+            None => None,
+        },
+    };
+
+    let Some(data) = upgraded else {
+        msg!("Account already migrated");
+        return Ok(());
+    };
+
+    resize_account(account, payer, system_program, data.len())?;
+    account.data.borrow_mut().copy_from_slice(&data);
+
+    msg!("Account {} upgraded", account.key);
+    Ok(())
+}
+
+fn initialize(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    config: CrossChainConfig,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+    let fee_history_account = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    config.validate()?;
+
+    let (expected_state, state_bump) = get_state_address(program_id);
+    if expected_state != *state_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (expected_audit, audit_bump) = get_audit_log_address(program_id);
+    if expected_audit != *audit_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let state = CrossChainState {
+        config: CrossChainConfig {
+            admin: *admin.key,
+            ..config
+        },
+        bump: state_bump,
+        ..Default::default()
+    };
+
+    create_pda_account(
+        admin,
+        state_account,
+        system_program,
+        program_id,
+        state.pack()?.len(),
+        0,
+        &[STATE_SEED, &[state_bump]],
+    )?;
+
+    state.pack_into(&mut state_account.data.borrow_mut()[..])?;
+
+    create_pda_account(
+        admin,
+        audit_account,
+        system_program,
+        program_id,
+        AuditLogBuffer::LEN,
+        0,
+        &[AUDIT_LOG_SEED, &[audit_bump]],
+    )?;
+    AuditLogBuffer::new(audit_bump).serialize(&mut &mut audit_account.data.borrow_mut()[..])?;
+
+    record_fee_schedule(
+        program_id,
+        fee_history_account,
+        admin,
+        system_program,
+        &state,
+    )?;
+
+    msg!("Cross-chain hub initialized successfully");
+    Ok(())
+}
+
+/// Enforces `CrossChainConfig.require_top_level` using the instructions
+/// sysvar: under CPI the top-level instruction belongs to the caller.
+fn require_top_level(
+    program_id: &Pubkey,
+    config: &CrossChainConfig,
+    instructions_sysvar: &AccountInfo,
+) -> ProgramResult {
+    if !config.require_top_level {
+        return Ok(());
+    }
+
+    let current = sysvar_instructions::get_instruction_relative(0, instructions_sysvar)?;
+    config.check_invocation(&current.program_id, program_id)?;
+    Ok(())
+}
+
+/// The instructions sysvar follows the fee history and is only required
+/// while `require_top_level` is set.
+fn update_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    config: CrossChainConfig,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let fee_history_account = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    if state.config.require_top_level {
+        let instructions_sysvar = next_account_info(account_info_iter)?;
+        require_top_level(program_id, &state.config, instructions_sysvar)?;
+    }
+
+    config.validate()?;
+
+    if state.config.config_timelock_slots > 0 && state.config.is_sensitive_change(&config) {
+        return Err(CrossChainError::ConfigChangeTimelocked.into());
+    }
+
+    apply_config(&mut state, config);
+    write_state(state_account, admin, system_program, &state)?;
+    record_fee_schedule(
+        program_id,
+        fee_history_account,
+        admin,
+        system_program,
+        &state,
+    )?;
+
+    msg!("Config updated successfully");
+    Ok(())
+}
+
+/// Admin rotation goes through ProposeAdmin / AcceptAdmin; the cluster a
+/// deployment signs for never changes, and neither does the hub chain id
+/// once set (states migrated from before it existed start unset).
+fn apply_config(state: &mut CrossChainState, config: CrossChainConfig) {
+    let hub_chain_id = match state.config.hub_chain_id {
+        0 => config.hub_chain_id,
+        hub_chain_id => hub_chain_id,
+    };
+    state.config = CrossChainConfig {
+        admin: state.config.admin,
+        genesis_hash: state.config.genesis_hash,
+        hub_chain_id,
+        ..config
+    };
+
+    HubEvent::ConfigUpdated {
+        admin: state.config.admin,
+        fee_basis_points: state.config.fee_basis_points,
+        paused: state.config.paused,
+    }
+    .emit();
+}
+
+/// Queues `config` to apply once the configured timelock has elapsed,
+/// replacing any update already queued. The instructions sysvar follows the
+/// system program and is only required while `require_top_level` is set.
+fn queue_config_update(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    config: CrossChainConfig,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    if state.config.require_top_level {
+        let instructions_sysvar = next_account_info(account_info_iter)?;
+        require_top_level(program_id, &state.config, instructions_sysvar)?;
+    }
+
+    config.validate()?;
+
+    let eta_slot = Clock::get()?
+        .slot
+        .saturating_add(state.config.config_timelock_slots);
+    state.pending_config = Some(PendingConfigUpdate { config, eta_slot });
+    write_state(state_account, admin, system_program, &state)?;
+
+    HubEvent::ConfigUpdateQueued {
+        admin: *admin.key,
+        eta_slot,
+    }
+    .emit();
+    msg!("Config update queued until slot {}", eta_slot);
+    Ok(())
+}
+
+/// Applies the queued config once its timelock has elapsed. Anyone may
+/// execute it; the pause switch keeps its current value so an emergency
+/// pause is not undone by an update queued before it.
+fn execute_config_update(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let fee_history_account = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    let pending = state
+        .pending_config
+        .take()
+        .ok_or(CrossChainError::NoPendingConfigUpdate)?;
+
+    if Clock::get()?.slot < pending.eta_slot {
+        return Err(CrossChainError::TimelockActive.into());
+    }
+
+    pending.config.validate()?;
+
+    let paused = state.config.paused;
+    apply_config(
+        &mut state,
+        CrossChainConfig {
+            paused,
+            ..pending.config
+        },
+    );
+    write_state(state_account, payer, system_program, &state)?;
+    record_fee_schedule(
+        program_id,
+        fee_history_account,
+        payer,
+        system_program,
+        &state,
+    )?;
+
+    msg!("Queued config update executed");
+    Ok(())
+}
+
+fn register_wallet(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    wallet_data: WalletData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let activity_account = next_account_info(account_info_iter)?;
+    let nonce_account = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let sponsor_account = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.is_paused(PauseFlags::WALLET_REGISTRATION) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    if wallet_account.owner == program_id {
+        return Err(CrossChainError::WalletAlreadyRegistered.into());
+    }
+
+    let (expected_wallet, wallet_bump) = get_wallet_address(owner.key, program_id);
+    let (expected_activity, activity_bump) = get_activity_log_address(owner.key, program_id);
+    let (expected_nonce, nonce_bump) = get_nonce_registry_address(owner.key, program_id);
+    let (expected_config, config_bump) = get_wallet_config_address(owner.key, program_id);
+    if expected_wallet != *wallet_account.key
+        || expected_activity != *activity_account.key
+        || expected_nonce != *nonce_account.key
+        || expected_config != *config_account.key
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if wallet_data.eidas_level > EidasLevel::None {
+        let eidas_authority = next_account_info(account_info_iter)?;
+        if !eidas_authority.is_signer || *eidas_authority.key != state.config.eidas_authority {
+            return Err(CrossChainError::Unauthorized.into());
+        }
+    }
+
+    let pending_verification = state.config.registration.admit(&wallet_data.eidas_level)?;
+
+    authorize_payer(program_id, &state, payer, sponsor_account, owner.key)?;
+
+    let wallet = Wallet {
+        owner: *owner.key,
         created_at: Clock::get()?.unix_timestamp,
         is_compliance_verified: false,
-        eidas_level: EidasLevel::None,
-        public_key: wallet_data.public_key,
-        metadata: wallet_data.metadata,
+        eidas_level: wallet_data.eidas_level,
+        public_key: wallet_data.public_key.into_inner(),
+        metadata: wallet_data.metadata.into_inner(),
+        kyc_verified: false,
+        aml_screened: false,
+        pending_verification,
+        frozen: false,
+    };
+
+    create_pda_account(
+        payer,
+        wallet_account,
+        system_program,
+        program_id,
+        wallet.pack()?.len(),
+        0,
+        &[WALLET_SEED, owner.key.as_ref(), &[wallet_bump]],
+    )?;
+
+    wallet.pack_into(&mut wallet_account.data.borrow_mut()[..])?;
+
+    create_pda_account(
+        payer,
+        activity_account,
+        system_program,
+        program_id,
+        WalletActivityLog::LEN,
+        0,
+        &[ACTIVITY_LOG_SEED, owner.key.as_ref(), &[activity_bump]],
+    )?;
+    WalletActivityLog::new(*owner.key)
+        .serialize(&mut &mut activity_account.data.borrow_mut()[..])?;
+
+    create_pda_account(
+        payer,
+        nonce_account,
+        system_program,
+        program_id,
+        NonceRegistry::LEN,
+        0,
+        &[NONCE_REGISTRY_SEED, owner.key.as_ref(), &[nonce_bump]],
+    )?;
+    NonceRegistry {
+        owner: *owner.key,
+        ..Default::default()
+    }
+    .serialize(&mut &mut nonce_account.data.borrow_mut()[..])?;
+
+    create_pda_account(
+        payer,
+        config_account,
+        system_program,
+        program_id,
+        ACCOUNT_HEADER_LEN + WalletConfigRecord::LEN,
+        0,
+        &[WALLET_CONFIG_SEED, owner.key.as_ref(), &[config_bump]],
+    )?;
+    WalletConfigRecord {
+        owner: *owner.key,
+        config: WalletConfig::default(),
+        bump: config_bump,
+    }
+    .pack_into(&mut config_account.data.borrow_mut()[..])?;
+
+    state.registered_wallets = state
+        .registered_wallets
+        .checked_add(1)
+        .ok_or(CrossChainError::ArithmeticOverflow)?;
+    state.pack_into(&mut state_account.data.borrow_mut()[..])?;
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            AuditAction::WalletRegistered,
+            *owner.key,
+            *wallet_account.key,
+            0,
+            &Clock::get()?,
+        ),
+    )?;
+
+    HubEvent::WalletRegistered {
+        owner: *owner.key,
+        wallet: *wallet_account.key,
+        eidas_level: wallet.eidas_level,
+        pending_verification,
+    }
+    .emit();
+    msg!("Wallet registered successfully");
+    Ok(())
+}
+
+/// Replaces the owner's wallet config, creating the account for wallets
+/// registered before configs were stored. While 2FA is on, any change must
+/// be co-signed so a stolen owner key cannot simply switch it off.
+fn update_wallet_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    config: WalletConfig,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let second_factor_account = next_account_info(account_info_iter)?;
+    let cosigner = next_account_info(account_info_iter)?;
+
+    load_owned_wallet(program_id, wallet_account, owner)?;
+
+    let (expected, bump) = get_wallet_config_address(owner.key, program_id);
+    if expected != *config_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let current = load_wallet_config(program_id, config_account, owner.key)?;
+    let second_factor = load_second_factor(program_id, second_factor_account, owner.key)?;
+    validate_second_factor(
+        &current,
+        second_factor.as_ref(),
+        u64::MAX,
+        cosigner.is_signer.then_some(cosigner.key),
+    )?;
+
+    if config_account.owner != program_id {
+        create_pda_account(
+            owner,
+            config_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + WalletConfigRecord::LEN,
+            0,
+            &[WALLET_CONFIG_SEED, owner.key.as_ref(), &[bump]],
+        )?;
+    }
+
+    WalletConfigRecord {
+        owner: *owner.key,
+        config,
+        bump,
+    }
+    .pack_into(&mut config_account.data.borrow_mut()[..])?;
+
+    msg!("Wallet config updated");
+    Ok(())
+}
+
+/// Adds `recipient` to, or removes it from, the owner's list of `kind`,
+/// creating the list on first use.
+fn update_recipient_list(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    kind: RecipientListKind,
+    recipient: Pubkey,
+    add: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let list_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    load_owned_wallet(program_id, wallet_account, owner)?;
+
+    let (expected, bump) = get_recipient_list_address(owner.key, kind, program_id);
+    if expected != *list_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut list = match load_recipient_list(program_id, list_account, owner.key, kind)? {
+        Some(list) => list,
+        None => {
+            create_pda_account(
+                owner,
+                list_account,
+                system_program,
+                program_id,
+                ACCOUNT_HEADER_LEN + RecipientList::LEN,
+                0,
+                &[
+                    RECIPIENT_LIST_SEED,
+                    owner.key.as_ref(),
+                    &[kind as u8],
+                    &[bump],
+                ],
+            )?;
+            RecipientList::new(*owner.key, kind, bump)
+        }
+    };
+
+    let changed = if add {
+        list.add(recipient)?
+    } else {
+        list.remove(&recipient)
     };
+    let mut data = list_account.data.borrow_mut();
+    data.fill(0);
+    list.pack_into(&mut data[..])?;
+
+    msg!(
+        "{:?} list {} {}: {}",
+        kind,
+        if add { "add" } else { "remove" },
+        recipient,
+        if changed { "updated" } else { "unchanged" }
+    );
+    Ok(())
+}
+
+/// Registers the owner's second factor, or replaces it. `device` must sign,
+/// and replacing a registered device also needs the current one; an owner
+/// who lost it goes through `RecoverSecondFactor` instead.
+fn register_second_factor(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    threshold: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let second_factor_account = next_account_info(account_info_iter)?;
+    let device = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let cosigner = next_account_info(account_info_iter)?;
+
+    load_owned_wallet(program_id, wallet_account, owner)?;
+
+    if !device.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if device.key == owner.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let (expected, bump) = get_second_factor_address(owner.key, program_id);
+    if expected != *second_factor_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    match load_second_factor(program_id, second_factor_account, owner.key)? {
+        Some(current) => {
+            if !cosigner.is_signer || *cosigner.key != current.device {
+                return Err(CrossChainError::SecondFactorRequired.into());
+            }
+        }
+        None => create_pda_account(
+            owner,
+            second_factor_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + SecondFactor::LEN,
+            0,
+            &[SECOND_FACTOR_SEED, owner.key.as_ref(), &[bump]],
+        )?,
+    }
+
+    let mut data = second_factor_account.data.borrow_mut();
+    data.fill(0);
+    SecondFactor::new(*owner.key, *device.key, threshold, bump).pack_into(&mut data[..])?;
+
+    HubEvent::SecondFactorUpdated {
+        owner: *owner.key,
+        device: *device.key,
+        threshold,
+    }
+    .emit();
+    msg!("Second factor registered");
+    Ok(())
+}
+
+/// Rotates a lost second factor with the owner's key alone, after
+/// `SECOND_FACTOR_RECOVERY_DELAY_SLOTS`. The new device signs both the
+/// request and, once the delay has passed, the call that applies it.
+fn recover_second_factor(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let second_factor_account = next_account_info(account_info_iter)?;
+    let device = next_account_info(account_info_iter)?;
+
+    load_owned_wallet(program_id, wallet_account, owner)?;
+
+    if !device.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if device.key == owner.key {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut second_factor = load_second_factor(program_id, second_factor_account, owner.key)?
+        .ok_or(ProgramError::UninitializedAccount)?;
+
+    if second_factor.recover(*device.key, Clock::get()?.slot)? {
+        HubEvent::SecondFactorUpdated {
+            owner: *owner.key,
+            device: *device.key,
+            threshold: second_factor.threshold,
+        }
+        .emit();
+    } else {
+        HubEvent::SecondFactorRecoveryStarted {
+            owner: *owner.key,
+            device: *device.key,
+            recovery_slot: second_factor.recovery_slot,
+        }
+        .emit();
+    }
+
+    let mut data = second_factor_account.data.borrow_mut();
+    data.fill(0);
+    second_factor.pack_into(&mut data[..])?;
+
+    msg!("Second factor recovery for {}", owner.key);
+    Ok(())
+}
+
+/// Issues `session_key`, or replaces the scope of one already issued.
+fn issue_session_key(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    session_key: Pubkey,
+    scope: SessionKeyData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let session_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    load_owned_wallet(program_id, wallet_account, owner)?;
+
+    if session_key == *owner.key || scope.expires_slot < Clock::get()?.slot {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let (expected, bump) = get_session_key_address(owner.key, &session_key, program_id);
+    if expected != *session_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if session_account.owner != program_id {
+        create_pda_account(
+            owner,
+            session_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + SessionKey::LEN,
+            0,
+            &[
+                SESSION_KEY_SEED,
+                owner.key.as_ref(),
+                session_key.as_ref(),
+                &[bump],
+            ],
+        )?;
+    }
+
+    let expires_slot = scope.expires_slot;
+    let mut data = session_account.data.borrow_mut();
+    data.fill(0);
+    SessionKey {
+        owner: *owner.key,
+        key: session_key,
+        scope,
+        bump,
+    }
+    .pack_into(&mut data[..])?;
+
+    HubEvent::SessionKeyIssued {
+        owner: *owner.key,
+        session_key,
+        expires_slot,
+    }
+    .emit();
+    msg!(
+        "Session key {} valid until slot {}",
+        session_key,
+        expires_slot
+    );
+    Ok(())
+}
+
+/// Closes the session key's account, returning its rent to the owner.
+fn revoke_session_key(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    session_key: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let session_account = next_account_info(account_info_iter)?;
+
+    load_owned_wallet(program_id, wallet_account, owner)?;
+
+    let (expected, _) = get_session_key_address(owner.key, &session_key, program_id);
+    if expected != *session_account.key || session_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    transfer_lamports(session_account, owner, session_account.lamports())?;
+    session_account.data.borrow_mut().fill(0);
+
+    HubEvent::SessionKeyRevoked {
+        owner: *owner.key,
+        session_key,
+    }
+    .emit();
+    msg!("Session key {} revoked", session_key);
+    Ok(())
+}
+
+/// Replaces the owner's guardians, dropping any pending recovery. An empty
+/// list removes the guardian set. Like any wallet config change, it needs
+/// the second factor's co-signature while 2FA is on.
+fn set_guardians(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    guardians: BoundedVec<Pubkey, MAX_GUARDIANS>,
+    threshold: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let guardians_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let second_factor_account = next_account_info(account_info_iter)?;
+    let cosigner = next_account_info(account_info_iter)?;
+
+    load_owned_wallet(program_id, wallet_account, owner)?;
+    let count = guardians.len() as u8;
+
+    let config = load_wallet_config(program_id, config_account, owner.key)?;
+    let second_factor = load_second_factor(program_id, second_factor_account, owner.key)?;
+    validate_second_factor(
+        &config,
+        second_factor.as_ref(),
+        u64::MAX,
+        cosigner.is_signer.then_some(cosigner.key),
+    )?;
+
+    let (expected, bump) = get_guardian_set_address(owner.key, program_id);
+    if expected != *guardians_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if guardians.is_empty() {
+        if guardians_account.owner == program_id {
+            transfer_lamports(guardians_account, owner, guardians_account.lamports())?;
+            guardians_account.data.borrow_mut().fill(0);
+        }
+    } else {
+        let guardian_set = GuardianSet::new(*owner.key, guardians, threshold, bump)?;
+        if guardians_account.owner != program_id {
+            create_pda_account(
+                owner,
+                guardians_account,
+                system_program,
+                program_id,
+                ACCOUNT_HEADER_LEN + GuardianSet::LEN,
+                0,
+                &[GUARDIANS_SEED, owner.key.as_ref(), &[bump]],
+            )?;
+        }
+
+        let mut data = guardians_account.data.borrow_mut();
+        data.fill(0);
+        guardian_set.pack_into(&mut data[..])?;
+    }
+
+    HubEvent::GuardiansUpdated {
+        owner: *owner.key,
+        guardians: count,
+        threshold,
+    }
+    .emit();
+    msg!("Guardians updated");
+    Ok(())
+}
+
+/// A guardian proposes moving the wallet to `new_owner`, approves the
+/// proposal already pending for it, or objects to a pending proposal naming
+/// someone else.
+fn propose_recovery(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_owner: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let wallet_account = next_account_info(account_info_iter)?;
+    let guardians_account = next_account_info(account_info_iter)?;
+    let guardian = next_account_info(account_info_iter)?;
+
+    if !guardian.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let wallet = load_wallet(program_id, wallet_account)?;
+    let mut guardian_set = load_guardian_set(program_id, guardians_account, &wallet.owner)?
+        .ok_or(CrossChainError::NotGuardian)?;
+
+    guardian_set.propose(*guardian.key, new_owner, Clock::get()?.slot)?;
+    guardians_account.data.borrow_mut().fill(0);
+    guardian_set.pack_into(&mut guardians_account.data.borrow_mut()[..])?;
+
+    match &guardian_set.pending {
+        Some(pending) if pending.new_owner == new_owner => HubEvent::RecoveryProposed {
+            owner: wallet.owner,
+            new_owner,
+            guardian: *guardian.key,
+            approvals: pending.approvals.len() as u8,
+            executable_slot: pending.executable_slot,
+        }
+        .emit(),
+        Some(pending) => HubEvent::RecoveryObjected {
+            owner: wallet.owner,
+            new_owner: pending.new_owner,
+            guardian: *guardian.key,
+            objections: pending.objections.len() as u8,
+        }
+        .emit(),
+        None => {}
+    }
+    msg!("Recovery of {} to {} proposed", wallet.owner, new_owner);
+    Ok(())
+}
+
+/// The current owner cancels a pending recovery.
+fn veto_recovery(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let guardians_account = next_account_info(account_info_iter)?;
+
+    load_owned_wallet(program_id, wallet_account, owner)?;
+
+    let mut guardian_set = load_guardian_set(program_id, guardians_account, owner.key)?
+        .ok_or(CrossChainError::NoPendingRecovery)?;
+    let new_owner = guardian_set.veto()?;
+    guardians_account.data.borrow_mut().fill(0);
+    guardian_set.pack_into(&mut guardians_account.data.borrow_mut()[..])?;
+
+    HubEvent::RecoveryVetoed {
+        owner: *owner.key,
+        new_owner,
+    }
+    .emit();
+    msg!("Recovery to {} vetoed", new_owner);
+    Ok(())
+}
+
+/// Moves an approved, unvetoed recovery's wallet to the new owner. Wallet
+/// PDAs are keyed by owner, so the wallet, its activity log, nonce registry,
+/// config and guardians are opened under the new key and the old wallet and
+/// guardian accounts are closed, which also retires the old key's session
+/// keys. Transfers still escrowed under the old key stay open and refund to
+/// the new owner. Compliance is tied to the key: the new owner must pass
+/// `VerifyCompliance` again, and re-register any second factor.
+fn execute_recovery(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let old_wallet_account = next_account_info(account_info_iter)?;
+    let old_guardians_account = next_account_info(account_info_iter)?;
+    let old_config_account = next_account_info(account_info_iter)?;
+    let old_activity_account = next_account_info(account_info_iter)?;
+    let old_allowlist_account = next_account_info(account_info_iter)?;
+    let old_blocklist_account = next_account_info(account_info_iter)?;
+    let old_second_factor_account = next_account_info(account_info_iter)?;
+    let new_owner = next_account_info(account_info_iter)?;
+    let wallet_account = next_account_info(account_info_iter)?;
+    let activity_account = next_account_info(account_info_iter)?;
+    let nonce_account = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let guardians_account = next_account_info(account_info_iter)?;
+    let allowlist_account = next_account_info(account_info_iter)?;
+    let blocklist_account = next_account_info(account_info_iter)?;
+    let second_factor_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+
+    if !new_owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let old_wallet = load_wallet(program_id, old_wallet_account)?;
+    let old_owner = old_wallet.owner;
+    let guardian_set = load_guardian_set(program_id, old_guardians_account, &old_owner)?
+        .ok_or(CrossChainError::NoPendingRecovery)?;
+    if guardian_set.recovery_owner(Clock::get()?.slot)? != *new_owner.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+    let config = load_wallet_config(program_id, old_config_account, &old_owner)?;
+    let allowlist = load_recipient_list(
+        program_id,
+        old_allowlist_account,
+        &old_owner,
+        RecipientListKind::Allow,
+    )?;
+    let blocklist = load_recipient_list(
+        program_id,
+        old_blocklist_account,
+        &old_owner,
+        RecipientListKind::Block,
+    )?;
+    let second_factor = load_second_factor(program_id, old_second_factor_account, &old_owner)?;
+
+    // Transfers still escrowed under the old key stay open; their refunds
+    // are redirected to the new owner rather than the abandoned key.
+    let (expected_old_activity, _) = get_activity_log_address(&old_owner, program_id);
+    if expected_old_activity != *old_activity_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if old_activity_account.owner == program_id {
+        let mut log = WalletActivityLog::deserialize(&mut &old_activity_account.data.borrow()[..])?;
+        log.recovered_to = Some(*new_owner.key);
+        log.serialize(&mut &mut old_activity_account.data.borrow_mut()[..])?;
+    }
+
+    if wallet_account.owner == program_id {
+        return Err(CrossChainError::WalletAlreadyRegistered.into());
+    }
+
+    let (expected_wallet, wallet_bump) = get_wallet_address(new_owner.key, program_id);
+    let (expected_activity, activity_bump) = get_activity_log_address(new_owner.key, program_id);
+    let (expected_nonce, nonce_bump) = get_nonce_registry_address(new_owner.key, program_id);
+    let (expected_config, config_bump) = get_wallet_config_address(new_owner.key, program_id);
+    let (expected_guardians, guardians_bump) = get_guardian_set_address(new_owner.key, program_id);
+    let (expected_allowlist, allowlist_bump) =
+        get_recipient_list_address(new_owner.key, RecipientListKind::Allow, program_id);
+    let (expected_blocklist, blocklist_bump) =
+        get_recipient_list_address(new_owner.key, RecipientListKind::Block, program_id);
+    let (expected_second_factor, second_factor_bump) =
+        get_second_factor_address(new_owner.key, program_id);
+    if expected_wallet != *wallet_account.key
+        || expected_activity != *activity_account.key
+        || expected_nonce != *nonce_account.key
+        || expected_config != *config_account.key
+        || expected_guardians != *guardians_account.key
+        || expected_allowlist != *allowlist_account.key
+        || expected_blocklist != *blocklist_account.key
+        || expected_second_factor != *second_factor_account.key
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let wallet = Wallet {
+        owner: *new_owner.key,
+        is_compliance_verified: false,
+        kyc_verified: false,
+        aml_screened: false,
+        ..old_wallet
+    };
+    create_pda_account(
+        new_owner,
+        wallet_account,
+        system_program,
+        program_id,
+        wallet.pack()?.len(),
+        0,
+        &[WALLET_SEED, new_owner.key.as_ref(), &[wallet_bump]],
+    )?;
+    wallet.pack_into(&mut wallet_account.data.borrow_mut()[..])?;
+
+    create_pda_account(
+        new_owner,
+        activity_account,
+        system_program,
+        program_id,
+        WalletActivityLog::LEN,
+        0,
+        &[ACTIVITY_LOG_SEED, new_owner.key.as_ref(), &[activity_bump]],
+    )?;
+    WalletActivityLog::new(*new_owner.key)
+        .serialize(&mut &mut activity_account.data.borrow_mut()[..])?;
+
+    create_pda_account(
+        new_owner,
+        nonce_account,
+        system_program,
+        program_id,
+        NonceRegistry::LEN,
+        0,
+        &[NONCE_REGISTRY_SEED, new_owner.key.as_ref(), &[nonce_bump]],
+    )?;
+    NonceRegistry {
+        owner: *new_owner.key,
+        ..Default::default()
+    }
+    .serialize(&mut &mut nonce_account.data.borrow_mut()[..])?;
+
+    create_pda_account(
+        new_owner,
+        config_account,
+        system_program,
+        program_id,
+        ACCOUNT_HEADER_LEN + WalletConfigRecord::LEN,
+        0,
+        &[WALLET_CONFIG_SEED, new_owner.key.as_ref(), &[config_bump]],
+    )?;
+    WalletConfigRecord {
+        owner: *new_owner.key,
+        config,
+        bump: config_bump,
+    }
+    .pack_into(&mut config_account.data.borrow_mut()[..])?;
+
+    // A guardian that became the owner stops guarding.
+    let guardians: BoundedVec<Pubkey, MAX_GUARDIANS> = guardian_set
+        .guardians
+        .iter()
+        .filter(|guardian| *guardian != new_owner.key)
+        .copied()
+        .collect();
+    if !guardians.is_empty() {
+        let threshold = guardian_set.threshold.min(guardians.len() as u8);
+        create_pda_account(
+            new_owner,
+            guardians_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + GuardianSet::LEN,
+            0,
+            &[GUARDIANS_SEED, new_owner.key.as_ref(), &[guardians_bump]],
+        )?;
+        GuardianSet::new(*new_owner.key, guardians, threshold, guardians_bump)?
+            .pack_into(&mut guardians_account.data.borrow_mut()[..])?;
+    }
+
+    for (list, list_account, bump, old_list_account) in [
+        (
+            allowlist,
+            allowlist_account,
+            allowlist_bump,
+            old_allowlist_account,
+        ),
+        (
+            blocklist,
+            blocklist_account,
+            blocklist_bump,
+            old_blocklist_account,
+        ),
+    ] {
+        let Some(list) = list else {
+            continue;
+        };
+        create_pda_account(
+            new_owner,
+            list_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + RecipientList::LEN,
+            0,
+            &[
+                RECIPIENT_LIST_SEED,
+                new_owner.key.as_ref(),
+                &[list.kind as u8],
+                &[bump],
+            ],
+        )?;
+        RecipientList {
+            owner: *new_owner.key,
+            bump,
+            ..list
+        }
+        .pack_into(&mut list_account.data.borrow_mut()[..])?;
+        close_recovered(old_list_account, new_owner)?;
+    }
+
+    // The second device is not the lost key, so it keeps guarding the wallet.
+    if let Some(second_factor) = second_factor {
+        create_pda_account(
+            new_owner,
+            second_factor_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + SecondFactor::LEN,
+            0,
+            &[
+                SECOND_FACTOR_SEED,
+                new_owner.key.as_ref(),
+                &[second_factor_bump],
+            ],
+        )?;
+        SecondFactor {
+            owner: *new_owner.key,
+            bump: second_factor_bump,
+            ..second_factor
+        }
+        .pack_into(&mut second_factor_account.data.borrow_mut()[..])?;
+        close_recovered(old_second_factor_account, new_owner)?;
+    }
+
+    for closed in [old_wallet_account, old_guardians_account] {
+        close_recovered(closed, new_owner)?;
+    }
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            AuditAction::WalletRecovered,
+            *new_owner.key,
+            old_owner,
+            0,
+            &Clock::get()?,
+        ),
+    )?;
+
+    HubEvent::WalletRecovered {
+        old_owner,
+        new_owner: *new_owner.key,
+    }
+    .emit();
+    msg!("Wallet of {} recovered to {}", old_owner, new_owner.key);
+    Ok(())
+}
+
+/// Closes an account of the recovered owner, paying its rent to the new one.
+fn close_recovered(closed: &AccountInfo, new_owner: &AccountInfo) -> ProgramResult {
+    transfer_lamports(closed, new_owner, closed.lamports())?;
+    closed.data.borrow_mut().fill(0);
+    Ok(())
+}
+
+fn sign_transaction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    signature_data: TransactionSignatureData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let wallet_account = next_account_info(account_info_iter)?;
+    let signer = next_account_info(account_info_iter)?;
+    let activity_account = next_account_info(account_info_iter)?;
+    let nonce_account = next_account_info(account_info_iter)?;
+    let compliance_account = next_account_info(account_info_iter)?;
+    let wallet_config_account = next_account_info(account_info_iter)?;
+    let allowlist_account = next_account_info(account_info_iter)?;
+    let blocklist_account = next_account_info(account_info_iter)?;
+    let second_factor_account = next_account_info(account_info_iter)?;
+    let cosigner = next_account_info(account_info_iter)?;
+    let session_account = next_account_info(account_info_iter)?;
+    let session_signer = next_account_info(account_info_iter)?;
+
+    authorize_owner(
+        program_id,
+        signer,
+        session_account,
+        session_signer,
+        SessionPermissions::SIGN_TRANSACTION,
+        signature_data.amount,
+        signature_data.destination_chain,
+    )?;
+
+    let wallet = load_wallet(program_id, wallet_account)?;
+
+    if wallet.owner != *signer.key {
+        return Err(CrossChainError::WalletOwnerMismatch.into());
+    }
+
+    if wallet.is_compliance_verified && wallet.eidas_level == EidasLevel::None {
+        return Err(CrossChainError::ComplianceRequired.into());
+    }
+
+    validate_wallet_active(&wallet)?;
+
+    let compliance = load_compliance_record(program_id, compliance_account, signer.key)?;
+    check_compliance_record(
+        wallet.is_compliance_verified,
+        compliance.as_ref(),
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    let wallet_config = load_wallet_config(program_id, wallet_config_account, signer.key)?;
+    let allowlist = load_recipient_list(
+        program_id,
+        allowlist_account,
+        signer.key,
+        RecipientListKind::Allow,
+    )?;
+    let blocklist = load_recipient_list(
+        program_id,
+        blocklist_account,
+        signer.key,
+        RecipientListKind::Block,
+    )?;
+    validate_recipient(
+        &wallet_config,
+        allowlist.as_ref(),
+        blocklist.as_ref(),
+        &signature_data.recipient,
+    )?;
+
+    let second_factor = load_second_factor(program_id, second_factor_account, signer.key)?;
+    validate_second_factor(
+        &wallet_config,
+        second_factor.as_ref(),
+        signature_data.amount,
+        cosigner.is_signer.then_some(cosigner.key),
+    )?;
+
+    update_nonce_registry(program_id, nonce_account, signer.key, |registry| {
+        registry.consume_signature_nonce(signature_data.nonce)
+    })?;
+
+    append_activity(
+        program_id,
+        activity_account,
+        signer.key,
+        TransactionRecord {
+            id: format!("{}-{}", signature_data.source_chain, signature_data.nonce),
+            timestamp: Clock::get()?.unix_timestamp,
+            tx_hash: signature_data.transaction_hash.to_vec(),
+            from: *signer.key,
+            to: signature_data.recipient,
+            amount: signature_data.amount,
+            fee: 0,
+            status: TransactionStatus::Pending,
+            chain_id: signature_data.destination_chain,
+            cross_chain: signature_data.source_chain != signature_data.destination_chain,
+        },
+    )?;
+
+    msg!(
+        "Transaction signed successfully for: {:?}",
+        signature_data.transaction_hash
+    );
+    Ok(())
+}
+
+fn verify_compliance(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    compliance_data: ComplianceData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let wallet_account = next_account_info(account_info_iter)?;
+    let verifier = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let compliance_account = next_account_info(account_info_iter)?;
+    let restricted_account = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let sponsor_account = next_account_info(account_info_iter)?;
+
+    if !verifier.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.config.eidas_authority != *verifier.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    if state.is_paused(PauseFlags::COMPLIANCE) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    validate_compliance_data(&compliance_data, Clock::get()?.unix_timestamp)?;
+
+    if compliance_data.verified {
+        let restricted = load_restricted_jurisdictions(program_id, restricted_account)?;
+        check_restricted_jurisdiction(&restricted, &compliance_data.jurisdiction)?;
+    }
+
+    let mut wallet = load_wallet(program_id, wallet_account)?;
+
+    let (expected_record, bump) = get_compliance_record_address(&wallet.owner, program_id);
+    if expected_record != *compliance_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    wallet.is_compliance_verified = compliance_data.verified;
+    wallet.eidas_level = compliance_data.eidas_level.clone();
+    wallet.kyc_verified = compliance_data.kyc_verified;
+    wallet.aml_screened = compliance_data.aml_screened;
+    if wallet.pending_verification {
+        wallet.pending_verification = !compliance_data.verified
+            || wallet.eidas_level < state.config.registration.min_eidas_level;
+    }
+
+    wallet.pack_into(&mut wallet_account.data.borrow_mut()[..])?;
+
+    authorize_payer(program_id, &state, payer, sponsor_account, &wallet.owner)?;
+
+    let record = ComplianceRecord {
+        owner: wallet.owner,
+        data: compliance_data,
+        renewals: 0,
+        bump,
+    };
+    let data = record.try_to_vec()?;
+
+    if compliance_account.owner != program_id {
+        create_pda_account(
+            payer,
+            compliance_account,
+            system_program,
+            program_id,
+            data.len(),
+            0,
+            &[COMPLIANCE_RECORD_SEED, wallet.owner.as_ref(), &[bump]],
+        )?;
+        state.compliance_records = state
+            .compliance_records
+            .checked_add(1)
+            .ok_or(CrossChainError::ArithmeticOverflow)?;
+        write_state(state_account, payer, system_program, &state)?;
+    } else if data.len() != compliance_account.data_len() {
+        resize_account(compliance_account, payer, system_program, data.len())?;
+    }
+    compliance_account.data.borrow_mut().copy_from_slice(&data);
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            AuditAction::ComplianceVerified,
+            *verifier.key,
+            record.owner,
+            0,
+            &Clock::get()?,
+        ),
+    )?;
+
+    HubEvent::ComplianceVerified {
+        owner: record.owner,
+        verifier: *verifier.key,
+        verified: record.data.verified,
+        eidas_level: record.data.eidas_level.clone(),
+    }
+    .emit();
+    msg!("Compliance verified: {:?}", record.data.verified);
+    Ok(())
+}
+
+/// Approves `sponsor` to pay for its end users' onboarding accounts, or
+/// updates or withdraws an approval. The count of instructions already
+/// sponsored is kept.
+fn set_sponsor(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    sponsor: Pubkey,
+    enabled: bool,
+    max_sponsored: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let sponsor_account = next_account_info(account_info_iter)?;
+    let eidas_authority = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !eidas_authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.eidas_authority != *eidas_authority.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let (expected, bump) = get_sponsor_address(&sponsor, program_id);
+    if expected != *sponsor_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let sponsored = if sponsor_account.owner == program_id {
+        Sponsor::unpack(&sponsor_account.data.borrow())?.sponsored
+    } else {
+        create_pda_account(
+            eidas_authority,
+            sponsor_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + Sponsor::LEN,
+            0,
+            &[SPONSOR_SEED, sponsor.as_ref(), &[bump]],
+        )?;
+        0
+    };
+
+    Sponsor {
+        sponsor,
+        enabled,
+        max_sponsored,
+        sponsored,
+        bump,
+    }
+    .pack_into(&mut sponsor_account.data.borrow_mut()[..])?;
+
+    HubEvent::SponsorUpdated {
+        sponsor,
+        enabled,
+        max_sponsored,
+    }
+    .emit();
+    msg!(
+        "Sponsor {} {}",
+        sponsor,
+        if enabled { "approved" } else { "disabled" }
+    );
+    Ok(())
+}
+
+/// Freezes a wallet for `reason`, or unfreezes it when `reason` is `None`.
+/// Only the eIDAS authority may do either; both are written to the audit log.
+fn set_wallet_frozen(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    reason: Option<FreezeReason>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let wallet_account = next_account_info(account_info_iter)?;
+    let eidas_authority = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+
+    if !eidas_authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.eidas_authority != *eidas_authority.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let mut wallet = load_wallet(program_id, wallet_account)?;
+    match (wallet.frozen, reason) {
+        (true, Some(_)) => return Err(CrossChainError::WalletFrozen.into()),
+        (false, None) => return Err(CrossChainError::WalletNotFrozen.into()),
+        _ => {}
+    }
+    wallet.frozen = reason.is_some();
+    wallet.pack_into(&mut wallet_account.data.borrow_mut()[..])?;
+
+    // The reason travels in the event; the audit entry has no field for it.
+    let action = if reason.is_some() {
+        AuditAction::WalletFrozen
+    } else {
+        AuditAction::WalletUnfrozen
+    };
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            action,
+            *eidas_authority.key,
+            wallet.owner,
+            0,
+            &Clock::get()?,
+        ),
+    )?;
+
+    match reason {
+        Some(reason) => HubEvent::WalletFrozen {
+            owner: wallet.owner,
+            reason,
+        },
+        None => HubEvent::WalletUnfrozen {
+            owner: wallet.owner,
+        },
+    }
+    .emit();
+    msg!("Wallet {} {:?}", wallet.owner, action);
+    Ok(())
+}
+
+/// Checks `payer` may fund the onboarding accounts of `beneficiary`: the
+/// beneficiary itself, the eIDAS authority, or an approved sponsor, whose
+/// quota is charged one instruction.
+fn authorize_payer(
+    program_id: &Pubkey,
+    state: &CrossChainState,
+    payer: &AccountInfo,
+    sponsor_account: &AccountInfo,
+    beneficiary: &Pubkey,
+) -> ProgramResult {
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if payer.key == beneficiary || *payer.key == state.config.eidas_authority {
+        return Ok(());
+    }
+
+    let (expected, _) = get_sponsor_address(payer.key, program_id);
+    if expected != *sponsor_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if sponsor_account.owner != program_id {
+        return Err(CrossChainError::SponsorNotApproved.into());
+    }
+
+    let mut sponsor = Sponsor::unpack(&sponsor_account.data.borrow())?;
+    sponsor.charge()?;
+    sponsor.pack_into(&mut sponsor_account.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Extends an existing positive verification without re-submitting it.
+fn renew_compliance(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    expiry_date: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let compliance_account = next_account_info(account_info_iter)?;
+    let verifier = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+
+    if !verifier.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.eidas_authority != *verifier.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    if state.is_paused(PauseFlags::COMPLIANCE) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    if compliance_account.owner != program_id {
+        return Err(CrossChainError::ComplianceRequired.into());
+    }
+
+    let mut record = ComplianceRecord::try_from_slice(&compliance_account.data.borrow())?;
+    let (expected_record, _) = get_compliance_record_address(&record.owner, program_id);
+    if expected_record != *compliance_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let clock = Clock::get()?;
+    record.renew(expiry_date, clock.unix_timestamp)?;
+    record.serialize(&mut &mut compliance_account.data.borrow_mut()[..])?;
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            AuditAction::ComplianceRenewed,
+            *verifier.key,
+            record.owner,
+            0,
+            &clock,
+        ),
+    )?;
+
+    msg!("Compliance renewed until {}", expiry_date);
+    Ok(())
+}
+
+/// Loads a wallet owner's compliance record, or `None` if the owner has never
+/// been through `VerifyCompliance`.
+fn load_compliance_record(
+    program_id: &Pubkey,
+    compliance_account: &AccountInfo,
+    owner: &Pubkey,
+) -> Result<Option<ComplianceRecord>, ProgramError> {
+    let (expected, _) = get_compliance_record_address(owner, program_id);
+    if expected != *compliance_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if compliance_account.owner != program_id {
+        return Ok(None);
+    }
+
+    Ok(Some(ComplianceRecord::try_from_slice(
+        &compliance_account.data.borrow(),
+    )?))
+}
+
+/// Loads an owner's wallet config, falling back to the default for wallets
+/// registered before configs were stored.
+fn load_wallet_config(
+    program_id: &Pubkey,
+    config_account: &AccountInfo,
+    owner: &Pubkey,
+) -> Result<WalletConfig, ProgramError> {
+    let (expected, _) = get_wallet_config_address(owner, program_id);
+    if expected != *config_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if config_account.owner != program_id {
+        return Ok(WalletConfig::default());
+    }
+
+    Ok(WalletConfigRecord::unpack(&config_account.data.borrow())?.config)
+}
+
+/// Loads the sanctions list, falling back to the default list until the
+/// eIDAS authority first edits it.
+fn load_restricted_jurisdictions(
+    program_id: &Pubkey,
+    restricted_account: &AccountInfo,
+) -> Result<RestrictedJurisdictions, ProgramError> {
+    let (expected, _) = get_restricted_jurisdictions_address(program_id);
+    if expected != *restricted_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if restricted_account.owner != program_id {
+        return Ok(RestrictedJurisdictions::default());
+    }
+
+    Ok(RestrictedJurisdictions::deserialize(
+        &mut &restricted_account.data.borrow()[..],
+    )?)
+}
+
+/// Loads one of an owner's recipient lists, if the owner has created it.
+fn load_recipient_list(
+    program_id: &Pubkey,
+    list_account: &AccountInfo,
+    owner: &Pubkey,
+    kind: RecipientListKind,
+) -> Result<Option<RecipientList>, ProgramError> {
+    let (expected, _) = get_recipient_list_address(owner, kind, program_id);
+    if expected != *list_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if list_account.owner != program_id {
+        return Ok(None);
+    }
+
+    Ok(Some(RecipientList::unpack(&list_account.data.borrow())?))
+}
+
+/// Loads an owner's second factor, if the owner has registered one.
+fn load_second_factor(
+    program_id: &Pubkey,
+    second_factor_account: &AccountInfo,
+    owner: &Pubkey,
+) -> Result<Option<SecondFactor>, ProgramError> {
+    let (expected, _) = get_second_factor_address(owner, program_id);
+    if expected != *second_factor_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if second_factor_account.owner != program_id {
+        return Ok(None);
+    }
+
+    Ok(Some(SecondFactor::unpack(
+        &second_factor_account.data.borrow(),
+    )?))
+}
+
+/// Loads an owner's guardian set, if the owner has named guardians.
+fn load_guardian_set(
+    program_id: &Pubkey,
+    guardians_account: &AccountInfo,
+    owner: &Pubkey,
+) -> Result<Option<GuardianSet>, ProgramError> {
+    let (expected, _) = get_guardian_set_address(owner, program_id);
+    if expected != *guardians_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if guardians_account.owner != program_id {
+        return Ok(None);
+    }
+
+    Ok(Some(GuardianSet::unpack(&guardians_account.data.borrow())?))
+}
+
+/// Loads a corridor's policy, falling back to the default until the admin
+/// first sets one.
+fn load_chain_pair_policy(
+    program_id: &Pubkey,
+    policy_account: &AccountInfo,
+    source_chain: u64,
+    destination_chain: u64,
+) -> Result<ChainPairPolicy, ProgramError> {
+    let (expected, _) = get_chain_pair_policy_address(source_chain, destination_chain, program_id);
+    if expected != *policy_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if policy_account.owner != program_id {
+        return Ok(ChainPairPolicy::new(source_chain, destination_chain));
+    }
+
+    ChainPairPolicy::unpack(&policy_account.data.borrow())
+}
+
+fn update_restricted_jurisdictions(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    jurisdiction: String,
+    restricted: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let restricted_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.eidas_authority != *authority.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let code = parse_jurisdiction(&jurisdiction)?;
+
+    let (expected, bump) = get_restricted_jurisdictions_address(program_id);
+    if expected != *restricted_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut list = if restricted_account.owner != program_id {
+        create_pda_account(
+            authority,
+            restricted_account,
+            system_program,
+            program_id,
+            RestrictedJurisdictions::LEN,
+            0,
+            &[RESTRICTED_JURISDICTIONS_SEED, &[bump]],
+        )?;
+        RestrictedJurisdictions {
+            bump,
+            ..Default::default()
+        }
+    } else {
+        RestrictedJurisdictions::deserialize(&mut &restricted_account.data.borrow()[..])?
+    };
+
+    if restricted {
+        list.add(code)?;
+    } else {
+        list.remove(&code)?;
+    }
+    list.serialize(&mut &mut restricted_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Jurisdiction {} {}",
+        String::from_utf8_lossy(&code),
+        if restricted {
+            "restricted"
+        } else {
+            "unrestricted"
+        }
+    );
+    Ok(())
+}
+
+/// Transfers that are not held are posted through the destination chain's
+/// transport, whose accounts follow any SPL token accounts. A session key
+/// may sign in the sender's place, paying for the record and escrow itself;
+/// for SPL transfers it must be the delegate of the sender's token account.
+fn initiate_cross_chain(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    transfer_data: CrossChainTransferData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let sender_wallet = next_account_info(account_info_iter)?;
+    let sender = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let chain_account = next_account_info(account_info_iter)?;
+    let record_account = next_account_info(account_info_iter)?;
+    let activity_account = next_account_info(account_info_iter)?;
+    let nonce_account = next_account_info(account_info_iter)?;
+    let compliance_account = next_account_info(account_info_iter)?;
+    let restricted_account = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+    let wallet_config_account = next_account_info(account_info_iter)?;
+    let policy_account = next_account_info(account_info_iter)?;
+    let allowlist_account = next_account_info(account_info_iter)?;
+    let blocklist_account = next_account_info(account_info_iter)?;
+    let second_factor_account = next_account_info(account_info_iter)?;
+    let cosigner = next_account_info(account_info_iter)?;
+    let session_account = next_account_info(account_info_iter)?;
+    let session_signer = next_account_info(account_info_iter)?;
+
+    let payer = authorize_owner(
+        program_id,
+        sender,
+        session_account,
+        session_signer,
+        SessionPermissions::INITIATE_CROSS_CHAIN,
+        transfer_data.amount,
+        transfer_data.destination_chain,
+    )?;
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.is_paused(PauseFlags::BRIDGE_OUT) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    if state.bridge_config.blocks_outbound() {
+        return Err(CrossChainError::BreakerTripped.into());
+    }
+
+    let wallet = load_wallet(program_id, sender_wallet)?;
+
+    if wallet.owner != *sender.key || transfer_data.sender != *sender.key {
+        return Err(CrossChainError::WalletOwnerMismatch.into());
+    }
+
+    validate_wallet_active(&wallet)?;
+
+    let clock = Clock::get()?;
+    let compliance = load_compliance_record(program_id, compliance_account, sender.key)?;
+    check_compliance_record(
+        wallet.is_compliance_verified,
+        compliance.as_ref(),
+        clock.unix_timestamp,
+    )?;
+
+    if let Some(compliance) = &compliance {
+        let restricted = load_restricted_jurisdictions(program_id, restricted_account)?;
+        check_restricted_jurisdiction(&restricted, &compliance.data.jurisdiction)?;
+    }
+
+    let (expected_chain, _) = get_chain_config_address(transfer_data.destination_chain, program_id);
+    if expected_chain != *chain_account.key || chain_account.owner != program_id {
+        return Err(CrossChainError::ChainNotRegistered.into());
+    }
+
+    let mut chain = ChainConfig::try_from_slice(&chain_account.data.borrow())?;
+
+    validate_destination_chain(&chain, &wallet.eidas_level)?;
+
+    let wallet_config = load_wallet_config(program_id, wallet_config_account, sender.key)?;
+    validate_allowed_chain(&wallet_config, transfer_data.destination_chain)?;
+
+    let allowlist = load_recipient_list(
+        program_id,
+        allowlist_account,
+        sender.key,
+        RecipientListKind::Allow,
+    )?;
+    let blocklist = load_recipient_list(
+        program_id,
+        blocklist_account,
+        sender.key,
+        RecipientListKind::Block,
+    )?;
+    validate_recipient(
+        &wallet_config,
+        allowlist.as_ref(),
+        blocklist.as_ref(),
+        &transfer_data.recipient,
+    )?;
+
+    let second_factor = load_second_factor(program_id, second_factor_account, sender.key)?;
+    validate_second_factor(
+        &wallet_config,
+        second_factor.as_ref(),
+        transfer_data.amount,
+        cosigner.is_signer.then_some(cosigner.key),
+    )?;
+
+    state
+        .config
+        .check_outbound_source(transfer_data.source_chain)?;
+    let policy = load_chain_pair_policy(
+        program_id,
+        policy_account,
+        transfer_data.source_chain,
+        transfer_data.destination_chain,
+    )?;
+    policy.check_amount(transfer_data.amount)?;
+
+    if transfer_data.amount < state.config.min_cross_chain_amount {
+        return Err(CrossChainError::AmountTooLow.into());
+    }
+
+    if transfer_data.amount > state.config.max_cross_chain_amount {
+        return Err(CrossChainError::AmountTooHigh.into());
+    }
+
+    state
+        .compliance_matrix
+        .check(transfer_data.amount, &wallet.compliance_profile())?;
+    state
+        .compliance_matrix
+        .check_travel_rule(transfer_data.amount, transfer_data.travel_rule.as_ref())?;
+
+    let terms = state.transfer_terms(&policy, clock.slot)?;
+    let fee = calculate_cross_chain_fee(
+        transfer_data.amount,
+        terms.fee_basis_points,
+        terms.relayer_fee,
+    )?;
+    if fee > transfer_data.amount {
+        return Err(CrossChainError::AmountTooLow.into());
+    }
+
+    update_nonce_registry(program_id, nonce_account, sender.key, |registry| {
+        registry.consume_transfer_nonce(transfer_data.nonce)
+    })?;
+
+    let hold_rules = &state.compliance_matrix.hold_rules;
+    let mut velocity = None;
+    update_activity_log(program_id, activity_account, sender.key, |log| {
+        log.open_transfer(state.config.max_pending_transfers)?;
+        if log
+            .velocity
+            .record(&hold_rules.velocity, transfer_data.amount, clock.slot)?
+        {
+            velocity = Some(log.velocity);
+        }
+        Ok(true)
+    })?;
+
+    let (expected_record, bump) =
+        get_transfer_record_address(sender.key, transfer_data.nonce, program_id);
+    if expected_record != *record_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut record = TransferRecord {
+        transfer: CrossChainTransferData {
+            fee,
+            timestamp: clock.unix_timestamp,
+            status: TransferStatus::Initiated,
+            ..transfer_data
+        },
+        escrowed_amount: transfer_data.amount,
+        created_slot: clock.slot,
+        completed_slot: 0,
+        bump,
+        wrapped: false,
+        relayer_fee: state.bridge_config.relayer_fee.min(fee),
+        timeout_slots: state.bridge_config.transfer_timeout_slots,
+        gateway_codehash: chain.gateway_codehash,
+        terms,
+    };
+
+    let velocity_hold = velocity.is_some() && hold_rules.velocity.auto_hold;
+    if velocity_hold || hold_rules.should_hold(transfer_data.amount, &wallet.compliance_profile()) {
+        record.hold(hold_rules.review_timeout_slots);
+    }
+
+    if let Some(velocity) = velocity {
+        HubEvent::VelocityAlert {
+            sender: *sender.key,
+            record: *record_account.key,
+            window_start: velocity.window_start,
+            transfers: velocity.transfers,
+            amount: velocity.amount,
+            held: record.transfer.status == TransferStatus::Held,
+        }
+        .emit();
+    }
+
+    let native_escrow = match record.transfer.token_address {
+        Some(_) => 0,
+        None => record.escrowed_amount,
+    };
+
+    create_pda_account(
+        payer,
+        record_account,
+        system_program,
+        program_id,
+        record.space(),
+        native_escrow,
+        &[
+            TRANSFER_RECORD_SEED,
+            sender.key.as_ref(),
+            &transfer_data.nonce.to_le_bytes(),
+            &[bump],
+        ],
+    )?;
+
+    if let Some(mint) = record.transfer.token_address {
+        let sender_token_account = next_account_info(account_info_iter)?;
+        let mint_account = next_account_info(account_info_iter)?;
+        let token_program = next_account_info(account_info_iter)?;
+
+        if *mint_account.key != mint || *mint_account.owner != spl_token::id() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let mint_state = spl_token::state::Mint::unpack(&mint_account.data.borrow())?;
+        let (mint_authority, _) = get_mint_authority_address(program_id);
+
+        if mint_state.mint_authority == COption::Some(mint_authority) {
+            // Hub-issued wrapped asset: burn here, the origin chain releases the original.
+            invoke(
+                &spl_token::instruction::burn(
+                    token_program.key,
+                    sender_token_account.key,
+                    mint_account.key,
+                    payer.key,
+                    &[],
+                    record.escrowed_amount,
+                )?,
+                &[
+                    sender_token_account.clone(),
+                    mint_account.clone(),
+                    payer.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+            record.wrapped = true;
+        } else {
+            let escrow_token_account = next_account_info(account_info_iter)?;
+
+            let (escrow_authority, _) = get_escrow_authority_address(program_id);
+            validate_escrow_token_account(escrow_token_account, &mint, &escrow_authority)?;
+
+            invoke(
+                &spl_token::instruction::transfer(
+                    token_program.key,
+                    sender_token_account.key,
+                    escrow_token_account.key,
+                    payer.key,
+                    &[],
+                    record.escrowed_amount,
+                )?,
+                &[
+                    sender_token_account.clone(),
+                    escrow_token_account.clone(),
+                    payer.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+    }
+
+    if !record.wrapped {
+        state
+            .escrow_ledger
+            .credit(record.transfer.token_address, record.escrowed_amount)?;
+    }
+    state.record_volume(record.transfer.amount)?;
+    write_state(state_account, payer, system_program, &state)?;
+
+    chain.record_outbound(record.transfer.amount)?;
+    chain
+        .rate_limit
+        .consume(record.transfer.amount, clock.slot)?;
+    chain.serialize(&mut &mut chain_account.data.borrow_mut()[..])?;
+
+    record.serialize(&mut &mut record_account.data.borrow_mut()[..])?;
+
+    append_activity(
+        program_id,
+        activity_account,
+        sender.key,
+        transfer_activity(&record, TransactionStatus::Pending),
+    )?;
+
+    let held = record.transfer.status == TransferStatus::Held;
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            if held {
+                AuditAction::TransferHeld
+            } else {
+                AuditAction::TransferInitiated
+            },
+            *payer.key,
+            *record_account.key,
+            record.transfer.amount,
+            &clock,
+        ),
+    )?;
+
+    if held {
+        HubEvent::TransferHeld {
+            record: *record_account.key,
+            sender: *sender.key,
+            destination_chain: record.transfer.destination_chain,
+            amount: record.transfer.amount,
+        }
+        .emit();
+        msg!(
+            "Held cross-chain transfer of {} to chain {} for review",
+            record.transfer.amount,
+            record.transfer.destination_chain
+        );
+        return Ok(());
+    }
+
+    transport_for(chain.transport).post_message(
+        &TransportContext {
+            program_id,
+            state: &state,
+        },
+        &OutboundTransfer {
+            record_key: record_account.key,
+            record: &record,
+        },
+        payer,
+        system_program,
+        account_info_iter,
+    )?;
+
+    emit_transfer_initiated(record_account.key, &record);
+    msg!(
+        "Initiated cross-chain transfer: {} SOL to chain {} with fee {}",
+        record.release_amount(),
+        record.transfer.destination_chain,
+        fee
+    );
+
+    Ok(())
+}
+
+/// Initiates up to `MAX_BATCH_TRANSFERS` native transfers to one chain in a
+/// single escrow and announces them in one `TokenTransferBatch` message.
+/// Accounts are those of `InitiateCrossChain` with the batch account in
+/// place of the transfer record. The batch is all-or-nothing: any entry that
+/// fails a limit or compliance check, or that the hold rules would divert to
+/// review, rejects the whole batch and nothing is escrowed.
+fn initiate_cross_chain_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    transfers: Vec<CrossChainTransferData>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let sender_wallet = next_account_info(account_info_iter)?;
+    let sender = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let chain_account = next_account_info(account_info_iter)?;
+    let batch_account = next_account_info(account_info_iter)?;
+    let activity_account = next_account_info(account_info_iter)?;
+    let nonce_account = next_account_info(account_info_iter)?;
+    let compliance_account = next_account_info(account_info_iter)?;
+    let restricted_account = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+    let wallet_config_account = next_account_info(account_info_iter)?;
+    let policy_account = next_account_info(account_info_iter)?;
+    let allowlist_account = next_account_info(account_info_iter)?;
+    let blocklist_account = next_account_info(account_info_iter)?;
+    let second_factor_account = next_account_info(account_info_iter)?;
+    let cosigner = next_account_info(account_info_iter)?;
+
+    if !sender.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    validate_batch(&transfers, sender.key)?;
+    let first = &transfers[0];
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.is_paused(PauseFlags::BRIDGE_OUT) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    if state.bridge_config.blocks_outbound() {
+        return Err(CrossChainError::BreakerTripped.into());
+    }
+
+    let wallet = load_wallet(program_id, sender_wallet)?;
+
+    if wallet.owner != *sender.key {
+        return Err(CrossChainError::WalletOwnerMismatch.into());
+    }
+
+    validate_wallet_active(&wallet)?;
+
+    let clock = Clock::get()?;
+    let compliance = load_compliance_record(program_id, compliance_account, sender.key)?;
+    check_compliance_record(
+        wallet.is_compliance_verified,
+        compliance.as_ref(),
+        clock.unix_timestamp,
+    )?;
+
+    if let Some(compliance) = &compliance {
+        let restricted = load_restricted_jurisdictions(program_id, restricted_account)?;
+        check_restricted_jurisdiction(&restricted, &compliance.data.jurisdiction)?;
+    }
+
+    let (expected_chain, _) = get_chain_config_address(first.destination_chain, program_id);
+    if expected_chain != *chain_account.key || chain_account.owner != program_id {
+        return Err(CrossChainError::ChainNotRegistered.into());
+    }
+
+    let mut chain = ChainConfig::try_from_slice(&chain_account.data.borrow())?;
+
+    validate_destination_chain(&chain, &wallet.eidas_level)?;
+
+    // Only relayers read the batch message; other transports carry one
+    // transfer per message.
+    if chain.transport != TransportKind::RelayerQuorum {
+        return Err(CrossChainError::BatchNotSupported.into());
+    }
+
+    let wallet_config = load_wallet_config(program_id, wallet_config_account, sender.key)?;
+    validate_allowed_chain(&wallet_config, first.destination_chain)?;
+
+    let allowlist = load_recipient_list(
+        program_id,
+        allowlist_account,
+        sender.key,
+        RecipientListKind::Allow,
+    )?;
+    let blocklist = load_recipient_list(
+        program_id,
+        blocklist_account,
+        sender.key,
+        RecipientListKind::Block,
+    )?;
+
+    state.config.check_outbound_source(first.source_chain)?;
+    let policy = load_chain_pair_policy(
+        program_id,
+        policy_account,
+        first.source_chain,
+        first.destination_chain,
+    )?;
+    let fee_basis_points = policy.fee_basis_points(state.config.fee_basis_points)?;
+
+    let profile = wallet.compliance_profile();
+    let hold_rules = &state.compliance_matrix.hold_rules;
+    let mut entries = Vec::with_capacity(transfers.len());
+    let mut escrowed_amount = 0u64;
+    let mut total_fee = 0u64;
+    let mut relayer_fee = 0u64;
+    for transfer in &transfers {
+        if transfer.amount < state.config.min_cross_chain_amount {
+            return Err(CrossChainError::AmountTooLow.into());
+        }
+
+        if transfer.amount > state.config.max_cross_chain_amount {
+            return Err(CrossChainError::AmountTooHigh.into());
+        }
+
+        policy.check_amount(transfer.amount)?;
+        validate_recipient(
+            &wallet_config,
+            allowlist.as_ref(),
+            blocklist.as_ref(),
+            &transfer.recipient,
+        )?;
+        state.compliance_matrix.check(transfer.amount, &profile)?;
+        state
+            .compliance_matrix
+            .check_travel_rule(transfer.amount, None)?;
+
+        if hold_rules.should_hold(transfer.amount, &profile) {
+            return Err(CrossChainError::ReviewNotSupported.into());
+        }
+
+        let fee = calculate_cross_chain_fee(
+            transfer.amount,
+            fee_basis_points,
+            state.bridge_config.relayer_fee,
+        )?;
+        if fee > transfer.amount {
+            return Err(CrossChainError::AmountTooLow.into());
+        }
+
+        escrowed_amount = escrowed_amount
+            .checked_add(transfer.amount)
+            .ok_or(CrossChainError::ArithmeticOverflow)?;
+        total_fee = total_fee
+            .checked_add(fee)
+            .ok_or(CrossChainError::ArithmeticOverflow)?;
+        relayer_fee = relayer_fee.saturating_add(state.bridge_config.relayer_fee.min(fee));
+        entries.push(BatchEntry {
+            recipient: transfer.recipient,
+            nonce: transfer.nonce,
+            amount: transfer.amount,
+            fee,
+        });
+    }
+
+    // The batch total counts against the threshold, so splitting a large
+    // transfer into entries does not avoid the co-signature.
+    let second_factor = load_second_factor(program_id, second_factor_account, sender.key)?;
+    validate_second_factor(
+        &wallet_config,
+        second_factor.as_ref(),
+        escrowed_amount,
+        cosigner.is_signer.then_some(cosigner.key),
+    )?;
+
+    update_nonce_registry(program_id, nonce_account, sender.key, |registry| {
+        for transfer in &transfers {
+            registry.consume_transfer_nonce(transfer.nonce)?;
+        }
+        Ok(true)
+    })?;
+
+    // The alert reports the window after the whole batch, as the single
+    // transfer path does, not where the first entry tripped it.
+    let mut velocity = None;
+    update_activity_log(program_id, activity_account, sender.key, |log| {
+        log.open_transfer(state.config.max_pending_transfers)?;
+        let mut exceeded = false;
+        for transfer in &transfers {
+            exceeded |= log
+                .velocity
+                .record(&hold_rules.velocity, transfer.amount, clock.slot)?;
+        }
+        if exceeded {
+            velocity = Some(log.velocity);
+        }
+        Ok(true)
+    })?;
+
+    if velocity.is_some() && hold_rules.velocity.auto_hold {
+        return Err(CrossChainError::ReviewNotSupported.into());
+    }
+
+    let (expected_batch, bump) = get_transfer_batch_address(sender.key, first.nonce, program_id);
+    if expected_batch != *batch_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let batch = TransferBatch {
+        sender: *sender.key,
+        source_chain: first.source_chain,
+        destination_chain: first.destination_chain,
+        first_nonce: first.nonce,
+        entries: entries.into(),
+        escrowed_amount,
+        fee: total_fee,
+        relayer_fee,
+        status: TransferStatus::Initiated,
+        timestamp: clock.unix_timestamp,
+        created_slot: clock.slot,
+        completed_slot: 0,
+        timeout_slots: state.bridge_config.transfer_timeout_slots,
+        gateway_codehash: chain.gateway_codehash,
+        bump,
+    };
+
+    create_pda_account(
+        sender,
+        batch_account,
+        system_program,
+        program_id,
+        ACCOUNT_HEADER_LEN + TransferBatch::LEN,
+        escrowed_amount,
+        &[
+            TRANSFER_BATCH_SEED,
+            sender.key.as_ref(),
+            &first.nonce.to_le_bytes(),
+            &[bump],
+        ],
+    )?;
+    batch.pack_into(&mut batch_account.data.borrow_mut()[..])?;
+
+    state.escrow_ledger.credit(None, escrowed_amount)?;
+    state.record_volume(escrowed_amount)?;
+    write_state(state_account, sender, system_program, &state)?;
+
+    chain.record_outbound(escrowed_amount)?;
+    chain.rate_limit.consume(escrowed_amount, clock.slot)?;
+    chain.serialize(&mut &mut chain_account.data.borrow_mut()[..])?;
+
+    append_activity(
+        program_id,
+        activity_account,
+        sender.key,
+        batch_activity(batch_account.key, &batch, TransactionStatus::Pending),
+    )?;
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            AuditAction::TransferInitiated,
+            *sender.key,
+            *batch_account.key,
+            escrowed_amount,
+            &clock,
+        ),
+    )?;
+
+    let message = batch.bridge_message(batch_account.key);
+    validate_bridge_message(&message).map_err(CrossChainError::from)?;
+    sol_log_data(&[&encode_bridge_message(&message)]);
+
+    if let Some(velocity) = velocity {
+        HubEvent::VelocityAlert {
+            sender: *sender.key,
+            record: *batch_account.key,
+            window_start: velocity.window_start,
+            transfers: velocity.transfers,
+            amount: velocity.amount,
+            held: false,
+        }
+        .emit();
+    }
+
+    HubEvent::BatchInitiated {
+        batch: *batch_account.key,
+        sender: *sender.key,
+        destination_chain: batch.destination_chain,
+        transfers: batch.entries.len() as u16,
+        amount: escrowed_amount,
+        fee: total_fee,
+    }
+    .emit();
+    msg!(
+        "Initiated batch of {} transfers: {} to chain {} with fee {}",
+        batch.entries.len(),
+        batch.release_amount(),
+        batch.destination_chain,
+        total_fee
+    );
+
+    Ok(())
+}
+
+/// Settles a batch once its source chain's transport proves the batch's
+/// settlement transfer, paying every entry's recipient from the escrow.
+/// Accounts: state, bridge authority, batch, sender's activity log, source
+/// chain config, instructions sysvar, audit log, the evidence accounts, the
+/// treasury, the system program, then one recipient per entry in order.
+fn complete_cross_chain_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    evidence: TransportEvidence,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let batch_account = next_account_info(account_info_iter)?;
+    let activity_account = next_account_info(account_info_iter)?;
+    let source_chain_account = next_account_info(account_info_iter)?;
+    let instructions_sysvar = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+    let policy_account = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.config.bridge_authority != *authority.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    require_top_level(program_id, &state.config, instructions_sysvar)?;
+
+    if state.is_paused(PauseFlags::BRIDGE_IN) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    if state.bridge_config.blocks_inbound() {
+        return Err(CrossChainError::BreakerTripped.into());
+    }
+
+    if batch_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut batch = TransferBatch::unpack(&batch_account.data.borrow())?;
+    let (expected_batch, _) =
+        get_transfer_batch_address(&batch.sender, batch.first_nonce, program_id);
+    if expected_batch != *batch_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    batch.validate_in_flight()?;
+
+    // Batches only leave the hub, so their destination confirms delivery.
+    let (expected_chain, _) = get_chain_config_address(batch.destination_chain, program_id);
+    if expected_chain != *source_chain_account.key || source_chain_account.owner != program_id {
+        return Err(CrossChainError::ChainNotRegistered.into());
+    }
+
+    let mut source_chain = ChainConfig::try_from_slice(&source_chain_account.data.borrow())?;
+    validate_source_chain(&source_chain)?;
+
+    let policy = load_chain_pair_policy(
+        program_id,
+        policy_account,
+        batch.source_chain,
+        batch.destination_chain,
+    )?;
+    transport_for(source_chain.transport).verify_message(
+        &TransportContext {
+            program_id,
+            state: &state,
+        },
+        &InboundTransfer {
+            transfer: &batch.settlement_transfer(batch_account.key),
+            source_chain: &source_chain,
+            policy: &policy,
+            evidence: &evidence,
+        },
+        instructions_sysvar,
+        account_info_iter,
+    )?;
+
+    let treasury_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    ensure_treasury(program_id, treasury_account, authority, system_program)?;
+
+    for entry in batch.entries.iter() {
+        let recipient_account = next_account_info(account_info_iter)?;
+        if *recipient_account.key != entry.recipient {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        transfer_lamports(
+            batch_account,
+            recipient_account,
+            entry.amount.saturating_sub(entry.fee),
+        )?;
+    }
+    transfer_lamports(batch_account, treasury_account, batch.fee)?;
+
+    let (protocol_fee, relayer_fee) = split_fee(batch.fee, batch.relayer_fee);
+    state.fee_ledger.accrue(None, protocol_fee, relayer_fee)?;
+    state
+        .fee_ledger
+        .record_chain(batch.destination_chain, None, batch.fee)?;
+    state.escrow_ledger.debit(None, batch.escrowed_amount);
+    let release = batch.release_amount();
+    state.record_volume(release)?;
+    write_state(state_account, authority, system_program, &state)?;
+
+    source_chain.record_inbound(release)?;
+    source_chain.serialize(&mut &mut source_chain_account.data.borrow_mut()[..])?;
+
+    let clock = Clock::get()?;
+    batch.status = TransferStatus::Completed;
+    batch.completed_slot = clock.slot;
+    batch.pack_into(&mut batch_account.data.borrow_mut()[..])?;
+
+    update_activity_log(
+        program_id,
+        activity_account,
+        &batch.sender,
+        WalletActivityLog::close_transfer,
+    )?;
+
+    append_activity(
+        program_id,
+        activity_account,
+        &batch.sender,
+        batch_activity(batch_account.key, &batch, TransactionStatus::Confirmed),
+    )?;
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            AuditAction::TransferCompleted,
+            *authority.key,
+            *batch_account.key,
+            release,
+            &clock,
+        ),
+    )?;
+
+    HubEvent::BatchCompleted {
+        batch: *batch_account.key,
+        transfers: batch.entries.len() as u16,
+        amount: release,
+        fee: batch.fee,
+    }
+    .emit();
+    msg!(
+        "Completed batch of {} transfers: {}",
+        batch.entries.len(),
+        release
+    );
+
+    Ok(())
+}
+
+/// Returns a batch that timed out in flight to its sender, less fees, or to
+/// the owner the sender's wallet was recovered to.
+fn refund_cross_chain_batch(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let batch_account = next_account_info(account_info_iter)?;
+    let sender = next_account_info(account_info_iter)?;
+    let activity_account = next_account_info(account_info_iter)?;
+    let treasury_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+
+    if !sender.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if batch_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+    let mut batch = TransferBatch::unpack(&batch_account.data.borrow())?;
+
+    let (expected_batch, _) =
+        get_transfer_batch_address(&batch.sender, batch.first_nonce, program_id);
+    if expected_batch != *batch_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    check_refund_beneficiary(program_id, activity_account, &batch.sender, sender.key)?;
+
+    let clock = Clock::get()?;
+    batch.validate_refund(clock.slot)?;
+
+    ensure_treasury(program_id, treasury_account, sender, system_program)?;
+
+    let refund = batch.release_amount();
+    transfer_lamports(batch_account, sender, refund)?;
+    transfer_lamports(batch_account, treasury_account, batch.fee)?;
+
+    let (protocol_fee, relayer_fee) = split_fee(batch.fee, batch.relayer_fee);
+    state.fee_ledger.accrue(None, protocol_fee, relayer_fee)?;
+    state
+        .fee_ledger
+        .record_chain(batch.destination_chain, None, batch.fee)?;
+    state.escrow_ledger.debit(None, batch.escrowed_amount);
+    write_state(state_account, sender, system_program, &state)?;
+
+    batch.status = TransferStatus::Failed;
+    batch.completed_slot = clock.slot;
+    batch.pack_into(&mut batch_account.data.borrow_mut()[..])?;
+
+    update_activity_log(
+        program_id,
+        activity_account,
+        &batch.sender,
+        WalletActivityLog::close_transfer,
+    )?;
+
+    append_activity(
+        program_id,
+        activity_account,
+        &batch.sender,
+        batch_activity(batch_account.key, &batch, TransactionStatus::Failed),
+    )?;
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            AuditAction::TransferRefunded,
+            *sender.key,
+            *batch_account.key,
+            refund,
+            &clock,
+        ),
+    )?;
+
+    msg!("Refunded batch of {} to {}", refund, sender.key);
+    Ok(())
+}
+
+fn emit_transfer_initiated(record_key: &Pubkey, record: &TransferRecord) {
+    HubEvent::TransferInitiated {
+        record: *record_key,
+        sender: record.transfer.sender,
+        recipient: record.transfer.recipient,
+        destination_chain: record.transfer.destination_chain,
+        nonce: record.transfer.nonce,
+        amount: record.transfer.amount,
+        fee: record.transfer.fee,
+        token: record.transfer.token_address,
+    }
+    .emit();
+}
+
+fn append_activity(
+    program_id: &Pubkey,
+    activity_account: &AccountInfo,
+    owner: &Pubkey,
+    record: TransactionRecord,
+) -> ProgramResult {
+    let (expected, _) = get_activity_log_address(owner, program_id);
+    if expected != *activity_account.key || activity_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut log = WalletActivityLog::deserialize(&mut &activity_account.data.borrow()[..])?;
+    log.push(record);
+    log.serialize(&mut &mut activity_account.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Checks that refunds of `sender`'s transfers may be paid to
+/// `beneficiary`: the sender itself, or only the new owner once a social
+/// recovery moved the wallet off the sender's key.
+fn check_refund_beneficiary(
+    program_id: &Pubkey,
+    activity_account: &AccountInfo,
+    sender: &Pubkey,
+    beneficiary: &Pubkey,
+) -> ProgramResult {
+    let (expected, _) = get_activity_log_address(sender, program_id);
+    if expected != *activity_account.key || activity_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let log = WalletActivityLog::deserialize(&mut &activity_account.data.borrow()[..])?;
+    if log.refund_beneficiary() != *beneficiary {
+        return Err(CrossChainError::WalletOwnerMismatch.into());
+    }
+    Ok(())
+}
+
+fn update_activity_log<F>(
+    program_id: &Pubkey,
+    activity_account: &AccountInfo,
+    owner: &Pubkey,
+    update: F,
+) -> ProgramResult
+where
+    F: FnOnce(&mut WalletActivityLog) -> Result<bool, CrossChainError>,
+{
+    let (expected, _) = get_activity_log_address(owner, program_id);
+    if expected != *activity_account.key || activity_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut log = WalletActivityLog::deserialize(&mut &activity_account.data.borrow()[..])?;
+    update(&mut log)?;
+    log.serialize(&mut &mut activity_account.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+fn append_audit(
+    program_id: &Pubkey,
+    audit_account: &AccountInfo,
+    entry: AuditEntry,
+) -> ProgramResult {
+    let (expected, _) = get_audit_log_address(program_id);
+    if expected != *audit_account.key || audit_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut log = AuditLogBuffer::deserialize(&mut &audit_account.data.borrow()[..])?;
+    log.append(entry);
+    log.serialize(&mut &mut audit_account.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Loads the fee history, creating it for hubs initialized before it existed.
+fn load_fee_history<'a>(
+    program_id: &Pubkey,
+    fee_history_account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> Result<FeeScheduleHistory, ProgramError> {
+    let (expected, bump) = get_fee_history_address(program_id);
+    if expected != *fee_history_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if fee_history_account.owner != program_id {
+        create_pda_account(
+            payer,
+            fee_history_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + FeeScheduleHistory::LEN,
+            0,
+            &[FEE_HISTORY_SEED, &[bump]],
+        )?;
+        return Ok(FeeScheduleHistory::new(bump));
+    }
+
+    // Every writer passes through here, so an older layout is brought up to
+    // date on the first fee change after an upgrade.
+    let upgraded = upgrade_fee_history(&fee_history_account.data.borrow())?;
+    match upgraded {
+        Some(history) => {
+            resize_account(
+                fee_history_account,
+                payer,
+                system_program,
+                ACCOUNT_HEADER_LEN + FeeScheduleHistory::LEN,
+            )?;
+            history.pack_into(&mut fee_history_account.data.borrow_mut()[..])?;
+            Ok(history)
+        }
+        None => Ok(FeeScheduleHistory::unpack(
+            &fee_history_account.data.borrow(),
+        )?),
+    }
+}
+
+/// Appends the hub's current fees to the fee history if they changed.
+fn record_fee_schedule<'a>(
+    program_id: &Pubkey,
+    fee_history_account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    state: &CrossChainState,
+) -> ProgramResult {
+    let mut history = load_fee_history(program_id, fee_history_account, payer, system_program)?;
+    if history.record(state.fee_schedule(Clock::get()?.slot)) {
+        history.pack_into(&mut fee_history_account.data.borrow_mut()[..])?;
+    }
+    Ok(())
+}
+
+/// Appends a corridor's fee multiplier to the fee history if it changed.
+fn record_corridor_fee_schedule<'a>(
+    program_id: &Pubkey,
+    fee_history_account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    policy: &ChainPairPolicy,
+) -> ProgramResult {
+    let mut history = load_fee_history(program_id, fee_history_account, payer, system_program)?;
+    if history.record_corridor(CorridorFeeSchedule {
+        source_chain: policy.source_chain,
+        destination_chain: policy.destination_chain,
+        fee_multiplier_bps: policy.fee_multiplier_bps,
+        effective_slot: Clock::get()?.slot,
+    }) {
+        history.pack_into(&mut fee_history_account.data.borrow_mut()[..])?;
+    }
+    Ok(())
+}
+
+fn update_nonce_registry<F>(
+    program_id: &Pubkey,
+    nonce_account: &AccountInfo,
+    owner: &Pubkey,
+    consume: F,
+) -> ProgramResult
+where
+    F: FnOnce(&mut NonceRegistry) -> Result<bool, CrossChainError>,
+{
+    let (expected, _) = get_nonce_registry_address(owner, program_id);
+    if expected != *nonce_account.key || nonce_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut registry = NonceRegistry::try_from_slice(&nonce_account.data.borrow())?;
+    consume(&mut registry)?;
+    registry.serialize(&mut &mut nonce_account.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Moves native or SPL balance between two registered hub wallets without
+/// the bridge. Both owners pass the compliance and sanctions checks, no fee
+/// is charged, and the transfer is logged in both activity logs. SPL
+/// transfers append the sender and recipient token accounts and the token
+/// program.
+fn transfer_internal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    mint: Option<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let sender_wallet_account = next_account_info(account_info_iter)?;
+    let sender = next_account_info(account_info_iter)?;
+    let recipient_wallet_account = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+    let sender_activity = next_account_info(account_info_iter)?;
+    let recipient_activity = next_account_info(account_info_iter)?;
+    let sender_compliance_account = next_account_info(account_info_iter)?;
+    let recipient_compliance_account = next_account_info(account_info_iter)?;
+    let restricted_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.is_paused(PauseFlags::INTERNAL_TRANSFER) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    let sender_wallet = load_owned_wallet(program_id, sender_wallet_account, sender)?;
+    let recipient_wallet = load_wallet(program_id, recipient_wallet_account)?;
+    if recipient_wallet.owner != *recipient.key {
+        return Err(CrossChainError::WalletOwnerMismatch.into());
+    }
+
+    validate_internal_transfer(&sender_wallet, &recipient_wallet, amount)?;
+    validate_wallet_active(&sender_wallet)?;
+    validate_wallet_active(&recipient_wallet)?;
+
+    let clock = Clock::get()?;
+    let restricted = load_restricted_jurisdictions(program_id, restricted_account)?;
+    for (wallet, compliance_account) in [
+        (&sender_wallet, sender_compliance_account),
+        (&recipient_wallet, recipient_compliance_account),
+    ] {
+        let compliance = load_compliance_record(program_id, compliance_account, &wallet.owner)?;
+        check_compliance_record(
+            wallet.is_compliance_verified,
+            compliance.as_ref(),
+            clock.unix_timestamp,
+        )?;
+        if let Some(compliance) = &compliance {
+            check_restricted_jurisdiction(&restricted, &compliance.data.jurisdiction)?;
+        }
+    }
+
+    state
+        .compliance_matrix
+        .check(amount, &sender_wallet.compliance_profile())?;
+
+    match mint {
+        None => invoke(
+            &system_instruction::transfer(sender.key, recipient.key, amount),
+            &[sender.clone(), recipient.clone(), system_program.clone()],
+        )?,
+        Some(mint) => {
+            let sender_token_account = next_account_info(account_info_iter)?;
+            let recipient_token_account = next_account_info(account_info_iter)?;
+            let token_program = next_account_info(account_info_iter)?;
+
+            if *token_program.key != spl_token::id() {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+
+            let recipient_token =
+                spl_token::state::Account::unpack(&recipient_token_account.data.borrow())?;
+            if recipient_token.owner != *recipient.key || recipient_token.mint != mint {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            invoke(
+                &spl_token::instruction::transfer(
+                    token_program.key,
+                    sender_token_account.key,
+                    recipient_token_account.key,
+                    sender.key,
+                    &[],
+                    amount,
+                )?,
+                &[
+                    sender_token_account.clone(),
+                    recipient_token_account.clone(),
+                    sender.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+    }
+
+    let record = TransactionRecord {
+        id: format!("internal-{}", clock.slot),
+        timestamp: clock.unix_timestamp,
+        tx_hash: Vec::new(),
+        from: *sender.key,
+        to: *recipient.key,
+        amount,
+        fee: 0,
+        status: TransactionStatus::Confirmed,
+        chain_id: 0,
+        cross_chain: false,
+    };
+    append_activity(program_id, sender_activity, sender.key, record.clone())?;
+    append_activity(program_id, recipient_activity, recipient.key, record)?;
+
+    msg!(
+        "Internal transfer of {} from {} to {}",
+        amount,
+        sender.key,
+        recipient.key
+    );
+    Ok(())
+}
+
+fn transfer_activity(record: &TransferRecord, status: TransactionStatus) -> TransactionRecord {
+    TransactionRecord {
+        id: format!(
+            "{}-{}",
+            record.transfer.destination_chain, record.transfer.nonce
+        ),
+        timestamp: record.transfer.timestamp,
+        tx_hash: Vec::new(),
+        from: record.transfer.sender,
+        to: record.transfer.recipient,
+        amount: record.transfer.amount,
+        fee: record.transfer.fee,
+        status,
+        chain_id: record.transfer.destination_chain,
+        cross_chain: true,
+    }
+}
+
+fn batch_activity(
+    batch_key: &Pubkey,
+    batch: &TransferBatch,
+    status: TransactionStatus,
+) -> TransactionRecord {
+    TransactionRecord {
+        id: format!("{}-{}", batch.destination_chain, batch.first_nonce),
+        timestamp: batch.timestamp,
+        tx_hash: Vec::new(),
+        from: batch.sender,
+        to: *batch_key,
+        amount: batch.escrowed_amount,
+        fee: batch.fee,
+        status,
+        chain_id: batch.destination_chain,
+        cross_chain: true,
+    }
+}
+
+fn validate_escrow_token_account(
+    escrow_token_account: &AccountInfo,
+    mint: &Pubkey,
+    escrow_authority: &Pubkey,
+) -> ProgramResult {
+    if *escrow_token_account.owner != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let token_account = spl_token::state::Account::unpack(&escrow_token_account.data.borrow())?;
+
+    if token_account.mint != *mint || token_account.owner != *escrow_authority {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
+/// Transfers tokens out of an escrow token account, signing as the escrow
+/// authority PDA.
+fn release_escrow_tokens<'a>(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    escrow_token_account: &AccountInfo<'a>,
+    escrow_authority: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    let (expected_authority, authority_bump) = get_escrow_authority_address(program_id);
+    if expected_authority != *escrow_authority.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    validate_escrow_token_account(escrow_token_account, mint, &expected_authority)?;
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            escrow_token_account.key,
+            destination.key,
+            escrow_authority.key,
+            &[],
+            amount,
+        )?,
+        &[
+            escrow_token_account.clone(),
+            destination.clone(),
+            escrow_authority.clone(),
+            token_program.clone(),
+        ],
+        &[&[ESCROW_AUTHORITY_SEED, &[authority_bump]]],
+    )
+}
+
+fn complete_cross_chain(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    transfer_data: CrossChainTransferData,
+    evidence: TransportEvidence,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let recipient_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let record_account = next_account_info(account_info_iter)?;
+    let activity_account = next_account_info(account_info_iter)?;
+    let source_chain_account = next_account_info(account_info_iter)?;
+    let instructions_sysvar = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+    let policy_account = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.config.bridge_authority != *authority.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    require_top_level(program_id, &state.config, instructions_sysvar)?;
+
+    if state.is_paused(PauseFlags::BRIDGE_IN) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    if state.bridge_config.blocks_inbound() {
+        return Err(CrossChainError::BreakerTripped.into());
+    }
+
+    // Outbound transfers are confirmed by the chain they were delivered to.
+    let confirming_chain = transfer_data.confirming_chain(state.config.hub_chain_id);
+    let (expected_chain, _) = get_chain_config_address(confirming_chain, program_id);
+    if expected_chain != *source_chain_account.key || source_chain_account.owner != program_id {
+        return Err(CrossChainError::ChainNotRegistered.into());
+    }
+    let source_chain = ChainConfig::try_from_slice(&source_chain_account.data.borrow())?;
+
+    let policy = load_chain_pair_policy(
+        program_id,
+        policy_account,
+        transfer_data.source_chain,
+        transfer_data.destination_chain,
+    )?;
+    transport_for(source_chain.transport).verify_message(
+        &TransportContext {
+            program_id,
+            state: &state,
+        },
+        &InboundTransfer {
+            transfer: &transfer_data,
+            source_chain: &source_chain,
+            policy: &policy,
+            evidence: &evidence,
+        },
+        instructions_sysvar,
+        account_info_iter,
+    )?;
+
+    let (expected_record, _) =
+        get_transfer_record_address(&transfer_data.sender, transfer_data.nonce, program_id);
+    if expected_record != *record_account.key {
+        // No local escrow: the transfer originated remotely and settles in wrapped tokens.
+        // Transfers escrowed here passed the corridor policy when initiated.
+        policy.check_amount(transfer_data.amount)?;
+        return complete_wrapped_transfer(
+            program_id,
+            state_account,
+            state,
+            recipient_account,
+            authority,
+            record_account,
+            activity_account,
+            source_chain_account,
+            audit_account,
+            account_info_iter,
+            transfer_data,
+        );
+    }
+
+    if record_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut record = TransferRecord::try_from_slice(&record_account.data.borrow())?;
+
+    if record.transfer.status == TransferStatus::Completed {
+        return Err(CrossChainError::NonceAlreadyUsed.into());
+    }
+
+    if record.transfer.status != TransferStatus::Initiated {
+        return Err(CrossChainError::TransferNotInFlight.into());
+    }
+
+    let (expected_chain, _) = get_chain_config_address(
+        record.transfer.confirming_chain(state.config.hub_chain_id),
+        program_id,
+    );
+    if expected_chain != *source_chain_account.key || source_chain_account.owner != program_id {
+        return Err(CrossChainError::ChainNotRegistered.into());
+    }
+
+    let mut source_chain = ChainConfig::try_from_slice(&source_chain_account.data.borrow())?;
+    validate_source_chain(&source_chain)?;
+
+    let treasury_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    ensure_treasury(program_id, treasury_account, authority, system_program)?;
+
+    // In-flight transfers settle under the terms they were initiated with
+    // until the grace period lapses, then under their corridor's current ones.
+    if policy.source_chain != record.transfer.source_chain
+        || policy.destination_chain != record.transfer.destination_chain
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let clock = Clock::get()?;
+    record.apply_terms(&state.transfer_terms(&policy, clock.slot)?, clock.slot);
+
+    let release = record.release_amount();
+    let fee = record.transfer.fee;
+    let hold_for_claim = record.terms.claim_delivery && !record.wrapped;
+
+    match record.transfer.token_address {
+        _ if record.wrapped => {}
+        None => {
+            if !hold_for_claim {
+                if *recipient_account.key != record.transfer.recipient {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                transfer_lamports(record_account, recipient_account, release)?;
+            }
+            transfer_lamports(record_account, treasury_account, fee)?;
+        }
+        Some(mint) => {
+            let escrow_token_account = next_account_info(account_info_iter)?;
+            let escrow_authority = next_account_info(account_info_iter)?;
+            let token_program = next_account_info(account_info_iter)?;
+            let treasury_token_account = next_account_info(account_info_iter)?;
+
+            if !hold_for_claim {
+                let recipient_token =
+                    spl_token::state::Account::unpack(&recipient_account.data.borrow())?;
+                if recipient_token.owner != record.transfer.recipient
+                    || recipient_token.mint != mint
+                {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+
+                release_escrow_tokens(
+                    program_id,
+                    &mint,
+                    escrow_token_account,
+                    escrow_authority,
+                    token_program,
+                    recipient_account,
+                    release,
+                )?;
+            }
+
+            validate_escrow_token_account(treasury_token_account, &mint, treasury_account.key)?;
+            release_escrow_tokens(
+                program_id,
+                &mint,
+                escrow_token_account,
+                escrow_authority,
+                token_program,
+                treasury_token_account,
+                fee,
+            )?;
+        }
+    }
+
+    if !record.wrapped {
+        let (protocol_fee, relayer_fee) = split_fee(fee, record.relayer_fee);
+        state
+            .fee_ledger
+            .accrue(record.transfer.token_address, protocol_fee, relayer_fee)?;
+        state.fee_ledger.record_chain(
+            record.transfer.destination_chain,
+            record.transfer.token_address,
+            fee,
+        )?;
+        let settled = if hold_for_claim {
+            fee
+        } else {
+            fee.saturating_add(release)
+        };
+        state
+            .escrow_ledger
+            .debit(record.transfer.token_address, settled);
+    }
+    state.record_volume(release)?;
+    write_state(state_account, authority, system_program, &state)?;
+
+    source_chain.record_inbound(release)?;
+    source_chain.serialize(&mut &mut source_chain_account.data.borrow_mut()[..])?;
+
+    let action = if hold_for_claim {
+        // Funds stay in escrow until the recipient signs a ClaimCrossChain.
+        record.transfer.status = TransferStatus::Confirmed;
+        AuditAction::TransferConfirmed
+    } else {
+        record.transfer.status = TransferStatus::Completed;
+        record.completed_slot = clock.slot;
+        AuditAction::TransferCompleted
+    };
+    record.serialize(&mut &mut record_account.data.borrow_mut()[..])?;
+
+    update_activity_log(
+        program_id,
+        activity_account,
+        &record.transfer.sender,
+        WalletActivityLog::close_transfer,
+    )?;
+
+    append_activity(
+        program_id,
+        activity_account,
+        &record.transfer.sender,
+        transfer_activity(&record, TransactionStatus::Confirmed),
+    )?;
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(action, *authority.key, *record_account.key, release, &clock),
+    )?;
+
+    HubEvent::TransferCompleted {
+        record: *record_account.key,
+        recipient: record.transfer.recipient,
+        amount: release,
+        fee,
+        claimable: hold_for_claim,
+    }
+    .emit();
+
+    if hold_for_claim {
+        msg!(
+            "Transfer confirmed: {} claimable by {}",
+            release,
+            record.transfer.recipient
+        );
+        return Ok(());
+    }
+
+    msg!(
+        "Completed cross-chain transfer: {} to {}",
+        release,
+        recipient_account.key
+    );
+
+    Ok(())
+}
+
+/// Creates the fee treasury PDA on first use.
+fn ensure_treasury<'a>(
+    program_id: &Pubkey,
+    treasury_account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let (expected_treasury, bump) = get_treasury_address(program_id);
+    if expected_treasury != *treasury_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if treasury_account.owner != program_id {
+        create_pda_account(
+            payer,
+            treasury_account,
+            system_program,
+            program_id,
+            0,
+            0,
+            &[TREASURY_SEED, &[bump]],
+        )?;
+    }
+    Ok(())
+}
+
+/// Mints the hub's wrapped representation of a remote token to the recipient
+/// and records an inbound receipt so the remote nonce cannot be replayed.
+#[allow(clippy::too_many_arguments)]
+fn complete_wrapped_transfer<'a, 'b>(
+    program_id: &Pubkey,
+    state_account: &'b AccountInfo<'a>,
+    mut state: CrossChainState,
+    recipient_account: &'b AccountInfo<'a>,
+    authority: &'b AccountInfo<'a>,
+    receipt_account: &'b AccountInfo<'a>,
+    activity_account: &'b AccountInfo<'a>,
+    source_chain_account: &'b AccountInfo<'a>,
+    audit_account: &'b AccountInfo<'a>,
+    account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    transfer_data: CrossChainTransferData,
+) -> ProgramResult {
+    let registry_account = next_account_info(account_info_iter)?;
+    let wrapped_mint = next_account_info(account_info_iter)?;
+    let mint_authority = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    let (expected_receipt, receipt_bump) = get_inbound_receipt_address(
+        transfer_data.source_chain,
+        &transfer_data.sender,
+        transfer_data.nonce,
+        program_id,
+    );
+    if expected_receipt != *receipt_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if receipt_account.owner == program_id {
+        return Err(CrossChainError::NonceAlreadyUsed.into());
+    }
+
+    let (expected_chain, _) = get_chain_config_address(transfer_data.source_chain, program_id);
+    if expected_chain != *source_chain_account.key || source_chain_account.owner != program_id {
+        return Err(CrossChainError::ChainNotRegistered.into());
+    }
+
+    let mut source_chain = ChainConfig::try_from_slice(&source_chain_account.data.borrow())?;
+    validate_source_chain(&source_chain)?;
+
+    let (expected_registry, _) = get_wrapped_registry_address(program_id);
+    if expected_registry != *registry_account.key || registry_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let registry = WrappedAssetRegistry::deserialize(&mut &registry_account.data.borrow()[..])?;
+    let remote_token = transfer_data
+        .token_address
+        .ok_or(CrossChainError::UnknownWrappedAsset)?;
+    let asset = registry
+        .find_by_remote(transfer_data.source_chain, &remote_token)
+        .ok_or(CrossChainError::UnknownWrappedAsset)?;
+
+    if asset.local_mint != *wrapped_mint.key {
+        return Err(CrossChainError::UnknownWrappedAsset.into());
+    }
+
+    let (expected_authority, authority_bump) = get_mint_authority_address(program_id);
+    if expected_authority != *mint_authority.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let recipient_token = spl_token::state::Account::unpack(&recipient_account.data.borrow())?;
+    if recipient_token.owner != transfer_data.recipient || recipient_token.mint != asset.local_mint
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let clock = Clock::get()?;
+    let receipt = TransferRecord {
+        transfer: CrossChainTransferData {
+            status: TransferStatus::Completed,
+            ..transfer_data
+        },
+        escrowed_amount: 0,
+        created_slot: clock.slot,
+        completed_slot: clock.slot,
+        bump: receipt_bump,
+        wrapped: true,
+        relayer_fee: 0,
+        timeout_slots: 0,
+        gateway_codehash: [0u8; 32],
+        terms: TransferTerms::default(),
+    };
+    let mint_amount =
+        asset.to_local_amount(receipt.transfer.amount.saturating_sub(receipt.transfer.fee))?;
+
+    create_pda_account(
+        authority,
+        receipt_account,
+        system_program,
+        program_id,
+        receipt.space(),
+        0,
+        &[
+            INBOUND_RECEIPT_SEED,
+            &receipt.transfer.source_chain.to_le_bytes(),
+            receipt.transfer.sender.as_ref(),
+            &receipt.transfer.nonce.to_le_bytes(),
+            &[receipt_bump],
+        ],
+    )?;
+    receipt.serialize(&mut &mut receipt_account.data.borrow_mut()[..])?;
+
+    invoke_signed(
+        &spl_token::instruction::mint_to(
+            token_program.key,
+            wrapped_mint.key,
+            recipient_account.key,
+            mint_authority.key,
+            &[],
+            mint_amount,
+        )?,
+        &[
+            wrapped_mint.clone(),
+            recipient_account.clone(),
+            mint_authority.clone(),
+            token_program.clone(),
+        ],
+        &[&[MINT_AUTHORITY_SEED, &[authority_bump]]],
+    )?;
+
+    state.record_volume(mint_amount)?;
+    state.pack_into(&mut state_account.data.borrow_mut()[..])?;
+    source_chain.record_inbound(mint_amount)?;
+    source_chain.serialize(&mut &mut source_chain_account.data.borrow_mut()[..])?;
+
+    append_activity(
+        program_id,
+        activity_account,
+        &receipt.transfer.recipient,
+        transfer_activity(&receipt, TransactionStatus::Confirmed),
+    )?;
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            AuditAction::TransferCompleted,
+            *authority.key,
+            *receipt_account.key,
+            mint_amount,
+            &clock,
+        ),
+    )?;
+
+    msg!(
+        "Minted {} wrapped tokens from chain {} to {}",
+        mint_amount,
+        receipt.transfer.source_chain,
+        recipient_account.key
+    );
+    Ok(())
+}
+
+fn create_qualified_signature(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    signature_data: QualifiedSignatureData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let revocation_account = next_account_info(account_info_iter)?;
+    let instructions_sysvar = next_account_info(account_info_iter)?;
+
+    let parsed = parse_certificate(&signature_data.certificate)?;
+    let revocations = load_revocation_list(program_id, revocation_account, &parsed.issuer_hash)?;
+    check_revocation(revocations.as_ref(), &parsed.serial_number)?;
+
+    let certificate = parsed.to_qualified_certificate()?;
+    validate_certificate(&certificate, Clock::get()?.unix_timestamp)?;
+    verify_certificate_signature(
+        &parsed,
+        &signature_data.signature_algorithm,
+        &signature_data.signature,
+        &signature_data.data_to_sign,
+        instructions_sysvar,
+    )?;
+
+    msg!(
+        "Creating eIDAS qualified signature for {} ({})",
+        certificate.subject,
+        certificate.country
+    );
+    Ok(())
+}
+
+/// Accepts a qualified signature only if its certificate was issued by a
+/// provider in the trusted issuer registry and has not been revoked.
+fn verify_qualified_signature_on_chain(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    signature_data: QualifiedSignatureData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let registry_account = next_account_info(account_info_iter)?;
+    let revocation_account = next_account_info(account_info_iter)?;
+    let instructions_sysvar = next_account_info(account_info_iter)?;
+
+    let (expected_registry, _) = get_trusted_issuer_registry_address(program_id);
+    if expected_registry != *registry_account.key || registry_account.owner != program_id {
+        return Err(CrossChainError::UntrustedIssuer.into());
+    }
+
+    let parsed = parse_certificate(&signature_data.certificate)?;
+    let registry = TrustedIssuerRegistry::deserialize(&mut &registry_account.data.borrow()[..])?;
+    if !registry.is_trusted(&parsed.issuer_hash) {
+        return Err(CrossChainError::UntrustedIssuer.into());
+    }
+
+    let revocations = load_revocation_list(program_id, revocation_account, &parsed.issuer_hash)?;
+    check_revocation(revocations.as_ref(), &parsed.serial_number)?;
+
+    let certificate = parsed.to_qualified_certificate()?;
+    validate_certificate(&certificate, Clock::get()?.unix_timestamp)?;
+    verify_certificate_signature(
+        &parsed,
+        &signature_data.signature_algorithm,
+        &signature_data.signature,
+        &signature_data.data_to_sign,
+        instructions_sysvar,
+    )?;
+
+    msg!(
+        "Verified eIDAS qualified signature for {} issued by {}",
+        certificate.subject,
+        certificate.issuer
+    );
+    Ok(())
+}
+
+/// Records a qualified electronic seal attached by a corporate wallet owner.
+/// The certificate must be a QCert for eSeal naming the organization, issued
+/// by a trusted provider and not revoked.
+fn create_qualified_seal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    seal_data: QualifiedSealData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let seal_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let registry_account = next_account_info(account_info_iter)?;
+    let revocation_account = next_account_info(account_info_iter)?;
+    let instructions_sysvar = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let (parsed, certificate, organization_identifier) = check_seal_certificate(
+        program_id,
+        registry_account,
+        revocation_account,
+        &seal_data,
+        now,
+    )?;
+    verify_certificate_signature(
+        &parsed,
+        &seal_data.signature_algorithm,
+        &seal_data.seal,
+        &seal_data.data_to_seal,
+        instructions_sysvar,
+    )?;
+
+    let data_hash = solana_program::hash::hash(&seal_data.data_to_seal).to_bytes();
+    let (expected_seal, bump) = get_qualified_seal_address(owner.key, &data_hash, program_id);
+    if expected_seal != *seal_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if seal_account.owner == program_id {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let record = QualifiedSeal {
+        owner: *owner.key,
+        seal: seal_data.seal.into_inner(),
+        certificate,
+        organization: parsed.subject.organization.clone(),
+        organization_identifier,
+        sealed_data_hash: data_hash,
+        timestamp: seal_data.timestamp.unwrap_or(now),
+        signature_algorithm: seal_data.signature_algorithm.into_inner(),
+    };
+    let data = record.try_to_vec()?;
+
+    create_pda_account(
+        owner,
+        seal_account,
+        system_program,
+        program_id,
+        data.len(),
+        0,
+        &[QUALIFIED_SEAL_SEED, owner.key.as_ref(), &data_hash, &[bump]],
+    )?;
+    seal_account.data.borrow_mut().copy_from_slice(&data);
+
+    msg!(
+        "Qualified seal by {} ({}) attached by {}",
+        record.organization,
+        parsed.subject.organization_identifier,
+        owner.key
+    );
+    Ok(())
+}
+
+fn verify_qualified_seal_on_chain(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    seal_data: QualifiedSealData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let registry_account = next_account_info(account_info_iter)?;
+    let revocation_account = next_account_info(account_info_iter)?;
+    let instructions_sysvar = next_account_info(account_info_iter)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let (parsed, _, _) = check_seal_certificate(
+        program_id,
+        registry_account,
+        revocation_account,
+        &seal_data,
+        now,
+    )?;
+    verify_certificate_signature(
+        &parsed,
+        &seal_data.signature_algorithm,
+        &seal_data.seal,
+        &seal_data.data_to_seal,
+        instructions_sysvar,
+    )?;
+
+    msg!(
+        "Verified eIDAS qualified seal for {} ({})",
+        parsed.subject.organization,
+        parsed.subject.organization_identifier
+    );
+    Ok(())
+}
+
+fn check_seal_certificate(
+    program_id: &Pubkey,
+    registry_account: &AccountInfo,
+    revocation_account: &AccountInfo,
+    seal_data: &QualifiedSealData,
+    now: i64,
+) -> Result<
+    (
+        ParsedCertificate,
+        QualifiedCertificate,
+        OrganizationIdentifier,
+    ),
+    ProgramError,
+> {
+    let (expected_registry, _) = get_trusted_issuer_registry_address(program_id);
+    if expected_registry != *registry_account.key || registry_account.owner != program_id {
+        return Err(CrossChainError::UntrustedIssuer.into());
+    }
+
+    let parsed = parse_certificate(&seal_data.certificate)?;
+    let registry = TrustedIssuerRegistry::deserialize(&mut &registry_account.data.borrow()[..])?;
+    if !registry.is_trusted(&parsed.issuer_hash) {
+        return Err(CrossChainError::UntrustedIssuer.into());
+    }
+
+    let revocations = load_revocation_list(program_id, revocation_account, &parsed.issuer_hash)?;
+    check_revocation(revocations.as_ref(), &parsed.serial_number)?;
+
+    let (certificate, organization_identifier) = parsed.to_seal_certificate()?;
+    validate_certificate(&certificate, now)?;
+    Ok((parsed, certificate, organization_identifier))
+}
+
+/// Checks the signature was made with the certificate's key, using the
+/// native ed25519 or secp256k1 program instructions earlier in the same
+/// transaction. The declared algorithm must match the certificate's key type.
+fn verify_certificate_signature(
+    certificate: &ParsedCertificate,
+    signature_algorithm: &str,
+    signature: &[u8],
+    message: &[u8],
+    instructions_sysvar: &AccountInfo,
+) -> ProgramResult {
+    let algorithm = SignatureAlgorithm::from_name(signature_algorithm)?;
+    match certificate.public_key_algorithm {
+        Some(key_algorithm) if key_algorithm == algorithm => {}
+        Some(_) => return Err(CrossChainError::InvalidQualifiedSignature.into()),
+        None => return Err(CrossChainError::UnsupportedSignatureAlgorithm.into()),
+    }
+
+    let current = sysvar_instructions::load_current_index_checked(instructions_sysvar)?;
+    let mut ed25519 = Vec::new();
+    let mut secp256k1 = Vec::new();
+    for index in 0..current {
+        let ix =
+            sysvar_instructions::load_instruction_at_checked(index as usize, instructions_sysvar)?;
+        if ix.program_id == solana_program::ed25519_program::id() {
+            ed25519.extend(
+                parse_ed25519_instruction(&ix.data)
+                    .map_err(|_| CrossChainError::InvalidQualifiedSignature)?,
+            );
+        } else if ix.program_id == solana_program::secp256k1_program::id() {
+            secp256k1.extend(parse_secp256k1_instruction(&ix.data, index as u8)?);
+        }
+    }
+
+    verify_with_precompiles(
+        algorithm,
+        &certificate.subject_public_key,
+        signature,
+        message,
+        &ed25519,
+        &secp256k1,
+    )?;
+    Ok(())
+}
+
+/// Loads the issuer's revocation list, or `None` if nothing was ever revoked
+/// for it. The account must still be the issuer's PDA so a caller cannot hide
+/// a revocation by passing some other empty account.
+fn load_revocation_list(
+    program_id: &Pubkey,
+    revocation_account: &AccountInfo,
+    issuer_hash: &[u8; 32],
+) -> Result<Option<RevocationList>, ProgramError> {
+    let (expected, _) = get_revocation_list_address(issuer_hash, program_id);
+    if expected != *revocation_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if revocation_account.owner != program_id {
+        return Ok(None);
+    }
+
+    Ok(Some(RevocationList::deserialize(
+        &mut &revocation_account.data.borrow()[..],
+    )?))
+}
+
+/// Verifies an RFC 3161 token over the SHA-256 of the supplied data and
+/// records it as a `QualifiedTimestamp` PDA keyed by that hash. The TSA must
+/// be issued by a trusted provider and not revoked; `require_qts` also
+/// demands a qualified (QcCompliance) TSA certificate.
+fn create_timestamp(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    timestamp_data: TimestampData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let timestamp_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let registry_account = next_account_info(account_info_iter)?;
+    let revocation_account = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if !timestamp_data
+        .hash_algorithm
+        .eq_ignore_ascii_case(TIMESTAMP_HASH_ALGORITHM)
+    {
+        return Err(CrossChainError::InvalidTimestampToken.into());
+    }
+
+    let data_hash = solana_program::hash::hash(&timestamp_data.data_to_timestamp).to_bytes();
+    let token = parse_timestamp_token(&timestamp_data.ts_token)?;
+    validate_timestamp_token(&token, &data_hash, Clock::get()?.unix_timestamp)?;
+
+    let tsa = &token.tsa_certificate;
+    let (expected_registry, _) = get_trusted_issuer_registry_address(program_id);
+    if expected_registry != *registry_account.key || registry_account.owner != program_id {
+        return Err(CrossChainError::UntrustedIssuer.into());
+    }
+    let registry = TrustedIssuerRegistry::deserialize(&mut &registry_account.data.borrow()[..])?;
+    if !registry.is_trusted(&tsa.issuer_hash) {
+        return Err(CrossChainError::UntrustedIssuer.into());
+    }
+
+    let revocations = load_revocation_list(program_id, revocation_account, &tsa.issuer_hash)?;
+    check_revocation(revocations.as_ref(), &tsa.serial_number)?;
+
+    let tsa_certificate = if timestamp_data.require_qts {
+        tsa.to_qualified_certificate()?
+    } else {
+        tsa.to_certificate()
+    };
+
+    let (expected_timestamp, bump) = get_timestamp_address(&data_hash, program_id);
+    if expected_timestamp != *timestamp_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if timestamp_account.owner == program_id {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let record = QualifiedTimestamp {
+        ts_token: timestamp_data.ts_token.into_inner(),
+        tsa_certificate,
+        time: token.gen_time,
+        hash_algorithm: TIMESTAMP_HASH_ALGORITHM.to_string(),
+        hash_value: data_hash.to_vec(),
+    };
+    let data = record.try_to_vec()?;
+
+    create_pda_account(
+        payer,
+        timestamp_account,
+        system_program,
+        program_id,
+        data.len(),
+        0,
+        &[TIMESTAMP_SEED, &data_hash, &[bump]],
+    )?;
+    timestamp_account.data.borrow_mut().copy_from_slice(&data);
+
+    msg!(
+        "Qualified timestamp {} recorded at {}",
+        hex::encode(data_hash),
+        record.time
+    );
+    Ok(())
+}
+
+/// Queues an encrypted computation with Arcium by CPI and opens the
+/// computation account its callback writes the result to. Accounts after the
+/// system program are Arcium's own and are passed through unchanged.
+fn process_arcium_transaction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    arcium_data: ArciumTransactionData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let arcium_program = next_account_info(account_info_iter)?;
+    let requester = next_account_info(account_info_iter)?;
+    let computation_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let arcium_accounts: Vec<AccountInfo> = account_info_iter.cloned().collect();
+
+    if !requester.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.arcium_program != *arcium_program.key
+        || arcium_data.arcium_program_id != *arcium_program.key
+    {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if state.is_paused(PauseFlags::ARCIUM) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    let config = ArciumConfig {
+        arcium_program: state.config.arcium_program,
+        ..Default::default()
+    };
+    validate_arcium_transaction(&arcium_data, &config)?;
+
+    let (expected_computation, bump) =
+        get_arcium_computation_address(requester.key, arcium_data.computation_offset, program_id);
+    if expected_computation != *computation_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if computation_account.owner == program_id {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    create_pda_account(
+        requester,
+        computation_account,
+        system_program,
+        program_id,
+        ArciumComputation::LEN,
+        0,
+        &[
+            ARCIUM_COMPUTATION_SEED,
+            requester.key.as_ref(),
+            &arcium_data.computation_offset.to_le_bytes(),
+            &[bump],
+        ],
+    )?;
+
+    let computation = ArciumComputation::new(
+        *requester.key,
+        &arcium_data,
+        *arcium_program.key,
+        Clock::get()?.slot,
+        bump,
+    );
+    computation.serialize(&mut &mut computation_account.data.borrow_mut()[..])?;
+
+    let instruction = queue_computation_instruction(
+        arcium_program.key,
+        requester.key,
+        computation_account.key,
+        program_id,
+        &arcium_data,
+        arcium_accounts
+            .iter()
+            .map(|a| AccountMeta {
+                pubkey: *a.key,
+                is_signer: a.is_signer,
+                is_writable: a.is_writable,
+            })
+            .collect(),
+    )?;
+
+    let mut cpi_accounts = vec![requester.clone(), computation_account.clone()];
+    cpi_accounts.extend(arcium_accounts);
+    cpi_accounts.push(arcium_program.clone());
+    invoke(&instruction, &cpi_accounts)?;
+
+    msg!(
+        "Queued Arcium computation {} for {}",
+        arcium_data.computation_offset,
+        requester.key
+    );
+    Ok(())
+}
+
+/// Result callback from Arcium. Only Arcium can sign as its callback
+/// authority PDA, so the result cannot be forged by calling in directly.
+fn arcium_callback(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    success: bool,
+    result: Vec<u8>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let computation_account = next_account_info(account_info_iter)?;
+    let callback_authority = next_account_info(account_info_iter)?;
+
+    if !callback_authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if computation_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let state = load_state(program_id, state_account)?;
+    let mut computation =
+        ArciumComputation::deserialize(&mut &computation_account.data.borrow()[..])?;
+
+    let (expected_authority, _) = get_arcium_callback_authority(&state.config.arcium_program);
+    if computation.arcium_program != state.config.arcium_program
+        || expected_authority != *callback_authority.key
+    {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    computation.resolve(success, result, Clock::get()?.slot)?;
+    computation.serialize(&mut &mut computation_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Arcium computation {} for {} {}",
+        computation.computation_offset,
+        computation.requester,
+        if success { "completed" } else { "failed" }
+    );
+    Ok(())
+}
+
+/// Checks `owner` signed for the wallet PDA it owns and returns the wallet.
+/// Loads a wallet, rejecting any account that is not the program-owned PDA
+/// of the owner it records.
+fn load_wallet(program_id: &Pubkey, wallet_account: &AccountInfo) -> Result<Wallet, ProgramError> {
+    if wallet_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let wallet = Wallet::unpack(&wallet_account.data.borrow())?;
+    let (expected_wallet, _) = get_wallet_address(&wallet.owner, program_id);
+    if expected_wallet != *wallet_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(wallet)
+}
+
+/// Resolves who signed for `owner`: the owner itself, or a session key whose
+/// scope covers the operation. The returned signer pays for anything the
+/// instruction creates.
+fn authorize_owner<'b, 'a>(
+    program_id: &Pubkey,
+    owner: &'b AccountInfo<'a>,
+    session_account: &AccountInfo,
+    session_signer: &'b AccountInfo<'a>,
+    permission: SessionPermissions,
+    amount: u64,
+    destination_chain: u64,
+) -> Result<&'b AccountInfo<'a>, ProgramError> {
+    if owner.is_signer {
+        return Ok(owner);
+    }
+
+    if !session_signer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected, _) = get_session_key_address(owner.key, session_signer.key, program_id);
+    if expected != *session_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if session_account.owner != program_id {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    SessionKey::unpack(&session_account.data.borrow())?.authorize(
+        permission,
+        amount,
+        destination_chain,
+        Clock::get()?.slot,
+    )?;
+    Ok(session_signer)
+}
+
+fn load_owned_wallet(
+    program_id: &Pubkey,
+    wallet_account: &AccountInfo,
+    owner: &AccountInfo,
+) -> Result<Wallet, ProgramError> {
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_wallet, _) = get_wallet_address(owner.key, program_id);
+    if expected_wallet != *wallet_account.key || wallet_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let wallet = Wallet::unpack(&wallet_account.data.borrow())?;
+    if wallet.owner != *owner.key {
+        return Err(CrossChainError::WalletOwnerMismatch.into());
+    }
+    Ok(wallet)
+}
+
+/// Signatures the ed25519 program verified earlier in this transaction.
+pub(crate) fn load_ed25519_signatures(
+    instructions_sysvar: &AccountInfo,
+) -> Result<Vec<VerifiedSignature>, ProgramError> {
+    let current = sysvar_instructions::load_current_index_checked(instructions_sysvar)?;
+    let mut verified = Vec::new();
+    for index in 0..current {
+        let ix =
+            sysvar_instructions::load_instruction_at_checked(index as usize, instructions_sysvar)?;
+        if ix.program_id == solana_program::ed25519_program::id() {
+            verified.extend(parse_ed25519_instruction(&ix.data)?);
+        }
+    }
+    Ok(verified)
+}
+
+/// Signatures verified by the ed25519 instruction at `index`, which must come
+/// earlier in this transaction.
+pub(crate) fn load_ed25519_instruction(
+    instructions_sysvar: &AccountInfo,
+    index: u8,
+) -> Result<Vec<VerifiedSignature>, ProgramError> {
+    let current = sysvar_instructions::load_current_index_checked(instructions_sysvar)?;
+    if u16::from(index) >= current {
+        return Err(CrossChainError::InvalidRelaySignature.into());
+    }
+    let ix = sysvar_instructions::load_instruction_at_checked(index as usize, instructions_sysvar)?;
+    if ix.program_id != solana_program::ed25519_program::id() {
+        return Err(CrossChainError::InvalidRelaySignature.into());
+    }
+    Ok(parse_ed25519_instruction(&ix.data)?)
+}
+
+/// Range proofs the ZK ElGamal proof program verified earlier in this
+/// transaction. The program cannot be invoked by CPI, so its instructions
+/// must sit at the top level before the current one.
+fn load_verified_range_proofs(
+    instructions_sysvar: &AccountInfo,
+) -> Result<Vec<RangeProofContext>, ProgramError> {
+    let current = sysvar_instructions::load_current_index_checked(instructions_sysvar)?;
+    let mut verified = Vec::new();
+    for index in 0..current {
+        let ix =
+            sysvar_instructions::load_instruction_at_checked(index as usize, instructions_sysvar)?;
+        if ix.program_id == ZK_ELGAMAL_PROOF_PROGRAM_ID {
+            if let Ok(context) = parse_range_proof_instruction(&ix.data) {
+                verified.push(context);
+            }
+        }
+    }
+    Ok(verified)
+}
+
+/// Opens the wallet's encrypted state account with its first ciphertext and
+/// commitment.
+fn init_encrypted_state(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    state_data: EncryptedStateData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let encrypted_state_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    load_owned_wallet(program_id, wallet_account, owner)?;
+
+    let state = load_state(program_id, state_account)?;
+    if state.is_paused(PauseFlags::ARCIUM) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    let config = ArciumConfig {
+        arcium_program: state.config.arcium_program,
+        ..Default::default()
+    };
+    validate_encrypted_state(&state_data, &config)?;
+
+    let (expected_state, bump) = get_encrypted_state_address(wallet_account.key, program_id);
+    if expected_state != *encrypted_state_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if encrypted_state_account.owner == program_id {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let encrypted_state =
+        EncryptedWalletState::new(*wallet_account.key, state_data, Clock::get()?.slot, bump);
+    let data = encrypted_state.try_to_vec()?;
+    create_pda_account(
+        owner,
+        encrypted_state_account,
+        system_program,
+        program_id,
+        data.len(),
+        0,
+        &[ENCRYPTED_STATE_SEED, wallet_account.key.as_ref(), &[bump]],
+    )?;
+    encrypted_state_account
+        .data
+        .borrow_mut()
+        .copy_from_slice(&data);
+
+    msg!("Encrypted state opened for wallet {}", wallet_account.key);
+    Ok(())
+}
+
+/// Replaces the wallet's encrypted state. A range proof verified in this
+/// transaction must cover both the stored and the new commitment.
+fn update_encrypted_state(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    state_data: EncryptedStateData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let encrypted_state_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let instructions_sysvar = next_account_info(account_info_iter)?;
+
+    load_owned_wallet(program_id, wallet_account, owner)?;
+
+    let state = load_state(program_id, state_account)?;
+    if state.is_paused(PauseFlags::ARCIUM) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    let config = ArciumConfig {
+        arcium_program: state.config.arcium_program,
+        ..Default::default()
+    };
+    validate_encrypted_state(&state_data, &config)?;
+
+    let (expected_state, _) = get_encrypted_state_address(wallet_account.key, program_id);
+    if expected_state != *encrypted_state_account.key || encrypted_state_account.owner != program_id
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let current = EncryptedWalletState::try_from_slice(&encrypted_state_account.data.borrow())?;
+    if current.wallet != *wallet_account.key {
+        return Err(CrossChainError::WalletOwnerMismatch.into());
+    }
+
+    verify_state_transition(
+        &current.ciphertext_commitment,
+        &state_data.ciphertext_commitment,
+        &load_verified_range_proofs(instructions_sysvar)?,
+    )?;
+
+    let updated =
+        EncryptedWalletState::new(current.wallet, state_data, Clock::get()?.slot, current.bump);
+    let data = updated.try_to_vec()?;
+    if data.len() != encrypted_state_account.data_len() {
+        resize_account(encrypted_state_account, owner, system_program, data.len())?;
+    }
+    encrypted_state_account.data.borrow_mut()[..data.len()].copy_from_slice(&data);
+
+    msg!("Encrypted state updated for wallet {}", wallet_account.key);
+    Ok(())
+}
+
+fn get_config(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let state_account = next_account_info(&mut accounts.iter())?;
+    let state = load_state(program_id, state_account)?;
+    set_return_data(&ConfigView::from(&state).try_to_vec()?);
+    Ok(())
+}
+
+/// Publishes the owner's compliance status to `destination_chain` as a
+/// `ComplianceAttestation` bridge message. The attestation must restate the
+/// owner's compliance record and be signed by the eIDAS authority in an
+/// ed25519 instruction earlier in the transaction.
+fn export_attestation(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    signed: SignedComplianceAttestation,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let compliance_account = next_account_info(account_info_iter)?;
+    let chain_account = next_account_info(account_info_iter)?;
+    let instructions_sysvar = next_account_info(account_info_iter)?;
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.is_paused(PauseFlags::BRIDGE_OUT) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    let wallet = load_owned_wallet(program_id, wallet_account, owner)?;
+    validate_wallet_active(&wallet)?;
+
+    let attestation = &signed.attestation;
+    if attestation.authority != state.config.eidas_authority {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let (expected_chain, _) = get_chain_config_address(attestation.destination_chain, program_id);
+    if expected_chain != *chain_account.key || chain_account.owner != program_id {
+        return Err(CrossChainError::ChainNotRegistered.into());
+    }
+    let chain = ChainConfig::try_from_slice(&chain_account.data.borrow())?;
+    validate_destination_chain(&chain, &wallet.eidas_level)?;
+
+    let clock = Clock::get()?;
+    let record = load_compliance_record(program_id, compliance_account, owner.key)?
+        .ok_or(CrossChainError::ComplianceRequired)?;
+    attestation.validate(&record, clock.unix_timestamp)?;
+    signed.verify(
+        &state.signing_domain(program_id),
+        &load_ed25519_signatures(instructions_sysvar)?,
+    )?;
+
+    let message = CrossChainMessage {
+        id: owner.key.to_string().into(),
+        // The hub itself is the source of an attestation.
+        source_chain: 0,
+        destination_chain: attestation.destination_chain,
+        sender: *owner.key,
+        recipient: *owner.key,
+        message_type: MessageType::ComplianceAttestation,
+        payload: signed.try_to_vec()?.into(),
+        nonce: attestation.issued_at as u64,
+        timestamp: clock.unix_timestamp,
+        gateway_codehash: chain.gateway_codehash,
+        compression: PayloadCompression::None,
+    };
+    validate_bridge_message(&message).map_err(CrossChainError::from)?;
+    sol_log_data(&[&encode_bridge_message(&message)]);
+
+    HubEvent::AttestationExported {
+        owner: *owner.key,
+        destination_chain: attestation.destination_chain,
+        eidas_level: attestation.eidas_level.clone(),
+        expires_at: attestation.expires_at,
+    }
+    .emit();
+    msg!(
+        "Exported compliance attestation for {} to chain {}",
+        owner.key,
+        attestation.destination_chain
+    );
+    Ok(())
+}
+
+/// Answers with the hub totals and the volume of every chain config account
+/// passed after the state.
+fn get_statistics(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let state = load_state(program_id, state_account)?;
+
+    let mut view = StatisticsView::from(&state);
+    for chain_account in account_info_iter {
+        if chain_account.owner != program_id {
+            return Err(CrossChainError::ChainNotRegistered.into());
+        }
+        let chain = ChainConfig::try_from_slice(&chain_account.data.borrow())?;
+        let (expected_chain, _) = get_chain_config_address(chain.chain_id, program_id);
+        if expected_chain != *chain_account.key {
+            return Err(CrossChainError::ChainNotRegistered.into());
+        }
+        view.chains.push(ChainVolumeView::from(&chain));
+    }
+
+    set_return_data(&view.try_to_vec()?);
+    Ok(())
+}
+
+/// Answers for the wallet PDA of `owner`; an account that was never
+/// initialized answers `None` rather than failing the caller.
+fn get_wallet_status(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    owner: &Pubkey,
+) -> ProgramResult {
+    let wallet_account = next_account_info(&mut accounts.iter())?;
+    let (expected_wallet, _) = get_wallet_address(owner, program_id);
+    if expected_wallet != *wallet_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let status = if wallet_account.owner == program_id {
+        Some(WalletStatusView::from(&Wallet::unpack(
+            &wallet_account.data.borrow(),
+        )?))
+    } else {
+        None
+    };
+    set_return_data(&status.try_to_vec()?);
+    Ok(())
+}
+
+fn get_transfer_status(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    sender: &Pubkey,
+    nonce: u64,
+) -> ProgramResult {
+    let record_account = next_account_info(&mut accounts.iter())?;
+    let (expected_record, _) = get_transfer_record_address(sender, nonce, program_id);
+    if expected_record != *record_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let status = if record_account.owner == program_id {
+        Some(TransferStatusView::from(&TransferRecord::try_from_slice(
+            &record_account.data.borrow(),
+        )?))
+    } else {
+        None
+    };
+    set_return_data(&status.try_to_vec()?);
+    Ok(())
+}
+
+/// Tells a UI whether `InitiateCrossChain` would accept the transfer before
+/// any funds are escrowed. Failing checks are answered in return data rather
+/// than failing the instruction; only malformed accounts fail it.
+fn precheck_recipient(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    sender: &Pubkey,
+    recipient: &Pubkey,
+    destination_chain: u64,
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let wallet_account = next_account_info(account_info_iter)?;
+    let chain_account = next_account_info(account_info_iter)?;
+    let sender_compliance_account = next_account_info(account_info_iter)?;
+    let recipient_compliance_account = next_account_info(account_info_iter)?;
+    let restricted_account = next_account_info(account_info_iter)?;
+    let wallet_config_account = next_account_info(account_info_iter)?;
+
+    let state = load_state(program_id, state_account)?;
+
+    let (expected_wallet, _) = get_wallet_address(sender, program_id);
+    if expected_wallet != *wallet_account.key || wallet_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let wallet = Wallet::unpack(&wallet_account.data.borrow())?;
+
+    let (expected_chain, _) = get_chain_config_address(destination_chain, program_id);
+    if expected_chain != *chain_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let chain = if chain_account.owner == program_id {
+        Some(ChainConfig::try_from_slice(&chain_account.data.borrow())?)
+    } else {
+        None
+    };
+
+    let sender_compliance = load_compliance_record(program_id, sender_compliance_account, sender)?;
+    let recipient_compliance =
+        load_compliance_record(program_id, recipient_compliance_account, recipient)?;
+    let restricted = load_restricted_jurisdictions(program_id, restricted_account)?;
+    let wallet_config = load_wallet_config(program_id, wallet_config_account, sender)?;
+
+    let precheck = TransferPrecheck {
+        wallet: &wallet,
+        wallet_config: &wallet_config,
+        chain: chain.as_ref(),
+        sender_compliance: sender_compliance.as_ref(),
+        recipient_compliance: recipient_compliance.as_ref(),
+        restricted: &restricted,
+    };
+    let view = PrecheckView::from(precheck.evaluate(&state, amount, Clock::get()?.unix_timestamp));
+
+    match view.rejection {
+        Some(code) => msg!("Precheck rejected: error {}", code),
+        None => msg!("Precheck passed: fee {}", view.fee),
+    }
+    set_return_data(&view.try_to_vec()?);
+    Ok(())
+}
+
+fn post_intent(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    intent_data: TransferIntentData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let intent_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let state_account = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.is_paused(PauseFlags::BRIDGE_OUT) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    if state.bridge_config.blocks_outbound() {
+        return Err(CrossChainError::BreakerTripped.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    validate_intent(&intent_data, now)?;
+
+    let (expected, bump) = get_intent_address(owner.key, intent_data.nonce, program_id);
+    if expected != *intent_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let intent = TransferIntent {
+        owner: *owner.key,
+        amount: intent_data.amount,
+        destination_chain: intent_data.destination_chain,
+        recipient: intent_data.recipient,
+        max_fee: intent_data.max_fee,
+        deadline: intent_data.deadline,
+        nonce: intent_data.nonce,
+        status: IntentStatus::Open,
+        best_solver: None,
+        best_fee: 0,
+        fill_tx_hash: [0u8; 32],
+        created_at: now,
+        bump,
+    };
+
+    create_pda_account(
+        owner,
+        intent_account,
+        system_program,
+        program_id,
+        ACCOUNT_HEADER_LEN + TransferIntent::LEN,
+        intent.escrow_amount(),
+        &[
+            INTENT_SEED,
+            owner.key.as_ref(),
+            &intent_data.nonce.to_le_bytes(),
+            &[bump],
+        ],
+    )?;
+
+    intent.pack_into(&mut intent_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Intent posted: {} to chain {} with max fee {}",
+        intent.amount,
+        intent.destination_chain,
+        intent.max_fee
+    );
+    Ok(())
+}
+
+/// Loads an intent, rejecting any account that is not the program-owned PDA
+/// of the owner and nonce it records.
+fn load_intent(
+    program_id: &Pubkey,
+    intent_account: &AccountInfo,
+) -> Result<TransferIntent, ProgramError> {
+    if intent_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let intent = TransferIntent::unpack(&intent_account.data.borrow())?;
+    let (expected, _) = get_intent_address(&intent.owner, intent.nonce, program_id);
+    if expected != *intent_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(intent)
+}
+
+fn bid_intent(program_id: &Pubkey, accounts: &[AccountInfo], fee: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let intent_account = next_account_info(account_info_iter)?;
+    let solver = next_account_info(account_info_iter)?;
+
+    if !solver.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut intent = load_intent(program_id, intent_account)?;
+
+    validate_solver_bid(&intent, fee, Clock::get()?.unix_timestamp)?;
+
+    intent.best_solver = Some(*solver.key);
+    intent.best_fee = fee;
+    intent.pack_into(&mut intent_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Solver {} leads intent auction with fee {}",
+        solver.key,
+        fee
+    );
+    Ok(())
+}
+
+fn fill_intent(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fill_data: IntentFillData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let intent_account = next_account_info(account_info_iter)?;
+    let solver = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+
+    if !solver.is_signer || !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    // The bridge authority attests that the solver delivered on the destination chain.
+    if state.config.bridge_authority != *authority.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let mut intent = load_intent(program_id, intent_account)?;
+
+    if intent.status != IntentStatus::Open {
+        return Err(CrossChainError::IntentNotOpen.into());
+    }
+
+    if intent.best_solver != Some(*solver.key) {
+        return Err(CrossChainError::NotWinningSolver.into());
+    }
+
+    if intent.owner != *owner.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let (solver_payout, owner_refund) = settle_intent(&intent);
+    transfer_lamports(intent_account, solver, solver_payout)?;
+    transfer_lamports(intent_account, owner, owner_refund)?;
+
+    intent.status = IntentStatus::Filled;
+    intent.fill_tx_hash = fill_data.destination_tx_hash;
+    intent.pack_into(&mut intent_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Intent filled by {}: paid {} and refunded {}",
+        solver.key,
+        solver_payout,
+        owner_refund
+    );
+    Ok(())
+}
+
+fn cancel_intent(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let intent_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut intent = load_intent(program_id, intent_account)?;
+
+    if intent.owner != *owner.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    if intent.status != IntentStatus::Open {
+        return Err(CrossChainError::IntentNotOpen.into());
+    }
+
+    if !can_cancel_intent(&intent, Clock::get()?.unix_timestamp) {
+        return Err(CrossChainError::IntentAwaitingFill.into());
+    }
+
+    transfer_lamports(intent_account, owner, intent.escrow_amount())?;
+
+    intent.status = IntentStatus::Cancelled;
+    intent.pack_into(&mut intent_account.data.borrow_mut()[..])?;
+
+    msg!("Intent cancelled and escrow returned");
+    Ok(())
+}
+
+fn register_keeper_job(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    kind: KeeperJobKind,
+    job_config: KeeperJobConfig,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let job_account = next_account_info(account_info_iter)?;
+    let tip_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let (expected_job, bump) = get_keeper_job_address(kind, program_id);
+    let (expected_tip, tip_bump) = get_keeper_tip_address(&expected_job, program_id);
+    if expected_job != *job_account.key || expected_tip != *tip_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let job = KeeperJob {
+        kind,
+        tip_per_crank: job_config.tip_per_crank,
+        min_interval_slots: job_config.min_interval_slots,
+        enabled: job_config.enabled,
+        last_crank_slot: 0,
+        total_cranks: 0,
+        total_tips_paid: 0,
+        bump,
+        tip_bump,
+    };
+
+    create_pda_account(
+        admin,
+        job_account,
+        system_program,
+        program_id,
+        KeeperJob::LEN,
+        0,
+        &[KEEPER_JOB_SEED, &kind.seed(), &[bump]],
+    )?;
+    create_pda_account(
+        admin,
+        tip_account,
+        system_program,
+        program_id,
+        0,
+        0,
+        &[KEEPER_TIP_SEED, job_account.key.as_ref(), &[tip_bump]],
+    )?;
+
+    job.serialize(&mut &mut job_account.data.borrow_mut()[..])?;
+
+    msg!("Keeper job registered: {:?}", kind);
+    Ok(())
+}
+
+fn update_keeper_job(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    job_config: KeeperJobConfig,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let job_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if job_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let mut job = KeeperJob::try_from_slice(&job_account.data.borrow())?;
+    job.tip_per_crank = job_config.tip_per_crank;
+    job.min_interval_slots = job_config.min_interval_slots;
+    job.enabled = job_config.enabled;
+    job.serialize(&mut &mut job_account.data.borrow_mut()[..])?;
+
+    msg!("Keeper job updated: {:?}", job.kind);
+    Ok(())
+}
+
+fn fund_keeper_job(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let job_account = next_account_info(account_info_iter)?;
+    let tip_account = next_account_info(account_info_iter)?;
+    let funder = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !funder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if job_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let (expected_tip, _) = get_keeper_tip_address(job_account.key, program_id);
+    if expected_tip != *tip_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    invoke(
+        &system_instruction::transfer(funder.key, tip_account.key, amount),
+        &[funder.clone(), tip_account.clone(), system_program.clone()],
+    )?;
+
+    msg!("Keeper job funded with {} lamports", amount);
+    Ok(())
+}
+
+fn crank_keeper_job(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let job_account = next_account_info(account_info_iter)?;
+    let tip_account = next_account_info(account_info_iter)?;
+    let keeper = next_account_info(account_info_iter)?;
+
+    if !keeper.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if job_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut job = KeeperJob::try_from_slice(&job_account.data.borrow())?;
+
+    let (expected_tip, _) = get_keeper_tip_address(job_account.key, program_id);
+    if expected_tip != *tip_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let clock = Clock::get()?;
+    validate_crank(&job, clock.slot)?;
+
+    match job.kind {
+        KeeperJobKind::ExpireIntent => {
+            let intent_account = next_account_info(account_info_iter)?;
+            let intent_owner = next_account_info(account_info_iter)?;
+
+            let mut intent = load_intent(program_id, intent_account)?;
+
+            if intent.owner != *intent_owner.key {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            if !is_intent_expired(&intent, clock.unix_timestamp) {
+                return Err(CrossChainError::NothingToCrank.into());
+            }
+
+            transfer_lamports(intent_account, intent_owner, intent.escrow_amount())?;
+            intent.status = IntentStatus::Cancelled;
+            intent.pack_into(&mut intent_account.data.borrow_mut()[..])?;
+        }
+    }
+
+    let tip = payable_tip(
+        &job,
+        tip_account.lamports(),
+        Rent::get()?.minimum_balance(0),
+    );
+    transfer_lamports(tip_account, keeper, tip)?;
+
+    job.last_crank_slot = clock.slot;
+    job.total_cranks = job.total_cranks.saturating_add(1);
+    job.total_tips_paid = job.total_tips_paid.saturating_add(tip);
+    job.serialize(&mut &mut job_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Keeper {} cranked {:?} for tip {}",
+        keeper.key,
+        job.kind,
+        tip
+    );
+    Ok(())
+}
+
+fn register_chain(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    chain_id: u64,
+    chain_data: ChainConfigData,
+) -> ProgramResult {
+    register_chain_with_caip2(
+        program_id,
+        accounts,
+        Caip2ChainId::from_legacy(chain_id),
+        chain_data,
+    )
+}
+
+/// Registers a chain by its CAIP-2 identifier. The registry PDA stays keyed by
+/// the u64 `legacy_id`, so chains registered by numeric id keep their address.
+fn register_chain_with_caip2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    caip2: Caip2ChainId,
+    chain_data: ChainConfigData,
+) -> ProgramResult {
+    let chain_id = caip2.legacy_id();
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let chain_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let (expected, bump) = get_chain_config_address(chain_id, program_id);
+    if expected != *chain_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let chain = ChainConfig {
+        chain_id,
+        enabled: chain_data.enabled,
+        min_eidas_level: chain_data.min_eidas_level,
+        inbound_enabled: chain_data.inbound_enabled,
+        bump,
+        caip2,
+        encryption_key: chain_data.encryption_key,
+        gateway_codehash: chain_data.gateway_codehash,
+        outbound_volume: 0,
+        inbound_volume: 0,
+        rate_limit: RateLimit::default(),
+        wormhole_emitter: chain_data.wormhole_emitter,
+        transport: chain_data.transport,
+    };
+
+    create_pda_account(
+        admin,
+        chain_account,
+        system_program,
+        program_id,
+        ChainConfig::LEN,
+        0,
+        &[CHAIN_CONFIG_SEED, &chain_id.to_le_bytes(), &[bump]],
+    )?;
+
+    chain.serialize(&mut &mut chain_account.data.borrow_mut()[..])?;
+
+    msg!("Chain {} ({}) registered", caip2, chain_id);
+    Ok(())
+}
+
+/// While a config timelock is set, changes to how a chain is trusted are
+/// refused until the timelock is lowered through `QueueConfigUpdate`.
+fn update_chain(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    chain_data: ChainConfigData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let chain_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if chain_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let mut chain = ChainConfig::try_from_slice(&chain_account.data.borrow())?;
+    // Any program-owned account could decode as a chain config; only the one
+    // at the chain's own PDA is.
+    let (expected_chain, _) = get_chain_config_address(chain.chain_id, program_id);
+    if expected_chain != *chain_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if state.config.config_timelock_slots > 0 && chain.is_sensitive_change(&chain_data) {
+        return Err(CrossChainError::ConfigChangeTimelocked.into());
+    }
+
+    chain.enabled = chain_data.enabled;
+    chain.min_eidas_level = chain_data.min_eidas_level;
+    chain.inbound_enabled = chain_data.inbound_enabled;
+    chain.encryption_key = chain_data.encryption_key;
+    chain.gateway_codehash = chain_data.gateway_codehash;
+    chain.wormhole_emitter = chain_data.wormhole_emitter;
+    chain.transport = chain_data.transport;
+    chain.serialize(&mut &mut chain_account.data.borrow_mut()[..])?;
+
+    msg!("Chain {} updated", chain.chain_id);
+    Ok(())
+}
+
+/// Sets the Axelar name and gateway address of a chain whose transport is
+/// Axelar, creating its route on first use.
+fn set_axelar_route(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    chain_id: u64,
+    chain_name: AxelarChainName,
+    gateway_address: AxelarAddress,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let route_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    if chain_name.is_empty() || gateway_address.is_empty() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let (expected, bump) = get_axelar_route_address(chain_id, program_id);
+    if expected != *route_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if route_account.owner != program_id {
+        create_pda_account(
+            admin,
+            route_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + AxelarRoute::LEN,
+            0,
+            &[AXELAR_ROUTE_SEED, &chain_id.to_le_bytes(), &[bump]],
+        )?;
+    }
+
+    let route = AxelarRoute {
+        chain_id,
+        chain_name,
+        gateway_address,
+        bump,
+    };
+    let mut data = route_account.data.borrow_mut();
+    data.fill(0);
+    route.pack_into(&mut data[..])?;
+
+    msg!(
+        "Chain {} routed to Axelar chain {} at {}",
+        chain_id,
+        route.chain_name,
+        route.gateway_address
+    );
+    Ok(())
+}
+
+/// Creates or replaces the policy for transfers from `source_chain` to
+/// `destination_chain`. Both chains keep their own settings; the policy only
+/// narrows or reprices the corridor between them. Repricing is refused while
+/// a config timelock is set; the multiplier in force is kept in the fee
+/// history alongside the hub-wide schedule.
+fn set_chain_pair_policy(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    source_chain: u64,
+    destination_chain: u64,
+    data: ChainPairPolicyData,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let policy_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let fee_history_account = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let (expected, bump) =
+        get_chain_pair_policy_address(source_chain, destination_chain, program_id);
+    if expected != *policy_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let current =
+        load_chain_pair_policy(program_id, policy_account, source_chain, destination_chain)?;
+
+    if policy_account.owner != program_id {
+        create_pda_account(
+            admin,
+            policy_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + ChainPairPolicy::LEN,
+            0,
+            &[
+                CHAIN_PAIR_POLICY_SEED,
+                &source_chain.to_le_bytes(),
+                &destination_chain.to_le_bytes(),
+                &[bump],
+            ],
+        )?;
+    }
+
+    let policy = ChainPairPolicy {
+        source_chain,
+        destination_chain,
+        enabled: data.enabled,
+        fee_multiplier_bps: data.fee_multiplier_bps,
+        max_amount: data.max_amount,
+        required_confirmations: data.required_confirmations,
+        bump,
+    };
+    policy.validate(state.config.fee_basis_points)?;
+    if state.config.config_timelock_slots > 0 && current.is_sensitive_change(&policy) {
+        return Err(CrossChainError::ConfigChangeTimelocked.into());
+    }
+    policy.pack_into(&mut policy_account.data.borrow_mut()[..])?;
+    record_corridor_fee_schedule(
+        program_id,
+        fee_history_account,
+        admin,
+        system_program,
+        &policy,
+    )?;
+
+    msg!(
+        "Corridor {} -> {} {}",
+        source_chain,
+        destination_chain,
+        if policy.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+    Ok(())
+}
+
+/// Sets a chain's outflow limit and starts a fresh window. The admin may set
+/// any limit, including lifting it; the guardian may only tighten it, so a
+/// suspected exploit can be throttled without waiting on the admin.
+fn set_rate_limit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_outflow: u64,
+    window_slots: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let chain_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if chain_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let state = load_state(program_id, state_account)?;
+    let mut chain = ChainConfig::try_from_slice(&chain_account.data.borrow())?;
+    let (expected_chain, _) = get_chain_config_address(chain.chain_id, program_id);
+    if expected_chain != *chain_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let limit = RateLimit {
+        max_outflow,
+        window_slots,
+        window_start: Clock::get()?.slot,
+        window_outflow: 0,
+    };
+
+    let is_admin = state.config.admin == *authority.key;
+    let is_guardian = state.config.guardian == *authority.key;
+    if !(is_admin || (is_guardian && chain.rate_limit.is_tightened_by(&limit))) {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    // A guardian tightening mid-window keeps what has already gone out.
+    let carried = if is_admin {
+        0
+    } else {
+        chain.rate_limit.window_outflow
+    };
+    chain.rate_limit = RateLimit {
+        window_outflow: carried,
+        ..limit
+    };
+    chain.serialize(&mut &mut chain_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Chain {} outflow limited to {} per {} slots",
+        chain.chain_id,
+        max_outflow,
+        window_slots
+    );
+    Ok(())
+}
+
+fn update_wallet_key(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    public_key: Vec<u8>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let wallet_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !owner.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_wallet, _) = get_wallet_address(owner.key, program_id);
+    if expected_wallet != *wallet_account.key || wallet_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut wallet = Wallet::unpack(&wallet_account.data.borrow())?;
+
+    if wallet.owner != *owner.key {
+        return Err(CrossChainError::WalletOwnerMismatch.into());
+    }
+
+    wallet.public_key = public_key;
+
+    let data = wallet.pack()?;
+    if data.len() != wallet_account.data_len() {
+        resize_account(wallet_account, owner, system_program, data.len())?;
+    }
+    wallet_account.data.borrow_mut()[..data.len()].copy_from_slice(&data);
+
+    msg!("Wallet key updated for {}", owner.key);
+    Ok(())
+}
+
+/// While a config timelock is set, fee and trust changes are refused until
+/// the timelock is lowered through `QueueConfigUpdate`.
+fn update_bridge_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    bridge_config: BridgeConfig,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let fee_history_account = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    if bridge_config.cancellation_fee_bps > MAX_FEE_BASIS_POINTS
+        || bridge_config.required_relayer_signatures == 0
+    {
+        return Err(CrossChainError::InvalidConfig.into());
+    }
+
+    if state.config.config_timelock_slots > 0
+        && state.bridge_config.is_sensitive_change(&bridge_config)
+    {
+        return Err(CrossChainError::ConfigChangeTimelocked.into());
+    }
+
+    // Breaker state is only changed through TripBreaker / ResetBreaker.
+    state.bridge_config = BridgeConfig {
+        emergency_breaker: state.bridge_config.emergency_breaker,
+        breaker_scope: state.bridge_config.breaker_scope,
+        ..bridge_config
+    };
+    write_state(state_account, admin, system_program, &state)?;
+    record_fee_schedule(
+        program_id,
+        fee_history_account,
+        admin,
+        system_program,
+        &state,
+    )?;
+
+    msg!("Bridge config updated");
+    Ok(())
+}
+
+fn trip_breaker(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    scope: BreakerScope,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let pauser = next_account_info(account_info_iter)?;
+
+    if !pauser.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.config.guardian != *pauser.key && state.config.admin != *pauser.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    state.bridge_config.emergency_breaker = true;
+    state.bridge_config.breaker_scope = scope;
+    state.pack_into(&mut state_account.data.borrow_mut()[..])?;
+
+    msg!("Emergency breaker tripped: {:?}", scope);
+    Ok(())
+}
+
+fn reset_breaker(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    state.bridge_config.emergency_breaker = false;
+    state.pack_into(&mut state_account.data.borrow_mut()[..])?;
+
+    msg!("Emergency breaker reset");
+    Ok(())
+}
+
+fn register_wrapped_asset(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    chain_id: u64,
+    remote_token: Pubkey,
+    remote_decimals: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let registry_account = next_account_info(account_info_iter)?;
+    let mint_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let (expected_registry, registry_bump) = get_wrapped_registry_address(program_id);
+    if expected_registry != *registry_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if *mint_account.owner != spl_token::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mint = spl_token::state::Mint::unpack(&mint_account.data.borrow())?;
+    let (mint_authority, _) = get_mint_authority_address(program_id);
+    if mint.mint_authority != COption::Some(mint_authority) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if registry_account.owner != program_id {
+        create_pda_account(
+            admin,
+            registry_account,
+            system_program,
+            program_id,
+            WrappedAssetRegistry::LEN,
+            0,
+            &[WRAPPED_REGISTRY_SEED, &[registry_bump]],
+        )?;
+        WrappedAssetRegistry::default()
+            .serialize(&mut &mut registry_account.data.borrow_mut()[..])?;
+    }
+
+    let mut registry = WrappedAssetRegistry::deserialize(&mut &registry_account.data.borrow()[..])?;
+    registry.register(WrappedAsset {
+        chain_id,
+        remote_token,
+        local_mint: *mint_account.key,
+        remote_decimals,
+        local_decimals: mint.decimals,
+    })?;
+    registry.serialize(&mut &mut registry_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Wrapped asset registered: chain {} token {} -> {}",
+        chain_id,
+        remote_token,
+        mint_account.key
+    );
+    Ok(())
+}
+
+/// Removes a wrapped asset from the registry. Refused while any of the local
+/// mint is in circulation, since holders could no longer redeem it.
+fn deregister_wrapped_asset(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    chain_id: u64,
+    remote_token: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let registry_account = next_account_info(account_info_iter)?;
+    let mint_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let (expected_registry, _) = get_wrapped_registry_address(program_id);
+    if expected_registry != *registry_account.key || registry_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut registry = WrappedAssetRegistry::deserialize(&mut &registry_account.data.borrow()[..])?;
+    let asset = registry
+        .find_by_remote(chain_id, &remote_token)
+        .ok_or(CrossChainError::UnknownWrappedAsset)?;
+    if asset.local_mint != *mint_account.key || *mint_account.owner != spl_token::id() {
+        return Err(CrossChainError::UnknownWrappedAsset.into());
+    }
+
+    let mint = spl_token::state::Mint::unpack(&mint_account.data.borrow())?;
+    if mint.supply > 0 {
+        return Err(CrossChainError::WrappedSupplyOutstanding.into());
+    }
+
+    registry.deregister(chain_id, &remote_token)?;
+    registry.serialize(&mut &mut registry_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Wrapped asset deregistered: chain {} token {}",
+        chain_id,
+        remote_token
+    );
+    Ok(())
+}
+
+fn update_compliance_matrix(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    matrix: ComplianceMatrix,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.config.eidas_authority != *authority.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    matrix.validate()?;
+
+    state.compliance_matrix = matrix;
+    write_state(state_account, authority, system_program, &state)?;
+
+    msg!(
+        "Compliance matrix updated with {} bands",
+        state.compliance_matrix.bands.len()
+    );
+    Ok(())
+}
+
+fn stake_relayer(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let relayer_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    let (expected_relayer, bump) = get_relayer_address(authority.key, program_id);
+    if expected_relayer != *relayer_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut relayer = if relayer_account.owner != program_id {
+        create_pda_account(
+            authority,
+            relayer_account,
+            system_program,
+            program_id,
+            Relayer::LEN,
+            amount,
+            &[RELAYER_SEED, authority.key.as_ref(), &[bump]],
+        )?;
+        Relayer {
+            authority: *authority.key,
+            stake: 0,
+            total_slashed: 0,
+            slash_count: 0,
+            registered_slot: Clock::get()?.slot,
+            bump,
+        }
+    } else {
+        invoke(
+            &system_instruction::transfer(authority.key, relayer_account.key, amount),
+            &[
+                authority.clone(),
+                relayer_account.clone(),
+                system_program.clone(),
+            ],
+        )?;
+        Relayer::try_from_slice(&relayer_account.data.borrow())?
+    };
+
+    relayer.stake = relayer
+        .stake
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if !relayer.is_bonded(state.bridge_config.min_relayer_stake) {
+        return Err(CrossChainError::InsufficientRelayerStake.into());
+    }
+
+    relayer.serialize(&mut &mut relayer_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Relayer {} staked {} lamports, total {}",
+        authority.key,
+        amount,
+        relayer.stake
+    );
+    Ok(())
+}
+
+/// Takes or extends the relayer leader lease. Only bonded relayers may lead.
+fn acquire_relayer_lease(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    duration_slots: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let lease_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let relayer_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    let (expected_relayer, _) = get_relayer_address(authority.key, program_id);
+    if expected_relayer != *relayer_account.key || relayer_account.owner != program_id {
+        return Err(CrossChainError::UnauthorizedRelayer.into());
+    }
+
+    let relayer = Relayer::try_from_slice(&relayer_account.data.borrow())?;
+    if !relayer.is_bonded(state.bridge_config.min_relayer_stake) {
+        return Err(CrossChainError::InsufficientRelayerStake.into());
+    }
+
+    let (expected_lease, bump) = get_relayer_lease_address(program_id);
+    if expected_lease != *lease_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut lease = if lease_account.owner != program_id {
+        create_pda_account(
+            authority,
+            lease_account,
+            system_program,
+            program_id,
+            RelayerLease::LEN,
+            0,
+            &[RELAYER_LEASE_SEED, &[bump]],
+        )?;
+        RelayerLease {
+            bump,
+            ..Default::default()
+        }
+    } else {
+        RelayerLease::try_from_slice(&lease_account.data.borrow())?
+    };
+
+    lease.acquire(*authority.key, Clock::get()?.slot, duration_slots)?;
+    lease.serialize(&mut &mut lease_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Relayer {} leads term {} until slot {}",
+        authority.key,
+        lease.term,
+        lease.expires_slot
+    );
+    Ok(())
+}
+
+/// Lets the leader hand over immediately, e.g. on graceful shutdown.
+fn release_relayer_lease(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let lease_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (expected_lease, _) = get_relayer_lease_address(program_id);
+    if expected_lease != *lease_account.key || lease_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut lease = RelayerLease::try_from_slice(&lease_account.data.borrow())?;
+    lease.release(authority.key, Clock::get()?.slot)?;
+    lease.serialize(&mut &mut lease_account.data.borrow_mut()[..])?;
+
+    msg!("Relayer {} released term {}", authority.key, lease.term);
+    Ok(())
+}
+
+/// Moves a relayer registration, stake and slash history to a new authority
+/// in one transaction. Both keys sign, so a leaked old key alone cannot
+/// redirect the stake. The old PDA is emptied and its rent returned.
+fn rotate_relayer_key(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let old_relayer_account = next_account_info(account_info_iter)?;
+    let old_authority = next_account_info(account_info_iter)?;
+    let new_relayer_account = next_account_info(account_info_iter)?;
+    let new_authority = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !old_authority.is_signer || !new_authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    let (expected_old, _) = get_relayer_address(old_authority.key, program_id);
+    if expected_old != *old_relayer_account.key || old_relayer_account.owner != program_id {
+        return Err(CrossChainError::UnauthorizedRelayer.into());
+    }
+
+    let (expected_new, bump) = get_relayer_address(new_authority.key, program_id);
+    if expected_new != *new_relayer_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if new_relayer_account.owner == program_id {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let old = Relayer::try_from_slice(&old_relayer_account.data.borrow())?;
+    if !old.is_bonded(state.bridge_config.min_relayer_stake) {
+        return Err(CrossChainError::InsufficientRelayerStake.into());
+    }
+
+    create_pda_account(
+        new_authority,
+        new_relayer_account,
+        system_program,
+        program_id,
+        Relayer::LEN,
+        0,
+        &[RELAYER_SEED, new_authority.key.as_ref(), &[bump]],
+    )?;
+
+    let rotated = Relayer {
+        authority: *new_authority.key,
+        bump,
+        ..old.clone()
+    };
+    rotated.serialize(&mut &mut new_relayer_account.data.borrow_mut()[..])?;
+
+    transfer_lamports(old_relayer_account, new_relayer_account, old.stake)?;
+    transfer_lamports(
+        old_relayer_account,
+        old_authority,
+        old_relayer_account.lamports(),
+    )?;
+    old_relayer_account.data.borrow_mut().fill(0);
+
+    msg!(
+        "Relayer {} rotated to {} with stake {}",
+        old_authority.key,
+        new_authority.key,
+        rotated.stake
+    );
+    Ok(())
+}
+
+/// Records a source-chain state root signed by a quorum of bonded relayers.
+/// Completions can then prove inclusion against it instead of carrying relay
+/// confirmations. Roots are write-once.
+fn post_state_root(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    chain_id: u64,
+    block_number: u64,
+    root: [u8; 32],
+    signers: Vec<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let state_root_account = next_account_info(account_info_iter)?;
+    let chain_account = next_account_info(account_info_iter)?;
+    let instructions_sysvar = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    let (expected_chain, _) = get_chain_config_address(chain_id, program_id);
+    if expected_chain != *chain_account.key || chain_account.owner != program_id {
+        return Err(CrossChainError::ChainNotRegistered.into());
+    }
+    validate_source_chain(&ChainConfig::try_from_slice(&chain_account.data.borrow())?)?;
+
+    let mut relayers = Vec::with_capacity(signers.len());
+    for signer in &signers {
+        let relayer_account = next_account_info(account_info_iter)?;
+        let (expected_relayer, _) = get_relayer_address(signer, program_id);
+        if expected_relayer != *relayer_account.key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if relayer_account.owner != program_id {
+            return Err(CrossChainError::UnauthorizedRelayer.into());
+        }
+        relayers.push(Relayer::try_from_slice(&relayer_account.data.borrow())?);
+    }
+
+    verify_state_root_quorum(
+        &state.signing_domain(program_id),
+        chain_id,
+        block_number,
+        &root,
+        &signers,
+        &relayers,
+        &load_ed25519_signatures(instructions_sysvar)?,
+        state.bridge_config.required_relayer_signatures,
+        state.bridge_config.min_relayer_stake,
+    )?;
+
+    let (expected_root, bump) = get_state_root_address(chain_id, block_number, program_id);
+    if expected_root != *state_root_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if state_root_account.owner == program_id {
+        return Err(CrossChainError::StateRootAlreadyPosted.into());
+    }
+
+    create_pda_account(
+        payer,
+        state_root_account,
+        system_program,
+        program_id,
+        ACCOUNT_HEADER_LEN + StateRoot::LEN,
+        0,
+        &[
+            STATE_ROOT_SEED,
+            &chain_id.to_le_bytes(),
+            &block_number.to_le_bytes(),
+            &[bump],
+        ],
+    )?;
+
+    let state_root = StateRoot {
+        chain_id,
+        block_number,
+        root,
+        posted_slot: Clock::get()?.slot,
+        signer_count: signers.len() as u8,
+        bump,
+    };
+    state_root.pack_into(&mut state_root_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "State root for chain {} block {} posted by {} relayers",
+        chain_id,
+        block_number,
+        signers.len()
+    );
+    Ok(())
+}
+
+fn slash_relayer(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let relayer_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if relayer_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let mut relayer = Relayer::try_from_slice(&relayer_account.data.borrow())?;
+    let slashed = slashable_amount(&relayer, amount);
+
+    transfer_lamports(relayer_account, recipient, slashed)?;
+    relayer.stake = relayer
+        .stake
+        .checked_sub(slashed)
+        .ok_or(CrossChainError::ArithmeticOverflow)?;
+    relayer.total_slashed = relayer.total_slashed.saturating_add(slashed);
+    relayer.slash_count = relayer.slash_count.saturating_add(1);
+    relayer.serialize(&mut &mut relayer_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Relayer {} slashed {} lamports, remaining stake {}",
+        relayer.authority,
+        slashed,
+        relayer.stake
+    );
+    Ok(())
+}
+
+fn propose_admin(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_admin: Option<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    state.pending_admin = new_admin;
+    write_state(state_account, admin, system_program, &state)?;
+
+    match new_admin {
+        Some(candidate) => msg!("Admin transfer proposed to {}", candidate),
+        None => msg!("Pending admin transfer cancelled"),
+    }
+    Ok(())
+}
+
+fn accept_admin(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let new_admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !new_admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.pending_admin != Some(*new_admin.key) {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let previous = state.config.admin;
+    state.config.admin = *new_admin.key;
+    state.pending_admin = None;
+    write_state(state_account, new_admin, system_program, &state)?;
+
+    msg!("Admin transferred from {} to {}", previous, new_admin.key);
+    Ok(())
+}
+
+/// Admin or guardian may pause a subsystem; only the admin can resume it,
+/// mirroring the emergency breaker.
+fn set_pause(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    flags: PauseFlags,
+    paused: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let pauser = next_account_info(account_info_iter)?;
+
+    if !pauser.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    let is_admin = state.config.admin == *pauser.key;
+    let is_guardian = state.config.guardian == *pauser.key;
+    if !(is_admin || (paused && is_guardian)) {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    if paused {
+        state.pause_flags.insert(flags);
+    } else {
+        state.pause_flags.remove(flags);
+    }
+    state.pack_into(&mut state_account.data.borrow_mut()[..])?;
+
+    msg!("Pause flags set to {:#07b}", state.pause_flags.0);
+    Ok(())
+}
+
+/// Pays accrued fees out of the treasury. Protocol fees are withdrawn by the
+/// admin, relayer fees claimed by the bridge authority that relays transfers.
+fn withdraw_treasury_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Option<Pubkey>,
+    amount: u64,
+    relayer: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let treasury_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let destination = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    let expected_authority = if relayer {
+        state.config.bridge_authority
+    } else {
+        state.config.admin
+    };
+    if expected_authority != *authority.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    if relayer {
+        state.fee_ledger.debit_relayer(mint, amount)?;
+    } else {
+        state.fee_ledger.debit_protocol(mint, amount)?;
+    }
+
+    pay_from_treasury(
+        program_id,
+        treasury_account,
+        destination,
+        mint,
+        amount,
+        account_info_iter,
+    )?;
+    write_state(state_account, authority, system_program, &state)?;
+
+    msg!(
+        "Withdrew {} {} fees to {}",
+        amount,
+        if relayer { "relayer" } else { "protocol" },
+        destination.key
+    );
+    Ok(())
+}
+
+/// Pays out everything accrued in `mint` that the signer may withdraw: the
+/// protocol share for the admin, the relayer share for the bridge authority,
+/// or both when one key holds both roles. Accounts match
+/// `WithdrawProtocolFees`.
+fn claim_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Option<Pubkey>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let treasury_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let destination = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    let is_admin = state.config.admin == *authority.key;
+    let is_relayer = state.config.bridge_authority == *authority.key;
+    if !(is_admin || is_relayer) {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let accrued = state
+        .fee_ledger
+        .get(mint)
+        .cloned()
+        .ok_or(CrossChainError::InsufficientAccruedFees)?;
+    let protocol = if is_admin { accrued.protocol } else { 0 };
+    let relayer = if is_relayer { accrued.relayer } else { 0 };
+    let amount = protocol
+        .checked_add(relayer)
+        .ok_or(CrossChainError::ArithmeticOverflow)?;
+    if amount == 0 {
+        return Err(CrossChainError::InsufficientAccruedFees.into());
+    }
+
+    state.fee_ledger.debit_protocol(mint, protocol)?;
+    state.fee_ledger.debit_relayer(mint, relayer)?;
+
+    pay_from_treasury(
+        program_id,
+        treasury_account,
+        destination,
+        mint,
+        amount,
+        account_info_iter,
+    )?;
+    write_state(state_account, authority, system_program, &state)?;
+
+    msg!(
+        "Claimed {} in {} fees to {}",
+        amount,
+        mint.map_or_else(|| "native".to_string(), |mint| mint.to_string()),
+        destination.key
+    );
+    Ok(())
+}
+
+/// Moves `amount` of `mint` from the treasury to `destination`. SPL payouts
+/// take the treasury token account and token program from `account_info_iter`.
+fn pay_from_treasury<'a, 'b>(
+    program_id: &Pubkey,
+    treasury_account: &'b AccountInfo<'a>,
+    destination: &'b AccountInfo<'a>,
+    mint: Option<Pubkey>,
+    amount: u64,
+    account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+) -> ProgramResult {
+    let (expected_treasury, treasury_bump) = get_treasury_address(program_id);
+    if expected_treasury != *treasury_account.key || treasury_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    match mint {
+        None => transfer_lamports(treasury_account, destination, amount),
+        Some(mint) => {
+            let treasury_token_account = next_account_info(account_info_iter)?;
+            let token_program = next_account_info(account_info_iter)?;
+
+            validate_escrow_token_account(treasury_token_account, &mint, treasury_account.key)?;
+
+            invoke_signed(
+                &spl_token::instruction::transfer(
+                    token_program.key,
+                    treasury_token_account.key,
+                    destination.key,
+                    treasury_account.key,
+                    &[],
+                    amount,
+                )?,
+                &[
+                    treasury_token_account.clone(),
+                    destination.clone(),
+                    treasury_account.clone(),
+                    token_program.clone(),
+                ],
+                &[&[TREASURY_SEED, &[treasury_bump]]],
+            )
+        }
+    }
+}
+
+/// Publishes the fee ledger to the treasury report PDA. Anyone may refresh
+/// it; the payer funds the account on first use.
+fn publish_treasury_report(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let report_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    let (expected, bump) = get_treasury_report_address(program_id);
+    if expected != *report_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if report_account.owner != program_id {
+        create_pda_account(
+            payer,
+            report_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + TreasuryReport::LEN,
+            0,
+            &[TREASURY_REPORT_SEED, &[bump]],
+        )?;
+    }
+
+    let report = TreasuryReport::from_ledger(&state.fee_ledger, Clock::get()?.slot, bump);
+    report.pack_into(&mut report_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Treasury report published: {} assets across {} chain entries",
+        report.assets.len(),
+        report.chains.len()
+    );
+    Ok(())
+}
+
+#[cfg(not(feature = "test-fixtures"))]
+fn process_fixture(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    _instruction: FixtureInstruction,
+) -> ProgramResult {
+    Err(ProgramError::InvalidInstructionData)
+}
+
+/// Accounts start with the hub state and the admin, followed by those of
+/// the fixture.
+#[cfg(feature = "test-fixtures")]
+fn process_fixture(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: FixtureInstruction,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    match instruction {
+        FixtureInstruction::MintTestTokens { amount } => {
+            let mint_account = next_account_info(account_info_iter)?;
+            let destination = next_account_info(account_info_iter)?;
+            let mint_authority = next_account_info(account_info_iter)?;
+            let token_program = next_account_info(account_info_iter)?;
+
+            let (expected_authority, authority_bump) = get_mint_authority_address(program_id);
+            if expected_authority != *mint_authority.key {
+                return Err(ProgramError::InvalidSeeds);
+            }
+
+            invoke_signed(
+                &spl_token::instruction::mint_to(
+                    token_program.key,
+                    mint_account.key,
+                    destination.key,
+                    mint_authority.key,
+                    &[],
+                    amount,
+                )?,
+                &[
+                    mint_account.clone(),
+                    destination.clone(),
+                    mint_authority.clone(),
+                    token_program.clone(),
+                ],
+                &[&[MINT_AUTHORITY_SEED, &[authority_bump]]],
+            )?;
+
+            msg!("Fixture: minted {} of {}", amount, mint_account.key);
+        }
+        FixtureInstruction::ResetRateLimitWindow => {
+            let chain_account = next_account_info(account_info_iter)?;
+
+            if chain_account.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+
+            let mut chain = ChainConfig::try_from_slice(&chain_account.data.borrow())?;
+            let (expected_chain, _) = get_chain_config_address(chain.chain_id, program_id);
+            if expected_chain != *chain_account.key {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            chain.rate_limit.window_start = Clock::get()?.slot;
+            chain.rate_limit.window_outflow = 0;
+            chain.serialize(&mut &mut chain_account.data.borrow_mut()[..])?;
+
+            msg!("Fixture: reset outflow window of chain {}", chain.chain_id);
+        }
+        FixtureInstruction::ForceCompleteTransfer => {
+            let record_account = next_account_info(account_info_iter)?;
+            let recipient = next_account_info(account_info_iter)?;
+            let treasury_account = next_account_info(account_info_iter)?;
+            let system_program = next_account_info(account_info_iter)?;
+            let activity_account = next_account_info(account_info_iter)?;
+
+            if record_account.owner != program_id {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+
+            let mut record = TransferRecord::try_from_slice(&record_account.data.borrow())?;
+            if record.transfer.status != TransferStatus::Initiated {
+                return Err(CrossChainError::TransferNotInFlight.into());
+            }
+            if record.transfer.token_address.is_some() || record.wrapped {
+                return Err(ProgramError::InvalidArgument);
+            }
+            if *recipient.key != record.transfer.recipient {
+                return Err(ProgramError::InvalidAccountData);
+            }
+
+            ensure_treasury(program_id, treasury_account, admin, system_program)?;
+
+            let release = record.release_amount();
+            let fee = record.transfer.fee;
+            transfer_lamports(record_account, recipient, release)?;
+            transfer_lamports(record_account, treasury_account, fee)?;
+
+            let (protocol_fee, relayer_fee) = split_fee(fee, record.relayer_fee);
+            state.fee_ledger.accrue(None, protocol_fee, relayer_fee)?;
+            state.escrow_ledger.debit(None, record.escrowed_amount);
+            write_state(state_account, admin, system_program, &state)?;
+
+            let clock = Clock::get()?;
+            record.transfer.status = TransferStatus::Completed;
+            record.completed_slot = clock.slot;
+            record.serialize(&mut &mut record_account.data.borrow_mut()[..])?;
+
+            update_activity_log(
+                program_id,
+                activity_account,
+                &record.transfer.sender,
+                WalletActivityLog::close_transfer,
+            )?;
+
+            msg!("Fixture: force-completed {} to {}", release, recipient.key);
+        }
+    }
+
+    Ok(())
+}
+
+/// Escrows one outbound transfer paid out to several foreign recipients. The
+/// transfer recipient is replaced by the commitment to the share list, so the
+/// usual initiation path (one compliance check, one record, one nonce) covers
+/// the whole distribution. Accounts are those of `InitiateCrossChain`.
+fn distribute_cross_chain(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    transfer_data: CrossChainTransferData,
+    shares: Vec<DistributionShare>,
+) -> ProgramResult {
+    validate_distribution(&shares)?;
+
+    let commitment = distribution_commitment(&shares);
+    initiate_cross_chain(
+        program_id,
+        accounts,
+        CrossChainTransferData {
+            recipient: commitment,
+            ..transfer_data
+        },
+    )?;
+
+    msg!("Distribution {} to {} recipients", commitment, shares.len());
+    Ok(())
+}
+
+/// Withdraws a transfer held for claim. Only the recipient can claim, to any
+/// destination they choose; when the bridge requires it, the recipient's hub
+/// wallet must also be compliance-verified.
+fn claim_cross_chain(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let record_account = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+    let destination = next_account_info(account_info_iter)?;
+    let activity_account = next_account_info(account_info_iter)?;
+    let recipient_wallet = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+
+    if !recipient.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if record_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+
+    if state.is_paused(PauseFlags::BRIDGE_IN) {
+        return Err(CrossChainError::ProgramPaused.into());
+    }
+
+    let mut record = TransferRecord::try_from_slice(&record_account.data.borrow())?;
+
+    let (expected_record, _) =
+        get_transfer_record_address(&record.transfer.sender, record.transfer.nonce, program_id);
+    if expected_record != *record_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if record.transfer.status != TransferStatus::Confirmed {
+        return Err(CrossChainError::TransferNotInFlight.into());
+    }
+
+    if record.transfer.recipient != *recipient.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    if state.bridge_config.claim_requires_compliance {
+        let (expected_wallet, _) = get_wallet_address(recipient.key, program_id);
+        if expected_wallet != *recipient_wallet.key || recipient_wallet.owner != program_id {
+            return Err(CrossChainError::ComplianceRequired.into());
+        }
+        let wallet = Wallet::unpack(&recipient_wallet.data.borrow())?;
+        if !wallet.is_compliance_verified {
+            return Err(CrossChainError::ComplianceRequired.into());
+        }
+    }
+
+    let release = record.release_amount();
+
+    match record.transfer.token_address {
+        None => transfer_lamports(record_account, destination, release)?,
+        Some(mint) => {
+            let escrow_token_account = next_account_info(account_info_iter)?;
+            let escrow_authority = next_account_info(account_info_iter)?;
+            let token_program = next_account_info(account_info_iter)?;
+
+            release_escrow_tokens(
+                program_id,
+                &mint,
+                escrow_token_account,
+                escrow_authority,
+                token_program,
+                destination,
+                release,
+            )?;
+        }
+    }
+
+    // The asset already has a ledger entry from initiation, so the state
+    // length is unchanged and no resize is needed.
+    state
+        .escrow_ledger
+        .debit(record.transfer.token_address, release);
+    state.pack_into(&mut state_account.data.borrow_mut()[..])?;
+
+    let clock = Clock::get()?;
+    record.transfer.status = TransferStatus::Completed;
+    record.completed_slot = clock.slot;
+    record.serialize(&mut &mut record_account.data.borrow_mut()[..])?;
+
+    append_activity(
+        program_id,
+        activity_account,
+        &record.transfer.sender,
+        transfer_activity(&record, TransactionStatus::Confirmed),
+    )?;
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            AuditAction::TransferClaimed,
+            *recipient.key,
+            *record_account.key,
+            release,
+            &clock,
+        ),
+    )?;
+
+    msg!(
+        "Claimed {} by {} to {}",
+        release,
+        recipient.key,
+        destination.key
+    );
+    Ok(())
+}
+
+/// Returns a transfer that was never completed to its sender once the record's
+/// timeout has elapsed. Fees are kept by the treasury; wrapped assets are
+/// re-minted since they were burned on initiation. Once the sender's wallet
+/// was recovered, the new owner signs in the sender's place and is refunded.
+fn refund_cross_chain(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    refund_transfer(program_id, accounts, RefundReason::TimedOut)
+}
+
+/// Refuses a held transfer and refunds its sender as `RefundCrossChain`
+/// would, without waiting for the review timeout. Accounts are the eIDAS
+/// authority followed by those of `RefundCrossChain`, whose sender need not
+/// sign.
+fn reject_hold(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let officer = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    refund_transfer(program_id, &accounts[1..], RefundReason::Rejected(officer))
+}
+
+/// Lets the sender withdraw a transfer relayers have not yet confirmed,
+/// refunding the escrow minus the cancellation fee instead of the transfer
+/// fee. Only relayer-delivered chains qualify: other transports posted their
+/// message on initiation and would deliver it regardless. Accounts are the
+/// destination chain config followed by those of `RefundCrossChain`.
+fn cancel_cross_chain(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let chain_account = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    refund_transfer(
+        program_id,
+        &accounts[1..],
+        RefundReason::Cancelled(chain_account),
+    )
+}
+
+/// Why `refund_transfer` returns an escrow to its sender.
+enum RefundReason<'r, 'a> {
+    /// The record sat in flight or in review past its timeout.
+    TimedOut,
+    /// The eIDAS authority refused it in review.
+    Rejected(&'r AccountInfo<'a>),
+    /// The sender withdrew it; carries the destination chain config.
+    Cancelled(&'r AccountInfo<'a>),
+}
+
+fn refund_transfer<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    reason: RefundReason<'_, 'a>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let record_account = next_account_info(account_info_iter)?;
+    let sender = next_account_info(account_info_iter)?;
+    let activity_account = next_account_info(account_info_iter)?;
+    let treasury_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+
+    // The signer pays for any treasury creation or state growth.
+    let payer = match reason {
+        RefundReason::Rejected(officer) => officer,
+        _ => sender,
+    };
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if record_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut state = load_state(program_id, state_account)?;
+    let mut record = TransferRecord::try_from_slice(&record_account.data.borrow())?;
+
+    let (expected_record, _) =
+        get_transfer_record_address(&record.transfer.sender, record.transfer.nonce, program_id);
+    if expected_record != *record_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    check_refund_beneficiary(
+        program_id,
+        activity_account,
+        &record.transfer.sender,
+        sender.key,
+    )?;
+
+    let clock = Clock::get()?;
+    match reason {
+        RefundReason::TimedOut => {
+            record.validate_refund(clock.slot)?;
+        }
+        RefundReason::Rejected(officer) => {
+            if state.config.eidas_authority != *officer.key {
+                return Err(CrossChainError::Unauthorized.into());
+            }
+            record.validate_held()?;
+        }
+        RefundReason::Cancelled(chain_account) => {
+            record.validate_cancel()?;
+
+            let (expected_chain, _) =
+                get_chain_config_address(record.transfer.destination_chain, program_id);
+            if expected_chain != *chain_account.key || chain_account.owner != program_id {
+                return Err(CrossChainError::ChainNotRegistered.into());
+            }
+            let chain = ChainConfig::try_from_slice(&chain_account.data.borrow())?;
+            if chain.transport != TransportKind::RelayerQuorum {
+                return Err(CrossChainError::CancellationNotSupported.into());
+            }
+
+            record.charge_cancellation(state.bridge_config.cancellation_fee_bps);
+        }
+    }
+
+    ensure_treasury(program_id, treasury_account, payer, system_program)?;
+
+    let refund = record.refund_amount();
+    let fee = record.transfer.fee;
+
+    match record.transfer.token_address {
+        None => {
+            transfer_lamports(record_account, sender, refund)?;
+            transfer_lamports(record_account, treasury_account, fee)?;
+        }
+        Some(mint) if record.wrapped => {
+            let sender_token_account = next_account_info(account_info_iter)?;
+            let mint_account = next_account_info(account_info_iter)?;
+            let mint_authority = next_account_info(account_info_iter)?;
+            let token_program = next_account_info(account_info_iter)?;
+
+            let (expected_authority, authority_bump) = get_mint_authority_address(program_id);
+            if expected_authority != *mint_authority.key || *mint_account.key != mint {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            // The officer rejecting a hold supplies these accounts, so the
+            // refund must be pinned to the sender's own token account.
+            validate_escrow_token_account(sender_token_account, &mint, sender.key)?;
+
+            invoke_signed(
+                &spl_token::instruction::mint_to(
+                    token_program.key,
+                    mint_account.key,
+                    sender_token_account.key,
+                    mint_authority.key,
+                    &[],
+                    refund,
+                )?,
+                &[
+                    mint_account.clone(),
+                    sender_token_account.clone(),
+                    mint_authority.clone(),
+                    token_program.clone(),
+                ],
+                &[&[MINT_AUTHORITY_SEED, &[authority_bump]]],
+            )?;
+        }
+        Some(mint) => {
+            let sender_token_account = next_account_info(account_info_iter)?;
+            let escrow_token_account = next_account_info(account_info_iter)?;
+            let escrow_authority = next_account_info(account_info_iter)?;
+            let token_program = next_account_info(account_info_iter)?;
+            let treasury_token_account = next_account_info(account_info_iter)?;
+
+            validate_escrow_token_account(sender_token_account, &mint, sender.key)?;
+            release_escrow_tokens(
+                program_id,
+                &mint,
+                escrow_token_account,
+                escrow_authority,
+                token_program,
+                sender_token_account,
+                refund,
+            )?;
+
+            validate_escrow_token_account(treasury_token_account, &mint, treasury_account.key)?;
+            release_escrow_tokens(
+                program_id,
+                &mint,
+                escrow_token_account,
+                escrow_authority,
+                token_program,
+                treasury_token_account,
+                fee,
+            )?;
+        }
+    }
+
+    if !record.wrapped {
+        let (protocol_fee, relayer_fee) = split_fee(fee, record.relayer_fee);
+        state
+            .fee_ledger
+            .accrue(record.transfer.token_address, protocol_fee, relayer_fee)?;
+        state.fee_ledger.record_chain(
+            record.transfer.destination_chain,
+            record.transfer.token_address,
+            fee,
+        )?;
+        state
+            .escrow_ledger
+            .debit(record.transfer.token_address, record.escrowed_amount);
+        write_state(state_account, payer, system_program, &state)?;
+    }
+
+    record.transfer.status = match reason {
+        RefundReason::TimedOut => TransferStatus::Failed,
+        RefundReason::Rejected(_) | RefundReason::Cancelled(_) => TransferStatus::Cancelled,
+    };
+    record.completed_slot = clock.slot;
+    record.serialize(&mut &mut record_account.data.borrow_mut()[..])?;
+
+    update_activity_log(
+        program_id,
+        activity_account,
+        &record.transfer.sender,
+        WalletActivityLog::close_transfer,
+    )?;
+
+    append_activity(
+        program_id,
+        activity_account,
+        &record.transfer.sender,
+        transfer_activity(
+            &record,
+            match reason {
+                RefundReason::Cancelled(_) => TransactionStatus::Cancelled,
+                _ => TransactionStatus::Failed,
+            },
+        ),
+    )?;
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            match reason {
+                RefundReason::TimedOut => AuditAction::TransferRefunded,
+                RefundReason::Rejected(_) => AuditAction::HoldRejected,
+                RefundReason::Cancelled(_) => AuditAction::TransferCancelled,
+            },
+            *payer.key,
+            *record_account.key,
+            refund,
+            &clock,
+        ),
+    )?;
+
+    match reason {
+        RefundReason::TimedOut => {}
+        RefundReason::Rejected(_) => HubEvent::HoldRejected {
+            record: *record_account.key,
+            officer: *payer.key,
+            refund,
+        }
+        .emit(),
+        RefundReason::Cancelled(_) => HubEvent::TransferCancelled {
+            record: *record_account.key,
+            sender: *sender.key,
+            refund,
+            fee,
+        }
+        .emit(),
+    }
+    msg!("Refunded {} to {}", refund, sender.key);
+    Ok(())
+}
+
+/// Puts a held transfer in flight after review and posts it through the
+/// destination chain's transport, whose accounts follow the system program.
+/// The officer pays any message fee.
+fn release_hold(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let record_account = next_account_info(account_info_iter)?;
+    let officer = next_account_info(account_info_iter)?;
+    let audit_account = next_account_info(account_info_iter)?;
+    let chain_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !officer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.eidas_authority != *officer.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    if record_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut record = TransferRecord::try_from_slice(&record_account.data.borrow())?;
+    let (expected_record, _) =
+        get_transfer_record_address(&record.transfer.sender, record.transfer.nonce, program_id);
+    if expected_record != *record_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let clock = Clock::get()?;
+    record.release_hold(clock.slot, state.bridge_config.transfer_timeout_slots)?;
+    record.serialize(&mut &mut record_account.data.borrow_mut()[..])?;
+
+    let (expected_chain, _) =
+        get_chain_config_address(record.transfer.destination_chain, program_id);
+    if expected_chain != *chain_account.key || chain_account.owner != program_id {
+        return Err(CrossChainError::ChainNotRegistered.into());
+    }
+    let chain = ChainConfig::try_from_slice(&chain_account.data.borrow())?;
+
+    transport_for(chain.transport).post_message(
+        &TransportContext {
+            program_id,
+            state: &state,
+        },
+        &OutboundTransfer {
+            record_key: record_account.key,
+            record: &record,
+        },
+        officer,
+        system_program,
+        account_info_iter,
+    )?;
+
+    append_audit(
+        program_id,
+        audit_account,
+        AuditEntry::new(
+            AuditAction::HoldReleased,
+            *officer.key,
+            *record_account.key,
+            record.transfer.amount,
+            &clock,
+        ),
+    )?;
+
+    emit_transfer_initiated(record_account.key, &record);
+    msg!(
+        "Released held transfer of {} to chain {}",
+        record.transfer.amount,
+        record.transfer.destination_chain
+    );
+    Ok(())
+}
+
+/// Outbound transfer whose amount is hidden in an ElGamal ciphertext. The
+/// public `transfer_data.amount` is an upper bound the range proof must
+/// respect; it is what gets escrowed, checked against limits and charged
+/// fees. Emits a `ConfidentialTransfer` bridge message with the ciphertext,
+/// which relayers deliver instead of the record's plain `TokenTransfer`.
+/// Accounts are the instructions sysvar followed by those of
+/// `InitiateCrossChain`.
+fn initiate_confidential_cross_chain(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    transfer_data: CrossChainTransferData,
+    proof: ConfidentialTransferProof,
+) -> ProgramResult {
+    let instructions_sysvar = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    verify_confidential_amount_bound(
+        &proof,
+        &load_verified_range_proofs(instructions_sysvar)?,
+        transfer_data.amount,
+    )?;
+    let payload = ConfidentialTransferPayload::from_proof(&proof, transfer_data.amount)?;
+
+    initiate_cross_chain(program_id, &accounts[1..], transfer_data)?;
+
+    // The transfer record is the sixth InitiateCrossChain account.
+    let record_account = accounts.get(6).ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let record = TransferRecord::try_from_slice(&record_account.data.borrow())?;
+    // The bridge message below is emitted here and cannot follow a later release.
+    if record.transfer.status == TransferStatus::Held {
+        return Err(CrossChainError::ReviewNotSupported.into());
+    }
+
+    let message = CrossChainMessage {
+        id: record_account.key.to_string().into(),
+        source_chain: record.transfer.source_chain,
+        destination_chain: record.transfer.destination_chain,
+        sender: record.transfer.sender,
+        recipient: record.transfer.recipient,
+        message_type: MessageType::ConfidentialTransfer,
+        payload: payload.try_to_vec()?.into(),
+        nonce: record.transfer.nonce,
+        timestamp: record.transfer.timestamp,
+        gateway_codehash: record.gateway_codehash,
+        compression: PayloadCompression::None,
+    };
+    validate_bridge_message(&message).map_err(CrossChainError::from)?;
+    sol_log_data(&[&encode_bridge_message(&message)]);
+
+    msg!(
+        "Initiated confidential transfer to chain {} bounded by {}",
+        record.transfer.destination_chain,
+        payload.amount_bound
+    );
+    Ok(())
+}
+
+/// Burns hub-issued wrapped tokens on their way back to the origin chain and
+/// emits a `TokenRedeem` bridge message naming the original asset to release.
+/// Accounts are the wrapped registry followed by those of `InitiateCrossChain`.
+fn redeem_wrapped(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    transfer_data: CrossChainTransferData,
+) -> ProgramResult {
+    let registry_account = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let (expected_registry, _) = get_wrapped_registry_address(program_id);
+    if expected_registry != *registry_account.key || registry_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let registry = WrappedAssetRegistry::deserialize(&mut &registry_account.data.borrow()[..])?;
+    let local_mint = transfer_data
+        .token_address
+        .ok_or(CrossChainError::UnknownWrappedAsset)?;
+    let asset = registry
+        .find_by_mint(&local_mint)
+        .ok_or(CrossChainError::UnknownWrappedAsset)?
+        .clone();
+
+    if asset.chain_id != transfer_data.destination_chain {
+        return Err(CrossChainError::UnknownWrappedAsset.into());
+    }
+
+    initiate_cross_chain(program_id, &accounts[1..], transfer_data.clone())?;
+
+    // The transfer record is the sixth InitiateCrossChain account.
+    let record_account = accounts.get(6).ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let record = TransferRecord::try_from_slice(&record_account.data.borrow())?;
+    // The bridge message below is emitted here and cannot follow a later release.
+    if record.transfer.status == TransferStatus::Held {
+        return Err(CrossChainError::ReviewNotSupported.into());
+    }
+    if !record.wrapped {
+        return Err(CrossChainError::UnknownWrappedAsset.into());
+    }
+
+    let payload = RedemptionPayload {
+        remote_token: asset.remote_token,
+        local_mint,
+        amount: asset.to_remote_amount(record.refund_amount())?,
+    };
+    let message = CrossChainMessage {
+        id: record_account.key.to_string().into(),
+        source_chain: record.transfer.source_chain,
+        destination_chain: asset.chain_id,
+        sender: record.transfer.sender,
+        recipient: record.transfer.recipient,
+        message_type: MessageType::TokenRedeem,
+        payload: payload.try_to_vec()?.into(),
+        nonce: record.transfer.nonce,
+        timestamp: record.transfer.timestamp,
+        gateway_codehash: record.gateway_codehash,
+        compression: PayloadCompression::None,
+    };
+    validate_bridge_message(&message).map_err(CrossChainError::from)?;
+    sol_log_data(&[&encode_bridge_message(&message)]);
+
+    msg!(
+        "Redeemed {} wrapped {} for {} on chain {}",
+        payload.amount,
+        local_mint,
+        asset.remote_token,
+        asset.chain_id
+    );
+    Ok(())
+}
+
+/// Permissionless check that the escrow token accounts for `mint` hold what
+/// in-flight transfers are owed. The outcome is written to the mint's
+/// discrepancy PDA each run, so a later call with the full set of escrow
+/// accounts clears a flag raised by an incomplete one. Native transfers are
+/// escrowed in their own records and are not reconciled here.
+fn reconcile(program_id: &Pubkey, accounts: &[AccountInfo], mint: Pubkey) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let discrepancy_account = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    let (expected_discrepancy, bump) = get_discrepancy_address(&mint, program_id);
+    if expected_discrepancy != *discrepancy_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let (escrow_authority, _) = get_escrow_authority_address(program_id);
+    let mut seen: Vec<Pubkey> = Vec::new();
+    let mut actual: u64 = 0;
+    for escrow_token_account in account_info_iter {
+        if seen.contains(escrow_token_account.key) {
+            continue;
+        }
+        validate_escrow_token_account(escrow_token_account, &mint, &escrow_authority)?;
+        let token_account = spl_token::state::Account::unpack(&escrow_token_account.data.borrow())?;
+        actual = actual.saturating_add(token_account.amount);
+        seen.push(*escrow_token_account.key);
+    }
+
+    if discrepancy_account.owner != program_id {
+        create_pda_account(
+            payer,
+            discrepancy_account,
+            system_program,
+            program_id,
+            EscrowDiscrepancy::LEN,
+            0,
+            &[DISCREPANCY_SEED, mint.as_ref(), &[bump]],
+        )?;
+    }
+
+    let expected = state.escrow_ledger.outstanding(Some(mint));
+    let drift = escrow_drift(expected, actual);
+    let discrepancy = EscrowDiscrepancy {
+        mint,
+        expected,
+        actual,
+        drift,
+        flagged: drift != 0,
+        last_reconciled_slot: Clock::get()?.slot,
+        bump,
+    };
+    discrepancy.serialize(&mut &mut discrepancy_account.data.borrow_mut()[..])?;
+
+    if discrepancy.flagged {
+        msg!(
+            "Escrow drift for {}: expected {}, held {} ({:+})",
+            mint,
+            expected,
+            actual,
+            drift
+        );
+    } else {
+        msg!("Escrow for {} reconciled at {}", mint, actual);
+    }
+    Ok(())
+}
+
+fn create_multisig(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    seed: u64,
+    config: MultisigConfig,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let multisig_account = next_account_info(account_info_iter)?;
+    let creator = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !creator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    validate_multisig_config(&config)?;
+
+    let (expected_multisig, bump) = get_multisig_address(creator.key, seed, program_id);
+    if expected_multisig != *multisig_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let (_, vault_bump) = get_multisig_vault_address(multisig_account.key, program_id);
+
+    create_pda_account(
+        creator,
+        multisig_account,
+        system_program,
+        program_id,
+        MultiSigWallet::LEN,
+        0,
+        &[
+            MULTISIG_SEED,
+            creator.key.as_ref(),
+            &seed.to_le_bytes(),
+            &[bump],
+        ],
+    )?;
+
+    let multisig = MultiSigWallet {
+        owners: config.owners.into_inner(),
+        threshold: config.threshold,
+        created_at: Clock::get()?.unix_timestamp,
+        eidas_required: config.eidas_required,
+        transaction_count: 0,
+        bump,
+        vault_bump,
+    };
+    multisig.serialize(&mut &mut multisig_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Multisig {} created: {} of {} owners",
+        multisig_account.key,
+        multisig.threshold,
+        multisig.owners.len()
+    );
+    Ok(())
+}
+
+fn propose_transaction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: ProposedInstruction,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let multisig_account = next_account_info(account_info_iter)?;
+    let transaction_account = next_account_info(account_info_iter)?;
+    let proposer = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
 
-    let rent = Rent::get()?;
-    let space = wallet.try_to_vec()?.len();
+    if !proposer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
 
-    invoke(
-        &system_instruction::create_account(
-            owner.key,
-            wallet_account.key,
-            rent.minimum_balance(space),
-            space as u64,
-            program_id,
-        ),
+    if multisig_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let mut multisig = MultiSigWallet::deserialize(&mut &multisig_account.data.borrow()[..])?;
+
+    if multisig.owner_index(proposer.key).is_none() {
+        return Err(CrossChainError::NotMultisigOwner.into());
+    }
+
+    let (vault, _) = get_multisig_vault_address(multisig_account.key, program_id);
+    validate_proposal(&instruction, &vault)?;
+
+    let index = multisig.transaction_count;
+    let (expected_transaction, bump) =
+        get_multisig_transaction_address(multisig_account.key, index, program_id);
+    if expected_transaction != *transaction_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let transaction = MultisigTransaction {
+        multisig: *multisig_account.key,
+        index,
+        proposer: *proposer.key,
+        instruction,
+        approvals: vec![false; multisig.owners.len()],
+        executed: false,
+        bump,
+    };
+    let data = transaction.try_to_vec()?;
+
+    create_pda_account(
+        proposer,
+        transaction_account,
+        system_program,
+        program_id,
+        data.len(),
+        0,
         &[
-            owner.clone(),
-            wallet_account.clone(),
-            system_program.clone(),
+            MULTISIG_TRANSACTION_SEED,
+            multisig_account.key.as_ref(),
+            &index.to_le_bytes(),
+            &[bump],
         ],
     )?;
+    transaction_account.data.borrow_mut().copy_from_slice(&data);
 
-    wallet.serialize(&mut &mut wallet_account.data.borrow_mut()[..])?;
-
-    state.registered_wallets += 1;
-    state.serialize(&mut &mut state_account.data.borrow_mut()[..])?;
+    multisig.transaction_count = multisig
+        .transaction_count
+        .checked_add(1)
+        .ok_or(CrossChainError::ArithmeticOverflow)?;
+    multisig.serialize(&mut &mut multisig_account.data.borrow_mut()[..])?;
 
-    msg!("Wallet registered successfully");
+    msg!(
+        "Multisig {} transaction {} proposed",
+        multisig_account.key,
+        index
+    );
     Ok(())
 }
 
-fn sign_transaction(
-    _program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    signature_data: TransactionSignatureData,
+/// Records one owner's approval. When the multisig requires eIDAS, the owner's
+/// wallet must be registered and compliance-verified at an eIDAS level.
+/// Rejects a frozen hub wallet. `wallet_account` must be `owner`'s wallet
+/// PDA; a key that never registered a hub wallet has nothing to freeze.
+fn check_wallet_not_frozen(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    wallet_account: &AccountInfo,
 ) -> ProgramResult {
+    let (expected_wallet, _) = get_wallet_address(owner, program_id);
+    if expected_wallet != *wallet_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if wallet_account.owner == program_id && Wallet::unpack(&wallet_account.data.borrow())?.frozen {
+        return Err(CrossChainError::WalletFrozen.into());
+    }
+    Ok(())
+}
+
+fn approve_transaction(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let wallet_account = next_account_info(account_info_iter)?;
-    let signer = next_account_info(account_info_iter)?;
+    let multisig_account = next_account_info(account_info_iter)?;
+    let transaction_account = next_account_info(account_info_iter)?;
+    let owner = next_account_info(account_info_iter)?;
+    let owner_wallet = next_account_info(account_info_iter)?;
 
-    if !signer.is_signer {
+    if !owner.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    let wallet = Wallet::try_from_slice(&wallet_account.data.borrow())?;
-
-    if wallet.owner != *signer.key {
+    if multisig_account.owner != program_id || transaction_account.owner != program_id {
         return Err(ProgramError::IncorrectProgramId);
     }
 
-    if wallet.is_compliance_verified && wallet.eidas_level == EidasLevel::None {
-        return Err(ProgramError::Custom(1001)); // Compliance required
+    let multisig = MultiSigWallet::deserialize(&mut &multisig_account.data.borrow()[..])?;
+    let mut transaction = MultisigTransaction::try_from_slice(&transaction_account.data.borrow())?;
+
+    if transaction.multisig != *multisig_account.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if transaction.executed {
+        return Err(CrossChainError::TransactionAlreadyExecuted.into());
+    }
+
+    let index = multisig
+        .owner_index(owner.key)
+        .ok_or(CrossChainError::NotMultisigOwner)?;
+
+    if multisig.eidas_required {
+        let (expected_wallet, _) = get_wallet_address(owner.key, program_id);
+        if expected_wallet != *owner_wallet.key || owner_wallet.owner != program_id {
+            return Err(CrossChainError::ComplianceRequired.into());
+        }
+        let wallet = Wallet::unpack(&owner_wallet.data.borrow())?;
+        if !wallet.is_compliance_verified || wallet.eidas_level == EidasLevel::None {
+            return Err(CrossChainError::ComplianceRequired.into());
+        }
+    }
+    check_wallet_not_frozen(program_id, owner.key, owner_wallet)?;
+
+    if transaction.approvals[index] {
+        return Err(CrossChainError::AlreadyApproved.into());
     }
+    transaction.approvals[index] = true;
+    transaction.serialize(&mut &mut transaction_account.data.borrow_mut()[..])?;
 
     msg!(
-        "Transaction signed successfully for: {:?}",
-        signature_data.transaction_hash
+        "Multisig transaction {} approved by {} ({} of {})",
+        transaction.index,
+        owner.key,
+        transaction.approval_count(),
+        multisig.threshold
     );
     Ok(())
 }
 
-fn verify_compliance(
-    _program_id: &Pubkey,
-    accounts: &[AccountInfo],
-    compliance_data: ComplianceData,
-) -> ProgramResult {
+/// Invokes an approved proposal with the multisig vault PDA as signer. The
+/// remaining accounts must cover every account the proposed instruction uses.
+fn execute_transaction(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let state_account = next_account_info(account_info_iter)?;
-    let wallet_account = next_account_info(account_info_iter)?;
-    let verifier = next_account_info(account_info_iter)?;
+    let multisig_account = next_account_info(account_info_iter)?;
+    let transaction_account = next_account_info(account_info_iter)?;
+    let executor = next_account_info(account_info_iter)?;
+    let vault = next_account_info(account_info_iter)?;
+    let vault_wallet = next_account_info(account_info_iter)?;
+    let executor_wallet = next_account_info(account_info_iter)?;
 
-    if !verifier.is_signer {
+    if !executor.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    let state = CrossChainState::try_from_slice(&state_account.data.borrow())?;
+    if multisig_account.owner != program_id || transaction_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let multisig = MultiSigWallet::deserialize(&mut &multisig_account.data.borrow()[..])?;
+    let mut transaction = MultisigTransaction::try_from_slice(&transaction_account.data.borrow())?;
 
-    if state.config.eidas_authority != *verifier.key {
-        return Err(ProgramError::Custom(1));
+    if transaction.multisig != *multisig_account.key {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    if multisig.owner_index(executor.key).is_none() {
+        return Err(CrossChainError::NotMultisigOwner.into());
     }
 
-    let mut wallet = Wallet::try_from_slice(&wallet_account.data.borrow())?;
+    validate_execution(&multisig, &transaction)?;
 
-    wallet.is_compliance_verified = compliance_data.verified;
-    wallet.eidas_level = compliance_data.eidas_level;
+    let vault_seeds: &[&[u8]] = &[
+        MULTISIG_VAULT_SEED,
+        multisig_account.key.as_ref(),
+        &[multisig.vault_bump],
+    ];
+    if Pubkey::create_program_address(vault_seeds, program_id)? != *vault.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    check_wallet_not_frozen(program_id, vault.key, vault_wallet)?;
+    check_wallet_not_frozen(program_id, executor.key, executor_wallet)?;
 
-    wallet.serialize(&mut &mut wallet_account.data.borrow_mut()[..])?;
+    // Mark executed before the CPI so a re-entrant call cannot replay it.
+    transaction.executed = true;
+    transaction.serialize(&mut &mut transaction_account.data.borrow_mut()[..])?;
 
-    msg!("Compliance verified: {:?}", compliance_data.verified);
+    let mut cpi_accounts = vec![vault.clone()];
+    cpi_accounts.extend(account_info_iter.cloned());
+    invoke_signed(
+        &transaction.instruction.to_instruction(),
+        &cpi_accounts,
+        &[vault_seeds],
+    )?;
+
+    msg!(
+        "Multisig {} executed transaction {}",
+        multisig_account.key,
+        transaction.index
+    );
     Ok(())
 }
 
-fn initiate_cross_chain(
-    _program_id: &Pubkey,
+/// Creates the governance account, or replaces its config. Signed by the hub
+/// admin, which is the governance authority itself once control is handed over.
+fn configure_governance(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    transfer_data: CrossChainTransferData,
+    config: GovernanceConfig,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let state_account = next_account_info(account_info_iter)?;
-    let sender_wallet = next_account_info(account_info_iter)?;
-    let sender = next_account_info(account_info_iter)?;
-    let _system_program = next_account_info(account_info_iter)?;
+    let governance_account = next_account_info(account_info_iter)?;
+    let admin = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
 
-    if !sender.is_signer {
+    if !admin.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    let state = CrossChainState::try_from_slice(&state_account.data.borrow())?;
+    let state = load_state(program_id, state_account)?;
 
-    if state.config.paused {
-        return Err(ProgramError::Custom(1002)); // Program paused
+    if state.config.admin != *admin.key {
+        return Err(CrossChainError::Unauthorized.into());
     }
 
-    let wallet = Wallet::try_from_slice(&sender_wallet.data.borrow())?;
+    config.validate()?;
 
-    if wallet.owner != *sender.key {
+    let (expected, bump) = get_governance_address(program_id);
+    if expected != *governance_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let governance = if governance_account.owner != program_id {
+        create_pda_account(
+            admin,
+            governance_account,
+            system_program,
+            program_id,
+            ACCOUNT_HEADER_LEN + Governance::LEN,
+            0,
+            &[GOVERNANCE_SEED, &[bump]],
+        )?;
+        Governance {
+            config,
+            proposal_count: 0,
+            bump,
+            authority_bump: get_governance_authority_address(program_id).1,
+        }
+    } else {
+        Governance {
+            config,
+            ..Governance::unpack(&governance_account.data.borrow())?
+        }
+    };
+    governance.pack_into(&mut governance_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Governance configured: quorum {}, voting {} slots, timelock {} slots",
+        governance.config.quorum_votes,
+        governance.config.voting_slots,
+        governance.config.timelock_slots
+    );
+    Ok(())
+}
+
+fn load_governance(
+    program_id: &Pubkey,
+    governance_account: &AccountInfo,
+) -> Result<Governance, ProgramError> {
+    let (expected, _) = get_governance_address(program_id);
+    if expected != *governance_account.key || governance_account.owner != program_id {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Governance::unpack(&governance_account.data.borrow())
+}
+
+/// Token balance of `owner`'s account in the governance mint.
+fn governance_token_balance(
+    token_account: &AccountInfo,
+    config: &GovernanceConfig,
+    owner: &Pubkey,
+) -> Result<u64, ProgramError> {
+    if *token_account.owner != spl_token::id() {
         return Err(ProgramError::IncorrectProgramId);
     }
 
-    if transfer_data.amount < state.config.min_cross_chain_amount {
-        return Err(ProgramError::Custom(1003)); // Amount too low
+    let account = spl_token::state::Account::unpack(&token_account.data.borrow())?;
+    if account.mint != config.token_mint || account.owner != *owner {
+        return Err(ProgramError::InvalidAccountData);
     }
+    Ok(account.amount)
+}
 
-    if transfer_data.amount > state.config.max_cross_chain_amount {
-        return Err(ProgramError::Custom(1004)); // Amount too high
+/// Opens a proposal to execute `instruction` as the governance authority.
+/// The proposer's token account must hold at least `min_proposal_weight`.
+fn create_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction: ProposedInstruction,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let governance_account = next_account_info(account_info_iter)?;
+    let proposal_account = next_account_info(account_info_iter)?;
+    let proposer = next_account_info(account_info_iter)?;
+    let proposer_token_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !proposer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut governance = load_governance(program_id, governance_account)?;
+
+    let balance =
+        governance_token_balance(proposer_token_account, &governance.config, proposer.key)?;
+    if balance < governance.config.min_proposal_weight {
+        return Err(CrossChainError::InsufficientVotingWeight.into());
+    }
+
+    let (authority, _) = get_governance_authority_address(program_id);
+    validate_proposal(&instruction, &authority)?;
+
+    let index = governance.proposal_count;
+    let (expected_proposal, bump) = get_proposal_address(index, program_id);
+    if expected_proposal != *proposal_account.key {
+        return Err(ProgramError::InvalidSeeds);
     }
 
-    let fee = (transfer_data.amount as u128 * state.config.fee_basis_points as u128 / 10000) as u64;
+    let proposal = Proposal {
+        index,
+        proposer: *proposer.key,
+        instruction,
+        votes_for: 0,
+        votes_against: 0,
+        voting_ends_slot: Clock::get()?
+            .slot
+            .saturating_add(governance.config.voting_slots),
+        executed: false,
+        bump,
+    };
+    let data = proposal.pack()?;
+
+    create_pda_account(
+        proposer,
+        proposal_account,
+        system_program,
+        program_id,
+        data.len(),
+        0,
+        &[PROPOSAL_SEED, &index.to_le_bytes(), &[bump]],
+    )?;
+    proposal_account.data.borrow_mut().copy_from_slice(&data);
+
+    governance.proposal_count = index
+        .checked_add(1)
+        .ok_or(CrossChainError::ArithmeticOverflow)?;
+    governance.pack_into(&mut governance_account.data.borrow_mut()[..])?;
 
     msg!(
-        "Initiated cross-chain transfer: {} SOL to chain {} with fee {}",
-        transfer_data.amount - fee,
-        transfer_data.destination_chain,
-        fee
+        "Proposal {} opened by {} until slot {}",
+        index,
+        proposer.key,
+        proposal.voting_ends_slot
     );
-
     Ok(())
 }
 
-fn complete_cross_chain(
-    _program_id: &Pubkey,
+/// Locks `weight` governance tokens in the vault as a vote. Each voter votes
+/// once per proposal.
+fn cast_vote(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    transfer_data: CrossChainTransferData,
+    support: bool,
+    weight: u64,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
-    let state_account = next_account_info(account_info_iter)?;
-    let recipient_account = next_account_info(account_info_iter)?;
-    let authority = next_account_info(account_info_iter)?;
+    let governance_account = next_account_info(account_info_iter)?;
+    let proposal_account = next_account_info(account_info_iter)?;
+    let vote_record_account = next_account_info(account_info_iter)?;
+    let voter = next_account_info(account_info_iter)?;
+    let voter_token_account = next_account_info(account_info_iter)?;
+    let vault = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
 
-    if !authority.is_signer {
+    if !voter.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    let state = CrossChainState::try_from_slice(&state_account.data.borrow())?;
+    if weight == 0 {
+        return Err(CrossChainError::InsufficientVotingWeight.into());
+    }
+
+    let governance = load_governance(program_id, governance_account)?;
 
-    if state.config.bridge_authority != *authority.key {
-        return Err(ProgramError::Custom(1));
+    if proposal_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut proposal = Proposal::unpack(&proposal_account.data.borrow())?;
+    if get_proposal_address(proposal.index, program_id).0 != *proposal_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    proposal.cast(support, weight, Clock::get()?.slot)?;
+
+    if governance_token_balance(voter_token_account, &governance.config, voter.key)? < weight {
+        return Err(CrossChainError::InsufficientVotingWeight.into());
+    }
+    let (authority, _) = get_governance_authority_address(program_id);
+    validate_escrow_token_account(vault, &governance.config.token_mint, &authority)?;
+
+    let (expected_record, bump) =
+        get_vote_record_address(proposal_account.key, voter.key, program_id);
+    if expected_record != *vote_record_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if vote_record_account.owner == program_id {
+        return Err(CrossChainError::AlreadyVoted.into());
     }
 
+    invoke(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            voter_token_account.key,
+            vault.key,
+            voter.key,
+            &[],
+            weight,
+        )?,
+        &[
+            voter_token_account.clone(),
+            vault.clone(),
+            voter.clone(),
+            token_program.clone(),
+        ],
+    )?;
+
+    create_pda_account(
+        voter,
+        vote_record_account,
+        system_program,
+        program_id,
+        ACCOUNT_HEADER_LEN + VoteRecord::LEN,
+        0,
+        &[
+            VOTE_RECORD_SEED,
+            proposal_account.key.as_ref(),
+            voter.key.as_ref(),
+            &[bump],
+        ],
+    )?;
+    let record = VoteRecord {
+        proposal: *proposal_account.key,
+        voter: *voter.key,
+        weight,
+        support,
+        withdrawn: false,
+        bump,
+    };
+    record.pack_into(&mut vote_record_account.data.borrow_mut()[..])?;
+    proposal.pack_into(&mut proposal_account.data.borrow_mut()[..])?;
+
     msg!(
-        "Completed cross-chain transfer: {} to {}",
-        transfer_data.amount,
-        recipient_account.key
+        "{} voted {} with {} on proposal {}",
+        voter.key,
+        if support { "for" } else { "against" },
+        weight,
+        proposal.index
     );
-
     Ok(())
 }
 
-fn create_qualified_signature(
-    _program_id: &Pubkey,
-    _accounts: &[AccountInfo],
-    _signature_data: QualifiedSignatureData,
-) -> ProgramResult {
-    msg!("Creating eIDAS qualified signature");
+/// Returns a voter's locked tokens once voting on the proposal has ended.
+fn withdraw_vote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let governance_account = next_account_info(account_info_iter)?;
+    let proposal_account = next_account_info(account_info_iter)?;
+    let vote_record_account = next_account_info(account_info_iter)?;
+    let voter = next_account_info(account_info_iter)?;
+    let voter_token_account = next_account_info(account_info_iter)?;
+    let vault = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !voter.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let governance = load_governance(program_id, governance_account)?;
+
+    if proposal_account.owner != program_id || vote_record_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let proposal = Proposal::unpack(&proposal_account.data.borrow())?;
+    let mut record = VoteRecord::unpack(&vote_record_account.data.borrow())?;
+
+    if get_vote_record_address(proposal_account.key, voter.key, program_id).0
+        != *vote_record_account.key
+        || record.proposal != *proposal_account.key
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    if record.withdrawn {
+        return Err(CrossChainError::VoteAlreadyWithdrawn.into());
+    }
+
+    if proposal.is_voting(Clock::get()?.slot) {
+        return Err(CrossChainError::VotingInProgress.into());
+    }
+
+    let authority_seeds: &[&[u8]] = &[GOVERNANCE_AUTHORITY_SEED, &[governance.authority_bump]];
+    if Pubkey::create_program_address(authority_seeds, program_id)? != *authority.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    validate_escrow_token_account(vault, &governance.config.token_mint, authority.key)?;
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            vault.key,
+            voter_token_account.key,
+            authority.key,
+            &[],
+            record.weight,
+        )?,
+        &[
+            vault.clone(),
+            voter_token_account.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        &[authority_seeds],
+    )?;
+
+    record.withdrawn = true;
+    record.pack_into(&mut vote_record_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "{} withdrew {} from proposal {}",
+        voter.key,
+        record.weight,
+        proposal.index
+    );
     Ok(())
 }
 
-fn verify_qualified_signature_on_chain(
-    _program_id: &Pubkey,
-    _accounts: &[AccountInfo],
-    _signature_data: QualifiedSignatureData,
-) -> ProgramResult {
-    msg!("Verifying eIDAS qualified signature");
+/// Executes a passed proposal once its timelock has elapsed, signing as the
+/// governance authority. Anyone may execute; the remaining accounts must
+/// cover every account the proposed instruction uses.
+fn execute_proposal(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let governance_account = next_account_info(account_info_iter)?;
+    let proposal_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+
+    let governance = load_governance(program_id, governance_account)?;
+
+    if proposal_account.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let mut proposal = Proposal::unpack(&proposal_account.data.borrow())?;
+    if get_proposal_address(proposal.index, program_id).0 != *proposal_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    proposal.validate_execution(&governance.config, Clock::get()?.slot)?;
+
+    let authority_seeds: &[&[u8]] = &[GOVERNANCE_AUTHORITY_SEED, &[governance.authority_bump]];
+    if Pubkey::create_program_address(authority_seeds, program_id)? != *authority.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    // Mark executed before the CPI so a re-entrant call cannot replay it.
+    proposal.executed = true;
+    proposal.pack_into(&mut proposal_account.data.borrow_mut()[..])?;
+
+    let mut cpi_accounts = vec![authority.clone()];
+    cpi_accounts.extend(account_info_iter.cloned());
+    invoke_signed(
+        &proposal.instruction.to_instruction(),
+        &cpi_accounts,
+        &[authority_seeds],
+    )?;
+
+    msg!("Executed proposal {}", proposal.index);
     Ok(())
 }
 
-fn create_timestamp(
-    _program_id: &Pubkey,
-    _accounts: &[AccountInfo],
-    _timestamp_data: TimestampData,
+/// Adds or removes an EU Trusted List provider. The registry PDA is created on
+/// first use, paid for by the eIDAS authority.
+fn update_trusted_issuers(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    issuer_hash: [u8; 32],
+    trusted: bool,
 ) -> ProgramResult {
-    msg!("Creating eIDAS qualified timestamp");
+    let account_info_iter = &mut accounts.iter();
+    let state_account = next_account_info(account_info_iter)?;
+    let registry_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let state = load_state(program_id, state_account)?;
+
+    if state.config.eidas_authority != *authority.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let (expected_registry, bump) = get_trusted_issuer_registry_address(program_id);
+    if expected_registry != *registry_account.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let mut registry = if registry_account.owner != program_id {
+        create_pda_account(
+            authority,
+            registry_account,
+            system_program,
+            program_id,
+            TrustedIssuerRegistry::LEN,
+            0,
+            &[TRUSTED_ISSUER_REGISTRY_SEED, &[bump]],
+        )?;
+        TrustedIssuerRegistry {
+            issuers: Vec::new(),
+            bump,
+        }
+    } else {
+        TrustedIssuerRegistry::deserialize(&mut &registry_account.data.borrow()[..])?
+    };
+
+    if trusted {
+        registry.add(issuer_hash, Clock::get()?.unix_timestamp)?;
+    } else {
+        registry.revoke(&issuer_hash)?;
+    }
+    registry.serialize(&mut &mut registry_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Trusted issuer {} {}",
+        hex::encode(issuer_hash),
+        if trusted { "added" } else { "revoked" }
+    );
     Ok(())
 }
 
-fn process_arcium_transaction(
-    _program_id: &Pubkey,
+fn revoke_certificate(
+    program_id: &Pubkey,
     accounts: &[AccountInfo],
-    _arcium_data: ArciumTransactionData,
+    issuer_hash: [u8; 32],
+    serial_number: Vec<u8>,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let state_account = next_account_info(account_info_iter)?;
-    let arcium_program = next_account_info(account_info_iter)?;
+    let revocation_account = next_account_info(account_info_iter)?;
+    let authority = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
 
-    let state = CrossChainState::try_from_slice(&state_account.data.borrow())?;
+    let state = load_state(program_id, state_account)?;
 
-    if state.config.arcium_program != *arcium_program.key {
-        return Err(ProgramError::IncorrectProgramId);
+    if state.config.eidas_authority != *authority.key {
+        return Err(CrossChainError::Unauthorized.into());
+    }
+
+    let (expected, bump) = get_revocation_list_address(&issuer_hash, program_id);
+    if expected != *revocation_account.key {
+        return Err(ProgramError::InvalidSeeds);
     }
 
-    msg!("Processing Arcium encrypted transaction");
+    let mut list = if revocation_account.owner != program_id {
+        create_pda_account(
+            authority,
+            revocation_account,
+            system_program,
+            program_id,
+            RevocationList::LEN,
+            0,
+            &[REVOCATION_LIST_SEED, &issuer_hash, &[bump]],
+        )?;
+        RevocationList {
+            issuer_hash,
+            bump,
+            ..Default::default()
+        }
+    } else {
+        RevocationList::deserialize(&mut &revocation_account.data.borrow()[..])?
+    };
+
+    list.revoke(&serial_number, Clock::get()?.unix_timestamp)?;
+    list.serialize(&mut &mut revocation_account.data.borrow_mut()[..])?;
+
+    msg!(
+        "Certificate {} revoked for issuer {}",
+        hex::encode(&serial_number),
+        hex::encode(issuer_hash)
+    );
     Ok(())
 }
@@ -0,0 +1,161 @@
+//! Inclusion proofs for inbound transfers. Bonded relayers post the state
+//! root of a source-chain block with `PostStateRoot`; a completion can then
+//! carry a Merkle proof that the transfer event is part of that block instead
+//! of relying on relay confirmations alone.
+
+use crate::account::VersionedAccount;
+use crate::bounded::BoundedVec;
+use crate::bridge::CrossChainTransferData;
+use crate::domain::SigningDomain;
+use crate::error::CrossChainError;
+use crate::relayer::{Relayer, VerifiedSignature};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{keccak::hashv, pubkey::Pubkey};
+
+pub const STATE_ROOT_SEED: &[u8] = b"state_root";
+pub const STATE_ROOT_DOMAIN: &[u8] = b"rivicq-state-root-v1";
+pub const MAX_MERKLE_PROOF_DEPTH: usize = 32;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// State root of one source-chain block, one PDA per chain and block.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StateRoot {
+    pub chain_id: u64,
+    pub block_number: u64,
+    pub root: [u8; 32],
+    pub posted_slot: u64,
+    /// Distinct relayers that signed the root.
+    pub signer_count: u8,
+    pub bump: u8,
+}
+
+impl VersionedAccount for StateRoot {
+    const DISCRIMINATOR: [u8; 8] = *b"RQSTROOT";
+    const VERSION: u8 = 1;
+}
+
+impl StateRoot {
+    pub const LEN: usize = 8 + 8 + 32 + 8 + 1 + 1;
+}
+
+/// Sibling hashes from the transfer's leaf up to the root of `block_number`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub block_number: u64,
+    pub siblings: BoundedVec<[u8; 32], MAX_MERKLE_PROOF_DEPTH>,
+}
+
+pub fn get_state_root_address(
+    chain_id: u64,
+    block_number: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            STATE_ROOT_SEED,
+            &chain_id.to_le_bytes(),
+            &block_number.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Digest relayers sign for a posted root, bound to this deployment.
+pub fn state_root_digest(
+    domain: &SigningDomain,
+    chain_id: u64,
+    block_number: u64,
+    root: &[u8; 32],
+) -> [u8; 32] {
+    domain.hash(
+        STATE_ROOT_DOMAIN,
+        &[&chain_id.to_le_bytes(), &block_number.to_le_bytes(), root],
+    )
+}
+
+/// Leaf for a transfer event. Leaves and inner nodes carry distinct prefixes
+/// so an inner node cannot be passed off as a transfer.
+pub fn transfer_leaf(transfer: &CrossChainTransferData) -> [u8; 32] {
+    let payload = transfer.try_to_vec().unwrap_or_default();
+    hashv(&[&[LEAF_PREFIX], &payload]).to_bytes()
+}
+
+/// Root reached from `leaf` through `siblings`. Pairs are hashed in sorted
+/// order, so proofs carry no left/right path bits.
+pub fn merkle_root(leaf: [u8; 32], siblings: &[[u8; 32]]) -> [u8; 32] {
+    siblings.iter().fold(leaf, |node, sibling| {
+        let (low, high) = if node <= *sibling {
+            (node, *sibling)
+        } else {
+            (*sibling, node)
+        };
+        hashv(&[&[NODE_PREFIX], &low, &high]).to_bytes()
+    })
+}
+
+/// Checks that `transfer` is included in the posted root for `chain_id`, the
+/// chain confirming it.
+pub fn verify_inclusion(
+    state_root: &StateRoot,
+    chain_id: u64,
+    transfer: &CrossChainTransferData,
+    proof: &InclusionProof,
+) -> Result<bool, CrossChainError> {
+    if state_root.chain_id != chain_id || state_root.block_number != proof.block_number {
+        return Err(CrossChainError::InvalidInclusionProof);
+    }
+
+    if merkle_root(transfer_leaf(transfer), proof.siblings.as_slice()) != state_root.root {
+        return Err(CrossChainError::InvalidInclusionProof);
+    }
+
+    Ok(true)
+}
+
+/// Enforces the relay quorum for a posted root: every signer must be a
+/// distinct bonded relayer with a verified ed25519 signature over
+/// `state_root_digest`.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_state_root_quorum(
+    domain: &SigningDomain,
+    chain_id: u64,
+    block_number: u64,
+    root: &[u8; 32],
+    signers: &[Pubkey],
+    relayers: &[Relayer],
+    verified: &[VerifiedSignature],
+    required_signatures: u8,
+    min_stake: u64,
+) -> Result<bool, CrossChainError> {
+    let message = state_root_digest(domain, chain_id, block_number, root);
+
+    for (i, signer) in signers.iter().enumerate() {
+        if signers[..i].contains(signer) {
+            return Err(CrossChainError::InvalidRelaySignature);
+        }
+
+        let relayer = relayers
+            .iter()
+            .find(|r| r.authority == *signer)
+            .ok_or(CrossChainError::UnauthorizedRelayer)?;
+
+        if !relayer.is_bonded(min_stake) {
+            return Err(CrossChainError::InsufficientRelayerStake);
+        }
+
+        if !verified
+            .iter()
+            .any(|v| v.signer == *signer && v.message == message)
+        {
+            return Err(CrossChainError::InvalidRelaySignature);
+        }
+    }
+
+    if signers.len() < required_signatures.max(1) as usize {
+        return Err(CrossChainError::InsufficientConfirmations);
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,150 @@
+use crate::bounded::{BoundedBytes, BoundedVec};
+use crate::error::CrossChainError;
+use crate::wallet::MultiSigWallet;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+pub const MULTISIG_SEED: &[u8] = b"multisig";
+pub const MULTISIG_VAULT_SEED: &[u8] = b"multisig_vault";
+pub const MULTISIG_TRANSACTION_SEED: &[u8] = b"multisig_tx";
+pub const MAX_MULTISIG_OWNERS: usize = 10;
+pub const MAX_PROPOSED_ACCOUNTS: usize = 32;
+pub const MAX_PROPOSED_DATA_LEN: usize = 1024;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MultisigConfig {
+    pub owners: BoundedVec<Pubkey, MAX_MULTISIG_OWNERS>,
+    pub threshold: u8,
+    pub eidas_required: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ProposedAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// Instruction executed on behalf of the multisig vault once approved.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ProposedInstruction {
+    pub program_id: Pubkey,
+    pub accounts: BoundedVec<ProposedAccountMeta, MAX_PROPOSED_ACCOUNTS>,
+    pub data: BoundedBytes<MAX_PROPOSED_DATA_LEN>,
+}
+
+impl ProposedInstruction {
+    pub fn to_instruction(&self) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: self
+                .accounts
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: self.data.to_vec(),
+        }
+    }
+}
+
+/// Pending multisig transaction, one PDA per multisig and proposal index.
+/// `approvals` is indexed like the multisig's owner list.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MultisigTransaction {
+    pub multisig: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub instruction: ProposedInstruction,
+    pub approvals: Vec<bool>,
+    pub executed: bool,
+    pub bump: u8,
+}
+
+impl MultisigTransaction {
+    pub fn approval_count(&self) -> usize {
+        self.approvals.iter().filter(|approved| **approved).count()
+    }
+}
+
+pub fn get_multisig_address(creator: &Pubkey, seed: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MULTISIG_SEED, creator.as_ref(), &seed.to_le_bytes()],
+        program_id,
+    )
+}
+
+pub fn get_multisig_vault_address(multisig: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MULTISIG_VAULT_SEED, multisig.as_ref()], program_id)
+}
+
+pub fn get_multisig_transaction_address(
+    multisig: &Pubkey,
+    index: u64,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            MULTISIG_TRANSACTION_SEED,
+            multisig.as_ref(),
+            &index.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn validate_multisig_config(config: &MultisigConfig) -> Result<bool, CrossChainError> {
+    if config.owners.is_empty() || config.owners.len() > MAX_MULTISIG_OWNERS {
+        return Err(CrossChainError::InvalidMultisigConfig);
+    }
+
+    for (i, owner) in config.owners.iter().enumerate() {
+        if config.owners[..i].contains(owner) {
+            return Err(CrossChainError::InvalidMultisigConfig);
+        }
+    }
+
+    if config.threshold == 0 || config.threshold as usize > config.owners.len() {
+        return Err(CrossChainError::InvalidMultisigConfig);
+    }
+
+    Ok(true)
+}
+
+/// The vault is the only account a proposal may ask the multisig to sign
+/// for; every other signer would have to sign the execute transaction itself.
+pub fn validate_proposal(
+    instruction: &ProposedInstruction,
+    vault: &Pubkey,
+) -> Result<bool, CrossChainError> {
+    if instruction
+        .accounts
+        .iter()
+        .any(|meta| meta.is_signer && meta.pubkey != *vault)
+    {
+        return Err(CrossChainError::InvalidMultisigConfig);
+    }
+
+    Ok(true)
+}
+
+pub fn validate_execution(
+    multisig: &MultiSigWallet,
+    transaction: &MultisigTransaction,
+) -> Result<bool, CrossChainError> {
+    if transaction.executed {
+        return Err(CrossChainError::TransactionAlreadyExecuted);
+    }
+
+    if transaction.approval_count() < multisig.threshold as usize {
+        return Err(CrossChainError::ThresholdNotMet);
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,227 @@
+//! Answers of the `Get*` instructions, written borsh-encoded to return data
+//! so other programs can read hub state by CPI instead of decoding accounts.
+//! Fields are only ever appended.
+
+use crate::bridge::{
+    calculate_cross_chain_fee, validate_destination_chain, ChainConfig, TransferRecord,
+    TransferStatus,
+};
+use crate::eidas::{
+    check_compliance_record, check_restricted_jurisdiction, ComplianceRecord, EidasLevel,
+    RestrictedJurisdictions,
+};
+use crate::error::CrossChainError;
+use crate::wallet::{validate_allowed_chain, Wallet, WalletConfig};
+use crate::{CrossChainConfig, CrossChainState, PauseFlags};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+/// Answer of `GetConfig`: the hub config plus the switches that decide
+/// whether a transfer would currently be accepted.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ConfigView {
+    pub config: CrossChainConfig,
+    pub pause_flags: u8,
+    pub relayer_fee: u64,
+    pub emergency_breaker: bool,
+}
+
+impl From<&CrossChainState> for ConfigView {
+    fn from(state: &CrossChainState) -> Self {
+        Self {
+            config: state.config.clone(),
+            pause_flags: state.pause_flags.0,
+            relayer_fee: state.bridge_config.relayer_fee,
+            emergency_breaker: state.bridge_config.emergency_breaker,
+        }
+    }
+}
+
+/// Answer of `GetWalletStatus`, returned as `Option<WalletStatusView>` with
+/// `None` when the owner has no wallet.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct WalletStatusView {
+    pub owner: Pubkey,
+    pub is_compliance_verified: bool,
+    pub eidas_level: EidasLevel,
+    pub kyc_verified: bool,
+    pub aml_screened: bool,
+}
+
+impl From<&Wallet> for WalletStatusView {
+    fn from(wallet: &Wallet) -> Self {
+        Self {
+            owner: wallet.owner,
+            is_compliance_verified: wallet.is_compliance_verified,
+            eidas_level: wallet.eidas_level.clone(),
+            kyc_verified: wallet.kyc_verified,
+            aml_screened: wallet.aml_screened,
+        }
+    }
+}
+
+/// Answer of `GetTransferStatus`, returned as `Option<TransferStatusView>`
+/// with `None` when no record exists for the sender and nonce.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct TransferStatusView {
+    pub status: TransferStatus,
+    pub amount: u64,
+    pub fee: u64,
+    pub destination_chain: u64,
+    pub created_slot: u64,
+    pub completed_slot: u64,
+    pub refundable_after_slot: u64,
+}
+
+impl From<&TransferRecord> for TransferStatusView {
+    fn from(record: &TransferRecord) -> Self {
+        Self {
+            status: record.transfer.status.clone(),
+            amount: record.transfer.amount,
+            fee: record.transfer.fee,
+            destination_chain: record.transfer.destination_chain,
+            created_slot: record.created_slot,
+            completed_slot: record.completed_slot,
+            refundable_after_slot: record.created_slot.saturating_add(record.timeout_slots),
+        }
+    }
+}
+
+/// Answer of `GetStatistics`: the hub totals followed by the volume of each
+/// chain config account passed, in account order. Borsh layout, all
+/// little-endian: `total_volume: u64`, `total_transactions: u64`,
+/// `registered_wallets: u64`, `compliance_records: u64`, then a `u32` count
+/// of `ChainVolumeView { chain_id: u64, outbound_volume: u64,
+/// inbound_volume: u64 }` entries.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct StatisticsView {
+    pub total_volume: u64,
+    pub total_transactions: u64,
+    pub registered_wallets: u64,
+    pub compliance_records: u64,
+    pub chains: Vec<ChainVolumeView>,
+}
+
+impl From<&CrossChainState> for StatisticsView {
+    fn from(state: &CrossChainState) -> Self {
+        Self {
+            total_volume: state.total_volume,
+            total_transactions: state.total_transactions,
+            registered_wallets: state.registered_wallets,
+            compliance_records: state.compliance_records,
+            chains: Vec::new(),
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ChainVolumeView {
+    pub chain_id: u64,
+    pub outbound_volume: u64,
+    pub inbound_volume: u64,
+}
+
+impl From<&ChainConfig> for ChainVolumeView {
+    fn from(chain: &ChainConfig) -> Self {
+        Self {
+            chain_id: chain.chain_id,
+            outbound_volume: chain.outbound_volume,
+            inbound_volume: chain.inbound_volume,
+        }
+    }
+}
+
+/// Answer of `PrecheckRecipient`: `rejection` is the `CrossChainError` code
+/// `InitiateCrossChain` would fail with, `None` when the transfer would pass
+/// and `fee` is what it would be charged.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct PrecheckView {
+    pub rejection: Option<u32>,
+    pub fee: u64,
+}
+
+impl From<Result<u64, CrossChainError>> for PrecheckView {
+    fn from(result: Result<u64, CrossChainError>) -> Self {
+        match result {
+            Ok(fee) => Self {
+                rejection: None,
+                fee,
+            },
+            Err(error) => Self {
+                rejection: Some(error as u32),
+                fee: 0,
+            },
+        }
+    }
+}
+
+/// Accounts a `PrecheckRecipient` evaluates against. Records that were never
+/// created are `None`.
+pub struct TransferPrecheck<'a> {
+    pub wallet: &'a Wallet,
+    pub wallet_config: &'a WalletConfig,
+    pub chain: Option<&'a ChainConfig>,
+    pub sender_compliance: Option<&'a ComplianceRecord>,
+    pub recipient_compliance: Option<&'a ComplianceRecord>,
+    pub restricted: &'a RestrictedJurisdictions,
+}
+
+impl TransferPrecheck<'_> {
+    /// Runs the jurisdiction, screening and limit checks of
+    /// `InitiateCrossChain` in the same order and returns the fee. The
+    /// travel-rule payload is only known at initiation and is not checked.
+    pub fn evaluate(
+        &self,
+        state: &CrossChainState,
+        amount: u64,
+        now: i64,
+    ) -> Result<u64, CrossChainError> {
+        if state.is_paused(PauseFlags::BRIDGE_OUT) {
+            return Err(CrossChainError::ProgramPaused);
+        }
+
+        if state.bridge_config.blocks_outbound() {
+            return Err(CrossChainError::BreakerTripped);
+        }
+
+        check_compliance_record(
+            self.wallet.is_compliance_verified,
+            self.sender_compliance,
+            now,
+        )?;
+
+        for record in [self.sender_compliance, self.recipient_compliance]
+            .into_iter()
+            .flatten()
+        {
+            check_restricted_jurisdiction(self.restricted, &record.data.jurisdiction)?;
+        }
+
+        let chain = self.chain.ok_or(CrossChainError::ChainNotRegistered)?;
+        validate_destination_chain(chain, &self.wallet.eidas_level)?;
+        validate_allowed_chain(self.wallet_config, chain.chain_id)?;
+
+        if amount < state.config.min_cross_chain_amount {
+            return Err(CrossChainError::AmountTooLow);
+        }
+
+        if amount > state.config.max_cross_chain_amount {
+            return Err(CrossChainError::AmountTooHigh);
+        }
+
+        state
+            .compliance_matrix
+            .check(amount, &self.wallet.compliance_profile())?;
+
+        let fee = calculate_cross_chain_fee(
+            amount,
+            state.config.fee_basis_points,
+            state.bridge_config.relayer_fee,
+        )?;
+        if fee > amount {
+            return Err(CrossChainError::AmountTooLow);
+        }
+
+        Ok(fee)
+    }
+}
@@ -0,0 +1,315 @@
+use crate::bridge::{AggregateAttestation, CrossChainTransferData, RelayConfirmation};
+use crate::domain::SigningDomain;
+use crate::error::CrossChainError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+pub const RELAYER_SEED: &[u8] = b"relayer";
+pub const RELAY_MESSAGE_DOMAIN: &[u8] = b"rivicq-relay-v1";
+pub const RELAYER_LEASE_SEED: &[u8] = b"relayer_lease";
+/// Longest lease a relayer can take in one call (about ten minutes), so a
+/// crashed leader blocks its standbys for at most this long.
+pub const MAX_RELAYER_LEASE_SLOTS: u64 = 1_500;
+
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+const ED25519_PUBKEY_LEN: usize = 32;
+const ED25519_SIGNATURE_LEN: usize = 64;
+
+/// Registry entry for a bonded relayer, one PDA per relayer key. The account
+/// itself holds the staked lamports on top of its rent.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct Relayer {
+    pub authority: Pubkey,
+    pub stake: u64,
+    pub total_slashed: u64,
+    pub slash_count: u32,
+    pub registered_slot: u64,
+    pub bump: u8,
+}
+
+impl Relayer {
+    pub const LEN: usize = 32 + 8 + 8 + 4 + 8 + 1;
+
+    pub fn is_bonded(&self, min_stake: u64) -> bool {
+        self.stake > 0 && self.stake >= min_stake
+    }
+}
+
+/// Leader lease for redundant relayer deployments. Only the holder submits
+/// completions while the lease is live; standbys wait for it to expire or be
+/// released and then take over with a new term.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct RelayerLease {
+    pub holder: Pubkey,
+    pub term: u64,
+    pub acquired_slot: u64,
+    pub expires_slot: u64,
+    pub bump: u8,
+}
+
+impl RelayerLease {
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 1;
+
+    pub fn is_active(&self, slot: u64) -> bool {
+        slot < self.expires_slot
+    }
+
+    pub fn is_leader(&self, relayer: &Pubkey, slot: u64) -> bool {
+        self.holder == *relayer && self.is_active(slot)
+    }
+
+    /// Takes a vacant or expired lease, or extends the caller's own. A new
+    /// holder starts a new term so relayers can detect a leadership change.
+    pub fn acquire(
+        &mut self,
+        relayer: Pubkey,
+        slot: u64,
+        duration_slots: u64,
+    ) -> Result<bool, CrossChainError> {
+        if duration_slots == 0 || duration_slots > MAX_RELAYER_LEASE_SLOTS {
+            return Err(CrossChainError::InvalidRelayerLease);
+        }
+
+        if !self.is_leader(&relayer, slot) {
+            if self.is_active(slot) {
+                return Err(CrossChainError::RelayerLeaseHeld);
+            }
+
+            self.holder = relayer;
+            self.term = self.term.saturating_add(1);
+            self.acquired_slot = slot;
+        }
+
+        self.expires_slot = slot.saturating_add(duration_slots);
+        Ok(true)
+    }
+
+    pub fn release(&mut self, relayer: &Pubkey, slot: u64) -> Result<bool, CrossChainError> {
+        if !self.is_leader(relayer, slot) {
+            return Err(CrossChainError::UnauthorizedRelayer);
+        }
+
+        self.expires_slot = slot;
+        Ok(true)
+    }
+}
+
+pub fn get_relayer_lease_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RELAYER_LEASE_SEED], program_id)
+}
+
+pub fn get_relayer_address(authority: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[RELAYER_SEED, authority.as_ref()], program_id)
+}
+
+/// A signature the ed25519 program already checked earlier in the same
+/// transaction, recovered through instruction introspection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedSignature {
+    pub signer: Pubkey,
+    pub signature: [u8; 64],
+    pub message: Vec<u8>,
+}
+
+/// Digest relayers sign for a confirmation: the source transaction hash bound
+/// to the exact transfer being completed and to this deployment.
+pub fn relay_message_hash(
+    domain: &SigningDomain,
+    tx_hash: &[u8],
+    transfer: &CrossChainTransferData,
+) -> [u8; 32] {
+    let payload = transfer.try_to_vec().unwrap_or_default();
+    domain.hash(RELAY_MESSAGE_DOMAIN, &[tx_hash, &payload])
+}
+
+/// Decodes the signatures checked by an ed25519 program instruction. Offsets
+/// pointing into other instructions are rejected so the signer, signature and
+/// message all come from the data that was actually verified.
+pub fn parse_ed25519_instruction(data: &[u8]) -> Result<Vec<VerifiedSignature>, CrossChainError> {
+    let count = *data.first().ok_or(CrossChainError::InvalidRelaySignature)? as usize;
+    let mut verified = Vec::with_capacity(count);
+
+    for i in 0..count {
+        let start = ED25519_OFFSETS_START + i * ED25519_OFFSETS_LEN;
+        let offsets = data
+            .get(start..start + ED25519_OFFSETS_LEN)
+            .ok_or(CrossChainError::InvalidRelaySignature)?;
+        let field = |n: usize| u16::from_le_bytes([offsets[n * 2], offsets[n * 2 + 1]]);
+
+        // signature, public key and message instruction indexes
+        if field(1) != u16::MAX || field(3) != u16::MAX || field(6) != u16::MAX {
+            return Err(CrossChainError::InvalidRelaySignature);
+        }
+
+        let slice = |offset: u16, len: usize| {
+            data.get(offset as usize..offset as usize + len)
+                .ok_or(CrossChainError::InvalidRelaySignature)
+        };
+        let signature = slice(field(0), ED25519_SIGNATURE_LEN)?;
+        let signer = slice(field(2), ED25519_PUBKEY_LEN)?;
+        let message = slice(field(4), field(5) as usize)?;
+
+        let mut sig = [0u8; 64];
+        sig.copy_from_slice(signature);
+        verified.push(VerifiedSignature {
+            signer: Pubkey::try_from(signer).map_err(|_| CrossChainError::InvalidRelaySignature)?,
+            signature: sig,
+            message: message.to_vec(),
+        });
+    }
+
+    Ok(verified)
+}
+
+/// Enforces a quorum of distinct bonded relayers whose signatures over
+/// `relay_message_hash` were verified by the ed25519 program. Any signer
+/// outside the registry, slashed below the minimum stake, or without a
+/// matching verified signature rejects the whole set rather than being skipped.
+/// A `required_signatures` of zero is never satisfied.
+pub fn verify_relay_confirmation(
+    domain: &SigningDomain,
+    transfer: &CrossChainTransferData,
+    confirmations: &[RelayConfirmation],
+    relayers: &[Relayer],
+    verified: &[VerifiedSignature],
+    required_signatures: u8,
+    min_stake: u64,
+) -> Result<bool, CrossChainError> {
+    let mut seen: Vec<Pubkey> = Vec::new();
+
+    for confirmation in confirmations {
+        if confirmation.signers.len() != confirmation.signatures.len() {
+            return Err(CrossChainError::InvalidRelaySignature);
+        }
+
+        let message = relay_message_hash(domain, &confirmation.tx_hash, transfer);
+
+        for (signer, signature) in confirmation
+            .signers
+            .iter()
+            .zip(confirmation.signatures.iter())
+        {
+            let relayer = relayers
+                .iter()
+                .find(|r| r.authority == *signer)
+                .ok_or(CrossChainError::UnauthorizedRelayer)?;
+
+            if !relayer.is_bonded(min_stake) {
+                return Err(CrossChainError::InsufficientRelayerStake);
+            }
+
+            let is_verified = verified.iter().any(|v| {
+                v.signer == *signer
+                    && v.signature.as_slice() == signature.as_slice()
+                    && v.message == message
+            });
+            if !is_verified {
+                return Err(CrossChainError::InvalidRelaySignature);
+            }
+
+            if !seen.contains(signer) {
+                seen.push(*signer);
+            }
+        }
+    }
+
+    // A zero quorum would accept a completion no relayer signed.
+    if required_signatures == 0 || seen.len() < required_signatures as usize {
+        return Err(CrossChainError::InsufficientConfirmations);
+    }
+
+    Ok(true)
+}
+
+/// Enforces the same quorum for an aggregate attestation. `verified` is the
+/// single ed25519 instruction the attestation names: every entry must sign
+/// `relay_message_hash` for the attested transaction, and each signer counts
+/// once. `relayers` holds the registry entry of each signer.
+pub fn verify_aggregate_attestation(
+    domain: &SigningDomain,
+    transfer: &CrossChainTransferData,
+    attestation: &AggregateAttestation,
+    relayers: &[Relayer],
+    verified: &[VerifiedSignature],
+    required_signatures: u8,
+    min_stake: u64,
+) -> Result<bool, CrossChainError> {
+    let message = relay_message_hash(domain, &attestation.tx_hash, transfer);
+    let mut seen: Vec<Pubkey> = Vec::new();
+
+    for signature in verified {
+        if signature.message != message || seen.contains(&signature.signer) {
+            return Err(CrossChainError::InvalidRelaySignature);
+        }
+
+        let relayer = relayers
+            .iter()
+            .find(|r| r.authority == signature.signer)
+            .ok_or(CrossChainError::UnauthorizedRelayer)?;
+        if !relayer.is_bonded(min_stake) {
+            return Err(CrossChainError::InsufficientRelayerStake);
+        }
+
+        seen.push(signature.signer);
+    }
+
+    // A zero quorum would accept a completion no relayer signed.
+    if required_signatures == 0 || seen.len() < required_signatures as usize {
+        return Err(CrossChainError::InsufficientConfirmations);
+    }
+
+    Ok(true)
+}
+
+/// Data for one ed25519 program instruction verifying every `(signer,
+/// signature)` pair against a single copy of `message`. Each extra signer
+/// costs 110 bytes instead of a separate instruction with its own message.
+pub fn aggregate_ed25519_instruction_data(
+    message: &[u8],
+    signatures: &[(Pubkey, [u8; 64])],
+) -> Vec<u8> {
+    let message_offset = ED25519_OFFSETS_START + signatures.len() * ED25519_OFFSETS_LEN;
+    let keys_offset = message_offset + message.len();
+    let entry_len = ED25519_PUBKEY_LEN + ED25519_SIGNATURE_LEN;
+
+    let mut data = vec![signatures.len() as u8, 0];
+    for i in 0..signatures.len() {
+        let pubkey_offset = keys_offset + i * entry_len;
+        for field in [
+            (pubkey_offset + ED25519_PUBKEY_LEN) as u16,
+            u16::MAX,
+            pubkey_offset as u16,
+            u16::MAX,
+            message_offset as u16,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+    }
+    data.extend_from_slice(message);
+    for (signer, signature) in signatures {
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(signature);
+    }
+    data
+}
+
+/// Distinct confirmation signers in first-seen order; `CompleteCrossChain`
+/// expects one relayer registry account per entry, in this order.
+pub fn relay_signers(confirmations: &[RelayConfirmation]) -> Vec<Pubkey> {
+    let mut signers: Vec<Pubkey> = Vec::new();
+    for signer in confirmations.iter().flat_map(|c| c.signers.iter()) {
+        if !signers.contains(signer) {
+            signers.push(*signer);
+        }
+    }
+    signers
+}
+
+/// Lamports removed from a relayer's stake for a fraudulent confirmation,
+/// capped by what is actually bonded.
+pub fn slashable_amount(relayer: &Relayer, amount: u64) -> u64 {
+    amount.min(relayer.stake)
+}
@@ -0,0 +1,134 @@
+use crate::error::CrossChainError;
+use crate::x509::{
+    parse_certificate, parse_time, Der, ParsedCertificate, TAG_GENERALIZED_TIME, TAG_INTEGER,
+    TAG_OCTET_STRING, TAG_OID, TAG_SEQUENCE, TAG_SET,
+};
+use solana_program::pubkey::Pubkey;
+
+pub const TIMESTAMP_SEED: &[u8] = b"timestamp";
+pub const MAX_TIMESTAMP_TOKEN_LEN: usize = 8192;
+/// How far a token's genTime may be from the cluster clock when it is
+/// recorded, allowing for TSA and validator clock drift.
+pub const TIMESTAMP_TOLERANCE_SECS: i64 = 300;
+pub const TIMESTAMP_HASH_ALGORITHM: &str = "SHA-256";
+
+const TAG_CONTEXT_0: u8 = 0xa0;
+const TAG_CONTEXT_1: u8 = 0xa1;
+
+const OID_SIGNED_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x02];
+const OID_TST_INFO: &[u8] = &[
+    0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x10, 0x01, 0x04,
+];
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+
+/// The parts of an RFC 3161 TimeStampToken the program checks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeStampToken {
+    pub policy: Vec<u8>,
+    pub hash_algorithm: Vec<u8>,
+    pub message_imprint: Vec<u8>,
+    pub serial_number: Vec<u8>,
+    pub gen_time: i64,
+    pub tsa_certificate: ParsedCertificate,
+}
+
+pub fn get_timestamp_address(data_hash: &[u8; 32], program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TIMESTAMP_SEED, data_hash], program_id)
+}
+
+/// Parses a DER TimeStampToken (CMS SignedData over TSTInfo). The token must
+/// embed the TSA certificate. The CMS signature itself is not verified
+/// on-chain; the TSA is trusted through its certificate and issuer instead.
+pub fn parse_timestamp_token(der: &[u8]) -> Result<TimeStampToken, CrossChainError> {
+    if der.len() > MAX_TIMESTAMP_TOKEN_LEN {
+        return Err(CrossChainError::InvalidTimestampToken);
+    }
+
+    parse_token(der).map_err(|_| CrossChainError::InvalidTimestampToken)
+}
+
+fn parse_token(der: &[u8]) -> Result<TimeStampToken, CrossChainError> {
+    let mut outer = Der::new(der);
+    let mut content_info = Der::new(outer.expect(TAG_SEQUENCE)?);
+    outer.finish()?;
+    if content_info.expect(TAG_OID)? != OID_SIGNED_DATA {
+        return Err(CrossChainError::InvalidTimestampToken);
+    }
+
+    let mut explicit = Der::new(content_info.expect(TAG_CONTEXT_0)?);
+    let mut signed_data = Der::new(explicit.expect(TAG_SEQUENCE)?);
+    signed_data.expect(TAG_INTEGER)?;
+    signed_data.expect(TAG_SET)?;
+
+    let mut encap = Der::new(signed_data.expect(TAG_SEQUENCE)?);
+    if encap.expect(TAG_OID)? != OID_TST_INFO {
+        return Err(CrossChainError::InvalidTimestampToken);
+    }
+    let mut econtent = Der::new(encap.expect(TAG_CONTEXT_0)?);
+    let tst_info = econtent.expect(TAG_OCTET_STRING)?;
+
+    let mut certificates = Der::new(signed_data.expect(TAG_CONTEXT_0)?);
+    let tsa_certificate = parse_certificate(certificates.read_element()?)?;
+    if signed_data.peek() == Some(TAG_CONTEXT_1) {
+        signed_data.read()?;
+    }
+    signed_data.expect(TAG_SET)?;
+
+    let mut info = Der::new(Der::new(tst_info).expect(TAG_SEQUENCE)?);
+    info.expect(TAG_INTEGER)?;
+    let policy = info.expect(TAG_OID)?.to_vec();
+
+    let mut imprint = Der::new(info.expect(TAG_SEQUENCE)?);
+    let mut algorithm = Der::new(imprint.expect(TAG_SEQUENCE)?);
+    let hash_algorithm = algorithm.expect(TAG_OID)?.to_vec();
+    let message_imprint = imprint.expect(TAG_OCTET_STRING)?.to_vec();
+    imprint.finish()?;
+
+    let serial_number = info.expect(TAG_INTEGER)?.to_vec();
+    // accuracy, ordering, nonce, tsa and extensions follow and are not needed
+    let gen_time = parse_time((TAG_GENERALIZED_TIME, info.expect(TAG_GENERALIZED_TIME)?))?;
+
+    Ok(TimeStampToken {
+        policy,
+        hash_algorithm,
+        message_imprint,
+        serial_number,
+        gen_time,
+        tsa_certificate,
+    })
+}
+
+/// Checks a token timestamps `data_hash` (SHA-256) close to `now`, and that
+/// it was issued by a time-stamping certificate valid at genTime.
+pub fn validate_timestamp_token(
+    token: &TimeStampToken,
+    data_hash: &[u8; 32],
+    now: i64,
+) -> Result<bool, CrossChainError> {
+    if token.hash_algorithm != OID_SHA256 {
+        return Err(CrossChainError::InvalidTimestampToken);
+    }
+
+    if token.message_imprint != data_hash {
+        return Err(CrossChainError::TimestampImprintMismatch);
+    }
+
+    if (token.gen_time - now).abs() > TIMESTAMP_TOLERANCE_SECS {
+        return Err(CrossChainError::TimestampOutOfTolerance);
+    }
+
+    let tsa = &token.tsa_certificate;
+    if !tsa.time_stamping {
+        return Err(CrossChainError::InvalidKeyUsage);
+    }
+
+    if token.gen_time < tsa.not_before {
+        return Err(CrossChainError::CertificateNotYetValid);
+    }
+
+    if token.gen_time > tsa.not_after {
+        return Err(CrossChainError::CertificateExpired);
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,176 @@
+use crate::error::CrossChainError;
+use crate::relayer::VerifiedSignature;
+use solana_program::{keccak, pubkey::Pubkey};
+
+const SECP256K1_OFFSETS_START: usize = 1;
+const SECP256K1_OFFSETS_LEN: usize = 11;
+const SECP256K1_SIGNATURE_LEN: usize = 64;
+const ETH_ADDRESS_LEN: usize = 20;
+const UNCOMPRESSED_PUBKEY_LEN: usize = 65;
+
+/// Signature algorithms a qualified signature may declare in
+/// `signature_algorithm`. secp256k1 signatures are over the keccak-256 of the
+/// message, as checked by the native secp256k1 program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureAlgorithm {
+    Ed25519,
+    Secp256k1,
+}
+
+impl SignatureAlgorithm {
+    pub fn from_name(name: &str) -> Result<Self, CrossChainError> {
+        match name.to_ascii_lowercase().as_str() {
+            "ed25519" | "eddsa" => Ok(Self::Ed25519),
+            "secp256k1" | "es256k" => Ok(Self::Secp256k1),
+            _ => Err(CrossChainError::UnsupportedSignatureAlgorithm),
+        }
+    }
+}
+
+/// A signature the secp256k1 program already checked earlier in the same
+/// transaction. The program recovers the key, so only its Ethereum address
+/// is available.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedSecp256k1Signature {
+    pub eth_address: [u8; 20],
+    pub signature: [u8; 64],
+    pub message: Vec<u8>,
+}
+
+/// Decodes the signatures checked by a secp256k1 program instruction at
+/// `instruction_index`. As with ed25519, offsets must point into the
+/// instruction itself.
+pub fn parse_secp256k1_instruction(
+    data: &[u8],
+    instruction_index: u8,
+) -> Result<Vec<VerifiedSecp256k1Signature>, CrossChainError> {
+    let count = *data
+        .first()
+        .ok_or(CrossChainError::InvalidQualifiedSignature)? as usize;
+    let mut verified = Vec::with_capacity(count);
+
+    for i in 0..count {
+        let start = SECP256K1_OFFSETS_START + i * SECP256K1_OFFSETS_LEN;
+        let offsets = data
+            .get(start..start + SECP256K1_OFFSETS_LEN)
+            .ok_or(CrossChainError::InvalidQualifiedSignature)?;
+        let field = |n: usize| u16::from_le_bytes([offsets[n], offsets[n + 1]]);
+
+        // signature, address and message instruction indexes
+        if offsets[2] != instruction_index
+            || offsets[5] != instruction_index
+            || offsets[10] != instruction_index
+        {
+            return Err(CrossChainError::InvalidQualifiedSignature);
+        }
+
+        let slice = |offset: u16, len: usize| {
+            data.get(offset as usize..offset as usize + len)
+                .ok_or(CrossChainError::InvalidQualifiedSignature)
+        };
+        let signature = slice(field(0), SECP256K1_SIGNATURE_LEN)?;
+        let eth_address = slice(field(3), ETH_ADDRESS_LEN)?;
+        let message = slice(field(6), field(8) as usize)?;
+
+        let mut sig = [0u8; 64];
+        sig.copy_from_slice(signature);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(eth_address);
+        verified.push(VerifiedSecp256k1Signature {
+            eth_address: address,
+            signature: sig,
+            message: message.to_vec(),
+        });
+    }
+
+    Ok(verified)
+}
+
+/// Ethereum address of an uncompressed secp256k1 key, the form the
+/// secp256k1 program compares against. Compressed keys cannot be checked
+/// on-chain.
+pub fn eth_address(public_key: &[u8]) -> Result<[u8; 20], CrossChainError> {
+    if public_key.len() != UNCOMPRESSED_PUBKEY_LEN || public_key[0] != 0x04 {
+        return Err(CrossChainError::InvalidQualifiedSignature);
+    }
+
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&keccak::hash(&public_key[1..]).to_bytes()[12..]);
+    Ok(address)
+}
+
+/// Checks that a native sig-verify instruction in the same transaction
+/// verified `signature` by `public_key` over exactly `message`.
+pub fn verify_with_precompiles(
+    algorithm: SignatureAlgorithm,
+    public_key: &[u8],
+    signature: &[u8],
+    message: &[u8],
+    ed25519: &[VerifiedSignature],
+    secp256k1: &[VerifiedSecp256k1Signature],
+) -> Result<bool, CrossChainError> {
+    // secp256k1 signatures may carry a trailing recovery id
+    let signature = signature
+        .get(..64)
+        .ok_or(CrossChainError::InvalidQualifiedSignature)?;
+
+    let found = match algorithm {
+        SignatureAlgorithm::Ed25519 => {
+            let signer = Pubkey::try_from(public_key)
+                .map_err(|_| CrossChainError::InvalidQualifiedSignature)?;
+            ed25519.iter().any(|v| {
+                v.signer == signer && v.signature.as_slice() == signature && v.message == message
+            })
+        }
+        SignatureAlgorithm::Secp256k1 => {
+            let address = eth_address(public_key)?;
+            secp256k1.iter().any(|v| {
+                v.eth_address == address
+                    && v.signature.as_slice() == signature
+                    && v.message == message
+            })
+        }
+    };
+
+    if !found {
+        return Err(CrossChainError::InvalidQualifiedSignature);
+    }
+
+    Ok(true)
+}
+
+/// Verifies a signature in pure Rust, for off-chain callers that cannot rely
+/// on the native programs.
+#[cfg(not(target_os = "solana"))]
+pub fn verify_signature(
+    algorithm: SignatureAlgorithm,
+    public_key: &[u8],
+    signature: &[u8],
+    message: &[u8],
+) -> Result<bool, CrossChainError> {
+    let valid = match algorithm {
+        SignatureAlgorithm::Ed25519 => {
+            let key = ed25519_dalek::PublicKey::from_bytes(public_key)
+                .map_err(|_| CrossChainError::InvalidQualifiedSignature)?;
+            let signature = ed25519_dalek::Signature::try_from(signature)
+                .map_err(|_| CrossChainError::InvalidQualifiedSignature)?;
+            key.verify_strict(message, &signature).is_ok()
+        }
+        SignatureAlgorithm::Secp256k1 => {
+            let key = libsecp256k1::PublicKey::parse_slice(public_key, None)
+                .map_err(|_| CrossChainError::InvalidQualifiedSignature)?;
+            let signature = signature
+                .get(..64)
+                .and_then(|s| libsecp256k1::Signature::parse_standard_slice(s).ok())
+                .ok_or(CrossChainError::InvalidQualifiedSignature)?;
+            let digest = libsecp256k1::Message::parse(&keccak::hash(message).to_bytes());
+            libsecp256k1::verify(&digest, &signature, &key)
+        }
+    };
+
+    if !valid {
+        return Err(CrossChainError::InvalidQualifiedSignature);
+    }
+
+    Ok(true)
+}
@@ -1,7 +1,9 @@
 #[cfg(test)]
 mod test_functions {
     use crate::arcium::*;
+    use crate::audit::*;
     use crate::bridge::*;
+    use crate::caip::*;
     use crate::eidas::*;
     use crate::*;
     use borsh::BorshSerialize;
@@ -27,7 +29,7 @@ mod test_functions {
             qscd: true,
         };
 
-        let result = validate_certificate(&cert);
+        let result = validate_certificate(&cert, 500_000_000);
         assert!(result.is_ok());
     }
 
@@ -45,8 +47,8 @@ mod test_functions {
             qscd: true,
         };
 
-        let result = validate_certificate(&cert);
-        assert!(result.is_err());
+        let result = validate_certificate(&cert, 500_000_000);
+        assert_eq!(result, Err(CrossChainError::CertificateExpired));
     }
 
     #[test]
@@ -63,23 +65,114 @@ mod test_functions {
             qscd: false,
         };
 
-        let result = validate_certificate(&cert);
+        let result = validate_certificate(&cert, 500_000_000);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_certificate_validity_boundaries() {
+        let cert = QualifiedCertificate {
+            subject: "Test User".to_string(),
+            issuer: "Test Authority".to_string(),
+            serial_number: vec![1, 2, 3, 4],
+            not_before: 1_700_000_000,
+            not_after: 1_800_000_000,
+            public_key_hash: vec![],
+            certificate_type: CertificateType::QES,
+            country: "DE".to_string(),
+            qscd: true,
+        };
+
+        assert_eq!(
+            validate_certificate(&cert, 1_699_999_999),
+            Err(CrossChainError::CertificateNotYetValid)
+        );
+        assert!(validate_certificate(&cert, 1_700_000_000).unwrap());
+        assert!(validate_certificate(&cert, 1_750_000_000).unwrap());
+        assert!(validate_certificate(&cert, 1_800_000_000).unwrap());
+        assert_eq!(
+            validate_certificate(&cert, 1_800_000_001),
+            Err(CrossChainError::CertificateExpired)
+        );
+
+        // Far-future certificates are no longer accepted unconditionally
+        let future = QualifiedCertificate {
+            not_before: 20_000_000_000,
+            not_after: 30_000_000_000,
+            ..cert.clone()
+        };
+        assert_eq!(
+            validate_certificate(&future, 1_750_000_000),
+            Err(CrossChainError::CertificateNotYetValid)
+        );
+
+        let signature = QualifiedSignature {
+            signature: vec![1],
+            certificate: cert,
+            signed_data: vec![],
+            timestamp: 0,
+            signature_algorithm: "Ed25519".to_string(),
+            signer_role: String::new(),
+        };
+        assert_eq!(
+            verify_qualified_signature(&signature, &[], &[], 1_900_000_000),
+            Err(CrossChainError::CertificateExpired)
+        );
+        assert_eq!(
+            verify_qualified_signature(&signature, &[], &[], 1_750_000_000),
+            Err(CrossChainError::TimestampRequired)
+        );
+    }
+
     #[test]
     fn test_restricted_jurisdiction() {
-        assert!(check_restricted_jurisdiction("KP"));
-        assert!(check_restricted_jurisdiction("IR"));
-        assert!(check_restricted_jurisdiction("SY"));
-        assert!(!check_restricted_jurisdiction("DE"));
-        assert!(!check_restricted_jurisdiction("US"));
+        let list = RestrictedJurisdictions::default();
+        assert!(list.is_restricted("KP"));
+        assert!(list.is_restricted("IR"));
+        assert!(list.is_restricted("SY"));
+        assert!(!list.is_restricted("DE"));
+        assert!(!list.is_restricted("US"));
+    }
+
+    #[test]
+    fn test_restricted_jurisdiction_management() {
+        let mut list = RestrictedJurisdictions::default();
+        assert!(list.is_restricted("kp"));
+        assert!(!list.is_restricted(""));
+        assert!(check_restricted_jurisdiction(&list, "").is_ok());
+
+        list.add(parse_jurisdiction("ru").unwrap()).unwrap();
+        list.add(parse_jurisdiction("RU").unwrap()).unwrap();
+        assert_eq!(list.codes.len(), 4);
+        assert_eq!(
+            check_restricted_jurisdiction(&list, "RU"),
+            Err(CrossChainError::RestrictedJurisdiction)
+        );
+
+        list.remove(b"SY").unwrap();
+        assert!(check_restricted_jurisdiction(&list, "SY").is_ok());
+        assert_eq!(
+            list.remove(b"SY"),
+            Err(CrossChainError::InvalidJurisdiction)
+        );
+
+        assert_eq!(
+            parse_jurisdiction("DEU"),
+            Err(CrossChainError::InvalidJurisdiction)
+        );
+        assert_eq!(
+            parse_jurisdiction("1A"),
+            Err(CrossChainError::InvalidJurisdiction)
+        );
     }
 
     #[test]
     fn test_audit_log_creation() {
         let test_key = Pubkey::new_from_array([1u8; 32]);
+        let program_id = crate::id();
+        let id = AuditId::for_transfer(&test_key, 4, 1_000, 7, &program_id);
         let log = create_audit_log(
+            id,
             "TEST_ACTION".to_string(),
             test_key,
             "Test details".to_string(),
@@ -88,7 +181,27 @@ mod test_functions {
 
         assert_eq!(log.action, "TEST_ACTION");
         assert_eq!(log.compliance_status, "COMPLIANT");
-        assert!(!log.id.is_empty());
+
+        // The id names the transfer record, so it joins to the on-chain entry
+        // for the same transfer without a lookup.
+        let record = get_transfer_record_address(&test_key, 4, &program_id).0;
+        assert_eq!(log.id.subject, record);
+        assert_eq!(log.id.to_string(), format!("{}:1000:7", record));
+
+        let mut entry = AuditEntry::new(
+            AuditAction::TransferInitiated,
+            test_key,
+            record,
+            1,
+            &solana_program::clock::Clock {
+                slot: 1_000,
+                ..Default::default()
+            },
+        );
+        entry.sequence = 7;
+        assert_eq!(AuditId::from(&entry), log.id);
+        entry.sequence = 8;
+        assert_ne!(AuditId::from(&entry), log.id);
     }
 
     #[test]
@@ -117,7 +230,18 @@ mod test_functions {
             relayer_fee: 1000,
             protocol_fee: 500,
             emergency_breaker: false,
-            supported_tokens: vec![],
+            supported_tokens: vec![].into(),
+            breaker_scope: BreakerScope::All,
+            min_relayer_stake: 0,
+            required_relayer_signatures: 1,
+            claim_delivery: false,
+            claim_requires_compliance: false,
+            transfer_timeout_slots: 216_000,
+            terms_grace_slots: 0,
+            require_inclusion_proof: false,
+            wormhole: None,
+            axelar: None,
+            cancellation_fee_bps: 10,
         };
 
         assert!(config.relayer_fee > 0);
@@ -125,12 +249,21 @@ mod test_functions {
         assert!(config.min_confirmation_blocks > 0);
     }
 
+    #[test]
+    fn test_bridge_config_defaults_push_delivery() {
+        let config = BridgeConfig::default();
+        assert!(!config.claim_delivery);
+        assert!(!config.claim_requires_compliance);
+        assert!(!config.emergency_breaker);
+        assert_eq!(config.required_relayer_signatures, 1);
+    }
+
     #[test]
     fn test_cross_chain_fee_calculation() {
-        let fee = calculate_cross_chain_fee(1_000_000, 25, 1000);
+        let fee = calculate_cross_chain_fee(1_000_000, 25, 1000).unwrap();
         assert!(fee > 0);
 
-        let fee_no_relayer = calculate_cross_chain_fee(1_000_000, 25, 0);
+        let fee_no_relayer = calculate_cross_chain_fee(1_000_000, 25, 0).unwrap();
         assert!(fee_no_relayer > 0);
     }
 
@@ -151,12 +284,13 @@ mod test_functions {
         };
 
         let tx_data = ArciumTransactionData {
-            encrypted_payload: vec![1, 2, 3, 4],
-            ciphertext: vec![],
-            proof: vec![1, 2, 3, 4],
-            public_inputs: vec![],
+            encrypted_payload: vec![1, 2, 3, 4].into(),
+            ciphertext: vec![].into(),
+            proof: vec![1, 2, 3, 4].into(),
+            public_inputs: vec![].into(),
             arcium_program_id: Pubkey::default(),
-            encryption_public_key: vec![],
+            encryption_public_key: vec![].into(),
+            computation_offset: 0,
         };
 
         let result = validate_arcium_transaction(&tx_data, &config);
@@ -172,12 +306,13 @@ mod test_functions {
         };
 
         let tx_data = ArciumTransactionData {
-            encrypted_payload: vec![],
-            ciphertext: vec![],
-            proof: vec![],
-            public_inputs: vec![],
+            encrypted_payload: vec![].into(),
+            ciphertext: vec![].into(),
+            proof: vec![].into(),
+            public_inputs: vec![].into(),
             arcium_program_id: Pubkey::default(),
-            encryption_public_key: vec![],
+            encryption_public_key: vec![].into(),
+            computation_offset: 0,
         };
 
         let result = validate_arcium_transaction(&tx_data, &config);
@@ -191,11 +326,18 @@ mod test_functions {
             bridge_authority: Pubkey::new_from_array([2u8; 32]),
             eidas_authority: Pubkey::new_from_array([3u8; 32]),
             arcium_program: Pubkey::new_from_array([4u8; 32]),
-            supported_chains: vec![1, 10, 42161],
+            supported_chains: vec![1, 10, 42161].into(),
             min_cross_chain_amount: 1000,
             max_cross_chain_amount: 1_000_000_000,
             fee_basis_points: 25,
             paused: false,
+            guardian: Pubkey::new_from_array([5u8; 32]),
+            genesis_hash: [6u8; 32],
+            require_top_level: true,
+            registration: RegistrationPolicy::default(),
+            config_timelock_slots: 0,
+            max_pending_transfers: 32,
+            hub_chain_id: 900,
         };
 
         let serialized = config.try_to_vec().unwrap();
@@ -204,6 +346,51 @@ mod test_functions {
         assert_eq!(config.admin, deserialized.admin);
         assert_eq!(config.supported_chains, deserialized.supported_chains);
         assert_eq!(config.fee_basis_points, deserialized.fee_basis_points);
+        assert!(deserialized.require_top_level);
+    }
+
+    #[test]
+    fn test_cross_chain_config_validation() {
+        assert!(CrossChainConfig::default().validate().unwrap());
+
+        let invalid = [
+            CrossChainConfig {
+                fee_basis_points: 10_001,
+                ..Default::default()
+            },
+            CrossChainConfig {
+                fee_basis_points: MAX_FEE_BASIS_POINTS + 1,
+                ..Default::default()
+            },
+            CrossChainConfig {
+                min_cross_chain_amount: 5_000,
+                max_cross_chain_amount: 1_000,
+                ..Default::default()
+            },
+            CrossChainConfig {
+                min_cross_chain_amount: 0,
+                ..Default::default()
+            },
+            CrossChainConfig {
+                supported_chains: vec![].into(),
+                ..Default::default()
+            },
+            // 25 bps of 100 rounds down to a zero fee
+            CrossChainConfig {
+                min_cross_chain_amount: 100,
+                ..Default::default()
+            },
+        ];
+        for config in invalid {
+            assert_eq!(config.validate(), Err(CrossChainError::InvalidConfig));
+        }
+
+        let fee_free = CrossChainConfig {
+            min_cross_chain_amount: 1,
+            fee_basis_points: 0,
+            ..Default::default()
+        };
+        assert!(fee_free.validate().unwrap());
     }
 
     #[test]
@@ -214,13 +401,50 @@ mod test_functions {
         assert_eq!(state.registered_wallets, 0);
         assert_eq!(state.compliance_records, 0);
         assert!(!state.config.paused);
+        assert!(state.pending_admin.is_none());
+    }
+
+    #[test]
+    fn test_pause_flags_per_subsystem() {
+        let mut state = CrossChainState::default();
+        assert!(!state.is_paused(PauseFlags::BRIDGE_OUT));
+
+        state
+            .pause_flags
+            .insert(PauseFlags(PauseFlags::BRIDGE_IN.0 | PauseFlags::ARCIUM.0));
+        assert!(state.is_paused(PauseFlags::BRIDGE_IN));
+        assert!(state.is_paused(PauseFlags::ARCIUM));
+        assert!(!state.is_paused(PauseFlags::BRIDGE_OUT));
+        assert!(!state.is_paused(PauseFlags::COMPLIANCE));
+
+        state.pause_flags.remove(PauseFlags::BRIDGE_IN);
+        assert!(!state.is_paused(PauseFlags::BRIDGE_IN));
+        assert!(state.is_paused(PauseFlags::ARCIUM));
+
+        state.config.paused = true;
+        assert!(state.is_paused(PauseFlags::WALLET_REGISTRATION));
+    }
+
+    #[test]
+    fn test_pending_admin_round_trip() {
+        let mut state = CrossChainState::default();
+        let base_len = state.try_to_vec().unwrap().len();
+
+        state.pending_admin = Some(Pubkey::new_from_array([4u8; 32]));
+        let serialized = state.try_to_vec().unwrap();
+        assert_eq!(serialized.len(), base_len + 32);
+
+        let deserialized = CrossChainState::try_from_slice(&serialized).unwrap();
+        assert_eq!(deserialized.pending_admin, state.pending_admin);
+        assert_eq!(deserialized.config.admin, Pubkey::default());
     }
 
     #[test]
     fn test_wallet_data_serialization() {
         let wallet_data = WalletData {
-            public_key: vec![1, 2, 3, 4, 5],
-            metadata: vec![6, 7, 8, 9, 10],
+            public_key: vec![1, 2, 3, 4, 5].into(),
+            metadata: vec![6, 7, 8, 9, 10].into(),
+            eidas_level: EidasLevel::None,
         };
 
         let serialized = wallet_data.try_to_vec().unwrap();
@@ -232,7 +456,7 @@ mod test_functions {
     #[test]
     fn test_transaction_signature_data_serialization() {
         let sig_data = TransactionSignatureData {
-            transaction_hash: vec![1u8; 32],
+            transaction_hash: vec![1u8; 32].into(),
             amount: 1000,
             recipient: Pubkey::new_from_array([2u8; 32]),
             source_chain: 1,
@@ -246,4 +470,6961 @@ mod test_functions {
         assert_eq!(sig_data.amount, deserialized.amount);
         assert_eq!(sig_data.source_chain, deserialized.source_chain);
     }
+
+    fn sample_intent() -> TransferIntent {
+        TransferIntent {
+            owner: Pubkey::new_from_array([1u8; 32]),
+            amount: 1_000_000,
+            destination_chain: 10,
+            recipient: Pubkey::new_from_array([2u8; 32]),
+            max_fee: 5000,
+            deadline: 1_000,
+            nonce: 7,
+            status: IntentStatus::Open,
+            best_solver: None,
+            best_fee: 0,
+            fill_tx_hash: [0u8; 32],
+            created_at: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_intent_len_matches_serialized_size() {
+        let mut intent = sample_intent();
+        intent.best_solver = Some(Pubkey::new_from_array([3u8; 32]));
+        assert_eq!(intent.try_to_vec().unwrap().len(), TransferIntent::LEN);
+    }
+
+    #[test]
+    fn test_unbid_intent_round_trips_through_account() {
+        use solana_program::account_info::AccountInfo;
+        use solana_program::program_error::ProgramError;
+
+        let program_id = crate::id();
+        let intent = sample_intent();
+        let (address, _) = get_intent_address(&intent.owner, intent.nonce, &program_id);
+
+        // A fresh intent has no solver, so its body is shorter than the
+        // account and the tail is padding.
+        let mut data = vec![0u8; ACCOUNT_HEADER_LEN + TransferIntent::LEN];
+        assert!(intent.try_to_vec().unwrap().len() < TransferIntent::LEN);
+        intent.pack_into(&mut data[..]).unwrap();
+
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &address,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        let loaded = load_intent(&program_id, &account).unwrap();
+        assert_eq!(loaded.best_solver, None);
+        assert_eq!(loaded.escrow_amount(), intent.escrow_amount());
+
+        let mut lamports = 0;
+        let mut data = vec![0u8; ACCOUNT_HEADER_LEN + TransferIntent::LEN];
+        intent.pack_into(&mut data[..]).unwrap();
+        let forged = Pubkey::new_unique();
+        let account = AccountInfo::new(
+            &forged,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        assert_eq!(
+            load_intent(&program_id, &account).unwrap_err(),
+            ProgramError::InvalidSeeds
+        );
+    }
+
+    #[test]
+    fn test_validate_intent() {
+        let data = TransferIntentData {
+            amount: 1000,
+            destination_chain: 10,
+            recipient: Pubkey::new_from_array([2u8; 32]),
+            max_fee: 10,
+            deadline: 100,
+            nonce: 0,
+        };
+        assert!(validate_intent(&data, 50).is_ok());
+        assert!(validate_intent(&data, 100).is_err());
+
+        let overflowing = TransferIntentData {
+            max_fee: u64::MAX,
+            ..data
+        };
+        assert!(validate_intent(&overflowing, 50).is_err());
+    }
+
+    #[test]
+    fn test_solver_bids_must_undercut() {
+        let mut intent = sample_intent();
+        assert!(validate_solver_bid(&intent, 6000, 10).is_err());
+        assert!(validate_solver_bid(&intent, 4000, 10).is_ok());
+
+        intent.best_solver = Some(Pubkey::new_from_array([3u8; 32]));
+        intent.best_fee = 4000;
+        assert!(validate_solver_bid(&intent, 4000, 10).is_err());
+        assert!(validate_solver_bid(&intent, 3999, 10).is_ok());
+        assert!(validate_solver_bid(&intent, 3999, 1_000).is_err());
+    }
+
+    #[test]
+    fn test_intent_settlement_and_cancel() {
+        let mut intent = sample_intent();
+        assert!(can_cancel_intent(&intent, 10));
+
+        intent.best_solver = Some(Pubkey::new_from_array([3u8; 32]));
+        intent.best_fee = 1500;
+        assert!(!can_cancel_intent(&intent, 10));
+        assert!(can_cancel_intent(&intent, 1_000 + INTENT_FILL_WINDOW + 1));
+
+        let (solver_payout, owner_refund) = settle_intent(&intent);
+        assert_eq!(solver_payout, 1_001_500);
+        assert_eq!(owner_refund, 3500);
+        assert_eq!(solver_payout + owner_refund, intent.escrow_amount());
+    }
+
+    fn sample_keeper_job() -> KeeperJob {
+        KeeperJob {
+            kind: KeeperJobKind::ExpireIntent,
+            tip_per_crank: 5000,
+            min_interval_slots: 10,
+            enabled: true,
+            last_crank_slot: 0,
+            total_cranks: 0,
+            total_tips_paid: 0,
+            bump: 255,
+            tip_bump: 254,
+        }
+    }
+
+    #[test]
+    fn test_keeper_crank_interval() {
+        let mut job = sample_keeper_job();
+        assert!(validate_crank(&job, 0).is_ok());
+
+        job.total_cranks = 1;
+        job.last_crank_slot = 100;
+        assert!(validate_crank(&job, 105).is_err());
+        assert!(validate_crank(&job, 110).is_ok());
+
+        job.enabled = false;
+        assert!(validate_crank(&job, 500).is_err());
+        assert_eq!(job.try_to_vec().unwrap().len(), KeeperJob::LEN);
+    }
+
+    #[test]
+    fn test_keeper_tip_capped_by_balance() {
+        let job = sample_keeper_job();
+        assert_eq!(payable_tip(&job, 1_000_000, 890_880), 5000);
+        assert_eq!(payable_tip(&job, 892_880, 890_880), 2000);
+        assert_eq!(payable_tip(&job, 100, 890_880), 0);
+    }
+
+    #[test]
+    fn test_intent_expiry() {
+        let mut intent = sample_intent();
+        assert!(!is_intent_expired(&intent, 999));
+        assert!(is_intent_expired(&intent, 1_000));
+
+        intent.best_solver = Some(Pubkey::new_from_array([3u8; 32]));
+        assert!(!is_intent_expired(&intent, 1_000 + INTENT_FILL_WINDOW));
+        assert!(is_intent_expired(&intent, 1_000 + INTENT_FILL_WINDOW + 1));
+    }
+
+    #[test]
+    fn test_keeper_expires_unbid_intent_from_account() {
+        use solana_program::account_info::AccountInfo;
+
+        let program_id = crate::id();
+        let intent = sample_intent();
+        let (address, _) = get_intent_address(&intent.owner, intent.nonce, &program_id);
+        let mut data = vec![0u8; ACCOUNT_HEADER_LEN + TransferIntent::LEN];
+        intent.pack_into(&mut data[..]).unwrap();
+
+        let mut lamports = intent.escrow_amount();
+        let account = AccountInfo::new(
+            &address,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        let mut loaded = load_intent(&program_id, &account).unwrap();
+        assert!(!is_intent_expired(&loaded, intent.deadline - 1));
+        assert!(is_intent_expired(&loaded, intent.deadline));
+
+        loaded.status = IntentStatus::Cancelled;
+        loaded
+            .pack_into(&mut account.data.borrow_mut()[..])
+            .unwrap();
+        assert_eq!(
+            load_intent(&program_id, &account).unwrap().status,
+            IntentStatus::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_eidas_level_ordering() {
+        assert!(EidasLevel::None < EidasLevel::Basic);
+        assert!(EidasLevel::Basic < EidasLevel::Substantial);
+        assert!(EidasLevel::Substantial < EidasLevel::High);
+    }
+
+    #[test]
+    fn test_destination_chain_requires_min_level() {
+        let mut chain = ChainConfig {
+            chain_id: 42161,
+            enabled: true,
+            min_eidas_level: EidasLevel::Substantial,
+            inbound_enabled: true,
+            bump: 255,
+            caip2: Caip2ChainId::from_legacy(42161),
+            encryption_key: [0u8; 32],
+            gateway_codehash: [0u8; 32],
+            outbound_volume: 0,
+            inbound_volume: 0,
+            rate_limit: RateLimit::default(),
+            wormhole_emitter: Default::default(),
+            transport: TransportKind::default(),
+        };
+        assert_eq!(chain.try_to_vec().unwrap().len(), ChainConfig::LEN);
+
+        assert!(validate_destination_chain(&chain, &EidasLevel::Basic).is_err());
+        assert!(validate_destination_chain(&chain, &EidasLevel::Substantial).is_ok());
+        assert!(validate_destination_chain(&chain, &EidasLevel::High).is_ok());
+
+        chain.enabled = false;
+        assert!(validate_destination_chain(&chain, &EidasLevel::High).is_err());
+    }
+
+    #[test]
+    fn test_transfer_record_layout_and_release() {
+        let record = TransferRecord {
+            transfer: CrossChainTransferData {
+                sender: Pubkey::new_from_array([1u8; 32]),
+                recipient: Pubkey::new_from_array([2u8; 32]),
+                amount: 1_000_000,
+                destination_chain: 10,
+                source_chain: 0,
+                token_address: Some(Pubkey::new_from_array([3u8; 32])),
+                fee: 2500,
+                nonce: 4,
+                timestamp: 0,
+                status: TransferStatus::Initiated,
+                travel_rule: None,
+            },
+            escrowed_amount: 1_000_000,
+            created_slot: 1,
+            completed_slot: 0,
+            bump: 255,
+            wrapped: false,
+            relayer_fee: 0,
+            timeout_slots: 0,
+            gateway_codehash: [0u8; 32],
+            terms: TransferTerms::default(),
+        };
+
+        assert_eq!(record.try_to_vec().unwrap().len(), TransferRecord::LEN);
+        assert_eq!(record.release_amount(), 997_500);
+
+        let burned = TransferRecord {
+            wrapped: true,
+            relayer_fee: 0,
+            timeout_slots: 0,
+            ..record
+        };
+        assert_eq!(burned.release_amount(), 0);
+    }
+
+    #[test]
+    fn test_transfer_record_address_is_nonce_scoped() {
+        let program_id = crate::id();
+        let sender = Pubkey::new_from_array([1u8; 32]);
+        let (first, _) = get_transfer_record_address(&sender, 1, &program_id);
+        let (second, _) = get_transfer_record_address(&sender, 2, &program_id);
+        assert_ne!(first, second);
+    }
+
+    fn activity_record(nonce: u64) -> TransactionRecord {
+        TransactionRecord {
+            id: format!("10-{}", nonce),
+            timestamp: nonce as i64,
+            tx_hash: vec![0xab; 64],
+            from: Pubkey::new_from_array([1u8; 32]),
+            to: Pubkey::new_from_array([2u8; 32]),
+            amount: 1000,
+            fee: 3,
+            status: TransactionStatus::Pending,
+            chain_id: 10,
+            cross_chain: true,
+        }
+    }
+
+    #[test]
+    fn test_activity_log_ring_buffer_wraps() {
+        let mut log = WalletActivityLog::new(Pubkey::new_from_array([1u8; 32]));
+        for nonce in 0..(ACTIVITY_LOG_CAPACITY as u64 + 3) {
+            log.push(activity_record(nonce));
+        }
+
+        assert_eq!(log.records.len(), ACTIVITY_LOG_CAPACITY);
+        assert_eq!(log.total_records, ACTIVITY_LOG_CAPACITY as u64 + 3);
+
+        let recent = log.recent();
+        assert_eq!(recent[0].timestamp, ACTIVITY_LOG_CAPACITY as i64 + 2);
+        assert_eq!(recent[ACTIVITY_LOG_CAPACITY - 1].timestamp, 3);
+    }
+
+    #[test]
+    fn test_activity_log_fits_account() {
+        let mut log = WalletActivityLog::new(Pubkey::new_from_array([1u8; 32]));
+        for nonce in 0..ACTIVITY_LOG_CAPACITY as u64 {
+            let mut record = activity_record(nonce);
+            record.id = "x".repeat(100);
+            log.push(record);
+        }
+
+        assert!(log.try_to_vec().unwrap().len() <= WalletActivityLog::LEN);
+        assert_eq!(log.records[0].tx_hash.len(), MAX_RECORD_TX_HASH_LEN);
+    }
+
+    #[test]
+    fn test_activity_log_caps_pending_transfers() {
+        let mut log = WalletActivityLog::new(Pubkey::new_from_array([1u8; 32]));
+        assert!(log.open_transfer(2).is_ok());
+        assert!(log.open_transfer(2).is_ok());
+        assert_eq!(
+            log.open_transfer(2),
+            Err(CrossChainError::TooManyPendingTransfers)
+        );
+
+        log.close_transfer().unwrap();
+        assert_eq!(log.pending_transfers, 1);
+        assert!(log.open_transfer(2).is_ok());
+
+        // Zero leaves the count uncapped.
+        assert!(log.open_transfer(0).is_ok());
+        assert_eq!(log.pending_transfers, 3);
+
+        for _ in 0..5 {
+            log.close_transfer().unwrap();
+        }
+        assert_eq!(log.pending_transfers, 0);
+    }
+
+    #[test]
+    fn test_activity_log_refunds_follow_recovery() {
+        let owner = Pubkey::new_from_array([1u8; 32]);
+        let new_owner = Pubkey::new_from_array([9u8; 32]);
+        let mut log = WalletActivityLog::new(owner);
+        assert_eq!(log.refund_beneficiary(), owner);
+
+        log.recovered_to = Some(new_owner);
+        assert_eq!(log.refund_beneficiary(), new_owner);
+        log.velocity.window_start = u64::MAX;
+        assert!(log.try_to_vec().unwrap().len() <= WalletActivityLog::LEN);
+    }
+
+    #[test]
+    fn test_wallet_address_is_unique_per_owner() {
+        let program_id = crate::id();
+        let owner = Pubkey::new_from_array([1u8; 32]);
+        let other = Pubkey::new_from_array([2u8; 32]);
+
+        assert_eq!(
+            get_wallet_address(&owner, &program_id),
+            get_wallet_address(&owner, &program_id)
+        );
+        assert_ne!(
+            get_wallet_address(&owner, &program_id).0,
+            get_wallet_address(&other, &program_id).0
+        );
+    }
+
+    #[test]
+    fn test_nonce_registry_rejects_reuse() {
+        let mut registry = NonceRegistry::default();
+        assert!(registry.consume_transfer_nonce(0).is_ok());
+        assert!(registry.consume_transfer_nonce(0).is_err());
+        assert!(registry.consume_transfer_nonce(5).is_ok());
+        assert!(registry.consume_transfer_nonce(3).is_err());
+        assert_eq!(registry.next_transfer_nonce, 6);
+
+        assert!(registry.consume_signature_nonce(0).is_ok());
+        assert!(registry.consume_transfer_nonce(u64::MAX).is_err());
+        assert_eq!(registry.try_to_vec().unwrap().len(), NonceRegistry::LEN);
+    }
+
+    fn sample_message(message_type: MessageType, payload: Vec<u8>) -> CrossChainMessage {
+        CrossChainMessage {
+            id: "msg-1".into(),
+            source_chain: 0,
+            destination_chain: 10,
+            sender: Pubkey::new_from_array([1u8; 32]),
+            recipient: Pubkey::new_from_array([2u8; 32]),
+            message_type,
+            payload: payload.into(),
+            nonce: 1,
+            timestamp: 0,
+            gateway_codehash: [4u8; 32],
+            compression: PayloadCompression::None,
+        }
+    }
+
+    #[test]
+    fn test_sealed_payload_envelope() {
+        let chain_key = [7u8; 32];
+        let sealed = |inner_type: MessageType, recipient_key: [u8; 32], len: usize| SealedPayload {
+            version: SEALED_PAYLOAD_VERSION,
+            inner_type,
+            recipient_key,
+            ephemeral_key: [9u8; 32],
+            nonce: [1u8; 12],
+            ciphertext: vec![0xab; len].into(),
+        };
+        let mut chain = ChainConfig {
+            chain_id: 10,
+            enabled: true,
+            min_eidas_level: EidasLevel::None,
+            inbound_enabled: true,
+            bump: 255,
+            caip2: Caip2ChainId::from_legacy(10),
+            encryption_key: [0u8; 32],
+            gateway_codehash: [0u8; 32],
+            outbound_volume: 0,
+            inbound_volume: 0,
+            rate_limit: RateLimit::default(),
+            wormhole_emitter: Default::default(),
+            transport: TransportKind::default(),
+        };
+
+        let message = sample_message(
+            MessageType::Sealed,
+            sealed(MessageType::ContractCall, chain_key, 64)
+                .try_to_vec()
+                .unwrap(),
+        );
+        let decoded = decode_bridge_message(&encode_bridge_message(&message)).unwrap();
+        assert_eq!(decoded.message_type, MessageType::Sealed);
+
+        assert_eq!(chain.encryption_key(), None);
+        assert_eq!(
+            validate_sealed_recipient(&message, &chain),
+            Err(CrossChainError::EncryptionKeyMismatch)
+        );
+        chain.encryption_key = chain_key;
+        assert!(validate_sealed_recipient(&message, &chain).unwrap());
+        chain.encryption_key = [8u8; 32];
+        assert_eq!(
+            validate_sealed_recipient(&message, &chain),
+            Err(CrossChainError::EncryptionKeyMismatch)
+        );
+
+        let plaintext = sample_message(MessageType::TokenTransfer, vec![1, 2, 3]);
+        assert!(validate_sealed_recipient(&plaintext, &chain).unwrap());
+
+        for invalid in [
+            sealed(MessageType::Sealed, chain_key, 64),
+            sealed(
+                MessageType::TokenTransfer,
+                chain_key,
+                SEALED_PAYLOAD_TAG_LEN,
+            ),
+            sealed(
+                MessageType::TokenTransfer,
+                chain_key,
+                MAX_TRANSFER_PAYLOAD_LEN + SEALED_PAYLOAD_TAG_LEN + 1,
+            ),
+            SealedPayload {
+                version: 0,
+                ..sealed(MessageType::TokenTransfer, chain_key, 64)
+            },
+        ] {
+            let message = sample_message(MessageType::Sealed, invalid.try_to_vec().unwrap());
+            assert!(matches!(
+                validate_bridge_message(&message),
+                Err(MessageError::InvalidEnvelope(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_gateway_codehash_pinning() {
+        let message = sample_message(MessageType::TokenTransfer, vec![1]);
+        let decoded = decode_bridge_message(&encode_bridge_message(&message)).unwrap();
+        assert_eq!(decoded.gateway_codehash, [4u8; 32]);
+
+        assert!(verify_gateway_codehash(&message, &[4u8; 32]).unwrap());
+        assert_eq!(
+            verify_gateway_codehash(&message, &[5u8; 32]),
+            Err(CrossChainError::GatewayMismatch)
+        );
+
+        let unpinned = CrossChainMessage {
+            gateway_codehash: [0u8; 32],
+            ..message
+        };
+        assert_eq!(
+            verify_gateway_codehash(&unpinned, &[0u8; 32]),
+            Err(CrossChainError::GatewayMismatch)
+        );
+    }
+
+    #[test]
+    fn test_decode_bridge_message_round_trip() {
+        let envelope = ContractCallEnvelope {
+            target: vec![0x11; 20].into(),
+            calldata: vec![1, 2, 3].into(),
+            gas_limit: 200_000,
+        };
+        let message = sample_message(MessageType::ContractCall, envelope.try_to_vec().unwrap());
+
+        let decoded = decode_bridge_message(&encode_bridge_message(&message)).unwrap();
+        assert_eq!(decoded.payload, message.payload);
+        assert!(matches!(
+            decode_bridge_message(&[1, 2, 3]),
+            Err(MessageError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_token_redeem_message() {
+        let payload = RedemptionPayload {
+            remote_token: Pubkey::new_from_array([5u8; 32]),
+            local_mint: Pubkey::new_from_array([6u8; 32]),
+            amount: 42,
+        };
+        let message = sample_message(MessageType::TokenRedeem, payload.try_to_vec().unwrap());
+        let decoded = decode_bridge_message(&encode_bridge_message(&message)).unwrap();
+        assert_eq!(
+            RedemptionPayload::try_from_slice(&decoded.payload).unwrap(),
+            payload
+        );
+
+        let empty = RedemptionPayload {
+            amount: 0,
+            ..payload
+        };
+        assert!(matches!(
+            validate_bridge_message(&sample_message(
+                MessageType::TokenRedeem,
+                empty.try_to_vec().unwrap()
+            )),
+            Err(MessageError::InvalidEnvelope(_))
+        ));
+    }
+
+    #[test]
+    fn test_bridge_message_payload_limits() {
+        let oversized = sample_message(
+            MessageType::TokenTransfer,
+            vec![0u8; MAX_TRANSFER_PAYLOAD_LEN + 1],
+        );
+        assert!(matches!(
+            validate_bridge_message(&oversized),
+            Err(MessageError::PayloadTooLarge { .. })
+        ));
+
+        let garbage_call = sample_message(MessageType::ContractCall, vec![9u8; 3]);
+        assert!(matches!(
+            validate_bridge_message(&garbage_call),
+            Err(MessageError::InvalidEnvelope(_))
+        ));
+
+        let empty_target = ContractCallEnvelope {
+            target: vec![].into(),
+            calldata: vec![].into(),
+            gas_limit: 1,
+        };
+        let message = sample_message(
+            MessageType::ContractCall,
+            empty_target.try_to_vec().unwrap(),
+        );
+        assert!(validate_bridge_message(&message).is_err());
+    }
+
+    #[test]
+    fn test_cross_chain_error_codes_are_stable() {
+        use num_traits::FromPrimitive;
+        use solana_program::program_error::ProgramError;
+
+        assert_eq!(
+            ProgramError::from(CrossChainError::Unauthorized),
+            ProgramError::Custom(1)
+        );
+        assert_eq!(
+            ProgramError::from(CrossChainError::AmountTooLow),
+            ProgramError::Custom(1003)
+        );
+        assert_eq!(
+            CrossChainError::from_u32(1002),
+            Some(CrossChainError::ProgramPaused)
+        );
+        assert_eq!(CrossChainError::from_u32(999), None);
+    }
+
+    #[test]
+    fn test_message_error_maps_to_cross_chain_error() {
+        let error = decode_bridge_message(&[0u8; 2]).unwrap_err();
+        assert_eq!(
+            CrossChainError::from(error),
+            CrossChainError::InvalidBridgeMessage
+        );
+        assert_eq!(
+            validate_certificate(
+                &QualifiedCertificate {
+                    subject: String::new(),
+                    issuer: String::new(),
+                    serial_number: vec![],
+                    not_before: 0,
+                    not_after: 0,
+                    public_key_hash: vec![],
+                    certificate_type: CertificateType::QES,
+                    country: "DE".to_string(),
+                    qscd: false,
+                },
+                0
+            ),
+            Err(CrossChainError::CertificateNotQscd)
+        );
+    }
+
+    #[test]
+    fn test_breaker_scopes() {
+        let mut config = BridgeConfig {
+            supported_tokens: vec![TokenConfig {
+                mint: Pubkey::new_from_array([9u8; 32]),
+                symbol: "USDC".into(),
+                decimals: 6,
+                max_transfer: u64::MAX,
+                min_transfer: 0,
+                enabled: true,
+            }]
+            .into(),
+            ..Default::default()
+        };
+        let profile = ComplianceProfile::default();
+        let matrix = ComplianceMatrix::default();
+        assert!(validate_cross_chain_transfer(1000, 10, &config, &profile, &matrix).is_ok());
+
+        config.emergency_breaker = true;
+        config.breaker_scope = BreakerScope::Inbound;
+        assert!(config.blocks_inbound());
+        assert!(!config.blocks_outbound());
+        assert!(validate_cross_chain_transfer(1000, 10, &config, &profile, &matrix).is_ok());
+
+        config.breaker_scope = BreakerScope::Outbound;
+        assert!(!config.blocks_inbound());
+        assert_eq!(
+            validate_cross_chain_transfer(1000, 10, &config, &profile, &matrix),
+            Err(CrossChainError::BreakerTripped)
+        );
+
+        config.breaker_scope = BreakerScope::All;
+        assert!(config.blocks_inbound() && config.blocks_outbound());
+    }
+
+    #[test]
+    fn test_source_chain_inbound_allowlist() {
+        let mut chain = ChainConfig {
+            chain_id: 1,
+            enabled: true,
+            min_eidas_level: EidasLevel::None,
+            inbound_enabled: true,
+            bump: 255,
+            caip2: Caip2ChainId::from_legacy(1),
+            encryption_key: [0u8; 32],
+            gateway_codehash: [0u8; 32],
+            outbound_volume: 0,
+            inbound_volume: 0,
+            rate_limit: RateLimit::default(),
+            wormhole_emitter: Default::default(),
+            transport: TransportKind::default(),
+        };
+        assert!(validate_source_chain(&chain).is_ok());
+
+        chain.inbound_enabled = false;
+        assert_eq!(
+            validate_source_chain(&chain),
+            Err(CrossChainError::InboundDisabled)
+        );
+
+        chain.enabled = false;
+        assert_eq!(
+            validate_source_chain(&chain),
+            Err(CrossChainError::ChainDisabled)
+        );
+    }
+
+    #[test]
+    fn test_wrapped_asset_registry() {
+        let mut registry = WrappedAssetRegistry::default();
+        let asset = WrappedAsset {
+            chain_id: 1,
+            remote_token: Pubkey::new_from_array([7u8; 32]),
+            local_mint: Pubkey::new_from_array([8u8; 32]),
+            remote_decimals: 18,
+            local_decimals: 8,
+        };
+
+        assert!(registry.register(asset.clone()).is_ok());
+        assert_eq!(
+            registry.register(asset.clone()),
+            Err(CrossChainError::WrappedAssetExists)
+        );
+        assert_eq!(
+            registry.find_by_remote(1, &asset.remote_token),
+            Some(&asset)
+        );
+        assert!(registry.find_by_remote(10, &asset.remote_token).is_none());
+        assert_eq!(registry.find_by_mint(&asset.local_mint), Some(&asset));
+
+        for i in 1..MAX_WRAPPED_ASSETS {
+            let mut key = [0u8; 32];
+            key[..8].copy_from_slice(&(i as u64).to_le_bytes());
+            registry
+                .register(WrappedAsset {
+                    chain_id: 2,
+                    remote_token: Pubkey::new_from_array(key),
+                    local_mint: Pubkey::new_from_array(key),
+                    remote_decimals: 6,
+                    local_decimals: 6,
+                })
+                .unwrap();
+        }
+        assert_eq!(
+            registry.register(WrappedAsset {
+                chain_id: 3,
+                remote_token: Pubkey::new_from_array([9u8; 32]),
+                local_mint: Pubkey::new_from_array([9u8; 32]),
+                remote_decimals: 6,
+                local_decimals: 6,
+            }),
+            Err(CrossChainError::WrappedRegistryFull)
+        );
+        assert_eq!(
+            registry.try_to_vec().unwrap().len(),
+            WrappedAssetRegistry::LEN
+        );
+    }
+
+    #[test]
+    fn test_wrapped_asset_rescaling_and_deregistration() {
+        let asset = WrappedAsset {
+            chain_id: 1,
+            remote_token: Pubkey::new_from_array([7u8; 32]),
+            local_mint: Pubkey::new_from_array([8u8; 32]),
+            remote_decimals: 18,
+            local_decimals: 8,
+        };
+        assert_eq!(
+            asset.to_local_amount(1_234_567_890_123_456_789),
+            Ok(123_456_789)
+        );
+        assert_eq!(
+            asset.to_remote_amount(123_456_789),
+            Ok(1_234_567_890_000_000_000)
+        );
+        assert_eq!(
+            asset.to_remote_amount(u64::MAX),
+            Err(CrossChainError::ArithmeticOverflow)
+        );
+
+        let same = WrappedAsset {
+            remote_decimals: 8,
+            ..asset.clone()
+        };
+        assert_eq!(same.to_local_amount(42), Ok(42));
+        assert_eq!(same.to_remote_amount(42), Ok(42));
+
+        let mut registry = WrappedAssetRegistry::default();
+        registry.register(asset.clone()).unwrap();
+        assert_eq!(
+            registry.deregister(2, &asset.remote_token),
+            Err(CrossChainError::UnknownWrappedAsset)
+        );
+        assert_eq!(registry.deregister(1, &asset.remote_token), Ok(true));
+        assert!(registry.find_by_mint(&asset.local_mint).is_none());
+        assert!(registry.register(asset).is_ok());
+    }
+
+    #[test]
+    fn test_default_compliance_matrix_matches_legacy_rule() {
+        let matrix = ComplianceMatrix::default();
+        let unverified = ComplianceProfile::default();
+        assert!(matrix.check(1_000_000_000, &unverified).is_ok());
+        assert_eq!(
+            matrix.check(1_000_000_001, &unverified),
+            Err(CrossChainError::ComplianceRequired)
+        );
+    }
+
+    #[test]
+    fn test_compliance_matrix_bands() {
+        let matrix = ComplianceMatrix {
+            bands: vec![
+                ComplianceBand {
+                    min_amount: 1_000,
+                    required_level: EidasLevel::Basic,
+                    require_kyc: true,
+                    require_aml: false,
+                },
+                ComplianceBand {
+                    min_amount: 1_000_000,
+                    required_level: EidasLevel::Substantial,
+                    require_kyc: true,
+                    require_aml: true,
+                },
+            ]
+            .into(),
+            travel_rule_threshold: None,
+            hold_rules: HoldRules::default(),
+        };
+        assert!(matrix.validate().is_ok());
+
+        let mut profile = ComplianceProfile {
+            eidas_level: EidasLevel::Basic,
+            kyc_verified: false,
+            aml_screened: false,
+        };
+        assert!(matrix.check(999, &profile).is_ok());
+        assert_eq!(
+            matrix.check(1_000, &profile),
+            Err(CrossChainError::KycRequired)
+        );
+
+        profile.kyc_verified = true;
+        assert!(matrix.check(5_000, &profile).is_ok());
+        assert_eq!(
+            matrix.check(1_000_000, &profile),
+            Err(CrossChainError::ComplianceRequired)
+        );
+
+        profile.eidas_level = EidasLevel::High;
+        assert_eq!(
+            matrix.check(1_000_000, &profile),
+            Err(CrossChainError::AmlScreeningRequired)
+        );
+
+        let unsorted = ComplianceMatrix {
+            bands: matrix.bands.iter().rev().cloned().collect(),
+            travel_rule_threshold: None,
+            hold_rules: HoldRules::default(),
+        };
+        assert!(unsorted.validate().is_err());
+    }
+
+    fn relayer(seed: u8, stake: u64) -> Relayer {
+        Relayer {
+            authority: Pubkey::new_from_array([seed; 32]),
+            stake,
+            total_slashed: 0,
+            slash_count: 0,
+            registered_slot: 0,
+            bump: 255,
+        }
+    }
+
+    fn confirmation(signers: Vec<Pubkey>) -> RelayConfirmation {
+        RelayConfirmation {
+            relayer: signers[0],
+            tx_hash: vec![0u8; 32].into(),
+            block_number: 1,
+            timestamp: 0,
+            signatures: signers
+                .iter()
+                .map(|s| vec![s.to_bytes()[0]; 64].into())
+                .collect(),
+            signers: signers.into(),
+        }
+    }
+
+    fn relay_transfer() -> CrossChainTransferData {
+        CrossChainTransferData {
+            sender: Pubkey::new_from_array([1u8; 32]),
+            recipient: Pubkey::new_from_array([2u8; 32]),
+            amount: 1_000_000,
+            destination_chain: 0,
+            source_chain: 10,
+            token_address: None,
+            fee: 2500,
+            nonce: 4,
+            timestamp: 0,
+            status: TransferStatus::Initiated,
+            travel_rule: None,
+        }
+    }
+
+    fn relay_domain() -> SigningDomain {
+        SigningDomain::new(crate::id(), [3u8; 32])
+    }
+
+    fn verified_for(
+        transfer: &CrossChainTransferData,
+        confirmation: &RelayConfirmation,
+    ) -> Vec<VerifiedSignature> {
+        let message = relay_message_hash(&relay_domain(), &confirmation.tx_hash, transfer).to_vec();
+        confirmation
+            .signers
+            .iter()
+            .zip(confirmation.signatures.iter())
+            .map(|(signer, signature)| VerifiedSignature {
+                signer: *signer,
+                signature: signature.as_slice().try_into().unwrap(),
+                message: message.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_relayer_len() {
+        assert_eq!(relayer(1, 0).try_to_vec().unwrap().len(), Relayer::LEN);
+    }
+
+    #[test]
+    fn test_verify_relay_confirmation_against_registry() {
+        let transfer = relay_transfer();
+        let relayers = vec![relayer(1, 1_000), relayer(2, 1_000), relayer(3, 10)];
+        let a = relayers[0].authority;
+        let b = relayers[1].authority;
+
+        let confirmations = vec![confirmation(vec![a]), confirmation(vec![b, a])];
+        let verified = verified_for(&transfer, &confirmations[1]);
+        assert!(verify_relay_confirmation(
+            &relay_domain(),
+            &transfer,
+            &confirmations,
+            &relayers,
+            &verified,
+            2,
+            1_000
+        )
+        .is_ok());
+        assert_eq!(
+            verify_relay_confirmation(
+                &relay_domain(),
+                &transfer,
+                &confirmations,
+                &relayers,
+                &verified,
+                3,
+                1_000
+            ),
+            Err(CrossChainError::InsufficientConfirmations)
+        );
+
+        let outsider = confirmation(vec![Pubkey::new_from_array([9; 32])]);
+        let outsider_verified = verified_for(&transfer, &outsider);
+        assert_eq!(
+            verify_relay_confirmation(
+                &relay_domain(),
+                &transfer,
+                &[outsider],
+                &relayers,
+                &outsider_verified,
+                1,
+                1_000
+            ),
+            Err(CrossChainError::UnauthorizedRelayer)
+        );
+
+        let underbonded = confirmation(vec![relayers[2].authority]);
+        let underbonded_verified = verified_for(&transfer, &underbonded);
+        assert_eq!(
+            verify_relay_confirmation(
+                &relay_domain(),
+                &transfer,
+                &[underbonded],
+                &relayers,
+                &underbonded_verified,
+                1,
+                1_000
+            ),
+            Err(CrossChainError::InsufficientRelayerStake)
+        );
+    }
+
+    #[test]
+    fn test_relay_signatures_must_cover_transfer() {
+        let transfer = relay_transfer();
+        let relayers = vec![relayer(1, 1_000)];
+        let confirmations = vec![confirmation(vec![relayers[0].authority])];
+        let verified = verified_for(&transfer, &confirmations[0]);
+
+        assert_eq!(
+            verify_relay_confirmation(
+                &relay_domain(),
+                &transfer,
+                &confirmations,
+                &relayers,
+                &[],
+                1,
+                1_000
+            ),
+            Err(CrossChainError::InvalidRelaySignature)
+        );
+
+        let tampered = CrossChainTransferData {
+            amount: 2_000_000,
+            ..relay_transfer()
+        };
+        assert_eq!(
+            verify_relay_confirmation(
+                &relay_domain(),
+                &tampered,
+                &confirmations,
+                &relayers,
+                &verified,
+                1,
+                1_000
+            ),
+            Err(CrossChainError::InvalidRelaySignature)
+        );
+
+        // An empty quorum is never met, even by an empty confirmation set.
+        for confirmations in [&confirmations[..], &[]] {
+            assert_eq!(
+                verify_relay_confirmation(
+                    &relay_domain(),
+                    &transfer,
+                    confirmations,
+                    &relayers,
+                    &verified,
+                    0,
+                    1_000
+                ),
+                Err(CrossChainError::InsufficientConfirmations)
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_aggregate_attestation() {
+        let transfer = relay_transfer();
+        let relayers = vec![relayer(1, 1_000), relayer(2, 1_000), relayer(3, 10)];
+        let attestation = AggregateAttestation {
+            tx_hash: vec![0u8; 32].into(),
+            block_number: 1,
+            timestamp: 0,
+            ed25519_instruction_index: 0,
+        };
+        let message = relay_message_hash(&relay_domain(), &attestation.tx_hash, &transfer);
+        let signed = |seeds: &[u8]| {
+            let signatures: Vec<(Pubkey, [u8; 64])> = seeds
+                .iter()
+                .map(|&seed| (Pubkey::new_from_array([seed; 32]), [seed; 64]))
+                .collect();
+            parse_ed25519_instruction(&aggregate_ed25519_instruction_data(&message, &signatures))
+                .unwrap()
+        };
+        let verify = |verified: &[VerifiedSignature], required: u8| {
+            verify_aggregate_attestation(
+                &relay_domain(),
+                &transfer,
+                &attestation,
+                &relayers,
+                verified,
+                required,
+                1_000,
+            )
+        };
+
+        // One copy of the message serves every signer.
+        let verified = signed(&[1, 2]);
+        assert_eq!(verified.len(), 2);
+        assert_eq!(verified[1].signer, relayers[1].authority);
+        assert_eq!(verified[1].signature, [2u8; 64]);
+        assert!(verified.iter().all(|v| v.message == message));
+        assert_eq!(
+            aggregate_ed25519_instruction_data(&message, &[(Pubkey::default(), [0; 64]); 2]).len(),
+            2 + 2 * 14 + 32 + 2 * 96
+        );
+
+        assert_eq!(verify(&verified, 2), Ok(true));
+        assert_eq!(
+            verify(&verified, 3),
+            Err(CrossChainError::InsufficientConfirmations)
+        );
+        for verified in [&verified[..], &[]] {
+            assert_eq!(
+                verify(verified, 0),
+                Err(CrossChainError::InsufficientConfirmations)
+            );
+        }
+        assert_eq!(
+            verify(&signed(&[1, 1]), 1),
+            Err(CrossChainError::InvalidRelaySignature)
+        );
+        assert_eq!(
+            verify(&signed(&[1, 9]), 1),
+            Err(CrossChainError::UnauthorizedRelayer)
+        );
+        assert_eq!(
+            verify(&signed(&[3]), 1),
+            Err(CrossChainError::InsufficientRelayerStake)
+        );
+
+        let mut other = signed(&[1, 2]);
+        other[1].message = vec![0u8; 32];
+        assert_eq!(
+            verify(&other, 1),
+            Err(CrossChainError::InvalidRelaySignature)
+        );
+    }
+
+    #[test]
+    fn test_parse_ed25519_instruction() {
+        let signer = Pubkey::new_from_array([7u8; 32]);
+        let signature = [5u8; 64];
+        let message = [1u8, 2, 3];
+
+        let pubkey_offset: u16 = 16;
+        let signature_offset: u16 = pubkey_offset + 32;
+        let message_offset: u16 = signature_offset + 64;
+        let mut data = vec![1u8, 0];
+        for field in [
+            signature_offset,
+            u16::MAX,
+            pubkey_offset,
+            u16::MAX,
+            message_offset,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&signature);
+        data.extend_from_slice(&message);
+
+        let parsed = parse_ed25519_instruction(&data).unwrap();
+        assert_eq!(
+            parsed,
+            vec![VerifiedSignature {
+                signer,
+                signature,
+                message: message.to_vec(),
+            }]
+        );
+
+        // Offsets into another instruction are not trusted.
+        data[4..6].copy_from_slice(&0u16.to_le_bytes());
+        assert_eq!(
+            parse_ed25519_instruction(&data),
+            Err(CrossChainError::InvalidRelaySignature)
+        );
+    }
+
+    #[test]
+    fn test_slashable_amount_capped_by_stake() {
+        let bonded = relayer(1, 500);
+        assert_eq!(slashable_amount(&bonded, 200), 200);
+        assert_eq!(slashable_amount(&bonded, 5_000), 500);
+        assert!(!relayer(1, 0).is_bonded(0));
+    }
+
+    fn wide_instruction(
+        payer: &Pubkey,
+        accounts: usize,
+    ) -> solana_program::instruction::Instruction {
+        use solana_program::instruction::{AccountMeta, Instruction};
+
+        let mut metas = vec![AccountMeta::new(*payer, true)];
+        metas.extend((0..accounts).map(|i| {
+            let mut key = [0u8; 32];
+            key[..8].copy_from_slice(&(i as u64 + 1).to_le_bytes());
+            AccountMeta::new(Pubkey::new_from_array(key), false)
+        }));
+        Instruction::new_with_bytes(crate::id(), &[0], metas)
+    }
+
+    #[test]
+    fn test_lookup_table_candidates_skip_signers_and_programs() {
+        use crate::client::*;
+
+        let payer = Pubkey::new_from_array([200u8; 32]);
+        let mut ix = wide_instruction(&payer, 3);
+        ix.accounts
+            .push(solana_program::instruction::AccountMeta::new_readonly(
+                crate::id(),
+                false,
+            ));
+        let candidates = lookup_table_candidates(&[ix.clone(), ix]);
+        assert_eq!(candidates.len(), 3);
+        assert!(!candidates.contains(&payer));
+        assert!(!candidates.contains(&crate::id()));
+
+        let table = Pubkey::new_from_array([201u8; 32]);
+        let extends = extend_lookup_table(&table, &payer, &payer, &candidates[..1], &candidates);
+        assert_eq!(extends.len(), 1);
+        assert!(extend_lookup_table(&table, &payer, &payer, &candidates, &candidates).is_empty());
+
+        let many: Vec<Pubkey> = (0..MAX_EXTEND_ADDRESSES as u8 + 1)
+            .map(|i| Pubkey::new_from_array([i; 32]))
+            .collect();
+        assert_eq!(
+            extend_lookup_table(&table, &payer, &payer, &[], &many).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_build_versioned_transaction_uses_lookup_tables() {
+        use crate::client::*;
+        use solana_sdk::{
+            hash::Hash,
+            message::{AddressLookupTableAccount, VersionedMessage},
+            signature::Keypair,
+            signer::Signer,
+        };
+
+        let payer = Keypair::new();
+        let ix = wide_instruction(&payer.pubkey(), 40);
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_from_array([201u8; 32]),
+            addresses: lookup_table_candidates(std::slice::from_ref(&ix)),
+        };
+
+        let tx = build_versioned_transaction(
+            &payer.pubkey(),
+            &[ix],
+            &[table],
+            Hash::default(),
+            &[&payer],
+        )
+        .unwrap();
+        match &tx.message {
+            VersionedMessage::V0(message) => {
+                assert_eq!(message.address_table_lookups.len(), 1);
+                assert_eq!(message.address_table_lookups[0].writable_indexes.len(), 40);
+                assert_eq!(message.account_keys.len(), 2);
+            }
+            VersionedMessage::Legacy(_) => panic!("expected a v0 message"),
+        }
+        assert!(tx.verify_with_results().iter().all(|ok| *ok));
+    }
+
+    struct MockRpc {
+        simulation: crate::client::SimulationResult,
+        sent: std::cell::Cell<u32>,
+        accounts: Vec<(Pubkey, Vec<u8>)>,
+    }
+
+    impl crate::client::RpcTransport for MockRpc {
+        fn simulate(
+            &self,
+            _tx: &solana_sdk::transaction::VersionedTransaction,
+        ) -> Result<crate::client::SimulationResult, crate::client::ClientError> {
+            Ok(self.simulation.clone())
+        }
+
+        fn send(
+            &self,
+            _tx: &solana_sdk::transaction::VersionedTransaction,
+        ) -> Result<solana_sdk::signature::Signature, crate::client::ClientError> {
+            self.sent.set(self.sent.get() + 1);
+            Ok(solana_sdk::signature::Signature::default())
+        }
+
+        fn get_account_data(
+            &self,
+            address: &Pubkey,
+        ) -> Result<Option<Vec<u8>>, crate::client::ClientError> {
+            Ok(self
+                .accounts
+                .iter()
+                .find(|(key, _)| key == address)
+                .map(|(_, data)| data.clone()))
+        }
+    }
+
+    #[test]
+    fn test_send_with_preflight_decodes_program_errors() {
+        use crate::client::*;
+        use solana_sdk::{
+            instruction::InstructionError, transaction::TransactionError,
+            transaction::VersionedTransaction,
+        };
+
+        let tx = VersionedTransaction::default();
+        let rpc = MockRpc {
+            simulation: SimulationResult {
+                err: Some(TransactionError::InstructionError(
+                    0,
+                    InstructionError::Custom(CrossChainError::AmountTooLow as u32),
+                )),
+                logs: vec![
+                    "Program 11111111111111111111111111111111 invoke [1]".to_string(),
+                    "Program log: Amount below minimum".to_string(),
+                ],
+                units_consumed: Some(1_200),
+            },
+            sent: std::cell::Cell::new(0),
+            accounts: vec![],
+        };
+
+        match send_with_preflight(&rpc, &tx) {
+            Err(ClientError::Simulation {
+                reason,
+                error,
+                logs,
+            }) => {
+                assert_eq!(error, Some(CrossChainError::AmountTooLow));
+                assert_eq!(reason, "Amount below minimum cross-chain amount");
+                assert_eq!(logs, vec!["Amount below minimum".to_string()]);
+            }
+            other => panic!("unexpected preflight result: {:?}", other),
+        }
+        assert_eq!(rpc.sent.get(), 0);
+
+        let ok = MockRpc {
+            simulation: SimulationResult::default(),
+            sent: std::cell::Cell::new(0),
+            accounts: vec![],
+        };
+        assert!(send_with_preflight(&ok, &tx).is_ok());
+        assert_eq!(ok.sent.get(), 1);
+        assert_eq!(
+            decode_transaction_error(&TransactionError::AccountNotFound),
+            None
+        );
+    }
+
+    #[test]
+    fn test_initiate_cross_chain_idempotent() {
+        use crate::client::*;
+        use solana_sdk::transaction::VersionedTransaction;
+
+        let program_id = crate::id();
+        let tx = VersionedTransaction::default();
+        let transfer = relay_transfer();
+        let key = transfer_idempotency_key(&transfer, &program_id);
+        let record = TransferRecord {
+            transfer: transfer.clone(),
+            escrowed_amount: transfer.amount,
+            created_slot: 7,
+            completed_slot: 0,
+            bump: 255,
+            wrapped: false,
+            relayer_fee: 0,
+            timeout_slots: 0,
+            gateway_codehash: [0u8; 32],
+            terms: TransferTerms::default(),
+        };
+
+        let fresh = MockRpc {
+            simulation: SimulationResult::default(),
+            sent: std::cell::Cell::new(0),
+            accounts: vec![],
+        };
+        assert!(matches!(
+            initiate_cross_chain_idempotent(&fresh, &program_id, &transfer, &tx),
+            Ok(Submission::Sent(_))
+        ));
+        assert_eq!(fresh.sent.get(), 1);
+
+        let resubmitted = MockRpc {
+            simulation: SimulationResult::default(),
+            sent: std::cell::Cell::new(0),
+            accounts: vec![(key, record.try_to_vec().unwrap())],
+        };
+        match initiate_cross_chain_idempotent(&resubmitted, &program_id, &transfer, &tx) {
+            Ok(Submission::Existing(existing)) => assert_eq!(existing.created_slot, 7),
+            other => panic!("unexpected submission: {:?}", other),
+        }
+        assert_eq!(resubmitted.sent.get(), 0);
+
+        let conflicting = CrossChainTransferData {
+            amount: transfer.amount + 1,
+            ..transfer
+        };
+        assert!(matches!(
+            initiate_cross_chain_idempotent(&resubmitted, &program_id, &conflicting, &tx),
+            Err(ClientError::IdempotencyConflict(k)) if k == key
+        ));
+    }
+
+    #[test]
+    fn test_fee_ledger_accrual_and_withdrawal() {
+        let usdc = Some(Pubkey::new_from_array([9u8; 32]));
+        let mut ledger = FeeLedger::default();
+
+        assert_eq!(split_fee(2_500, 1_000), (1_500, 1_000));
+        assert_eq!(split_fee(500, 1_000), (0, 500));
+
+        ledger.accrue(None, 1_500, 1_000).unwrap();
+        ledger.accrue(None, 500, 0).unwrap();
+        ledger.accrue(usdc, 10, 5).unwrap();
+        assert_eq!(ledger.entries.len(), 2);
+        assert_eq!(ledger.get(None).unwrap().protocol, 2_000);
+
+        assert!(ledger.debit_protocol(None, 2_000).is_ok());
+        assert_eq!(
+            ledger.debit_protocol(None, 1),
+            Err(CrossChainError::InsufficientAccruedFees)
+        );
+        assert!(ledger.debit_relayer(usdc, 5).is_ok());
+        assert_eq!(
+            ledger.debit_relayer(Some(Pubkey::new_from_array([8u8; 32])), 1),
+            Err(CrossChainError::InsufficientAccruedFees)
+        );
+
+        for i in 0..MAX_FEE_ASSETS as u8 - 2 {
+            ledger
+                .accrue(Some(Pubkey::new_from_array([100 + i; 32])), 1, 0)
+                .unwrap();
+        }
+        assert_eq!(
+            ledger.accrue(Some(Pubkey::new_from_array([250u8; 32])), 1, 0),
+            Err(CrossChainError::FeeLedgerFull)
+        );
+    }
+
+    #[test]
+    fn test_fee_ledger_per_chain_report() {
+        let usdc = Some(Pubkey::new_from_array([9u8; 32]));
+        let mut ledger = FeeLedger::default();
+
+        ledger.accrue(None, 2_000, 500).unwrap();
+        ledger.record_chain(1, None, 1_500).unwrap();
+        ledger.record_chain(10, None, 1_000).unwrap();
+        ledger.accrue(usdc, 30, 0).unwrap();
+        ledger.record_chain(10, usdc, 30).unwrap();
+        ledger.record_chain(1, None, 0).unwrap();
+
+        assert_eq!(ledger.chain_total(1, None), 1_500);
+        assert_eq!(ledger.chain_total(10, None), 1_000);
+        assert_eq!(ledger.chain_total(10, usdc), 30);
+        assert_eq!(ledger.chain_total(42161, None), 0);
+        assert_eq!(ledger.by_chain.len(), 3);
+
+        // Withdrawals draw on the asset balance, not the per-chain totals.
+        ledger.debit_protocol(None, 2_000).unwrap();
+        assert_eq!(ledger.chain_total(1, None), 1_500);
+
+        let report = TreasuryReport::from_ledger(&ledger, 77, 255);
+        assert_eq!(report.assets, ledger.entries);
+        assert_eq!(report.chains, ledger.by_chain);
+
+        for i in 0..MAX_FEE_ASSETS as u8 {
+            ledger
+                .accrue(Some(Pubkey::new_from_array([100 + i; 32])), 1, 0)
+                .ok();
+        }
+        for chain_id in 0..MAX_CHAIN_FEE_ENTRIES as u64 - 3 {
+            ledger
+                .record_chain(1_000 + chain_id, Some(Pubkey::new_unique()), 1)
+                .unwrap();
+        }
+        assert_eq!(
+            ledger.record_chain(5, None, 1),
+            Err(CrossChainError::FeeLedgerFull)
+        );
+        let full = TreasuryReport::from_ledger(&ledger, 78, 255);
+        assert!(full.try_to_vec().unwrap().len() <= TreasuryReport::LEN);
+
+        // Republishing a smaller report over a full one leaves stale padding
+        // that decoding ignores.
+        let mut data = vec![0u8; ACCOUNT_HEADER_LEN + TreasuryReport::LEN];
+        full.pack_into(&mut data[..]).unwrap();
+        report.pack_into(&mut data[..]).unwrap();
+        let decoded = TreasuryReport::unpack(&data).unwrap();
+        assert_eq!(decoded.slot, 77);
+        assert_eq!(decoded.assets, report.assets);
+    }
+
+    #[test]
+    fn test_fee_schedule_history() {
+        let schedule = |fee_basis_points, relayer_fee, effective_slot| FeeSchedule {
+            fee_basis_points,
+            relayer_fee,
+            effective_slot,
+        };
+        let mut history = FeeScheduleHistory::new(255);
+        assert!(history.at(100).is_none());
+
+        assert!(history.record(schedule(25, 1_000, 100)));
+        assert!(!history.record(schedule(25, 1_000, 150)));
+        assert!(history.record(schedule(30, 1_000, 200)));
+        assert!(history.record(schedule(40, 1_000, 200)));
+        assert_eq!(history.entries.len(), 2);
+
+        assert!(history.at(99).is_none());
+        assert_eq!(history.at(100), Some(&schedule(25, 1_000, 100)));
+        assert_eq!(history.at(199).unwrap().fee_basis_points, 25);
+        assert_eq!(history.at(200), Some(&schedule(40, 1_000, 200)));
+        assert_eq!(history.at(u64::MAX).unwrap().fee_basis_points, 40);
+
+        for i in 0..FEE_HISTORY_CAPACITY as u64 {
+            assert!(history.record(schedule(40, 2_000 + i, 300 + i)));
+        }
+        assert_eq!(history.entries.len(), FEE_HISTORY_CAPACITY);
+        assert_eq!(history.evicted, 2);
+        assert!(history.at(299).is_none());
+        assert_eq!(history.at(305).unwrap().relayer_fee, 2_005);
+        for i in 0..FEE_HISTORY_CAPACITY as u64 {
+            history.corridors.push(CorridorFeeSchedule {
+                source_chain: i,
+                destination_chain: i,
+                fee_multiplier_bps: 10_000,
+                effective_slot: i,
+            });
+        }
+        assert_eq!(history.try_to_vec().unwrap().len(), FeeScheduleHistory::LEN);
+        assert_eq!(
+            FeeScheduleHistory::unpack(&history.pack().unwrap())
+                .unwrap()
+                .at(305),
+            history.at(305)
+        );
+    }
+
+    #[test]
+    fn test_corridor_fee_schedule_history() {
+        let corridor =
+            |destination_chain, fee_multiplier_bps, effective_slot| CorridorFeeSchedule {
+                source_chain: 900,
+                destination_chain,
+                fee_multiplier_bps,
+                effective_slot,
+            };
+        let mut history = FeeScheduleHistory::new(255);
+        assert!(history.record(FeeSchedule {
+            fee_basis_points: 20,
+            relayer_fee: 1_000,
+            effective_slot: 100,
+        }));
+
+        // Corridors without an entry pay the hub rate.
+        assert_eq!(history.fee_basis_points_at(900, 10, 150), Some(20));
+        assert_eq!(history.fee_basis_points_at(900, 10, 99), None);
+
+        assert!(history.record_corridor(corridor(10, 15_000, 200)));
+        assert!(!history.record_corridor(corridor(10, 15_000, 250)));
+        assert!(history.record_corridor(corridor(42161, 5_000, 250)));
+        // A same-slot change replaces only that corridor's latest entry.
+        assert!(history.record_corridor(corridor(10, 20_000, 200)));
+        assert!(history.record_corridor(corridor(10, 12_500, 300)));
+        assert_eq!(history.corridors.len(), 3);
+
+        assert!(history.corridor_at(900, 10, 199).is_none());
+        assert_eq!(
+            history.corridor_at(900, 10, 299),
+            Some(&corridor(10, 20_000, 200))
+        );
+        assert_eq!(history.fee_basis_points_at(900, 10, 150), Some(20));
+        assert_eq!(history.fee_basis_points_at(900, 10, 250), Some(40));
+        assert_eq!(history.fee_basis_points_at(900, 10, 300), Some(25));
+        assert_eq!(history.fee_basis_points_at(900, 42161, 300), Some(10));
+
+        for i in 0..FEE_HISTORY_CAPACITY as u64 {
+            assert!(history.record_corridor(corridor(1_000 + i, 10_000, 400 + i)));
+        }
+        assert_eq!(history.corridors.len(), FEE_HISTORY_CAPACITY);
+        assert_eq!(history.corridors_evicted, 3);
+        // Once entries are evicted a missing corridor entry proves nothing.
+        assert_eq!(history.fee_basis_points_at(900, 10, 300), None);
+        assert_eq!(history.fee_basis_points_at(900, 1_005, 405), Some(20));
+    }
+
+    #[test]
+    fn test_upgrade_version_one_fee_history() {
+        use borsh::BorshSerialize;
+
+        let schedule = FeeSchedule {
+            fee_basis_points: 25,
+            relayer_fee: 1_000,
+            effective_slot: 100,
+        };
+        let legacy = LegacyFeeScheduleHistoryV1 {
+            entries: vec![schedule].into(),
+            evicted: 3,
+            bump: 250,
+        };
+        let mut v1_history = b"RQFEEHST".to_vec();
+        v1_history.push(1);
+        v1_history.extend_from_slice(&legacy.try_to_vec().unwrap());
+
+        assert_eq!(
+            FeeScheduleHistory::unpack(&v1_history).unwrap_err(),
+            CrossChainError::AccountMigrationRequired.into()
+        );
+        let history = upgrade_fee_history(&v1_history).unwrap().unwrap();
+        assert_eq!(history.at(100), Some(&schedule));
+        assert_eq!(history.evicted, 3);
+        assert!(history.corridors.is_empty());
+        assert_eq!(history.corridors_evicted, 0);
+        assert_eq!(history.bump, 250);
+        assert!(upgrade_fee_history(&history.pack().unwrap())
+            .unwrap()
+            .is_none());
+
+        let mut unknown = history.pack().unwrap();
+        unknown[8] = FeeScheduleHistory::VERSION + 1;
+        assert!(upgrade_fee_history(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_distribution_split_and_commitment() {
+        let shares: Vec<DistributionShare> = (1..=3u8)
+            .map(|i| DistributionShare {
+                recipient: Pubkey::new_from_array([i; 32]),
+                share: i as u32,
+            })
+            .collect();
+        assert!(validate_distribution(&shares).is_ok());
+
+        let payouts = split_distribution(1_000, &shares).unwrap();
+        assert_eq!(payouts, vec![167, 333, 500]);
+        assert_eq!(payouts.iter().sum::<u64>(), 1_000);
+
+        let mut reordered = shares.clone();
+        reordered.swap(0, 2);
+        assert_ne!(
+            distribution_commitment(&shares),
+            distribution_commitment(&reordered)
+        );
+
+        assert_eq!(
+            validate_distribution(&[]),
+            Err(CrossChainError::InvalidDistribution)
+        );
+        let zero = vec![DistributionShare {
+            recipient: Pubkey::new_from_array([1; 32]),
+            share: 0,
+        }];
+        assert_eq!(
+            validate_distribution(&zero),
+            Err(CrossChainError::InvalidDistribution)
+        );
+    }
+
+    #[test]
+    fn test_transfer_refund_timeout() {
+        let mut record = TransferRecord {
+            transfer: relay_transfer(),
+            escrowed_amount: 1_000_000,
+            created_slot: 100,
+            completed_slot: 0,
+            bump: 255,
+            wrapped: false,
+            relayer_fee: 0,
+            timeout_slots: 50,
+            gateway_codehash: [0u8; 32],
+            terms: TransferTerms::default(),
+        };
+
+        assert_eq!(
+            record.validate_refund(149),
+            Err(CrossChainError::RefundNotYetAvailable)
+        );
+        assert!(record.validate_refund(150).is_ok());
+        assert_eq!(record.refund_amount(), 997_500);
+
+        record.wrapped = true;
+        assert_eq!(record.release_amount(), 0);
+        assert_eq!(record.refund_amount(), 997_500);
+
+        record.transfer.status = TransferStatus::Completed;
+        assert_eq!(
+            record.validate_refund(1_000),
+            Err(CrossChainError::TransferNotInFlight)
+        );
+    }
+
+    #[test]
+    fn test_transfer_cancellation() {
+        let mut record = TransferRecord {
+            transfer: relay_transfer(),
+            escrowed_amount: 1_000_000,
+            created_slot: 100,
+            completed_slot: 0,
+            bump: 255,
+            wrapped: false,
+            relayer_fee: 1_000,
+            timeout_slots: 50,
+            gateway_codehash: [0u8; 32],
+            terms: TransferTerms::default(),
+        };
+
+        assert!(record.validate_cancel().is_ok());
+        record.charge_cancellation(BridgeConfig::default().cancellation_fee_bps);
+        assert_eq!(record.transfer.fee, 1_000);
+        assert_eq!(record.relayer_fee, 0);
+        assert_eq!(record.refund_amount(), 999_000);
+
+        // A fee above the escrow leaves nothing to refund rather than failing.
+        record.escrowed_amount = 10;
+        record.charge_cancellation(MAX_FEE_BASIS_POINTS);
+        assert_eq!(record.transfer.fee, 10);
+        assert_eq!(record.refund_amount(), 0);
+
+        for status in [
+            TransferStatus::Held,
+            TransferStatus::Confirmed,
+            TransferStatus::Completed,
+            TransferStatus::Cancelled,
+        ] {
+            record.transfer.status = status;
+            assert_eq!(
+                record.validate_cancel(),
+                Err(CrossChainError::TransferNotInFlight)
+            );
+        }
+        record.transfer.status = TransferStatus::Pending;
+        assert!(record.validate_cancel().is_ok());
+    }
+
+    #[test]
+    fn test_escrow_ledger_and_drift() {
+        let usdc = Some(Pubkey::new_from_array([9u8; 32]));
+        let mut ledger = EscrowLedger::default();
+
+        ledger.credit(usdc, 1_000).unwrap();
+        ledger.credit(usdc, 500).unwrap();
+        ledger.credit(None, 10).unwrap();
+        ledger.debit(usdc, 400);
+        assert_eq!(ledger.outstanding(usdc), 1_100);
+        assert_eq!(ledger.outstanding(None), 10);
+
+        ledger.debit(None, 50);
+        assert_eq!(ledger.outstanding(None), 0);
+        assert_eq!(
+            ledger.outstanding(Some(Pubkey::new_from_array([1u8; 32]))),
+            0
+        );
+
+        assert_eq!(escrow_drift(1_100, 1_100), 0);
+        assert_eq!(escrow_drift(1_100, 1_000), -100);
+        assert_eq!(escrow_drift(0, u64::MAX), u64::MAX as i128);
+    }
+
+    #[test]
+    fn test_escrow_discrepancy_len() {
+        let discrepancy = EscrowDiscrepancy {
+            mint: Pubkey::new_from_array([9u8; 32]),
+            expected: 1,
+            actual: 2,
+            drift: 1,
+            flagged: true,
+            last_reconciled_slot: 3,
+            bump: 255,
+        };
+        assert_eq!(
+            discrepancy.try_to_vec().unwrap().len(),
+            EscrowDiscrepancy::LEN
+        );
+    }
+
+    #[test]
+    fn test_caip2_chain_ids() {
+        let mainnet = Caip2ChainId::parse("eip155:1").unwrap();
+        assert_eq!(mainnet, Caip2ChainId::from_legacy(1));
+        assert_eq!(mainnet.legacy_id(), 1);
+        assert_eq!(mainnet.to_string(), "eip155:1");
+
+        let solana = Caip2ChainId::parse("solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp").unwrap();
+        assert_eq!(solana.namespace(), "solana");
+        assert!(solana.legacy_id() & DERIVED_LEGACY_ID_FLAG != 0);
+
+        let cosmos = Caip2ChainId::parse("cosmos:cosmoshub-4").unwrap();
+        assert_ne!(cosmos.legacy_id(), solana.legacy_id());
+        assert_eq!(cosmos.try_to_vec().unwrap().len(), Caip2ChainId::LEN);
+
+        for invalid in ["eip155", "EIP155:1", "ab:1", "eip155:", "eip155:a.b"] {
+            assert_eq!(
+                Caip2ChainId::parse(invalid),
+                Err(CrossChainError::InvalidChainId),
+                "{}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_multisig_config_validation() {
+        let owners: Vec<Pubkey> = (1..=3).map(|i| Pubkey::new_from_array([i; 32])).collect();
+        let config = |owners: Vec<Pubkey>, threshold| MultisigConfig {
+            owners: owners.into(),
+            threshold,
+            eidas_required: false,
+        };
+
+        assert!(validate_multisig_config(&config(owners.clone(), 2)).unwrap());
+        for invalid in [
+            config(owners.clone(), 0),
+            config(owners.clone(), 4),
+            config(vec![], 1),
+            config(vec![owners[0], owners[1], owners[0]], 2),
+            config(vec![owners[0]; MAX_MULTISIG_OWNERS + 1], 1),
+        ] {
+            assert_eq!(
+                validate_multisig_config(&invalid),
+                Err(CrossChainError::InvalidMultisigConfig)
+            );
+        }
+
+        let multisig = MultiSigWallet {
+            owners: vec![Pubkey::new_from_array([7; 32]); MAX_MULTISIG_OWNERS],
+            threshold: 2,
+            created_at: 0,
+            eidas_required: true,
+            transaction_count: u64::MAX,
+            bump: 255,
+            vault_bump: 254,
+        };
+        assert_eq!(multisig.try_to_vec().unwrap().len(), MultiSigWallet::LEN);
+    }
+
+    #[test]
+    fn test_multisig_execution_threshold() {
+        let program_id = Pubkey::new_unique();
+        let owners: Vec<Pubkey> = (1..=3).map(|i| Pubkey::new_from_array([i; 32])).collect();
+        let multisig = MultiSigWallet {
+            owners: owners.clone(),
+            threshold: 2,
+            created_at: 0,
+            eidas_required: false,
+            transaction_count: 1,
+            bump: 255,
+            vault_bump: 255,
+        };
+        let multisig_key = get_multisig_address(&owners[0], 0, &program_id).0;
+        let (vault, _) = get_multisig_vault_address(&multisig_key, &program_id);
+
+        let instruction = ProposedInstruction {
+            program_id: solana_program::system_program::id(),
+            accounts: vec![
+                ProposedAccountMeta {
+                    pubkey: vault,
+                    is_signer: true,
+                    is_writable: true,
+                },
+                ProposedAccountMeta {
+                    pubkey: owners[2],
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ]
+            .into(),
+            data: vec![2, 0, 0, 0].into(),
+        };
+        assert!(validate_proposal(&instruction, &vault).unwrap());
+
+        let mut foreign_signer = instruction.clone();
+        foreign_signer.accounts[1].is_signer = true;
+        assert_eq!(
+            validate_proposal(&foreign_signer, &vault),
+            Err(CrossChainError::InvalidMultisigConfig)
+        );
+
+        let mut transaction = MultisigTransaction {
+            multisig: multisig_key,
+            index: 0,
+            proposer: owners[0],
+            instruction,
+            approvals: vec![false; 3],
+            executed: false,
+            bump: 255,
+        };
+        assert_eq!(multisig.owner_index(&owners[1]), Some(1));
+        assert_eq!(multisig.owner_index(&Pubkey::new_unique()), None);
+
+        transaction.approvals[multisig.owner_index(&owners[0]).unwrap()] = true;
+        assert_eq!(
+            validate_execution(&multisig, &transaction),
+            Err(CrossChainError::ThresholdNotMet)
+        );
+
+        transaction.approvals[2] = true;
+        assert!(validate_execution(&multisig, &transaction).unwrap());
+        assert_eq!(transaction.instruction.to_instruction().accounts.len(), 2);
+
+        transaction.executed = true;
+        assert_eq!(
+            validate_execution(&multisig, &transaction),
+            Err(CrossChainError::TransactionAlreadyExecuted)
+        );
+    }
+
+    /// Self-signed Ed25519 certificate with QcCompliance, QcSSCD and QcType
+    /// esign statements and a critical nonRepudiation key usage.
+    const QUALIFIED_CERT_DER: &str = "3082018c3082013ea00302010202021234300506032b65703039310b300906035504061302444531143012060355040a0c0b52697669635120546573743114301206035504030c0b54657374205369676e6572301e170d3235303130313030303030305a170d3330303130313030303030305a3039310b300906035504061302444531143012060355040a0c0b52697669635120546573743114301206035504030c0b54657374205369676e6572302a300506032b6570032100a7ba240ec67982badbfbed7e794ec441d3388863223a4a07fc6fd2d42119d6c4a36a3068300e0603551d0f0101ff040403020640303706082b06010505070103042b30293008060604008e4601013008060604008e4601043013060604008e4601063009060704008e46010601301d0603551d0e04160414ede2aaf25681d25adcf72ac71b78898012c35ab7300506032b6570034100bdd89007bb002a3ed96a955c5cd0e2e5e3e1661c5ec669304d19b9ddeeae7057789379b72b14aae397c4a4f7bbee731f5d71448bb74d6f98c7eb533ae544400b";
+
+    #[test]
+    fn test_parse_qualified_certificate() {
+        let der = hex::decode(QUALIFIED_CERT_DER).unwrap();
+        let parsed = parse_certificate(&der).unwrap();
+
+        assert_eq!(parsed.serial_number, vec![0x12, 0x34]);
+        assert_eq!(parsed.subject.common_name, "Test Signer");
+        assert_eq!(parsed.subject.organization, "RivicQ Test");
+        assert_eq!(parsed.issuer.country, "DE");
+        assert_eq!(parsed.not_before, 1_735_689_600);
+        assert_eq!(parsed.not_after, 1_893_456_000);
+        assert_eq!(parsed.subject_public_key.len(), 32);
+        assert_eq!(
+            parsed.public_key_algorithm,
+            Some(SignatureAlgorithm::Ed25519)
+        );
+        assert_eq!(parsed.key_usage, Some(KEY_USAGE_NON_REPUDIATION));
+        assert!(parsed.qc_statements.qc_compliance);
+        assert!(parsed.qc_statements.qc_sscd);
+        assert_eq!(parsed.qc_statements.qc_types, vec![CertificateType::QES]);
+
+        let cert = parsed.to_qualified_certificate().unwrap();
+        assert_eq!(cert.subject, "Test Signer");
+        assert_eq!(cert.country, "DE");
+        assert_eq!(cert.certificate_type, CertificateType::QES);
+        assert!(cert.qscd);
+        assert!(validate_certificate(&cert, 1_800_000_000).unwrap());
+    }
+
+    #[test]
+    fn test_parse_certificate_rejects_unqualified_and_malformed() {
+        let der = hex::decode(QUALIFIED_CERT_DER).unwrap();
+        let parsed = parse_certificate(&der).unwrap();
+
+        let mut unqualified = parsed.clone();
+        unqualified.qc_statements.qc_compliance = false;
+        assert_eq!(
+            unqualified.to_qualified_certificate().unwrap_err(),
+            CrossChainError::CertificateNotQualified
+        );
+
+        let mut ca_only = parsed;
+        ca_only.key_usage = Some(KEY_USAGE_KEY_CERT_SIGN);
+        assert_eq!(
+            ca_only.to_qualified_certificate().unwrap_err(),
+            CrossChainError::InvalidKeyUsage
+        );
+
+        for malformed in [
+            der[..der.len() - 1].to_vec(),
+            [der.clone(), vec![0]].concat(),
+            vec![0x30, 0x84, 0, 0, 0, 1, 0],
+            vec![0u8; MAX_CERTIFICATE_LEN + 1],
+        ] {
+            assert_eq!(
+                parse_certificate(&malformed),
+                Err(CrossChainError::InvalidCertificate)
+            );
+        }
+    }
+
+    #[test]
+    fn test_trusted_issuer_registry() {
+        let der = hex::decode(QUALIFIED_CERT_DER).unwrap();
+        let issuer_hash = parse_certificate(&der).unwrap().issuer_hash;
+        let mut registry = TrustedIssuerRegistry::default();
+        assert!(!registry.is_trusted(&issuer_hash));
+
+        registry.add(issuer_hash, 100).unwrap();
+        registry.add(issuer_hash, 200).unwrap();
+        assert!(registry.is_trusted(&issuer_hash));
+        assert_eq!(registry.issuers.len(), 1);
+        assert_eq!(registry.issuers[0].added_at, 100);
+
+        registry.revoke(&issuer_hash).unwrap();
+        assert!(!registry.is_trusted(&issuer_hash));
+        assert_eq!(
+            registry.revoke(&issuer_hash),
+            Err(CrossChainError::UntrustedIssuer)
+        );
+
+        for i in 0..MAX_TRUSTED_ISSUERS {
+            registry.add([i as u8; 32], 0).unwrap();
+        }
+        assert_eq!(
+            registry.add(issuer_hash, 0),
+            Err(CrossChainError::TrustedIssuerRegistryFull)
+        );
+        assert_eq!(
+            registry.try_to_vec().unwrap().len(),
+            TrustedIssuerRegistry::LEN
+        );
+    }
+
+    #[test]
+    fn test_certificate_revocation_list() {
+        let der = hex::decode(QUALIFIED_CERT_DER).unwrap();
+        let parsed = parse_certificate(&der).unwrap();
+        let mut list = RevocationList {
+            issuer_hash: parsed.issuer_hash,
+            ..Default::default()
+        };
+
+        assert!(check_revocation(None, &parsed.serial_number).unwrap());
+        assert!(check_revocation(Some(&list), &parsed.serial_number).unwrap());
+
+        list.revoke(&parsed.serial_number, 10).unwrap();
+        list.revoke(&parsed.serial_number, 20).unwrap();
+        assert_eq!(list.revoked_serials.len(), 1);
+        assert_eq!(list.updated_at, 20);
+        assert_eq!(
+            check_revocation(Some(&list), &parsed.serial_number),
+            Err(CrossChainError::CertificateRevoked)
+        );
+        assert!(check_revocation(Some(&list), &[0x12, 0x35]).unwrap());
+
+        for i in 1..MAX_REVOKED_CERTIFICATES {
+            list.revoke(&(i as u64).to_be_bytes(), 0).unwrap();
+        }
+        assert_eq!(
+            list.revoke(&[0xff], 0),
+            Err(CrossChainError::RevocationListFull)
+        );
+        assert_eq!(list.try_to_vec().unwrap().len(), RevocationList::LEN);
+    }
+
+    #[test]
+    fn test_signing_domain_separation() {
+        let transfer = relay_transfer();
+        let domain = relay_domain();
+        let other_program = SigningDomain::new(Pubkey::new_unique(), domain.genesis_hash);
+        let other_cluster = SigningDomain::new(domain.program_id, [4u8; 32]);
+        let next_version = SigningDomain {
+            version: SIGNING_DOMAIN_VERSION + 1,
+            ..domain
+        };
+
+        let digest = relay_message_hash(&domain, &[0u8; 32], &transfer);
+        for other in [other_program, other_cluster, next_version] {
+            assert_ne!(relay_message_hash(&other, &[0u8; 32], &transfer), digest);
+        }
+
+        // A quorum signed for another cluster does not verify here
+        let relayers = vec![relayer(1, 1_000)];
+        let confirmations = vec![confirmation(vec![relayers[0].authority])];
+        let verified = verified_for(&transfer, &confirmations[0]);
+        assert_eq!(
+            verify_relay_confirmation(
+                &other_cluster,
+                &transfer,
+                &confirmations,
+                &relayers,
+                &verified,
+                1,
+                1_000
+            ),
+            Err(CrossChainError::InvalidRelaySignature)
+        );
+
+        let owner = Pubkey::new_from_array([1u8; 32]);
+        let intent = TransferIntentData {
+            amount: 10,
+            destination_chain: 1,
+            recipient: owner,
+            max_fee: 1,
+            deadline: 100,
+            nonce: 0,
+        };
+        assert_ne!(
+            intent_signing_hash(&domain, &owner, &intent),
+            intent_signing_hash(&other_program, &owner, &intent)
+        );
+
+        let meta = TransactionSignatureData {
+            transaction_hash: vec![1; 32].into(),
+            amount: 10,
+            recipient: owner,
+            source_chain: 0,
+            destination_chain: 1,
+            nonce: 0,
+        };
+        assert_ne!(
+            meta.signing_hash(&domain, &owner),
+            meta.signing_hash(&other_cluster, &owner)
+        );
+        assert_ne!(
+            meta.signing_hash(&domain, &owner),
+            intent_signing_hash(&domain, &owner, &intent)
+        );
+
+        let state = CrossChainState {
+            config: CrossChainConfig {
+                genesis_hash: [3u8; 32],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(state.signing_domain(&crate::id()), domain);
+    }
+
+    // RFC 3161 token over sha256("rivicq timestamp test"), genTime
+    // 2026-10-15T11:21:11Z, from a test TSA certificate (serial 0x77).
+    const TIMESTAMP_TOKEN_DER: &str = "308203b706092a864886f70d010702a08203a8308203a4020103310f300d060960864801650304020105003068060b2a864886f70d0109100104a0590457305502010106042a0304013031300d0609608648016503040201050004205da558b447be87f181d47055044c5a88b408c1199b337ae6bc575f15940b7c8b020102180f32303236313031353131323131315a3003020101a08201ca308201c63082016ca003020102020177300a06082a8648ce3d040302303a310b300906035504061302444531183016060355040a0c0f5269766963512054657374205453413111300f06035504030c085465737420545341301e170d3235303130313030303030305a170d3335303130313030303030305a303a310b300906035504061302444531183016060355040a0c0f5269766963512054657374205453413111300f06035504030c0854657374205453413059301306072a8648ce3d020106082a8648ce3d0301070342000438e3564ea8c1f9343f300aed6a80a402090e329b1e5113172e7891f92768450e6e91b3a4f07114206b616a66a94e9689c35c14c028ce1ff3db5bdeb95b65d471a3633061300e0603551d0f0101ff04040302078030160603551d250101ff040c300a06082b06010505070308301806082b06010505070103040c300a3008060604008e460101301d0603551d0e04160414d26cefa8dc6a9e7b58b0bcbc4f110f7fb194c7e9300a06082a8648ce3d0403020348003045022100973be674fb5f78fb9c2590f5786fe80a151c20c2c415027094e680412c39588202204f8bae74ab46193c1809a21708cbf9fe614f0ba4736538e93783c9e63659b27a3182015430820150020101303f303a310b300906035504061302444531183016060355040a0c0f5269766963512054657374205453413111300f06035504030c085465737420545341020177300d06096086480165030402010500a081a4301a06092a864886f70d010903310d060b2a864886f70d0109100104301c06092a864886f70d010905310f170d3236313031353131323131315a302f06092a864886f70d01090431220420bc58443e6e67271aa876523832653e0b2e19e1ecab23232e50dd5349077f7f9f3037060b2a864886f70d010910022f31283026302430220420954170c3b33c1e8b99926e702fa44dd6f3076bcd23c7be24e64bdf00b02dd667300a06082a8648ce3d04030204483046022100c6b698062d920d3c9cd59d782ebbe87c6a527100b5dbefca8958d127301efc72022100c3cc86ab4aed6c44f9511134dcd40a5146a1756edf678df43b74a19c805eae66";
+    const TIMESTAMP_GEN_TIME: i64 = 1_792_063_271;
+
+    #[test]
+    fn test_parse_and_validate_timestamp_token() {
+        let der = hex::decode(TIMESTAMP_TOKEN_DER).unwrap();
+        let token = parse_timestamp_token(&der).unwrap();
+        let data_hash = solana_program::hash::hash(b"rivicq timestamp test").to_bytes();
+
+        assert_eq!(token.gen_time, TIMESTAMP_GEN_TIME);
+        assert_eq!(token.serial_number, vec![0x02]);
+        assert_eq!(token.message_imprint, data_hash.to_vec());
+        assert_eq!(token.tsa_certificate.serial_number, vec![0x77]);
+        assert_eq!(token.tsa_certificate.subject.common_name, "Test TSA");
+        assert!(token.tsa_certificate.time_stamping);
+
+        assert!(validate_timestamp_token(&token, &data_hash, TIMESTAMP_GEN_TIME + 60).is_ok());
+        assert_eq!(
+            validate_timestamp_token(&token, &[0u8; 32], TIMESTAMP_GEN_TIME),
+            Err(CrossChainError::TimestampImprintMismatch)
+        );
+        assert_eq!(
+            validate_timestamp_token(
+                &token,
+                &data_hash,
+                TIMESTAMP_GEN_TIME + TIMESTAMP_TOLERANCE_SECS + 1
+            ),
+            Err(CrossChainError::TimestampOutOfTolerance)
+        );
+
+        let mut without_eku = token.clone();
+        without_eku.tsa_certificate.time_stamping = false;
+        assert_eq!(
+            validate_timestamp_token(&without_eku, &data_hash, TIMESTAMP_GEN_TIME),
+            Err(CrossChainError::InvalidKeyUsage)
+        );
+    }
+
+    #[test]
+    fn test_parse_timestamp_token_rejects_malformed() {
+        let der = hex::decode(TIMESTAMP_TOKEN_DER).unwrap();
+        assert_eq!(
+            parse_timestamp_token(&der[..der.len() - 1]),
+            Err(CrossChainError::InvalidTimestampToken)
+        );
+        assert_eq!(
+            parse_timestamp_token(&hex::decode(QUALIFIED_CERT_DER).unwrap()),
+            Err(CrossChainError::InvalidTimestampToken)
+        );
+        assert_eq!(
+            parse_timestamp_token(&[]),
+            Err(CrossChainError::InvalidTimestampToken)
+        );
+    }
+
+    #[test]
+    fn test_compliance_record_expiry_and_renewal() {
+        let now = 1_800_000_000;
+        let data = ComplianceData {
+            verified: true,
+            eidas_level: EidasLevel::Substantial,
+            verification_date: now,
+            expiry_date: now + 86_400,
+            jurisdiction: "DE".into(),
+            ..Default::default()
+        };
+        assert!(validate_compliance_data(&data, now).is_ok());
+        assert_eq!(
+            validate_compliance_data(&data, now + 86_400),
+            Err(CrossChainError::InvalidComplianceExpiry)
+        );
+
+        let mut record = ComplianceRecord {
+            owner: Pubkey::new_unique(),
+            data,
+            renewals: 0,
+            bump: 255,
+        };
+        assert!(check_compliance_record(true, Some(&record), now).is_ok());
+        assert_eq!(
+            check_compliance_record(true, Some(&record), now + 86_400),
+            Err(CrossChainError::ComplianceExpired)
+        );
+        assert_eq!(
+            check_compliance_record(true, None, now),
+            Err(CrossChainError::ComplianceExpired)
+        );
+        assert!(check_compliance_record(false, None, now).is_ok());
+
+        let later = now + 90_000;
+        assert_eq!(
+            record.renew(later, later),
+            Err(CrossChainError::InvalidComplianceExpiry)
+        );
+        record.renew(later + 86_400, later).unwrap();
+        assert_eq!(record.renewals, 1);
+        assert_eq!(record.data.verification_date, later);
+        assert!(check_compliance_record(true, Some(&record), later).is_ok());
+
+        record.data.verified = false;
+        assert_eq!(
+            record.renew(later + 2 * 86_400, later),
+            Err(CrossChainError::ComplianceRequired)
+        );
+    }
+
+    #[test]
+    fn test_sponsor_quota() {
+        let mut sponsor = Sponsor {
+            sponsor: Pubkey::new_from_array([5u8; 32]),
+            enabled: true,
+            max_sponsored: 2,
+            sponsored: 0,
+            bump: 255,
+        };
+        assert_eq!(sponsor.charge(), Ok(true));
+        assert_eq!(sponsor.charge(), Ok(true));
+        assert_eq!(
+            sponsor.charge(),
+            Err(CrossChainError::SponsorQuotaExhausted)
+        );
+        assert_eq!(sponsor.sponsored, 2);
+
+        sponsor.max_sponsored = 0;
+        assert_eq!(sponsor.charge(), Ok(true));
+        sponsor.enabled = false;
+        assert_eq!(sponsor.charge(), Err(CrossChainError::SponsorNotApproved));
+        assert_eq!(sponsor.try_to_vec().unwrap().len(), Sponsor::LEN);
+    }
+
+    #[test]
+    fn test_audit_log_buffer_wraps_with_monotonic_sequence() {
+        let mut log = AuditLogBuffer::new(255);
+        let actor = Pubkey::new_unique();
+        let clock = solana_program::clock::Clock {
+            slot: 42,
+            unix_timestamp: 1_800_000_000,
+            ..Default::default()
+        };
+
+        for amount in 0..(AUDIT_LOG_CAPACITY as u64 + 3) {
+            let entry = AuditEntry::new(
+                AuditAction::TransferInitiated,
+                actor,
+                Pubkey::new_unique(),
+                amount,
+                &clock,
+            );
+            assert_eq!(log.append(entry), amount);
+        }
+
+        assert_eq!(log.entries.len(), AUDIT_LOG_CAPACITY);
+        assert_eq!(log.next_sequence, AUDIT_LOG_CAPACITY as u64 + 3);
+
+        let ordered = log.entries_in_order();
+        assert_eq!(ordered.first().unwrap().sequence, 3);
+        assert_eq!(
+            ordered.last().unwrap().sequence,
+            AUDIT_LOG_CAPACITY as u64 + 2
+        );
+        assert!(ordered
+            .windows(2)
+            .all(|w| w[1].sequence == w[0].sequence + 1));
+        assert_eq!(ordered[0].slot, 42);
+
+        let entry = AuditEntry::new(AuditAction::WalletRegistered, actor, actor, 0, &clock);
+        assert_eq!(entry.try_to_vec().unwrap().len(), AuditEntry::LEN);
+        assert!(log.try_to_vec().unwrap().len() <= AuditLogBuffer::LEN);
+    }
+
+    #[test]
+    fn test_relayer_lease_election() {
+        let leader = Pubkey::new_unique();
+        let standby = Pubkey::new_unique();
+        let mut lease = RelayerLease::default();
+
+        lease.acquire(leader, 100, 50).unwrap();
+        assert_eq!(lease.term, 1);
+        assert!(lease.is_leader(&leader, 149));
+        assert_eq!(
+            lease.acquire(standby, 120, 50),
+            Err(CrossChainError::RelayerLeaseHeld)
+        );
+        assert_eq!(
+            lease.acquire(leader, 120, MAX_RELAYER_LEASE_SLOTS + 1),
+            Err(CrossChainError::InvalidRelayerLease)
+        );
+
+        // renewal by the holder keeps the term
+        lease.acquire(leader, 140, 50).unwrap();
+        assert_eq!((lease.term, lease.expires_slot), (1, 190));
+
+        lease.acquire(standby, 190, 50).unwrap();
+        assert_eq!(lease.term, 2);
+        assert!(!lease.is_leader(&leader, 191));
+        assert_eq!(
+            lease.release(&leader, 191),
+            Err(CrossChainError::UnauthorizedRelayer)
+        );
+        lease.release(&standby, 191).unwrap();
+        lease.acquire(leader, 191, 50).unwrap();
+        assert_eq!(lease.term, 3);
+
+        let program_id = crate::id();
+        let rpc = MockRpc {
+            simulation: Default::default(),
+            sent: std::cell::Cell::new(0),
+            accounts: vec![(
+                get_relayer_lease_address(&program_id).0,
+                lease.try_to_vec().unwrap(),
+            )],
+        };
+        assert!(crate::client::is_relay_leader(&rpc, &program_id, &leader, 200).unwrap());
+        assert!(!crate::client::is_relay_leader(&rpc, &program_id, &standby, 200).unwrap());
+        assert!(!crate::client::is_relay_leader(&rpc, &program_id, &leader, 241).unwrap());
+    }
+
+    #[test]
+    fn test_qualified_signature_ed25519_verification() {
+        use solana_sdk::signer::{keypair::Keypair, Signer};
+
+        let keypair = Keypair::new();
+        let public_key = keypair.pubkey().to_bytes();
+        let data = b"contract v1";
+        let signature = keypair.sign_message(data).as_ref().to_vec();
+
+        let qualified = QualifiedSignature {
+            signature: signature.clone(),
+            certificate: QualifiedCertificate {
+                subject: "Test Signer".to_string(),
+                issuer: "Test Authority".to_string(),
+                serial_number: vec![1],
+                not_before: 1_700_000_000,
+                not_after: 1_900_000_000,
+                public_key_hash: solana_program::hash::hash(&public_key).to_bytes().to_vec(),
+                certificate_type: CertificateType::QES,
+                country: "DE".to_string(),
+                qscd: true,
+            },
+            signed_data: data.to_vec(),
+            timestamp: 1_800_000_000,
+            signature_algorithm: "Ed25519".to_string(),
+            signer_role: String::new(),
+        };
+        let now = 1_800_000_000;
+        assert!(verify_qualified_signature(&qualified, &public_key, data, now).unwrap());
+        assert_eq!(
+            verify_qualified_signature(&qualified, &public_key, b"contract v2", now),
+            Err(CrossChainError::InvalidQualifiedSignature)
+        );
+        assert_eq!(
+            verify_qualified_signature(&qualified, &[9u8; 32], data, now),
+            Err(CrossChainError::InvalidQualifiedSignature)
+        );
+
+        let rsa = QualifiedSignature {
+            signature_algorithm: "RSA-PSS".to_string(),
+            ..qualified
+        };
+        assert_eq!(
+            verify_qualified_signature(&rsa, &public_key, data, now),
+            Err(CrossChainError::UnsupportedSignatureAlgorithm)
+        );
+
+        let verified = vec![VerifiedSignature {
+            signer: keypair.pubkey(),
+            signature: signature.clone().try_into().unwrap(),
+            message: data.to_vec(),
+        }];
+        assert!(verify_with_precompiles(
+            SignatureAlgorithm::Ed25519,
+            &public_key,
+            &signature,
+            data,
+            &verified,
+            &[],
+        )
+        .unwrap());
+        assert_eq!(
+            verify_with_precompiles(
+                SignatureAlgorithm::Ed25519,
+                &public_key,
+                &signature,
+                b"contract v2",
+                &verified,
+                &[],
+            ),
+            Err(CrossChainError::InvalidQualifiedSignature)
+        );
+    }
+
+    #[test]
+    fn test_qualified_signature_secp256k1_verification() {
+        let secret = libsecp256k1::SecretKey::parse(&[7u8; 32]).unwrap();
+        let public_key = libsecp256k1::PublicKey::from_secret_key(&secret).serialize();
+        let data = b"contract v1";
+        let digest = solana_program::keccak::hash(data).to_bytes();
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&digest), &secret);
+        let mut signature = signature.serialize().to_vec();
+        signature.push(recovery_id.serialize());
+
+        assert!(
+            verify_signature(SignatureAlgorithm::Secp256k1, &public_key, &signature, data).unwrap()
+        );
+        assert_eq!(
+            verify_signature(
+                SignatureAlgorithm::Secp256k1,
+                &public_key,
+                &signature,
+                b"v2"
+            ),
+            Err(CrossChainError::InvalidQualifiedSignature)
+        );
+
+        // secp256k1 program instruction at index 1: address, signature, message
+        let address = eth_address(&public_key).unwrap();
+        let address_offset: u16 = 12;
+        let signature_offset: u16 = address_offset + 20;
+        let message_offset: u16 = signature_offset + 65;
+        let mut ix_data = vec![1u8];
+        ix_data.extend_from_slice(&signature_offset.to_le_bytes());
+        ix_data.push(1);
+        ix_data.extend_from_slice(&address_offset.to_le_bytes());
+        ix_data.push(1);
+        ix_data.extend_from_slice(&message_offset.to_le_bytes());
+        ix_data.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        ix_data.push(1);
+        ix_data.extend_from_slice(&address);
+        ix_data.extend_from_slice(&signature);
+        ix_data.extend_from_slice(data);
+
+        let verified = parse_secp256k1_instruction(&ix_data, 1).unwrap();
+        assert_eq!(verified[0].eth_address, address);
+        assert!(verify_with_precompiles(
+            SignatureAlgorithm::Secp256k1,
+            &public_key,
+            &signature,
+            data,
+            &[],
+            &verified,
+        )
+        .unwrap());
+
+        assert_eq!(
+            parse_secp256k1_instruction(&ix_data, 0),
+            Err(CrossChainError::InvalidQualifiedSignature)
+        );
+        assert_eq!(
+            SignatureAlgorithm::from_name("ES256K"),
+            Ok(SignatureAlgorithm::Secp256k1)
+        );
+    }
+
+    #[test]
+    fn test_priority_fee_estimation_and_budget() {
+        use crate::client::*;
+
+        let policy = PriorityFeePolicy {
+            percentile: 50,
+            min_unit_price: 100,
+            max_unit_price: 50_000,
+            compute_unit_margin_bps: 2_000,
+        };
+        assert_eq!(estimate_unit_price(&[], &policy), 100);
+        assert_eq!(
+            estimate_unit_price(&[900, 10, 500, 70_000, 300], &policy),
+            500
+        );
+        assert_eq!(
+            estimate_unit_price(
+                &[70_000],
+                &PriorityFeePolicy {
+                    percentile: 100,
+                    ..policy.clone()
+                }
+            ),
+            50_000
+        );
+
+        assert_eq!(compute_unit_limit(100_000, 2_000), 120_000);
+        assert_eq!(compute_unit_limit(2_000_000, 0), MAX_COMPUTE_UNIT_LIMIT);
+        assert_eq!(priority_fee_lamports(120_000, 500), 60);
+        assert_eq!(priority_fee_lamports(1, 1), 1);
+
+        let mut budget = FeeBudget {
+            chains: vec![ChainFeeCap {
+                chain_id: 1,
+                cap_lamports: 100_000,
+                spent_lamports: 0,
+            }],
+            alert_threshold_bps: 8_000,
+        };
+        assert_eq!(
+            budget.record(1, 50_000).unwrap(),
+            FeeBudgetStatus::WithinBudget
+        );
+        assert_eq!(
+            budget.record(1, 30_000).unwrap(),
+            FeeBudgetStatus::Alert {
+                chain_id: 1,
+                spent: 80_000,
+                cap: 100_000
+            }
+        );
+        assert!(matches!(
+            budget.record(1, 30_000),
+            Err(ClientError::FeeBudgetExceeded {
+                chain_id: 1,
+                cap: 100_000
+            })
+        ));
+        assert!(matches!(
+            budget.check(10, 1),
+            Err(ClientError::FeeBudgetExceeded {
+                chain_id: 10,
+                cap: 0
+            })
+        ));
+        budget.reset();
+
+        let rpc = MockRpc {
+            simulation: Default::default(),
+            sent: std::cell::Cell::new(0),
+            accounts: vec![],
+        };
+        let ix = solana_sdk::instruction::Instruction::new_with_bytes(
+            crate::id(),
+            &[],
+            vec![solana_sdk::instruction::AccountMeta::new(
+                Pubkey::new_unique(),
+                false,
+            )],
+        );
+        let priced = price_instructions(&rpc, &[ix], 100_000, 2, &policy, &budget, 1).unwrap();
+        assert_eq!(priced.instructions.len(), 3);
+        assert_eq!(
+            priced.instructions[0].program_id,
+            solana_sdk::compute_budget::id()
+        );
+        assert_eq!((priced.unit_limit, priced.unit_price), (120_000, 100));
+        assert_eq!(priced.max_fee_lamports, 2 * LAMPORTS_PER_SIGNATURE + 12);
+    }
+
+    #[test]
+    fn test_keystore_round_trip_and_rotation() {
+        use crate::keystore::*;
+        use solana_sdk::signer::{keypair::Keypair, Signer};
+
+        let keypair = Keypair::new();
+        assert_eq!(
+            encrypt_keypair(&keypair, "correct horse", 1_000).unwrap_err(),
+            KeystoreError::WeakKdf(1_000)
+        );
+        let keystore = encrypt_keypair(&keypair, "correct horse", MIN_KEYSTORE_ITERATIONS).unwrap();
+        let keystore = EncryptedKeystore::from_json(&keystore.to_json()).unwrap();
+        assert_eq!(keystore.pubkey, keypair.pubkey().to_string());
+        assert!(!keystore
+            .ciphertext
+            .contains(&hex::encode(&keypair.to_bytes()[..32])));
+
+        let decrypted = decrypt_keypair(&keystore, "correct horse").unwrap();
+        assert_eq!(decrypted.to_bytes(), keypair.to_bytes());
+        assert_eq!(
+            decrypt_keypair(&keystore, "wrong horse").unwrap_err(),
+            KeystoreError::Authentication
+        );
+
+        let mut tampered = keystore.clone();
+        tampered.iterations = 1;
+        assert_eq!(
+            decrypt_keypair(&tampered, "correct horse").unwrap_err(),
+            KeystoreError::WeakKdf(1)
+        );
+        tampered.iterations = MIN_KEYSTORE_ITERATIONS + 1;
+        assert_eq!(
+            decrypt_keypair(&tampered, "correct horse").unwrap_err(),
+            KeystoreError::Authentication
+        );
+        tampered.iterations = keystore.iterations;
+        tampered.pubkey = Pubkey::new_unique().to_string();
+        assert_eq!(
+            decrypt_keypair(&tampered, "correct horse").unwrap_err(),
+            KeystoreError::Authentication
+        );
+        tampered.kdf = "scrypt".to_string();
+        assert!(matches!(
+            decrypt_keypair(&tampered, "correct horse"),
+            Err(KeystoreError::UnsupportedKdf(_))
+        ));
+
+        let program_id = crate::id();
+        let state = Pubkey::new_unique();
+        let rotation = rotate_relayer_keystore(
+            &program_id,
+            &state,
+            &keystore,
+            "correct horse",
+            MIN_KEYSTORE_ITERATIONS,
+        )
+        .unwrap();
+        assert_eq!(rotation.old_authority.pubkey(), keypair.pubkey());
+        assert_ne!(rotation.new_authority.pubkey(), keypair.pubkey());
+        assert_eq!(
+            decrypt_keypair(&rotation.keystore, "correct horse")
+                .unwrap()
+                .pubkey(),
+            rotation.new_authority.pubkey()
+        );
+
+        let accounts = &rotation.instruction.accounts;
+        assert_eq!(accounts.len(), 6);
+        assert_eq!(
+            accounts[1].pubkey,
+            get_relayer_address(&keypair.pubkey(), &program_id).0
+        );
+        assert_eq!(
+            accounts[3].pubkey,
+            get_relayer_address(&rotation.new_authority.pubkey(), &program_id).0
+        );
+        assert!(accounts[2].is_signer && accounts[4].is_signer);
+        assert!(matches!(
+            CrossChainInstruction::try_from_slice(&rotation.instruction.data).unwrap(),
+            CrossChainInstruction::RotateRelayerKey
+        ));
+    }
+
+    #[test]
+    fn test_qualified_seal_certificate_and_organization_identifier() {
+        use solana_sdk::signer::{keypair::Keypair, Signer};
+
+        assert!(is_valid_lei("5493001KJTIIGC8Y1R12"));
+        assert!(!is_valid_lei("5493001KJTIIGC8Y1R13"));
+        assert_eq!(
+            parse_organization_identifier("LEIXG-5493001KJTIIGC8Y1R12").unwrap(),
+            OrganizationIdentifier {
+                scheme: OrganizationIdScheme::Lei,
+                country: "XG".to_string(),
+                reference: "5493001KJTIIGC8Y1R12".to_string(),
+            }
+        );
+        assert_eq!(
+            parse_organization_identifier("VATDE-123456789")
+                .unwrap()
+                .scheme,
+            OrganizationIdScheme::Vat
+        );
+        for invalid in [
+            "",
+            "VATDE123456789",
+            "VATde-123456789",
+            "GOVDE-123",
+            "VATDE-",
+            "NTRBE-12 34",
+            "LEIXG-5493001KJTIIGC8Y1R13",
+        ] {
+            assert_eq!(
+                parse_organization_identifier(invalid),
+                Err(CrossChainError::InvalidOrganizationIdentifier)
+            );
+        }
+
+        let der = hex::decode(QUALIFIED_CERT_DER).unwrap();
+        let mut parsed = parse_certificate(&der).unwrap();
+        assert_eq!(parsed.subject.organization_identifier, "");
+        assert_eq!(
+            parsed.to_seal_certificate().unwrap_err(),
+            CrossChainError::CertificateNotForSeal
+        );
+
+        parsed.qc_statements.qc_types = vec![CertificateType::QESe];
+        assert_eq!(
+            parsed.to_seal_certificate().unwrap_err(),
+            CrossChainError::InvalidOrganizationIdentifier
+        );
+        parsed.subject.organization_identifier = "VATDE-123456789".to_string();
+        let (certificate, identifier) = parsed.to_seal_certificate().unwrap();
+        assert_eq!(certificate.certificate_type, CertificateType::QESe);
+        assert_eq!(identifier.reference, "123456789");
+
+        let keypair = Keypair::new();
+        let public_key = keypair.pubkey().to_bytes();
+        let data = b"invoice 2026-0042";
+        let seal = QualifiedSeal {
+            owner: Pubkey::new_unique(),
+            seal: keypair.sign_message(data).as_ref().to_vec(),
+            certificate: QualifiedCertificate {
+                public_key_hash: solana_program::hash::hash(&public_key).to_bytes().to_vec(),
+                ..certificate
+            },
+            organization: parsed.subject.organization.clone(),
+            organization_identifier: identifier,
+            sealed_data_hash: solana_program::hash::hash(data).to_bytes(),
+            timestamp: 1_800_000_000,
+            signature_algorithm: "Ed25519".to_string(),
+        };
+        let now = 1_800_000_000;
+        assert!(verify_qualified_seal(&seal, &public_key, data, now).unwrap());
+        assert_eq!(
+            verify_qualified_seal(&seal, &public_key, b"invoice 2026-0043", now),
+            Err(CrossChainError::InvalidQualifiedSignature)
+        );
+
+        let mut personal = seal;
+        personal.certificate.certificate_type = CertificateType::QES;
+        assert_eq!(
+            verify_qualified_seal(&personal, &public_key, data, now),
+            Err(CrossChainError::CertificateNotForSeal)
+        );
+    }
+
+    #[test]
+    fn test_service_config_loading_and_overrides() {
+        use crate::config::*;
+
+        let example = include_str!("../config/services.example.toml");
+        let mut config = ServiceConfig::from_toml_str(example).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.program_id, crate::id());
+        assert_eq!(config.chains.len(), 2);
+        assert_eq!(config.relayer.as_ref().unwrap().lease_duration_slots, 300);
+        assert_eq!(config.priority_fee_policy(), Default::default());
+
+        let vars = [
+            ("RIVICQ_RPC_URL", "http://127.0.0.1:8899"),
+            ("RIVICQ_CHAIN_137_ENABLED", "false"),
+            ("RIVICQ_CHAIN_1_FEE_CAP", "42"),
+            ("RIVICQ_RELAYER_KEYSTORE_PASSPHRASE", "ignored"),
+            ("HOME", "/root"),
+        ];
+        config
+            .apply_overrides(vars.iter().map(|(k, v)| (k.to_string(), v.to_string())))
+            .unwrap();
+        assert_eq!(config.cluster.rpc_url, "http://127.0.0.1:8899");
+        let budget = config.fee_budget();
+        assert_eq!(budget.chains.len(), 1);
+        assert_eq!(
+            (budget.chains[0].chain_id, budget.chains[0].cap_lamports),
+            (1, 42)
+        );
+        config.validate().unwrap();
+
+        assert!(matches!(
+            config.apply_overrides([("RIVICQ_CHAIN_10_RPC_URL".to_string(), String::new())]),
+            Err(ConfigError::InvalidOverride { .. })
+        ));
+        assert!(matches!(
+            config.apply_overrides([("RIVICQ_PROGRAM_ID".to_string(), "nope".to_string())]),
+            Err(ConfigError::InvalidOverride { .. })
+        ));
+
+        let mut invalid = config.clone();
+        invalid.cluster.rpc_url = "127.0.0.1:8899".to_string();
+        assert!(matches!(
+            invalid.validate(),
+            Err(ConfigError::Invalid { .. })
+        ));
+        let mut invalid = config.clone();
+        invalid.chains[1].chain_id = 1;
+        assert!(matches!(
+            invalid.validate(),
+            Err(ConfigError::Invalid { .. })
+        ));
+        let mut invalid = config.clone();
+        invalid.fees.min_unit_price = invalid.fees.max_unit_price + 1;
+        assert!(matches!(
+            invalid.validate(),
+            Err(ConfigError::Invalid { .. })
+        ));
+        let mut invalid = config.clone();
+        invalid.chains.iter_mut().for_each(|c| c.enabled = false);
+        assert!(matches!(
+            invalid.validate(),
+            Err(ConfigError::Invalid { .. })
+        ));
+
+        let json = r#"{
+            "program_id": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            "cluster": { "rpc_url": "https://api.devnet.solana.com" }
+        }"#;
+        let minimal = ServiceConfig::from_json_str(json).unwrap();
+        assert_eq!(minimal.cluster.commitment, "confirmed");
+        minimal.validate().unwrap();
+        assert!(matches!(
+            ServiceConfig::from_toml_str(
+                "program_id = \"x\"\n[cluster]\nrpc_url = \"https://a\"\n"
+            ),
+            Err(ConfigError::Parse(_))
+        ));
+        assert!(matches!(
+            load_config(std::path::Path::new("/nonexistent/services.toml")),
+            Err(ConfigError::Io { .. })
+        ));
+    }
+
+    #[test]
+    fn test_cross_chain_config_builder() {
+        use crate::config::*;
+
+        let authority = Pubkey::new_unique();
+        let base = CrossChainConfigBuilder::new()
+            .admin(authority)
+            .bridge_authority(authority)
+            .eidas_authority(authority)
+            .guardian(authority)
+            .supported_chains([1, 137])
+            .amount_limits(10_000, 1_000_000)
+            .fee_basis_points(30);
+        let config = base.clone().build().unwrap();
+        assert_eq!(config.supported_chains, vec![1, 137]);
+        assert_eq!(config.fee_basis_points, 30);
+        assert!(config.validate().unwrap());
+
+        let field = |builder: CrossChainConfigBuilder| match builder.build() {
+            Err(ConfigError::Invalid { field, .. }) => field,
+            other => panic!("expected an invalid field, got {other:?}"),
+        };
+        assert_eq!(
+            field(base.clone().fee_basis_points(MAX_FEE_BASIS_POINTS + 1)),
+            "fee_basis_points"
+        );
+        assert_eq!(
+            field(base.clone().amount_limits(0, 10)),
+            "min_cross_chain_amount"
+        );
+        assert_eq!(
+            field(base.clone().amount_limits(10, 10)),
+            "min_cross_chain_amount"
+        );
+        assert_eq!(
+            field(base.clone().amount_limits(10, 1_000)),
+            "min_cross_chain_amount"
+        );
+        assert_eq!(field(base.clone().supported_chains([])), "supported_chains");
+        assert_eq!(
+            field(base.clone().supported_chains([1, 0])),
+            "supported_chains"
+        );
+        assert_eq!(
+            field(base.clone().supported_chains([1, 1])),
+            "supported_chains"
+        );
+        assert_eq!(
+            field(
+                base.clone()
+                    .supported_chains(1..=MAX_SUPPORTED_CHAINS as u64 + 1)
+            ),
+            "supported_chains"
+        );
+        assert_eq!(field(base.clone().guardian(Pubkey::default())), "guardian");
+        assert_eq!(
+            field(CrossChainConfigBuilder::from_config(config).admin(Pubkey::default())),
+            "admin"
+        );
+    }
+
+    #[test]
+    fn test_travel_rule_payload() {
+        let payload = Ivms101Payload {
+            originator_name_hash: solana_program::hash::hash(b"Erika Mustermann").to_bytes(),
+            beneficiary_vasp: "5493001KJTIIGC8Y1R12".into(),
+            encrypted_pii: vec![7u8; MAX_IVMS101_PII_LEN].into(),
+        };
+        assert!(payload.validate().unwrap());
+        assert_eq!(payload.encoded_len(), Ivms101Payload::MAX_LEN);
+        assert_eq!(payload.try_to_vec().unwrap().len(), payload.encoded_len());
+
+        for invalid in [
+            Ivms101Payload {
+                originator_name_hash: [0u8; 32],
+                ..payload.clone()
+            },
+            Ivms101Payload {
+                beneficiary_vasp: "VASP-1".into(),
+                ..payload.clone()
+            },
+            Ivms101Payload {
+                encrypted_pii: vec![].into(),
+                ..payload.clone()
+            },
+            Ivms101Payload {
+                encrypted_pii: vec![7u8; MAX_IVMS101_PII_LEN + 1].into(),
+                ..payload.clone()
+            },
+        ] {
+            assert_eq!(
+                invalid.validate(),
+                Err(CrossChainError::InvalidTravelRulePayload)
+            );
+        }
+
+        let matrix = ComplianceMatrix {
+            travel_rule_threshold: Some(1_000_000),
+            ..Default::default()
+        };
+        assert!(matrix.check_travel_rule(999_999, None).unwrap());
+        assert_eq!(
+            matrix.check_travel_rule(1_000_000, None),
+            Err(CrossChainError::TravelRuleRequired)
+        );
+        assert!(matrix.check_travel_rule(1_000_000, Some(&payload)).unwrap());
+        assert!(ComplianceMatrix::default()
+            .check_travel_rule(u64::MAX, None)
+            .unwrap());
+
+        let mut record = TransferRecord {
+            transfer: CrossChainTransferData {
+                token_address: Some(Pubkey::new_from_array([3u8; 32])),
+                travel_rule: Some(payload.clone()),
+                ..relay_transfer()
+            },
+            escrowed_amount: 1_000_000,
+            created_slot: 1,
+            completed_slot: 0,
+            bump: 255,
+            wrapped: false,
+            relayer_fee: 0,
+            timeout_slots: 0,
+            gateway_codehash: [4u8; 32],
+            terms: TransferTerms::default(),
+        };
+        assert_eq!(record.try_to_vec().unwrap().len(), record.space());
+
+        let message = record.bridge_message("transfer-1".to_string());
+        assert_eq!(message.message_type, MessageType::TokenTransfer);
+        assert!(validate_bridge_message(&message).is_ok());
+        assert_eq!(
+            Ivms101Payload::try_from_slice(&message.payload).unwrap(),
+            payload
+        );
+
+        record.transfer.travel_rule = None;
+        assert_eq!(record.space(), TransferRecord::LEN);
+        assert!(record
+            .bridge_message("transfer-2".to_string())
+            .payload
+            .is_empty());
+    }
+
+    #[test]
+    fn test_arcium_queue_computation_and_callback() {
+        let arcium_program = Pubkey::new_unique();
+        let requester = Pubkey::new_unique();
+        let data = ArciumTransactionData {
+            encrypted_payload: vec![1, 2, 3].into(),
+            ciphertext: vec![4u8; 64].into(),
+            proof: vec![5u8; 32].into(),
+            public_inputs: vec![6u8; 8].into(),
+            arcium_program_id: arcium_program,
+            encryption_public_key: vec![7u8; 32].into(),
+            computation_offset: 42,
+        };
+        let (computation, bump) = get_arcium_computation_address(&requester, 42, &crate::id());
+        let mempool = solana_program::instruction::AccountMeta::new(Pubkey::new_unique(), false);
+
+        let ix = queue_computation_instruction(
+            &arcium_program,
+            &requester,
+            &computation,
+            &crate::id(),
+            &data,
+            vec![mempool.clone()],
+        )
+        .unwrap();
+        assert_eq!(ix.program_id, arcium_program);
+        assert_eq!(ix.accounts.len(), 4);
+        assert!(ix.accounts[0].is_signer && ix.accounts[0].pubkey == requester);
+        assert_eq!(ix.accounts[3], mempool);
+        match ArciumInstruction::try_from_slice(&ix.data).unwrap() {
+            ArciumInstruction::QueueComputation {
+                computation_offset,
+                ciphertext,
+                public_inputs,
+                callback_program,
+                callback_account,
+                ..
+            } => {
+                assert_eq!(computation_offset, 42);
+                assert_eq!(ciphertext, data.ciphertext);
+                assert_eq!(public_inputs, data.public_inputs);
+                assert_eq!(callback_program, crate::id());
+                assert_eq!(callback_account, computation);
+            }
+        }
+
+        let mut record = ArciumComputation::new(requester, &data, arcium_program, 10, bump);
+        assert_eq!(record.status, ComputationStatus::Queued);
+        assert_eq!(
+            record.resolve(true, vec![0u8; MAX_COMPUTATION_RESULT_LEN + 1], 11),
+            Err(CrossChainError::EncryptedStateTooLarge)
+        );
+        assert!(record
+            .resolve(true, vec![9u8; MAX_COMPUTATION_RESULT_LEN], 11)
+            .unwrap());
+        assert_eq!(record.status, ComputationStatus::Completed);
+        assert_eq!(record.completed_slot, 11);
+        assert_eq!(record.try_to_vec().unwrap().len(), ArciumComputation::LEN);
+        assert_eq!(
+            record.resolve(false, vec![], 12),
+            Err(CrossChainError::ComputationNotPending)
+        );
+
+        let (authority, _) = get_arcium_callback_authority(&arcium_program);
+        assert!(!authority.is_on_curve());
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_mock_evm_node_json_rpc() {
+        use crate::devnet::*;
+        use serde_json::json;
+
+        let node = MockEvmNode::start(MOCK_EVM_CHAIN_ID).unwrap();
+        let url = node.url();
+
+        assert_eq!(json_rpc(&url, "eth_chainId", json!([])).unwrap(), "0x7a69");
+        assert_eq!(json_rpc(&url, "eth_blockNumber", json!([])).unwrap(), "0x0");
+
+        let hash = json_rpc(&url, "eth_sendRawTransaction", json!(["0xdeadbeef"])).unwrap();
+        let receipt = json_rpc(&url, "eth_getTransactionReceipt", json!([hash])).unwrap();
+        assert_eq!(receipt["status"], "0x1");
+        assert_eq!(receipt["blockNumber"], "0x1");
+        assert_eq!(receipt["logs"][0]["data"], "0xdeadbeef");
+
+        let deposit = node.emit(vec![7u8; 4]);
+        assert_eq!(deposit.block_number, 2);
+        assert_eq!(node.transaction(&deposit.hash), Some(deposit));
+
+        let logs = json_rpc(
+            &url,
+            "eth_getLogs",
+            json!([{ "fromBlock": "0x2", "toBlock": "latest" }]),
+        )
+        .unwrap();
+        assert_eq!(logs.as_array().unwrap().len(), 1);
+        assert_eq!(logs[0]["data"], "0x07070707");
+
+        let unknown = json_rpc(
+            &url,
+            "eth_getTransactionReceipt",
+            json!([format!("0x{}", "00".repeat(32))]),
+        );
+        assert!(unknown.unwrap().is_null());
+        assert!(matches!(
+            json_rpc(&url, "eth_call", json!([])),
+            Err(DevnetError::Rpc(_))
+        ));
+        assert!(matches!(
+            http_post_json("https://localhost", &json!({})),
+            Err(DevnetError::Http(_))
+        ));
+    }
+
+    /// Compares `bytes` against `fixtures/layouts/<name>.hex`. Set
+    /// `UPDATE_LAYOUT_SNAPSHOTS=1` to rewrite the fixture after an
+    /// intentional layout change (which also needs an account migration).
+    fn assert_layout_snapshot(name: &str, bytes: &[u8]) {
+        let path = format!("{}/fixtures/layouts/{name}.hex", env!("CARGO_MANIFEST_DIR"));
+        let actual = hex::encode(bytes);
+
+        if std::env::var_os("UPDATE_LAYOUT_SNAPSHOTS").is_some() {
+            std::fs::write(&path, format!("{actual}\n")).unwrap();
+            return;
+        }
+
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("missing layout snapshot {path}"));
+        assert_eq!(
+            actual,
+            expected.trim(),
+            "serialized layout of {name} changed; deployed accounts would no longer decode"
+        );
+    }
+
+    #[test]
+    fn test_account_layout_snapshots() {
+        use crate::intent::*;
+        use crate::keeper::*;
+        use crate::relayer::*;
+        use crate::treasury::*;
+        use crate::wallet::*;
+
+        let key = |n: u8| Pubkey::new_from_array([n; 32]);
+        let certificate = QualifiedCertificate {
+            subject: "CN=Erika Mustermann".to_string(),
+            issuer: "CN=Test QTSP".to_string(),
+            serial_number: vec![1, 2, 3, 4],
+            not_before: 1_600_000_000,
+            not_after: 1_900_000_000,
+            public_key_hash: vec![5u8; 32],
+            certificate_type: CertificateType::QESe,
+            country: "DE".to_string(),
+            qscd: true,
+        };
+        let transfer = CrossChainTransferData {
+            sender: key(1),
+            recipient: key(2),
+            amount: 1_000_000,
+            destination_chain: 10,
+            source_chain: 1,
+            token_address: Some(key(3)),
+            fee: 2500,
+            nonce: 7,
+            timestamp: 1_700_000_000,
+            status: TransferStatus::Initiated,
+            travel_rule: Some(Ivms101Payload {
+                originator_name_hash: [4u8; 32],
+                beneficiary_vasp: "5493001KJTIIGC8Y1R12".into(),
+                encrypted_pii: vec![6u8; 8].into(),
+            }),
+        };
+
+        let state = CrossChainState {
+            config: CrossChainConfig {
+                admin: key(10),
+                bridge_authority: key(11),
+                eidas_authority: key(12),
+                arcium_program: key(13),
+                guardian: key(14),
+                genesis_hash: [15u8; 32],
+                ..Default::default()
+            },
+            total_volume: 1,
+            total_transactions: 2,
+            registered_wallets: 3,
+            compliance_records: 4,
+            pending_admin: Some(key(16)),
+            pending_config: None,
+            pause_flags: PauseFlags::BRIDGE_IN,
+            fee_ledger: FeeLedger {
+                entries: vec![FeeAccrual {
+                    mint: None,
+                    protocol: 5,
+                    relayer: 6,
+                }],
+                by_chain: vec![ChainFeeAccrual {
+                    chain_id: 10,
+                    mint: None,
+                    collected: 11,
+                }],
+            },
+            escrow_ledger: EscrowLedger {
+                entries: vec![EscrowBalance {
+                    mint: Some(key(17)),
+                    outstanding: 7,
+                }],
+            },
+            bump: 254,
+            ..Default::default()
+        };
+
+        let mut metadata = ComplianceMetadata::new();
+        metadata.insert("provider".into(), "test".into());
+
+        let snapshots: Vec<(&str, Vec<u8>)> = vec![
+            ("cross_chain_state", state.try_to_vec().unwrap()),
+            (
+                "wallet",
+                Wallet {
+                    owner: key(1),
+                    created_at: 1_700_000_000,
+                    is_compliance_verified: true,
+                    eidas_level: EidasLevel::Substantial,
+                    public_key: vec![8u8; 32],
+                    metadata: b"meta".to_vec(),
+                    kyc_verified: true,
+                    aml_screened: false,
+                    pending_verification: false,
+                    frozen: false,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "wallet_activity_log",
+                WalletActivityLog {
+                    owner: key(1),
+                    head: 1,
+                    total_records: 1,
+                    pending_transfers: 1,
+                    recovered_to: Some(key(3)),
+                    velocity: TransferVelocity {
+                        window_start: 5_000,
+                        transfers: 1,
+                        amount: 100,
+                    },
+                    records: vec![TransactionRecord {
+                        id: "tx-1".to_string(),
+                        timestamp: 1_700_000_000,
+                        tx_hash: vec![9u8; 32],
+                        from: key(1),
+                        to: key(2),
+                        amount: 100,
+                        fee: 1,
+                        status: TransactionStatus::Confirmed,
+                        chain_id: 10,
+                        cross_chain: true,
+                    }],
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "nonce_registry",
+                NonceRegistry {
+                    owner: key(1),
+                    next_transfer_nonce: 3,
+                    next_signature_nonce: 4,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "chain_config",
+                ChainConfig {
+                    chain_id: 10,
+                    enabled: true,
+                    min_eidas_level: EidasLevel::Basic,
+                    inbound_enabled: false,
+                    bump: 254,
+                    caip2: Caip2ChainId::from_legacy(10),
+                    encryption_key: [18u8; 32],
+                    gateway_codehash: [19u8; 32],
+                    outbound_volume: 20,
+                    inbound_volume: 21,
+                    rate_limit: RateLimit::default(),
+                    wormhole_emitter: Default::default(),
+                    transport: TransportKind::default(),
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "transfer_record",
+                TransferRecord {
+                    transfer,
+                    escrowed_amount: 1_000_000,
+                    created_slot: 100,
+                    completed_slot: 0,
+                    bump: 253,
+                    wrapped: false,
+                    relayer_fee: 1000,
+                    timeout_slots: 216_000,
+                    gateway_codehash: [20u8; 32],
+                    terms: TransferTerms::default(),
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "wrapped_asset_registry",
+                WrappedAssetRegistry {
+                    assets: vec![WrappedAsset {
+                        chain_id: 10,
+                        remote_token: key(21),
+                        local_mint: key(22),
+                        remote_decimals: 18,
+                        local_decimals: 9,
+                    }],
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "escrow_discrepancy",
+                EscrowDiscrepancy {
+                    mint: key(17),
+                    expected: 100,
+                    actual: 90,
+                    drift: -10,
+                    flagged: true,
+                    last_reconciled_slot: 200,
+                    bump: 252,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "trusted_issuer_registry",
+                TrustedIssuerRegistry {
+                    issuers: vec![TrustedIssuer {
+                        issuer_hash: [23u8; 32],
+                        added_at: 1_700_000_000,
+                    }],
+                    bump: 251,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "revocation_list",
+                RevocationList {
+                    issuer_hash: [23u8; 32],
+                    revoked_serials: vec![[24u8; 32]],
+                    updated_at: 1_700_000_000,
+                    bump: 250,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "compliance_record",
+                ComplianceRecord {
+                    owner: key(1),
+                    data: ComplianceData {
+                        verified: true,
+                        eidas_level: EidasLevel::High,
+                        kyc_verified: true,
+                        aml_screened: true,
+                        restricted: false,
+                        verification_date: 1_700_000_000,
+                        expiry_date: 1_800_000_000,
+                        jurisdiction: "DE".into(),
+                        metadata,
+                    },
+                    renewals: 2,
+                    bump: 249,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "restricted_jurisdictions",
+                RestrictedJurisdictions {
+                    codes: vec![*b"KP", *b"IR"],
+                    bump: 248,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "qualified_signature",
+                QualifiedSignature {
+                    signature: vec![25u8; 64],
+                    certificate: certificate.clone(),
+                    signed_data: b"payload".to_vec(),
+                    timestamp: 1_700_000_000,
+                    signature_algorithm: "Ed25519".to_string(),
+                    signer_role: "director".to_string(),
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "qualified_seal",
+                QualifiedSeal {
+                    owner: key(1),
+                    seal: vec![26u8; 64],
+                    certificate: certificate.clone(),
+                    organization: "Example GmbH".to_string(),
+                    organization_identifier: OrganizationIdentifier {
+                        scheme: OrganizationIdScheme::Vat,
+                        country: "DE".to_string(),
+                        reference: "123456789".to_string(),
+                    },
+                    sealed_data_hash: [27u8; 32],
+                    timestamp: 1_700_000_000,
+                    signature_algorithm: "Ed25519".to_string(),
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "qualified_timestamp",
+                QualifiedTimestamp {
+                    ts_token: vec![28u8; 16],
+                    tsa_certificate: certificate,
+                    time: 1_700_000_000,
+                    hash_algorithm: "SHA-256".to_string(),
+                    hash_value: vec![29u8; 32],
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "relayer",
+                Relayer {
+                    authority: key(30),
+                    stake: 1_000_000_000,
+                    total_slashed: 5,
+                    slash_count: 1,
+                    registered_slot: 300,
+                    bump: 247,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "relayer_lease",
+                RelayerLease {
+                    holder: key(30),
+                    term: 4,
+                    acquired_slot: 400,
+                    expires_slot: 500,
+                    bump: 246,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "audit_log_buffer",
+                AuditLogBuffer {
+                    head: 1,
+                    next_sequence: 2,
+                    entries: vec![AuditEntry {
+                        sequence: 1,
+                        slot: 600,
+                        timestamp: 1_700_000_000,
+                        action: AuditAction::TransferInitiated,
+                        actor: key(1),
+                        subject: key(31),
+                        amount: 1_000_000,
+                    }],
+                    bump: 245,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "multisig_wallet",
+                MultiSigWallet {
+                    owners: vec![key(32), key(33)],
+                    threshold: 2,
+                    created_at: 1_700_000_000,
+                    eidas_required: true,
+                    transaction_count: 3,
+                    bump: 244,
+                    vault_bump: 243,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "multisig_transaction",
+                MultisigTransaction {
+                    multisig: key(34),
+                    index: 3,
+                    proposer: key(32),
+                    instruction: ProposedInstruction {
+                        program_id: key(35),
+                        accounts: vec![ProposedAccountMeta {
+                            pubkey: key(36),
+                            is_signer: false,
+                            is_writable: true,
+                        }]
+                        .into(),
+                        data: vec![1, 2, 3].into(),
+                    },
+                    approvals: vec![true, false],
+                    executed: false,
+                    bump: 242,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "keeper_job",
+                KeeperJob {
+                    kind: KeeperJobKind::ExpireIntent,
+                    tip_per_crank: 5000,
+                    min_interval_slots: 150,
+                    enabled: true,
+                    last_crank_slot: 700,
+                    total_cranks: 8,
+                    total_tips_paid: 40_000,
+                    bump: 241,
+                    tip_bump: 240,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "transfer_intent",
+                TransferIntent {
+                    owner: key(1),
+                    amount: 1_000_000,
+                    destination_chain: 10,
+                    recipient: key(2),
+                    max_fee: 3000,
+                    deadline: 1_700_003_600,
+                    nonce: 9,
+                    status: IntentStatus::Filled,
+                    best_solver: Some(key(37)),
+                    best_fee: 2000,
+                    fill_tx_hash: [38u8; 32],
+                    created_at: 1_700_000_000,
+                    bump: 239,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "arcium_computation",
+                ArciumComputation {
+                    requester: key(1),
+                    arcium_program: key(13),
+                    computation_offset: 42,
+                    ciphertext_hash: [39u8; 32],
+                    status: ComputationStatus::Completed,
+                    result: vec![40u8; 16],
+                    queued_slot: 800,
+                    completed_slot: 810,
+                    bump: 238,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "transfer_batch",
+                TransferBatch {
+                    sender: key(1),
+                    source_chain: 0,
+                    destination_chain: 10,
+                    first_nonce: 7,
+                    entries: vec![BatchEntry {
+                        recipient: key(2),
+                        nonce: 7,
+                        amount: 1_000_000,
+                        fee: 2_500,
+                    }]
+                    .into(),
+                    escrowed_amount: 1_000_000,
+                    fee: 2_500,
+                    relayer_fee: 1_000,
+                    status: TransferStatus::Initiated,
+                    timestamp: 1_700_000_000,
+                    created_slot: 900,
+                    completed_slot: 0,
+                    timeout_slots: 216_000,
+                    gateway_codehash: [41u8; 32],
+                    bump: 237,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "axelar_route",
+                crate::bridge::axelar::AxelarRoute {
+                    chain_id: 10,
+                    chain_name: "optimism".into(),
+                    gateway_address: "0xe432150cce91c13a887f7D836923d5597adD8E31".into(),
+                    bump: 236,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "chain_pair_policy",
+                ChainPairPolicy {
+                    source_chain: 1,
+                    destination_chain: 42161,
+                    enabled: true,
+                    fee_multiplier_bps: 15_000,
+                    max_amount: 5_000_000,
+                    required_confirmations: 3,
+                    bump: 251,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "recipient_list",
+                RecipientList {
+                    owner: key(23),
+                    kind: RecipientListKind::Block,
+                    entries: vec![key(24), key(25)].into(),
+                    bump: 250,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "fee_history",
+                FeeScheduleHistory {
+                    entries: vec![
+                        FeeSchedule {
+                            fee_basis_points: 25,
+                            relayer_fee: 1_000,
+                            effective_slot: 10,
+                        },
+                        FeeSchedule {
+                            fee_basis_points: 40,
+                            relayer_fee: 1_500,
+                            effective_slot: 90_000,
+                        },
+                    ]
+                    .into(),
+                    evicted: 3,
+                    corridors: vec![CorridorFeeSchedule {
+                        source_chain: 900,
+                        destination_chain: 10,
+                        fee_multiplier_bps: 15_000,
+                        effective_slot: 90_500,
+                    }]
+                    .into(),
+                    corridors_evicted: 1,
+                    bump: 248,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "sponsor",
+                Sponsor {
+                    sponsor: key(31),
+                    enabled: true,
+                    max_sponsored: 10_000,
+                    sponsored: 42,
+                    bump: 246,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "guardian_set",
+                GuardianSet {
+                    owner: key(32),
+                    guardians: vec![key(33), key(34), key(35)].into(),
+                    threshold: 2,
+                    pending: Some(PendingRecovery {
+                        new_owner: key(36),
+                        approvals: vec![key(34)].into(),
+                        objections: vec![key(35)].into(),
+                        executable_slot: 648_500,
+                    }),
+                    bump: 245,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "session_key",
+                SessionKey {
+                    owner: key(29),
+                    key: key(30),
+                    scope: SessionKeyData {
+                        expires_slot: 250_000,
+                        max_amount: 75_000,
+                        permissions: SessionPermissions(3),
+                        allowed_chains: vec![10, 137].into(),
+                    },
+                    bump: 247,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+            (
+                "second_factor",
+                SecondFactor {
+                    owner: key(26),
+                    device: key(27),
+                    threshold: 50_000,
+                    pending_device: Some(key(28)),
+                    recovery_slot: 432_900,
+                    bump: 249,
+                }
+                .try_to_vec()
+                .unwrap(),
+            ),
+        ];
+
+        for (name, bytes) in &snapshots {
+            assert_layout_snapshot(name, bytes);
+        }
+    }
+
+    #[test]
+    fn test_encrypted_payload_round_trip() {
+        use x25519_dalek::{PublicKey, StaticSecret};
+
+        let secret = StaticSecret::from([7u8; 32]);
+        let public = PublicKey::from(&secret);
+        let plaintext = b"confidential balance update";
+
+        let payload = create_encrypted_payload(plaintext, public.as_bytes()).unwrap();
+        assert_eq!(payload.len(), plaintext.len() + ENCRYPTED_PAYLOAD_OVERHEAD);
+        assert_eq!(payload[0], ENCRYPTED_PAYLOAD_VERSION);
+        assert!(!payload
+            .windows(plaintext.len())
+            .any(|w| w == plaintext.as_slice()));
+        assert_eq!(
+            decrypt_payload(&payload, secret.as_bytes()).unwrap(),
+            plaintext.to_vec()
+        );
+
+        // Fresh ephemeral key and nonce on every call.
+        let again = create_encrypted_payload(plaintext, public.as_bytes()).unwrap();
+        assert_ne!(payload, again);
+
+        let other = StaticSecret::from([8u8; 32]);
+        assert_eq!(
+            decrypt_payload(&payload, other.as_bytes()),
+            Err(CrossChainError::PayloadDecryptionFailed)
+        );
+
+        let mut tampered = payload.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            decrypt_payload(&tampered, secret.as_bytes()),
+            Err(CrossChainError::PayloadDecryptionFailed)
+        );
+        assert_eq!(
+            decrypt_payload(&payload[..ENCRYPTED_PAYLOAD_OVERHEAD], secret.as_bytes()),
+            Err(CrossChainError::PayloadDecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn test_encrypted_payload_rejects_bad_keys() {
+        assert_eq!(
+            create_encrypted_payload(b"data", &[]),
+            Err(CrossChainError::EmptyPublicKey)
+        );
+        assert_eq!(
+            create_encrypted_payload(&[], &[9u8; 32]),
+            Err(CrossChainError::EmptyPlaintext)
+        );
+        assert_eq!(
+            create_encrypted_payload(b"data", &[9u8; 16]),
+            Err(CrossChainError::InvalidEncryptionKey)
+        );
+        // The identity point would yield an all-zero shared secret.
+        assert_eq!(
+            create_encrypted_payload(b"data", &[0u8; 32]),
+            Err(CrossChainError::InvalidEncryptionKey)
+        );
+        assert_eq!(
+            decrypt_payload(&[1u8; 100], &[1u8; 31]),
+            Err(CrossChainError::InvalidEncryptionKey)
+        );
+    }
+
+    /// Inline `VerifyBatchedRangeProofU64` data proving each commitment in
+    /// `slots` to its bit length, with a placeholder proof body.
+    fn range_proof_instruction(slots: &[([u8; 32], u8)]) -> Vec<u8> {
+        let mut commitments = [[0u8; 32]; MAX_RANGE_PROOF_COMMITMENTS];
+        let mut bit_lengths = [0u8; MAX_RANGE_PROOF_COMMITMENTS];
+        for (i, (commitment, bits)) in slots.iter().enumerate() {
+            commitments[i] = *commitment;
+            bit_lengths[i] = *bits;
+        }
+
+        let mut data = vec![VERIFY_BATCHED_RANGE_PROOF_U64];
+        data.extend(commitments.iter().flatten());
+        data.extend_from_slice(&bit_lengths);
+        data.extend_from_slice(&[0xAB; 672]);
+        data
+    }
+
+    #[test]
+    fn test_parse_range_proof_instruction() {
+        let data = range_proof_instruction(&[([1u8; 32], 32), ([2u8; 32], 32)]);
+        let context = parse_range_proof_instruction(&data).unwrap();
+        assert_eq!(context.commitments, vec![[1u8; 32], [2u8; 32]]);
+        assert_eq!(context.bit_lengths, vec![32, 32]);
+        assert_eq!(context.bit_length_of(&[2u8; 32]), Some(32));
+        assert_eq!(context.bit_length_of(&[3u8; 32]), None);
+
+        let mut wrong_kind = data.clone();
+        wrong_kind[0] = 1;
+        assert_eq!(
+            parse_range_proof_instruction(&wrong_kind),
+            Err(CrossChainError::InvalidRangeProof)
+        );
+        // proof read from an account: discriminator plus a u32 offset
+        assert_eq!(
+            parse_range_proof_instruction(&[VERIFY_BATCHED_RANGE_PROOF_U64, 0, 0, 0, 0]),
+            Err(CrossChainError::InvalidRangeProof)
+        );
+        assert_eq!(
+            parse_range_proof_instruction(&data[..1 + 8 * 32 + 8]),
+            Err(CrossChainError::InvalidRangeProof)
+        );
+        assert_eq!(
+            parse_range_proof_instruction(&range_proof_instruction(&[])),
+            Err(CrossChainError::InvalidRangeProof)
+        );
+    }
+
+    #[test]
+    fn test_verify_confidential_range_proof() {
+        let commitment = [4u8; 32];
+        let mut ciphertext = commitment.to_vec();
+        ciphertext.extend_from_slice(&[5u8; 32]);
+        let range_proof = range_proof_instruction(&[(commitment, 64)]);
+        let verified = vec![parse_range_proof_instruction(&range_proof).unwrap()];
+
+        let proof = ConfidentialTransferProof {
+            zero_balance_proof: BoundedBytes::new(),
+            range_proof: range_proof.clone().into(),
+            ciphertext: ciphertext.clone().into(),
+            public_encryption_key: vec![6u8; 32].into(),
+        };
+        assert!(verify_confidential_range_proof(&proof, &verified).is_ok());
+
+        assert_eq!(
+            verify_confidential_range_proof(&proof, &[]),
+            Err(CrossChainError::RangeProofNotVerified)
+        );
+        assert_eq!(
+            verify_confidential_range_proof(
+                &ConfidentialTransferProof {
+                    range_proof: BoundedBytes::new(),
+                    ..proof.clone()
+                },
+                &verified
+            ),
+            Err(CrossChainError::ProofRequired)
+        );
+
+        // The proof must bound the commitment inside the amount ciphertext.
+        let other = range_proof_instruction(&[([7u8; 32], 64)]);
+        assert_eq!(
+            verify_confidential_range_proof(
+                &ConfidentialTransferProof {
+                    range_proof: other.clone().into(),
+                    ..proof.clone()
+                },
+                &[parse_range_proof_instruction(&other).unwrap()]
+            ),
+            Err(CrossChainError::InvalidRangeProof)
+        );
+
+        let too_wide = range_proof_instruction(&[(commitment, 128)]);
+        assert_eq!(
+            verify_confidential_range_proof(
+                &ConfidentialTransferProof {
+                    range_proof: too_wide.clone().into(),
+                    ..proof.clone()
+                },
+                &[parse_range_proof_instruction(&too_wide).unwrap()]
+            ),
+            Err(CrossChainError::InvalidRangeProof)
+        );
+
+        assert!(verify_zero_knowledge_proof(&range_proof, &commitment).is_ok());
+        assert_eq!(
+            verify_zero_knowledge_proof(&range_proof, &[7u8; 32]),
+            Err(CrossChainError::InvalidRangeProof)
+        );
+        assert_eq!(
+            verify_zero_knowledge_proof(&[1, 2, 3], &commitment),
+            Err(CrossChainError::InvalidRangeProof)
+        );
+    }
+
+    #[test]
+    fn test_bounded_deserialization() {
+        // Bounded fields share the unbounded wire format.
+        let bytes: BoundedBytes<8> = vec![1, 2, 3].into();
+        let encoded = bytes.try_to_vec().unwrap();
+        assert_eq!(encoded, vec![1u8, 2, 3].try_to_vec().unwrap());
+        assert_eq!(BoundedBytes::<8>::try_from_slice(&encoded).unwrap(), bytes);
+
+        let name: BoundedString<4> = "USDC".into();
+        assert_eq!(
+            BoundedString::<4>::try_from_slice(&name.try_to_vec().unwrap()).unwrap(),
+            "USDC"
+        );
+        assert!(BoundedString::<3>::try_from_slice(&name.try_to_vec().unwrap()).is_err());
+        assert!(BoundedString::<3>::from("USDC").try_to_vec().is_err());
+
+        // A claimed length above the cap is refused before any element is read.
+        let mut hostile = u32::MAX.to_le_bytes().to_vec();
+        hostile.extend_from_slice(&[0u8; 16]);
+        assert!(BoundedBytes::<64>::try_from_slice(&hostile).is_err());
+        assert!(BoundedVec::<Pubkey, 4>::try_from_slice(&hostile).is_err());
+        assert!(ComplianceMetadata::try_from_slice(&hostile).is_err());
+        assert!(
+            BoundedBytes::<8>::try_from_slice(&[9u8; 9].to_vec().try_to_vec().unwrap()).is_err()
+        );
+
+        // Same for a nested field of an instruction.
+        let mut instruction = CrossChainInstruction::ArciumCallback {
+            success: true,
+            result: BoundedBytes::new(),
+        }
+        .try_to_vec()
+        .unwrap();
+        instruction.truncate(instruction.len() - 4);
+        instruction.extend_from_slice(&(MAX_COMPUTATION_RESULT_LEN as u32 + 1).to_le_bytes());
+        instruction.resize(instruction.len() + MAX_COMPUTATION_RESULT_LEN + 1, 0);
+        assert!(CrossChainInstruction::try_from_slice(&instruction).is_err());
+
+        let message = CrossChainMessage {
+            payload: vec![0u8; MAX_MESSAGE_PAYLOAD_LEN + 1].into(),
+            ..sample_message(MessageType::ContractCall, vec![])
+        };
+        assert!(message.try_to_vec().is_err());
+    }
+
+    #[test]
+    fn test_encrypted_state_transition() {
+        let wallet = Pubkey::new_unique();
+        let (old, new) = ([3u8; 32], [4u8; 32]);
+        let data = EncryptedStateData {
+            encrypted_balance: vec![1u8; 64].into(),
+            encrypted_nonce: vec![2u8; 24].into(),
+            ciphertext_commitment: new.to_vec().into(),
+            encryption_public_key: vec![9u8; 32].into(),
+        };
+        let config = ArciumConfig::default();
+        assert!(validate_encrypted_state(&data, &config).unwrap());
+
+        for (invalid, error) in [
+            (
+                EncryptedStateData {
+                    encrypted_balance: BoundedBytes::new(),
+                    ..data.clone()
+                },
+                CrossChainError::EncryptedPayloadRequired,
+            ),
+            (
+                EncryptedStateData {
+                    encrypted_balance: vec![1u8; config.max_encrypted_state_size].into(),
+                    ..data.clone()
+                },
+                CrossChainError::EncryptedStateTooLarge,
+            ),
+            (
+                EncryptedStateData {
+                    ciphertext_commitment: vec![4u8; 31].into(),
+                    ..data.clone()
+                },
+                CrossChainError::InvalidCommitment,
+            ),
+            (
+                EncryptedStateData {
+                    encryption_public_key: BoundedBytes::new(),
+                    ..data.clone()
+                },
+                CrossChainError::InvalidEncryptionKey,
+            ),
+        ] {
+            assert_eq!(validate_encrypted_state(&invalid, &config), Err(error));
+        }
+
+        let state = EncryptedWalletState::new(wallet, data, 7, 254);
+        assert_eq!(state.ciphertext_commitment, new.to_vec());
+        let decoded = EncryptedWalletState::try_from_slice(&state.try_to_vec().unwrap()).unwrap();
+        assert_eq!(decoded.wallet, wallet);
+        assert_eq!(decoded.updated_slot, 7);
+
+        let both = parse_range_proof_instruction(&range_proof_instruction(&[(old, 64), (new, 64)]))
+            .unwrap();
+        assert!(verify_state_transition(&old, &new, &[both]).unwrap());
+
+        assert_eq!(
+            verify_state_transition(&old, &new, &[]),
+            Err(CrossChainError::RangeProofNotVerified)
+        );
+        let only_new =
+            parse_range_proof_instruction(&range_proof_instruction(&[(new, 64)])).unwrap();
+        let only_old =
+            parse_range_proof_instruction(&range_proof_instruction(&[(old, 64)])).unwrap();
+        assert_eq!(
+            verify_state_transition(&old, &new, &[only_old, only_new]),
+            Err(CrossChainError::InvalidStateTransition)
+        );
+        let too_wide =
+            parse_range_proof_instruction(&range_proof_instruction(&[(old, 64), (new, 128)]))
+                .unwrap();
+        assert_eq!(
+            verify_state_transition(&old, &new, &[too_wide]),
+            Err(CrossChainError::InvalidStateTransition)
+        );
+
+        let (address, _) = get_encrypted_state_address(&wallet, &crate::id());
+        assert_ne!(
+            address,
+            get_encrypted_state_address(&Pubkey::new_unique(), &crate::id()).0
+        );
+    }
+
+    #[test]
+    fn test_top_level_invocation_guard() {
+        let program_id = crate::id();
+        let caller = Pubkey::new_unique();
+
+        let open = CrossChainConfig::default();
+        assert!(!open.require_top_level);
+        assert!(open.check_invocation(&caller, &program_id).unwrap());
+
+        let guarded = CrossChainConfig {
+            require_top_level: true,
+            ..Default::default()
+        };
+        assert!(guarded.check_invocation(&program_id, &program_id).unwrap());
+        assert_eq!(
+            guarded.check_invocation(&caller, &program_id),
+            Err(CrossChainError::CpiNotAllowed)
+        );
+    }
+
+    #[test]
+    fn test_confidential_transfer_bound() {
+        let commitment = [4u8; 32];
+        let mut ciphertext = commitment.to_vec();
+        ciphertext.extend_from_slice(&[5u8; 32]);
+        let range_proof = range_proof_instruction(&[(commitment, 32)]);
+        let verified = vec![parse_range_proof_instruction(&range_proof).unwrap()];
+        let proof = ConfidentialTransferProof {
+            zero_balance_proof: BoundedBytes::new(),
+            range_proof: range_proof.into(),
+            ciphertext: ciphertext.into(),
+            public_encryption_key: vec![6u8; 32].into(),
+        };
+
+        // A 32-bit proof admits amounts up to 2^32 - 1.
+        let bound = u32::MAX as u64;
+        assert!(verify_confidential_amount_bound(&proof, &verified, bound).unwrap());
+        assert!(verify_confidential_amount_bound(&proof, &verified, bound + 1).unwrap());
+        assert_eq!(
+            verify_confidential_amount_bound(&proof, &verified, bound - 1),
+            Err(CrossChainError::ConfidentialBoundExceeded)
+        );
+        assert_eq!(
+            verify_confidential_amount_bound(&proof, &[], bound),
+            Err(CrossChainError::RangeProofNotVerified)
+        );
+
+        let payload = ConfidentialTransferPayload::from_proof(&proof, bound).unwrap();
+        assert_eq!(payload.ciphertext[..32], commitment);
+        assert_eq!(payload.encryption_public_key, [6u8; 32]);
+        assert_eq!(
+            ConfidentialTransferPayload::from_proof(
+                &ConfidentialTransferProof {
+                    public_encryption_key: vec![6u8; 31].into(),
+                    ..proof.clone()
+                },
+                bound
+            ),
+            Err(CrossChainError::InvalidEncryptionKey)
+        );
+
+        let message = sample_message(
+            MessageType::ConfidentialTransfer,
+            payload.try_to_vec().unwrap(),
+        );
+        let decoded = decode_bridge_message(&encode_bridge_message(&message)).unwrap();
+        assert_eq!(
+            ConfidentialTransferPayload::try_from_slice(&decoded.payload).unwrap(),
+            payload
+        );
+
+        let unbounded = ConfidentialTransferPayload {
+            amount_bound: 0,
+            ..payload
+        };
+        assert!(matches!(
+            validate_bridge_message(&sample_message(
+                MessageType::ConfidentialTransfer,
+                unbounded.try_to_vec().unwrap()
+            )),
+            Err(MessageError::InvalidEnvelope(_))
+        ));
+    }
+
+    #[test]
+    fn test_query_views() {
+        let mut state = CrossChainState {
+            pause_flags: PauseFlags::BRIDGE_OUT,
+            ..Default::default()
+        };
+        state.bridge_config.relayer_fee = 750;
+        let view = ConfigView::from(&state);
+        let decoded = ConfigView::try_from_slice(&view.try_to_vec().unwrap()).unwrap();
+        assert_eq!(
+            decoded.config.supported_chains,
+            state.config.supported_chains
+        );
+        assert_eq!(decoded.pause_flags, PauseFlags::BRIDGE_OUT.0);
+        assert_eq!(decoded.relayer_fee, 750);
+
+        let wallet = Wallet {
+            owner: Pubkey::new_from_array([1u8; 32]),
+            created_at: 0,
+            is_compliance_verified: true,
+            eidas_level: EidasLevel::Substantial,
+            public_key: vec![2u8; 32],
+            metadata: vec![],
+            kyc_verified: true,
+            aml_screened: false,
+            pending_verification: false,
+            frozen: false,
+        };
+        let status = Some(WalletStatusView::from(&wallet));
+        let decoded =
+            Option::<WalletStatusView>::try_from_slice(&status.try_to_vec().unwrap()).unwrap();
+        assert_eq!(decoded, status);
+        assert_eq!(decoded.unwrap().eidas_level, EidasLevel::Substantial);
+        assert_eq!(None::<WalletStatusView>.try_to_vec().unwrap(), vec![0]);
+
+        let record = TransferRecord {
+            transfer: CrossChainTransferData {
+                sender: Pubkey::new_from_array([1u8; 32]),
+                recipient: Pubkey::new_from_array([2u8; 32]),
+                amount: 1_000_000,
+                destination_chain: 10,
+                source_chain: 0,
+                token_address: None,
+                fee: 2500,
+                nonce: 4,
+                timestamp: 0,
+                status: TransferStatus::Initiated,
+                travel_rule: None,
+            },
+            escrowed_amount: 1_000_000,
+            created_slot: 100,
+            completed_slot: 0,
+            bump: 255,
+            wrapped: false,
+            relayer_fee: 0,
+            timeout_slots: 50,
+            gateway_codehash: [0u8; 32],
+            terms: TransferTerms::default(),
+        };
+        let view = TransferStatusView::from(&record);
+        assert_eq!(view.status, TransferStatus::Initiated);
+        assert_eq!(view.fee, 2500);
+        assert_eq!(view.refundable_after_slot, 150);
+        assert_eq!(
+            TransferStatusView::try_from_slice(&view.try_to_vec().unwrap()).unwrap(),
+            view
+        );
+    }
+
+    #[test]
+    fn test_transfer_terms_grace_period() {
+        let mut state = CrossChainState::default();
+        state.config.fee_basis_points = 25;
+        state.bridge_config.relayer_fee = 500;
+        state.bridge_config.claim_delivery = true;
+        let mut policy = ChainPairPolicy::new(10, 0);
+        let terms_at = |state: &CrossChainState, policy: &ChainPairPolicy, slot| {
+            state.transfer_terms(policy, slot).unwrap()
+        };
+        assert_eq!(terms_at(&state, &policy, 100).expires_slot, u64::MAX);
+
+        state.bridge_config.terms_grace_slots = 1_000;
+        let terms = terms_at(&state, &policy, 100);
+        assert_eq!(terms.expires_slot, 1_100);
+        assert_eq!(terms.fee_basis_points, 25);
+
+        let mut record = TransferRecord {
+            transfer: relay_transfer(),
+            escrowed_amount: 1_000_000,
+            created_slot: 100,
+            completed_slot: 0,
+            bump: 255,
+            wrapped: false,
+            relayer_fee: 500,
+            timeout_slots: 0,
+            gateway_codehash: [0u8; 32],
+            terms: terms.clone(),
+        };
+
+        // The admin raises fees and switches to push delivery mid-flight.
+        state.config.fee_basis_points = 100;
+        state.bridge_config.relayer_fee = 2_000;
+        state.bridge_config.claim_delivery = false;
+
+        assert!(!record.apply_terms(&terms_at(&state, &policy, 1_100), 1_100));
+        assert_eq!(record.terms, terms);
+        assert_eq!(record.transfer.fee, 2500);
+        assert_eq!(record.relayer_fee, 500);
+
+        assert!(record.apply_terms(&terms_at(&state, &policy, 1_101), 1_101));
+        assert_eq!(record.transfer.fee, 12_000);
+        assert_eq!(record.relayer_fee, 2_000);
+        assert!(!record.terms.claim_delivery);
+        assert_eq!(record.terms.expires_slot, u64::MAX);
+        assert!(!record.apply_terms(&terms_at(&state, &policy, 5_000), 5_000));
+
+        // A corridor at twice the base rate records that rate, and waiting
+        // out the grace period recharges at the corridor's rate, not the
+        // base one.
+        policy.fee_multiplier_bps = 20_000;
+        state.config.fee_basis_points = 25;
+        let terms = terms_at(&state, &policy, 100);
+        assert_eq!(terms.fee_basis_points, 50);
+        let mut record = TransferRecord { terms, ..record };
+        assert!(record.apply_terms(&terms_at(&state, &policy, 1_101), 1_101));
+        assert_eq!(record.terms.fee_basis_points, 50);
+        assert_eq!(record.transfer.fee, 7_000);
+    }
+
+    #[test]
+    fn test_state_account_validation() {
+        use solana_program::account_info::AccountInfo;
+        use solana_program::program_error::ProgramError;
+
+        let program_id = crate::id();
+        let (address, bump) = get_state_address(&program_id);
+        let state = CrossChainState {
+            bump,
+            ..Default::default()
+        };
+
+        let load = |key: &Pubkey, owner: &Pubkey, state: &CrossChainState| {
+            let mut lamports = 0;
+            let mut data = state.pack().unwrap();
+            let account =
+                AccountInfo::new(key, false, true, &mut lamports, &mut data, owner, false, 0);
+            load_state(&program_id, &account).map(|state| state.bump)
+        };
+
+        assert_eq!(load(&address, &program_id, &state), Ok(bump));
+        assert_eq!(
+            load(&address, &Pubkey::new_unique(), &state),
+            Err(ProgramError::IncorrectProgramId)
+        );
+        // A program-owned account at any other address is a forgery, even
+        // when its contents claim the canonical bump.
+        assert_eq!(
+            load(&Pubkey::new_unique(), &program_id, &state),
+            Err(ProgramError::InvalidSeeds)
+        );
+    }
+
+    #[test]
+    fn test_precheck_recipient() {
+        let now = 1_800_000_000;
+        let mut state = CrossChainState::default();
+        state.config.min_cross_chain_amount = 1_000;
+        state.config.max_cross_chain_amount = 1_000_000_000;
+        state.config.fee_basis_points = 25;
+        state.bridge_config.relayer_fee = 500;
+
+        let wallet = Wallet {
+            owner: Pubkey::new_from_array([1u8; 32]),
+            created_at: 0,
+            is_compliance_verified: false,
+            eidas_level: EidasLevel::Substantial,
+            public_key: vec![2u8; 32],
+            metadata: vec![],
+            kyc_verified: true,
+            aml_screened: true,
+            pending_verification: false,
+            frozen: false,
+        };
+        let chain = ChainConfig {
+            chain_id: 10,
+            enabled: true,
+            min_eidas_level: EidasLevel::Substantial,
+            inbound_enabled: true,
+            bump: 255,
+            caip2: Caip2ChainId::from_legacy(10),
+            encryption_key: [0u8; 32],
+            gateway_codehash: [0u8; 32],
+            outbound_volume: 0,
+            inbound_volume: 0,
+            rate_limit: RateLimit::default(),
+            wormhole_emitter: Default::default(),
+            transport: TransportKind::default(),
+        };
+        let sanctioned = ComplianceRecord {
+            owner: Pubkey::new_from_array([3u8; 32]),
+            data: ComplianceData {
+                verified: true,
+                expiry_date: now + 86_400,
+                jurisdiction: "KP".into(),
+                ..Default::default()
+            },
+            renewals: 0,
+            bump: 255,
+        };
+        let restricted = RestrictedJurisdictions::default();
+        let wallet_config = WalletConfig::default();
+        let mut precheck = TransferPrecheck {
+            wallet: &wallet,
+            wallet_config: &wallet_config,
+            chain: Some(&chain),
+            sender_compliance: None,
+            recipient_compliance: None,
+            restricted: &restricted,
+        };
+
+        assert_eq!(precheck.evaluate(&state, 1_000_000, now), Ok(3_000));
+        assert_eq!(
+            precheck.evaluate(&state, 999, now),
+            Err(CrossChainError::AmountTooLow)
+        );
+
+        precheck.recipient_compliance = Some(&sanctioned);
+        assert_eq!(
+            precheck.evaluate(&state, 1_000_000, now),
+            Err(CrossChainError::RestrictedJurisdiction)
+        );
+
+        precheck.recipient_compliance = None;
+        precheck.chain = None;
+        let view = PrecheckView::from(precheck.evaluate(&state, 1_000_000, now));
+        assert_eq!(
+            view.rejection,
+            Some(CrossChainError::ChainNotRegistered as u32)
+        );
+        assert_eq!(
+            PrecheckView::try_from_slice(&view.try_to_vec().unwrap()).unwrap(),
+            view
+        );
+
+        state.pause_flags = PauseFlags::BRIDGE_OUT;
+        precheck.chain = Some(&chain);
+        assert_eq!(
+            precheck.evaluate(&state, 1_000_000, now),
+            Err(CrossChainError::ProgramPaused)
+        );
+    }
+
+    #[test]
+    fn test_wallet_account_validation() {
+        use solana_program::account_info::AccountInfo;
+        use solana_program::program_error::ProgramError;
+
+        let program_id = crate::id();
+        let owner = Pubkey::new_unique();
+        let (address, _) = get_wallet_address(&owner, &program_id);
+        let wallet = Wallet {
+            owner,
+            created_at: 0,
+            is_compliance_verified: false,
+            eidas_level: EidasLevel::None,
+            public_key: vec![],
+            metadata: vec![],
+            kyc_verified: false,
+            aml_screened: false,
+            pending_verification: false,
+            frozen: false,
+        };
+
+        let load = |key: &Pubkey, account_owner: &Pubkey| {
+            let mut lamports = 0;
+            let mut data = wallet.pack().unwrap();
+            let account = AccountInfo::new(
+                key,
+                false,
+                true,
+                &mut lamports,
+                &mut data,
+                account_owner,
+                false,
+                0,
+            );
+            load_wallet(&program_id, &account).map(|wallet| wallet.owner)
+        };
+
+        assert_eq!(load(&address, &program_id), Ok(owner));
+        assert_eq!(
+            load(&address, &Pubkey::new_unique()),
+            Err(ProgramError::IncorrectProgramId)
+        );
+        // A second wallet for the same owner can only live off the PDA.
+        assert_eq!(
+            load(&Pubkey::new_unique(), &program_id),
+            Err(ProgramError::InvalidSeeds)
+        );
+        assert_ne!(
+            get_wallet_address(&Pubkey::new_unique(), &program_id).0,
+            address
+        );
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_client_instruction_builders() {
+        use crate::client::instructions::*;
+        use borsh::BorshDeserialize;
+
+        let program_id = crate::id();
+        let transfer = relay_transfer();
+        let ix = initiate_cross_chain_ix(&program_id, transfer.clone()).unwrap();
+        assert_eq!(ix.program_id, program_id);
+        assert_eq!(ix.accounts.len(), 19);
+        assert_eq!(
+            ix.accounts[14].pubkey,
+            get_recipient_list_address(&transfer.sender, RecipientListKind::Block, &program_id).0
+        );
+        assert_eq!(
+            ix.accounts[15].pubkey,
+            get_second_factor_address(&transfer.sender, &program_id).0
+        );
+        assert!(ix.accounts[16].pubkey == transfer.sender && !ix.accounts[16].is_signer);
+        let device = Pubkey::new_unique();
+        let cosigned = with_second_factor(&program_id, ix.clone(), &transfer.sender, &device);
+        assert!(cosigned.accounts[16].pubkey == device && cosigned.accounts[16].is_signer);
+        assert_eq!(cosigned.accounts[..16], ix.accounts[..16]);
+        let session_key = Pubkey::new_unique();
+        let delegated = with_session_key(&program_id, ix.clone(), &transfer.sender, &session_key);
+        assert!(!delegated.accounts[2].is_signer);
+        assert_eq!(
+            delegated.accounts[17].pubkey,
+            get_session_key_address(&transfer.sender, &session_key, &program_id).0
+        );
+        assert!(delegated.accounts[18].pubkey == session_key && delegated.accounts[18].is_signer);
+        assert!(
+            delegated
+                .accounts
+                .iter()
+                .filter(|meta| meta.is_signer)
+                .count()
+                == 1
+        );
+        assert_eq!(ix.accounts[0].pubkey, get_state_address(&program_id).0);
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            get_wallet_address(&transfer.sender, &program_id).0
+        );
+        assert!(ix.accounts[2].is_signer);
+        assert_eq!(
+            ix.accounts[5].pubkey,
+            get_transfer_record_address(&transfer.sender, transfer.nonce, &program_id).0
+        );
+        assert!(matches!(
+            CrossChainInstruction::try_from_slice(&ix.data).unwrap(),
+            CrossChainInstruction::InitiateCrossChain { transfer_data } if transfer_data.nonce == transfer.nonce
+        ));
+
+        let relayers = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let confirmation = |signers: &[Pubkey]| RelayConfirmation {
+            relayer: signers[0],
+            tx_hash: vec![1u8; 32].into(),
+            block_number: 1,
+            timestamp: 0,
+            signatures: vec![].into(),
+            signers: signers.into(),
+        };
+        let authority = Pubkey::new_unique();
+        // The sample transfer arrives from chain 10 at a hub known as chain 0.
+        let hub_chain_id = transfer.destination_chain;
+        let ix = complete_cross_chain_ix(
+            &program_id,
+            &authority,
+            hub_chain_id,
+            transfer.clone(),
+            vec![confirmation(&relayers), confirmation(&relayers[..1])],
+        )
+        .unwrap();
+        assert_eq!(
+            ix.accounts[5].pubkey,
+            get_chain_config_address(transfer.source_chain, &program_id).0
+        );
+        // Each signer's relayer account appears once, between the corridor
+        // policy and the treasury.
+        assert_eq!(ix.accounts.len(), 13);
+        assert_eq!(
+            ix.accounts[8].pubkey,
+            get_chain_pair_policy_address(
+                transfer.source_chain,
+                transfer.destination_chain,
+                &program_id
+            )
+            .0
+        );
+        assert_eq!(
+            ix.accounts[9].pubkey,
+            get_relayer_address(&relayers[0], &program_id).0
+        );
+        assert_eq!(
+            ix.accounts[10].pubkey,
+            get_relayer_address(&relayers[1], &program_id).0
+        );
+        assert_eq!(ix.accounts[11].pubkey, get_treasury_address(&program_id).0);
+
+        // Leaving a hub known as chain 10, the transfer is confirmed by its
+        // destination instead.
+        let outbound =
+            complete_cross_chain_ix(&program_id, &authority, 10, transfer.clone(), vec![]).unwrap();
+        assert_eq!(
+            outbound.accounts[5].pubkey,
+            get_chain_config_address(transfer.destination_chain, &program_id).0
+        );
+
+        let proof = InclusionProof {
+            block_number: 77,
+            siblings: vec![[4u8; 32]].into(),
+        };
+        let ix = complete_cross_chain_with_proof_ix(
+            &program_id,
+            &authority,
+            hub_chain_id,
+            transfer.clone(),
+            proof,
+        )
+        .unwrap();
+        assert_eq!(ix.accounts.len(), 12);
+        assert_eq!(
+            ix.accounts[9].pubkey,
+            get_state_root_address(transfer.source_chain, 77, &program_id).0
+        );
+        assert_eq!(ix.accounts[10].pubkey, get_treasury_address(&program_id).0);
+
+        let ix = post_state_root_ix(
+            &program_id,
+            &authority,
+            10,
+            77,
+            [5u8; 32],
+            relayers.to_vec(),
+        )
+        .unwrap();
+        assert_eq!(ix.accounts.len(), 8);
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            get_state_root_address(10, 77, &program_id).0
+        );
+        assert_eq!(
+            ix.accounts[7].pubkey,
+            get_relayer_address(&relayers[1], &program_id).0
+        );
+
+        let ix = reject_hold_ix(&program_id, &authority, &transfer.sender, transfer.nonce).unwrap();
+        assert_eq!(ix.accounts.len(), 8);
+        assert!(ix.accounts[0].is_signer);
+        assert!(!ix.accounts[3].is_signer);
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            get_transfer_record_address(&transfer.sender, transfer.nonce, &program_id).0
+        );
+
+        let ix = set_rate_limit_ix(&program_id, &authority, 10, 1_000, 432_000).unwrap();
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            get_chain_config_address(10, &program_id).0
+        );
+        assert!(ix.accounts[1].is_writable);
+        assert!(ix.accounts[2].is_signer);
+
+        let proposed = ProposedInstruction {
+            program_id,
+            accounts: vec![
+                ProposedAccountMeta {
+                    pubkey: get_state_address(&program_id).0,
+                    is_signer: false,
+                    is_writable: true,
+                },
+                ProposedAccountMeta {
+                    pubkey: get_governance_authority_address(&program_id).0,
+                    is_signer: true,
+                    is_writable: false,
+                },
+            ]
+            .into(),
+            data: vec![].into(),
+        };
+        let ix = execute_proposal_ix(&program_id, 3, &proposed).unwrap();
+        assert_eq!(ix.accounts.len(), 5);
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            get_proposal_address(3, &program_id).0
+        );
+        assert!(ix.accounts.iter().all(|meta| !meta.is_signer));
+        assert!(ix.accounts[4].is_writable);
+
+        let usdc_vault = Pubkey::new_unique();
+        let ix = claim_fees_ix(
+            &program_id,
+            &authority,
+            &Pubkey::new_unique(),
+            Some(Pubkey::new_unique()),
+            Some(&usdc_vault),
+        )
+        .unwrap();
+        assert_eq!(ix.accounts.len(), 7);
+        assert_eq!(ix.accounts[1].pubkey, get_treasury_address(&program_id).0);
+        assert_eq!(ix.accounts[5].pubkey, usdc_vault);
+        let ix = publish_treasury_report_ix(&program_id, &authority).unwrap();
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            get_treasury_report_address(&program_id).0
+        );
+
+        let ix =
+            queue_config_update_ix(&program_id, &authority, CrossChainConfig::default()).unwrap();
+        assert!(ix.accounts[1].is_signer && ix.accounts[1].is_writable);
+        assert_eq!(
+            ix.accounts[3].pubkey,
+            solana_program::sysvar::instructions::id()
+        );
+        let ix = execute_config_update_ix(&program_id, &authority).unwrap();
+        assert_eq!(ix.accounts.len(), 4);
+        assert_eq!(
+            ix.accounts[3].pubkey,
+            get_fee_history_address(&program_id).0
+        );
+        let ix = update_config_ix(&program_id, &authority, CrossChainConfig::default()).unwrap();
+        assert!(ix.accounts[1].is_signer && ix.accounts[1].is_writable);
+        assert_eq!(
+            ix.accounts[3].pubkey,
+            get_fee_history_address(&program_id).0
+        );
+        assert_eq!(
+            ix.accounts[4].pubkey,
+            solana_program::sysvar::instructions::id()
+        );
+
+        let posted_vaa = Pubkey::new_unique();
+        let ix = complete_cross_chain_with_vaa_ix(
+            &program_id,
+            &authority,
+            hub_chain_id,
+            relay_transfer(),
+            &posted_vaa,
+        )
+        .unwrap();
+        assert_eq!(ix.accounts[9].pubkey, posted_vaa);
+        let core_bridge = Pubkey::new_unique();
+        let metas = wormhole_post_accounts(&program_id, &core_bridge, &authority, 4);
+        assert_eq!(metas[0].pubkey, core_bridge);
+        assert_eq!(
+            metas[2].pubkey,
+            get_wormhole_message_address(
+                &get_transfer_record_address(&authority, 4, &program_id).0,
+                &program_id
+            )
+            .0
+        );
+
+        let ix = release_hold_ix(&program_id, &authority, &authority, 4, 10).unwrap();
+        assert_eq!(
+            ix.accounts[4].pubkey,
+            get_chain_config_address(10, &program_id).0
+        );
+        let ix = set_axelar_route_ix(&program_id, &authority, 10, "optimism", "0xgateway").unwrap();
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            get_axelar_route_address(10, &program_id).0
+        );
+        let gateway = Pubkey::new_unique();
+        let approval = crate::bridge::axelar::AxelarApproval {
+            message_id: "0xabc-1".into(),
+            payload: vec![1u8].into(),
+        };
+        let ix = complete_cross_chain_with_approval_ix(
+            &program_id,
+            &authority,
+            hub_chain_id,
+            &gateway,
+            "optimism",
+            relay_transfer(),
+            approval,
+        )
+        .unwrap();
+        assert_eq!(
+            ix.accounts[10].pubkey,
+            get_incoming_message_address(
+                &crate::bridge::axelar::command_id("optimism", "0xabc-1"),
+                &gateway
+            )
+        );
+        let metas = axelar_call_accounts(&program_id, &gateway, 10);
+        assert_eq!(metas[2].pubkey, program_id);
+
+        let signers = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let ix =
+            aggregate_attestation_ix(&[1u8; 32], &[(signers[0], [0; 64]), (signers[1], [0; 64])]);
+        assert_eq!(ix.program_id, solana_program::ed25519_program::id());
+        assert!(ix.accounts.is_empty());
+        let attestation = AggregateAttestation {
+            tx_hash: vec![0u8; 32].into(),
+            block_number: 1,
+            timestamp: 0,
+            ed25519_instruction_index: 0,
+        };
+        let ix = complete_cross_chain_with_aggregate_ix(
+            &program_id,
+            &authority,
+            hub_chain_id,
+            relay_transfer(),
+            attestation,
+            &signers,
+        )
+        .unwrap();
+        assert_eq!(ix.accounts.len(), 13);
+        assert_eq!(
+            ix.accounts[10].pubkey,
+            get_relayer_address(&signers[1], &program_id).0
+        );
+
+        let first = relay_transfer();
+        let ix =
+            initiate_cross_chain_batch_ix(&program_id, vec![first.clone(), first.clone()]).unwrap();
+        assert_eq!(
+            ix.accounts[5].pubkey,
+            get_transfer_batch_address(&first.sender, first.nonce, &program_id).0
+        );
+        let ix = refund_cross_chain_batch_ix(&program_id, &first.sender, first.nonce).unwrap();
+        assert!(ix.accounts[2].is_signer);
+        let new_owner = Pubkey::new_unique();
+        let ix =
+            refund_recovered_batch_ix(&program_id, &new_owner, &first.sender, first.nonce).unwrap();
+        assert_eq!(ix.accounts[2].pubkey, new_owner);
+        assert!(ix.accounts[2].is_signer);
+        assert_eq!(
+            ix.accounts[3].pubkey,
+            get_activity_log_address(&first.sender, &program_id).0
+        );
+
+        let mint = Pubkey::new_unique();
+        let ix = register_wrapped_asset_ix(
+            &program_id,
+            &authority,
+            1,
+            Pubkey::new_from_array([7u8; 32]),
+            18,
+            &mint,
+        )
+        .unwrap();
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            get_wrapped_registry_address(&program_id).0
+        );
+        let ix = deregister_wrapped_asset_ix(
+            &program_id,
+            &authority,
+            1,
+            Pubkey::new_from_array([7u8; 32]),
+            &mint,
+        )
+        .unwrap();
+        assert_eq!(ix.accounts[2].pubkey, mint);
+        assert!(ix.accounts[3].is_signer);
+
+        let ix = set_chain_pair_policy_ix(
+            &program_id,
+            &authority,
+            1,
+            42161,
+            ChainPairPolicyData {
+                enabled: false,
+                fee_multiplier_bps: 10_000,
+                max_amount: 0,
+                required_confirmations: 0,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            get_chain_pair_policy_address(1, 42161, &program_id).0
+        );
+        assert_eq!(
+            ix.accounts[4].pubkey,
+            get_fee_history_address(&program_id).0
+        );
+        let ix = update_recipient_list_ix(
+            &program_id,
+            &authority,
+            RecipientListKind::Allow,
+            Pubkey::new_unique(),
+            false,
+        )
+        .unwrap();
+        assert!(matches!(
+            CrossChainInstruction::try_from_slice(&ix.data).unwrap(),
+            CrossChainInstruction::RemoveRecipientListEntry {
+                kind: RecipientListKind::Allow,
+                ..
+            }
+        ));
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            get_recipient_list_address(&authority, RecipientListKind::Allow, &program_id).0
+        );
+        let ix = cancel_cross_chain_ix(&program_id, &authority, 4, 10).unwrap();
+        assert_eq!(
+            ix.accounts[0].pubkey,
+            get_chain_config_address(10, &program_id).0
+        );
+        assert!(ix.accounts[3].is_signer);
+        let device = Pubkey::new_unique();
+        let current = Pubkey::new_unique();
+        let ix = register_second_factor_ix(&program_id, &authority, &device, Some(&current), 5_000)
+            .unwrap();
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            get_second_factor_address(&authority, &program_id).0
+        );
+        assert!(ix.accounts[3].is_signer && ix.accounts[5].is_signer);
+        assert_eq!(ix.accounts[5].pubkey, current);
+        let ix = recover_second_factor_ix(&program_id, &authority, &device).unwrap();
+        assert_eq!(ix.accounts.len(), 4);
+        assert!(ix.accounts[1].is_signer && ix.accounts[3].is_signer);
+        let ix = with_second_factor(
+            &program_id,
+            set_guardians_ix(&program_id, &authority, vec![device], 1).unwrap(),
+            &authority,
+            &device,
+        );
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            get_guardian_set_address(&authority, &program_id).0
+        );
+        assert!(ix.accounts[6].is_signer && ix.accounts[6].pubkey == device);
+        let ix = propose_recovery_ix(&program_id, &device, &authority, &current).unwrap();
+        assert!(ix.accounts[2].is_signer && ix.accounts[1].is_writable);
+        let ix = execute_recovery_ix(&program_id, &authority, &current).unwrap();
+        assert_eq!(ix.accounts.len(), 18);
+        assert!(ix.accounts[7].is_signer && ix.accounts[7].pubkey == current);
+        assert_eq!(
+            ix.accounts[12].pubkey,
+            get_guardian_set_address(&current, &program_id).0
+        );
+        // Lists and the second factor move with the wallet; the old activity
+        // log is only read for pending transfers.
+        assert!(!ix.accounts[3].is_writable);
+        assert_eq!(
+            ix.accounts[4].pubkey,
+            get_recipient_list_address(&authority, RecipientListKind::Allow, &program_id).0
+        );
+        assert_eq!(
+            ix.accounts[14].pubkey,
+            get_recipient_list_address(&current, RecipientListKind::Block, &program_id).0
+        );
+        assert_eq!(
+            ix.accounts[15].pubkey,
+            get_second_factor_address(&current, &program_id).0
+        );
+        let ix =
+            freeze_wallet_ix(&program_id, &authority, &device, FreezeReason::CourtOrder).unwrap();
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            get_wallet_address(&device, &program_id).0
+        );
+        assert!(ix.accounts[1].is_writable && ix.accounts[2].is_signer);
+        assert_eq!(
+            unfreeze_wallet_ix(&program_id, &authority, &device)
+                .unwrap()
+                .accounts,
+            ix.accounts
+        );
+        let scope = SessionKeyData {
+            expires_slot: 1_000,
+            max_amount: 5_000,
+            permissions: SessionPermissions::SIGN_TRANSACTION,
+            allowed_chains: vec![10].into(),
+        };
+        let ix = issue_session_key_ix(&program_id, &authority, &device, scope).unwrap();
+        assert_eq!(
+            ix.accounts[2].pubkey,
+            get_session_key_address(&authority, &device, &program_id).0
+        );
+        let ix = revoke_session_key_ix(&program_id, &authority, &device).unwrap();
+        assert!(matches!(
+            CrossChainInstruction::try_from_slice(&ix.data).unwrap(),
+            CrossChainInstruction::RevokeSessionKey { session_key } if session_key == device
+        ));
+
+        let oversized = WalletData {
+            public_key: vec![0u8; MAX_WALLET_PUBLIC_KEY_LEN + 1].into(),
+            metadata: vec![].into(),
+            eidas_level: EidasLevel::None,
+        };
+        assert!(register_wallet_ix(&program_id, &authority, oversized).is_err());
+
+        let sponsor = Pubkey::new_unique();
+        let wallet_data = WalletData {
+            public_key: vec![1u8; 32].into(),
+            metadata: vec![].into(),
+            eidas_level: EidasLevel::None,
+        };
+        let ix = register_wallet_ix(&program_id, &authority, wallet_data.clone()).unwrap();
+        assert_eq!(ix.accounts.len(), 10);
+        assert_eq!(ix.accounts[8].pubkey, authority);
+        let ix =
+            sponsored_register_wallet_ix(&program_id, &authority, &sponsor, wallet_data).unwrap();
+        assert!(ix.accounts[8].pubkey == sponsor && ix.accounts[8].is_signer);
+        assert_eq!(
+            ix.accounts[9].pubkey,
+            get_sponsor_address(&sponsor, &program_id).0
+        );
+        let ix = sponsored_verify_compliance_ix(
+            &program_id,
+            &authority,
+            &Pubkey::new_unique(),
+            &sponsor,
+            ComplianceData::default(),
+        )
+        .unwrap();
+        assert!(ix.accounts[7].pubkey == sponsor && ix.accounts[7].is_signer);
+        let ix = set_sponsor_ix(&program_id, &authority, &sponsor, true, 100).unwrap();
+        assert_eq!(
+            ix.accounts[1].pubkey,
+            get_sponsor_address(&sponsor, &program_id).0
+        );
+        assert!(ix.accounts[2].is_signer);
+
+        let recipient = Pubkey::new_unique();
+        let ix = transfer_internal_ix(&program_id, &authority, &recipient, 500, None).unwrap();
+        assert_eq!(ix.accounts.len(), 11);
+        assert!(ix.accounts[2].is_signer);
+        assert_eq!(
+            ix.accounts[3].pubkey,
+            get_wallet_address(&recipient, &program_id).0
+        );
+    }
+
+    #[test]
+    fn test_recipient_lists() {
+        let owner = Pubkey::new_from_array([1u8; 32]);
+        let friend = Pubkey::new_from_array([2u8; 32]);
+        let stranger = Pubkey::new_from_array([3u8; 32]);
+        let mut allowlist = RecipientList::new(owner, RecipientListKind::Allow, 255);
+        let mut blocklist = RecipientList::new(owner, RecipientListKind::Block, 254);
+        assert_eq!(allowlist.add(friend), Ok(true));
+        assert_eq!(allowlist.add(friend), Ok(false));
+        assert_eq!(blocklist.add(stranger), Ok(true));
+
+        // Lists are ignored until the wallet config enables them.
+        let mut config = WalletConfig::default();
+        assert!(validate_recipient(&config, None, Some(&blocklist), &stranger).is_ok());
+
+        config.enable_allowlist = true;
+        assert!(validate_recipient(&config, Some(&allowlist), None, &friend).is_ok());
+        assert_eq!(
+            validate_recipient(&config, Some(&allowlist), None, &stranger),
+            Err(CrossChainError::RecipientNotAllowed)
+        );
+        assert_eq!(
+            validate_recipient(&config, None, None, &friend),
+            Err(CrossChainError::RecipientNotAllowed)
+        );
+
+        config.enable_allowlist = false;
+        config.enable_blocklist = true;
+        assert!(validate_recipient(&config, None, None, &stranger).is_ok());
+        assert_eq!(
+            validate_recipient(&config, None, Some(&blocklist), &stranger),
+            Err(CrossChainError::RecipientBlocked)
+        );
+        assert!(blocklist.remove(&stranger));
+        assert!(!blocklist.remove(&stranger));
+        assert!(validate_recipient(&config, None, Some(&blocklist), &stranger).is_ok());
+
+        for i in 1..MAX_RECIPIENT_LIST_ENTRIES {
+            allowlist
+                .add(Pubkey::new_from_array([i as u8 + 10; 32]))
+                .unwrap();
+        }
+        assert_eq!(
+            allowlist.add(stranger),
+            Err(CrossChainError::RecipientListFull)
+        );
+        assert_eq!(allowlist.try_to_vec().unwrap().len(), RecipientList::LEN);
+    }
+
+    #[test]
+    fn test_session_key_scope() {
+        let session = SessionKey {
+            owner: Pubkey::new_from_array([1u8; 32]),
+            key: Pubkey::new_from_array([2u8; 32]),
+            scope: SessionKeyData {
+                expires_slot: 1_000,
+                max_amount: 50_000,
+                permissions: SessionPermissions::INITIATE_CROSS_CHAIN,
+                allowed_chains: vec![10, 42161].into(),
+            },
+            bump: 255,
+        };
+        let initiate = SessionPermissions::INITIATE_CROSS_CHAIN;
+        assert!(session.authorize(initiate, 50_000, 10, 1_000).is_ok());
+        assert_eq!(
+            session.authorize(initiate, 50_000, 10, 1_001),
+            Err(CrossChainError::SessionKeyExpired)
+        );
+        assert_eq!(
+            session.authorize(initiate, 50_001, 10, 500),
+            Err(CrossChainError::SessionKeyOutOfScope)
+        );
+        assert_eq!(
+            session.authorize(initiate, 1, 1, 500),
+            Err(CrossChainError::SessionKeyOutOfScope)
+        );
+        assert_eq!(
+            session.authorize(SessionPermissions::SIGN_TRANSACTION, 1, 10, 500),
+            Err(CrossChainError::SessionKeyOutOfScope)
+        );
+        assert_eq!(
+            session.authorize(SessionPermissions::default(), 1, 10, 500),
+            Err(CrossChainError::SessionKeyOutOfScope)
+        );
+
+        let mut any_chain = session.clone();
+        any_chain.scope.allowed_chains = BoundedVec::new();
+        any_chain.scope.permissions.0 |= SessionPermissions::SIGN_TRANSACTION.0;
+        assert!(any_chain
+            .authorize(SessionPermissions::SIGN_TRANSACTION, 1, 1, 500)
+            .is_ok());
+
+        any_chain.scope.allowed_chains = vec![1; MAX_SESSION_KEY_CHAINS].into();
+        assert_eq!(any_chain.try_to_vec().unwrap().len(), SessionKey::LEN);
+    }
+
+    #[test]
+    fn test_second_factor() {
+        let owner = Pubkey::new_from_array([1u8; 32]);
+        let device = Pubkey::new_from_array([2u8; 32]);
+        let replacement = Pubkey::new_from_array([3u8; 32]);
+        let mut factor = SecondFactor::new(owner, device, 10_000, 255);
+
+        // Without 2FA enabled the device is never consulted.
+        let mut config = WalletConfig::default();
+        assert!(validate_second_factor(&config, None, u64::MAX, None).is_ok());
+
+        config.require_2fa = true;
+        assert!(validate_second_factor(&config, Some(&factor), 10_000, None).is_ok());
+        assert_eq!(
+            validate_second_factor(&config, Some(&factor), 10_001, None),
+            Err(CrossChainError::SecondFactorRequired)
+        );
+        assert_eq!(
+            validate_second_factor(&config, Some(&factor), 10_001, Some(&owner)),
+            Err(CrossChainError::SecondFactorRequired)
+        );
+        assert!(validate_second_factor(&config, Some(&factor), 10_001, Some(&device)).is_ok());
+        assert_eq!(
+            validate_second_factor(&config, None, 1, None),
+            Err(CrossChainError::SecondFactorRequired)
+        );
+
+        // Recovery without the device waits out the delay; naming another
+        // device restarts it.
+        assert_eq!(factor.recover(replacement, 100), Ok(false));
+        assert_eq!(
+            factor.recover(replacement, 99 + SECOND_FACTOR_RECOVERY_DELAY_SLOTS),
+            Err(CrossChainError::SecondFactorRecoveryLocked)
+        );
+        assert_eq!(factor.recover(owner, 200), Ok(false));
+        assert_eq!(factor.pending_device, Some(owner));
+        assert_eq!(factor.recover(replacement, 300), Ok(false));
+        assert_eq!(
+            factor.recover(replacement, 300 + SECOND_FACTOR_RECOVERY_DELAY_SLOTS),
+            Ok(true)
+        );
+        assert_eq!(factor.device, replacement);
+        assert_eq!(factor.pending_device, None);
+        assert!(validate_second_factor(&config, Some(&factor), 10_001, Some(&device)).is_err());
+
+        factor.pending_device = Some(device);
+        assert_eq!(factor.try_to_vec().unwrap().len(), SecondFactor::LEN);
+    }
+
+    #[test]
+    fn test_social_recovery() {
+        let owner = Pubkey::new_from_array([1u8; 32]);
+        let guardians: Vec<Pubkey> = (2..5).map(|i| Pubkey::new_from_array([i; 32])).collect();
+        let new_owner = Pubkey::new_from_array([9u8; 32]);
+        let outsider = Pubkey::new_from_array([8u8; 32]);
+
+        for (list, threshold) in [
+            (guardians.clone(), 0),
+            (guardians.clone(), 4),
+            (vec![guardians[0], guardians[0]], 1),
+            (vec![guardians[0], owner], 1),
+        ] {
+            assert_eq!(
+                GuardianSet::new(owner, list.into(), threshold, 255),
+                Err(CrossChainError::InvalidGuardianSet)
+            );
+        }
+
+        let mut set = GuardianSet::new(owner, guardians.clone().into(), 2, 255).unwrap();
+        assert_eq!(
+            set.propose(outsider, new_owner, 100),
+            Err(CrossChainError::NotGuardian)
+        );
+        assert_eq!(set.veto(), Err(CrossChainError::NoPendingRecovery));
+
+        assert_eq!(set.propose(guardians[0], new_owner, 100), Ok(true));
+        assert_eq!(set.propose(guardians[0], new_owner, 150), Ok(false));
+        assert_eq!(
+            set.recovery_owner(100 + SOCIAL_RECOVERY_DELAY_SLOTS),
+            Err(CrossChainError::RecoveryNotApproved)
+        );
+
+        // One guardian naming someone else only objects; it takes the
+        // threshold to replace the pending recovery.
+        assert_eq!(set.propose(guardians[1], outsider, 150), Ok(true));
+        assert_eq!(set.propose(guardians[1], outsider, 160), Ok(false));
+        assert_eq!(set.pending.as_ref().unwrap().new_owner, new_owner);
+        assert_eq!(set.pending.as_ref().unwrap().objections.len(), 1);
+
+        // Approving again withdraws the objection.
+        assert_eq!(set.propose(guardians[1], new_owner, 170), Ok(true));
+        assert!(set.pending.as_ref().unwrap().objections.is_empty());
+        assert_eq!(set.propose(guardians[1], outsider, 180), Ok(true));
+        assert_eq!(set.pending.as_ref().unwrap().approvals.len(), 1);
+        assert_eq!(
+            set.recovery_owner(100 + SOCIAL_RECOVERY_DELAY_SLOTS),
+            Err(CrossChainError::RecoveryNotApproved)
+        );
+
+        // Later approvals do not extend the veto window.
+        assert_eq!(set.propose(guardians[1], new_owner, 200), Ok(true));
+        assert_eq!(
+            set.recovery_owner(99 + SOCIAL_RECOVERY_DELAY_SLOTS),
+            Err(CrossChainError::RecoveryLocked)
+        );
+        assert_eq!(
+            set.recovery_owner(100 + SOCIAL_RECOVERY_DELAY_SLOTS),
+            Ok(new_owner)
+        );
+
+        // A rogue first proposer is replaced once enough guardians object.
+        assert_eq!(set.propose(guardians[1], outsider, 190), Ok(true));
+        assert_eq!(set.propose(guardians[2], outsider, 190), Ok(true));
+        let replaced = set.pending.as_ref().unwrap();
+        assert_eq!(replaced.new_owner, outsider);
+        assert_eq!(replaced.approvals[..], [guardians[2]]);
+        assert!(replaced.objections.is_empty());
+        assert_eq!(replaced.executable_slot, 190 + SOCIAL_RECOVERY_DELAY_SLOTS);
+        assert_eq!(set.propose(guardians[1], outsider, 250), Ok(true));
+        assert_eq!(
+            set.recovery_owner(190 + SOCIAL_RECOVERY_DELAY_SLOTS),
+            Ok(outsider)
+        );
+
+        // A veto clears the approvals; a fresh proposal starts over.
+        assert_eq!(set.veto(), Ok(outsider));
+        assert_eq!(
+            set.recovery_owner(u64::MAX),
+            Err(CrossChainError::NoPendingRecovery)
+        );
+        assert_eq!(set.propose(guardians[2], outsider, 300), Ok(true));
+        assert_eq!(
+            set.pending.as_ref().unwrap().executable_slot,
+            300 + SOCIAL_RECOVERY_DELAY_SLOTS
+        );
+
+        set.guardians = vec![Pubkey::default(); MAX_GUARDIANS].into();
+        set.pending.as_mut().unwrap().approvals = vec![Pubkey::default(); MAX_GUARDIANS].into();
+        set.pending.as_mut().unwrap().objections = vec![Pubkey::default(); MAX_GUARDIANS].into();
+        assert_eq!(set.try_to_vec().unwrap().len(), GuardianSet::LEN);
+    }
+
+    #[test]
+    fn test_wallet_chain_allowlist() {
+        let mut config = WalletConfig::default();
+        assert!(config.allowed_chains.is_empty());
+        assert!(validate_allowed_chain(&config, 31337).is_ok());
+
+        config.allowed_chains = vec![1, 10].into();
+        assert!(validate_allowed_chain(&config, 10).is_ok());
+        assert_eq!(
+            validate_allowed_chain(&config, 42161),
+            Err(CrossChainError::ChainNotAllowedForWallet)
+        );
+
+        config.allowed_chains = vec![1; MAX_WALLET_ALLOWED_CHAINS].into();
+        let record = WalletConfigRecord {
+            owner: Pubkey::new_unique(),
+            config: config.clone(),
+            bump: 255,
+        };
+        assert_eq!(record.try_to_vec().unwrap().len(), WalletConfigRecord::LEN);
+
+        config.allowed_chains.push(1);
+        assert!(config.try_to_vec().is_err());
+    }
+
+    #[test]
+    fn test_hub_accounts_carry_headers() {
+        // Packs into an account of the allocated size, padding included, and
+        // checks the header keeps it from decoding as another type.
+        fn round_trip<T: VersionedAccount>(account: &T, len: usize) -> T {
+            let mut data = vec![0u8; ACCOUNT_HEADER_LEN + len];
+            account.pack_into(&mut data[..]).unwrap();
+            assert_eq!(
+                Wallet::unpack(&data).unwrap_err(),
+                CrossChainError::InvalidAccountDiscriminator.into()
+            );
+            T::unpack(&data).unwrap()
+        }
+
+        let state_root = StateRoot {
+            chain_id: 10,
+            block_number: 42,
+            root: [1u8; 32],
+            posted_slot: 7,
+            signer_count: 2,
+            bump: 255,
+        };
+        assert_eq!(round_trip(&state_root, StateRoot::LEN), state_root);
+
+        let sponsor = Sponsor {
+            sponsor: Pubkey::new_from_array([5u8; 32]),
+            enabled: true,
+            max_sponsored: 3,
+            sponsored: 1,
+            bump: 254,
+        };
+        assert_eq!(round_trip(&sponsor, Sponsor::LEN), sponsor);
+    }
+
+    #[test]
+    fn test_account_discriminators() {
+        let state = CrossChainState {
+            bump: 254,
+            ..Default::default()
+        };
+        let wallet = Wallet {
+            owner: Pubkey::new_unique(),
+            created_at: 0,
+            is_compliance_verified: false,
+            eidas_level: EidasLevel::None,
+            public_key: vec![],
+            metadata: vec![],
+            kyc_verified: false,
+            aml_screened: false,
+            pending_verification: false,
+            frozen: false,
+        };
+
+        let packed = state.pack().unwrap();
+        assert_eq!(&packed[..8], b"RQSTATE_");
+        assert_eq!(packed[8], CrossChainState::VERSION);
+        assert_eq!(
+            &packed[ACCOUNT_HEADER_LEN..],
+            &state.try_to_vec().unwrap()[..]
+        );
+        assert_eq!(CrossChainState::unpack(&packed).unwrap().bump, 254);
+        assert!(CrossChainState::is_tagged(&packed));
+
+        // A wallet can no longer stand in for the hub state.
+        assert_eq!(
+            CrossChainState::unpack(&wallet.pack().unwrap()).unwrap_err(),
+            CrossChainError::InvalidAccountDiscriminator.into()
+        );
+        // Accounts written before headers existed must be migrated first.
+        let legacy = wallet.try_to_vec().unwrap();
+        assert!(!Wallet::is_tagged(&legacy));
+        assert_eq!(
+            Wallet::unpack(&legacy).unwrap_err(),
+            CrossChainError::InvalidAccountDiscriminator.into()
+        );
+
+        let mut outdated = wallet.pack().unwrap();
+        outdated[8] = Wallet::VERSION + 1;
+        assert!(Wallet::is_tagged(&outdated));
+        assert_eq!(
+            Wallet::unpack(&outdated).unwrap_err(),
+            CrossChainError::AccountMigrationRequired.into()
+        );
+
+        let record = WalletConfigRecord {
+            owner: wallet.owner,
+            config: WalletConfig::default(),
+            bump: 255,
+        };
+        let mut padded = vec![0u8; ACCOUNT_HEADER_LEN + WalletConfigRecord::LEN];
+        record.pack_into(&mut padded[..]).unwrap();
+        assert_eq!(
+            WalletConfigRecord::unpack(&padded).unwrap().owner,
+            wallet.owner
+        );
+    }
+
+    #[test]
+    fn test_migrate_first_release_layouts() {
+        use borsh::BorshDeserialize;
+
+        // Wallet bytes as the first release wrote them: owner, created_at,
+        // is_compliance_verified, eidas_level, public_key, metadata.
+        let owner = Pubkey::new_from_array([7u8; 32]);
+        let mut legacy_wallet = owner.to_bytes().to_vec();
+        legacy_wallet.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        legacy_wallet.push(1);
+        legacy_wallet.push(EidasLevel::Substantial as u8);
+        legacy_wallet.extend_from_slice(&3u32.to_le_bytes());
+        legacy_wallet.extend_from_slice(&[0xaa, 0xbb, 0xcc]);
+        legacy_wallet.extend_from_slice(&0u32.to_le_bytes());
+
+        let wallet = Wallet::from(LegacyWalletV0::try_from_slice(&legacy_wallet).unwrap());
+        assert_eq!(wallet.owner, owner);
+        assert_eq!(wallet.created_at, 1_700_000_000);
+        assert!(wallet.is_compliance_verified);
+        assert_eq!(wallet.eidas_level, EidasLevel::Substantial);
+        assert_eq!(wallet.public_key, vec![0xaa, 0xbb, 0xcc]);
+        assert!(wallet.metadata.is_empty());
+        assert!(!wallet.kyc_verified && !wallet.aml_screened);
+        assert!(!wallet.pending_verification && !wallet.frozen);
+        assert_eq!(
+            Wallet::unpack(&wallet.pack().unwrap()).unwrap().owner,
+            owner
+        );
+        // The current layout is longer, so it never reads baseline bytes.
+        assert!(Wallet::try_from_slice(&legacy_wallet).is_err());
+
+        // State bytes as the first release wrote them: the nine-field config
+        // followed by the four totals, with no bump.
+        let admin = Pubkey::new_from_array([8u8; 32]);
+        let mut legacy_state = admin.to_bytes().to_vec();
+        for fill in [9u8, 10, 11] {
+            legacy_state.extend_from_slice(&[fill; 32]);
+        }
+        legacy_state.extend_from_slice(&2u32.to_le_bytes());
+        legacy_state.extend_from_slice(&1u64.to_le_bytes());
+        legacy_state.extend_from_slice(&137u64.to_le_bytes());
+        legacy_state.extend_from_slice(&5_000u64.to_le_bytes());
+        legacy_state.extend_from_slice(&9_000_000u64.to_le_bytes());
+        legacy_state.extend_from_slice(&30u16.to_le_bytes());
+        legacy_state.push(0);
+        for total in [1_000_000u64, 12, 4, 2] {
+            legacy_state.extend_from_slice(&total.to_le_bytes());
+        }
+
+        let state = LegacyStateV0::try_from_slice(&legacy_state)
+            .unwrap()
+            .into_state(253);
+        assert_eq!(state.config.admin, admin);
+        assert_eq!(
+            state.config.bridge_authority,
+            Pubkey::new_from_array([9u8; 32])
+        );
+        assert_eq!(
+            state.config.arcium_program,
+            Pubkey::new_from_array([11u8; 32])
+        );
+        assert_eq!(&state.config.supported_chains[..], &[1, 137]);
+        assert_eq!(state.config.min_cross_chain_amount, 5_000);
+        assert_eq!(state.config.max_cross_chain_amount, 9_000_000);
+        assert_eq!(state.config.fee_basis_points, 30);
+        assert_eq!(state.total_volume, 1_000_000);
+        assert_eq!(state.total_transactions, 12);
+        assert_eq!(state.registered_wallets, 4);
+        assert_eq!(state.compliance_records, 2);
+        assert_eq!(state.bump, 253);
+        assert_eq!(state.config.guardian, CrossChainConfig::default().guardian);
+        assert_eq!(
+            CrossChainState::unpack(&state.pack().unwrap())
+                .unwrap()
+                .bump,
+            253
+        );
+    }
+
+    #[test]
+    fn test_upgrade_version_two_wallet() {
+        // A version 2 wallet carries the quarantine flag but not `frozen`.
+        let owner = Pubkey::new_from_array([19u8; 32]);
+        let mut v2_wallet = b"RQWALLET".to_vec();
+        v2_wallet.push(2);
+        v2_wallet.extend_from_slice(&owner.to_bytes());
+        v2_wallet.extend_from_slice(&7i64.to_le_bytes());
+        v2_wallet.extend_from_slice(&[0, EidasLevel::Basic as u8]);
+        v2_wallet.extend_from_slice(&0u32.to_le_bytes());
+        v2_wallet.extend_from_slice(&0u32.to_le_bytes());
+        v2_wallet.extend_from_slice(&[0, 0, 1]);
+
+        assert_eq!(
+            Wallet::unpack(&v2_wallet).unwrap_err(),
+            CrossChainError::AccountMigrationRequired.into()
+        );
+        let wallet = upgrade_wallet(&v2_wallet).unwrap().unwrap();
+        assert_eq!(wallet.owner, owner);
+        assert!(wallet.pending_verification);
+        assert!(!wallet.frozen);
+        assert_eq!(
+            Wallet::unpack(&wallet.pack().unwrap()).unwrap().owner,
+            owner
+        );
+
+        let mut unknown = wallet.pack().unwrap();
+        unknown[8] = Wallet::VERSION + 1;
+        assert!(upgrade_wallet(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_upgrade_version_one_headers() {
+        use borsh::BorshSerialize;
+
+        // A version 1 wallet: header, then the body before quarantine.
+        let owner = Pubkey::new_from_array([12u8; 32]);
+        let mut v1_wallet = b"RQWALLET".to_vec();
+        v1_wallet.push(1);
+        v1_wallet.extend_from_slice(&owner.to_bytes());
+        v1_wallet.extend_from_slice(&42i64.to_le_bytes());
+        v1_wallet.extend_from_slice(&[1, EidasLevel::High as u8]);
+        v1_wallet.extend_from_slice(&0u32.to_le_bytes());
+        v1_wallet.extend_from_slice(&0u32.to_le_bytes());
+        v1_wallet.extend_from_slice(&[1, 1]);
+
+        assert_eq!(
+            Wallet::unpack(&v1_wallet).unwrap_err(),
+            CrossChainError::AccountMigrationRequired.into()
+        );
+        let wallet = upgrade_wallet(&v1_wallet).unwrap().unwrap();
+        assert_eq!(wallet.owner, owner);
+        assert_eq!(wallet.eidas_level, EidasLevel::High);
+        assert!(wallet.kyc_verified && wallet.aml_screened);
+        assert!(!wallet.pending_verification && !wallet.frozen);
+        assert!(upgrade_wallet(&wallet.pack().unwrap()).unwrap().is_none());
+
+        let legacy = LegacyStateV1 {
+            config: LegacyConfigV1 {
+                admin: Pubkey::new_from_array([13u8; 32]),
+                bridge_authority: Pubkey::new_from_array([14u8; 32]),
+                eidas_authority: Pubkey::new_from_array([15u8; 32]),
+                arcium_program: Pubkey::new_from_array([16u8; 32]),
+                supported_chains: vec![1, 10].into(),
+                min_cross_chain_amount: 10,
+                max_cross_chain_amount: 1_000,
+                fee_basis_points: 40,
+                paused: false,
+                guardian: Pubkey::new_from_array([17u8; 32]),
+                genesis_hash: [18u8; 32],
+                require_top_level: true,
+            },
+            total_volume: 500,
+            total_transactions: 3,
+            registered_wallets: 2,
+            compliance_records: 1,
+            bridge_config: LegacyBridgeConfigV1 {
+                min_confirmation_blocks: 6,
+                max_confirmation_blocks: 60,
+                relayer_fee: 7,
+                protocol_fee: 8,
+                emergency_breaker: false,
+                supported_tokens: Vec::new().into(),
+                breaker_scope: BreakerScope::All,
+                min_relayer_stake: 9,
+                required_relayer_signatures: 2,
+                claim_delivery: true,
+                claim_requires_compliance: false,
+                transfer_timeout_slots: 100,
+                terms_grace_slots: 50,
+            },
+            compliance_bands: Vec::new().into(),
+            travel_rule_threshold: Some(1_000),
+            pending_admin: None,
+            pause_flags: PauseFlags::default(),
+            fee_entries: Vec::new(),
+            escrow_ledger: EscrowLedger::default(),
+            bump: 252,
+        };
+        let mut v1_state = b"RQSTATE_".to_vec();
+        v1_state.push(1);
+        v1_state.extend_from_slice(&legacy.try_to_vec().unwrap());
+
+        assert_eq!(
+            CrossChainState::unpack(&v1_state).unwrap_err(),
+            CrossChainError::AccountMigrationRequired.into()
+        );
+        let state = upgrade_state(&v1_state).unwrap().unwrap();
+        assert_eq!(state.config.guardian, Pubkey::new_from_array([17u8; 32]));
+        assert_eq!(state.config.genesis_hash, [18u8; 32]);
+        assert!(state.config.require_top_level);
+        assert_eq!(state.bridge_config.terms_grace_slots, 50);
+        assert_eq!(state.compliance_matrix.travel_rule_threshold, Some(1_000));
+        assert!(state.pending_config.is_none());
+        assert_eq!(state.total_volume, 500);
+        assert_eq!(state.bump, 252);
+        assert!(upgrade_state(&state.pack().unwrap()).unwrap().is_none());
+
+        let legacy = LegacyStateV2 {
+            config: LegacyConfigV2 {
+                admin: Pubkey::new_from_array([13u8; 32]),
+                bridge_authority: Pubkey::new_from_array([14u8; 32]),
+                eidas_authority: Pubkey::new_from_array([15u8; 32]),
+                arcium_program: Pubkey::new_from_array([16u8; 32]),
+                supported_chains: vec![1, 10].into(),
+                min_cross_chain_amount: 10,
+                max_cross_chain_amount: 1_000,
+                fee_basis_points: 40,
+                paused: false,
+                guardian: Pubkey::new_from_array([17u8; 32]),
+                genesis_hash: [18u8; 32],
+                require_top_level: false,
+                registration: RegistrationPolicy::default(),
+                config_timelock_slots: 300,
+                max_pending_transfers: 4,
+            },
+            total_volume: 700,
+            total_transactions: 5,
+            registered_wallets: 2,
+            compliance_records: 1,
+            bridge_config: BridgeConfig::default(),
+            compliance_matrix: ComplianceMatrix::default(),
+            pending_admin: None,
+            pending_config: Some(LegacyPendingConfigV2 {
+                config: LegacyConfigV2 {
+                    admin: Pubkey::new_from_array([13u8; 32]),
+                    bridge_authority: Pubkey::new_from_array([19u8; 32]),
+                    eidas_authority: Pubkey::new_from_array([15u8; 32]),
+                    arcium_program: Pubkey::new_from_array([16u8; 32]),
+                    supported_chains: vec![1, 10].into(),
+                    min_cross_chain_amount: 10,
+                    max_cross_chain_amount: 1_000,
+                    fee_basis_points: 40,
+                    paused: false,
+                    guardian: Pubkey::new_from_array([17u8; 32]),
+                    genesis_hash: [18u8; 32],
+                    require_top_level: false,
+                    registration: RegistrationPolicy::default(),
+                    config_timelock_slots: 300,
+                    max_pending_transfers: 4,
+                },
+                eta_slot: 900,
+            }),
+            pause_flags: PauseFlags::default(),
+            fee_ledger: FeeLedger::default(),
+            escrow_ledger: EscrowLedger::default(),
+            bump: 251,
+        };
+        let mut v2_state = b"RQSTATE_".to_vec();
+        v2_state.push(2);
+        v2_state.extend_from_slice(&legacy.try_to_vec().unwrap());
+
+        assert_eq!(
+            CrossChainState::unpack(&v2_state).unwrap_err(),
+            CrossChainError::AccountMigrationRequired.into()
+        );
+        let state = upgrade_state(&v2_state).unwrap().unwrap();
+        assert_eq!(state.config.config_timelock_slots, 300);
+        assert_eq!(state.config.max_pending_transfers, 4);
+        assert_eq!(state.config.hub_chain_id, 0);
+        let pending = state.pending_config.as_ref().unwrap();
+        assert_eq!(
+            pending.config.bridge_authority,
+            Pubkey::new_from_array([19u8; 32])
+        );
+        assert_eq!(pending.eta_slot, 900);
+        assert_eq!(state.total_volume, 700);
+        assert_eq!(state.bump, 251);
+        assert!(upgrade_state(&state.pack().unwrap()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_internal_transfer_validation() {
+        let wallet = |owner| Wallet {
+            owner,
+            created_at: 0,
+            is_compliance_verified: true,
+            eidas_level: EidasLevel::None,
+            public_key: vec![],
+            metadata: vec![],
+            kyc_verified: true,
+            aml_screened: true,
+            pending_verification: false,
+            frozen: false,
+        };
+        let sender = wallet(Pubkey::new_unique());
+        let recipient = wallet(Pubkey::new_unique());
+
+        assert_eq!(
+            validate_internal_transfer(&sender, &recipient, 500),
+            Ok(true)
+        );
+        assert_eq!(
+            validate_internal_transfer(&sender, &recipient, 0),
+            Err(CrossChainError::AmountTooLow)
+        );
+        assert_eq!(
+            validate_internal_transfer(&sender, &sender, 500),
+            Err(CrossChainError::SelfTransfer)
+        );
+    }
+
+    #[test]
+    fn test_registration_policy() {
+        let open = RegistrationPolicy::default();
+        assert_eq!(open.admit(&EidasLevel::None), Ok(false));
+
+        let strict = RegistrationPolicy {
+            min_eidas_level: EidasLevel::Substantial,
+            quarantine_unverified: false,
+        };
+        assert_eq!(strict.admit(&EidasLevel::High), Ok(false));
+        assert_eq!(strict.admit(&EidasLevel::Substantial), Ok(false));
+        assert_eq!(
+            strict.admit(&EidasLevel::Basic),
+            Err(CrossChainError::EidasLevelBelowMinimum)
+        );
+
+        let quarantine = RegistrationPolicy {
+            quarantine_unverified: true,
+            ..strict
+        };
+        assert_eq!(quarantine.admit(&EidasLevel::Basic), Ok(true));
+        assert_eq!(quarantine.admit(&EidasLevel::Substantial), Ok(false));
+
+        let mut wallet = Wallet {
+            owner: Pubkey::new_unique(),
+            created_at: 0,
+            is_compliance_verified: false,
+            eidas_level: EidasLevel::Basic,
+            public_key: vec![],
+            metadata: vec![],
+            kyc_verified: false,
+            aml_screened: false,
+            pending_verification: true,
+            frozen: false,
+        };
+        assert_eq!(
+            validate_wallet_active(&wallet),
+            Err(CrossChainError::WalletPendingVerification)
+        );
+        wallet.pending_verification = false;
+        assert_eq!(validate_wallet_active(&wallet), Ok(true));
+    }
+
+    #[test]
+    fn test_frozen_wallet_cannot_transact() {
+        let mut wallet = Wallet {
+            owner: Pubkey::new_unique(),
+            created_at: 0,
+            is_compliance_verified: true,
+            eidas_level: EidasLevel::High,
+            public_key: vec![],
+            metadata: vec![],
+            kyc_verified: true,
+            aml_screened: true,
+            pending_verification: true,
+            frozen: true,
+        };
+        assert_eq!(
+            validate_wallet_active(&wallet),
+            Err(CrossChainError::WalletFrozen)
+        );
+        wallet.pending_verification = false;
+        assert_eq!(
+            validate_wallet_active(&wallet),
+            Err(CrossChainError::WalletFrozen)
+        );
+        wallet.frozen = false;
+        assert_eq!(validate_wallet_active(&wallet), Ok(true));
+    }
+
+    #[test]
+    fn test_multisig_signer_wallet_freeze_check() {
+        use solana_program::account_info::AccountInfo;
+        use solana_program::program_error::ProgramError;
+
+        let program_id = crate::id();
+        let system = solana_program::system_program::id();
+        let owner = Pubkey::new_unique();
+        let (address, _) = get_wallet_address(&owner, &program_id);
+        let wallet = Wallet {
+            owner,
+            created_at: 0,
+            is_compliance_verified: true,
+            eidas_level: EidasLevel::High,
+            public_key: vec![],
+            metadata: vec![],
+            kyc_verified: true,
+            aml_screened: true,
+            pending_verification: false,
+            frozen: true,
+        };
+
+        let mut lamports = 0;
+        let mut data = wallet.pack().unwrap();
+        let frozen = AccountInfo::new(
+            &address,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        assert_eq!(
+            check_wallet_not_frozen(&program_id, &owner, &frozen),
+            Err(CrossChainError::WalletFrozen.into())
+        );
+        // Another owner cannot present this wallet as their own.
+        assert_eq!(
+            check_wallet_not_frozen(&program_id, &Pubkey::new_unique(), &frozen),
+            Err(ProgramError::InvalidSeeds)
+        );
+
+        // An owner who never registered a hub wallet is not frozen.
+        let mut lamports = 0;
+        let mut data = vec![];
+        let unregistered = AccountInfo::new(
+            &address,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &system,
+            false,
+            0,
+        );
+        assert_eq!(
+            check_wallet_not_frozen(&program_id, &owner, &unregistered),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_event_round_trip() {
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+        let event = HubEvent::TransferInitiated {
+            record: Pubkey::new_unique(),
+            sender: Pubkey::new_unique(),
+            recipient: Pubkey::new_unique(),
+            destination_chain: 10,
+            nonce: 7,
+            amount: 5000,
+            fee: 12,
+            token: None,
+        };
+        let data = event.encode();
+        assert_eq!(&data[..8], &EVENT_DISCRIMINATOR);
+        assert_eq!(parse_event(&data), Some(event.clone()));
+
+        let line = format!("Program data: {}", BASE64.encode(&data));
+        assert_eq!(parse_event_log(&line), Some(event));
+        assert_eq!(
+            parse_event_log("Program log: Wallet registered successfully"),
+            None
+        );
+
+        // Bridge messages share the log channel but are not events.
+        let message = sample_message(MessageType::TokenTransfer, vec![]);
+        assert_eq!(parse_event(&encode_bridge_message(&message)), None);
+    }
+
+    #[test]
+    fn test_bridge_message_compression() {
+        let samples: [&[u8]; 4] = [&[], &[7], &[1, 2, 3, 3, 3, 4], &[0u8; 300]];
+        for data in samples {
+            assert_eq!(rle_decompress(&rle_compress(data)).unwrap(), data);
+        }
+        assert_eq!(rle_compress(&[0u8; 300]).len(), 6);
+        assert_eq!(rle_decoded_len(&[128]), None);
+        assert_eq!(rle_decoded_len(&[2, 1, 2]), None);
+
+        let envelope = ContractCallEnvelope {
+            target: vec![0x11; 20].into(),
+            calldata: vec![0u8; 2048].into(),
+            gas_limit: 200_000,
+        };
+        let plain = envelope.try_to_vec().unwrap();
+        let mut message = sample_message(MessageType::ContractCall, plain.clone());
+        assert!(message.compress());
+        assert_eq!(message.compression, PayloadCompression::Rle);
+        assert!(message.payload.len() < plain.len() / 10);
+        assert!(!message.compress());
+
+        let decoded = decode_bridge_message(&encode_bridge_message(&message)).unwrap();
+        assert_eq!(decoded.plain_payload().unwrap().as_ref(), &plain[..]);
+
+        // Incompressible payloads are left alone.
+        let mut small = sample_message(MessageType::TokenTransfer, vec![1, 2, 3]);
+        assert!(!small.compress());
+        assert_eq!(small.compression, PayloadCompression::None);
+
+        // The decompressed size is what counts against the limit.
+        let bomb = sample_message(MessageType::TokenTransfer, vec![0u8; 1024]);
+        let bomb = CrossChainMessage {
+            payload: rle_compress(&bomb.payload).into(),
+            compression: PayloadCompression::Rle,
+            ..bomb
+        };
+        assert!(matches!(
+            validate_bridge_message(&bomb),
+            Err(MessageError::PayloadTooLarge { len: 1024, .. })
+        ));
+    }
+
+    #[test]
+    fn test_transfer_statistics() {
+        let mut state = CrossChainState::default();
+        state.record_volume(5000).unwrap();
+        state.record_volume(2500).unwrap();
+        assert_eq!(state.total_volume, 7500);
+        assert_eq!(state.total_transactions, 2);
+
+        // An overflow leaves both totals untouched.
+        state.total_volume = u64::MAX - 1;
+        assert_eq!(
+            state.record_volume(2),
+            Err(CrossChainError::ArithmeticOverflow)
+        );
+        assert_eq!(state.total_volume, u64::MAX - 1);
+        assert_eq!(state.total_transactions, 2);
+
+        let mut chain = ChainConfig {
+            chain_id: 10,
+            enabled: true,
+            min_eidas_level: EidasLevel::None,
+            inbound_enabled: true,
+            bump: 255,
+            caip2: Caip2ChainId::from_legacy(10),
+            encryption_key: [0u8; 32],
+            gateway_codehash: [0u8; 32],
+            outbound_volume: 0,
+            inbound_volume: 0,
+            rate_limit: RateLimit::default(),
+            wormhole_emitter: Default::default(),
+            transport: TransportKind::default(),
+        };
+        chain.record_outbound(700).unwrap();
+        chain.record_inbound(300).unwrap();
+        assert_eq!(chain.try_to_vec().unwrap().len(), ChainConfig::LEN);
+        chain.inbound_volume = u64::MAX;
+        assert_eq!(
+            chain.record_inbound(1),
+            Err(CrossChainError::ArithmeticOverflow)
+        );
+
+        let mut view = StatisticsView::from(&state);
+        view.chains.push(ChainVolumeView::from(&chain));
+        let data = view.try_to_vec().unwrap();
+        assert_eq!(data.len(), 4 * 8 + 4 + 3 * 8);
+        assert_eq!(&data[..8], &(u64::MAX - 1).to_le_bytes());
+        assert_eq!(&data[32..36], &1u32.to_le_bytes());
+        assert_eq!(&data[44..52], &700u64.to_le_bytes());
+        assert_eq!(StatisticsView::try_from_slice(&data).unwrap(), view);
+    }
+
+    #[test]
+    fn test_arithmetic_overflow_edges() {
+        assert_eq!(calculate_cross_chain_fee(u64::MAX, 10_000, 0), Ok(u64::MAX));
+        assert_eq!(
+            calculate_cross_chain_fee(u64::MAX, 10_000, 1),
+            Err(CrossChainError::ArithmeticOverflow)
+        );
+        assert_eq!(
+            calculate_cross_chain_fee(u64::MAX, u16::MAX, 0),
+            Err(CrossChainError::ArithmeticOverflow)
+        );
+
+        let config = WalletConfig {
+            max_daily_transfer: u64::MAX,
+            ..WalletConfig::default()
+        };
+        assert_eq!(
+            verify_transaction_limits(u64::MAX - 1, 1, &config),
+            Ok(true)
+        );
+        assert_eq!(
+            verify_transaction_limits(u64::MAX, 1, &config),
+            Err(CrossChainError::ArithmeticOverflow)
+        );
+
+        let shares = [
+            DistributionShare {
+                recipient: Pubkey::new_unique(),
+                share: u32::MAX,
+            },
+            DistributionShare {
+                recipient: Pubkey::new_unique(),
+                share: 1,
+            },
+        ];
+        let payouts = split_distribution(u64::MAX, &shares).unwrap();
+        assert_eq!(
+            payouts.iter().try_fold(0u64, |a, p| a.checked_add(*p)),
+            Some(u64::MAX)
+        );
+
+        // A fee past u64 on lapsed terms is capped at the escrow, not wrapped.
+        let mut record = TransferRecord {
+            transfer: relay_transfer(),
+            escrowed_amount: 5_000,
+            created_slot: 0,
+            completed_slot: 0,
+            bump: 255,
+            wrapped: false,
+            relayer_fee: 0,
+            timeout_slots: 0,
+            gateway_codehash: [0u8; 32],
+            terms: TransferTerms {
+                expires_slot: 0,
+                ..TransferTerms::default()
+            },
+        };
+        record.transfer.amount = u64::MAX;
+        let terms = TransferTerms {
+            fee_basis_points: u16::MAX,
+            ..TransferTerms::default()
+        };
+        assert!(record.apply_terms(&terms, 1));
+        assert_eq!(record.transfer.fee, 5_000);
+    }
+
+    #[test]
+    fn test_canonical_encoding() {
+        let entry = |key: &str, value: &str| {
+            let mut data = (key.len() as u32).to_le_bytes().to_vec();
+            data.extend(key.as_bytes());
+            data.extend((value.len() as u32).to_le_bytes());
+            data.extend(value.as_bytes());
+            data
+        };
+        let mut canonical = 2u32.to_le_bytes().to_vec();
+        canonical.extend(entry("country", "DE"));
+        canonical.extend(entry("level", "high"));
+        let metadata: ComplianceMetadata = decode_canonical(&canonical).unwrap();
+        assert_eq!(metadata.len(), 2);
+
+        // Borsh accepts map entries in any order and repeated keys, but only
+        // the sorted, unique encoding is canonical.
+        let mut reordered = 2u32.to_le_bytes().to_vec();
+        reordered.extend(entry("level", "high"));
+        reordered.extend(entry("country", "DE"));
+        assert!(ComplianceMetadata::try_from_slice(&reordered).is_ok());
+        assert_eq!(
+            decode_canonical::<ComplianceMetadata>(&reordered).unwrap_err(),
+            CrossChainError::NonCanonicalEncoding.into()
+        );
+
+        let mut repeated = 2u32.to_le_bytes().to_vec();
+        repeated.extend(entry("level", "low"));
+        repeated.extend(entry("level", "high"));
+        assert_eq!(
+            check_canonical(
+                &ComplianceMetadata::try_from_slice(&repeated).unwrap(),
+                &repeated
+            ),
+            Err(CrossChainError::NonCanonicalEncoding)
+        );
+
+        let ix = CrossChainInstruction::GetConfig.try_to_vec().unwrap();
+        assert!(decode_canonical::<CrossChainInstruction>(&ix).is_ok());
+    }
+
+    #[test]
+    fn test_compliance_attestation_export() {
+        let now = 1_700_000_000;
+        let authority = Pubkey::new_unique();
+        let record = ComplianceRecord {
+            owner: Pubkey::new_unique(),
+            data: ComplianceData {
+                verified: true,
+                eidas_level: EidasLevel::Substantial,
+                kyc_verified: true,
+                aml_screened: true,
+                verification_date: now - 100,
+                expiry_date: now + 86_400,
+                jurisdiction: "de".into(),
+                ..ComplianceData::default()
+            },
+            renewals: 0,
+            bump: 255,
+        };
+        let attestation = ComplianceAttestation {
+            owner: record.owner,
+            destination_chain: 10,
+            eidas_level: EidasLevel::Substantial,
+            kyc_verified: true,
+            aml_screened: true,
+            jurisdiction: *b"DE",
+            issued_at: now,
+            expires_at: now + 3_600,
+            authority,
+        };
+        assert_eq!(attestation.validate(&record, now), Ok(true));
+
+        let inflated = ComplianceAttestation {
+            eidas_level: EidasLevel::High,
+            ..attestation.clone()
+        };
+        assert_eq!(
+            inflated.validate(&record, now),
+            Err(CrossChainError::InvalidAttestation)
+        );
+        let outliving = ComplianceAttestation {
+            expires_at: record.data.expiry_date + 1,
+            ..attestation.clone()
+        };
+        assert_eq!(
+            outliving.validate(&record, now),
+            Err(CrossChainError::InvalidAttestation)
+        );
+
+        let domain = SigningDomain::new(crate::id(), [3u8; 32]);
+        let signed = SignedComplianceAttestation {
+            attestation: attestation.clone(),
+            signature: [7u8; 64],
+        };
+        assert_eq!(
+            signed.try_to_vec().unwrap().len(),
+            SignedComplianceAttestation::LEN
+        );
+        let verified = |signer, message: [u8; 32]| VerifiedSignature {
+            signer,
+            signature: [7u8; 64],
+            message: message.to_vec(),
+        };
+        let digest = attestation.digest(&domain);
+        assert_eq!(
+            signed.verify(&domain, &[verified(authority, digest)]),
+            Ok(true)
+        );
+        assert_eq!(
+            signed.verify(&domain, &[verified(Pubkey::new_unique(), digest)]),
+            Err(CrossChainError::AttestationSignatureMissing)
+        );
+        let other_cluster = SigningDomain::new(crate::id(), [4u8; 32]);
+        assert_eq!(
+            signed.verify(
+                &domain,
+                &[verified(authority, attestation.digest(&other_cluster))]
+            ),
+            Err(CrossChainError::AttestationSignatureMissing)
+        );
+
+        let message = sample_message(
+            MessageType::ComplianceAttestation,
+            signed.try_to_vec().unwrap(),
+        );
+        assert!(validate_bridge_message(&message).is_ok());
+        let backdated = SignedComplianceAttestation {
+            attestation: ComplianceAttestation {
+                expires_at: now,
+                ..attestation
+            },
+            signature: [7u8; 64],
+        };
+        assert!(matches!(
+            validate_bridge_message(&sample_message(
+                MessageType::ComplianceAttestation,
+                backdated.try_to_vec().unwrap()
+            )),
+            Err(MessageError::InvalidEnvelope(_))
+        ));
+    }
+
+    #[test]
+    fn test_state_root_inclusion_proof() {
+        let transfer = relay_transfer();
+        let mut other = relay_transfer();
+        other.nonce += 1;
+        let third = [7u8; 32];
+
+        // Three-leaf tree: root = node(node(transfer, other), third).
+        let siblings = vec![transfer_leaf(&other), third];
+        let root = merkle_root(transfer_leaf(&transfer), &siblings);
+        let state_root = StateRoot {
+            chain_id: transfer.source_chain,
+            block_number: 500,
+            root,
+            posted_slot: 0,
+            signer_count: 2,
+            bump: 255,
+        };
+        assert_eq!(state_root.try_to_vec().unwrap().len(), StateRoot::LEN);
+
+        let proof = InclusionProof {
+            block_number: 500,
+            siblings: siblings.into(),
+        };
+        assert!(verify_inclusion(&state_root, transfer.source_chain, &transfer, &proof).is_ok());
+
+        // The sibling's own proof reaches the same root.
+        let other_proof = InclusionProof {
+            block_number: 500,
+            siblings: vec![transfer_leaf(&transfer), third].into(),
+        };
+        assert!(verify_inclusion(&state_root, other.source_chain, &other, &other_proof).is_ok());
+
+        let mut tampered = transfer.clone();
+        tampered.amount += 1;
+        assert_eq!(
+            verify_inclusion(&state_root, tampered.source_chain, &tampered, &proof),
+            Err(CrossChainError::InvalidInclusionProof)
+        );
+
+        assert_eq!(
+            verify_inclusion(&state_root, transfer.destination_chain, &transfer, &proof),
+            Err(CrossChainError::InvalidInclusionProof)
+        );
+
+        let wrong_block = InclusionProof {
+            block_number: 501,
+            ..proof.clone()
+        };
+        assert_eq!(
+            verify_inclusion(&state_root, transfer.source_chain, &transfer, &wrong_block),
+            Err(CrossChainError::InvalidInclusionProof)
+        );
+
+        // An inner node cannot be presented as a leaf.
+        let inner = merkle_root(transfer_leaf(&transfer), &[transfer_leaf(&other)]);
+        assert_ne!(inner, transfer_leaf(&transfer));
+
+        let relayers = vec![relayer(1, 1_000), relayer(2, 1_000), relayer(3, 10)];
+        let signers = vec![relayers[0].authority, relayers[1].authority];
+        let message = state_root_digest(&relay_domain(), 10, 500, &root).to_vec();
+        let verified: Vec<VerifiedSignature> = signers
+            .iter()
+            .map(|signer| VerifiedSignature {
+                signer: *signer,
+                signature: [0u8; 64],
+                message: message.clone(),
+            })
+            .collect();
+        let quorum = |signers: &[Pubkey], root: &[u8; 32], required: u8| {
+            verify_state_root_quorum(
+                &relay_domain(),
+                10,
+                500,
+                root,
+                signers,
+                &relayers,
+                &verified,
+                required,
+                1_000,
+            )
+        };
+
+        assert!(quorum(&signers, &root, 2).is_ok());
+        assert_eq!(
+            quorum(&signers, &root, 3),
+            Err(CrossChainError::InsufficientConfirmations)
+        );
+        assert_eq!(
+            quorum(&[signers[0], signers[0]], &root, 1),
+            Err(CrossChainError::InvalidRelaySignature)
+        );
+        assert_eq!(
+            quorum(&signers, &[0u8; 32], 1),
+            Err(CrossChainError::InvalidRelaySignature)
+        );
+        assert_eq!(
+            quorum(&[relayers[2].authority], &root, 1),
+            Err(CrossChainError::InsufficientRelayerStake)
+        );
+        assert_eq!(
+            quorum(&[Pubkey::new_unique()], &root, 1),
+            Err(CrossChainError::UnauthorizedRelayer)
+        );
+        assert!(!BridgeConfig::default().require_inclusion_proof);
+    }
+
+    #[test]
+    fn test_transfer_hold_review() {
+        let rules = HoldRules {
+            amount_threshold: Some(500_000),
+            hold_unscreened: true,
+            review_timeout_slots: 1_000,
+            velocity: VelocityRules::default(),
+        };
+        let screened = ComplianceProfile {
+            aml_screened: true,
+            ..Default::default()
+        };
+        assert!(!rules.should_hold(499_999, &screened));
+        assert!(rules.should_hold(500_000, &screened));
+        assert!(rules.should_hold(1, &ComplianceProfile::default()));
+        assert!(!HoldRules::default().should_hold(u64::MAX, &ComplianceProfile::default()));
+
+        let mut record = TransferRecord {
+            transfer: relay_transfer(),
+            escrowed_amount: 1_000_000,
+            created_slot: 100,
+            completed_slot: 0,
+            bump: 255,
+            wrapped: false,
+            relayer_fee: 0,
+            timeout_slots: 50,
+            gateway_codehash: [0u8; 32],
+            terms: TransferTerms::default(),
+        };
+        assert_eq!(
+            record.validate_held(),
+            Err(CrossChainError::TransferNotHeld)
+        );
+
+        record.hold(rules.review_timeout_slots);
+        assert_eq!(record.transfer.status, TransferStatus::Held);
+        assert!(record.validate_held().is_ok());
+        // Unreviewed holds fall back to the sender after the review timeout.
+        assert_eq!(
+            record.validate_refund(1_099),
+            Err(CrossChainError::RefundNotYetAvailable)
+        );
+        assert!(record.validate_refund(1_100).is_ok());
+
+        // Release restarts the transfer timeout from the release slot.
+        record.release_hold(600, 50).unwrap();
+        assert_eq!(record.transfer.status, TransferStatus::Initiated);
+        assert_eq!(
+            record.validate_refund(649),
+            Err(CrossChainError::RefundNotYetAvailable)
+        );
+        assert!(record.validate_refund(650).is_ok());
+        assert_eq!(
+            record.release_hold(700, 50),
+            Err(CrossChainError::TransferNotHeld)
+        );
+
+        let event = HubEvent::TransferHeld {
+            record: Pubkey::new_unique(),
+            sender: record.transfer.sender,
+            destination_chain: 10,
+            amount: record.transfer.amount,
+        };
+        assert_eq!(parse_event(&event.encode()), Some(event));
+    }
+
+    #[test]
+    fn test_chain_rate_limit() {
+        let mut limit = RateLimit::default();
+        assert!(limit.consume(u64::MAX, 0).is_ok());
+        assert_eq!(limit.window_outflow, 0);
+
+        limit.max_outflow = 1_000;
+        limit.window_slots = 100;
+        limit.consume(600, 10).unwrap();
+        limit.consume(400, 50).unwrap();
+        assert_eq!(
+            limit.consume(1, 99),
+            Err(CrossChainError::RateLimitExceeded)
+        );
+        // A rejected transfer leaves the window untouched.
+        assert_eq!(limit.window_outflow, 1_000);
+
+        // The window restarts at the first transfer after it lapses.
+        limit.consume(700, 100).unwrap();
+        assert_eq!((limit.window_start, limit.window_outflow), (100, 700));
+        assert_eq!(
+            limit.consume(301, 199),
+            Err(CrossChainError::RateLimitExceeded)
+        );
+
+        let tighter = RateLimit {
+            max_outflow: 500,
+            window_slots: 200,
+            ..Default::default()
+        };
+        assert!(limit.is_tightened_by(&tighter));
+        assert!(RateLimit::default().is_tightened_by(&tighter));
+        assert!(!tighter.is_tightened_by(&limit));
+        assert!(!limit.is_tightened_by(&RateLimit::default()));
+        assert!(!limit.is_tightened_by(&RateLimit {
+            window_slots: 50,
+            ..tighter
+        }));
+    }
+
+    #[test]
+    fn test_governance_proposal_lifecycle() {
+        let config = GovernanceConfig {
+            token_mint: Pubkey::new_unique(),
+            min_proposal_weight: 100,
+            quorum_votes: 1_000,
+            voting_slots: 50,
+            timelock_slots: 20,
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            GovernanceConfig {
+                quorum_votes: 0,
+                ..config.clone()
+            }
+            .validate(),
+            Err(CrossChainError::InvalidGovernanceConfig)
+        );
+
+        let governance = Governance {
+            config: config.clone(),
+            proposal_count: 0,
+            bump: 255,
+            authority_bump: 254,
+        };
+        assert_eq!(governance.try_to_vec().unwrap().len(), Governance::LEN);
+        let vote = VoteRecord {
+            proposal: Pubkey::new_unique(),
+            voter: Pubkey::new_unique(),
+            weight: 1,
+            support: true,
+            withdrawn: false,
+            bump: 255,
+        };
+        assert_eq!(vote.try_to_vec().unwrap().len(), VoteRecord::LEN);
+        assert_eq!(
+            Governance::unpack(&governance.pack().unwrap())
+                .unwrap()
+                .authority_bump,
+            254
+        );
+        assert_eq!(
+            Proposal::unpack(&vote.pack().unwrap()).unwrap_err(),
+            CrossChainError::InvalidAccountDiscriminator.into()
+        );
+
+        let mut proposal = Proposal {
+            index: 0,
+            proposer: Pubkey::new_unique(),
+            instruction: ProposedInstruction {
+                program_id: crate::id(),
+                accounts: vec![].into(),
+                data: vec![].into(),
+            },
+            votes_for: 0,
+            votes_against: 0,
+            voting_ends_slot: 150,
+            executed: false,
+            bump: 255,
+        };
+
+        assert_eq!(
+            Proposal::unpack(&proposal.pack().unwrap())
+                .unwrap()
+                .voting_ends_slot,
+            150
+        );
+
+        proposal.cast(true, 600, 100).unwrap();
+        proposal.cast(false, 300, 149).unwrap();
+        assert_eq!(
+            proposal.cast(true, 1, 150),
+            Err(CrossChainError::VotingClosed)
+        );
+        assert_eq!(
+            proposal.validate_execution(&config, 149),
+            Err(CrossChainError::VotingInProgress)
+        );
+        // 900 votes cast is short of the 1,000 quorum.
+        assert_eq!(
+            proposal.validate_execution(&config, 200),
+            Err(CrossChainError::ProposalNotPassed)
+        );
+
+        proposal.votes_against = 600;
+        assert_eq!(
+            proposal.validate_execution(&config, 200),
+            Err(CrossChainError::ProposalNotPassed)
+        );
+
+        proposal.votes_for = 700;
+        assert_eq!(
+            proposal.validate_execution(&config, 169),
+            Err(CrossChainError::TimelockActive)
+        );
+        assert!(proposal.validate_execution(&config, 170).is_ok());
+
+        proposal.executed = true;
+        assert_eq!(
+            proposal.validate_execution(&config, 170),
+            Err(CrossChainError::TransactionAlreadyExecuted)
+        );
+    }
+
+    #[test]
+    fn test_fixture_instructions_gated() {
+        let data = CrossChainInstruction::Fixture {
+            instruction: FixtureInstruction::MintTestTokens { amount: 5 },
+        }
+        .try_to_vec()
+        .unwrap();
+        assert!(matches!(
+            CrossChainInstruction::try_from_slice(&data).unwrap(),
+            CrossChainInstruction::Fixture {
+                instruction: FixtureInstruction::MintTestTokens { amount: 5 }
+            }
+        ));
+
+        let result = process_instruction(&Pubkey::new_unique(), &[], &data);
+        if cfg!(feature = "test-fixtures") {
+            assert_eq!(result, Err(ProgramError::NotEnoughAccountKeys));
+        } else {
+            assert_eq!(result, Err(ProgramError::InvalidInstructionData));
+        }
+    }
+
+    #[test]
+    fn test_config_timelock_sensitive_changes() {
+        let current = CrossChainConfig {
+            config_timelock_slots: 1_000,
+            ..Default::default()
+        };
+
+        let mut paused = current.clone();
+        paused.paused = true;
+        paused.max_cross_chain_amount *= 2;
+        paused.supported_chains = vec![1, 10, 42161, 8453].into();
+        assert!(!current.is_sensitive_change(&paused));
+
+        let mut fee = current.clone();
+        fee.fee_basis_points += 1;
+        assert!(current.is_sensitive_change(&fee));
+
+        let mut rotated = current.clone();
+        rotated.bridge_authority = Pubkey::new_unique();
+        assert!(current.is_sensitive_change(&rotated));
+
+        let mut removed = current.clone();
+        removed.supported_chains = vec![1, 10].into();
+        assert!(current.is_sensitive_change(&removed));
+
+        let mut shortened = current.clone();
+        shortened.config_timelock_slots = 10;
+        assert!(current.is_sensitive_change(&shortened));
+        shortened.config_timelock_slots = 5_000;
+        assert!(!current.is_sensitive_change(&shortened));
+
+        let mut rechained = current.clone();
+        rechained.hub_chain_id = 900;
+        assert!(current.is_sensitive_change(&rechained));
+
+        assert_eq!(
+            ProgramError::from(CrossChainError::ConfigChangeTimelocked),
+            ProgramError::Custom(1116)
+        );
+    }
+
+    #[test]
+    fn test_wormhole_vaa_verification() {
+        use crate::bridge::wormhole::*;
+
+        let transfer = relay_transfer();
+        let mut message = sample_message(MessageType::TokenTransfer, vec![]);
+        message.source_chain = transfer.source_chain;
+        message.destination_chain = transfer.destination_chain;
+        message.nonce = transfer.nonce;
+        let payload = TransferPayload {
+            message,
+            amount: transfer.amount,
+            token_address: None,
+        };
+        let emitter = WormholeEmitter {
+            chain: 24,
+            address: [8u8; 32],
+        };
+
+        let vaa = PostedVaa {
+            vaa_version: 1,
+            consistency_level: 1,
+            vaa_time: 1_700_000_000,
+            vaa_signature_account: Pubkey::new_unique(),
+            submission_time: 1_700_000_010,
+            nonce: 0,
+            sequence: 7,
+            emitter_chain: emitter.chain,
+            emitter_address: emitter.address,
+            payload: payload.encode(),
+        };
+        let mut data = b"vaa".to_vec();
+        data.extend(vaa.try_to_vec().unwrap());
+        let posted = PostedVaa::unpack(&data).unwrap();
+        assert_eq!(posted, vaa);
+        assert_eq!(
+            PostedVaa::unpack(&data[1..]),
+            Err(CrossChainError::InvalidVaa)
+        );
+
+        assert!(verify_transfer_vaa(&posted, Some(&emitter), &transfer).is_ok());
+        assert_eq!(
+            verify_transfer_vaa(&posted, None, &transfer),
+            Err(CrossChainError::InvalidVaa)
+        );
+        let other_gateway = WormholeEmitter {
+            address: [9u8; 32],
+            ..emitter
+        };
+        assert_eq!(
+            verify_transfer_vaa(&posted, Some(&other_gateway), &transfer),
+            Err(CrossChainError::InvalidVaa)
+        );
+        let mut inflated = transfer.clone();
+        inflated.amount += 1;
+        assert_eq!(
+            verify_transfer_vaa(&posted, Some(&emitter), &inflated),
+            Err(CrossChainError::InvalidVaa)
+        );
+
+        // The posted account is seeded by the body hash, so a different
+        // sequence lands at a different address.
+        let core_bridge = Pubkey::new_unique();
+        let mut resequenced = vaa.clone();
+        resequenced.sequence += 1;
+        assert_ne!(
+            get_posted_vaa_address(&vaa.body_hash(), &core_bridge),
+            get_posted_vaa_address(&resequenced.body_hash(), &core_bridge)
+        );
+
+        let mut unversioned = payload.encode();
+        unversioned[0] = 0;
+        assert!(TransferPayload::decode(&unversioned).is_err());
+
+        let mut bridge_data = Vec::new();
+        bridge_data.extend(3u32.to_le_bytes());
+        bridge_data.extend(0u64.to_le_bytes());
+        bridge_data.extend(86_400u32.to_le_bytes());
+        bridge_data.extend(100u64.to_le_bytes());
+        assert_eq!(core_bridge_fee(&bridge_data), Ok(100));
+        assert!(core_bridge_fee(&bridge_data[..10]).is_err());
+
+        let emitter_key = get_wormhole_emitter_address(&crate::id()).0;
+        let ix = post_message_instruction(
+            &core_bridge,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &emitter_key,
+            vec![1, 2, 3],
+            1,
+        );
+        assert_eq!(ix.data[0], 1);
+        assert_eq!(ix.accounts.len(), 9);
+        assert!(ix.accounts[1].is_signer && ix.accounts[2].is_signer);
+        assert_eq!(
+            ix.accounts[3].pubkey,
+            get_core_sequence_address(&emitter_key, &core_bridge)
+        );
+    }
+
+    #[test]
+    fn test_axelar_gateway_approval() {
+        use crate::bridge::axelar::*;
+
+        let program_id = Pubkey::new_unique();
+        let transfer = relay_transfer();
+        let mut message = sample_message(MessageType::TokenTransfer, vec![]);
+        message.source_chain = transfer.source_chain;
+        message.destination_chain = transfer.destination_chain;
+        message.nonce = transfer.nonce;
+        let payload = TransferPayload {
+            message,
+            amount: transfer.amount,
+            token_address: None,
+        }
+        .encode();
+
+        let config = AxelarConfig {
+            gateway: Pubkey::new_unique(),
+            chain_name: "solana".into(),
+        };
+        let route = AxelarRoute {
+            chain_id: transfer.source_chain,
+            chain_name: "optimism".into(),
+            gateway_address: "0x4F4495243837681061C4743b74B3eEdf548D56A5".into(),
+            bump: 255,
+        };
+        let approval = AxelarApproval {
+            message_id: "0x5f3c-7".into(),
+            payload: payload.clone().into(),
+        };
+        let incoming = IncomingMessage {
+            bump: 254,
+            signing_pda_bump: 253,
+            pad: [0u8; 3],
+            status: 0,
+            message_hash: message_hash(&config, &route, &program_id, "0x5f3c-7", &payload),
+            payload_hash: solana_program::keccak::hash(&payload).to_bytes(),
+        };
+        assert_eq!(
+            IncomingMessage::unpack(&incoming.try_to_vec().unwrap()),
+            Ok(incoming.clone())
+        );
+        assert!(verify_gateway_approval(
+            &config,
+            &route,
+            transfer.source_chain,
+            &program_id,
+            &incoming,
+            &approval,
+            &transfer
+        )
+        .is_ok());
+
+        // Approved for another program, already executed, or for a
+        // different transfer: all rejected.
+        assert_eq!(
+            verify_gateway_approval(
+                &config,
+                &route,
+                transfer.source_chain,
+                &Pubkey::new_unique(),
+                &incoming,
+                &approval,
+                &transfer
+            ),
+            Err(CrossChainError::InvalidGatewayApproval)
+        );
+        let executed = IncomingMessage {
+            status: 1,
+            ..incoming.clone()
+        };
+        assert_eq!(
+            verify_gateway_approval(
+                &config,
+                &route,
+                transfer.source_chain,
+                &program_id,
+                &executed,
+                &approval,
+                &transfer
+            ),
+            Err(CrossChainError::InvalidGatewayApproval)
+        );
+        let mut inflated = transfer.clone();
+        inflated.amount += 1;
+        assert_eq!(
+            verify_gateway_approval(
+                &config,
+                &route,
+                transfer.source_chain,
+                &program_id,
+                &incoming,
+                &approval,
+                &inflated
+            ),
+            Err(CrossChainError::InvalidGatewayApproval)
+        );
+
+        // A padded route decodes despite its trailing zeroes.
+        let mut route_data = vec![0u8; ACCOUNT_HEADER_LEN + AxelarRoute::LEN];
+        route.pack_into(&mut route_data[..]).unwrap();
+        assert_eq!(AxelarRoute::unpack(&route_data).unwrap(), route);
+
+        assert_ne!(
+            command_id("optimism", "0x5f3c-7"),
+            command_id("optimism", "0x5f3c-8")
+        );
+        assert_eq!(TransportKind::default(), TransportKind::RelayerQuorum);
+        assert_eq!(
+            ProgramError::from(CrossChainError::EvidenceNotSupported),
+            ProgramError::Custom(1123)
+        );
+    }
+
+    #[test]
+    fn test_velocity_rules_alert() {
+        let rules = VelocityRules {
+            window_slots: 100,
+            max_transfers: 2,
+            max_amount: Some(1_000),
+            auto_hold: true,
+        };
+        let mut velocity = TransferVelocity::default();
+        assert_eq!(velocity.record(&rules, 100, 10), Ok(false));
+        assert_eq!(velocity.record(&rules, 100, 20), Ok(false));
+        assert_eq!(velocity.record(&rules, 100, 30), Ok(true));
+        assert_eq!(velocity.transfers, 3);
+        assert_eq!(velocity.window_start, 0);
+
+        // A new window starts once the previous one has elapsed.
+        assert_eq!(velocity.record(&rules, 100, 110), Ok(false));
+        assert_eq!(velocity.window_start, 110);
+        assert_eq!(velocity.transfers, 1);
+        assert_eq!(velocity.record(&rules, 901, 120), Ok(true));
+
+        // Disabled rules never alert and leave the tracker untouched.
+        let disabled = VelocityRules::default();
+        assert!(!disabled.is_enabled());
+        let before = velocity;
+        assert_eq!(velocity.record(&disabled, u64::MAX, 500), Ok(false));
+        assert_eq!(velocity, before);
+
+        let event = HubEvent::VelocityAlert {
+            sender: Pubkey::new_unique(),
+            record: Pubkey::new_unique(),
+            window_start: 110,
+            transfers: 2,
+            amount: 1_001,
+            held: true,
+        };
+        assert_eq!(parse_event(&event.encode()), Some(event));
+    }
+
+    #[test]
+    fn test_transfer_batch_validation() {
+        let sender = Pubkey::new_from_array([1u8; 32]);
+        let first = relay_transfer();
+        let second = CrossChainTransferData {
+            recipient: Pubkey::new_unique(),
+            nonce: first.nonce + 3,
+            ..first.clone()
+        };
+        assert_eq!(
+            validate_batch(&[first.clone(), second.clone()], &sender),
+            Ok(true)
+        );
+        assert_eq!(
+            validate_batch(&[], &sender),
+            Err(CrossChainError::InvalidBatch)
+        );
+        assert_eq!(
+            validate_batch(&[second.clone(), first.clone()], &sender),
+            Err(CrossChainError::InvalidBatch)
+        );
+        let other_chain = CrossChainTransferData {
+            destination_chain: 42,
+            ..second.clone()
+        };
+        assert_eq!(
+            validate_batch(&[first.clone(), other_chain], &sender),
+            Err(CrossChainError::InvalidBatch)
+        );
+        let token = CrossChainTransferData {
+            token_address: Some(Pubkey::new_unique()),
+            ..second.clone()
+        };
+        assert_eq!(
+            validate_batch(&[first.clone(), token], &sender),
+            Err(CrossChainError::InvalidBatch)
+        );
+        assert_eq!(
+            validate_batch(std::slice::from_ref(&first), &Pubkey::new_unique()),
+            Err(CrossChainError::InvalidBatch)
+        );
+
+        let entry = BatchEntry {
+            recipient: first.recipient,
+            nonce: first.nonce,
+            amount: 1_000_000,
+            fee: 2_500,
+        };
+        let batch = TransferBatch {
+            sender,
+            source_chain: first.source_chain,
+            destination_chain: first.destination_chain,
+            first_nonce: first.nonce,
+            entries: vec![entry; MAX_BATCH_TRANSFERS].into(),
+            escrowed_amount: 16_000_000,
+            fee: 40_000,
+            relayer_fee: 16_000,
+            status: TransferStatus::Initiated,
+            timestamp: 1_700_000_000,
+            created_slot: 100,
+            completed_slot: 0,
+            timeout_slots: 50,
+            gateway_codehash: [0u8; 32],
+            bump: 255,
+        };
+        assert_eq!(batch.try_to_vec().unwrap().len(), TransferBatch::LEN);
+        assert_eq!(batch.release_amount(), 15_960_000);
+
+        let mut small = batch.clone();
+        small.entries.truncate(2);
+        let mut data = vec![0u8; ACCOUNT_HEADER_LEN + TransferBatch::LEN];
+        small.pack_into(&mut data[..]).unwrap();
+        assert_eq!(TransferBatch::unpack(&data).unwrap().entries.len(), 2);
+
+        let batch_key = Pubkey::new_unique();
+        let settlement = batch.settlement_transfer(&batch_key);
+        assert_eq!(settlement.recipient, batch_key);
+        assert_eq!(settlement.amount, batch.escrowed_amount);
+        assert_eq!(settlement.nonce, batch.first_nonce);
+        assert!(validate_bridge_message(&batch.bridge_message(&batch_key)).is_ok());
+
+        assert_eq!(
+            batch.validate_refund(149),
+            Err(CrossChainError::RefundNotYetAvailable)
+        );
+        assert_eq!(batch.validate_refund(150), Ok(true));
+        let mut completed = batch.clone();
+        completed.status = TransferStatus::Completed;
+        assert_eq!(
+            completed.validate_refund(150),
+            Err(CrossChainError::TransferNotInFlight)
+        );
+
+        assert_eq!(
+            ProgramError::from(CrossChainError::InvalidBatch),
+            ProgramError::Custom(1126)
+        );
+    }
+
+    #[test]
+    fn test_chain_pair_policy() {
+        let mut policy = ChainPairPolicy::new(1, 42161);
+        assert_eq!(policy.check_amount(u64::MAX), Ok(true));
+        assert_eq!(policy.fee_basis_points(30), Ok(30));
+        assert_eq!(policy.required_confirmations(2), 2);
+        assert_eq!(policy.try_to_vec().unwrap().len(), ChainPairPolicy::LEN);
+        assert_eq!(
+            ChainPairPolicy::unpack(&policy.pack().unwrap()).unwrap(),
+            policy
+        );
+
+        policy.fee_multiplier_bps = 25_000;
+        policy.max_amount = 1_000;
+        policy.required_confirmations = 4;
+        assert_eq!(policy.fee_basis_points(30), Ok(75));
+        assert_eq!(policy.check_amount(1_000), Ok(true));
+        assert_eq!(
+            policy.check_amount(1_001),
+            Err(CrossChainError::AmountTooHigh)
+        );
+        assert_eq!(policy.required_confirmations(2), 4);
+        assert_eq!(policy.required_confirmations(5), 5);
+
+        assert_eq!(policy.validate(30), Ok(true));
+        // 6.5x a 200 bps rate would be 1,300 bps, past the cap.
+        policy.fee_multiplier_bps = 65_000;
+        assert_eq!(policy.validate(200), Err(CrossChainError::InvalidConfig));
+        assert_eq!(policy.validate(150), Ok(true));
+        // A rate raised after the corridor was set is still capped.
+        assert_eq!(policy.fee_basis_points(200), Ok(MAX_FEE_BASIS_POINTS));
+
+        policy.fee_multiplier_bps = 0;
+        assert_eq!(policy.fee_basis_points(MAX_FEE_BASIS_POINTS), Ok(0));
+
+        policy.enabled = false;
+        assert_eq!(
+            policy.check_amount(1),
+            Err(CrossChainError::CorridorDisabled)
+        );
+    }
+
+    #[test]
+    fn test_refund_token_account_must_belong_to_sender() {
+        use solana_program::program_pack::Pack;
+
+        let mint = Pubkey::new_unique();
+        let sender = Pubkey::new_unique();
+        let check = |owner: Pubkey, account_mint: Pubkey| {
+            let mut data = vec![0u8; spl_token::state::Account::LEN];
+            spl_token::state::Account {
+                mint: account_mint,
+                owner,
+                state: spl_token::state::AccountState::Initialized,
+                ..Default::default()
+            }
+            .pack_into_slice(&mut data);
+
+            let key = Pubkey::new_unique();
+            let token_program = spl_token::id();
+            let mut lamports = 0;
+            let account = AccountInfo::new(
+                &key,
+                false,
+                true,
+                &mut lamports,
+                &mut data,
+                &token_program,
+                false,
+                0,
+            );
+            validate_escrow_token_account(&account, &mint, &sender)
+        };
+
+        assert_eq!(check(sender, mint), Ok(()));
+        // An officer rejecting a hold cannot redirect the refund to an
+        // account of their own, nor to one for another mint.
+        assert_eq!(
+            check(Pubkey::new_unique(), mint),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            check(sender, Pubkey::new_unique()),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_outbound_source_is_hub_chain() {
+        let config = CrossChainConfig {
+            hub_chain_id: 900,
+            ..Default::default()
+        };
+        assert_eq!(config.check_outbound_source(900), Ok(true));
+        // Naming another source chain would select another corridor policy.
+        assert_eq!(
+            config.check_outbound_source(1),
+            Err(CrossChainError::SourceChainMismatch)
+        );
+        assert_eq!(
+            ProgramError::from(CrossChainError::SourceChainMismatch),
+            ProgramError::Custom(1149)
+        );
+
+        // Leaving the hub, delivery is confirmed by the destination chain.
+        let mut transfer = relay_transfer();
+        assert_eq!(transfer.confirming_chain(900), transfer.source_chain);
+        transfer.source_chain = 900;
+        assert_eq!(transfer.confirming_chain(900), transfer.destination_chain);
+
+        let mut state = CrossChainState {
+            config: CrossChainConfig::default(),
+            ..Default::default()
+        };
+        apply_config(&mut state, config.clone());
+        assert_eq!(state.config.hub_chain_id, 900);
+        apply_config(
+            &mut state,
+            CrossChainConfig {
+                hub_chain_id: 1,
+                ..config
+            },
+        );
+        assert_eq!(state.config.hub_chain_id, 900);
+    }
+
+    #[test]
+    fn test_bridge_and_chain_timelocked_changes() {
+        let current = BridgeConfig::default();
+        let mut timeout = current.clone();
+        timeout.transfer_timeout_slots *= 2;
+        timeout.claim_delivery = true;
+        assert!(!current.is_sensitive_change(&timeout));
+
+        let mut relayer_fee = current.clone();
+        relayer_fee.relayer_fee += 1;
+        assert!(current.is_sensitive_change(&relayer_fee));
+        let mut cancellation = current.clone();
+        cancellation.cancellation_fee_bps += 1;
+        assert!(current.is_sensitive_change(&cancellation));
+        let mut quorum = current.clone();
+        quorum.required_relayer_signatures += 1;
+        assert!(current.is_sensitive_change(&quorum));
+        let mut stake = current.clone();
+        stake.min_relayer_stake /= 2;
+        assert!(current.is_sensitive_change(&stake));
+
+        let chain = ChainConfig {
+            chain_id: 10,
+            enabled: true,
+            min_eidas_level: EidasLevel::None,
+            inbound_enabled: true,
+            bump: 255,
+            caip2: Caip2ChainId::from_legacy(10),
+            encryption_key: [0u8; 32],
+            gateway_codehash: [0u8; 32],
+            outbound_volume: 0,
+            inbound_volume: 0,
+            rate_limit: RateLimit::default(),
+            wormhole_emitter: Default::default(),
+            transport: TransportKind::default(),
+        };
+        let data = ChainConfigData {
+            enabled: true,
+            min_eidas_level: EidasLevel::Substantial,
+            inbound_enabled: true,
+            encryption_key: [1u8; 32],
+            gateway_codehash: [0u8; 32],
+            wormhole_emitter: Default::default(),
+            transport: TransportKind::default(),
+        };
+        assert!(!chain.is_sensitive_change(&data));
+        // Cutting a chain off stays immediate.
+        assert!(!chain.is_sensitive_change(&ChainConfigData {
+            enabled: false,
+            inbound_enabled: false,
+            ..data.clone()
+        }));
+        assert!(chain.is_sensitive_change(&ChainConfigData {
+            transport: TransportKind::Wormhole,
+            ..data.clone()
+        }));
+        assert!(chain.is_sensitive_change(&ChainConfigData {
+            gateway_codehash: [2u8; 32],
+            ..data.clone()
+        }));
+        let disabled = ChainConfig {
+            enabled: false,
+            ..chain
+        };
+        assert!(disabled.is_sensitive_change(&data));
+
+        let policy = ChainPairPolicy::new(900, 10);
+        let mut tightened = policy.clone();
+        tightened.enabled = false;
+        tightened.max_amount = 1_000;
+        tightened.required_confirmations = 3;
+        assert!(!policy.is_sensitive_change(&tightened));
+        assert!(tightened.is_sensitive_change(&policy));
+        let mut repriced = policy.clone();
+        repriced.fee_multiplier_bps = 15_000;
+        assert!(policy.is_sensitive_change(&repriced));
+    }
 }
@@ -0,0 +1,360 @@
+use crate::account::VersionedAccount;
+use crate::bounded::BoundedVec;
+use crate::bridge::ChainPairPolicy;
+use crate::error::CrossChainError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::Pubkey;
+
+pub const TREASURY_SEED: &[u8] = b"treasury";
+pub const TREASURY_REPORT_SEED: &[u8] = b"treasury_report";
+pub const MAX_FEE_ASSETS: usize = 16;
+pub const MAX_CHAIN_FEE_ENTRIES: usize = 64;
+pub const FEE_HISTORY_SEED: &[u8] = b"fee_history";
+pub const FEE_HISTORY_CAPACITY: usize = 64;
+
+/// Fees collected for one asset; `mint` is `None` for native lamports.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct FeeAccrual {
+    pub mint: Option<Pubkey>,
+    pub protocol: u64,
+    pub relayer: u64,
+}
+
+/// Fees ever collected in one asset on transfers to one chain. Totals only
+/// grow; withdrawals are tracked per asset in `FeeAccrual`, since the
+/// treasury holds each asset in a single balance whatever chain it came from.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct ChainFeeAccrual {
+    pub chain_id: u64,
+    pub mint: Option<Pubkey>,
+    pub collected: u64,
+}
+
+impl ChainFeeAccrual {
+    pub const LEN: usize = 8 + 33 + 8;
+}
+
+/// Fees held by the treasury PDA and its token accounts, split by who may
+/// withdraw them: protocol fees by the admin, relayer fees by the bridge
+/// authority.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct FeeLedger {
+    pub entries: Vec<FeeAccrual>,
+    pub by_chain: Vec<ChainFeeAccrual>,
+}
+
+impl FeeLedger {
+    pub fn get(&self, mint: Option<Pubkey>) -> Option<&FeeAccrual> {
+        self.entries.iter().find(|e| e.mint == mint)
+    }
+
+    pub fn accrue(
+        &mut self,
+        mint: Option<Pubkey>,
+        protocol: u64,
+        relayer: u64,
+    ) -> Result<bool, CrossChainError> {
+        let index = match self.entries.iter().position(|e| e.mint == mint) {
+            Some(index) => index,
+            None => {
+                if self.entries.len() >= MAX_FEE_ASSETS {
+                    return Err(CrossChainError::FeeLedgerFull);
+                }
+                self.entries.push(FeeAccrual {
+                    mint,
+                    protocol: 0,
+                    relayer: 0,
+                });
+                self.entries.len() - 1
+            }
+        };
+
+        let entry = &mut self.entries[index];
+        entry.protocol = entry.protocol.saturating_add(protocol);
+        entry.relayer = entry.relayer.saturating_add(relayer);
+        Ok(true)
+    }
+
+    /// Attributes `fee` in `mint` to `chain_id` in the per-chain totals.
+    pub fn record_chain(
+        &mut self,
+        chain_id: u64,
+        mint: Option<Pubkey>,
+        fee: u64,
+    ) -> Result<bool, CrossChainError> {
+        match self
+            .by_chain
+            .iter_mut()
+            .find(|e| e.chain_id == chain_id && e.mint == mint)
+        {
+            Some(entry) => entry.collected = entry.collected.saturating_add(fee),
+            None => {
+                if self.by_chain.len() >= MAX_CHAIN_FEE_ENTRIES {
+                    return Err(CrossChainError::FeeLedgerFull);
+                }
+                self.by_chain.push(ChainFeeAccrual {
+                    chain_id,
+                    mint,
+                    collected: fee,
+                });
+            }
+        }
+        Ok(true)
+    }
+
+    pub fn chain_total(&self, chain_id: u64, mint: Option<Pubkey>) -> u64 {
+        self.by_chain
+            .iter()
+            .find(|e| e.chain_id == chain_id && e.mint == mint)
+            .map_or(0, |e| e.collected)
+    }
+
+    pub fn debit_protocol(
+        &mut self,
+        mint: Option<Pubkey>,
+        amount: u64,
+    ) -> Result<bool, CrossChainError> {
+        let entry = self.entry_mut(mint)?;
+        entry.protocol = entry
+            .protocol
+            .checked_sub(amount)
+            .ok_or(CrossChainError::InsufficientAccruedFees)?;
+        Ok(true)
+    }
+
+    pub fn debit_relayer(
+        &mut self,
+        mint: Option<Pubkey>,
+        amount: u64,
+    ) -> Result<bool, CrossChainError> {
+        let entry = self.entry_mut(mint)?;
+        entry.relayer = entry
+            .relayer
+            .checked_sub(amount)
+            .ok_or(CrossChainError::InsufficientAccruedFees)?;
+        Ok(true)
+    }
+
+    fn entry_mut(&mut self, mint: Option<Pubkey>) -> Result<&mut FeeAccrual, CrossChainError> {
+        self.entries
+            .iter_mut()
+            .find(|e| e.mint == mint)
+            .ok_or(CrossChainError::InsufficientAccruedFees)
+    }
+}
+
+/// Snapshot of the fee ledger published to its own PDA, so accounting tools
+/// can read fee balances per asset and per chain without decoding the hub
+/// state.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct TreasuryReport {
+    pub slot: u64,
+    pub assets: Vec<FeeAccrual>,
+    pub chains: Vec<ChainFeeAccrual>,
+    pub bump: u8,
+}
+
+impl VersionedAccount for TreasuryReport {
+    const DISCRIMINATOR: [u8; 8] = *b"RQTREPRT";
+    const VERSION: u8 = 1;
+}
+
+impl TreasuryReport {
+    pub const LEN: usize = 8
+        + 4
+        + MAX_FEE_ASSETS * (33 + 8 + 8)
+        + 4
+        + MAX_CHAIN_FEE_ENTRIES * ChainFeeAccrual::LEN
+        + 1;
+
+    pub fn from_ledger(ledger: &FeeLedger, slot: u64, bump: u8) -> Self {
+        Self {
+            slot,
+            assets: ledger.entries.clone(),
+            chains: ledger.by_chain.clone(),
+            bump,
+        }
+    }
+}
+
+/// Protocol and relayer fee in force from `effective_slot` until the next
+/// schedule takes over.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeSchedule {
+    pub fee_basis_points: u16,
+    pub relayer_fee: u64,
+    pub effective_slot: u64,
+}
+
+impl FeeSchedule {
+    pub const LEN: usize = 2 + 8 + 8;
+}
+
+/// A corridor's fee multiplier in force from `effective_slot`, scaling
+/// whichever hub-wide schedule is in force alongside it.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CorridorFeeSchedule {
+    pub source_chain: u64,
+    pub destination_chain: u64,
+    pub fee_multiplier_bps: u16,
+    pub effective_slot: u64,
+}
+
+impl CorridorFeeSchedule {
+    pub const LEN: usize = 8 + 8 + 2 + 8;
+}
+
+/// The last `FEE_HISTORY_CAPACITY` fee schedules, oldest first, so auditors
+/// and the indexer can tell which fee applied to a transfer from its
+/// `created_slot`, and as many corridor multiplier changes, which apply on
+/// top.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct FeeScheduleHistory {
+    pub entries: BoundedVec<FeeSchedule, FEE_HISTORY_CAPACITY>,
+    /// Schedules dropped to stay within capacity.
+    pub evicted: u64,
+    pub corridors: BoundedVec<CorridorFeeSchedule, FEE_HISTORY_CAPACITY>,
+    /// Corridor entries dropped to stay within capacity.
+    pub corridors_evicted: u64,
+    pub bump: u8,
+}
+
+impl VersionedAccount for FeeScheduleHistory {
+    const DISCRIMINATOR: [u8; 8] = *b"RQFEEHST";
+    const VERSION: u8 = 2;
+}
+
+impl FeeScheduleHistory {
+    pub const LEN: usize = 4
+        + FEE_HISTORY_CAPACITY * FeeSchedule::LEN
+        + 8
+        + 4
+        + FEE_HISTORY_CAPACITY * CorridorFeeSchedule::LEN
+        + 8
+        + 1;
+
+    pub fn new(bump: u8) -> Self {
+        Self {
+            entries: BoundedVec::new(),
+            evicted: 0,
+            corridors: BoundedVec::new(),
+            corridors_evicted: 0,
+            bump,
+        }
+    }
+
+    /// Appends `schedule` unless its fees match the current entry, dropping
+    /// the oldest entry when full. A second change in the same slot replaces
+    /// the first. Returns whether the history changed.
+    pub fn record(&mut self, schedule: FeeSchedule) -> bool {
+        if let Some(current) = self.entries.last_mut() {
+            if current.fee_basis_points == schedule.fee_basis_points
+                && current.relayer_fee == schedule.relayer_fee
+            {
+                return false;
+            }
+
+            if current.effective_slot == schedule.effective_slot {
+                *current = schedule;
+                return true;
+            }
+        }
+
+        if self.entries.len() >= FEE_HISTORY_CAPACITY {
+            self.entries.remove(0);
+            self.evicted += 1;
+        }
+        self.entries.push(schedule);
+        true
+    }
+
+    /// Schedule in force at `slot`, or `None` if `slot` predates the
+    /// retained history.
+    pub fn at(&self, slot: u64) -> Option<&FeeSchedule> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|schedule| schedule.effective_slot <= slot)
+    }
+
+    /// Appends a corridor's multiplier unless it matches that corridor's
+    /// latest entry, with the same eviction and same-slot rules as `record`.
+    pub fn record_corridor(&mut self, schedule: CorridorFeeSchedule) -> bool {
+        let latest = self.corridors.iter().rposition(|current| {
+            current.source_chain == schedule.source_chain
+                && current.destination_chain == schedule.destination_chain
+        });
+        if let Some(index) = latest {
+            let current = &mut self.corridors[index];
+            if current.fee_multiplier_bps == schedule.fee_multiplier_bps {
+                return false;
+            }
+
+            if current.effective_slot == schedule.effective_slot {
+                *current = schedule;
+                return true;
+            }
+        }
+
+        if self.corridors.len() >= FEE_HISTORY_CAPACITY {
+            self.corridors.remove(0);
+            self.corridors_evicted += 1;
+        }
+        self.corridors.push(schedule);
+        true
+    }
+
+    /// A corridor's multiplier entry in force at `slot`, or `None` if the
+    /// corridor had none retained by then.
+    pub fn corridor_at(
+        &self,
+        source_chain: u64,
+        destination_chain: u64,
+        slot: u64,
+    ) -> Option<&CorridorFeeSchedule> {
+        self.corridors.iter().rev().find(|schedule| {
+            schedule.source_chain == source_chain
+                && schedule.destination_chain == destination_chain
+                && schedule.effective_slot <= slot
+        })
+    }
+
+    /// Rate a transfer on the corridor initiated at `slot` was charged: the
+    /// hub schedule scaled by the corridor multiplier, or the default
+    /// multiplier if the corridor never had one. `None` when either part
+    /// may have been evicted.
+    pub fn fee_basis_points_at(
+        &self,
+        source_chain: u64,
+        destination_chain: u64,
+        slot: u64,
+    ) -> Option<u16> {
+        let base = self.at(slot)?.fee_basis_points;
+        let mut policy = ChainPairPolicy::new(source_chain, destination_chain);
+        match self.corridor_at(source_chain, destination_chain, slot) {
+            Some(schedule) => policy.fee_multiplier_bps = schedule.fee_multiplier_bps,
+            None if self.corridors_evicted > 0 => return None,
+            None => {}
+        }
+        policy.fee_basis_points(base).ok()
+    }
+}
+
+pub fn get_fee_history_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[FEE_HISTORY_SEED], program_id)
+}
+
+pub fn get_treasury_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_SEED], program_id)
+}
+
+pub fn get_treasury_report_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_REPORT_SEED], program_id)
+}
+
+/// Splits a transfer fee into its protocol and relayer parts. The relayer part
+/// is the flat fee recorded at initiation, capped by the total.
+pub fn split_fee(fee: u64, relayer_fee: u64) -> (u64, u64) {
+    let relayer = relayer_fee.min(fee);
+    (fee - relayer, relayer)
+}
@@ -1,7 +1,38 @@
-use crate::eidas::EidasLevel;
+use crate::account::VersionedAccount;
+use crate::bounded::{BoundedBytes, BoundedVec};
+use crate::domain::SigningDomain;
+use crate::eidas::{ComplianceProfile, EidasLevel, VelocityRules};
+use crate::error::CrossChainError;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
 
+pub const WALLET_SEED: &[u8] = b"wallet";
+pub const ACTIVITY_LOG_SEED: &[u8] = b"activity";
+pub const NONCE_REGISTRY_SEED: &[u8] = b"nonce";
+pub const WALLET_CONFIG_SEED: &[u8] = b"wallet_config";
+pub const RECIPIENT_LIST_SEED: &[u8] = b"recipient_list";
+pub const SECOND_FACTOR_SEED: &[u8] = b"second_factor";
+pub const SESSION_KEY_SEED: &[u8] = b"session_key";
+pub const GUARDIANS_SEED: &[u8] = b"guardians";
+pub const META_TRANSACTION_DOMAIN: &[u8] = b"rivicq-meta-tx-v1";
+pub const ACTIVITY_LOG_CAPACITY: usize = 16;
+pub const MAX_RECORD_ID_LEN: usize = 32;
+pub const MAX_RECORD_TX_HASH_LEN: usize = 32;
+/// Uncompressed secp256k1 is the largest key a wallet registers.
+pub const MAX_WALLET_PUBLIC_KEY_LEN: usize = 65;
+pub const MAX_WALLET_METADATA_LEN: usize = 256;
+pub const MAX_TRANSACTION_HASH_LEN: usize = 64;
+pub const MAX_WALLET_ALLOWED_CHAINS: usize = 32;
+pub const MAX_RECIPIENT_LIST_ENTRIES: usize = 64;
+pub const MAX_SESSION_KEY_CHAINS: usize = 8;
+pub const MAX_GUARDIANS: usize = 10;
+/// Roughly two days; how long an owner who lost their second device waits
+/// before a replacement takes effect, giving the old device time to object.
+pub const SECOND_FACTOR_RECOVERY_DELAY_SLOTS: u64 = 432_000;
+/// Roughly three days; how long a guardian-proposed owner waits before the
+/// recovery can execute, during which the current owner can veto it.
+pub const SOCIAL_RECOVERY_DELAY_SLOTS: u64 = 648_000;
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct Wallet {
     pub owner: Pubkey,
@@ -10,17 +41,79 @@ pub struct Wallet {
     pub eidas_level: EidasLevel,
     pub public_key: Vec<u8>,
     pub metadata: Vec<u8>,
+    pub kyc_verified: bool,
+    pub aml_screened: bool,
+    /// Registered below the hub's minimum eIDAS level under a quarantine
+    /// policy. The wallet cannot transact until `VerifyCompliance` lifts it.
+    pub pending_verification: bool,
+    /// Frozen by the eIDAS authority. The wallet cannot sign, bridge,
+    /// transfer or execute multisig transactions until `UnfreezeWallet`.
+    pub frozen: bool,
+}
+
+impl VersionedAccount for Wallet {
+    const DISCRIMINATOR: [u8; 8] = *b"RQWALLET";
+    const VERSION: u8 = 3;
+}
+
+impl Wallet {
+    pub fn compliance_profile(&self) -> ComplianceProfile {
+        ComplianceProfile {
+            eidas_level: self.eidas_level.clone(),
+            kyc_verified: self.kyc_verified,
+            aml_screened: self.aml_screened,
+        }
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct WalletData {
-    pub public_key: Vec<u8>,
-    pub metadata: Vec<u8>,
+    pub public_key: BoundedBytes<MAX_WALLET_PUBLIC_KEY_LEN>,
+    pub metadata: BoundedBytes<MAX_WALLET_METADATA_LEN>,
+    /// Level attested at registration. Anything above `None` must be
+    /// co-signed by the eIDAS authority.
+    pub eidas_level: EidasLevel,
+}
+
+/// Admission rules for `RegisterWallet`. With the default policy any wallet
+/// may register. Regulated deployments raise `min_eidas_level` and either
+/// reject weaker registrations or, with `quarantine_unverified`, admit them
+/// in a pending state that cannot transact.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegistrationPolicy {
+    pub min_eidas_level: EidasLevel,
+    pub quarantine_unverified: bool,
+}
+
+impl RegistrationPolicy {
+    /// Whether a wallet registering at `attested` starts out quarantined.
+    pub fn admit(&self, attested: &EidasLevel) -> Result<bool, CrossChainError> {
+        if *attested >= self.min_eidas_level {
+            return Ok(false);
+        }
+
+        if !self.quarantine_unverified {
+            return Err(CrossChainError::EidasLevelBelowMinimum);
+        }
+
+        Ok(true)
+    }
+}
+
+pub fn validate_wallet_active(wallet: &Wallet) -> Result<bool, CrossChainError> {
+    if wallet.frozen {
+        return Err(CrossChainError::WalletFrozen);
+    }
+
+    if wallet.pending_verification {
+        return Err(CrossChainError::WalletPendingVerification);
+    }
+    Ok(true)
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct TransactionSignatureData {
-    pub transaction_hash: Vec<u8>,
+    pub transaction_hash: BoundedBytes<MAX_TRANSACTION_HASH_LEN>,
     pub amount: u64,
     pub recipient: Pubkey,
     pub source_chain: u64,
@@ -28,19 +121,41 @@ pub struct TransactionSignatureData {
     pub nonce: u64,
 }
 
+impl TransactionSignatureData {
+    /// Digest a wallet key signs for a meta-transaction.
+    pub fn signing_hash(&self, domain: &SigningDomain, owner: &Pubkey) -> [u8; 32] {
+        let payload = self.try_to_vec().unwrap_or_default();
+        domain.hash(META_TRANSACTION_DOMAIN, &[owner.as_ref(), &payload])
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct MultiSigWallet {
     pub owners: Vec<Pubkey>,
     pub threshold: u8,
     pub created_at: i64,
     pub eidas_required: bool,
+    pub transaction_count: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl MultiSigWallet {
+    /// Allocated size; the owner list is padded up to `MAX_MULTISIG_OWNERS`.
+    pub const LEN: usize = 4 + 32 * crate::multisig::MAX_MULTISIG_OWNERS + 1 + 8 + 1 + 8 + 1 + 1;
+
+    pub fn owner_index(&self, key: &Pubkey) -> Option<usize> {
+        self.owners.iter().position(|owner| owner == key)
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct WalletConfig {
     pub min_balance: u64,
     pub max_daily_transfer: u64,
-    pub allowed_chains: Vec<u64>,
+    /// Destination chains the owner accepts outbound transfers to; empty
+    /// leaves every registered chain open.
+    pub allowed_chains: BoundedVec<u64, MAX_WALLET_ALLOWED_CHAINS>,
     pub require_eidas: bool,
     pub require_2fa: bool,
     pub enable_allowlist: bool,
@@ -52,7 +167,7 @@ impl Default for WalletConfig {
         Self {
             min_balance: 0,
             max_daily_transfer: u64::MAX,
-            allowed_chains: vec![1, 10, 42161, 8453],
+            allowed_chains: BoundedVec::new(),
             require_eidas: false,
             require_2fa: false,
             enable_allowlist: false,
@@ -61,6 +176,413 @@ impl Default for WalletConfig {
     }
 }
 
+impl WalletConfig {
+    pub const MAX_LEN: usize = 8 + 8 + 4 + MAX_WALLET_ALLOWED_CHAINS * 8 + 4;
+
+    pub fn allows_chain(&self, chain_id: u64) -> bool {
+        self.allowed_chains.is_empty() || self.allowed_chains.contains(&chain_id)
+    }
+}
+
+/// An owner's `WalletConfig`, stored at `[b"wallet_config", owner]`. Wallets
+/// registered before the account existed behave as if it held the default.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct WalletConfigRecord {
+    pub owner: Pubkey,
+    pub config: WalletConfig,
+    pub bump: u8,
+}
+
+impl WalletConfigRecord {
+    pub const LEN: usize = 32 + WalletConfig::MAX_LEN + 1;
+}
+
+impl VersionedAccount for WalletConfigRecord {
+    const DISCRIMINATOR: [u8; 8] = *b"RQWALCFG";
+    const VERSION: u8 = 1;
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipientListKind {
+    /// Transfers may only go to listed recipients.
+    Allow,
+    /// Transfers may not go to listed recipients.
+    Block,
+}
+
+/// Recipients an owner's transfers are restricted to or barred from, stored
+/// at `[b"recipient_list", owner, kind]`. A list is only consulted while the
+/// owner's `WalletConfig` enables it.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct RecipientList {
+    pub owner: Pubkey,
+    pub kind: RecipientListKind,
+    pub entries: BoundedVec<Pubkey, MAX_RECIPIENT_LIST_ENTRIES>,
+    pub bump: u8,
+}
+
+impl RecipientList {
+    pub const LEN: usize = 32 + 1 + 4 + MAX_RECIPIENT_LIST_ENTRIES * 32 + 1;
+
+    pub fn new(owner: Pubkey, kind: RecipientListKind, bump: u8) -> Self {
+        Self {
+            owner,
+            kind,
+            entries: BoundedVec::new(),
+            bump,
+        }
+    }
+
+    /// Adds `recipient`, returning whether it was not already listed.
+    pub fn add(&mut self, recipient: Pubkey) -> Result<bool, CrossChainError> {
+        if self.entries.contains(&recipient) {
+            return Ok(false);
+        }
+
+        if self.entries.len() >= MAX_RECIPIENT_LIST_ENTRIES {
+            return Err(CrossChainError::RecipientListFull);
+        }
+
+        self.entries.push(recipient);
+        Ok(true)
+    }
+
+    /// Removes `recipient`, returning whether it was listed.
+    pub fn remove(&mut self, recipient: &Pubkey) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry != recipient);
+        self.entries.len() != before
+    }
+}
+
+impl VersionedAccount for RecipientList {
+    const DISCRIMINATOR: [u8; 8] = *b"RQRCPLST";
+    const VERSION: u8 = 1;
+}
+
+/// Applies whichever of the owner's recipient lists `config` enables. An
+/// enabled allowlist that was never created admits no one.
+pub fn validate_recipient(
+    config: &WalletConfig,
+    allowlist: Option<&RecipientList>,
+    blocklist: Option<&RecipientList>,
+    recipient: &Pubkey,
+) -> Result<bool, CrossChainError> {
+    if config.enable_allowlist && !allowlist.is_some_and(|list| list.entries.contains(recipient)) {
+        return Err(CrossChainError::RecipientNotAllowed);
+    }
+
+    if config.enable_blocklist && blocklist.is_some_and(|list| list.entries.contains(recipient)) {
+        return Err(CrossChainError::RecipientBlocked);
+    }
+
+    Ok(true)
+}
+
+/// A secondary device an owner registered, stored at
+/// `[b"second_factor", owner]`. While the owner's `WalletConfig` requires
+/// 2FA, operations moving more than `threshold` must be co-signed by
+/// `device`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SecondFactor {
+    pub owner: Pubkey,
+    pub device: Pubkey,
+    pub threshold: u64,
+    /// Replacement proposed without the current device, applied once
+    /// `recovery_slot` is reached.
+    pub pending_device: Option<Pubkey>,
+    pub recovery_slot: u64,
+    pub bump: u8,
+}
+
+impl SecondFactor {
+    pub const LEN: usize = 32 + 32 + 8 + 33 + 8 + 1;
+
+    pub fn new(owner: Pubkey, device: Pubkey, threshold: u64, bump: u8) -> Self {
+        Self {
+            owner,
+            device,
+            threshold,
+            pending_device: None,
+            recovery_slot: 0,
+            bump,
+        }
+    }
+
+    /// Owner-only rotation to `device`. The first call starts the recovery
+    /// delay; calling again with the same device once it has elapsed applies
+    /// it. Returns whether the rotation was applied.
+    pub fn recover(&mut self, device: Pubkey, slot: u64) -> Result<bool, CrossChainError> {
+        if self.pending_device != Some(device) {
+            self.pending_device = Some(device);
+            self.recovery_slot = slot.saturating_add(SECOND_FACTOR_RECOVERY_DELAY_SLOTS);
+            return Ok(false);
+        }
+
+        if slot < self.recovery_slot {
+            return Err(CrossChainError::SecondFactorRecoveryLocked);
+        }
+
+        self.device = device;
+        self.pending_device = None;
+        self.recovery_slot = 0;
+        Ok(true)
+    }
+}
+
+impl VersionedAccount for SecondFactor {
+    const DISCRIMINATOR: [u8; 8] = *b"RQ2NDFAC";
+    const VERSION: u8 = 1;
+}
+
+/// Requires the second factor to have co-signed when `config` enables 2FA
+/// and `amount` exceeds the owner's threshold. `cosigner` is the key that
+/// signed in the co-signer slot, if any. Enabling 2FA without registering a
+/// device blocks every operation it covers.
+pub fn validate_second_factor(
+    config: &WalletConfig,
+    factor: Option<&SecondFactor>,
+    amount: u64,
+    cosigner: Option<&Pubkey>,
+) -> Result<bool, CrossChainError> {
+    if !config.require_2fa {
+        return Ok(true);
+    }
+
+    match factor {
+        Some(factor) if amount <= factor.threshold || cosigner == Some(&factor.device) => Ok(true),
+        _ => Err(CrossChainError::SecondFactorRequired),
+    }
+}
+
+/// Owner instructions a session key may sign in the owner's place.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionPermissions(pub u8);
+
+impl SessionPermissions {
+    pub const SIGN_TRANSACTION: SessionPermissions = SessionPermissions(1 << 0);
+    pub const INITIATE_CROSS_CHAIN: SessionPermissions = SessionPermissions(1 << 1);
+
+    pub fn contains(&self, permissions: SessionPermissions) -> bool {
+        permissions.0 != 0 && self.0 & permissions.0 == permissions.0
+    }
+}
+
+/// Scope an owner grants a session key with `IssueSessionKey`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SessionKeyData {
+    /// Last slot the key may sign in.
+    pub expires_slot: u64,
+    /// Largest amount a single operation signed by the key may move.
+    pub max_amount: u64,
+    pub permissions: SessionPermissions,
+    /// Destination chains the key may target; empty allows any chain the
+    /// wallet config allows.
+    pub allowed_chains: BoundedVec<u64, MAX_SESSION_KEY_CHAINS>,
+}
+
+/// A key an owner delegated a scoped, expiring subset of its authority to,
+/// stored at `[b"session_key", owner, key]`. The key signs and pays in the
+/// owner's place; every other wallet rule still applies as if the owner had
+/// signed.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct SessionKey {
+    pub owner: Pubkey,
+    pub key: Pubkey,
+    pub scope: SessionKeyData,
+    pub bump: u8,
+}
+
+impl SessionKey {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 1 + 4 + MAX_SESSION_KEY_CHAINS * 8 + 1;
+
+    /// Checks an operation moving `amount` to `destination_chain` at `slot`
+    /// is within the key's scope.
+    pub fn authorize(
+        &self,
+        permission: SessionPermissions,
+        amount: u64,
+        destination_chain: u64,
+        slot: u64,
+    ) -> Result<bool, CrossChainError> {
+        if slot > self.scope.expires_slot {
+            return Err(CrossChainError::SessionKeyExpired);
+        }
+
+        if !self.scope.permissions.contains(permission)
+            || amount > self.scope.max_amount
+            || !(self.scope.allowed_chains.is_empty()
+                || self.scope.allowed_chains.contains(&destination_chain))
+        {
+            return Err(CrossChainError::SessionKeyOutOfScope);
+        }
+
+        Ok(true)
+    }
+}
+
+impl VersionedAccount for SessionKey {
+    const DISCRIMINATOR: [u8; 8] = *b"RQSESSKY";
+    const VERSION: u8 = 1;
+}
+
+/// A guardian-proposed owner rotation, the guardians backing it, and the
+/// guardians who have since proposed someone else.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct PendingRecovery {
+    pub new_owner: Pubkey,
+    pub approvals: BoundedVec<Pubkey, MAX_GUARDIANS>,
+    pub objections: BoundedVec<Pubkey, MAX_GUARDIANS>,
+    pub executable_slot: u64,
+}
+
+impl PendingRecovery {
+    fn new(new_owner: Pubkey, slot: u64) -> Self {
+        Self {
+            new_owner,
+            approvals: BoundedVec::new(),
+            objections: BoundedVec::new(),
+            executable_slot: slot.saturating_add(SOCIAL_RECOVERY_DELAY_SLOTS),
+        }
+    }
+}
+
+/// Guardians an owner trusts to move the wallet to a new key, stored at
+/// `[b"guardians", owner]`. Once `threshold` of them back the same new
+/// owner and `SOCIAL_RECOVERY_DELAY_SLOTS` pass without a veto, the wallet
+/// can be recovered.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
+pub struct GuardianSet {
+    pub owner: Pubkey,
+    pub guardians: BoundedVec<Pubkey, MAX_GUARDIANS>,
+    pub threshold: u8,
+    pub pending: Option<PendingRecovery>,
+    pub bump: u8,
+}
+
+impl GuardianSet {
+    pub const LEN: usize = 32
+        + 4
+        + MAX_GUARDIANS * 32
+        + 1
+        + 1
+        + 32
+        + 4
+        + MAX_GUARDIANS * 32
+        + 4
+        + MAX_GUARDIANS * 32
+        + 8
+        + 1;
+
+    /// Guardians must be distinct, exclude the owner, and number at least
+    /// `threshold`, which must be non-zero.
+    pub fn new(
+        owner: Pubkey,
+        guardians: BoundedVec<Pubkey, MAX_GUARDIANS>,
+        threshold: u8,
+        bump: u8,
+    ) -> Result<Self, CrossChainError> {
+        for (i, guardian) in guardians.iter().enumerate() {
+            if *guardian == owner || guardians[..i].contains(guardian) {
+                return Err(CrossChainError::InvalidGuardianSet);
+            }
+        }
+
+        if threshold == 0 || threshold as usize > guardians.len() {
+            return Err(CrossChainError::InvalidGuardianSet);
+        }
+
+        Ok(Self {
+            owner,
+            guardians,
+            threshold,
+            pending: None,
+            bump,
+        })
+    }
+
+    /// Records `guardian` backing `new_owner`. The first call opens the veto
+    /// window; other guardians approve by proposing the same owner. Proposing
+    /// a different owner withdraws the guardian's approval and objects to the
+    /// pending recovery instead; once `threshold` guardians object, it is
+    /// replaced by this proposal, so one guardian cannot hold the wallet's
+    /// recovery hostage. Returns whether the approval or objection is new.
+    pub fn propose(
+        &mut self,
+        guardian: Pubkey,
+        new_owner: Pubkey,
+        slot: u64,
+    ) -> Result<bool, CrossChainError> {
+        if !self.guardians.contains(&guardian) {
+            return Err(CrossChainError::NotGuardian);
+        }
+
+        if new_owner == self.owner {
+            return Err(CrossChainError::InvalidGuardianSet);
+        }
+
+        let threshold = self.threshold as usize;
+        let pending = match &mut self.pending {
+            Some(pending) if pending.new_owner != new_owner => {
+                pending.approvals.retain(|approval| *approval != guardian);
+                if pending.objections.contains(&guardian) {
+                    return Ok(false);
+                }
+
+                pending.objections.push(guardian);
+                if pending.objections.len() < threshold {
+                    return Ok(true);
+                }
+
+                self.pending.insert(PendingRecovery::new(new_owner, slot))
+            }
+            Some(pending) => pending,
+            None => self.pending.insert(PendingRecovery::new(new_owner, slot)),
+        };
+
+        pending
+            .objections
+            .retain(|objection| *objection != guardian);
+        if pending.approvals.contains(&guardian) {
+            return Ok(false);
+        }
+
+        pending.approvals.push(guardian);
+        Ok(true)
+    }
+
+    /// Cancels the pending recovery, returning the owner it named.
+    pub fn veto(&mut self) -> Result<Pubkey, CrossChainError> {
+        self.pending
+            .take()
+            .map(|pending| pending.new_owner)
+            .ok_or(CrossChainError::NoPendingRecovery)
+    }
+
+    /// The owner the wallet can be recovered to at `slot`.
+    pub fn recovery_owner(&self, slot: u64) -> Result<Pubkey, CrossChainError> {
+        let pending = self
+            .pending
+            .as_ref()
+            .ok_or(CrossChainError::NoPendingRecovery)?;
+
+        if pending.approvals.len() < self.threshold as usize {
+            return Err(CrossChainError::RecoveryNotApproved);
+        }
+
+        if slot < pending.executable_slot {
+            return Err(CrossChainError::RecoveryLocked);
+        }
+
+        Ok(pending.new_owner)
+    }
+}
+
+impl VersionedAccount for GuardianSet {
+    const DISCRIMINATOR: [u8; 8] = *b"RQGUARDS";
+    const VERSION: u8 = 1;
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct TransactionRecord {
     pub id: String,
@@ -75,6 +597,186 @@ pub struct TransactionRecord {
     pub cross_chain: bool,
 }
 
+impl TransactionRecord {
+    pub const MAX_LEN: usize =
+        4 + MAX_RECORD_ID_LEN + 8 + 4 + MAX_RECORD_TX_HASH_LEN + 32 + 32 + 8 + 8 + 1 + 8 + 1;
+
+    /// Clamps the variable-length fields so the record fits a ring-buffer slot.
+    pub fn bounded(mut self) -> Self {
+        let mut id_len = self.id.len().min(MAX_RECORD_ID_LEN);
+        while !self.id.is_char_boundary(id_len) {
+            id_len -= 1;
+        }
+        self.id.truncate(id_len);
+        self.tx_hash.truncate(MAX_RECORD_TX_HASH_LEN);
+        self
+    }
+}
+
+/// Last `ACTIVITY_LOG_CAPACITY` records for a wallet owner, oldest overwritten first.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct WalletActivityLog {
+    pub owner: Pubkey,
+    pub head: u16,
+    pub total_records: u64,
+    /// Outbound transfers escrowed and not yet completed or refunded.
+    pub pending_transfers: u16,
+    /// Owner a social recovery moved the wallet to. Refunds of transfers
+    /// still open under this log go to it rather than the abandoned key.
+    pub recovered_to: Option<Pubkey>,
+    pub velocity: TransferVelocity,
+    pub records: Vec<TransactionRecord>,
+}
+
+impl WalletActivityLog {
+    pub const LEN: usize = 32
+        + 2
+        + 8
+        + 2
+        + 1
+        + 32
+        + TransferVelocity::LEN
+        + 4
+        + ACTIVITY_LOG_CAPACITY * TransactionRecord::MAX_LEN;
+
+    pub fn new(owner: Pubkey) -> Self {
+        Self {
+            owner,
+            head: 0,
+            total_records: 0,
+            pending_transfers: 0,
+            recovered_to: None,
+            velocity: TransferVelocity::default(),
+            records: Vec::with_capacity(ACTIVITY_LOG_CAPACITY),
+        }
+    }
+
+    pub fn push(&mut self, record: TransactionRecord) {
+        let record = record.bounded();
+
+        if self.records.len() < ACTIVITY_LOG_CAPACITY {
+            self.records.push(record);
+        } else {
+            self.records[self.head as usize] = record;
+        }
+
+        self.head = ((self.head as usize + 1) % ACTIVITY_LOG_CAPACITY) as u16;
+        self.total_records = self.total_records.saturating_add(1);
+    }
+
+    /// Counts a newly escrowed outbound transfer against `max_pending`; zero
+    /// leaves the count unbounded.
+    pub fn open_transfer(&mut self, max_pending: u16) -> Result<bool, CrossChainError> {
+        if max_pending > 0 && self.pending_transfers >= max_pending {
+            return Err(CrossChainError::TooManyPendingTransfers);
+        }
+
+        self.pending_transfers = self
+            .pending_transfers
+            .checked_add(1)
+            .ok_or(CrossChainError::ArithmeticOverflow)?;
+        Ok(true)
+    }
+
+    /// Releases the slot of a transfer that was completed or refunded.
+    pub fn close_transfer(&mut self) -> Result<bool, CrossChainError> {
+        self.pending_transfers = self.pending_transfers.saturating_sub(1);
+        Ok(true)
+    }
+
+    /// Who refunds of this log's transfers are paid to.
+    pub fn refund_beneficiary(&self) -> Pubkey {
+        self.recovered_to.unwrap_or(self.owner)
+    }
+
+    /// Records ordered from most recent to oldest.
+    pub fn recent(&self) -> Vec<&TransactionRecord> {
+        let split = if self.records.len() < ACTIVITY_LOG_CAPACITY {
+            self.records.len()
+        } else {
+            self.head as usize
+        };
+
+        self.records[..split]
+            .iter()
+            .rev()
+            .chain(self.records[split..].iter().rev())
+            .collect()
+    }
+}
+
+/// Outbound transfers a wallet initiated in its current velocity window.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TransferVelocity {
+    pub window_start: u64,
+    pub transfers: u16,
+    pub amount: u64,
+}
+
+impl TransferVelocity {
+    pub const LEN: usize = 8 + 2 + 8;
+
+    /// Counts a transfer of `amount` at `slot`, starting a new window once
+    /// the previous one has elapsed. Returns whether the window now exceeds
+    /// `rules`.
+    pub fn record(
+        &mut self,
+        rules: &VelocityRules,
+        amount: u64,
+        slot: u64,
+    ) -> Result<bool, CrossChainError> {
+        if !rules.is_enabled() {
+            return Ok(false);
+        }
+
+        if slot >= self.window_start.saturating_add(rules.window_slots) {
+            *self = Self {
+                window_start: slot,
+                ..Self::default()
+            };
+        }
+
+        self.transfers = self.transfers.saturating_add(1);
+        self.amount = self
+            .amount
+            .checked_add(amount)
+            .ok_or(CrossChainError::ArithmeticOverflow)?;
+        Ok(rules.is_exceeded(self.transfers, self.amount))
+    }
+}
+
+/// Replay protection for a wallet owner. Nonces must strictly increase; gaps
+/// are allowed so clients can reserve nonces for in-flight submissions.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct NonceRegistry {
+    pub owner: Pubkey,
+    pub next_transfer_nonce: u64,
+    pub next_signature_nonce: u64,
+}
+
+impl NonceRegistry {
+    pub const LEN: usize = 32 + 8 + 8;
+
+    pub fn consume_transfer_nonce(&mut self, nonce: u64) -> Result<bool, CrossChainError> {
+        consume_nonce(&mut self.next_transfer_nonce, nonce)
+    }
+
+    pub fn consume_signature_nonce(&mut self, nonce: u64) -> Result<bool, CrossChainError> {
+        consume_nonce(&mut self.next_signature_nonce, nonce)
+    }
+}
+
+fn consume_nonce(next: &mut u64, nonce: u64) -> Result<bool, CrossChainError> {
+    if nonce < *next {
+        return Err(CrossChainError::NonceAlreadyUsed);
+    }
+
+    *next = nonce
+        .checked_add(1)
+        .ok_or(CrossChainError::NonceAlreadyUsed)?;
+    Ok(true)
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq)]
 pub enum TransactionStatus {
     Pending,
@@ -87,18 +789,92 @@ pub fn validate_wallet_transfer(
     wallet: &Wallet,
     amount: u64,
     config: &WalletConfig,
-) -> Result<bool, String> {
+) -> Result<bool, CrossChainError> {
     if config.require_eidas && wallet.eidas_level == EidasLevel::None {
-        return Err("eIDAS verification required for this transaction".to_string());
+        return Err(CrossChainError::ComplianceRequired);
     }
 
     if wallet.eidas_level == EidasLevel::Basic && amount > 100_000_000 {
-        return Err("Basic eIDAS level has transaction limits".to_string());
+        return Err(CrossChainError::EidasLevelLimitExceeded);
+    }
+
+    Ok(true)
+}
+
+pub fn validate_allowed_chain(
+    config: &WalletConfig,
+    destination_chain: u64,
+) -> Result<bool, CrossChainError> {
+    if !config.allows_chain(destination_chain) {
+        return Err(CrossChainError::ChainNotAllowedForWallet);
+    }
+    Ok(true)
+}
+
+/// An internal transfer moves a non-zero amount between two distinct wallets.
+pub fn validate_internal_transfer(
+    sender: &Wallet,
+    recipient: &Wallet,
+    amount: u64,
+) -> Result<bool, CrossChainError> {
+    if amount == 0 {
+        return Err(CrossChainError::AmountTooLow);
+    }
+
+    if sender.owner == recipient.owner {
+        return Err(CrossChainError::SelfTransfer);
     }
 
     Ok(true)
 }
 
+/// Each owner has exactly one hub wallet, derived from `[b"wallet", owner]`.
+pub fn get_wallet_address(owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WALLET_SEED, owner.as_ref()], program_id)
+}
+
+pub fn get_wallet_config_address(owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WALLET_CONFIG_SEED, owner.as_ref()], program_id)
+}
+
+pub fn get_recipient_list_address(
+    owner: &Pubkey,
+    kind: RecipientListKind,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[RECIPIENT_LIST_SEED, owner.as_ref(), &[kind as u8]],
+        program_id,
+    )
+}
+
+pub fn get_second_factor_address(owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SECOND_FACTOR_SEED, owner.as_ref()], program_id)
+}
+
+pub fn get_session_key_address(
+    owner: &Pubkey,
+    session_key: &Pubkey,
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[SESSION_KEY_SEED, owner.as_ref(), session_key.as_ref()],
+        program_id,
+    )
+}
+
+pub fn get_guardian_set_address(owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GUARDIANS_SEED, owner.as_ref()], program_id)
+}
+
+pub fn get_activity_log_address(owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ACTIVITY_LOG_SEED, owner.as_ref()], program_id)
+}
+
+pub fn get_nonce_registry_address(owner: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[NONCE_REGISTRY_SEED, owner.as_ref()], program_id)
+}
+
 pub fn derive_multi_chain_address(master_key: &Pubkey, chain_id: u64, index: u32) -> Pubkey {
     use solana_sdk::pubkey::Pubkey;
 
@@ -112,9 +888,12 @@ pub fn verify_transaction_limits(
     daily_total: u64,
     amount: u64,
     config: &WalletConfig,
-) -> Result<bool, String> {
-    if daily_total + amount > config.max_daily_transfer {
-        return Err("Daily transfer limit exceeded".to_string());
+) -> Result<bool, CrossChainError> {
+    let total = daily_total
+        .checked_add(amount)
+        .ok_or(CrossChainError::ArithmeticOverflow)?;
+    if total > config.max_daily_transfer {
+        return Err(CrossChainError::DailyLimitExceeded);
     }
     Ok(true)
 }
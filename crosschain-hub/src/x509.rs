@@ -0,0 +1,499 @@
+use crate::eidas::{
+    parse_organization_identifier, CertificateType, OrganizationIdentifier, QualifiedCertificate,
+};
+use crate::error::CrossChainError;
+use crate::sigverify::SignatureAlgorithm;
+use solana_program::hash::hash;
+
+/// Compute bound for on-chain parsing: certificates larger than this are
+/// rejected before any decoding work is done.
+pub const MAX_CERTIFICATE_LEN: usize = 4096;
+pub const MAX_NAME_ATTRIBUTES: usize = 16;
+pub const MAX_EXTENSIONS: usize = 16;
+pub const MAX_QC_STATEMENTS: usize = 8;
+
+/// keyUsage bits, first bit string byte in the high byte.
+pub const KEY_USAGE_DIGITAL_SIGNATURE: u16 = 0x8000;
+pub const KEY_USAGE_NON_REPUDIATION: u16 = 0x4000;
+pub const KEY_USAGE_KEY_CERT_SIGN: u16 = 0x0400;
+
+const TAG_BOOLEAN: u8 = 0x01;
+pub(crate) const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+pub(crate) const TAG_OCTET_STRING: u8 = 0x04;
+pub(crate) const TAG_OID: u8 = 0x06;
+const TAG_UTF8_STRING: u8 = 0x0c;
+const TAG_PRINTABLE_STRING: u8 = 0x13;
+const TAG_IA5_STRING: u8 = 0x16;
+const TAG_UTC_TIME: u8 = 0x17;
+pub(crate) const TAG_GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const TAG_SEQUENCE: u8 = 0x30;
+pub(crate) const TAG_SET: u8 = 0x31;
+const TAG_VERSION: u8 = 0xa0;
+const TAG_ISSUER_UID: u8 = 0x81;
+const TAG_SUBJECT_UID: u8 = 0x82;
+const TAG_EXTENSIONS: u8 = 0xa3;
+
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+const OID_COUNTRY: &[u8] = &[0x55, 0x04, 0x06];
+const OID_ORGANIZATION: &[u8] = &[0x55, 0x04, 0x0a];
+const OID_ORGANIZATION_IDENTIFIER: &[u8] = &[0x55, 0x04, 0x61];
+const OID_ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+const OID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+const OID_SECP256K1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x0a];
+const OID_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x0f];
+const OID_EXTENDED_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x25];
+const OID_KP_TIME_STAMPING: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x08];
+const OID_QC_STATEMENTS: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x01, 0x03];
+// ETSI EN 319 412-5 statements under 0.4.0.1862.1
+const OID_QC_COMPLIANCE: &[u8] = &[0x04, 0x00, 0x8e, 0x46, 0x01, 0x01];
+const OID_QC_SSCD: &[u8] = &[0x04, 0x00, 0x8e, 0x46, 0x01, 0x04];
+const OID_QC_TYPE: &[u8] = &[0x04, 0x00, 0x8e, 0x46, 0x01, 0x06];
+const OID_QC_TYPE_ESIGN: &[u8] = &[0x04, 0x00, 0x8e, 0x46, 0x01, 0x06, 0x01];
+const OID_QC_TYPE_ESEAL: &[u8] = &[0x04, 0x00, 0x8e, 0x46, 0x01, 0x06, 0x02];
+const OID_QC_TYPE_WEB: &[u8] = &[0x04, 0x00, 0x8e, 0x46, 0x01, 0x06, 0x03];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DistinguishedName {
+    pub common_name: String,
+    pub organization: String,
+    /// X.520 organizationIdentifier, e.g. `LEIXG-...` or `VATDE-...` on
+    /// legal-entity certificates.
+    pub organization_identifier: String,
+    pub country: String,
+}
+
+/// QCStatements relevant to eIDAS qualification.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QcStatements {
+    pub qc_compliance: bool,
+    pub qc_sscd: bool,
+    pub qc_types: Vec<CertificateType>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedCertificate {
+    pub serial_number: Vec<u8>,
+    pub issuer: DistinguishedName,
+    /// SHA-256 of the DER contents of the issuer name, the key used by the
+    /// trusted issuer registry.
+    pub issuer_hash: [u8; 32],
+    pub subject: DistinguishedName,
+    pub not_before: i64,
+    pub not_after: i64,
+    pub subject_public_key: Vec<u8>,
+    /// `None` for key types qualified signatures cannot be verified with.
+    pub public_key_algorithm: Option<SignatureAlgorithm>,
+    pub key_usage: Option<u16>,
+    /// Extended key usage includes id-kp-timeStamping, as TSA certificates must.
+    pub time_stamping: bool,
+    pub qc_statements: QcStatements,
+}
+
+impl ParsedCertificate {
+    /// Maps a parsed certificate onto the eIDAS model. Only certificates
+    /// declaring QcCompliance with a signing key usage qualify; without a
+    /// QcType statement the certificate is for electronic signatures.
+    pub fn to_qualified_certificate(&self) -> Result<QualifiedCertificate, CrossChainError> {
+        if !self.qc_statements.qc_compliance {
+            return Err(CrossChainError::CertificateNotQualified);
+        }
+
+        let signing = KEY_USAGE_DIGITAL_SIGNATURE | KEY_USAGE_NON_REPUDIATION;
+        if self.key_usage.is_some_and(|usage| usage & signing == 0) {
+            return Err(CrossChainError::InvalidKeyUsage);
+        }
+
+        Ok(self.to_certificate())
+    }
+
+    /// Maps a QCert for eSeal onto the eIDAS model. Beyond the checks for
+    /// signatures, it must declare the eSeal QcType and name the sealing
+    /// organization with a valid organizationIdentifier.
+    pub fn to_seal_certificate(
+        &self,
+    ) -> Result<(QualifiedCertificate, OrganizationIdentifier), CrossChainError> {
+        let certificate = self.to_qualified_certificate()?;
+        if !self.qc_statements.qc_types.contains(&CertificateType::QESe) {
+            return Err(CrossChainError::CertificateNotForSeal);
+        }
+
+        if self.subject.organization.is_empty() {
+            return Err(CrossChainError::CertificateNotForSeal);
+        }
+
+        let identifier = parse_organization_identifier(&self.subject.organization_identifier)?;
+        Ok((
+            QualifiedCertificate {
+                certificate_type: CertificateType::QESe,
+                ..certificate
+            },
+            identifier,
+        ))
+    }
+
+    /// Maps the certificate onto the eIDAS model without requiring it to be
+    /// qualified.
+    pub fn to_certificate(&self) -> QualifiedCertificate {
+        QualifiedCertificate {
+            subject: self.subject.common_name.clone(),
+            issuer: self.issuer.common_name.clone(),
+            serial_number: self.serial_number.clone(),
+            not_before: self.not_before,
+            not_after: self.not_after,
+            public_key_hash: hash(&self.subject_public_key).to_bytes().to_vec(),
+            certificate_type: self
+                .qc_statements
+                .qc_types
+                .first()
+                .cloned()
+                .unwrap_or(CertificateType::QES),
+            country: self.subject.country.clone(),
+            qscd: self.qc_statements.qc_sscd,
+        }
+    }
+}
+
+/// Parses the subset of a DER X.509 v3 certificate needed for eIDAS checks.
+/// Signature verification against the issuer is out of scope; callers bind
+/// the certificate to a signer through its public key.
+pub fn parse_certificate(der: &[u8]) -> Result<ParsedCertificate, CrossChainError> {
+    if der.len() > MAX_CERTIFICATE_LEN {
+        return Err(CrossChainError::InvalidCertificate);
+    }
+
+    let mut outer = Der::new(der);
+    let mut certificate = Der::new(outer.expect(TAG_SEQUENCE)?);
+    outer.finish()?;
+    let mut tbs = Der::new(certificate.expect(TAG_SEQUENCE)?);
+
+    if tbs.peek() == Some(TAG_VERSION) {
+        tbs.read()?;
+    }
+    let serial_number = tbs.expect(TAG_INTEGER)?.to_vec();
+    tbs.expect(TAG_SEQUENCE)?;
+    let issuer_der = tbs.expect(TAG_SEQUENCE)?;
+    let issuer = parse_name(issuer_der)?;
+
+    let mut validity = Der::new(tbs.expect(TAG_SEQUENCE)?);
+    let not_before = parse_time(validity.read()?)?;
+    let not_after = parse_time(validity.read()?)?;
+    validity.finish()?;
+
+    let subject = parse_name(tbs.expect(TAG_SEQUENCE)?)?;
+
+    let mut spki = Der::new(tbs.expect(TAG_SEQUENCE)?);
+    let public_key_algorithm = parse_public_key_algorithm(spki.expect(TAG_SEQUENCE)?)?;
+    let subject_public_key = bit_string_bytes(spki.expect(TAG_BIT_STRING)?)?.to_vec();
+    spki.finish()?;
+
+    let mut parsed = ParsedCertificate {
+        serial_number,
+        issuer,
+        issuer_hash: hash(issuer_der).to_bytes(),
+        subject,
+        not_before,
+        not_after,
+        subject_public_key,
+        public_key_algorithm,
+        key_usage: None,
+        time_stamping: false,
+        qc_statements: QcStatements::default(),
+    };
+
+    while let Some(tag) = tbs.peek() {
+        let (_, value) = tbs.read()?;
+        match tag {
+            TAG_ISSUER_UID | TAG_SUBJECT_UID => {}
+            TAG_EXTENSIONS => parse_extensions(value, &mut parsed)?,
+            _ => return Err(CrossChainError::InvalidCertificate),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_public_key_algorithm(
+    algorithm_identifier: &[u8],
+) -> Result<Option<SignatureAlgorithm>, CrossChainError> {
+    let mut algorithm = Der::new(algorithm_identifier);
+    let oid = algorithm.expect(TAG_OID)?;
+
+    if oid == OID_ED25519 {
+        return Ok(Some(SignatureAlgorithm::Ed25519));
+    }
+
+    if oid == OID_EC_PUBLIC_KEY
+        && algorithm.peek() == Some(TAG_OID)
+        && algorithm.expect(TAG_OID)? == OID_SECP256K1
+    {
+        return Ok(Some(SignatureAlgorithm::Secp256k1));
+    }
+
+    Ok(None)
+}
+
+fn parse_name(data: &[u8]) -> Result<DistinguishedName, CrossChainError> {
+    let mut name = DistinguishedName::default();
+    let mut rdns = Der::new(data);
+    let mut attributes = 0;
+
+    while !rdns.is_empty() {
+        let mut set = Der::new(rdns.expect(TAG_SET)?);
+        while !set.is_empty() {
+            attributes += 1;
+            if attributes > MAX_NAME_ATTRIBUTES {
+                return Err(CrossChainError::InvalidCertificate);
+            }
+
+            let mut attribute = Der::new(set.expect(TAG_SEQUENCE)?);
+            let oid = attribute.expect(TAG_OID)?;
+            let (tag, value) = attribute.read()?;
+            let field = match oid {
+                OID_COMMON_NAME => &mut name.common_name,
+                OID_ORGANIZATION => &mut name.organization,
+                OID_ORGANIZATION_IDENTIFIER => &mut name.organization_identifier,
+                OID_COUNTRY => &mut name.country,
+                _ => continue,
+            };
+            *field = parse_string(tag, value)?;
+        }
+    }
+
+    Ok(name)
+}
+
+fn parse_string(tag: u8, value: &[u8]) -> Result<String, CrossChainError> {
+    match tag {
+        TAG_UTF8_STRING | TAG_PRINTABLE_STRING | TAG_IA5_STRING => std::str::from_utf8(value)
+            .map(str::to_string)
+            .map_err(|_| CrossChainError::InvalidCertificate),
+        _ => Err(CrossChainError::InvalidCertificate),
+    }
+}
+
+fn parse_extensions(data: &[u8], parsed: &mut ParsedCertificate) -> Result<(), CrossChainError> {
+    let mut wrapper = Der::new(data);
+    let mut extensions = Der::new(wrapper.expect(TAG_SEQUENCE)?);
+    wrapper.finish()?;
+
+    let mut count = 0;
+    while !extensions.is_empty() {
+        count += 1;
+        if count > MAX_EXTENSIONS {
+            return Err(CrossChainError::InvalidCertificate);
+        }
+
+        let mut extension = Der::new(extensions.expect(TAG_SEQUENCE)?);
+        let oid = extension.expect(TAG_OID)?;
+        if extension.peek() == Some(TAG_BOOLEAN) {
+            extension.read()?;
+        }
+        let value = extension.expect(TAG_OCTET_STRING)?;
+        extension.finish()?;
+
+        match oid {
+            OID_KEY_USAGE => {
+                let mut inner = Der::new(value);
+                let bits = bit_string_bytes(inner.expect(TAG_BIT_STRING)?)?;
+                let high = *bits.first().unwrap_or(&0) as u16;
+                let low = *bits.get(1).unwrap_or(&0) as u16;
+                parsed.key_usage = Some(high << 8 | low);
+            }
+            OID_EXTENDED_KEY_USAGE => {
+                let mut wrapper = Der::new(value);
+                let mut purposes = Der::new(wrapper.expect(TAG_SEQUENCE)?);
+                while !purposes.is_empty() {
+                    if purposes.expect(TAG_OID)? == OID_KP_TIME_STAMPING {
+                        parsed.time_stamping = true;
+                    }
+                }
+            }
+            OID_QC_STATEMENTS => parsed.qc_statements = parse_qc_statements(value)?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_qc_statements(data: &[u8]) -> Result<QcStatements, CrossChainError> {
+    let mut wrapper = Der::new(data);
+    let mut statements = Der::new(wrapper.expect(TAG_SEQUENCE)?);
+    wrapper.finish()?;
+
+    let mut qc = QcStatements::default();
+    let mut count = 0;
+    while !statements.is_empty() {
+        count += 1;
+        if count > MAX_QC_STATEMENTS {
+            return Err(CrossChainError::InvalidCertificate);
+        }
+
+        let mut statement = Der::new(statements.expect(TAG_SEQUENCE)?);
+        match statement.expect(TAG_OID)? {
+            OID_QC_COMPLIANCE => qc.qc_compliance = true,
+            OID_QC_SSCD => qc.qc_sscd = true,
+            OID_QC_TYPE => {
+                let mut types = Der::new(statement.expect(TAG_SEQUENCE)?);
+                while !types.is_empty() {
+                    let qc_type = match types.expect(TAG_OID)? {
+                        OID_QC_TYPE_ESIGN => CertificateType::QES,
+                        OID_QC_TYPE_ESEAL => CertificateType::QESe,
+                        OID_QC_TYPE_WEB => CertificateType::WebAuth,
+                        _ => continue,
+                    };
+                    qc.qc_types.push(qc_type);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(qc)
+}
+
+/// UTCTime (`YYMMDDHHMMSSZ`, years 1950-2049) or GeneralizedTime
+/// (`YYYYMMDDHHMMSS[.fff]Z`, fraction truncated) to unix seconds.
+pub(crate) fn parse_time((tag, value): (u8, &[u8])) -> Result<i64, CrossChainError> {
+    let (year, rest) = match (tag, value.len()) {
+        (TAG_UTC_TIME, 13) => {
+            let year = digits(&value[..2])?;
+            (
+                if year < 50 { 2000 + year } else { 1900 + year },
+                &value[2..],
+            )
+        }
+        (TAG_GENERALIZED_TIME, len) if len >= 15 => (digits(&value[..4])?, &value[4..]),
+        _ => return Err(CrossChainError::InvalidCertificate),
+    };
+
+    let suffix = &rest[10..];
+    let fraction_ok = match suffix {
+        [b'Z'] => true,
+        [b'.', fraction @ .., b'Z'] => {
+            tag == TAG_GENERALIZED_TIME
+                && !fraction.is_empty()
+                && fraction.iter().all(u8::is_ascii_digit)
+        }
+        _ => false,
+    };
+    if !fraction_ok {
+        return Err(CrossChainError::InvalidCertificate);
+    }
+
+    let month = digits(&rest[0..2])?;
+    let day = digits(&rest[2..4])?;
+    let hour = digits(&rest[4..6])?;
+    let minute = digits(&rest[6..8])?;
+    let second = digits(&rest[8..10])?;
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return Err(CrossChainError::InvalidCertificate);
+    }
+
+    Ok(days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+fn digits(bytes: &[u8]) -> Result<i64, CrossChainError> {
+    bytes.iter().try_fold(0i64, |acc, b| {
+        if b.is_ascii_digit() {
+            Ok(acc * 10 + (b - b'0') as i64)
+        } else {
+            Err(CrossChainError::InvalidCertificate)
+        }
+    })
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn bit_string_bytes(value: &[u8]) -> Result<&[u8], CrossChainError> {
+    match value.split_first() {
+        Some((unused, bits)) if *unused < 8 => Ok(bits),
+        _ => Err(CrossChainError::InvalidCertificate),
+    }
+}
+
+/// Minimal DER reader over single-byte tags and definite lengths.
+pub(crate) struct Der<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Der<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Der { data }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub(crate) fn peek(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// Reads the next element and returns it with its tag and length header.
+    pub(crate) fn read_element(&mut self) -> Result<&'a [u8], CrossChainError> {
+        let start = self.data;
+        self.read()?;
+        Ok(&start[..start.len() - self.data.len()])
+    }
+
+    pub(crate) fn read(&mut self) -> Result<(u8, &'a [u8]), CrossChainError> {
+        let (&tag, rest) = self
+            .data
+            .split_first()
+            .ok_or(CrossChainError::InvalidCertificate)?;
+        if tag & 0x1f == 0x1f {
+            return Err(CrossChainError::InvalidCertificate);
+        }
+
+        let (&first, rest) = rest
+            .split_first()
+            .ok_or(CrossChainError::InvalidCertificate)?;
+        let (len, rest) = match first {
+            0x00..=0x7f => (first as usize, rest),
+            0x81 | 0x82 => {
+                let count = (first & 0x7f) as usize;
+                let bytes = rest
+                    .get(..count)
+                    .ok_or(CrossChainError::InvalidCertificate)?;
+                let len = bytes.iter().fold(0usize, |acc, b| acc << 8 | *b as usize);
+                (len, &rest[count..])
+            }
+            _ => return Err(CrossChainError::InvalidCertificate),
+        };
+
+        let value = rest.get(..len).ok_or(CrossChainError::InvalidCertificate)?;
+        self.data = &rest[len..];
+        Ok((tag, value))
+    }
+
+    pub(crate) fn expect(&mut self, tag: u8) -> Result<&'a [u8], CrossChainError> {
+        match self.read()? {
+            (found, value) if found == tag => Ok(value),
+            _ => Err(CrossChainError::InvalidCertificate),
+        }
+    }
+
+    pub(crate) fn finish(&self) -> Result<(), CrossChainError> {
+        if self.data.is_empty() {
+            Ok(())
+        } else {
+            Err(CrossChainError::InvalidCertificate)
+        }
+    }
+}